@@ -3,7 +3,7 @@ from enum import IntEnum
 from ipaddress import IPv4Address
 from typing import Annotated, Self
 
-from pydantic import BaseModel, ConfigDict, BeforeValidator, field_serializer, model_serializer
+from pydantic import BaseModel, ConfigDict, BeforeValidator, Field, field_serializer, model_serializer
 
 
 class MinerHardware(BaseModel):
@@ -14,6 +14,21 @@ class MinerHardware(BaseModel):
     boards: int | None
 
 
+class MinerCapabilities(BaseModel):
+    model_config = ConfigDict(from_attributes=True)
+
+    supports_fault_light: bool
+    supports_power_limit: bool
+    supports_password: bool
+    supports_hostname: bool
+    supports_pool_update: bool
+    supports_board_tuning: bool
+    supports_restart: bool
+    supports_pause: bool
+    has_chip_telemetry: bool
+    has_psu_telemetry: bool
+
+
 class DeviceInfo(BaseModel):
     model_config = ConfigDict(from_attributes=True)
 
@@ -135,6 +150,7 @@ class ChipData(BaseModel):
     frequency: float | None
     tuned: bool | None
     working: bool | None
+    estimated: bool = False
 
 
 class BoardData(BaseModel):
@@ -150,10 +166,14 @@ class BoardData(BaseModel):
     working_chips: int | None
     serial_number: str | None
     chips: list[ChipData]
+    sensor_temperatures: list[float] = []
     voltage: float | None
     frequency: float | None
+    wattage: float | None
     tuned: bool | None
     active: bool | None
+    status: str | None = None
+    extra: dict[str, str] | None
 
 
 class FanData(BaseModel):
@@ -163,6 +183,37 @@ class FanData(BaseModel):
     rpm: float | None
 
 
+class FanControl(BaseModel):
+    model_config = ConfigDict(from_attributes=True)
+
+    mode: str
+    target_pct: int | None
+
+
+class AutotuneProfileBounds(BaseModel):
+    model_config = ConfigDict(from_attributes=True)
+
+    min: str
+    max: str
+
+
+class PsuData(BaseModel):
+    model_config = ConfigDict(from_attributes=True)
+
+    voltage: float | None
+    current: float | None
+    power: float | None
+
+
+class PsuRail(BaseModel):
+    model_config = ConfigDict(from_attributes=True)
+
+    name: str
+    voltage: float | None
+    current: float | None
+    power: float | None
+
+
 class PoolData(BaseModel):
     model_config = ConfigDict(from_attributes=True)
 
@@ -173,6 +224,11 @@ class PoolData(BaseModel):
     active: bool | None
     alive: bool | None
     user: str | None
+    password: str | None = Field(default=None, exclude=True)
+    last_share_difficulty: float | None = None
+    last_share_time: int | None = None
+    group: str | None = None
+    quota: int | None = None
 
 
 class MinerMessage(BaseModel):
@@ -182,6 +238,7 @@ class MinerMessage(BaseModel):
     code: int
     message: str
     severity: Annotated[str, BeforeValidator(str)]
+    kind: Annotated[str, BeforeValidator(str)] = "other"
 
 
 class MinerData(BaseModel):
@@ -189,33 +246,68 @@ class MinerData(BaseModel):
 
     schema_version: str
     timestamp: int
+    collection_duration: timedelta
+    clock_skew: int | None = None
     ip: IPv4Address
-    mac: str
+    reported_ip: IPv4Address | None = None
+    mac: str | None
+    labels: dict[str, str] | None
     device_info: DeviceInfo
     serial_number: str | None
     hostname: str | None
     api_version: str | None
     firmware_version: str | None
+    firmware_build_time: str | None = None
+    base_firmware: str | None = None
+    control_board_version: str | None
+    control_board_type: str | None
     expected_hashboards: int | None
     hashboards: list[BoardData]
     hashrate: HashRate | None
     expected_hashrate: HashRate | None
+    average_hashrate: HashRate | None
     expected_chips: int | None
     total_chips: int | None
     expected_fans: int | None
+    working_fans: int | None = None
     fans: list[FanData]
     psu_fans: list[FanData]
+    fan_control: FanControl | None = None
     average_temperature: float | None
     fluid_temperature: float | None
+    fluid_flow: float | None
+    fluid_pressure: float | None
     wattage: float | None
+    wattage_estimated: bool
+    wattage_location: str | None = None
     wattage_limit: float | None
     efficiency: float | None
+    psu: PsuData | None
+    psu_rails: list[PsuRail] = []
+    frequency: float | None
+    voltage: float | None
+    work_mode: str | None
+    autotune_enabled: bool | None = None
+    autotune_profile_bounds: AutotuneProfileBounds | None = None
     light_flashing: bool | None
     messages: list[MinerMessage]
     uptime: timedelta | None
+    status: str
     is_mining: bool
     pools: list[PoolData]
 
     @field_serializer("uptime")
-    def serialize_uptime(self, uptime: timedelta, _info) -> float:
+    def serialize_uptime(self, uptime: timedelta | None, _info) -> float | None:
+        if uptime is None:
+            return None
         return uptime.total_seconds()
+
+    @field_serializer("collection_duration")
+    def serialize_collection_duration(self, duration: timedelta, _info) -> float:
+        return duration.total_seconds()
+
+    def to_dict(self) -> dict:
+        return self.model_dump(mode="json")
+
+    def as_json(self) -> str:
+        return self.model_dump_json()
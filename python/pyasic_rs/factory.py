@@ -32,14 +32,23 @@ class MinerFactory:
     def from_range(cls, ip_range: str) -> Self:
         return cls(inner=_rs_MinerFactory.from_range(ip_range))
 
+    def with_command_policy(
+        self, *, read_only: bool = False, allow: list[str] | None = None, deny: list[str] | None = None
+    ) -> Self:
+        self.__inner.with_command_policy(read_only, allow, deny)
+        return self
+
     async def get_miner(self, ip: str) -> Miner | None:
         base = await self.__inner.get_miner(ip)
         if base is not None:
             return Miner(inner=base)
         return None
 
-    async def scan(self) -> list[Miner]:
-        bases = await self.__inner.scan()
+    async def scan(self, ip_range: str | None = None) -> list[Miner]:
+        if ip_range is not None:
+            bases = await self.__inner.scan_range(ip_range)
+        else:
+            bases = await self.__inner.scan()
         return [Miner(inner=m) for m in filter(lambda x: x is not None, bases)]
 
     def scan_stream(self) -> AsyncIterable[Miner]:
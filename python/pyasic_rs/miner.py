@@ -5,7 +5,7 @@ from pyasic_rs.asic_rs import MinerModel as _rs_MinerModel
 from pyasic_rs.asic_rs import HashAlgorithm as _rs_HashAlgorithm
 from pyasic_rs.asic_rs import MinerFirmware as _rs_MinerFirmware
 from pyasic_rs.asic_rs import MinerMake as _rs_MinerMake
-from .data import MinerData, BoardData, HashRate, FanData, MinerMessage, PoolData
+from .data import MinerData, MinerCapabilities, BoardData, HashRate, FanData, FanControl, AutotuneProfileBounds, PsuData, PsuRail, MinerMessage, PoolData
 
 
 class Miner:
@@ -31,6 +31,10 @@ class Miner:
     def algo(self) -> _rs_HashAlgorithm:
         return self.__inner.algo
 
+    @property
+    def capabilities(self) -> MinerCapabilities:
+        return MinerCapabilities.model_validate(self.__inner.capabilities)
+
     @property
     def expected_hashboards(self) -> int:
         return self.__inner.expected_hashboards
@@ -79,21 +83,66 @@ class Miner:
             return HashRate.model_validate(inner)
         return None
 
+    async def get_average_hashrate(self) -> HashRate | None:
+        inner = await self.__inner.get_average_hashrate()
+        if inner is not None:
+            return HashRate.model_validate(inner)
+        return None
+
     async def get_fans(self) -> list[FanData]:
         return [FanData.model_validate(f) for f in await self.__inner.get_fans()]
 
     async def get_psu_fans(self) -> list[FanData]:
         return [FanData.model_validate(f) for f in await self.__inner.get_psu_fans()]
 
+    async def get_fan_control(self) -> FanControl | None:
+        inner = await self.__inner.get_fan_control()
+        if inner is not None:
+            return FanControl.model_validate(inner)
+        return None
+
+    async def get_psu(self) -> PsuData | None:
+        inner = await self.__inner.get_psu()
+        if inner is not None:
+            return PsuData.model_validate(inner)
+        return None
+
+    async def get_psu_rails(self) -> list[PsuRail]:
+        return [PsuRail.model_validate(r) for r in await self.__inner.get_psu_rails()]
+
     async def get_fluid_temperature(self) -> float | None:
         return await self.__inner.get_fluid_temperature()
 
+    async def get_fluid_flow(self) -> float | None:
+        return await self.__inner.get_fluid_flow()
+
+    async def get_fluid_pressure(self) -> float | None:
+        return await self.__inner.get_fluid_pressure()
+
     async def get_wattage(self) -> float | None:
         return await self.__inner.get_wattage()
 
     async def get_wattage_limit(self) -> float | None:
         return await self.__inner.get_wattage_limit()
 
+    async def get_frequency(self) -> float | None:
+        return await self.__inner.get_frequency()
+
+    async def get_voltage(self) -> float | None:
+        return await self.__inner.get_voltage()
+
+    async def get_work_mode(self) -> str | None:
+        return await self.__inner.get_work_mode()
+
+    async def get_autotune_enabled(self) -> bool | None:
+        return await self.__inner.get_autotune_enabled()
+
+    async def get_autotune_profile_bounds(self) -> AutotuneProfileBounds | None:
+        inner = await self.__inner.get_autotune_profile_bounds()
+        if inner is not None:
+            return AutotuneProfileBounds.model_validate(inner)
+        return None
+
     async def get_light_flashing(self) -> bool | None:
         return await self.__inner.get_light_flashing()
 
@@ -106,15 +155,41 @@ class Miner:
     async def get_is_mining(self) -> bool | None:
         return await self.__inner.get_is_mining()
 
+    async def get_status(self) -> str:
+        return await self.__inner.get_status()
+
     async def get_pools(self) -> list[PoolData]:
         return [PoolData.model_validate(b) for b in await self.__inner.get_pools()]
 
     async def set_fault_light(self, fault: bool) -> bool | None:
         return await self.__inner.set_fault_light(fault)
 
+    async def set_power_limit(self, limit: float, unchecked: bool = False) -> bool | None:
+        return await self.__inner.set_power_limit(float(limit), unchecked)
+
+    async def apply_power_limit(self, limit: float) -> float | None:
+        return await self.__inner.apply_power_limit(float(limit))
+
+    async def set_password(self, old: str, new: str) -> bool | None:
+        return await self.__inner.set_password(old, new)
+
+    async def set_hostname(self, hostname: str) -> bool | None:
+        return await self.__inner.set_hostname(hostname)
+
+    async def set_board_tuning(
+        self, board: int, frequency: float | None = None, voltage: float | None = None
+    ) -> bool | None:
+        return await self.__inner.set_board_tuning(board, frequency, voltage)
+
     async def restart(self) -> bool | None:
         return await self.__inner.restart()
 
+    async def restart_mining(self) -> bool | None:
+        return await self.__inner.restart_mining()
+
+    async def reboot_system(self) -> bool | None:
+        return await self.__inner.reboot_system()
+
     async def pause(self, at_time: timedelta | int) -> bool | None:
         if isinstance(at_time, int):
             at_time = timedelta(seconds=at_time)
@@ -123,4 +198,4 @@ class Miner:
     async def resume(self, at_time: timedelta | int) -> bool | None:
         if isinstance(at_time, int):
             at_time = timedelta(seconds=at_time)
-        return await self.__inner.restart(at_time)
+        return await self.__inner.resume(at_time)
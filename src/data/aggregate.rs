@@ -0,0 +1,475 @@
+use measurements::{Power, Temperature, Voltage};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::hashrate::{HashRate, HashRateUnit};
+use super::miner::MinerData;
+use super::serialize::{serialize_power, serialize_temperature, serialize_voltage};
+
+/// Spread of the average temperature reported by each miner in a fleet.
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
+pub struct TemperatureSummary {
+    /// The number of miners that reported a temperature
+    pub samples: usize,
+    #[serde(serialize_with = "serialize_temperature")]
+    pub min: Option<Temperature>,
+    #[serde(serialize_with = "serialize_temperature")]
+    pub max: Option<Temperature>,
+    #[serde(serialize_with = "serialize_temperature")]
+    pub mean: Option<Temperature>,
+    #[serde(serialize_with = "serialize_temperature")]
+    pub p50: Option<Temperature>,
+    #[serde(serialize_with = "serialize_temperature")]
+    pub p90: Option<Temperature>,
+    #[serde(serialize_with = "serialize_temperature")]
+    pub p99: Option<Temperature>,
+}
+
+impl TemperatureSummary {
+    fn from_celsius(mut temps: Vec<f64>) -> Self {
+        if temps.is_empty() {
+            return Self::default();
+        }
+        temps.sort_unstable_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            // nearest rank, so the result is always a reading that was actually reported
+            let rank = ((p / 100.0) * temps.len() as f64).ceil() as usize;
+            Temperature::from_celsius(temps[rank.clamp(1, temps.len()) - 1])
+        };
+        Self {
+            samples: temps.len(),
+            min: temps.first().copied().map(Temperature::from_celsius),
+            max: temps.last().copied().map(Temperature::from_celsius),
+            mean: Some(Temperature::from_celsius(
+                temps.iter().sum::<f64>() / temps.len() as f64,
+            )),
+            p50: Some(percentile(50.0)),
+            p90: Some(percentile(90.0)),
+            p99: Some(percentile(99.0)),
+        }
+    }
+}
+
+/// Fleet level rollup of many [`MinerData`], built with [`FleetSummary::from_iter`].
+///
+/// Fields a miner did not report are skipped rather than counted as zero, and each metric keeps
+/// the number of miners it was computed from. Hashrates are converted to TH/s before they are
+/// summed, so fleets mixing algorithms should be summarized per algorithm.
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
+pub struct FleetSummary {
+    /// The number of miners summarized
+    pub miners: usize,
+    /// Miners that are hashing
+    pub mining: usize,
+    /// Miners that are not hashing, whether stopped, failed or unreachable
+    pub offline: usize,
+    /// Miners that are hashing, but with fewer chips or boards than expected
+    pub degraded: usize,
+    /// Total hashrate in TH/s
+    pub hashrate: Option<HashRate>,
+    /// The number of miners that reported a hashrate
+    pub hashrate_samples: usize,
+    /// Total power consumption
+    #[serde(serialize_with = "serialize_power")]
+    pub wattage: Option<Power>,
+    /// The number of miners that reported a wattage
+    pub wattage_samples: usize,
+    /// Fleet efficiency in W/TH/s (J/TH), weighted by hashrate
+    pub efficiency: Option<f64>,
+    /// The number of miners that reported both a hashrate and a wattage
+    pub efficiency_samples: usize,
+    pub temperature: TemperatureSummary,
+    /// Mean voltage of the circuits feeding the PSUs, a sagging circuit pulls it down
+    #[serde(serialize_with = "serialize_voltage")]
+    pub input_voltage: Option<Voltage>,
+    /// The number of miners that reported a PSU input voltage
+    pub input_voltage_samples: usize,
+    pub by_make: BTreeMap<String, usize>,
+    pub by_model: BTreeMap<String, usize>,
+    pub by_firmware: BTreeMap<String, usize>,
+}
+
+impl<'a> FromIterator<&'a MinerData> for FleetSummary {
+    fn from_iter<I: IntoIterator<Item = &'a MinerData>>(iter: I) -> Self {
+        let mut summary = FleetSummary::default();
+        let mut hashrate = 0.0;
+        let mut wattage = 0.0;
+        let (mut efficiency_watts, mut efficiency_hashrate) = (0.0, 0.0);
+        let mut temps = Vec::new();
+        let mut input_voltage = 0.0;
+
+        for data in iter {
+            summary.miners += 1;
+            if data.is_mining {
+                summary.mining += 1;
+                if is_degraded(data) {
+                    summary.degraded += 1;
+                }
+            } else {
+                summary.offline += 1;
+            }
+
+            let th = data
+                .hashrate
+                .as_ref()
+                .map(|hr| hr.clone().as_unit(HashRateUnit::TeraHash).value)
+                .filter(|v| v.is_finite());
+            let watts = data.wattage.map(|w| w.as_watts()).filter(|v| v.is_finite());
+            if let Some(th) = th {
+                hashrate += th;
+                summary.hashrate_samples += 1;
+            }
+            if let Some(watts) = watts {
+                wattage += watts;
+                summary.wattage_samples += 1;
+            }
+            if let (Some(th), Some(watts)) = (th, watts)
+                && th > 0.0
+            {
+                efficiency_hashrate += th;
+                efficiency_watts += watts;
+                summary.efficiency_samples += 1;
+            }
+            if let Some(temp) = data.average_temperature {
+                temps.push(temp.as_celsius());
+            }
+            if let Some(volts) = data
+                .psu_input()
+                .and_then(|rail| rail.voltage)
+                .map(|v| v.as_volts())
+                .filter(|v| v.is_finite())
+            {
+                input_voltage += volts;
+                summary.input_voltage_samples += 1;
+            }
+
+            let info = &data.device_info;
+            *summary.by_make.entry(info.make.to_string()).or_default() += 1;
+            *summary.by_model.entry(info.model.to_string()).or_default() += 1;
+            *summary
+                .by_firmware
+                .entry(info.firmware.to_string())
+                .or_default() += 1;
+        }
+
+        if summary.hashrate_samples > 0 {
+            summary.hashrate = Some(HashRate {
+                value: hashrate,
+                unit: HashRateUnit::TeraHash,
+                algo: String::from("SHA256"),
+            });
+        }
+        if summary.wattage_samples > 0 {
+            summary.wattage = Some(Power::from_watts(wattage));
+        }
+        if efficiency_hashrate > 0.0 {
+            summary.efficiency = Some(efficiency_watts / efficiency_hashrate);
+        }
+        summary.temperature = TemperatureSummary::from_celsius(temps);
+        if summary.input_voltage_samples > 0 {
+            summary.input_voltage = Some(Voltage::from_volts(
+                input_voltage / summary.input_voltage_samples as f64,
+            ));
+        }
+        summary
+    }
+}
+
+/// Hashing, but missing chips or boards it should have.
+fn is_degraded(data: &MinerData) -> bool {
+    let missing_chips = matches!(
+        (data.total_chips, data.expected_chips),
+        (Some(total), Some(expected)) if total < expected
+    );
+    let active_boards = data
+        .hashboards
+        .iter()
+        .filter(|b| b.active.unwrap_or(false))
+        .count();
+    let missing_boards = data
+        .expected_hashboards
+        .is_some_and(|expected| !data.hashboards.is_empty() && active_boards < expected as usize);
+    missing_chips || missing_boards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::board::BoardData;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::device::models::whatsminer::WhatsMinerModel;
+    use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerMake, MinerModel};
+    use crate::data::miner::MinerStatus;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    fn miner(
+        model: MinerModel,
+        firmware: MinerFirmware,
+        hashrate: Option<HashRate>,
+        wattage: Option<f64>,
+        temperature: Option<f64>,
+    ) -> MinerData {
+        let is_mining = hashrate.as_ref().is_some_and(|h| h.value > 0.0);
+        MinerData {
+            schema_version: env!("CARGO_PKG_VERSION").to_owned(),
+            timestamp: 1_700_000_000,
+            collection_duration: Duration::ZERO,
+            clock_skew: None,
+            ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            reported_ip: None,
+            mac: None,
+            labels: None,
+            device_info: DeviceInfo::new(
+                MinerMake::from(&model),
+                model,
+                firmware,
+                HashAlgorithm::SHA256,
+            ),
+            serial_number: None,
+            hostname: None,
+            api_version: None,
+            firmware_version: None,
+            firmware_build_time: None,
+            base_firmware: None,
+            control_board_version: None,
+            control_board_type: None,
+            expected_hashboards: Some(3),
+            hashboards: vec![],
+            hashrate,
+            expected_hashrate: None,
+            average_hashrate: None,
+            expected_chips: None,
+            total_chips: None,
+            expected_fans: None,
+            working_fans: None,
+            fans: vec![],
+            psu_fans: vec![],
+            fan_control: None,
+            average_temperature: temperature.map(Temperature::from_celsius),
+            fluid_temperature: None,
+            fluid_flow: None,
+            fluid_pressure: None,
+            wattage: wattage.map(Power::from_watts),
+            wattage_estimated: false,
+            wattage_location: None,
+            wattage_limit: None,
+            efficiency: None,
+            psu: None,
+            psu_rails: vec![],
+            frequency: None,
+            voltage: None,
+            work_mode: None,
+            autotune_enabled: None,
+            autotune_profile_bounds: None,
+            light_flashing: None,
+            messages: vec![],
+            uptime: None,
+            status: if is_mining {
+                MinerStatus::Mining
+            } else {
+                MinerStatus::Unknown
+            },
+            is_mining,
+            pools: vec![],
+        }
+    }
+
+    fn hashrate(value: f64, unit: HashRateUnit) -> Option<HashRate> {
+        Some(HashRate {
+            value,
+            unit,
+            algo: String::from("SHA256"),
+        })
+    }
+
+    fn fleet() -> Vec<MinerData> {
+        let s19 = MinerModel::AntMiner(AntMinerModel::S19Pro);
+        let m30 = MinerModel::WhatsMiner(WhatsMinerModel::M30SV10);
+
+        let mut degraded = miner(
+            s19.clone(),
+            MinerFirmware::Stock,
+            hashrate(100.0, HashRateUnit::TeraHash),
+            Some(3000.0),
+            Some(70.0),
+        );
+        degraded.hashboards = (0..3)
+            .map(|position| BoardData {
+                position,
+                active: Some(position < 2),
+                ..Default::default()
+            })
+            .collect();
+
+        vec![
+            miner(
+                s19.clone(),
+                MinerFirmware::Stock,
+                // reported in GH/s, must be normalized before summing
+                hashrate(110_000.0, HashRateUnit::GigaHash),
+                Some(3250.0),
+                Some(60.0),
+            ),
+            miner(
+                s19.clone(),
+                MinerFirmware::BraiinsOS,
+                hashrate(90_000_000.0, HashRateUnit::MegaHash),
+                None,
+                Some(65.0),
+            ),
+            degraded,
+            miner(
+                m30.clone(),
+                MinerFirmware::Stock,
+                hashrate(88.0, HashRateUnit::TeraHash),
+                Some(3344.0),
+                None,
+            ),
+            miner(m30, MinerFirmware::Stock, None, None, None),
+        ]
+    }
+
+    #[test]
+    fn test_fleet_totals() {
+        let fleet = fleet();
+        let summary = FleetSummary::from_iter(&fleet);
+
+        assert_eq!(summary.miners, 5);
+        assert_eq!(summary.mining, 4);
+        assert_eq!(summary.offline, 1);
+        assert_eq!(summary.degraded, 1);
+
+        let hashrate = summary.hashrate.unwrap();
+        assert_eq!(hashrate.unit, HashRateUnit::TeraHash);
+        assert!((hashrate.value - 388.0).abs() < 1e-9);
+        assert_eq!(summary.hashrate_samples, 4);
+
+        assert_eq!(summary.wattage, Some(Power::from_watts(9594.0)));
+        assert_eq!(summary.wattage_samples, 3);
+
+        // the miner without a wattage does not dilute the efficiency
+        assert_eq!(summary.efficiency_samples, 3);
+        assert!((summary.efficiency.unwrap() - 9594.0 / 298.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fleet_grouping() {
+        let fleet = fleet();
+        let summary = FleetSummary::from_iter(&fleet);
+
+        assert_eq!(
+            summary.by_make,
+            BTreeMap::from([("AntMiner".to_string(), 3), ("WhatsMiner".to_string(), 2)])
+        );
+        assert_eq!(summary.by_model.len(), 2);
+        assert_eq!(summary.by_model.values().sum::<usize>(), 5);
+        assert_eq!(
+            summary.by_firmware,
+            BTreeMap::from([("BraiinsOS".to_string(), 1), ("Stock".to_string(), 4)])
+        );
+    }
+
+    #[test]
+    fn test_fleet_temperatures() {
+        let fleet = fleet();
+        let summary = FleetSummary::from_iter(&fleet);
+
+        let temps = summary.temperature;
+        assert_eq!(temps.samples, 3);
+        assert_eq!(temps.min, Some(Temperature::from_celsius(60.0)));
+        assert_eq!(temps.max, Some(Temperature::from_celsius(70.0)));
+        assert_eq!(temps.mean, Some(Temperature::from_celsius(65.0)));
+        assert_eq!(temps.p50, Some(Temperature::from_celsius(65.0)));
+        assert_eq!(temps.p99, Some(Temperature::from_celsius(70.0)));
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "antminer", feature = "epic", feature = "whatsminer"))]
+    async fn test_fleet_input_voltage() {
+        use crate::miners::backends::antminer::AntMinerV2020;
+        use crate::miners::backends::epic::PowerPlayV1;
+        use crate::miners::backends::traits::GetMinerData;
+        use crate::miners::backends::whatsminer::{WhatsMinerV2, WhatsMinerV3};
+        use crate::miners::commands::MinerCommand;
+        use crate::miners::data::DataCollector;
+        use crate::test::api::MockAPIClient;
+        use crate::test::json::bmminer::antminer_web::AM_WEB_STATS_S21_PSU;
+        use crate::test::json::btminer::v2::GET_PSU_20230302_COMMAND;
+        use crate::test::json::btminer::v3::GET_DEVICE_INFO_COMMAND;
+        use crate::test::json::epic::v1::{SUMMARY, SUMMARY_AC};
+        use serde_json::Value;
+        use std::collections::HashMap;
+
+        async fn parse<M: GetMinerData>(
+            miner: M,
+            command: MinerCommand,
+            fixture: &str,
+        ) -> MinerData {
+            let mock_api = MockAPIClient::new(HashMap::from([(
+                command,
+                serde_json::from_str::<Value>(fixture).unwrap(),
+            )]));
+            let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+            miner.parse_data(collector.collect_all().await)
+        }
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        let web = |command| MinerCommand::WebAPI {
+            command,
+            parameters: None,
+        };
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let s21 = MinerModel::AntMiner(AntMinerModel::S21);
+        let m50 = MinerModel::WhatsMiner(WhatsMinerModel::M50SVH20);
+        let s19xp = MinerModel::AntMiner(AntMinerModel::S19XP);
+
+        let fleet = vec![
+            parse(
+                AntMinerV2020::new(ip, s21),
+                web("stats"),
+                AM_WEB_STATS_S21_PSU,
+            )
+            .await,
+            parse(
+                WhatsMinerV2::new(ip, m50.clone()),
+                rpc("get_psu"),
+                GET_PSU_20230302_COMMAND,
+            )
+            .await,
+            parse(
+                WhatsMinerV3::new(ip, m50),
+                rpc("get.device.info"),
+                GET_DEVICE_INFO_COMMAND,
+            )
+            .await,
+            parse(
+                PowerPlayV1::new(ip, s19xp.clone()),
+                web("summary"),
+                SUMMARY_AC,
+            )
+            .await,
+            // 0 V from a PSU that doesn't measure its input must not drag the mean down
+            parse(PowerPlayV1::new(ip, s19xp), web("summary"), SUMMARY).await,
+        ];
+        let summary = FleetSummary::from_iter(&fleet);
+
+        assert_eq!(summary.input_voltage_samples, 4);
+        let mean = summary.input_voltage.unwrap().as_volts();
+        assert!((mean - (226.5 + 223.75 + 223.75 + 228.4) / 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty_fleet() {
+        let summary = FleetSummary::from_iter(std::iter::empty());
+        assert_eq!(summary, FleetSummary::default());
+        assert!(summary.hashrate.is_none());
+        assert!(summary.efficiency.is_none());
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["miners"], 0);
+        assert!(json["wattage"].is_null());
+    }
+}
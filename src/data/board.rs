@@ -1,9 +1,31 @@
+use super::format::{Measured, OrDash};
 use super::hashrate::HashRate;
-use super::serialize::{serialize_frequency, serialize_temperature, serialize_voltage};
-use measurements::{Frequency, Temperature, Voltage};
+use super::serialize::{
+    serialize_frequency, serialize_power, serialize_temperature, serialize_temperatures,
+    serialize_voltage,
+};
+use measurements::{Frequency, Power, Temperature, Voltage};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ESTIMATE_CHIPS: AtomicBool = AtomicBool::new(false);
+
+/// Fill in estimated chips on boards that only report a total hashrate and working chip count.
+///
+/// Off by default. The estimated chips split the board hashrate evenly and are marked with
+/// [`ChipData::estimated`], boards with chip data from the firmware are never changed.
+pub fn set_estimate_chips(enabled: bool) {
+    ESTIMATE_CHIPS.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn estimate_chips() -> bool {
+    ESTIMATE_CHIPS.load(Ordering::Relaxed)
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ChipData {
     /// The position of the chip on the board, indexed from 0
     pub position: u16,
@@ -11,23 +33,72 @@ pub struct ChipData {
     pub hashrate: Option<HashRate>,
     /// The current chip temperature
     #[serde(serialize_with = "serialize_temperature")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "°C")))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<Temperature>,
     /// The voltage set point for this chip
     #[serde(serialize_with = "serialize_voltage")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "V")))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub voltage: Option<Voltage>,
     /// The frequency set point for this chip
     #[serde(serialize_with = "serialize_frequency")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "MHz")))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency: Option<Frequency>,
     /// Whether this chip is tuned and optimizations have completed
     pub tuned: Option<bool>,
     /// Whether this chip is working and actively mining
     pub working: Option<bool>,
+    /// Whether this chip was estimated from the board totals rather than reported by the firmware
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub estimated: bool,
+}
+
+/// The operating state of a single hashboard, as reported by its firmware.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BoardStatus {
+    /// Hashing at its configured frequency
+    Normal,
+    /// Powering up or ramping frequency before hashing at full speed
+    Initializing,
+    /// Running at reduced frequency to keep the chips within temperature limits
+    ThermalThrottled,
+    /// Running at reduced frequency to stay within the power limit
+    PowerLimited,
+    /// Stopped hashing because of a fault
+    Failed,
+    /// A state the firmware reported that has no common equivalent, kept as reported
+    Unknown(String),
+}
+
+impl BoardStatus {
+    /// Whether the board is held below its configured frequency in this state
+    pub fn is_throttled(&self) -> bool {
+        matches!(
+            self,
+            BoardStatus::ThermalThrottled | BoardStatus::PowerLimited
+        )
+    }
+}
+
+impl fmt::Display for BoardStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardStatus::Normal => write!(f, "normal"),
+            BoardStatus::Initializing => write!(f, "initializing"),
+            BoardStatus::ThermalThrottled => write!(f, "thermal_throttled"),
+            BoardStatus::PowerLimited => write!(f, "power_limited"),
+            BoardStatus::Failed => write!(f, "failed"),
+            BoardStatus::Unknown(status) => write!(f, "{status}"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BoardData {
     /// The board position in the miner, indexed from 0
     pub position: u8,
@@ -37,14 +108,17 @@ pub struct BoardData {
     pub expected_hashrate: Option<HashRate>,
     /// The board temperature, also sometimes called PCB temperature
     #[serde(serialize_with = "serialize_temperature")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "°C")))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub board_temperature: Option<Temperature>,
     /// The temperature of the chips at the intake, usually from the first sensor on the board
     #[serde(serialize_with = "serialize_temperature")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "°C")))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub intake_temperature: Option<Temperature>,
     /// The temperature of the chips at the outlet, usually from the last sensor on the board
     #[serde(serialize_with = "serialize_temperature")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "°C")))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub outlet_temperature: Option<Temperature>,
     /// The expected number of chips on this board
@@ -56,16 +130,154 @@ pub struct BoardData {
     /// Chip level information for this board
     /// May be empty, most machines do not provide this level of in depth information
     pub chips: Vec<ChipData>,
+    /// Raw readings from the board's temperature sensors, in sensor order
+    /// Used when the miner reports a handful of sensors rather than per-chip data
+    #[serde(default, serialize_with = "serialize_temperatures")]
+    #[cfg_attr(feature = "schema", schemars(with = "Vec<f64>", extend("unit" = "°C")))]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sensor_temperatures: Vec<Temperature>,
     /// The average voltage or voltage set point of this board
     #[serde(serialize_with = "serialize_voltage")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "V")))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub voltage: Option<Voltage>,
     /// The average frequency or frequency set point of this board
     #[serde(serialize_with = "serialize_frequency")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "MHz")))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency: Option<Frequency>,
+    /// The power consumed by this board
+    #[serde(default, serialize_with = "serialize_power")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "W")))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wattage: Option<Power>,
     /// Whether this board has been tuned and optimizations have completed
     pub tuned: Option<bool>,
     /// Whether this board is enabled and actively mining
     pub active: Option<bool>,
+    /// The operating state of this board, including why it is throttled
+    /// Only populated by backends that report a per-board state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<BoardStatus>,
+    /// Vendor specific board metadata, such as chip bin or core type
+    /// Only populated by backends that expose metadata with no common field
+    #[serde(default, skip_serializing_if = "extra_is_empty")]
+    pub extra: Option<HashMap<String, String>>,
+}
+
+impl BoardData {
+    /// Add one estimated chip per working chip, each with an even share of the board hashrate.
+    ///
+    /// Only boards without chip data that report both their hashrate and working chips are
+    /// filled in, chip positions are indexes rather than the physical location on the board.
+    pub(crate) fn fill_estimated_chips(&mut self) {
+        if !self.chips.is_empty() {
+            return;
+        }
+        let (Some(hashrate), Some(working)) = (&self.hashrate, self.working_chips) else {
+            return;
+        };
+        if working == 0 {
+            return;
+        }
+        let chip_hashrate = HashRate {
+            value: hashrate.value / working as f64,
+            ..hashrate.clone()
+        };
+        self.chips = (0..working)
+            .map(|position| ChipData {
+                position,
+                hashrate: Some(chip_hashrate.clone()),
+                working: Some(true),
+                estimated: true,
+                ..Default::default()
+            })
+            .collect();
+    }
+}
+
+/// One line such as `board 0 | 49.1 TH/s / 52.0 TH/s | 64°C | 126/126 chips | 525 MHz`,
+/// followed by the status if the firmware reports one.
+impl fmt::Display for BoardData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "board {} | {:.1} / {:.1} | {} | {}/{} chips | {}",
+            self.position,
+            OrDash(self.hashrate.as_ref().map(HashRate::scaled)),
+            OrDash(self.expected_hashrate.as_ref().map(HashRate::scaled)),
+            OrDash(
+                self.board_temperature
+                    .map(|t| Measured(t.as_celsius(), "°C"))
+            ),
+            OrDash(self.working_chips),
+            OrDash(self.expected_chips),
+            OrDash(
+                self.frequency
+                    .map(|freq| Measured(freq.as_megahertz(), " MHz"))
+            ),
+        )?;
+        if let Some(status) = &self.status {
+            write!(f, " | {status}")?;
+        }
+        if self.active == Some(false) {
+            f.write_str(" | inactive")?;
+        }
+        Ok(())
+    }
+}
+
+fn extra_is_empty(extra: &Option<HashMap<String, String>>) -> bool {
+    extra.as_ref().is_none_or(|e| e.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::hashrate::HashRateUnit;
+
+    #[test]
+    fn test_fill_estimated_chips() {
+        let mut board = BoardData {
+            hashrate: Some(HashRate {
+                value: 43.0,
+                unit: HashRateUnit::TeraHash,
+                algo: String::from("SHA256"),
+            }),
+            expected_chips: Some(111),
+            working_chips: Some(108),
+            ..Default::default()
+        };
+        board.fill_estimated_chips();
+        assert_eq!(board.chips.len(), 108);
+        assert!(
+            board
+                .chips
+                .iter()
+                .all(|c| c.estimated && c.working == Some(true))
+        );
+        let total: f64 = board
+            .chips
+            .iter()
+            .filter_map(|c| c.hashrate.as_ref())
+            .map(|h| h.value)
+            .sum();
+        assert!((total - 43.0).abs() < 1e-9);
+
+        // chips are only estimated once, and never over chips from the firmware
+        let chips = board.chips.clone();
+        board.working_chips = Some(100);
+        board.fill_estimated_chips();
+        assert_eq!(board.chips, chips);
+
+        let mut idle = BoardData {
+            working_chips: Some(108),
+            ..Default::default()
+        };
+        idle.fill_estimated_chips();
+        assert!(idle.chips.is_empty());
+
+        let json = serde_json::to_value(ChipData::default()).unwrap();
+        assert!(json.get("estimated").is_none());
+    }
 }
@@ -0,0 +1,269 @@
+//! Detection of address conflicts across a batch of [`MinerData`].
+//!
+//! Two miners sharing a static IP, or a stale DHCP lease showing one miner at two IPs, both
+//! produce confusing data when polled. Feed the data from one or more scans into
+//! [`ConflictReport::analyze`] to find them.
+
+use macaddr::MacAddr;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+
+use super::deserialize::deserialize_shared_macs;
+use super::device::{MinerMake, MinerModel};
+use super::miner::MinerData;
+use super::serialize::serialize_shared_macs;
+
+/// An identifier reported by more than one IP.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedIdentifier<T> {
+    pub value: T,
+    pub ips: Vec<IpAddr>,
+}
+
+/// An IP that answered as different miners, in the order they were seen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityChange {
+    pub ip: IpAddr,
+    pub identities: Vec<(MinerMake, MinerModel)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ConflictReport {
+    /// MAC addresses seen at more than one IP
+    #[serde(
+        serialize_with = "serialize_shared_macs",
+        deserialize_with = "deserialize_shared_macs"
+    )]
+    pub duplicate_macs: Vec<SharedIdentifier<MacAddr>>,
+    /// Serial numbers seen at more than one IP
+    pub duplicate_serials: Vec<SharedIdentifier<String>>,
+    /// IPs whose make or model changed between polls
+    pub identity_changes: Vec<IdentityChange>,
+}
+
+impl ConflictReport {
+    /// Find conflicts in `data`, which may hold several polls of the same miners.
+    pub fn analyze<'a>(data: impl IntoIterator<Item = &'a MinerData>) -> Self {
+        let mut macs: BTreeMap<String, (MacAddr, BTreeSet<IpAddr>)> = BTreeMap::new();
+        let mut serials: BTreeMap<String, BTreeSet<IpAddr>> = BTreeMap::new();
+        let mut identities: BTreeMap<IpAddr, Vec<(MinerMake, MinerModel)>> = BTreeMap::new();
+
+        for miner in data {
+            if let Some(mac) = miner.mac {
+                macs.entry(mac.to_string())
+                    .or_insert_with(|| (mac, BTreeSet::new()))
+                    .1
+                    .insert(miner.ip);
+            }
+            if let Some(serial) = miner.serial_number.as_deref().map(str::trim)
+                && !serial.is_empty()
+            {
+                serials
+                    .entry(serial.to_string())
+                    .or_default()
+                    .insert(miner.ip);
+            }
+            let identity = (miner.device_info.make, miner.device_info.model.clone());
+            let seen = identities.entry(miner.ip).or_default();
+            if !seen.contains(&identity) {
+                seen.push(identity);
+            }
+        }
+
+        Self {
+            duplicate_macs: macs
+                .into_values()
+                .filter(|(_, ips)| ips.len() > 1)
+                .map(|(value, ips)| SharedIdentifier {
+                    value,
+                    ips: ips.into_iter().collect(),
+                })
+                .collect(),
+            duplicate_serials: serials
+                .into_iter()
+                .filter(|(_, ips)| ips.len() > 1)
+                .map(|(value, ips)| SharedIdentifier {
+                    value,
+                    ips: ips.into_iter().collect(),
+                })
+                .collect(),
+            identity_changes: identities
+                .into_iter()
+                .filter(|(_, identities)| identities.len() > 1)
+                .map(|(ip, identities)| IdentityChange { ip, identities })
+                .collect(),
+        }
+    }
+
+    /// Whether no conflicts were found.
+    pub fn is_empty(&self) -> bool {
+        self.duplicate_macs.is_empty()
+            && self.duplicate_serials.is_empty()
+            && self.identity_changes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::device::models::whatsminer::WhatsMinerModel;
+    use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware};
+    use crate::data::miner::MinerStatus;
+    use std::net::Ipv4Addr;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    fn miner_data(ip: u8, mac: &str, serial: &str) -> MinerData {
+        MinerData {
+            schema_version: env!("CARGO_PKG_VERSION").to_owned(),
+            timestamp: 1_700_000_000,
+            collection_duration: Duration::ZERO,
+            clock_skew: None,
+            ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, ip)),
+            reported_ip: None,
+            mac: MacAddr::from_str(mac).ok(),
+            labels: None,
+            device_info: DeviceInfo::new(
+                MinerMake::AntMiner,
+                MinerModel::AntMiner(AntMinerModel::S19Pro),
+                MinerFirmware::Stock,
+                HashAlgorithm::SHA256,
+            ),
+            serial_number: Some(serial.to_string()),
+            hostname: None,
+            api_version: None,
+            firmware_version: None,
+            firmware_build_time: None,
+            base_firmware: None,
+            control_board_version: None,
+            control_board_type: None,
+            expected_hashboards: None,
+            hashboards: vec![],
+            hashrate: None,
+            expected_hashrate: None,
+            average_hashrate: None,
+            expected_chips: None,
+            total_chips: None,
+            expected_fans: None,
+            working_fans: None,
+            fans: vec![],
+            psu_fans: vec![],
+            fan_control: None,
+            average_temperature: None,
+            fluid_temperature: None,
+            fluid_flow: None,
+            fluid_pressure: None,
+            wattage: None,
+            wattage_estimated: false,
+            wattage_location: None,
+            wattage_limit: None,
+            efficiency: None,
+            psu: None,
+            psu_rails: vec![],
+            frequency: None,
+            voltage: None,
+            work_mode: None,
+            autotune_enabled: None,
+            autotune_profile_bounds: None,
+            light_flashing: None,
+            messages: vec![],
+            uptime: None,
+            status: MinerStatus::Mining,
+            is_mining: true,
+            pools: vec![],
+        }
+    }
+
+    fn fleet() -> Vec<MinerData> {
+        vec![
+            miner_data(1, "AA:BB:CC:DD:EE:01", "SN01"),
+            miner_data(2, "AA:BB:CC:DD:EE:02", "SN02"),
+            miner_data(3, "AA:BB:CC:DD:EE:03", "SN03"),
+        ]
+    }
+
+    #[test]
+    fn test_clean_fleet() {
+        let mut polls = fleet();
+        polls.extend(fleet());
+
+        assert!(ConflictReport::analyze(&polls).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_mac() {
+        let mut data = fleet();
+        data.push(miner_data(4, "AA:BB:CC:DD:EE:02", "SN02"));
+
+        let report = ConflictReport::analyze(&data);
+
+        assert_eq!(
+            report.duplicate_macs,
+            vec![SharedIdentifier {
+                value: MacAddr::from_str("AA:BB:CC:DD:EE:02").unwrap(),
+                ips: vec![data[1].ip, data[3].ip],
+            }]
+        );
+        assert_eq!(report.duplicate_serials.len(), 1);
+        assert!(report.identity_changes.is_empty());
+
+        // MACs are written as strings, like in MinerData
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["duplicate_macs"][0]["value"], "AA:BB:CC:DD:EE:02");
+        assert_eq!(
+            serde_json::from_value::<ConflictReport>(json).unwrap(),
+            report
+        );
+    }
+
+    #[test]
+    fn test_duplicate_serial() {
+        let mut data = fleet();
+        data.push(miner_data(4, "AA:BB:CC:DD:EE:04", "SN01"));
+
+        let report = ConflictReport::analyze(&data);
+
+        assert!(report.duplicate_macs.is_empty());
+        assert_eq!(
+            report.duplicate_serials,
+            vec![SharedIdentifier {
+                value: "SN01".to_string(),
+                ips: vec![data[0].ip, data[3].ip],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_identity_change() {
+        let first_poll = fleet();
+        let mut second_poll = fleet();
+        second_poll[2].device_info = DeviceInfo::new(
+            MinerMake::WhatsMiner,
+            MinerModel::WhatsMiner(WhatsMinerModel::M50SVH20),
+            MinerFirmware::Stock,
+            HashAlgorithm::SHA256,
+        );
+
+        let report = ConflictReport::analyze(first_poll.iter().chain(&second_poll));
+
+        assert!(report.duplicate_macs.is_empty());
+        assert_eq!(
+            report.identity_changes,
+            vec![IdentityChange {
+                ip: first_poll[2].ip,
+                identities: vec![
+                    (
+                        MinerMake::AntMiner,
+                        MinerModel::AntMiner(AntMinerModel::S19Pro)
+                    ),
+                    (
+                        MinerMake::WhatsMiner,
+                        MinerModel::WhatsMiner(WhatsMinerModel::M50SVH20)
+                    ),
+                ],
+            }]
+        );
+    }
+}
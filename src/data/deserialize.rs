@@ -1,3 +1,4 @@
+use crate::data::conflicts::SharedIdentifier;
 use macaddr::MacAddr;
 use serde::{Deserialize, Deserializer};
 
@@ -14,3 +15,20 @@ where
         None => Ok(None),
     }
 }
+
+pub(crate) fn deserialize_shared_macs<'de, D>(
+    deserializer: D,
+) -> Result<Vec<SharedIdentifier<MacAddr>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<SharedIdentifier<String>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|mac| {
+            Ok(SharedIdentifier {
+                value: mac.value.parse().map_err(serde::de::Error::custom)?,
+                ips: mac.ips,
+            })
+        })
+        .collect()
+}
@@ -3,14 +3,19 @@ use pyo3::prelude::*;
 use std::fmt::Display;
 use std::str::FromStr;
 
+use measurements::{Frequency, Power, Voltage};
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
+use crate::data::hashrate::HashRateUnit;
+use crate::miners::backends::errors::ControlError;
+
 pub mod models;
 pub use models::MinerModel;
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum MinerFirmware {
     #[serde(rename = "Stock")]
     Stock,
@@ -32,6 +37,7 @@ pub enum MinerFirmware {
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum MinerMake {
     #[serde(rename = "AntMiner")]
     AntMiner,
@@ -45,10 +51,14 @@ pub enum MinerMake {
     Braiins,
     #[serde(rename = "Bitaxe")]
     Bitaxe,
+    /// A cgminer based miner that didn't match any known make
+    #[serde(rename = "Unknown")]
+    Unknown,
 }
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum HashAlgorithm {
     #[serde(rename = "SHA256")]
     SHA256,
@@ -62,8 +72,37 @@ pub enum HashAlgorithm {
     Kadena,
 }
 
+impl HashAlgorithm {
+    /// The unit rates for this algorithm are usually quoted in, so totals stay readable.
+    pub fn default_unit(&self) -> HashRateUnit {
+        match self {
+            HashAlgorithm::SHA256 | HashAlgorithm::Blake2S256 => HashRateUnit::TeraHash,
+            HashAlgorithm::X11 => HashRateUnit::GigaHash,
+            HashAlgorithm::Scrypt | HashAlgorithm::Kadena => HashRateUnit::MegaHash,
+        }
+    }
+}
+
+impl From<&MinerModel> for HashAlgorithm {
+    fn from(model: &MinerModel) -> Self {
+        use models::antminer::AntMinerModel;
+
+        match model {
+            MinerModel::AntMiner(AntMinerModel::D3 | AntMinerModel::D7 | AntMinerModel::D9) => {
+                HashAlgorithm::X11
+            }
+            MinerModel::AntMiner(AntMinerModel::L3Plus | AntMinerModel::L7 | AntMinerModel::L9) => {
+                HashAlgorithm::Scrypt
+            }
+            MinerModel::AntMiner(AntMinerModel::KA3) => HashAlgorithm::Kadena,
+            _ => HashAlgorithm::SHA256,
+        }
+    }
+}
+
 #[cfg_attr(feature = "python", pyclass(get_all, module = "asic_rs"))]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DeviceInfo {
     pub make: MinerMake,
     pub model: MinerModel,
@@ -91,6 +130,7 @@ impl DeviceInfo {
 
 #[cfg_attr(feature = "python", pyclass(get_all, module = "asic_rs"))]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MinerHardware {
     pub chips: Option<u16>,
     pub fans: Option<u8>,
@@ -106,11 +146,103 @@ impl From<&MinerModel> for MinerHardware {
             MinerModel::Bitaxe(model_name) => Self::from(model_name),
             MinerModel::EPic(model_name) => Self::from(model_name),
             MinerModel::AvalonMiner(model_name) => Self::from(model_name),
+            MinerModel::Unknown { .. } => Self {
+                chips: None,
+                fans: None,
+                boards: None,
+            },
         }
     }
 }
 
+/// The range of per-board frequency and voltage that is safe to set manually on a model.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TuningLimits {
+    pub min_frequency: Frequency,
+    pub max_frequency: Frequency,
+    pub min_voltage: Voltage,
+    pub max_voltage: Voltage,
+}
+
+impl TuningLimits {
+    pub(crate) fn new(frequency_mhz: (f64, f64), voltage: (f64, f64)) -> Self {
+        Self {
+            min_frequency: Frequency::from_megahertz(frequency_mhz.0),
+            max_frequency: Frequency::from_megahertz(frequency_mhz.1),
+            min_voltage: Voltage::from_volts(voltage.0),
+            max_voltage: Voltage::from_volts(voltage.1),
+        }
+    }
+
+    /// Check a requested board tuning against this range.
+    pub fn validate(
+        &self,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<(), ControlError> {
+        if let Some(frequency) = frequency
+            && !(self.min_frequency.as_megahertz()..=self.max_frequency.as_megahertz())
+                .contains(&frequency.as_megahertz())
+        {
+            return Err(ControlError::InvalidParameter(format!(
+                "frequency {:.0} MHz is outside of the safe range {:.0}-{:.0} MHz",
+                frequency.as_megahertz(),
+                self.min_frequency.as_megahertz(),
+                self.max_frequency.as_megahertz(),
+            )));
+        }
+        if let Some(voltage) = voltage
+            && !(self.min_voltage.as_volts()..=self.max_voltage.as_volts())
+                .contains(&voltage.as_volts())
+        {
+            return Err(ControlError::InvalidParameter(format!(
+                "voltage {:.2} V is outside of the safe range {:.2}-{:.2} V",
+                voltage.as_volts(),
+                self.min_voltage.as_volts(),
+                self.max_voltage.as_volts(),
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The range of power limits a model hashes reliably at, outside of it firmware either clamps the
+/// limit or stops hashing until it is reset.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PowerLimits {
+    pub min: Power,
+    pub max: Power,
+}
+
+impl PowerLimits {
+    pub(crate) fn new(min_watts: f64, max_watts: f64) -> Self {
+        Self {
+            min: Power::from_watts(min_watts),
+            max: Power::from_watts(max_watts),
+        }
+    }
+
+    /// 50% to 110% of the nominal wattage, for models without a published range.
+    pub(crate) fn from_nominal(nominal: Power) -> Self {
+        Self::new(nominal.as_watts() * 0.5, nominal.as_watts() * 1.1)
+    }
+
+    /// Check a requested power limit against this range.
+    pub fn validate(&self, limit: Power) -> Result<(), ControlError> {
+        if !(self.min.as_watts()..=self.max.as_watts()).contains(&limit.as_watts()) {
+            return Err(ControlError::InvalidParameter(format!(
+                "power limit {:.0} W is outside of the allowed range {:.0}-{:.0} W",
+                limit.as_watts(),
+                self.min.as_watts(),
+                self.max.as_watts(),
+            )));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum MinerControlBoard {
     // Antminer control boards
     #[serde(rename = "Xilinx")]
@@ -225,3 +357,134 @@ impl FromStr for MinerControlBoard {
         }
     }
 }
+
+/// The platform a control board is built on.
+///
+/// Firmware images and recovery procedures are specific to the platform rather than to the exact
+/// board revision, so this groups the raw board names reported by the different firmwares.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ControlBoardType {
+    #[serde(rename = "Xilinx")]
+    Xilinx,
+    #[serde(rename = "BeagleBone")]
+    BeagleBone,
+    #[serde(rename = "CVITek")]
+    CVITek,
+    #[serde(rename = "AMLogic")]
+    AMLogic,
+    #[serde(rename = "Allwinner")]
+    Allwinner,
+    #[serde(rename = "X86")]
+    X86,
+    Unknown(String),
+}
+
+impl FromStr for ControlBoardType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = s.trim();
+        let name: String = raw
+            .to_uppercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect();
+        let platform = match name.as_str() {
+            n if n.starts_with("XIL") || n.contains("ZYNQ") => Self::Xilinx,
+            "BB" | "BBB" => Self::BeagleBone,
+            n if n.contains("BEAGLE") || n.starts_with("AM335") => Self::BeagleBone,
+            n if n.contains("CVITEK") || n.starts_with("CVCTRL") || n.starts_with("CV18") => {
+                Self::CVITek
+            }
+            n if n.starts_with("AML") || n.contains("AMLOGIC") || n.contains("MESON") => {
+                Self::AMLogic
+            }
+            n if n.starts_with("A113") || n.starts_with("S905") => Self::AMLogic,
+            n if n.contains("ALLWINNER") || n.starts_with("SUNXI") => Self::Allwinner,
+            "H3" | "H6" | "H6OS" | "H616" => Self::Allwinner,
+            n if n.starts_with("X86") || n.starts_with("AMD64") || n.contains("INTEL") => Self::X86,
+            _ => Self::Unknown(raw.to_string()),
+        };
+        Ok(platform)
+    }
+}
+
+impl From<&MinerControlBoard> for ControlBoardType {
+    fn from(board: &MinerControlBoard) -> Self {
+        match board {
+            MinerControlBoard::Xilinx => Self::Xilinx,
+            MinerControlBoard::BeagleBoneBlack => Self::BeagleBone,
+            MinerControlBoard::AMLogic => Self::AMLogic,
+            MinerControlBoard::CVITek => Self::CVITek,
+            MinerControlBoard::H3
+            | MinerControlBoard::H6
+            | MinerControlBoard::H6OS
+            | MinerControlBoard::H616 => Self::Allwinner,
+            MinerControlBoard::Unknown(raw) => {
+                Self::from_str(raw).unwrap_or(Self::Unknown(raw.clone()))
+            }
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_board_type_from_raw() {
+        let cases = [
+            ("zynq", ControlBoardType::Xilinx),
+            ("xil", ControlBoardType::Xilinx),
+            ("Xilinx", ControlBoardType::Xilinx),
+            ("bb", ControlBoardType::BeagleBone),
+            ("BBB", ControlBoardType::BeagleBone),
+            ("BeagleBone Black", ControlBoardType::BeagleBone),
+            ("cvitek", ControlBoardType::CVITek),
+            ("CVITEK_CV1835", ControlBoardType::CVITek),
+            ("CVCtrl_BHB56902", ControlBoardType::CVITek),
+            ("aml", ControlBoardType::AMLogic),
+            ("AMLogic", ControlBoardType::AMLogic),
+            ("Amlogic Meson GXL (S905X)", ControlBoardType::AMLogic),
+            ("ALLWINNER_H6", ControlBoardType::Allwinner),
+            ("H616", ControlBoardType::Allwinner),
+            ("x86_64", ControlBoardType::X86),
+            (" Intel(R) Atom(TM) ", ControlBoardType::X86),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(ControlBoardType::from_str(raw), Ok(expected), "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_control_board_type_unknown_keeps_raw() {
+        assert_eq!(
+            ControlBoardType::from_str(" STM32 (Device Tree Support) "),
+            Ok(ControlBoardType::Unknown(
+                "STM32 (Device Tree Support)".to_string()
+            ))
+        );
+        assert_eq!(
+            ControlBoardType::from_str("MaraCB_v1.4"),
+            Ok(ControlBoardType::Unknown("MaraCB_v1.4".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_control_board_type_from_board() {
+        assert_eq!(
+            ControlBoardType::from(&MinerControlBoard::H3),
+            ControlBoardType::Allwinner
+        );
+        assert_eq!(
+            ControlBoardType::from(&MinerControlBoard::BeagleBoneBlack),
+            ControlBoardType::BeagleBone
+        );
+        assert_eq!(
+            ControlBoardType::from(&MinerControlBoard::BraiinsCB),
+            ControlBoardType::Unknown("BraiinsCB".to_string())
+        );
+    }
+}
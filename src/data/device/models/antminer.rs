@@ -2,10 +2,11 @@
 use pyo3::prelude::*;
 
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumIter};
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, Display)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, Display, EnumIter)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum AntMinerModel {
     #[serde(alias = "ANTMINER D3")]
     D3,
@@ -83,6 +84,8 @@ pub enum AntMinerModel {
     S19jProPlus,
     #[serde(alias = "ANTMINER S19 XP")]
     S19XP,
+    #[serde(alias = "ANTMINER S19 XP HYD.")]
+    S19XPHydro,
     #[serde(alias = "ANTMINER S19A")]
     S19a,
     #[serde(alias = "ANTMINER S19A PRO")]
@@ -104,15 +107,38 @@ pub enum AntMinerModel {
     #[serde(alias = "ANTMINER BHB68606")]
     S21,
     #[serde(alias = "ANTMINER S21 PRO")]
+    #[serde(alias = "ANTMINER S21PRO")]
     S21Pro,
     #[serde(alias = "ANTMINER S21 XP")]
+    #[serde(alias = "ANTMINER S21XP")]
     S21XP,
     #[serde(alias = "ANTMINER S21+")]
+    #[serde(alias = "ANTMINER S21 PLUS")]
     S21Plus,
     #[serde(alias = "ANTMINER S21 HYD.")]
+    #[serde(alias = "ANTMINER S21 HYD")]
+    #[serde(alias = "ANTMINER S21 HYDRO")]
     S21Hydro,
     #[serde(alias = "ANTMINER S21+ HYD.")]
+    #[serde(alias = "ANTMINER S21+ HYD")]
+    #[serde(alias = "ANTMINER S21+ HYDRO")]
     S21PlusHydro,
     #[serde(alias = "ANTMINER T21")]
     T21,
 }
+
+impl AntMinerModel {
+    /// Whether this is a water cooled hydro model, which has no fans and reports coolant
+    /// temperatures on its boards.
+    pub fn is_hydro(&self) -> bool {
+        matches!(
+            self,
+            AntMinerModel::S19Hydro
+                | AntMinerModel::S19ProHydro
+                | AntMinerModel::S19ProPlusHydro
+                | AntMinerModel::S19XPHydro
+                | AntMinerModel::S21Hydro
+                | AntMinerModel::S21PlusHydro
+        )
+    }
+}
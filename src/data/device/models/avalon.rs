@@ -2,10 +2,11 @@
 use pyo3::prelude::*;
 
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumIter};
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
-#[derive(Debug, Display, Clone, PartialEq, Eq, Serialize, Deserialize, Copy, Hash)]
+#[derive(Debug, Display, Clone, PartialEq, Eq, Serialize, Deserialize, Copy, Hash, EnumIter)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum AvalonMinerModel {
     #[serde(alias = "721")]
     Avalon721,
@@ -2,13 +2,23 @@
 use pyo3::prelude::*;
 
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumIter};
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, Display)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, Display, EnumIter)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum BraiinsModel {
     #[serde(alias = "BRAIINS MINI MINER BMM 100")]
+    #[serde(alias = "BRAIINS BMM 100")]
+    #[serde(alias = "BMM 100")]
     BMM100,
     #[serde(alias = "BRAIINS MINI MINER BMM 101")]
+    #[serde(alias = "BRAIINS BMM 101")]
+    #[serde(alias = "BMM 101")]
     BMM101,
+    /// A Braiins control board reporting without the hashboards it drives
+    #[serde(alias = "BRAIINS CONTROL BOARD BCB100")]
+    #[serde(alias = "BRAIINS BCB100")]
+    #[serde(alias = "BCB 100")]
+    BCB100,
 }
@@ -2,13 +2,16 @@
 use pyo3::prelude::*;
 
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumIter};
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, Display)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, Display, EnumIter)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum EPicModel {
-    #[serde(alias = "BLOCKMINER 520i")]
+    #[serde(alias = "BLOCKMINER 520i", alias = "BLOCKMINER 520I")]
     BM520i,
+    #[serde(alias = "BLOCKMINER 720i", alias = "BLOCKMINER 720I")]
+    BM720i,
     #[serde(alias = "ANTMINER S19J PRO DUAL")]
     S19JProDual,
 }
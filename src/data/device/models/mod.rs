@@ -82,7 +82,8 @@ impl FromStr for EPicModel {
 }
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum MinerModel {
     AntMiner(AntMinerModel),
@@ -91,6 +92,12 @@ pub enum MinerModel {
     Bitaxe(BitaxeModel),
     AvalonMiner(AvalonMinerModel),
     EPic(EPicModel),
+    /// A model string reported by the miner that is not in the model tables yet.
+    /// Keeps the raw string so the miner can still be identified and collected from.
+    Unknown {
+        make: MinerMake,
+        raw: String,
+    },
 }
 
 impl Display for MinerModel {
@@ -102,12 +109,13 @@ impl Display for MinerModel {
             MinerModel::Bitaxe(m) => Ok(m.fmt(f)?),
             MinerModel::EPic(m) => Ok(m.fmt(f)?),
             MinerModel::AvalonMiner(m) => Ok(m.fmt(f)?),
+            MinerModel::Unknown { raw, .. } => write!(f, "{raw}"),
         }
     }
 }
 
-impl From<MinerModel> for MinerMake {
-    fn from(model: MinerModel) -> Self {
+impl From<&MinerModel> for MinerMake {
+    fn from(model: &MinerModel) -> Self {
         match model {
             MinerModel::AntMiner(_) => MinerMake::AntMiner,
             MinerModel::WhatsMiner(_) => MinerMake::WhatsMiner,
@@ -115,6 +123,7 @@ impl From<MinerModel> for MinerMake {
             MinerModel::Bitaxe(_) => MinerMake::Bitaxe,
             MinerModel::EPic(_) => MinerMake::EPic,
             MinerModel::AvalonMiner(_) => MinerMake::AvalonMiner,
+            MinerModel::Unknown { make, .. } => *make,
         }
     }
 }
@@ -142,6 +151,29 @@ impl MinerModelFactory {
     }
 
     pub(crate) fn parse_model(&self, model_str: &str) -> Option<MinerModel> {
+        self.parse_known_model(model_str).or_else(|| {
+            let raw = model_str.trim();
+            if raw.is_empty() {
+                return None;
+            }
+            Some(MinerModel::Unknown {
+                make: self.fallback_make()?,
+                raw: raw.to_string(),
+            })
+        })
+    }
+
+    /// The make to attach to an unrecognized model string, if one can be inferred.
+    /// Alternative firmwares without a known make are almost always running on AntMiners.
+    fn fallback_make(&self) -> Option<MinerMake> {
+        match (self.make, self.firmware) {
+            (Some(make), _) => Some(make),
+            (None, Some(_)) => Some(MinerMake::AntMiner),
+            (None, None) => None,
+        }
+    }
+
+    fn parse_known_model(&self, model_str: &str) -> Option<MinerModel> {
         match self.make {
             Some(MinerMake::AntMiner) => {
                 let model = AntMinerModel::from_str(model_str).ok();
@@ -188,6 +220,14 @@ impl MinerModelFactory {
                     if let Ok(model) = AntMinerModel::from_str(model_str) {
                         return Some(MinerModel::AntMiner(model));
                     }
+                    // MaraFW also runs on AvalonMiners, reported as e.g. `AVALON 1246`
+                    let avalon = model_str
+                        .trim_start_matches("AVALONMINER")
+                        .trim_start_matches("AVALON")
+                        .trim();
+                    if let Ok(model) = AvalonMinerModel::from_str(avalon) {
+                        return Some(MinerModel::AvalonMiner(model));
+                    }
                     None
                 }
                 None => None,
@@ -197,3 +237,101 @@ impl MinerModelFactory {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "whatsminer")]
+    use crate::miners::factory::model::whatsminer::normalize_model;
+    use strum::IntoEnumIterator;
+
+    /// Model strings as firmwares report them, from the AntMiner web `minertype`, cgminer
+    /// `stats` types and the WhatsMiner `devdetails`/`get.device.info` models.
+    const ANTMINER_RAW: &[(&str, AntMinerModel)] = &[
+        ("Antminer S19 XP", AntMinerModel::S19XP),
+        ("Antminer S19 XP Hyd.", AntMinerModel::S19XPHydro),
+        ("Antminer S19k Pro", AntMinerModel::S19KPro),
+        ("Antminer S21", AntMinerModel::S21),
+        ("Antminer S21 Pro", AntMinerModel::S21Pro),
+        ("Antminer S21Pro", AntMinerModel::S21Pro),
+        ("Antminer S21 XP", AntMinerModel::S21XP),
+        ("Antminer S21XP", AntMinerModel::S21XP),
+        ("Antminer S21+", AntMinerModel::S21Plus),
+        ("Antminer S21 Hyd.", AntMinerModel::S21Hydro),
+        ("Antminer S21 Hyd", AntMinerModel::S21Hydro),
+        ("Antminer S21+ Hyd.", AntMinerModel::S21PlusHydro),
+        ("Antminer T21", AntMinerModel::T21),
+    ];
+
+    #[cfg(feature = "whatsminer")]
+    const WHATSMINER_RAW: &[(&str, WhatsMinerModel)] = &[
+        ("M60S++_VL30", WhatsMinerModel::M60SPlusPlusVL30),
+        ("M60S++_VL41", WhatsMinerModel::M60SPlusPlusVL40),
+        ("M60S_VK10", WhatsMinerModel::M60SVK10),
+        ("M61_VL50", WhatsMinerModel::M61VL50),
+        ("M63S_VL10", WhatsMinerModel::M63SVL10),
+        ("M63S+_VL20", WhatsMinerModel::M63SPlusVL20),
+        ("M66S_VK20", WhatsMinerModel::M66SVK20),
+        ("M66S+_VL40", WhatsMinerModel::M66SPlusVL40),
+        ("M70_VM30", WhatsMinerModel::M70VM30),
+    ];
+
+    #[test]
+    fn test_raw_antminer_model_strings() {
+        let mut antminer = MinerModelFactory::new();
+        antminer.with_make(MinerMake::AntMiner);
+        for (raw, model) in ANTMINER_RAW {
+            assert_eq!(
+                antminer.parse_model(&raw.to_uppercase()),
+                Some(MinerModel::AntMiner(*model)),
+                "{raw}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "whatsminer")]
+    fn test_raw_whatsminer_model_strings() {
+        let mut whatsminer = MinerModelFactory::new();
+        whatsminer.with_make(MinerMake::WhatsMiner);
+        for (raw, model) in WHATSMINER_RAW {
+            assert_eq!(
+                whatsminer.parse_model(&normalize_model(raw)),
+                Some(MinerModel::WhatsMiner(*model)),
+                "{raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_marathon_hardware_make() {
+        let mut marathon = MinerModelFactory::new();
+        marathon.with_firmware(MinerFirmware::Marathon);
+        let cases = [
+            ("ANTMINER S19J PRO", MinerMake::AntMiner),
+            ("ANTMINER S21", MinerMake::AntMiner),
+            ("AVALON 1246", MinerMake::AvalonMiner),
+            ("AVALONMINER 1566", MinerMake::AvalonMiner),
+        ];
+        for (raw, make) in cases {
+            let model = marathon.parse_model(raw).unwrap();
+            assert!(!matches!(model, MinerModel::Unknown { .. }), "{raw}");
+            assert_eq!(MinerMake::from(&model), make, "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_every_variant_parses() {
+        // every variant must at least be reachable through its own name
+        let unparsed: Vec<String> = AntMinerModel::iter()
+            .filter(|m| AntMinerModel::from_str(&m.to_string()).ok() != Some(*m))
+            .map(|m| m.to_string())
+            .chain(
+                WhatsMinerModel::iter()
+                    .filter(|m| WhatsMinerModel::from_str(&m.to_string()).ok() != Some(*m))
+                    .map(|m| m.to_string()),
+            )
+            .collect();
+        assert!(unparsed.is_empty(), "models that don't parse: {unparsed:?}");
+    }
+}
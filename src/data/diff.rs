@@ -0,0 +1,605 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeSet, HashSet};
+
+use super::board::BoardData;
+use super::hashrate::{HashRate, HashRateUnit};
+use super::miner::MinerData;
+
+/// Fields ignored by default, as they change on every poll.
+pub const DEFAULT_IGNORED_FIELDS: [&str; 4] =
+    ["timestamp", "collection_duration", "clock_skew", "uptime"];
+
+/// Tolerances and ignored fields used by [`diff_with_options`].
+///
+/// Fields are identified by the same strings used in [`FieldChange::field`], ignoring a field
+/// also ignores everything nested under it, so `hashboards` ignores `hashboards.0.hashrate`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffOptions {
+    /// Relative hashrate and efficiency change in percent that is still considered unchanged
+    pub hashrate_tolerance: f64,
+    /// Absolute temperature change in °C that is still considered unchanged
+    pub temperature_tolerance: f64,
+    /// Relative power consumption, coolant flow and PSU input voltage change in percent that is
+    /// still considered unchanged
+    pub wattage_tolerance: f64,
+    /// Fields that are never reported as changed
+    pub ignore: HashSet<String>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            hashrate_tolerance: 5.0,
+            temperature_tolerance: 2.0,
+            wattage_tolerance: 5.0,
+            ignore: DEFAULT_IGNORED_FIELDS.map(String::from).into(),
+        }
+    }
+}
+
+impl DiffOptions {
+    pub fn with_hashrate_tolerance(mut self, percent: f64) -> Self {
+        self.hashrate_tolerance = percent;
+        self
+    }
+
+    pub fn with_temperature_tolerance(mut self, celsius: f64) -> Self {
+        self.temperature_tolerance = celsius;
+        self
+    }
+
+    pub fn with_wattage_tolerance(mut self, percent: f64) -> Self {
+        self.wattage_tolerance = percent;
+        self
+    }
+
+    /// Never report changes to `field` or anything nested under it.
+    pub fn ignore(mut self, field: &str) -> Self {
+        self.ignore.insert(field.to_string());
+        self
+    }
+
+    /// Report changes to `field` again, such as one of the [`DEFAULT_IGNORED_FIELDS`].
+    pub fn include(mut self, field: &str) -> Self {
+        self.ignore.remove(field);
+        self
+    }
+
+    fn is_ignored(&self, field: &str) -> bool {
+        self.ignore.iter().any(|ignored| {
+            field
+                .strip_prefix(ignored.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    }
+}
+
+/// A single changed field between two [`MinerData`] snapshots.
+///
+/// Hashrates are given in TH/s, temperatures in °C and power in W.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Stable identifier of the field, such as `firmware_version`, `pools.active` or
+    /// `hashboards.1.active`
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// The meaningful changes between two [`MinerData`] snapshots of the same miner.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MinerDataDiff {
+    pub changes: Vec<FieldChange>,
+}
+
+impl MinerDataDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The change to `field`, if it changed.
+    pub fn get(&self, field: &str) -> Option<&FieldChange> {
+        self.changes.iter().find(|c| c.field == field)
+    }
+
+    /// The identifiers of every changed field.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.changes.iter().map(|c| c.field.as_str())
+    }
+}
+
+/// Compare two snapshots with the default [`DiffOptions`].
+pub fn diff(old: &MinerData, new: &MinerData) -> MinerDataDiff {
+    diff_with_options(old, new, &DiffOptions::default())
+}
+
+/// Compare two snapshots, ignoring noise within the tolerances in `options`.
+pub fn diff_with_options(old: &MinerData, new: &MinerData, options: &DiffOptions) -> MinerDataDiff {
+    let mut differ = Differ {
+        options,
+        changes: Vec::new(),
+    };
+
+    differ.exact("schema_version", &old.schema_version, &new.schema_version);
+    differ.exact("timestamp", &old.timestamp, &new.timestamp);
+    differ.exact(
+        "collection_duration",
+        &(old.collection_duration.as_millis() as u64),
+        &(new.collection_duration.as_millis() as u64),
+    );
+    differ.exact("clock_skew", &old.clock_skew, &new.clock_skew);
+    differ.exact("ip", &old.ip, &new.ip);
+    differ.exact(
+        "mac",
+        &old.mac.map(|m| m.to_string()),
+        &new.mac.map(|m| m.to_string()),
+    );
+    differ.exact("labels", &old.labels, &new.labels);
+    differ.exact("device_info", &old.device_info, &new.device_info);
+    differ.exact("serial_number", &old.serial_number, &new.serial_number);
+    differ.exact("hostname", &old.hostname, &new.hostname);
+    differ.exact("api_version", &old.api_version, &new.api_version);
+    differ.exact(
+        "firmware_version",
+        &old.firmware_version,
+        &new.firmware_version,
+    );
+    differ.exact(
+        "firmware_build_time",
+        &old.firmware_build_time,
+        &new.firmware_build_time,
+    );
+    differ.exact("base_firmware", &old.base_firmware, &new.base_firmware);
+    differ.exact(
+        "control_board_version",
+        &old.control_board_version,
+        &new.control_board_version,
+    );
+    differ.exact(
+        "control_board_type",
+        &old.control_board_type,
+        &new.control_board_type,
+    );
+    differ.exact(
+        "expected_hashboards",
+        &old.expected_hashboards,
+        &new.expected_hashboards,
+    );
+    differ.hashboards(&old.hashboards, &new.hashboards);
+    differ.hashrate("hashrate", &old.hashrate, &new.hashrate);
+    differ.hashrate(
+        "expected_hashrate",
+        &old.expected_hashrate,
+        &new.expected_hashrate,
+    );
+    differ.hashrate(
+        "average_hashrate",
+        &old.average_hashrate,
+        &new.average_hashrate,
+    );
+    differ.exact("expected_chips", &old.expected_chips, &new.expected_chips);
+    differ.exact("total_chips", &old.total_chips, &new.total_chips);
+    differ.exact("expected_fans", &old.expected_fans, &new.expected_fans);
+    differ.exact("fans.count", &old.fans.len(), &new.fans.len());
+    differ.exact("psu_fans.count", &old.psu_fans.len(), &new.psu_fans.len());
+    differ.exact("fan_control", &old.fan_control, &new.fan_control);
+    differ.within(
+        "average_temperature",
+        old.average_temperature.map(|t| t.as_celsius()),
+        new.average_temperature.map(|t| t.as_celsius()),
+        Tolerance::Absolute(options.temperature_tolerance),
+    );
+    differ.within(
+        "fluid_temperature",
+        old.fluid_temperature.map(|t| t.as_celsius()),
+        new.fluid_temperature.map(|t| t.as_celsius()),
+        Tolerance::Absolute(options.temperature_tolerance),
+    );
+    differ.within(
+        "fluid_flow",
+        old.fluid_flow,
+        new.fluid_flow,
+        Tolerance::Relative(options.wattage_tolerance),
+    );
+    differ.exact("fluid_pressure", &old.fluid_pressure, &new.fluid_pressure);
+    differ.within(
+        "wattage",
+        old.wattage.map(|w| w.as_watts()),
+        new.wattage.map(|w| w.as_watts()),
+        Tolerance::Relative(options.wattage_tolerance),
+    );
+    differ.exact(
+        "wattage_estimated",
+        &old.wattage_estimated,
+        &new.wattage_estimated,
+    );
+    differ.exact(
+        "wattage_location",
+        &old.wattage_location,
+        &new.wattage_location,
+    );
+    differ.exact(
+        "wattage_limit",
+        &old.wattage_limit.map(|w| w.as_watts()),
+        &new.wattage_limit.map(|w| w.as_watts()),
+    );
+    differ.within(
+        "efficiency",
+        old.efficiency,
+        new.efficiency,
+        Tolerance::Relative(options.hashrate_tolerance),
+    );
+    differ.within(
+        "psu.power",
+        old.psu.as_ref().and_then(|p| p.power).map(|w| w.as_watts()),
+        new.psu.as_ref().and_then(|p| p.power).map(|w| w.as_watts()),
+        Tolerance::Relative(options.wattage_tolerance),
+    );
+    differ.within(
+        "psu_rails.input.voltage",
+        old.psu_input()
+            .and_then(|r| r.voltage)
+            .map(|v| v.as_volts()),
+        new.psu_input()
+            .and_then(|r| r.voltage)
+            .map(|v| v.as_volts()),
+        Tolerance::Relative(options.wattage_tolerance),
+    );
+    differ.exact(
+        "frequency",
+        &old.frequency.map(|f| f.as_megahertz()),
+        &new.frequency.map(|f| f.as_megahertz()),
+    );
+    differ.exact(
+        "voltage",
+        &old.voltage.map(|v| v.as_volts()),
+        &new.voltage.map(|v| v.as_volts()),
+    );
+    differ.exact("work_mode", &old.work_mode, &new.work_mode);
+    differ.exact(
+        "autotune_enabled",
+        &old.autotune_enabled,
+        &new.autotune_enabled,
+    );
+    differ.exact(
+        "autotune_profile_bounds",
+        &old.autotune_profile_bounds,
+        &new.autotune_profile_bounds,
+    );
+    differ.exact("light_flashing", &old.light_flashing, &new.light_flashing);
+    differ.exact("messages", &messages(old), &messages(new));
+    differ.exact(
+        "uptime",
+        &old.uptime.map(|u| u.as_secs()),
+        &new.uptime.map(|u| u.as_secs()),
+    );
+    differ.exact("status", &old.status, &new.status);
+    differ.exact("is_mining", &old.is_mining, &new.is_mining);
+    // the order pools are reported in is not meaningful, only which pools are configured
+    differ.exact("pools", &pool_urls(old), &pool_urls(new));
+    differ.exact("pools.active", &active_pool(old), &active_pool(new));
+
+    MinerDataDiff {
+        changes: differ.changes,
+    }
+}
+
+enum Tolerance {
+    /// Percent of the old value
+    Relative(f64),
+    Absolute(f64),
+}
+
+struct Differ<'a> {
+    options: &'a DiffOptions,
+    changes: Vec<FieldChange>,
+}
+
+impl Differ<'_> {
+    fn push(&mut self, field: &str, old: Value, new: Value) {
+        self.changes.push(FieldChange {
+            field: field.to_string(),
+            old,
+            new,
+        });
+    }
+
+    fn exact<T: Serialize + PartialEq>(&mut self, field: &str, old: &T, new: &T) {
+        if old != new && !self.options.is_ignored(field) {
+            self.push(field, json!(old), json!(new));
+        }
+    }
+
+    fn within(&mut self, field: &str, old: Option<f64>, new: Option<f64>, tolerance: Tolerance) {
+        let changed = match (old, new) {
+            (Some(old), Some(new)) => {
+                let allowed = match tolerance {
+                    Tolerance::Relative(percent) => old.abs() * percent / 100.0,
+                    Tolerance::Absolute(allowed) => allowed,
+                };
+                (new - old).abs() > allowed
+            }
+            (old, new) => old.is_some() != new.is_some(),
+        };
+        if changed && !self.options.is_ignored(field) {
+            self.push(field, json!(old), json!(new));
+        }
+    }
+
+    fn hashrate(&mut self, field: &str, old: &Option<HashRate>, new: &Option<HashRate>) {
+        let as_th = |hr: &HashRate| hr.clone().as_unit(HashRateUnit::TeraHash).value;
+        self.within(
+            field,
+            old.as_ref().map(as_th),
+            new.as_ref().map(as_th),
+            Tolerance::Relative(self.options.hashrate_tolerance),
+        );
+    }
+
+    /// Boards are matched by position, boards only present on one side are covered by the count.
+    fn hashboards(&mut self, old: &[BoardData], new: &[BoardData]) {
+        self.exact("hashboards.count", &old.len(), &new.len());
+        for old_board in old {
+            let Some(new_board) = new.iter().find(|b| b.position == old_board.position) else {
+                continue;
+            };
+            let field = |name: &str| format!("hashboards.{}.{name}", old_board.position);
+            self.exact(&field("active"), &old_board.active, &new_board.active);
+            self.exact(&field("tuned"), &old_board.tuned, &new_board.tuned);
+            self.exact(&field("status"), &old_board.status, &new_board.status);
+            self.exact(
+                &field("working_chips"),
+                &old_board.working_chips,
+                &new_board.working_chips,
+            );
+            self.exact(
+                &field("serial_number"),
+                &old_board.serial_number,
+                &new_board.serial_number,
+            );
+            self.hashrate(&field("hashrate"), &old_board.hashrate, &new_board.hashrate);
+            self.within(
+                &field("board_temperature"),
+                old_board.board_temperature.map(|t| t.as_celsius()),
+                new_board.board_temperature.map(|t| t.as_celsius()),
+                Tolerance::Absolute(self.options.temperature_tolerance),
+            );
+            self.exact(
+                &field("frequency"),
+                &old_board.frequency.map(|f| f.as_megahertz()),
+                &new_board.frequency.map(|f| f.as_megahertz()),
+            );
+            self.exact(
+                &field("voltage"),
+                &old_board.voltage.map(|v| v.as_volts()),
+                &new_board.voltage.map(|v| v.as_volts()),
+            );
+        }
+    }
+}
+
+fn pool_urls(data: &MinerData) -> BTreeSet<String> {
+    data.pools
+        .iter()
+        .filter_map(|p| p.url.as_ref())
+        .map(|url| url.to_string())
+        .collect()
+}
+
+fn active_pool(data: &MinerData) -> Option<String> {
+    data.pools
+        .iter()
+        .find(|p| p.active == Some(true))
+        .and_then(|p| p.url.as_ref())
+        .map(|url| url.to_string())
+}
+
+/// Messages keyed by code and text, ignoring when they were raised.
+fn messages(data: &MinerData) -> BTreeSet<(u64, String)> {
+    data.messages
+        .iter()
+        .map(|m| (m.code, m.message.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerMake, MinerModel};
+    use crate::data::miner::MinerStatus;
+    use crate::data::pool::{PoolData, PoolURL};
+    use measurements::Temperature;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    fn pool(position: u16, url: &str, active: bool) -> PoolData {
+        PoolData {
+            position: Some(position),
+            url: Some(PoolURL::from(url.to_string())),
+            accepted_shares: None,
+            rejected_shares: None,
+            active: Some(active),
+            alive: Some(true),
+            user: Some("worker".to_string()),
+            password: None,
+            last_share_difficulty: None,
+            last_share_time: None,
+            group: None,
+            quota: None,
+        }
+    }
+
+    fn board(position: u8, hashrate: f64, temperature: f64) -> BoardData {
+        BoardData {
+            position,
+            hashrate: Some(HashRate {
+                value: hashrate,
+                unit: HashRateUnit::TeraHash,
+                algo: String::from("SHA256"),
+            }),
+            board_temperature: Some(Temperature::from_celsius(temperature)),
+            active: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn miner_data() -> MinerData {
+        MinerData {
+            schema_version: env!("CARGO_PKG_VERSION").to_owned(),
+            timestamp: 1_700_000_000,
+            collection_duration: Duration::ZERO,
+            clock_skew: None,
+            ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            reported_ip: None,
+            mac: None,
+            labels: None,
+            device_info: DeviceInfo::new(
+                MinerMake::AntMiner,
+                MinerModel::AntMiner(AntMinerModel::S19Pro),
+                MinerFirmware::Stock,
+                HashAlgorithm::SHA256,
+            ),
+            serial_number: None,
+            hostname: None,
+            api_version: None,
+            firmware_version: Some("2024-01-01".to_string()),
+            firmware_build_time: None,
+            base_firmware: None,
+            control_board_version: None,
+            control_board_type: None,
+            expected_hashboards: Some(3),
+            hashboards: vec![board(0, 36.0, 60.0), board(1, 36.0, 60.0)],
+            hashrate: Some(HashRate {
+                value: 110.0,
+                unit: HashRateUnit::TeraHash,
+                algo: String::from("SHA256"),
+            }),
+            expected_hashrate: None,
+            average_hashrate: None,
+            expected_chips: None,
+            total_chips: None,
+            expected_fans: None,
+            working_fans: None,
+            fans: vec![],
+            psu_fans: vec![],
+            fan_control: None,
+            average_temperature: Some(Temperature::from_celsius(65.0)),
+            fluid_temperature: None,
+            fluid_flow: None,
+            fluid_pressure: None,
+            wattage: None,
+            wattage_estimated: false,
+            wattage_location: None,
+            wattage_limit: None,
+            efficiency: None,
+            psu: None,
+            psu_rails: vec![],
+            frequency: None,
+            voltage: None,
+            work_mode: None,
+            autotune_enabled: None,
+            autotune_profile_bounds: None,
+            light_flashing: None,
+            messages: vec![],
+            uptime: Some(Duration::from_secs(3600)),
+            status: MinerStatus::Mining,
+            is_mining: true,
+            pools: vec![
+                pool(0, "stratum+tcp://pool-a.example.com:3333", true),
+                pool(1, "stratum+tcp://pool-b.example.com:3333", false),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_numeric_tolerance() {
+        let old = miner_data();
+        let mut new = old.clone();
+        new.hashrate.as_mut().unwrap().value = 107.0;
+        new.average_temperature = Some(Temperature::from_celsius(66.5));
+        new.hashboards[0].board_temperature = Some(Temperature::from_celsius(61.0));
+        assert!(diff(&old, &new).is_empty());
+
+        new.hashrate.as_mut().unwrap().value = 95.0;
+        new.average_temperature = Some(Temperature::from_celsius(70.0));
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes.fields().collect::<Vec<_>>(),
+            vec!["hashrate", "average_temperature"]
+        );
+        assert_eq!(changes.get("hashrate").unwrap().old, json!(110.0));
+        assert_eq!(changes.get("hashrate").unwrap().new, json!(95.0));
+
+        // a wider tolerance swallows the same change
+        let options = DiffOptions::default().with_hashrate_tolerance(20.0);
+        assert!(
+            diff_with_options(&old, &new, &options)
+                .get("hashrate")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_board_changes() {
+        let old = miner_data();
+        let mut new = old.clone();
+        new.hashboards[1].active = Some(false);
+        new.hashboards.push(board(2, 36.0, 60.0));
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes.fields().collect::<Vec<_>>(),
+            vec!["hashboards.count", "hashboards.1.active"]
+        );
+        assert_eq!(changes.get("hashboards.count").unwrap().new, json!(3));
+    }
+
+    #[test]
+    fn test_pool_reordering() {
+        let old = miner_data();
+        let mut new = old.clone();
+        new.pools.reverse();
+        assert!(diff(&old, &new).is_empty());
+
+        // switching to the backup pool is reported even though the set is unchanged
+        new.pools[0].active = Some(true);
+        new.pools[1].active = Some(false);
+        let changes = diff(&old, &new);
+        assert_eq!(changes.fields().collect::<Vec<_>>(), vec!["pools.active"]);
+
+        let mut new = old.clone();
+        new.pools[1] = pool(1, "stratum+tcp://pool-c.example.com:3333", false);
+        assert_eq!(diff(&old, &new).fields().collect::<Vec<_>>(), vec!["pools"]);
+    }
+
+    #[test]
+    fn test_ignore_list() {
+        let old = miner_data();
+        let mut new = old.clone();
+        new.timestamp += 60;
+        new.uptime = Some(Duration::from_secs(3660));
+        new.firmware_version = Some("2024-06-01".to_string());
+        new.hashboards[0].active = Some(false);
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes.fields().collect::<Vec<_>>(),
+            vec!["firmware_version", "hashboards.0.active"]
+        );
+
+        let options = DiffOptions::default()
+            .ignore("hashboards")
+            .include("uptime");
+        let changes = diff_with_options(&old, &new, &options);
+        assert_eq!(
+            changes.fields().collect::<Vec<_>>(),
+            vec!["firmware_version", "uptime"]
+        );
+
+        let json = serde_json::to_string(&changes).unwrap();
+        let restored: MinerDataDiff = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, changes);
+    }
+}
@@ -0,0 +1,485 @@
+//! Flat exports of [`MinerData`] for tools that don't take JSON.
+//!
+//! [`to_line_protocol`] writes InfluxDB line protocol for time series databases, [`csv_header`]
+//! and [`to_csv_row`] write one CSV row per miner for spreadsheets.
+
+use super::hashrate::{HashRate, HashRateUnit};
+use super::miner::MinerData;
+use std::fmt::Write;
+
+/// The columns written by [`to_csv_row`], in order.
+///
+/// - `timestamp`: collection time in seconds since the Unix epoch
+/// - `ip`, `mac`, `make`, `model`, `firmware`: the miner's identity
+/// - `firmware_version`, `hostname`, `serial_number`: as reported by the miner
+/// - `hashrate_ths`, `expected_hashrate_ths`: hashrates in TH/s
+/// - `wattage_w`, `wattage_limit_w`: power draw and limit in W
+/// - `efficiency_jth`: efficiency in J/TH
+/// - `average_temperature_c`: average chip temperature in °C
+/// - `working_chips`, `expected_chips`: chip counts across all boards
+/// - `fans`: number of fans reported
+/// - `status`, `is_mining`: the operating state
+/// - `uptime_s`: system uptime in seconds
+///
+/// Missing values are left empty.
+pub const CSV_COLUMNS: [&str; 21] = [
+    "timestamp",
+    "ip",
+    "mac",
+    "make",
+    "model",
+    "firmware",
+    "firmware_version",
+    "hostname",
+    "serial_number",
+    "hashrate_ths",
+    "expected_hashrate_ths",
+    "wattage_w",
+    "wattage_limit_w",
+    "efficiency_jth",
+    "average_temperature_c",
+    "working_chips",
+    "expected_chips",
+    "fans",
+    "status",
+    "is_mining",
+    "uptime_s",
+];
+
+/// The CSV header line for [`to_csv_row`], without a line terminator.
+pub fn csv_header() -> String {
+    CSV_COLUMNS.join(",")
+}
+
+/// One CSV row with the columns in [`CSV_COLUMNS`], without a line terminator.
+///
+/// Values containing commas, quotes or line breaks are quoted as in RFC 4180.
+pub fn to_csv_row(data: &MinerData) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let row: [String; 21] = [
+        data.timestamp.to_string(),
+        data.ip.to_string(),
+        optional(data.mac.map(|mac| mac.to_string())),
+        data.device_info.make.to_string(),
+        data.device_info.model.to_string(),
+        data.device_info.firmware.to_string(),
+        optional(data.firmware_version.clone()),
+        optional(data.hostname.clone()),
+        optional(data.serial_number.clone()),
+        optional(terahash(&data.hashrate).map(|h| h.to_string())),
+        optional(terahash(&data.expected_hashrate).map(|h| h.to_string())),
+        optional(data.wattage.map(|w| w.as_watts().to_string())),
+        optional(data.wattage_limit.map(|w| w.as_watts().to_string())),
+        optional(
+            data.efficiency
+                .filter(|e| e.is_finite())
+                .map(|e| e.to_string()),
+        ),
+        optional(data.average_temperature.map(|t| t.as_celsius().to_string())),
+        optional(data.total_chips.map(|c| c.to_string())),
+        optional(data.expected_chips.map(|c| c.to_string())),
+        data.fans.len().to_string(),
+        data.status.to_string(),
+        data.is_mining.to_string(),
+        optional(data.uptime.map(|u| u.as_secs().to_string())),
+    ];
+    row.iter()
+        .map(|value| csv_escape(value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// InfluxDB line protocol for one collection, one point per line.
+///
+/// The first line carries the miner totals, `hashrate_ths`, `wattage_w`, `efficiency_jth` and
+/// so on. Each hashboard and fan gets its own line in the same measurement, told apart by a
+/// `board` or `fan` tag holding its position. Every line is tagged with `ip`, `mac`, `make`,
+/// `model` and `firmware`, and timestamped with the collection time in nanoseconds.
+///
+/// Points without any field, such as a fan with no reading, are left out.
+pub fn to_line_protocol(data: &MinerData, measurement: &str) -> String {
+    let mut tags = vec![
+        ("firmware", data.device_info.firmware.to_string()),
+        ("ip", data.ip.to_string()),
+        ("make", data.device_info.make.to_string()),
+        ("model", data.device_info.model.to_string()),
+    ];
+    if let Some(mac) = data.mac {
+        tags.push(("mac", mac.to_string()));
+    }
+    let timestamp = data.timestamp.saturating_mul(1_000_000_000);
+    let measurement = escape(measurement, &[',', ' ']);
+
+    let mut fields = Vec::new();
+    float_field(&mut fields, "hashrate_ths", terahash(&data.hashrate));
+    float_field(
+        &mut fields,
+        "expected_hashrate_ths",
+        terahash(&data.expected_hashrate),
+    );
+    float_field(&mut fields, "wattage_w", data.wattage.map(|w| w.as_watts()));
+    float_field(
+        &mut fields,
+        "wattage_limit_w",
+        data.wattage_limit.map(|w| w.as_watts()),
+    );
+    float_field(&mut fields, "efficiency_jth", data.efficiency);
+    float_field(
+        &mut fields,
+        "average_temperature_c",
+        data.average_temperature.map(|t| t.as_celsius()),
+    );
+    if let Some(chips) = data.total_chips {
+        fields.push(("working_chips", format!("{chips}i")));
+    }
+    if let Some(uptime) = data.uptime {
+        fields.push(("uptime_s", format!("{}i", uptime.as_secs())));
+    }
+    fields.push(("is_mining", data.is_mining.to_string()));
+    fields.push(("status", format!("\"{}\"", data.status)));
+
+    let mut lines = String::new();
+    write_point(&mut lines, &measurement, &tags, None, &fields, timestamp);
+
+    for board in &data.hashboards {
+        let mut fields = Vec::new();
+        float_field(&mut fields, "hashrate_ths", terahash(&board.hashrate));
+        float_field(
+            &mut fields,
+            "temperature_c",
+            board.board_temperature.map(|t| t.as_celsius()),
+        );
+        float_field(
+            &mut fields,
+            "intake_temperature_c",
+            board.intake_temperature.map(|t| t.as_celsius()),
+        );
+        float_field(
+            &mut fields,
+            "outlet_temperature_c",
+            board.outlet_temperature.map(|t| t.as_celsius()),
+        );
+        if let Some(chips) = board.working_chips {
+            fields.push(("working_chips", format!("{chips}i")));
+        }
+        let index = ("board", board.position.to_string());
+        write_point(
+            &mut lines,
+            &measurement,
+            &tags,
+            Some(index),
+            &fields,
+            timestamp,
+        );
+    }
+
+    for fan in &data.fans {
+        let mut fields = Vec::new();
+        float_field(&mut fields, "rpm", fan.rpm.map(|r| r.as_rpm()));
+        let index = ("fan", fan.position.to_string());
+        write_point(
+            &mut lines,
+            &measurement,
+            &tags,
+            Some(index),
+            &fields,
+            timestamp,
+        );
+    }
+
+    lines
+}
+
+fn terahash(hashrate: &Option<HashRate>) -> Option<f64> {
+    hashrate
+        .clone()
+        .map(|h| h.as_unit(HashRateUnit::TeraHash).value)
+}
+
+/// Floats are written in plain decimal, NaN and infinity can't be represented so are skipped.
+fn float_field(fields: &mut Vec<(&'static str, String)>, key: &'static str, value: Option<f64>) {
+    if let Some(value) = value.filter(|v| v.is_finite()) {
+        fields.push((key, value.to_string()));
+    }
+}
+
+fn write_point(
+    lines: &mut String,
+    measurement: &str,
+    tags: &[(&str, String)],
+    index: Option<(&str, String)>,
+    fields: &[(&str, String)],
+    timestamp: u64,
+) {
+    if fields.is_empty() {
+        return;
+    }
+
+    // tags sorted by key, as InfluxDB recommends
+    let mut tags: Vec<_> = tags.iter().cloned().chain(index).collect();
+    tags.sort_by_key(|(key, _)| *key);
+
+    lines.push_str(measurement);
+    for (key, value) in tags {
+        // empty tag values are not allowed
+        if value.is_empty() {
+            continue;
+        }
+        let _ = write!(lines, ",{key}={}", escape(&value, &[',', '=', ' ']));
+    }
+    for (i, (key, value)) in fields.iter().enumerate() {
+        let separator = if i == 0 { ' ' } else { ',' };
+        let _ = write!(lines, "{separator}{key}={}", escape_field(value));
+    }
+    let _ = writeln!(lines, " {timestamp}");
+}
+
+/// Backslash escape `special` characters and backslashes themselves.
+///
+/// Line protocol has no escape for line breaks, so they are replaced with spaces first.
+fn escape(value: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        let c = if matches!(c, '\n' | '\r') { ' ' } else { c };
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Field values are written pre-formatted, only string values in quotes need escaping.
+fn escape_field(value: &str) -> String {
+    match value
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+    {
+        Some(inner) => format!("\"{}\"", escape(inner, &['"'])),
+        None => value.to_string(),
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::board::BoardData;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerMake, MinerModel};
+    use crate::data::fan::FanData;
+    use crate::data::miner::MinerStatus;
+    use influxdb_line_protocol::{FieldValue, ParsedLine, parse_lines};
+    use macaddr::MacAddr;
+    use measurements::{AngularVelocity, Power, Temperature};
+    use std::collections::HashMap;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    const ADVERSARIAL_MODELS: [&str; 5] = [
+        "Antminer S19j Pro+",
+        "S21 \"Hydro\", rev=2",
+        "trailing\\",
+        "back\\slash\\, comma",
+        "multi\nline",
+    ];
+
+    fn hashrate(value: f64) -> Option<HashRate> {
+        Some(HashRate {
+            value,
+            unit: HashRateUnit::TeraHash,
+            algo: String::from("SHA256"),
+        })
+    }
+
+    fn miner_data(model: MinerModel) -> MinerData {
+        MinerData {
+            schema_version: env!("CARGO_PKG_VERSION").to_owned(),
+            timestamp: 1_700_000_000,
+            collection_duration: Duration::ZERO,
+            clock_skew: None,
+            ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            reported_ip: None,
+            mac: Some(MacAddr::from_str("AA:BB:CC:DD:EE:FF").unwrap()),
+            labels: None,
+            device_info: DeviceInfo::new(
+                MinerMake::AntMiner,
+                model,
+                MinerFirmware::Stock,
+                HashAlgorithm::SHA256,
+            ),
+            serial_number: None,
+            hostname: Some("rack 1, slot \"4\"".to_string()),
+            api_version: None,
+            firmware_version: Some("2024-01-01".to_string()),
+            firmware_build_time: None,
+            base_firmware: None,
+            control_board_version: None,
+            control_board_type: None,
+            expected_hashboards: Some(3),
+            hashboards: (0..3)
+                .map(|position| BoardData {
+                    position,
+                    hashrate: hashrate(36.5),
+                    board_temperature: Some(Temperature::from_celsius(60.0)),
+                    working_chips: Some(76),
+                    ..Default::default()
+                })
+                .collect(),
+            hashrate: hashrate(109.5),
+            expected_hashrate: hashrate(110.0),
+            average_hashrate: None,
+            expected_chips: Some(228),
+            total_chips: Some(228),
+            expected_fans: Some(4),
+            working_fans: None,
+            fans: vec![
+                FanData {
+                    position: 0,
+                    rpm: Some(AngularVelocity::from_rpm(4320.0)),
+                },
+                FanData {
+                    position: 1,
+                    rpm: None,
+                },
+            ],
+            psu_fans: vec![],
+            fan_control: None,
+            average_temperature: Some(Temperature::from_celsius(65.0)),
+            fluid_temperature: None,
+            fluid_flow: None,
+            fluid_pressure: None,
+            wattage: Some(Power::from_watts(3250.0)),
+            wattage_estimated: false,
+            wattage_location: None,
+            wattage_limit: None,
+            efficiency: Some(29.68),
+            psu: None,
+            psu_rails: vec![],
+            frequency: None,
+            voltage: None,
+            work_mode: None,
+            autotune_enabled: None,
+            autotune_profile_bounds: None,
+            light_flashing: None,
+            messages: vec![],
+            uptime: Some(Duration::from_secs(3600)),
+            status: MinerStatus::Mining,
+            is_mining: true,
+            pools: vec![],
+        }
+    }
+
+    fn tags(line: &ParsedLine) -> HashMap<String, String> {
+        line.series
+            .tag_set
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn float(line: &ParsedLine, key: &str) -> Option<f64> {
+        line.field_set
+            .iter()
+            .find(|(k, _)| k.to_string() == key)
+            .and_then(|(_, value)| match value {
+                FieldValue::F64(value) => Some(*value),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn test_line_protocol_escaping() {
+        for raw in ADVERSARIAL_MODELS {
+            let model = MinerModel::Unknown {
+                make: MinerMake::AntMiner,
+                raw: raw.to_string(),
+            };
+            let output = to_line_protocol(&miner_data(model), "asic miners,v2");
+            let lines = parse_lines(&output)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_or_else(|e| panic!("{raw:?} does not parse: {e}\n{output}"));
+
+            // totals, three boards and the fan with a reading
+            assert_eq!(lines.len(), 5, "{output}");
+            for line in &lines {
+                assert_eq!(line.series.measurement.to_string(), "asic miners,v2");
+                assert_eq!(line.timestamp, Some(1_700_000_000_000_000_000));
+                let tags = tags(line);
+                assert_eq!(tags["model"], raw.replace('\n', " "));
+                assert_eq!(tags["ip"], "10.0.0.1");
+                assert_eq!(tags["mac"], "AA:BB:CC:DD:EE:FF");
+                assert_eq!(tags["make"], "AntMiner");
+                assert_eq!(tags["firmware"], "Stock");
+            }
+
+            let totals = &lines[0];
+            assert_eq!(float(totals, "hashrate_ths"), Some(109.5));
+            assert_eq!(float(totals, "wattage_w"), Some(3250.0));
+            assert_eq!(float(totals, "efficiency_jth"), Some(29.68));
+            assert!(totals.field_set.iter().any(|(key, value)| {
+                key.to_string() == "status"
+                    && matches!(value, FieldValue::String(s) if s.to_string() == "Mining")
+            }));
+        }
+    }
+
+    #[test]
+    fn test_line_protocol_series() {
+        let mut data = miner_data(MinerModel::AntMiner(AntMinerModel::S19jPro));
+        data.efficiency = Some(f64::NAN);
+        let output = to_line_protocol(&data, "miner");
+        let lines = parse_lines(&output).collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert!(!tags(&lines[0]).contains_key("board"));
+        assert_eq!(float(&lines[0], "efficiency_jth"), None);
+        for (position, line) in lines[1..4].iter().enumerate() {
+            assert_eq!(tags(line)["board"], position.to_string());
+            assert_eq!(float(line, "hashrate_ths"), Some(36.5));
+            assert_eq!(float(line, "temperature_c"), Some(60.0));
+        }
+        assert_eq!(tags(&lines[4])["fan"], "0");
+        assert_eq!(float(&lines[4], "rpm"), Some(4320.0));
+    }
+
+    #[test]
+    fn test_csv_escaping() {
+        let mut output = csv_header();
+        for raw in ADVERSARIAL_MODELS {
+            let model = MinerModel::Unknown {
+                make: MinerMake::AntMiner,
+                raw: raw.to_string(),
+            };
+            output.push('\n');
+            output.push_str(&to_csv_row(&miner_data(model)));
+        }
+
+        let mut reader = csv::Reader::from_reader(output.as_bytes());
+        let header = reader.headers().unwrap().clone();
+        assert_eq!(header.iter().collect::<Vec<_>>(), CSV_COLUMNS);
+
+        let rows = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(rows.len(), ADVERSARIAL_MODELS.len());
+        for (row, raw) in rows.iter().zip(ADVERSARIAL_MODELS) {
+            let column = |name| &row[CSV_COLUMNS.iter().position(|c| *c == name).unwrap()];
+            assert_eq!(row.len(), CSV_COLUMNS.len());
+            assert_eq!(column("model"), raw);
+            assert_eq!(column("hostname"), "rack 1, slot \"4\"");
+            assert_eq!(column("mac"), "AA:BB:CC:DD:EE:FF");
+            assert_eq!(column("hashrate_ths"), "109.5");
+            assert_eq!(column("wattage_limit_w"), "");
+            assert_eq!(column("fans"), "2");
+            assert_eq!(column("status"), "Mining");
+            assert_eq!(column("uptime_s"), "3600");
+        }
+    }
+}
@@ -2,14 +2,41 @@ use crate::data::serialize;
 use measurements::AngularVelocity;
 use serde::{Deserialize, Serialize};
 use serialize::serialize_angular_velocity;
+use strum::Display;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct FanData {
     /// The position or index of the fan as seen by the device
     /// Usually dependent on where to fan is connected to the control board
     pub position: i16,
     /// The RPM of the fan
     #[serde(serialize_with = "serialize_angular_velocity")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "RPM")))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rpm: Option<AngularVelocity>,
 }
+
+/// How the firmware drives the fans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FanMode {
+    /// The firmware adjusts the fan speed to hold a target temperature
+    Auto,
+    /// The fans run at a fixed, configured speed
+    Manual,
+    /// The fans are switched off, as for immersion or liquid cooling
+    Off,
+}
+
+/// What the miner is set to do with its fans, to compare with the measured [`FanData`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FanControl {
+    pub mode: FanMode,
+    /// The duty cycle the fans are driven at in percent.
+    /// Configured in manual mode, chosen by the firmware in auto mode if it reports it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_pct: Option<u8>,
+}
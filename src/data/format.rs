@@ -0,0 +1,292 @@
+//! Human-readable formatting shared by the `Display` impls of the data types.
+//!
+//! Missing readings are shown as a dash, so the columns of a summary stay in place.
+
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+use measurements::Power;
+
+use crate::data::device::HashAlgorithm;
+use crate::data::hashrate::{HashRate, HashRateUnit};
+
+/// The value, or a dash when it is missing. Formatting flags such as precision are passed on.
+pub(crate) struct OrDash<T>(pub Option<T>);
+
+impl<T: Display> Display for OrDash<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(value) => value.fmt(f),
+            None => f.write_str("-"),
+        }
+    }
+}
+
+/// A reading rounded to a whole number, followed by its unit, such as `3021 W`.
+pub(crate) struct Measured(pub f64, pub &'static str);
+
+impl Display for Measured {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.0}{}", self.0, self.1)
+    }
+}
+
+/// A duration in its two largest units, such as `3d 4h` or `12h 5m`.
+pub(crate) struct Uptime(pub Duration);
+
+impl Display for Uptime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let (days, hours, minutes) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60);
+        match (days, hours, minutes) {
+            (0, 0, 0) => write!(f, "{secs}s"),
+            (0, 0, _) => write!(f, "{minutes}m"),
+            (0, _, _) => write!(f, "{hours}h {minutes}m"),
+            _ => write!(f, "{days}d {hours}h"),
+        }
+    }
+}
+
+/// Joules per unit of hashing in the default unit of the algorithm, such as J/TH for SHA256
+/// and J/MH for Scrypt.
+pub(crate) struct Efficiency {
+    joules: f64,
+    unit: HashRateUnit,
+}
+
+impl Efficiency {
+    pub(crate) fn new(wattage: Power, hashrate: &HashRate, algo: &HashAlgorithm) -> Option<Self> {
+        let unit = algo.default_unit();
+        let hashrate = hashrate.clone().as_unit(unit.clone()).value;
+        (hashrate > 0.0).then(|| Self {
+            joules: wattage.as_watts() / hashrate,
+            unit,
+        })
+    }
+}
+
+impl Display for Efficiency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // J/MH figures are well below 1, keep them from rounding to nothing
+        let precision = if self.joules < 10.0 { 2 } else { 1 };
+        let unit = self.unit.to_string();
+        write!(
+            f,
+            "{:.*} J/{}",
+            precision,
+            self.joules,
+            unit.trim_end_matches("/s")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::board::{BoardData, BoardStatus};
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::device::{DeviceInfo, MinerFirmware, MinerMake, MinerModel};
+    use crate::data::fan::FanData;
+    use crate::data::miner::{MinerData, MinerStatus};
+    use crate::data::pool::{PoolData, PoolURL};
+    use measurements::{AngularVelocity, Frequency, Temperature};
+    use std::net::IpAddr;
+
+    /// A miner that reported nothing beyond what identification found.
+    fn sparse() -> MinerData {
+        MinerData {
+            schema_version: env!("CARGO_PKG_VERSION").to_owned(),
+            timestamp: 1_700_000_000,
+            collection_duration: Duration::ZERO,
+            clock_skew: None,
+            ip: IpAddr::from([10, 0, 0, 2]),
+            reported_ip: None,
+            mac: None,
+            labels: None,
+            device_info: DeviceInfo::new(
+                MinerMake::AntMiner,
+                MinerModel::AntMiner(AntMinerModel::S19jPro),
+                MinerFirmware::Stock,
+                HashAlgorithm::SHA256,
+            ),
+            serial_number: None,
+            hostname: None,
+            api_version: None,
+            firmware_version: None,
+            firmware_build_time: None,
+            base_firmware: None,
+            control_board_version: None,
+            control_board_type: None,
+            expected_hashboards: None,
+            hashboards: vec![],
+            hashrate: None,
+            expected_hashrate: None,
+            average_hashrate: None,
+            expected_chips: None,
+            total_chips: None,
+            expected_fans: None,
+            working_fans: None,
+            fans: vec![],
+            psu_fans: vec![],
+            fan_control: None,
+            average_temperature: None,
+            fluid_temperature: None,
+            fluid_flow: None,
+            fluid_pressure: None,
+            wattage: None,
+            wattage_estimated: false,
+            wattage_location: None,
+            wattage_limit: None,
+            efficiency: None,
+            psu: None,
+            psu_rails: vec![],
+            frequency: None,
+            voltage: None,
+            work_mode: None,
+            autotune_enabled: None,
+            autotune_profile_bounds: None,
+            light_flashing: None,
+            messages: vec![],
+            uptime: None,
+            status: MinerStatus::Mining,
+            is_mining: true,
+            pools: vec![],
+        }
+    }
+
+    fn hashrate(value: f64, unit: HashRateUnit, algo: &str) -> Option<HashRate> {
+        Some(HashRate {
+            value,
+            unit,
+            algo: algo.to_string(),
+        })
+    }
+
+    fn board(position: u8, temperature: f64, status: Option<BoardStatus>) -> BoardData {
+        BoardData {
+            position,
+            hashrate: hashrate(49.1, HashRateUnit::TeraHash, "SHA256"),
+            expected_hashrate: hashrate(52.0, HashRateUnit::TeraHash, "SHA256"),
+            board_temperature: Some(Temperature::from_celsius(temperature)),
+            expected_chips: Some(126),
+            working_chips: Some(126),
+            frequency: Some(Frequency::from_megahertz(525.0)),
+            active: Some(true),
+            status,
+            ..Default::default()
+        }
+    }
+
+    fn pool(position: u16, host: &str, active: bool, accepted: u64, rejected: u64) -> PoolData {
+        PoolData {
+            position: Some(position),
+            url: PoolURL::parse(&format!("stratum+tcp://{host}:3333")),
+            accepted_shares: Some(accepted),
+            rejected_shares: Some(rejected),
+            active: Some(active),
+            alive: Some(true),
+            user: Some("worker.1".to_string()),
+            password: Some("hunter2".to_string()),
+            last_share_difficulty: None,
+            last_share_time: None,
+            group: None,
+            quota: None,
+        }
+    }
+
+    fn full() -> MinerData {
+        MinerData {
+            ip: IpAddr::from([192, 168, 1, 50]),
+            hashboards: vec![
+                board(0, 64.0, None),
+                board(1, 66.0, Some(BoardStatus::ThermalThrottled)),
+            ],
+            hashrate: hashrate(98.2, HashRateUnit::TeraHash, "SHA256"),
+            // reported in GH/s, shown in the largest unit that fits
+            expected_hashrate: hashrate(104_000.0, HashRateUnit::GigaHash, "SHA256"),
+            fans: vec![
+                FanData {
+                    position: 0,
+                    rpm: Some(AngularVelocity::from_rpm(5460.0)),
+                },
+                FanData {
+                    position: 1,
+                    rpm: None,
+                },
+            ],
+            average_temperature: Some(Temperature::from_celsius(65.0)),
+            wattage: Some(Power::from_watts(3021.0)),
+            uptime: Some(Duration::from_secs(3 * 86_400 + 4 * 3_600 + 120)),
+            pools: vec![
+                pool(0, "stratum.example.com", true, 15234, 37),
+                pool(1, "backup.example.com", false, 0, 0),
+            ],
+            ..sparse()
+        }
+    }
+
+    #[test]
+    fn test_summary_full() {
+        let data = full();
+        assert_eq!(
+            data.to_string(),
+            "192.168.1.50 | S19jPro (Stock) | 98.2 TH/s / 104.0 TH/s | 3021 W | 30.8 J/TH | 65°C | 2 pools | up 3d 4h"
+        );
+        assert_eq!(
+            data.summary_table(),
+            [
+                "192.168.1.50 | S19jPro (Stock) | 98.2 TH/s / 104.0 TH/s | 3021 W | 30.8 J/TH | 65°C | 2 pools | up 3d 4h",
+                "  board 0 | 49.1 TH/s / 52.0 TH/s | 64°C | 126/126 chips | 525 MHz",
+                "  board 1 | 49.1 TH/s / 52.0 TH/s | 66°C | 126/126 chips | 525 MHz | thermal_throttled",
+                "  fan 0 | 5460 RPM",
+                "  fan 1 | -",
+                "  pool 0 | stratum+tcp://stratum.example.com:3333 | worker.1 | alive, active | 15234 accepted, 37 rejected",
+                "  pool 1 | stratum+tcp://backup.example.com:3333 | worker.1 | alive | 0 accepted, 0 rejected",
+            ]
+            .join("\n")
+        );
+        assert!(!data.summary_table().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_summary_sparse() {
+        let data = sparse();
+        let line = "10.0.0.2 | S19jPro (Stock) | - / - | - | - | - | 0 pools | up -";
+        assert_eq!(data.to_string(), line);
+        assert_eq!(data.summary_table(), line);
+
+        let board = BoardData {
+            position: 2,
+            active: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            board.to_string(),
+            "board 2 | - / - | - | -/- chips | - | inactive"
+        );
+    }
+
+    #[test]
+    fn test_summary_scrypt() {
+        let data = MinerData {
+            ip: IpAddr::from([10, 0, 0, 3]),
+            device_info: DeviceInfo::new(
+                MinerMake::AntMiner,
+                MinerModel::AntMiner(AntMinerModel::L7),
+                MinerFirmware::Stock,
+                HashAlgorithm::Scrypt,
+            ),
+            hashrate: hashrate(9_500.0, HashRateUnit::MegaHash, "Scrypt"),
+            expected_hashrate: hashrate(9_300.0, HashRateUnit::MegaHash, "Scrypt"),
+            wattage: Some(Power::from_watts(3425.0)),
+            average_temperature: Some(Temperature::from_celsius(70.0)),
+            uptime: Some(Duration::from_secs(45 * 60 + 30)),
+            pools: vec![pool(0, "ltc.example.com", true, 812, 3)],
+            ..sparse()
+        };
+        assert_eq!(
+            data.to_string(),
+            "10.0.0.3 | L7 (Stock) | 9.5 GH/s / 9.3 GH/s | 3425 W | 0.36 J/MH | 70°C | 1 pool | up 45m"
+        );
+    }
+}
@@ -10,6 +10,8 @@ use std::{
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum HashRateUnit {
     Hash,
     KiloHash,
@@ -24,6 +26,18 @@ pub enum HashRateUnit {
 }
 
 impl HashRateUnit {
+    const ALL: [HashRateUnit; 9] = [
+        HashRateUnit::Hash,
+        HashRateUnit::KiloHash,
+        HashRateUnit::MegaHash,
+        HashRateUnit::GigaHash,
+        HashRateUnit::TeraHash,
+        HashRateUnit::PetaHash,
+        HashRateUnit::ExaHash,
+        HashRateUnit::ZettaHash,
+        HashRateUnit::YottaHash,
+    ];
+
     fn to_multiplier(&self) -> f64 {
         match self {
             HashRateUnit::Hash => 1e0,
@@ -57,6 +71,7 @@ impl Display for HashRateUnit {
 
 #[cfg_attr(feature = "python", pyclass(get_all, module = "asic_rs"))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct HashRate {
     /// The current amount of hashes being computed
     pub value: f64,
@@ -76,6 +91,18 @@ impl HashRate {
             algo: self.algo,
         }
     }
+
+    /// This hashrate in the largest unit that keeps the value at 1 or above, such as 98.2 TH/s
+    /// rather than 98200 GH/s.
+    pub fn scaled(&self) -> Self {
+        let hashes = self.value * self.unit.to_multiplier();
+        let unit = HashRateUnit::ALL
+            .iter()
+            .rev()
+            .find(|unit| hashes >= unit.to_multiplier())
+            .unwrap_or(&self.unit);
+        self.clone().as_unit(unit.clone())
+    }
 }
 
 impl Display for HashRate {
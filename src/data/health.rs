@@ -0,0 +1,434 @@
+//! Thresholds for the health warnings derived from the readings a miner reports.
+//!
+//! Thermal limits depend on how a site is cooled, so [`HealthThresholds`] can be set per miner,
+//! or for every miner through the factory, and loaded from a config file.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+use crate::data::board::BoardData;
+use crate::data::fan::FanData;
+use crate::data::hashrate::HashRate;
+use crate::data::message::{MessageSeverity, MinerMessage, MinerMessageKind};
+use crate::data::miner::{MinerData, MinerStatus};
+use crate::data::pool::PoolData;
+
+static SOLO_POOLS: LazyLock<RwLock<Vec<String>>> = LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Leave pools whose URL contains one of `patterns` out of the stratum watchdog, see
+/// [`HealthThresholds::max_share_age_seconds`].
+///
+/// Meant for solo mining, where going a long time without a share is normal.
+pub fn set_solo_pools(patterns: Vec<String>) {
+    *SOLO_POOLS.write().expect("solo pools poisoned") = patterns;
+}
+
+fn is_solo_pool(pool: &PoolData) -> bool {
+    let Some(url) = &pool.url else {
+        return false;
+    };
+    let url = url.to_string();
+    SOLO_POOLS
+        .read()
+        .expect("solo pools poisoned")
+        .iter()
+        .any(|pattern| url.contains(pattern.as_str()))
+}
+
+/// Limits outside of which a miner gets a health warning, `None` disables a check.
+///
+/// The defaults suit air-cooled miners, see [`HealthThresholds::immersion`] for immersion
+/// cooled sites.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct HealthThresholds {
+    /// Hottest chip temperature on a board, in °C
+    pub max_chip_temperature: Option<f64>,
+    /// Board temperature, in °C
+    pub max_board_temperature: Option<f64>,
+    /// Difference between the outlet and intake temperature of a board, in °C
+    pub max_temperature_delta: Option<f64>,
+    /// Lowest share of the expected chips a board has to report working
+    pub min_working_chip_fraction: Option<f64>,
+    /// Furthest a fan may be from the average fan speed, as a fraction of the average
+    pub max_fan_rpm_deviation: Option<f64>,
+    /// Speed below which a fan counts as stopped, in RPM
+    pub min_fan_rpm: Option<f64>,
+    /// Uptime below which a miner is still warming up, in seconds
+    ///
+    /// Right after booting miners report no hashrate and partial board data, so until then
+    /// the board and fan checks are skipped and a hashing or failing miner is `Starting`.
+    pub warmup_seconds: Option<u64>,
+    /// Longest an active pool may go without a share while the miner hashes, in seconds
+    ///
+    /// Catches a wedged stratum connection, where the miner reports a normal hashrate but no
+    /// pool gets its work. Only firmware reporting when the last share was submitted can be
+    /// checked, a miner hashing while every pool is dead is caught either way.
+    pub max_share_age_seconds: Option<u64>,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            max_chip_temperature: Some(90.0),
+            max_board_temperature: Some(80.0),
+            max_temperature_delta: Some(25.0),
+            min_working_chip_fraction: Some(0.9),
+            max_fan_rpm_deviation: Some(0.25),
+            min_fan_rpm: Some(500.0),
+            warmup_seconds: Some(600),
+            max_share_age_seconds: Some(900),
+        }
+    }
+}
+
+impl HealthThresholds {
+    /// Thresholds for immersion cooled miners, which run hotter and usually have no fans.
+    pub fn immersion() -> Self {
+        Self {
+            max_chip_temperature: Some(100.0),
+            max_board_temperature: Some(90.0),
+            max_fan_rpm_deviation: None,
+            ..Self::default()
+        }
+    }
+
+    /// Thresholds with every check disabled.
+    pub fn disabled() -> Self {
+        Self {
+            max_chip_temperature: None,
+            max_board_temperature: None,
+            max_temperature_delta: None,
+            min_working_chip_fraction: None,
+            max_fan_rpm_deviation: None,
+            min_fan_rpm: None,
+            warmup_seconds: None,
+            max_share_age_seconds: None,
+        }
+    }
+
+    /// Whether a miner up for `uptime` is still warming up, `false` if the uptime is unknown.
+    pub fn is_warming_up(&self, uptime: Option<Duration>) -> bool {
+        matches!(
+            (self.warmup_seconds, uptime),
+            (Some(warmup), Some(uptime)) if uptime < Duration::from_secs(warmup)
+        )
+    }
+
+    /// `status` of a miner up for `uptime`, `Starting` in place of `Mining` or `Failure` while
+    /// it warms up.
+    pub fn status(&self, status: MinerStatus, uptime: Option<Duration>) -> MinerStatus {
+        match status {
+            MinerStatus::Mining | MinerStatus::Failure if self.is_warming_up(uptime) => {
+                MinerStatus::Starting
+            }
+            status => status,
+        }
+    }
+
+    /// Positions of the fans spinning faster than `min_fan_rpm`, or than 0 RPM without one.
+    pub fn working_fans(&self, fans: &[FanData]) -> Vec<i16> {
+        let min = self.min_fan_rpm.unwrap_or(0.0);
+        fans.iter()
+            .filter(|f| f.rpm.is_some_and(|rpm| rpm.as_rpm() > min))
+            .map(|f| f.position)
+            .collect()
+    }
+
+    /// Whether the miner in `data` hashes while its stratum connection is wedged, see
+    /// [`HealthThresholds::max_share_age_seconds`].
+    pub fn is_stratum_wedged(&self, data: &MinerData) -> bool {
+        self.stratum_wedged(data).is_some()
+    }
+
+    /// Why the stratum connection of the miner in `data` is wedged, `None` if it isn't.
+    ///
+    /// Pools registered with [`set_solo_pools`] are left out, and pools that never had a share
+    /// don't count as stale, so zero shares on a solo pool is never taken for a wedge.
+    fn stratum_wedged(&self, data: &MinerData) -> Option<String> {
+        // the share times are by the miner's clock
+        let now = (data.timestamp as i64 - data.clock_skew.unwrap_or(0)).max(0) as u64;
+        self.stratum_wedged_at(&data.pools, data.hashrate.as_ref(), data.uptime, now)
+    }
+
+    /// [`HealthThresholds::stratum_wedged`] from just the readings it needs, with `now` in
+    /// seconds since the Unix epoch by the miner's clock.
+    pub(crate) fn stratum_wedged_at(
+        &self,
+        pools: &[PoolData],
+        hashrate: Option<&HashRate>,
+        uptime: Option<Duration>,
+        now: u64,
+    ) -> Option<String> {
+        let max_age = self.max_share_age_seconds?;
+        if self.is_warming_up(uptime) {
+            return None;
+        }
+        wedged_reason(pools, hashrate, now, max_age)
+    }
+
+    /// Warnings for every reading in `data` outside of these thresholds, and errors for dead
+    /// boards, stopped fans and a wedged stratum connection.
+    ///
+    /// Only the temperature checks apply while the miner is warming up.
+    pub fn messages(&self, data: &MinerData) -> Vec<MinerMessage> {
+        let warming_up = self.is_warming_up(data.uptime);
+        let mut messages = Vec::new();
+        let mut warn = |kind: MinerMessageKind, message: String| {
+            messages.push(
+                MinerMessage::new(data.timestamp as u32, 0, message, MessageSeverity::Warning)
+                    .with_kind(kind),
+            )
+        };
+        let mut dead_boards = Vec::new();
+
+        for board in &data.hashboards {
+            let position = board.position;
+            if let (Some(max), Some(temp)) = (self.max_chip_temperature, chip_temperature(board))
+                && temp > max
+            {
+                warn(
+                    MinerMessageKind::OverTemperature,
+                    format!("Board {position} chip temperature {temp:.1} °C is above {max} °C"),
+                );
+            }
+            if let (Some(max), Some(temp)) = (
+                self.max_board_temperature,
+                board.board_temperature.map(|t| t.as_celsius()),
+            ) && temp > max
+            {
+                warn(
+                    MinerMessageKind::OverTemperature,
+                    format!("Board {position} temperature {temp:.1} °C is above {max} °C"),
+                );
+            }
+            if let (Some(max), Some(intake), Some(outlet)) = (
+                self.max_temperature_delta,
+                board.intake_temperature,
+                board.outlet_temperature,
+            ) {
+                let delta = outlet.as_celsius() - intake.as_celsius();
+                if delta > max {
+                    warn(
+                        MinerMessageKind::OverTemperature,
+                        format!(
+                            "Board {position} outlet is {delta:.1} °C above intake, more than {max} °C"
+                        ),
+                    );
+                }
+            }
+            if !warming_up
+                && let (Some(min), Some(working), Some(expected)) = (
+                    self.min_working_chip_fraction,
+                    board.working_chips,
+                    board.expected_chips.filter(|c| *c > 0),
+                )
+                && (working as f64) < expected as f64 * min
+            {
+                match working {
+                    0 => dead_boards.push(position),
+                    _ => warn(
+                        MinerMessageKind::BoardFailure,
+                        format!("Board {position} has {working} of {expected} chips working"),
+                    ),
+                }
+            }
+        }
+
+        if let Some(max) = self.max_fan_rpm_deviation.filter(|_| !warming_up) {
+            let speeds: Vec<(i16, f64)> = data
+                .fans
+                .iter()
+                .filter_map(|f| f.rpm.map(|rpm| (f.position, rpm.as_rpm())))
+                .collect();
+            let average = speeds.iter().map(|(_, rpm)| rpm).sum::<f64>() / speeds.len() as f64;
+            if speeds.len() > 1 && average > 0.0 {
+                for (position, rpm) in speeds {
+                    if (rpm - average).abs() / average > max {
+                        warn(
+                            MinerMessageKind::FanFailure,
+                            format!(
+                                "Fan {position} speed {rpm:.0} RPM is off the average of {average:.0} RPM"
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        for position in dead_boards {
+            messages.push(
+                MinerMessage::new(
+                    data.timestamp as u32,
+                    0,
+                    format!("Board {position} has no working chips"),
+                    MessageSeverity::Error,
+                )
+                .with_kind(MinerMessageKind::BoardFailure),
+            );
+        }
+
+        // an empty list can't be told apart from firmware that doesn't report fans, and a
+        // sleeping miner may stop its fans
+        if self.min_fan_rpm.is_some()
+            && !warming_up
+            && let Some(expected) = data.expected_fans.filter(|f| *f > 0)
+            && !data.fans.is_empty()
+            && data.status != MinerStatus::Paused
+        {
+            let working = self.working_fans(&data.fans);
+            if working.len() < expected as usize {
+                let stopped = (0..expected as i16)
+                    .filter(|position| !working.contains(position))
+                    .map(|position| position.to_string())
+                    .collect::<Vec<_>>();
+                let mut message = format!("{} of {expected} fans running", working.len());
+                if !stopped.is_empty() {
+                    message.push_str(&format!(", fan {} stopped", stopped.join(", ")));
+                }
+                messages.push(
+                    MinerMessage::new(data.timestamp as u32, 0, message, MessageSeverity::Error)
+                        .with_kind(MinerMessageKind::FanFailure),
+                );
+            }
+        }
+
+        if let Some(reason) = self.stratum_wedged(data) {
+            messages.push(
+                MinerMessage::new(
+                    data.timestamp as u32,
+                    0,
+                    format!("Hashing, but {reason}"),
+                    MessageSeverity::Error,
+                )
+                .with_kind(MinerMessageKind::PoolIssue),
+            );
+        }
+
+        messages
+    }
+}
+
+/// Why a miner hashing at `hashrate` isn't getting work to any of `pools`, with `now` by the
+/// miner's clock.
+fn wedged_reason(
+    pools: &[PoolData],
+    hashrate: Option<&HashRate>,
+    now: u64,
+    max_age: u64,
+) -> Option<String> {
+    if !hashrate.is_some_and(|h| h.value > 0.0) {
+        return None;
+    }
+    let pools: Vec<&PoolData> = pools.iter().filter(|p| !is_solo_pool(p)).collect();
+    if pools.is_empty() {
+        return None;
+    }
+    if pools.iter().all(|p| p.alive == Some(false)) {
+        return Some("every pool is dead".to_string());
+    }
+    // an active pool without a last share time can't be judged, so it keeps the miner healthy
+    let ages: Option<Vec<u64>> = pools
+        .iter()
+        .filter(|p| p.active == Some(true))
+        .map(|p| p.last_share_time.map(|time| now.saturating_sub(time)))
+        .collect();
+    let newest = ages?.into_iter().min()?;
+    (newest > max_age).then(|| format!("no share submitted to an active pool for {newest} s"))
+}
+
+/// The hottest chip reading of a board, from its chips and chip sensors.
+fn chip_temperature(board: &BoardData) -> Option<f64> {
+    board
+        .chips
+        .iter()
+        .filter_map(|c| c.temperature)
+        .chain(board.sensor_temperatures.iter().copied())
+        .chain(board.intake_temperature)
+        .chain(board.outlet_temperature)
+        .map(|t| t.as_celsius())
+        .reduce(f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_thresholds_config() {
+        let thresholds: HealthThresholds =
+            serde_json::from_str(r#"{"max_chip_temperature":95.0,"max_fan_rpm_deviation":null}"#)
+                .unwrap();
+        assert_eq!(thresholds.max_chip_temperature, Some(95.0));
+        assert_eq!(thresholds.max_fan_rpm_deviation, None);
+        assert_eq!(
+            thresholds.max_board_temperature,
+            HealthThresholds::default().max_board_temperature
+        );
+
+        let immersion = HealthThresholds::immersion();
+        let json = serde_json::to_string(&immersion).unwrap();
+        assert_eq!(
+            serde_json::from_str::<HealthThresholds>(&json).unwrap(),
+            immersion
+        );
+    }
+
+    #[test]
+    fn test_wedged_reason() {
+        use crate::data::hashrate::HashRateUnit;
+        use crate::data::pool::PoolURL;
+
+        let hashrate = HashRate {
+            value: 110.0,
+            unit: HashRateUnit::TeraHash,
+            algo: String::from("SHA256"),
+        };
+        let pool = |url: &str, alive: bool, last_share_time: Option<u64>| PoolData {
+            position: Some(0),
+            url: PoolURL::parse(url),
+            accepted_shares: Some(0),
+            rejected_shares: Some(0),
+            active: Some(alive),
+            alive: Some(alive),
+            user: Some("worker.1".to_string()),
+            password: None,
+            last_share_difficulty: None,
+            last_share_time,
+            group: None,
+            quota: None,
+        };
+        let now = 1_717_000_000;
+
+        let dead = [
+            pool("stratum+tcp://pool.example.com:3333", false, None),
+            pool("stratum+tcp://backup.example.com:3333", false, None),
+        ];
+        assert_eq!(
+            wedged_reason(&dead, Some(&hashrate), now, 900).as_deref(),
+            Some("every pool is dead")
+        );
+        // a miner that isn't hashing has a different problem
+        assert_eq!(wedged_reason(&dead, None, now, 900), None);
+
+        let fresh = [pool(
+            "stratum+tcp://pool.example.com:3333",
+            true,
+            Some(now - 30),
+        )];
+        assert_eq!(wedged_reason(&fresh, Some(&hashrate), now, 900), None);
+
+        let stale = [pool(
+            "stratum+tcp://solo.watchdog.example:3333",
+            true,
+            Some(now - 7200),
+        )];
+        assert!(wedged_reason(&stale, Some(&hashrate), now, 900).is_some());
+        set_solo_pools(vec!["solo.watchdog.example".to_string()]);
+        let excluded = wedged_reason(&stale, Some(&hashrate), now, 900);
+        set_solo_pools(vec![]);
+        assert_eq!(excluded, None);
+    }
+}
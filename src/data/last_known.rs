@@ -0,0 +1,266 @@
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime};
+
+use super::miner::MinerData;
+
+/// Default age after which the last known data of a miner is no longer returned.
+pub const DEFAULT_MAX_STALENESS: Duration = Duration::from_secs(15 * 60);
+
+/// The outcome of polling a miner, as returned by [`LastKnownData::update`].
+#[derive(Debug)]
+pub enum PollResult {
+    /// The poll succeeded
+    Fresh(MinerData),
+    /// The poll failed, this is the last successful poll and how long ago it was
+    Stale(MinerData, Duration),
+    /// The poll failed and there is no recent enough data to fall back to
+    Failed(Error),
+}
+
+impl PollResult {
+    /// The freshest data available for the miner, if any.
+    pub fn data(&self) -> Option<&MinerData> {
+        match self {
+            PollResult::Fresh(data) | PollResult::Stale(data, _) => Some(data),
+            PollResult::Failed(_) => None,
+        }
+    }
+
+    pub fn is_stale(&self) -> bool {
+        matches!(self, PollResult::Stale(..))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LastSuccess {
+    data: MinerData,
+    last_success: SystemTime,
+}
+
+/// Keeps the last successful [`MinerData`] of each miner in a long running poller.
+///
+/// When a miner stops responding its last known data is returned as [`PollResult::Stale`] with
+/// its age, so it doesn't disappear from results the moment a poll fails. Once the data is older
+/// than `max_staleness` the failure is returned as is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LastKnownData {
+    max_staleness: Duration,
+    miners: HashMap<IpAddr, LastSuccess>,
+}
+
+impl Default for LastKnownData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LastKnownData {
+    pub fn new() -> Self {
+        Self {
+            max_staleness: DEFAULT_MAX_STALENESS,
+            miners: HashMap::new(),
+        }
+    }
+
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.max_staleness = max_staleness;
+        self
+    }
+
+    pub fn max_staleness(&self) -> Duration {
+        self.max_staleness
+    }
+
+    /// Record the result of polling a miner.
+    pub fn update(&mut self, ip: IpAddr, result: anyhow::Result<MinerData>) -> PollResult {
+        self.update_at(ip, result, SystemTime::now())
+    }
+
+    fn update_at(
+        &mut self,
+        ip: IpAddr,
+        result: anyhow::Result<MinerData>,
+        now: SystemTime,
+    ) -> PollResult {
+        match result {
+            Ok(data) => {
+                self.miners.insert(
+                    ip,
+                    LastSuccess {
+                        data: data.clone(),
+                        last_success: now,
+                    },
+                );
+                PollResult::Fresh(data)
+            }
+            Err(error) => {
+                let Some(last) = self.miners.get(&ip) else {
+                    return PollResult::Failed(error);
+                };
+                // a clock stepping backwards should not make old data look fresh
+                let age = now
+                    .duration_since(last.last_success)
+                    .unwrap_or(Duration::ZERO);
+                if age > self.max_staleness {
+                    return PollResult::Failed(error);
+                }
+                PollResult::Stale(last.data.clone(), age)
+            }
+        }
+    }
+
+    /// When the miner was last polled successfully.
+    pub fn last_success(&self, ip: &IpAddr) -> Option<SystemTime> {
+        self.miners.get(ip).map(|last| last.last_success)
+    }
+
+    /// The last successfully polled data of a miner, regardless of its age.
+    pub fn get(&self, ip: &IpAddr) -> Option<&MinerData> {
+        self.miners.get(ip).map(|last| &last.data)
+    }
+
+    /// Forget a miner, such as after it was removed from the fleet.
+    pub fn remove(&mut self, ip: &IpAddr) {
+        self.miners.remove(ip);
+    }
+
+    /// The miners that have been polled successfully at least once.
+    pub fn ips(&self) -> impl Iterator<Item = &IpAddr> {
+        self.miners.keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerMake, MinerModel};
+    use crate::data::miner::MinerStatus;
+    use anyhow::anyhow;
+    use std::net::Ipv4Addr;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+    fn data(timestamp: u64) -> MinerData {
+        MinerData {
+            schema_version: env!("CARGO_PKG_VERSION").to_owned(),
+            timestamp,
+            collection_duration: Duration::ZERO,
+            clock_skew: None,
+            ip: IP,
+            reported_ip: None,
+            mac: None,
+            labels: None,
+            device_info: DeviceInfo::new(
+                MinerMake::AntMiner,
+                MinerModel::AntMiner(AntMinerModel::S19Pro),
+                MinerFirmware::Stock,
+                HashAlgorithm::SHA256,
+            ),
+            serial_number: None,
+            hostname: None,
+            api_version: None,
+            firmware_version: None,
+            firmware_build_time: None,
+            base_firmware: None,
+            control_board_version: None,
+            control_board_type: None,
+            expected_hashboards: Some(3),
+            hashboards: vec![],
+            hashrate: None,
+            expected_hashrate: None,
+            average_hashrate: None,
+            expected_chips: None,
+            total_chips: None,
+            expected_fans: None,
+            working_fans: None,
+            fans: vec![],
+            psu_fans: vec![],
+            fan_control: None,
+            average_temperature: None,
+            fluid_temperature: None,
+            fluid_flow: None,
+            fluid_pressure: None,
+            wattage: None,
+            wattage_estimated: false,
+            wattage_location: None,
+            wattage_limit: None,
+            efficiency: None,
+            psu: None,
+            psu_rails: vec![],
+            frequency: None,
+            voltage: None,
+            work_mode: None,
+            autotune_enabled: None,
+            autotune_profile_bounds: None,
+            light_flashing: None,
+            messages: vec![],
+            uptime: None,
+            status: MinerStatus::Mining,
+            is_mining: true,
+            pools: vec![],
+        }
+    }
+
+    #[test]
+    fn test_stale_until_cutoff() {
+        let mut store = LastKnownData::new().with_max_staleness(Duration::from_secs(300));
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert!(matches!(
+            store.update_at(IP, Ok(data(1)), at(0)),
+            PollResult::Fresh(_)
+        ));
+        assert!(matches!(
+            store.update_at(IP, Ok(data(2)), at(60)),
+            PollResult::Fresh(_)
+        ));
+        assert_eq!(store.last_success(&IP), Some(at(60)));
+
+        let mut last_age = Duration::ZERO;
+        for secs in [120, 240, 360] {
+            match store.update_at(IP, Err(anyhow!("timed out")), at(secs)) {
+                PollResult::Stale(stale, age) => {
+                    // the newest successful poll is kept
+                    assert_eq!(stale.timestamp, 2);
+                    assert_eq!(age, Duration::from_secs(secs - 60));
+                    assert!(age > last_age);
+                    last_age = age;
+                }
+                other => panic!("expected stale data at {secs}s, got {other:?}"),
+            }
+        }
+
+        let result = store.update_at(IP, Err(anyhow!("timed out")), at(361));
+        assert!(matches!(result, PollResult::Failed(_)));
+        assert!(result.data().is_none());
+
+        // recovering makes the data fresh again
+        assert!(matches!(
+            store.update_at(IP, Ok(data(3)), at(420)),
+            PollResult::Fresh(_)
+        ));
+    }
+
+    #[test]
+    fn test_unknown_miner_fails() {
+        let mut store = LastKnownData::new();
+        let result = store.update(IP, Err(anyhow!("connection refused")));
+        assert!(matches!(result, PollResult::Failed(_)));
+    }
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let mut store = LastKnownData::new();
+        store.update(IP, Ok(data(1)));
+
+        let json = serde_json::to_string(&store).unwrap();
+        let restored: LastKnownData = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, store);
+        assert_eq!(restored.get(&IP).map(|d| d.timestamp), Some(1));
+    }
+}
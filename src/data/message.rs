@@ -6,14 +6,49 @@ use strum::Display;
 
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum MessageSeverity {
     Error,
     Warning,
     Info,
 }
 
+/// What a message is about, the same for every vendor, so alerts can be routed without rules
+/// for the codes and texts of each firmware.
+#[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MinerMessageKind {
+    FanFailure,
+    OverTemperature,
+    /// Hashboards, chips, their sensors and EEPROMs, and the control board
+    BoardFailure,
+    PsuFailure,
+    NetworkIssue,
+    PoolIssue,
+    TuningIssue,
+    /// Anything not in the mapping of the backend
+    #[default]
+    Other,
+}
+
+impl MinerMessageKind {
+    /// The kind of the first keyword in `table` found in `text`, ignoring case.
+    pub(crate) fn from_text(text: &str, table: &[(&str, MinerMessageKind)]) -> Self {
+        let text = text.to_lowercase();
+        table
+            .iter()
+            .find(|(keyword, _)| text.contains(keyword))
+            .map(|(_, kind)| *kind)
+            .unwrap_or_default()
+    }
+}
+
 #[cfg_attr(feature = "python", pyclass(get_all, module = "asic_rs"))]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MinerMessage {
     /// The time this message was generated or occurred
     pub timestamp: u32,
@@ -24,6 +59,9 @@ pub struct MinerMessage {
     pub message: String,
     /// The severity of this message
     pub severity: MessageSeverity,
+    /// What the message is about, mapped from the code or text of the device
+    #[serde(default)]
+    pub kind: MinerMessageKind,
 }
 
 impl MinerMessage {
@@ -33,6 +71,54 @@ impl MinerMessage {
             code,
             message,
             severity,
+            kind: MinerMessageKind::Other,
         }
     }
+
+    pub fn with_kind(mut self, kind: MinerMessageKind) -> Self {
+        self.kind = kind;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_from_text() {
+        let table = [
+            ("fan", MinerMessageKind::FanFailure),
+            ("temp", MinerMessageKind::OverTemperature),
+        ];
+        assert_eq!(
+            MinerMessageKind::from_text("Fan 2 stopped", &table),
+            MinerMessageKind::FanFailure
+        );
+        assert_eq!(
+            MinerMessageKind::from_text("Pool 0 is dead", &table),
+            MinerMessageKind::Other
+        );
+
+        let message = MinerMessage::new(
+            0,
+            110,
+            "Intake fan speed error".into(),
+            MessageSeverity::Warning,
+        )
+        .with_kind(MinerMessageKind::FanFailure);
+        let serialized = serde_json::to_value(&message).unwrap();
+        assert_eq!(serialized["kind"], "fan_failure");
+        assert_eq!(serialized["code"], 110);
+
+        // messages stored before the kind was added
+        let stored = serde_json::json!({
+            "timestamp": 0,
+            "code": 0,
+            "message": "",
+            "severity": "info",
+        });
+        let message: MinerMessage = serde_json::from_value(stored).unwrap();
+        assert_eq!(message.kind, MinerMessageKind::Other);
+    }
 }
@@ -1,32 +1,130 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
 use crate::data::deserialize::deserialize_macaddr;
+use crate::data::serialize::serialize_frequency;
 use crate::data::serialize::serialize_macaddr;
 use crate::data::serialize::serialize_power;
 use crate::data::serialize::serialize_temperature;
-use std::{net::IpAddr, time::Duration};
+use crate::data::serialize::serialize_voltage;
+use std::fmt::Write;
+use std::{collections::HashMap, net::IpAddr, time::Duration};
 
 use super::{
-    board::BoardData, device::DeviceInfo, fan::FanData, hashrate::HashRate, message::MinerMessage,
+    board::BoardData,
+    device::DeviceInfo,
+    fan::{FanControl, FanData},
+    format::{Efficiency, Measured, OrDash, Uptime},
+    hashrate::HashRate,
+    message::MinerMessage,
     pool::PoolData,
+    psu::{PsuData, PsuRail},
 };
-use crate::data::device::MinerControlBoard;
+use crate::data::device::{ControlBoardType, MinerControlBoard};
 use macaddr::MacAddr;
-use measurements::{Power, Temperature};
+use measurements::{Frequency, Power, Temperature, Voltage};
 use serde::{Deserialize, Serialize};
+use strum::Display;
+
+/// The operating state of a miner, as reported by its firmware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MinerStatus {
+    /// Hashing normally
+    Mining,
+    /// Intentionally stopped, such as sleep mode or a scheduled soft off
+    Paused,
+    /// Booting, initializing or tuning before hashing
+    Starting,
+    /// Supposed to be hashing, but the firmware reports an error or no hashrate
+    Failure,
+    /// The firmware did not report enough to tell
+    #[default]
+    Unknown,
+}
+
+impl MinerStatus {
+    /// Whether the hashing process is running in this state
+    pub fn is_mining(&self) -> bool {
+        matches!(self, MinerStatus::Mining)
+    }
+}
+
+/// The performance mode a miner is configured to run in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum WorkMode {
+    /// Reduced frequency and power for better efficiency
+    LowPower,
+    /// The stock frequency and power
+    Normal,
+    /// Raised frequency and power for more hashrate
+    HighPerformance,
+    /// Hashboards powered down while the control board stays up
+    Sleep,
+}
+
+/// The profiles automatic tuning scales the miner between, such as LuxOS ATM lowering the
+/// profile as temperatures rise.
+#[cfg_attr(feature = "python", pyclass(get_all, module = "asic_rs"))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AutotuneProfileBounds {
+    /// The profile the miner is never tuned below
+    pub min: String,
+    /// The profile the miner runs at when nothing holds it back
+    pub max: String,
+}
+
+/// Where in the power path a miner's wattage is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum WattageLocation {
+    /// Measured at the AC input of the PSU, the power drawn from the wall
+    #[serde(rename = "wall")]
+    Wall,
+    /// Measured on the DC side of the PSU, short of the wall draw by the PSU efficiency
+    #[serde(rename = "dc_bus")]
+    DCBus,
+    /// Estimated from the model's nominal power rather than measured
+    #[serde(rename = "estimated")]
+    Estimated,
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MinerData {
     /// The schema version of this MinerData object, for use in external APIs
+    ///
+    /// Follows the crate version, the serialized layout only changes along with it.
     pub schema_version: String,
-    /// The time this data was gathered and constructed
+    /// The time collection of this data started, in seconds since the Unix epoch
     pub timestamp: u64,
+    /// How long collecting this data took, from the first command sent to the last response
+    #[serde(default)]
+    pub collection_duration: Duration,
+    /// How far the host's clock is ahead of the miner's in seconds, from the time cgminer APIs
+    /// report with each response
+    #[serde(default)]
+    pub clock_skew: Option<i64>,
     /// The IP address of the miner this data is for
     pub ip: IpAddr,
+    /// The IP address the miner reports in its own network settings, which differs from `ip`
+    /// when the miner is reached through a NAT or port-forward
+    #[serde(default)]
+    pub reported_ip: Option<IpAddr>,
     /// The MAC address of the miner this data is for
     #[serde(
         serialize_with = "serialize_macaddr",
         deserialize_with = "deserialize_macaddr"
     )]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
     pub mac: Option<MacAddr>,
+    /// User supplied labels for this miner, such as its rack, row or slot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
     /// Hardware information about this miner
     pub device_info: DeviceInfo,
     /// The serial number of the miner, also known as the control board serial
@@ -37,8 +135,16 @@ pub struct MinerData {
     pub api_version: Option<String>,
     /// The firmware version of the miner
     pub firmware_version: Option<String>,
+    /// The build time of the firmware, as reported by the miner
+    #[serde(default)]
+    pub firmware_build_time: Option<String>,
+    /// The vendor firmware a converted miner runs on, such as the Bitmain build under MaraFW
+    #[serde(default)]
+    pub base_firmware: Option<String>,
     /// The type of control board on the miner
     pub control_board_version: Option<MinerControlBoard>,
+    /// The platform of the control board, such as Xilinx or AMLogic
+    pub control_board_type: Option<ControlBoardType>,
     /// The expected number of boards in the miner.
     pub expected_hashboards: Option<u8>,
     /// Per-hashboard data for this miner
@@ -47,38 +153,275 @@ pub struct MinerData {
     pub hashrate: Option<HashRate>,
     /// The expected hashrate of the miner
     pub expected_hashrate: Option<HashRate>,
+    /// The hashrate averaged by the miner itself over a longer window, such as `MHS 5m`
+    #[serde(default)]
+    pub average_hashrate: Option<HashRate>,
     /// The total expected number of chips across all boards on this miner
     pub expected_chips: Option<u16>,
     /// The total number of working chips across all boards on this miner
     pub total_chips: Option<u16>,
     /// The expected number of fans on the miner
     pub expected_fans: Option<u8>,
+    /// The number of fans spinning above the minimum speed of the health thresholds, `None`
+    /// when the miner reports no fans
+    #[serde(default)]
+    pub working_fans: Option<u8>,
     /// The current fan information for the miner
     pub fans: Vec<FanData>,
     /// The current PDU fan information for the miner
     pub psu_fans: Vec<FanData>,
+    /// The fan mode and target speed the miner is set to
+    #[serde(default)]
+    pub fan_control: Option<FanControl>,
     /// The average temperature across all chips in the miner
     #[serde(serialize_with = "serialize_temperature")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "°C")))]
     pub average_temperature: Option<Temperature>,
     /// The environment temperature of the miner, such as air temperature or immersion fluid temperature
     #[serde(serialize_with = "serialize_temperature")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "°C")))]
     pub fluid_temperature: Option<Temperature>,
+    /// The coolant flow rate of water cooled miners in litres per minute
+    #[serde(default)]
+    pub fluid_flow: Option<f64>,
+    /// The coolant pressure of water cooled miners in bar
+    #[serde(default)]
+    pub fluid_pressure: Option<f64>,
     /// The current power consumption of the miner
     #[serde(serialize_with = "serialize_power")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "W")))]
     pub wattage: Option<Power>,
+    /// Whether `wattage` is estimated from the model's nominal power rather than measured
+    #[serde(default)]
+    pub wattage_estimated: bool,
+    /// Where `wattage` is measured, so DC side readings aren't taken for wall power
+    #[serde(default)]
+    pub wattage_location: Option<WattageLocation>,
     /// The current power limit or power target of the miner
     #[serde(serialize_with = "serialize_power")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "W")))]
     pub wattage_limit: Option<Power>,
     /// The current efficiency in W/TH/s (J/TH) of the miner
     pub efficiency: Option<f64>,
-    /// The state of the fault/alert light on the miner
+    /// The output voltage, current and power of the PSU
+    #[serde(default)]
+    pub psu: Option<PsuData>,
+    /// The input voltage, current and power of the PSU, to watch the circuit feeding it
+    #[serde(default)]
+    pub psu_rails: Vec<PsuRail>,
+    /// The configured chip frequency, for miners with one frequency across all boards
+    #[serde(default, serialize_with = "serialize_frequency")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "MHz")))]
+    pub frequency: Option<Frequency>,
+    /// The configured hashboard supply voltage
+    #[serde(default, serialize_with = "serialize_voltage")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "V")))]
+    pub voltage: Option<Voltage>,
+    /// The performance mode the miner is configured to run in
+    #[serde(default)]
+    pub work_mode: Option<WorkMode>,
+    /// Whether the firmware scales the miner's profile on its own, so hashrate dips may be
+    /// deliberate
+    #[serde(default)]
+    pub autotune_enabled: Option<bool>,
+    /// The profiles automatic tuning scales between
+    #[serde(default)]
+    pub autotune_profile_bounds: Option<AutotuneProfileBounds>,
+    /// Whether the locate light is active, `None` if the firmware doesn't report it
     pub light_flashing: Option<bool>,
     /// Any message on the miner, including errors
     pub messages: Vec<MinerMessage>,
     /// The total uptime of the miner's system
     pub uptime: Option<Duration>,
-    /// Whether the hashing process is currently running
+    /// The current operating state of the miner
+    #[serde(default)]
+    pub status: MinerStatus,
+    /// Whether the hashing process is currently running, derived from the status the firmware
+    /// reports, before warm-up turns it into `Starting`
     pub is_mining: bool,
     /// The current pools configured on the miner
     pub pools: Vec<PoolData>,
 }
+
+impl MinerData {
+    /// The readings of the circuit feeding the PSU, if the miner reports them.
+    pub fn psu_input(&self) -> Option<&PsuRail> {
+        self.psu_rails
+            .iter()
+            .find(|rail| rail.name == PsuRail::INPUT)
+    }
+
+    /// The one line summary of [`Display`][`std::fmt::Display`], followed by a line for each
+    /// board, fan and pool.
+    pub fn summary_table(&self) -> String {
+        let mut table = self.to_string();
+        for board in &self.hashboards {
+            let _ = write!(table, "\n  {board}");
+        }
+        for fan in &self.fans {
+            let rpm = fan.rpm.map(|rpm| Measured(rpm.as_rpm(), " RPM"));
+            let _ = write!(table, "\n  fan {} | {}", fan.position, OrDash(rpm));
+        }
+        for pool in &self.pools {
+            let _ = write!(table, "\n  {pool}");
+        }
+        table
+    }
+
+    /// JSON schema of serialized `MinerData`, for consumers in other languages.
+    ///
+    /// Measurements are serialized as plain numbers, the schema records their unit in a `unit`
+    /// keyword, e.g. `W` for `wattage`.
+    #[cfg(feature = "schema")]
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(MinerData))
+            .expect("MinerData schema is valid JSON")
+    }
+}
+
+/// A one line summary for logs and command line output, such as
+/// `192.168.1.50 | S19jPro (Stock) | 98.2 TH/s / 104.0 TH/s | 3021 W | 30.8 J/TH | 65°C |
+/// 2 pools | up 3d 4h`.
+///
+/// Missing readings are shown as a dash, the hashrates in the largest unit that fits them and
+/// the efficiency per unit of the algorithm's default, J/TH for SHA256.
+impl std::fmt::Display for MinerData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let efficiency = match (self.wattage, &self.hashrate) {
+            (Some(wattage), Some(hashrate)) => {
+                Efficiency::new(wattage, hashrate, &self.device_info.algo)
+            }
+            _ => None,
+        };
+        write!(
+            f,
+            "{} | {} ({}) | {:.1} / {:.1} | {} | {} | {} | {} pool{} | up {}",
+            self.ip,
+            self.device_info.model,
+            self.device_info.firmware,
+            OrDash(self.hashrate.as_ref().map(HashRate::scaled)),
+            OrDash(self.expected_hashrate.as_ref().map(HashRate::scaled)),
+            OrDash(self.wattage.map(|w| Measured(w.as_watts(), " W"))),
+            OrDash(efficiency),
+            OrDash(
+                self.average_temperature
+                    .map(|t| Measured(t.as_celsius(), "°C"))
+            ),
+            self.pools.len(),
+            if self.pools.len() == 1 { "" } else { "s" },
+            OrDash(self.uptime.map(Uptime)),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "schema"))]
+mod tests {
+    use super::*;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::device::{HashAlgorithm, MinerFirmware, MinerMake, MinerModel};
+    use std::net::Ipv4Addr;
+    use std::path::Path;
+
+    const SCHEMA_SNAPSHOT: &str = "src/test/json/schema/miner_data.json";
+
+    fn miner_data() -> MinerData {
+        MinerData {
+            schema_version: env!("CARGO_PKG_VERSION").to_owned(),
+            timestamp: 1_700_000_000,
+            collection_duration: Duration::ZERO,
+            clock_skew: None,
+            ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            reported_ip: None,
+            mac: None,
+            labels: Some(HashMap::from([("rack".to_string(), "A1".to_string())])),
+            device_info: DeviceInfo::new(
+                MinerMake::AntMiner,
+                MinerModel::AntMiner(AntMinerModel::S19Pro),
+                MinerFirmware::Stock,
+                HashAlgorithm::SHA256,
+            ),
+            serial_number: None,
+            hostname: None,
+            api_version: None,
+            firmware_version: None,
+            firmware_build_time: None,
+            base_firmware: None,
+            control_board_version: None,
+            control_board_type: None,
+            expected_hashboards: None,
+            hashboards: vec![],
+            hashrate: None,
+            expected_hashrate: None,
+            average_hashrate: None,
+            expected_chips: None,
+            total_chips: None,
+            expected_fans: None,
+            working_fans: None,
+            fans: vec![],
+            psu_fans: vec![],
+            fan_control: None,
+            average_temperature: None,
+            fluid_temperature: None,
+            fluid_flow: None,
+            fluid_pressure: None,
+            wattage: Some(Power::from_watts(3250.0)),
+            wattage_estimated: false,
+            wattage_location: None,
+            wattage_limit: None,
+            efficiency: None,
+            psu: None,
+            psu_rails: vec![],
+            frequency: None,
+            voltage: None,
+            work_mode: Some(WorkMode::LowPower),
+            autotune_enabled: None,
+            autotune_profile_bounds: None,
+            light_flashing: None,
+            messages: vec![],
+            uptime: None,
+            status: MinerStatus::Mining,
+            is_mining: true,
+            pools: vec![],
+        }
+    }
+
+    #[test]
+    fn test_schema_covers_serialized_fields() {
+        let schema = MinerData::json_schema();
+        let properties = schema["properties"].as_object().unwrap();
+
+        let serialized = serde_json::to_value(miner_data()).unwrap();
+        for field in serialized.as_object().unwrap().keys() {
+            assert!(
+                properties.contains_key(field),
+                "{field} missing from schema"
+            );
+        }
+        assert_eq!(serialized["status"], "mining");
+        assert_eq!(serialized["work_mode"], "low_power");
+        assert_eq!(properties["wattage"]["unit"], "W");
+        assert_eq!(properties["average_temperature"]["unit"], "°C");
+    }
+
+    /// Fails on any change to the serialized layout. Run with `UPDATE_SCHEMA_SNAPSHOT=1` to
+    /// accept an intended change, which should come with a crate version bump.
+    #[test]
+    fn test_schema_snapshot() {
+        let schema = MinerData::json_schema();
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(SCHEMA_SNAPSHOT);
+
+        if std::env::var_os("UPDATE_SCHEMA_SNAPSHOT").is_some() || !path.exists() {
+            let json = serde_json::to_string_pretty(&schema).unwrap();
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, json + "\n").unwrap();
+            return;
+        }
+
+        let snapshot: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            schema, snapshot,
+            "MinerData schema changed, rerun with UPDATE_SCHEMA_SNAPSHOT=1 if this is intended"
+        );
+    }
+}
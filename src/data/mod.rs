@@ -2,12 +2,22 @@
 //!
 //! The most important data type is [`MinerData`][`miner::MinerData`], it contains all the data asic-rs gathers with `get_data`.
 
+pub mod aggregate;
 pub mod board;
+pub mod conflicts;
 pub(crate) mod deserialize;
 pub mod device;
+pub mod diff;
+pub mod export;
 pub mod fan;
+pub(crate) mod format;
 pub mod hashrate;
+pub mod health;
+pub mod last_known;
 pub mod message;
 pub mod miner;
 pub mod pool;
+pub mod psu;
+pub mod rolling;
+pub mod sanitize;
 pub(crate) mod serialize;
@@ -1,26 +1,53 @@
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::data::format::OrDash;
+
+static SERIALIZE_SECRETS: AtomicBool = AtomicBool::new(false);
+
+/// Include pool passwords when serializing [`PoolData`] and [`PoolConfig`].
+///
+/// Passwords are left out by default, so serialized data can be logged or exported without
+/// leaking credentials.
+pub fn set_serialize_secrets(enabled: bool) {
+    SERIALIZE_SECRETS.store(enabled, Ordering::Relaxed);
+}
+
+fn skip_secret(secret: &Option<String>) -> bool {
+    secret.is_none() || !SERIALIZE_SECRETS.load(Ordering::Relaxed)
+}
+
 #[cfg_attr(feature = "python", pyclass(str, module = "asic_rs"))]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum PoolScheme {
     StratumV1,
+    #[serde(rename = "stratum_v1_ssl")]
     StratumV1SSL,
     StratumV2,
 }
 
+impl PoolScheme {
+    /// The scheme named `scheme`, `None` if it isn't a stratum scheme.
+    pub fn parse(scheme: &str) -> Option<Self> {
+        match scheme {
+            "stratum+tcp" => Some(PoolScheme::StratumV1),
+            "stratum+ssl" => Some(PoolScheme::StratumV1SSL),
+            "stratum2+tcp" => Some(PoolScheme::StratumV2),
+            _ => None,
+        }
+    }
+}
+
 impl From<String> for PoolScheme {
     fn from(scheme: String) -> Self {
-        match scheme.as_str() {
-            "stratum+tcp" => PoolScheme::StratumV1,
-            "stratum+ssl" => PoolScheme::StratumV1SSL,
-            "stratum2+tcp" => PoolScheme::StratumV2,
-            _ => panic!("Invalid pool scheme"),
-        }
+        PoolScheme::parse(&scheme).expect("Invalid pool scheme")
     }
 }
 
@@ -36,6 +63,7 @@ impl Display for PoolScheme {
 
 #[cfg_attr(feature = "python", pyclass(get_all, module = "asic_rs"))]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PoolURL {
     /// The scheme being used to connect to this pool
     pub scheme: PoolScheme,
@@ -48,28 +76,38 @@ pub struct PoolURL {
     pub pubkey: Option<String>,
 }
 
-impl From<String> for PoolURL {
-    fn from(url: String) -> Self {
-        let stratum_url = if url.starts_with("stratum") {
-            url
+impl PoolURL {
+    /// Parse a pool URL as reported by a miner, `None` if it can't be read.
+    ///
+    /// URLs without a scheme are taken as `stratum+tcp`. Use this rather than `From<String>`
+    /// for anything read from a miner, which panics on a malformed URL.
+    pub fn parse(url: &str) -> Option<Self> {
+        let parsed = if url.starts_with("stratum") {
+            Url::parse(url)
         } else {
-            format!("stratum+tcp://{url}")
-        };
-        let parsed = Url::parse(&stratum_url).expect("Invalid pool URL");
-        let scheme = PoolScheme::from(parsed.scheme().to_string());
+            Url::parse(&format!("stratum+tcp://{url}"))
+        }
+        .ok()?;
+        let scheme = PoolScheme::parse(parsed.scheme())?;
         let host = parsed.host_str().unwrap_or("").to_string();
         let port = parsed.port().unwrap_or(80);
         let path = parsed.path();
         let pubkey = match path {
             "" => None,
-            _ => Some(path[1..].to_string()),
+            _ => Some(path.strip_prefix('/').unwrap_or(path).to_string()),
         };
-        PoolURL {
+        Some(PoolURL {
             scheme,
             host,
             port,
             pubkey,
-        }
+        })
+    }
+}
+
+impl From<String> for PoolURL {
+    fn from(url: String) -> Self {
+        PoolURL::parse(&url).expect("Invalid pool URL")
     }
 }
 
@@ -83,13 +121,151 @@ impl Display for PoolURL {
 }
 
 #[cfg_attr(feature = "python", pyclass(get_all, module = "asic_rs"))]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PoolData {
     pub position: Option<u16>,
     pub url: Option<PoolURL>,
     pub accepted_shares: Option<u64>,
     pub rejected_shares: Option<u64>,
+    /// Whether the miner is getting work from this pool.
+    ///
+    /// Usually one pool at a time, but firmware that splits hashrate between pool groups by
+    /// quota mines on a pool of every group, there a pool is active once it has received work
+    /// in the current session.
     pub active: Option<bool>,
     pub alive: Option<bool>,
     pub user: Option<String>,
+    /// The configured password, only reported by some firmware
+    #[serde(default, skip_serializing_if = "skip_secret")]
+    pub password: Option<String>,
+    /// The difficulty of the last share submitted to this pool, which follows vardiff changes
+    #[serde(default)]
+    pub last_share_difficulty: Option<f64>,
+    /// When the last share was submitted to this pool, in seconds since the Unix epoch by the
+    /// miner's clock, only reported by firmware that gives it as a timestamp
+    #[serde(default)]
+    pub last_share_time: Option<u64>,
+    /// The name of the pool group this pool belongs to, on firmware that groups pools
+    #[serde(default)]
+    pub group: Option<String>,
+    /// The hashrate quota of this pool's group, relative to the quotas of the other groups
+    #[serde(default)]
+    pub quota: Option<u32>,
+}
+
+/// One line such as `pool 0 | stratum+tcp://pool.example.com:3333 | worker.1 | alive, active |
+/// 15234 accepted, 37 rejected`, the password is never shown.
+impl Display for PoolData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let alive = match self.alive {
+            Some(true) => "alive",
+            Some(false) => "dead",
+            None => "-",
+        };
+        write!(
+            f,
+            "pool {} | {} | {} | {alive}",
+            OrDash(self.position),
+            OrDash(self.url.as_ref()),
+            OrDash(self.user.as_deref()),
+        )?;
+        if self.active == Some(true) {
+            f.write_str(", active")?;
+        }
+        write!(
+            f,
+            " | {} accepted, {} rejected",
+            OrDash(self.accepted_shares),
+            OrDash(self.rejected_shares)
+        )
+    }
+}
+
+/// A pool to write to a miner, see [`SetPools`][`crate::miners::backends::traits::SetPools`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolConfig {
+    pub url: PoolURL,
+    pub user: String,
+    #[serde(default, skip_serializing_if = "skip_secret")]
+    pub password: Option<String>,
+}
+
+impl PoolConfig {
+    pub fn new(url: PoolURL, user: impl Into<String>, password: Option<String>) -> Self {
+        Self {
+            url,
+            user: user.into(),
+            password,
+        }
+    }
+
+    /// The password to send to the miner, firmware expects a placeholder when there is none.
+    pub(crate) fn password_or_default(&self) -> &str {
+        self.password.as_deref().unwrap_or("x")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_password_serialization() {
+        let config = PoolConfig::new(
+            PoolURL::from("stratum+tcp://pool.example.com:3333".to_string()),
+            "worker.1",
+            Some("hunter2".to_string()),
+        );
+
+        let serialized = serde_json::to_value(&config).unwrap();
+        assert_eq!(serialized.get("password"), None);
+        assert_eq!(serialized["user"], json!("worker.1"));
+        assert!(!serde_json::to_string(&config).unwrap().contains("hunter2"));
+
+        set_serialize_secrets(true);
+        let serialized = serde_json::to_value(&config).unwrap();
+        set_serialize_secrets(false);
+        assert_eq!(serialized["password"], json!("hunter2"));
+
+        let restored: PoolConfig = serde_json::from_value(serialized).unwrap();
+        assert_eq!(restored, config);
+
+        let pool = PoolData {
+            position: Some(0),
+            url: Some(config.url.clone()),
+            accepted_shares: None,
+            rejected_shares: None,
+            active: None,
+            alive: None,
+            user: Some("worker.1".to_string()),
+            password: Some("hunter2".to_string()),
+            last_share_difficulty: None,
+            last_share_time: None,
+            group: None,
+            quota: None,
+        };
+        assert!(!serde_json::to_string(&pool).unwrap().contains("hunter2"));
+
+        let without_password: PoolConfig = serde_json::from_value(json!({
+            "url": config.url,
+            "user": "worker.1",
+        }))
+        .unwrap();
+        assert_eq!(without_password.password, None);
+    }
+
+    #[test]
+    fn test_parse_malformed_url() {
+        assert_eq!(
+            PoolURL::parse("pool.example.com:3333"),
+            Some(PoolURL::from(
+                "stratum+tcp://pool.example.com:3333".to_string()
+            ))
+        );
+        assert_eq!(PoolURL::parse("stratum+udp://pool.example.com:3333"), None);
+        assert_eq!(PoolURL::parse("pool.example.com:99999"), None);
+        assert_eq!(PoolURL::parse("pool example.com:3333"), None);
+    }
 }
@@ -0,0 +1,60 @@
+use super::serialize::{serialize_current, serialize_power, serialize_voltage};
+use measurements::{Current, Power, Voltage};
+use serde::{Deserialize, Serialize};
+
+/// Output readings of the power supply feeding the hashboards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PsuData {
+    /// The output voltage of the PSU
+    #[serde(serialize_with = "serialize_voltage")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "V")))]
+    pub voltage: Option<Voltage>,
+    /// The output current of the PSU
+    #[serde(serialize_with = "serialize_current")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "A")))]
+    pub current: Option<Current>,
+    /// The output power of the PSU
+    #[serde(serialize_with = "serialize_power")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "W")))]
+    pub power: Option<Power>,
+}
+
+/// Readings of one side of the power supply, such as the AC feed it draws from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PsuRail {
+    /// The side of the PSU the readings are from, [`PsuRail::INPUT`] for its supply
+    pub name: String,
+    /// The voltage of the rail
+    #[serde(serialize_with = "serialize_voltage")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "V")))]
+    pub voltage: Option<Voltage>,
+    /// The current drawn on the rail
+    #[serde(serialize_with = "serialize_current")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "A")))]
+    pub current: Option<Current>,
+    /// The power drawn on the rail
+    #[serde(serialize_with = "serialize_power")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<f64>", extend("unit" = "W")))]
+    pub power: Option<Power>,
+}
+
+impl PsuRail {
+    /// Name of the rail feeding the PSU, the circuit it is plugged into.
+    pub const INPUT: &str = "input";
+
+    /// The input side of the PSU, `None` if it reported none of the readings.
+    pub(crate) fn input(
+        voltage: Option<f64>,
+        current: Option<f64>,
+        power: Option<f64>,
+    ) -> Option<Self> {
+        (voltage.is_some() || current.is_some() || power.is_some()).then(|| Self {
+            name: Self::INPUT.to_owned(),
+            voltage: voltage.map(Voltage::from_volts),
+            current: current.map(Current::from_amperes),
+            power: power.map(Power::from_watts),
+        })
+    }
+}
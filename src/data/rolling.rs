@@ -0,0 +1,647 @@
+use measurements::Power;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::time::Duration;
+
+use super::hashrate::{HashRate, HashRateUnit};
+use super::message::{MessageSeverity, MinerMessage, MinerMessageKind};
+use super::miner::MinerData;
+use super::pool::PoolData;
+use super::serialize::serialize_power;
+
+/// Default number of samples kept per miner.
+pub const DEFAULT_MAX_SAMPLES: usize = 256;
+
+/// Below this many expected shares the accepted count is too noisy to call a deficit.
+const MIN_EXPECTED_SHARES: f64 = 10.0;
+
+/// A reduced view of a [`MinerData`] sample, holding only what is needed for averaging.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Sample {
+    timestamp: u64,
+    /// Instantaneous hashrate in TH/s
+    hashrate: Option<f64>,
+    /// Hashrate averaged by the miner in TH/s
+    average_hashrate: Option<f64>,
+    /// Power consumption in W
+    wattage: Option<f64>,
+    accepted_shares: Option<u64>,
+    rejected_shares: Option<u64>,
+    /// The highest last share difficulty across pools
+    #[serde(default)]
+    share_difficulty: Option<f64>,
+}
+
+impl From<&MinerData> for Sample {
+    fn from(data: &MinerData) -> Self {
+        let as_th = |hr: &HashRate| hr.clone().as_unit(HashRateUnit::TeraHash).value;
+        let shares =
+            |f: fn(&PoolData) -> Option<u64>| data.pools.iter().filter_map(f).reduce(|a, b| a + b);
+        Self {
+            timestamp: data.timestamp,
+            hashrate: data.hashrate.as_ref().map(as_th),
+            average_hashrate: data.average_hashrate.as_ref().map(as_th),
+            wattage: data.wattage.map(|w| w.as_watts()),
+            accepted_shares: shares(|p| p.accepted_shares),
+            rejected_shares: shares(|p| p.rejected_shares),
+            share_difficulty: data
+                .pools
+                .iter()
+                .filter_map(|p| p.last_share_difficulty)
+                .filter(|d| *d > 0.0)
+                .reduce(f64::max),
+        }
+    }
+}
+
+/// One entry of the short-term history a miner keeps itself, such as the ePIC PowerPlay
+/// `history` endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub hashrate: HashRate,
+    /// Accepted shares since mining started, like the pool counters
+    pub accepted: u64,
+    /// Rejected shares since mining started, like the pool counters
+    pub rejected: u64,
+}
+
+impl From<&HistoryPoint> for Sample {
+    fn from(point: &HistoryPoint) -> Self {
+        Self {
+            timestamp: point.timestamp,
+            hashrate: Some(point.hashrate.clone().as_unit(HashRateUnit::TeraHash).value),
+            average_hashrate: None,
+            wattage: None,
+            accepted_shares: Some(point.accepted),
+            rejected_shares: Some(point.rejected),
+            share_difficulty: None,
+        }
+    }
+}
+
+/// Windowed averages for a single miner, as returned by [`RollingMinerStats::averages`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RollingAverages {
+    /// The number of samples inside the window
+    pub samples: usize,
+    /// The time between the oldest and newest sample inside the window
+    pub span: Duration,
+    /// The averaged hashrate, taken from the miner's own long average when it reports one
+    pub hashrate: Option<HashRate>,
+    /// The time weighted average power consumption
+    #[serde(serialize_with = "serialize_power")]
+    pub wattage: Option<Power>,
+    /// The efficiency in W/TH/s (J/TH) derived from the averaged wattage and hashrate
+    pub efficiency: Option<f64>,
+    /// Accepted shares per minute across all pools
+    pub accepted_shares_per_minute: Option<f64>,
+    /// Rejected shares per minute across all pools
+    pub rejected_shares_per_minute: Option<f64>,
+    /// Shares per minute the hashrate should find at the pools' last share difficulty
+    pub expected_shares_per_minute: Option<f64>,
+    /// How far accepted shares fall short of the expected ones, from 0 (none missing) to 1 (no
+    /// shares accepted), or `None` while too few shares are expected to tell
+    pub share_deficit_ratio: Option<f64>,
+}
+
+/// When [`RollingMinerStats::share_deficit_warning`] raises a warning.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShareDeficitAlarm {
+    /// The share deficit ratio above which the alarm fires
+    pub threshold: f64,
+    /// How long the deficit has to last, should not exceed the stats window
+    pub sustain: Duration,
+}
+
+impl Default for ShareDeficitAlarm {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            sustain: Duration::from_secs(20 * 60),
+        }
+    }
+}
+
+/// Smooths successive [`MinerData`] samples into windowed averages per miner.
+///
+/// Instantaneous readings such as `GHS 5s` are noisy, so efficiency computed from a single sample
+/// tends to jump around. Samples are weighted by the time since the previous sample using the
+/// timestamp of each [`MinerData`], so irregular poll intervals do not skew the result. Each miner
+/// keeps at most `max_samples` samples, and anything older than `window` is dropped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollingMinerStats {
+    window: Duration,
+    max_samples: usize,
+    miners: HashMap<IpAddr, VecDeque<Sample>>,
+    #[serde(default)]
+    share_deficit_alarm: ShareDeficitAlarm,
+}
+
+impl RollingMinerStats {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            max_samples: DEFAULT_MAX_SAMPLES,
+            miners: HashMap::new(),
+            share_deficit_alarm: ShareDeficitAlarm::default(),
+        }
+    }
+
+    pub fn with_share_deficit_alarm(mut self, alarm: ShareDeficitAlarm) -> Self {
+        self.share_deficit_alarm = alarm;
+        self
+    }
+
+    pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples.max(1);
+        for samples in self.miners.values_mut() {
+            while samples.len() > self.max_samples {
+                samples.pop_front();
+            }
+        }
+        self
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn max_samples(&self) -> usize {
+        self.max_samples
+    }
+
+    pub fn share_deficit_alarm(&self) -> ShareDeficitAlarm {
+        self.share_deficit_alarm
+    }
+
+    /// Add a sample for the miner it was gathered from.
+    ///
+    /// Returns `false` if the sample is older than the newest sample already held for that miner.
+    pub fn ingest(&mut self, data: &MinerData) -> bool {
+        self.push(data.ip, Sample::from(data))
+    }
+
+    /// Merge the history a miner keeps itself into its samples.
+    ///
+    /// Unlike [`ingest`](Self::ingest), points older than the samples already held are slotted in
+    /// by timestamp, so history can be merged after polling has started. Points at a timestamp
+    /// that already has a sample are skipped.
+    pub fn ingest_history(&mut self, ip: IpAddr, history: &[HistoryPoint]) {
+        if history.is_empty() {
+            return;
+        }
+        let samples = self.miners.entry(ip).or_default();
+        for point in history {
+            let sample = Sample::from(point);
+            if let Err(index) = samples.binary_search_by_key(&sample.timestamp, |s| s.timestamp) {
+                samples.insert(index, sample);
+            }
+        }
+        self.trim(&ip);
+    }
+
+    fn push(&mut self, ip: IpAddr, sample: Sample) -> bool {
+        let samples = self.miners.entry(ip).or_default();
+        if samples
+            .back()
+            .is_some_and(|s| s.timestamp > sample.timestamp)
+        {
+            return false;
+        }
+        samples.push_back(sample);
+        self.trim(&ip);
+        true
+    }
+
+    /// Drop the samples of a miner that are outside the window or over capacity.
+    fn trim(&mut self, ip: &IpAddr) {
+        let max_samples = self.max_samples;
+        let Some(samples) = self.miners.get_mut(ip) else {
+            return;
+        };
+        let Some(newest) = samples.back().map(|s| s.timestamp) else {
+            return;
+        };
+        let cutoff = newest.saturating_sub(self.window.as_secs());
+        while samples
+            .front()
+            .is_some_and(|s| s.timestamp < cutoff || samples.len() > max_samples)
+        {
+            samples.pop_front();
+        }
+    }
+
+    /// Forget all samples for a miner.
+    pub fn remove(&mut self, ip: &IpAddr) {
+        self.miners.remove(ip);
+    }
+
+    /// The miners that currently have samples.
+    pub fn ips(&self) -> impl Iterator<Item = &IpAddr> {
+        self.miners.keys()
+    }
+
+    /// Get the windowed averages for a miner, or `None` if it has no samples.
+    pub fn averages(&self, ip: &IpAddr) -> Option<RollingAverages> {
+        let samples = self.miners.get(ip).filter(|s| !s.is_empty())?;
+        let weights = weights(samples);
+
+        let newest = samples.back()?;
+        let hashrate = newest
+            .average_hashrate
+            .or_else(|| weighted_mean(samples, &weights, |s| s.hashrate));
+        let wattage = weighted_mean(samples, &weights, |s| s.wattage);
+        let efficiency = match (hashrate, wattage) {
+            (Some(hr), Some(w)) if hr > 0.0 => Some(w / hr),
+            _ => None,
+        };
+
+        Some(RollingAverages {
+            samples: samples.len(),
+            span: Duration::from_secs(newest.timestamp - samples.front()?.timestamp),
+            hashrate: hashrate.map(|value| HashRate {
+                value,
+                unit: HashRateUnit::TeraHash,
+                algo: String::from("SHA256"),
+            }),
+            wattage: wattage.map(Power::from_watts),
+            efficiency,
+            accepted_shares_per_minute: share_rate(samples, |s| s.accepted_shares),
+            rejected_shares_per_minute: share_rate(samples, |s| s.rejected_shares),
+            expected_shares_per_minute: share_throughput(samples.iter())
+                .map(|t| t.expected * 60.0 / t.seconds as f64),
+            share_deficit_ratio: share_throughput(samples.iter()).and_then(|t| t.deficit_ratio()),
+        })
+    }
+
+    /// A warning if a miner's accepted shares fell short of its hashrate by more than the alarm
+    /// threshold over the whole sustain period.
+    ///
+    /// This catches a miner hashing at full speed whose shares never reach the pool, which
+    /// hashrate alone doesn't show. Returns `None` until the samples cover the sustain period.
+    pub fn share_deficit_warning(&self, ip: &IpAddr) -> Option<MinerMessage> {
+        let samples = self.miners.get(ip)?;
+        let newest = samples.back()?;
+        let sustain = self.share_deficit_alarm.sustain;
+        let cutoff = newest.timestamp.checked_sub(sustain.as_secs())?;
+        let start = samples.iter().rposition(|s| s.timestamp <= cutoff)?;
+        let ratio = share_throughput(samples.range(start..))?.deficit_ratio()?;
+        (ratio > self.share_deficit_alarm.threshold).then(|| {
+            MinerMessage::new(
+                newest.timestamp as u32,
+                0,
+                format!(
+                    "Accepted shares {:.0}% below hashrate for {} minutes",
+                    ratio * 100.0,
+                    sustain.as_secs() / 60
+                ),
+                MessageSeverity::Warning,
+            )
+            .with_kind(MinerMessageKind::PoolIssue)
+        })
+    }
+}
+
+/// Shares expected from the hashrate against shares accepted, over a run of samples.
+struct ShareThroughput {
+    expected: f64,
+    accepted: u64,
+    seconds: u64,
+}
+
+impl ShareThroughput {
+    fn deficit_ratio(&self) -> Option<f64> {
+        (self.expected >= MIN_EXPECTED_SHARES)
+            .then(|| (1.0 - self.accepted as f64 / self.expected).max(0.0))
+    }
+}
+
+/// Total the shares the hashrate should have found against the shares accepted.
+///
+/// A share at difficulty `d` takes `d * 2^32` hashes on average. Each interval uses the higher
+/// difficulty of its two samples, so a vardiff change between polls can only lower the expected
+/// count. Intervals missing a reading or where the accepted counter was reset are skipped.
+fn share_throughput<'a>(
+    samples: impl Iterator<Item = &'a Sample> + Clone,
+) -> Option<ShareThroughput> {
+    let hashrate = |s: &Sample| s.hashrate.or(s.average_hashrate);
+    let totals = samples
+        .clone()
+        .zip(samples.skip(1))
+        .filter_map(|(prev, next)| {
+            let accepted = next.accepted_shares?.checked_sub(prev.accepted_shares?)?;
+            let difficulty = prev.share_difficulty?.max(next.share_difficulty?);
+            let hashes_per_second = (hashrate(prev)? + hashrate(next)?) / 2.0 * 1e12;
+            let seconds = next.timestamp - prev.timestamp;
+            let expected = hashes_per_second * seconds as f64 / (difficulty * 2f64.powi(32));
+            Some((expected, accepted, seconds))
+        })
+        .fold(
+            ShareThroughput {
+                expected: 0.0,
+                accepted: 0,
+                seconds: 0,
+            },
+            |total, (expected, accepted, seconds)| ShareThroughput {
+                expected: total.expected + expected,
+                accepted: total.accepted + accepted,
+                seconds: total.seconds + seconds,
+            },
+        );
+    (totals.seconds > 0).then_some(totals)
+}
+
+/// Weight each sample by the time since the previous one.
+///
+/// The first sample has no previous interval inside the window, so it gets the mean interval.
+/// If every sample shares a timestamp, all samples are weighted equally.
+fn weights(samples: &VecDeque<Sample>) -> Vec<f64> {
+    let mut weights: Vec<f64> = samples
+        .iter()
+        .zip(samples.iter().skip(1))
+        .map(|(prev, next)| (next.timestamp - prev.timestamp) as f64)
+        .collect();
+    let total: f64 = weights.iter().sum();
+    if total == 0.0 {
+        return vec![1.0; samples.len()];
+    }
+    weights.insert(0, total / weights.len() as f64);
+    weights
+}
+
+fn weighted_mean(
+    samples: &VecDeque<Sample>,
+    weights: &[f64],
+    value: impl Fn(&Sample) -> Option<f64>,
+) -> Option<f64> {
+    let (sum, weight) = samples
+        .iter()
+        .zip(weights)
+        .filter_map(|(s, w)| value(s).filter(|v| v.is_finite()).map(|v| (v * w, *w)))
+        .fold((0.0, 0.0), |(sum, weight), (v, w)| (sum + v, weight + w));
+    (weight > 0.0).then_some(sum / weight)
+}
+
+/// Shares per minute from the growth of a share counter, skipping intervals where it was reset.
+fn share_rate(samples: &VecDeque<Sample>, counter: impl Fn(&Sample) -> Option<u64>) -> Option<f64> {
+    let (shares, seconds) = samples
+        .iter()
+        .zip(samples.iter().skip(1))
+        .filter_map(|(prev, next)| {
+            let delta = counter(next)?.checked_sub(counter(prev)?)?;
+            Some((delta, next.timestamp - prev.timestamp))
+        })
+        .fold((0, 0), |(shares, seconds), (d, s)| {
+            (shares + d, seconds + s)
+        });
+    (seconds > 0).then_some(shares as f64 * 60.0 / seconds as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+    /// Small deterministic generator so noisy input is reproducible without extra dependencies.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next_f64(&mut self) -> f64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 11) as f64 / (1u64 << 53) as f64
+        }
+    }
+
+    fn sample(timestamp: u64, hashrate: f64, wattage: f64) -> Sample {
+        Sample {
+            timestamp,
+            hashrate: Some(hashrate),
+            average_hashrate: None,
+            wattage: Some(wattage),
+            accepted_shares: None,
+            rejected_shares: None,
+            share_difficulty: None,
+        }
+    }
+
+    #[test]
+    fn test_converges_to_true_mean() {
+        for seed in 1..=20 {
+            let mut rng = Lcg(seed);
+            let mut stats =
+                RollingMinerStats::new(Duration::from_secs(3600)).with_max_samples(1000);
+            let mut timestamp = 1_700_000_000;
+            for _ in 0..500 {
+                // irregular poll intervals between 1 and 10 seconds, noise of +/- 10%
+                timestamp += 1 + (rng.next_f64() * 10.0) as u64;
+                let hashrate = 200.0 * (0.9 + rng.next_f64() * 0.2);
+                let wattage = 3500.0 * (0.9 + rng.next_f64() * 0.2);
+                assert!(stats.push(IP, sample(timestamp, hashrate, wattage)));
+            }
+
+            let averages = stats.averages(&IP).unwrap();
+            let hashrate = averages.hashrate.unwrap().value;
+            let wattage = averages.wattage.unwrap().as_watts();
+            assert!((hashrate - 200.0).abs() < 2.0, "seed {seed}: {hashrate}");
+            assert!((wattage - 3500.0).abs() < 35.0, "seed {seed}: {wattage}");
+            assert!((averages.efficiency.unwrap() - 17.5).abs() < 0.35);
+        }
+    }
+
+    #[test]
+    fn test_time_weighting() {
+        let mut stats = RollingMinerStats::new(Duration::from_secs(600));
+        stats.push(IP, sample(0, 100.0, 3000.0));
+        stats.push(IP, sample(10, 100.0, 3000.0));
+        // a single long interval at the new value outweighs the short ones before it
+        stats.push(IP, sample(100, 200.0, 3000.0));
+
+        let hashrate = stats.averages(&IP).unwrap().hashrate.unwrap().value;
+        assert!((hashrate - 160.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_window_and_capacity_bounds() {
+        let mut stats = RollingMinerStats::new(Duration::from_secs(60)).with_max_samples(4);
+        for t in 0..10 {
+            stats.push(IP, sample(t * 10, 100.0, 3000.0));
+        }
+        let averages = stats.averages(&IP).unwrap();
+        assert_eq!(averages.samples, 4);
+        assert_eq!(averages.span, Duration::from_secs(30));
+
+        let mut stats = RollingMinerStats::new(Duration::from_secs(25));
+        for t in 0..10 {
+            stats.push(IP, sample(t * 10, 100.0, 3000.0));
+        }
+        assert_eq!(stats.averages(&IP).unwrap().samples, 3);
+        assert!(!stats.push(IP, sample(5, 100.0, 3000.0)));
+    }
+
+    #[test]
+    fn test_ingest_history() {
+        let point = |timestamp, th| HistoryPoint {
+            timestamp,
+            hashrate: HashRate {
+                value: th * 1000.0,
+                unit: HashRateUnit::GigaHash,
+                algo: String::from("SHA256"),
+            },
+            accepted: timestamp,
+            rejected: 0,
+        };
+        let mut stats = RollingMinerStats::new(Duration::from_secs(120));
+        stats.push(IP, sample(300, 100.0, 3000.0));
+        // history from before polling started is slotted in behind the polled sample, the
+        // duplicate timestamp and the point outside the window are dropped
+        stats.ingest_history(
+            IP,
+            &[
+                point(100, 90.0),
+                point(240, 100.0),
+                point(270, 100.0),
+                point(300, 50.0),
+            ],
+        );
+
+        let averages = stats.averages(&IP).unwrap();
+        assert_eq!(averages.samples, 3);
+        assert_eq!(averages.span, Duration::from_secs(60));
+        assert!((averages.hashrate.unwrap().value - 100.0).abs() < 1e-9);
+        assert_eq!(averages.accepted_shares_per_minute, Some(60.0));
+        assert!(stats.push(IP, sample(330, 100.0, 3000.0)));
+    }
+
+    #[test]
+    fn test_prefers_firmware_average_and_share_rates() {
+        let mut stats = RollingMinerStats::new(Duration::from_secs(600));
+        for (t, accepted) in [(0, 100), (60, 110), (120, 5), (180, 17)] {
+            stats.push(
+                IP,
+                Sample {
+                    accepted_shares: Some(accepted),
+                    rejected_shares: Some(0),
+                    average_hashrate: Some(190.0),
+                    ..sample(t, 250.0, 3800.0)
+                },
+            );
+        }
+
+        let averages = stats.averages(&IP).unwrap();
+        assert_eq!(averages.hashrate.unwrap().value, 190.0);
+        assert!((averages.efficiency.unwrap() - 20.0).abs() < 1e-9);
+        // the counter reset between 60 and 120 is skipped
+        assert_eq!(averages.accepted_shares_per_minute, Some(11.0));
+        assert_eq!(averages.rejected_shares_per_minute, Some(0.0));
+    }
+
+    /// Shares a minute at 200 TH/s and the given difficulty.
+    fn shares_per_minute(difficulty: f64) -> f64 {
+        200e12 * 60.0 / (difficulty * 2f64.powi(32))
+    }
+
+    /// One sample a minute at 200 TH/s for half an hour, `minute` returns the difficulty reported
+    /// at the end of each minute and the shares accepted during it.
+    fn share_stats(minute: impl Fn(u64) -> (f64, f64)) -> RollingMinerStats {
+        let mut stats = RollingMinerStats::new(Duration::from_secs(3600));
+        let mut accepted = 0.0;
+        for t in 0..=30 {
+            let (difficulty, shares) = minute(t);
+            if t > 0 {
+                accepted += shares;
+            }
+            stats.push(
+                IP,
+                Sample {
+                    accepted_shares: Some(accepted as u64),
+                    share_difficulty: Some(difficulty),
+                    ..sample(t * 60, 200.0, 3500.0)
+                },
+            );
+        }
+        stats
+    }
+
+    #[test]
+    fn test_share_deficit() {
+        let low = 65536.0;
+        let high = 524288.0;
+
+        let healthy = share_stats(|_| (low, shares_per_minute(low)));
+        let averages = healthy.averages(&IP).unwrap();
+        assert!((averages.expected_shares_per_minute.unwrap() - 42.63).abs() < 0.01);
+        assert!(averages.share_deficit_ratio.unwrap() < 0.01);
+        assert_eq!(healthy.share_deficit_warning(&IP), None);
+
+        // the pool stops accepting shares after five minutes
+        let stuck = share_stats(|t| (low, if t < 5 { shares_per_minute(low) } else { 0.0 }));
+        assert!(stuck.averages(&IP).unwrap().share_deficit_ratio.unwrap() > 0.8);
+        let warning = stuck.share_deficit_warning(&IP).unwrap();
+        assert_eq!(warning.severity, MessageSeverity::Warning);
+        assert_eq!(warning.timestamp, 1800);
+
+        // vardiff raises the difficulty halfway through minute 10 and lowers it again halfway
+        // through minute 20, the share rate follows
+        let spike = share_stats(|t| match t {
+            10 | 20 => (
+                if t == 10 { high } else { low },
+                (shares_per_minute(low) + shares_per_minute(high)) / 2.0,
+            ),
+            11..20 => (high, shares_per_minute(high)),
+            _ => (low, shares_per_minute(low)),
+        });
+        assert!(spike.averages(&IP).unwrap().share_deficit_ratio.unwrap() < 0.05);
+        assert_eq!(spike.share_deficit_warning(&IP), None);
+    }
+
+    #[test]
+    fn test_share_deficit_sustain() {
+        let stuck = |t| {
+            (
+                65536.0,
+                if t < 23 {
+                    shares_per_minute(65536.0)
+                } else {
+                    0.0
+                },
+            )
+        };
+        // eight minutes without shares leave the default 20 minute period 40% short
+        assert_eq!(share_stats(stuck).share_deficit_warning(&IP), None);
+
+        let alarm = ShareDeficitAlarm {
+            threshold: 0.5,
+            sustain: Duration::from_secs(10 * 60),
+        };
+        let stats = share_stats(stuck).with_share_deficit_alarm(alarm);
+        assert!(stats.share_deficit_warning(&IP).is_some());
+
+        // samples that don't reach back the full sustain period can't raise it
+        let alarm = ShareDeficitAlarm {
+            sustain: Duration::from_secs(3600),
+            ..alarm
+        };
+        let stats = share_stats(stuck).with_share_deficit_alarm(alarm);
+        assert_eq!(stats.share_deficit_warning(&IP), None);
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut stats = RollingMinerStats::new(Duration::from_secs(600));
+        stats.push(IP, sample(0, 100.0, 3000.0));
+        stats.push(IP, sample(30, 110.0, 3100.0));
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let restored: RollingMinerStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, stats);
+        assert_eq!(restored.averages(&IP), stats.averages(&IP));
+    }
+}
@@ -0,0 +1,214 @@
+//! Plausibility checks for readings reported by miners.
+//!
+//! Firmware reports sentinel values such as 65535 °C, -1 RPM or 0 W while sensors or PSUs are
+//! starting up. Readings outside of the [`PlausibilityRanges`] of a miner are dropped before they
+//! reach [`MinerData`][`crate::data::miner::MinerData`], so they can't skew averages.
+
+use crate::data::board::BoardData;
+use crate::data::fan::FanData;
+use crate::data::hashrate::HashRate;
+use crate::data::psu::PsuRail;
+use measurements::{AngularVelocity, Current, Power, Temperature, Voltage};
+use std::ops::RangeInclusive;
+
+/// Ranges of plausible readings, anything outside of them or not finite is treated as missing.
+///
+/// Every miner has its own, see
+/// [`GetPlausibilityRanges`][`crate::miners::backends::traits::GetPlausibilityRanges`] and
+/// [`MinerFactory::with_plausibility_ranges`][`crate::miners::factory::MinerFactory::with_plausibility_ranges`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlausibilityRanges {
+    /// Temperatures in °C
+    pub temperature: RangeInclusive<f64>,
+    /// Fan speeds in RPM
+    pub fan_rpm: RangeInclusive<f64>,
+    /// Power draw and limits in W, 0 W is excluded since PSUs report it while starting up
+    pub wattage: RangeInclusive<f64>,
+    /// PSU voltages in V, 0 V is excluded for the same reason
+    pub voltage: RangeInclusive<f64>,
+    /// PSU currents in A, 0 A is excluded for the same reason
+    pub current: RangeInclusive<f64>,
+    /// Lowest hashrate, in any unit
+    pub min_hashrate: f64,
+}
+
+impl Default for PlausibilityRanges {
+    fn default() -> Self {
+        Self {
+            temperature: -40.0..=150.0,
+            fan_rpm: 0.0..=20_000.0,
+            wattage: f64::MIN_POSITIVE..=20_000.0,
+            voltage: f64::MIN_POSITIVE..=1_000.0,
+            current: f64::MIN_POSITIVE..=1_000.0,
+            min_hashrate: 0.0,
+        }
+    }
+}
+
+impl PlausibilityRanges {
+    fn check<T>(value: Option<T>, raw: f64, range: &RangeInclusive<f64>, field: &str) -> Option<T> {
+        // an infinite sentinel would pass a range without an upper bound
+        if raw.is_finite() && range.contains(&raw) {
+            value
+        } else {
+            tracing::debug!(field, raw, "dropping implausible reading");
+            None
+        }
+    }
+
+    pub(crate) fn temperature(
+        &self,
+        value: Option<Temperature>,
+        field: &str,
+    ) -> Option<Temperature> {
+        let raw = value?.as_celsius();
+        Self::check(value, raw, &self.temperature, field)
+    }
+
+    pub(crate) fn fan_rpm(
+        &self,
+        value: Option<AngularVelocity>,
+        field: &str,
+    ) -> Option<AngularVelocity> {
+        let raw = value?.as_rpm();
+        Self::check(value, raw, &self.fan_rpm, field)
+    }
+
+    pub(crate) fn wattage(&self, value: Option<Power>, field: &str) -> Option<Power> {
+        let raw = value?.as_watts();
+        Self::check(value, raw, &self.wattage, field)
+    }
+
+    pub(crate) fn voltage(&self, value: Option<Voltage>, field: &str) -> Option<Voltage> {
+        let raw = value?.as_volts();
+        Self::check(value, raw, &self.voltage, field)
+    }
+
+    pub(crate) fn amperes(&self, value: Option<Current>, field: &str) -> Option<Current> {
+        let raw = value?.as_amperes();
+        Self::check(value, raw, &self.current, field)
+    }
+
+    pub(crate) fn hashrate(&self, value: Option<HashRate>, field: &str) -> Option<HashRate> {
+        let raw = value.as_ref()?.value;
+        Self::check(value, raw, &(self.min_hashrate..=f64::INFINITY), field)
+    }
+
+    pub(crate) fn fans(&self, fans: Vec<FanData>, field: &str) -> Vec<FanData> {
+        fans.into_iter()
+            .map(|fan| FanData {
+                rpm: self.fan_rpm(fan.rpm, field),
+                ..fan
+            })
+            .collect()
+    }
+
+    pub(crate) fn psu_rails(&self, rails: Vec<PsuRail>) -> Vec<PsuRail> {
+        rails
+            .into_iter()
+            .map(|rail| PsuRail {
+                voltage: self.voltage(rail.voltage, "psu_rails.voltage"),
+                current: self.amperes(rail.current, "psu_rails.current"),
+                power: self.wattage(rail.power, "psu_rails.power"),
+                ..rail
+            })
+            .collect()
+    }
+
+    pub(crate) fn hashboards(&self, boards: Vec<BoardData>) -> Vec<BoardData> {
+        boards
+            .into_iter()
+            .map(|mut board| {
+                board.hashrate = self.hashrate(board.hashrate, "hashboards.hashrate");
+                board.expected_hashrate =
+                    self.hashrate(board.expected_hashrate, "hashboards.expected_hashrate");
+                board.board_temperature =
+                    self.temperature(board.board_temperature, "hashboards.board_temperature");
+                board.intake_temperature =
+                    self.temperature(board.intake_temperature, "hashboards.intake_temperature");
+                board.outlet_temperature =
+                    self.temperature(board.outlet_temperature, "hashboards.outlet_temperature");
+                board.sensor_temperatures = std::mem::take(&mut board.sensor_temperatures)
+                    .into_iter()
+                    .filter_map(|t| self.temperature(Some(t), "hashboards.sensor_temperatures"))
+                    .collect();
+                for chip in &mut board.chips {
+                    chip.hashrate =
+                        self.hashrate(chip.hashrate.take(), "hashboards.chips.hashrate");
+                    chip.temperature =
+                        self.temperature(chip.temperature, "hashboards.chips.temperature");
+                }
+                board
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::hashrate::HashRateUnit;
+
+    #[test]
+    fn test_sentinel_values() {
+        let ranges = PlausibilityRanges::default();
+
+        assert_eq!(
+            ranges.temperature(Some(Temperature::from_celsius(65535.0)), "temp"),
+            None
+        );
+        assert_eq!(
+            ranges.temperature(Some(Temperature::from_celsius(f64::NAN)), "temp"),
+            None
+        );
+        assert!(
+            ranges
+                .temperature(Some(Temperature::from_celsius(-10.0)), "temp")
+                .is_some()
+        );
+        assert_eq!(
+            ranges.fan_rpm(Some(AngularVelocity::from_rpm(-1.0)), "fan"),
+            None
+        );
+        assert_eq!(ranges.wattage(Some(Power::from_watts(0.0)), "power"), None);
+        assert_eq!(
+            ranges.hashrate(
+                Some(HashRate {
+                    value: f64::INFINITY,
+                    unit: HashRateUnit::TeraHash,
+                    algo: "SHA256".to_string(),
+                }),
+                "hashrate"
+            ),
+            None
+        );
+        assert!(
+            ranges
+                .wattage(Some(Power::from_watts(3250.0)), "power")
+                .is_some()
+        );
+
+        let bring_up = ranges.psu_rails(vec![
+            PsuRail::input(Some(0.0), Some(0.0), Some(0.0)).unwrap(),
+        ]);
+        assert_eq!(
+            (bring_up[0].voltage, bring_up[0].current, bring_up[0].power),
+            (None, None, None)
+        );
+        assert!(
+            ranges
+                .voltage(Some(Voltage::from_volts(223.75)), "voltage")
+                .is_some()
+        );
+
+        let custom = PlausibilityRanges {
+            temperature: -40.0..=200.0,
+            ..Default::default()
+        };
+        assert!(
+            custom
+                .temperature(Some(Temperature::from_celsius(180.0)), "temp")
+                .is_some()
+        );
+    }
+}
@@ -1,5 +1,6 @@
+use crate::data::conflicts::SharedIdentifier;
 use macaddr::MacAddr;
-use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
+use measurements::{AngularVelocity, Current, Frequency, Power, Temperature, Voltage};
 
 pub(crate) fn serialize_angular_velocity<S>(
     v: &Option<AngularVelocity>,
@@ -27,6 +28,13 @@ where
     }
 }
 
+pub(crate) fn serialize_temperatures<S>(t: &[Temperature], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_seq(t.iter().map(|temperature| temperature.as_celsius()))
+}
+
 pub(crate) fn serialize_power<S>(p: &Option<Power>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
@@ -59,6 +67,16 @@ where
     }
 }
 
+pub(crate) fn serialize_current<S>(c: &Option<Current>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match c {
+        Some(current) => serializer.serialize_f64(current.as_amperes()),
+        None => serializer.serialize_none(),
+    }
+}
+
 pub(crate) fn serialize_macaddr<S>(m: &Option<MacAddr>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
@@ -68,3 +86,16 @@ where
         None => serializer.serialize_none(),
     }
 }
+
+pub(crate) fn serialize_shared_macs<S>(
+    shared: &[SharedIdentifier<MacAddr>],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_seq(shared.iter().map(|mac| SharedIdentifier {
+        value: mac.value.to_string(),
+        ips: mac.ips.clone(),
+    }))
+}
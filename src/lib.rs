@@ -64,6 +64,30 @@
 //! These also have corresponding methods for appending to an existing factory, or overwriting existing ranges.
 //! See [`MinerFactory`] for more details.
 //!
+//! A factory can be configured once and cloned for each subnet, with the range set on each clone:
+//! ```no_run
+//! use asic_rs::MinerFactory;
+//! use asic_rs::data::device::MinerMake;
+//! use asic_rs::miners::backends::traits::Credentials;
+//! use tokio;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let template = MinerFactory::new()
+//!         .with_search_makes(vec![MinerMake::AntMiner, MinerMake::WhatsMiner])
+//!         .with_identification_timeout_secs(5)
+//!         .with_credentials(vec![Credentials::new("root", "root")]);
+//!
+//!     for subnet in ["10.0.1.0/24", "10.0.2.0/24"] {
+//!         let mut factory = template.clone();
+//!         factory.set_subnet(subnet).unwrap();
+//!         // the password of the credentials is redacted
+//!         println!("scanning {:?} with {factory:?}", factory.ranges());
+//!         let miners = factory.scan().await.unwrap();
+//!     }
+//! }
+//! ```
+//!
 //! ### Data gathering
 //! Getting data is very simple with asic-rs, everything you need can be gathered with a single call.
 //! Extending the "Getting a miner" example:
@@ -133,6 +157,25 @@
 //!     }
 //! }
 //! ```
+//!
+//! ### Backend features
+//! Each backend family is behind a cargo feature, all enabled by default: `antminer`, `avalon`,
+//! `bitaxe`, `braiins`, `cgminer`, `epic`, `luxos`, `marathon`, `vnish` and `whatsminer`, where
+//! `cgminer` is the generic fallback of [`MinerFactory::with_generic_fallback`].
+//! Disabling the ones you don't manage drops their code, and the dependencies only they use:
+//! ```toml
+//! asic-rs = { version = "0.2", default-features = false, features = ["whatsminer"] }
+//! ```
+//! A miner identified as a make or firmware whose backend is disabled is returned as a
+//! "backend not compiled in" error from [`MinerFactory::get_miner`].
+//!
+//! ### Testing backends
+//! The `test-util` feature exports `test_util::MockAPIClient`, the mock API client the backends
+//! of this crate are tested with. It answers commands from recorded responses or sequences of
+//! them, injects timeouts, dropped connections and malformed JSON, and records the commands it
+//! was sent. New backends should be tested with it. `test_util::MinerSimulator` serves the
+//! recorded responses of a whole miner over its real ports on a loopback address, for end-to-end
+//! tests.
 
 pub use crate::miners::factory::MinerFactory;
 pub use crate::miners::listener::MinerListener;
@@ -140,6 +183,8 @@ pub use crate::miners::listener::MinerListener;
 pub mod data;
 pub mod miners;
 pub(crate) mod test;
+#[cfg(feature = "test-util")]
+pub use test::api as test_util;
 
 #[cfg(feature = "python")]
 mod python;
@@ -0,0 +1,551 @@
+//! The `asic-rs` command line tool, built with the `cli` feature.
+//!
+//! Exit codes are stable for scripting: `0` when everything succeeded, `1` when nothing did or
+//! the command could not run, `2` for invalid arguments and `3` when only some miners succeeded.
+
+use anyhow::{Result, bail};
+use asic_rs::MinerFactory;
+use asic_rs::data::device::DeviceInfo;
+use asic_rs::data::miner::MinerData;
+use asic_rs::miners::backends::capabilities::MinerCapabilities;
+use asic_rs::miners::backends::dry_run::{DryRun, PlannedAction};
+use asic_rs::miners::backends::errors::ControlError;
+use asic_rs::miners::backends::traits::*;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use measurements::Power;
+use serde::Serialize;
+use std::io::Write;
+use std::net::IpAddr;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Duration;
+
+const SUCCESS: u8 = 0;
+const FAILURE: u8 = 1;
+const PARTIAL_FAILURE: u8 = 3;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "asic-rs",
+    version,
+    about = "Discover, monitor and control ASIC miners"
+)]
+struct Cli {
+    /// Print one JSON object per line instead of a table
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Find the miners in IPs, ranges ("10.0.0.1-50"), subnets ("10.0.0.0/24") or files of them
+    Scan {
+        #[arg(required = true)]
+        targets: Vec<String>,
+    },
+    /// Print all data of a miner
+    Data { ip: IpAddr },
+    /// Poll the miners in the targets until interrupted, printing their data as it arrives
+    Watch {
+        #[arg(required = true)]
+        targets: Vec<String>,
+        /// Time between polls, such as "30s", "5m" or a number of seconds
+        #[arg(long, default_value = "30s", value_parser = parse_interval)]
+        interval: Duration,
+    },
+    /// Reboot miners, or restart mining where the firmware can't reboot
+    Reboot(ControlArgs),
+    /// Stop hashing
+    Pause(ControlArgs),
+    /// Start hashing again after a pause
+    Resume(ControlArgs),
+    /// Turn the fault light on or off
+    Led {
+        #[command(flatten)]
+        control: ControlArgs,
+        #[arg(value_enum)]
+        state: LedState,
+    },
+    /// Set the power limit in watts
+    PowerLimit {
+        #[command(flatten)]
+        control: ControlArgs,
+        watts: f64,
+        /// Skip checking the limit against the range of the model
+        #[arg(long)]
+        unchecked: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+struct ControlArgs {
+    /// An IP, range, subnet or file of targets
+    targets: String,
+    /// Don't ask for confirmation
+    #[arg(long)]
+    yes: bool,
+    /// Print the commands that would be sent instead of sending them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum LedState {
+    On,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Reboot,
+    Pause,
+    Resume,
+    Led(bool),
+    PowerLimit { limit: Power, unchecked: bool },
+}
+
+impl Action {
+    fn describe(&self) -> String {
+        match self {
+            Action::Reboot => "Reboot".to_string(),
+            Action::Pause => "Pause".to_string(),
+            Action::Resume => "Resume".to_string(),
+            Action::Led(true) => "Turn on the fault light of".to_string(),
+            Action::Led(false) => "Turn off the fault light of".to_string(),
+            Action::PowerLimit { limit, .. } => {
+                format!("Set a {} W power limit on", limit.as_watts())
+            }
+        }
+    }
+
+    async fn run(&self, miner: &dyn Miner) -> Result<bool> {
+        match self {
+            Action::Reboot => match miner.reboot_system().await {
+                // the firmware can only restart mining, which is what the help promises
+                Err(e)
+                    if matches!(
+                        e.downcast_ref::<ControlError>(),
+                        Some(ControlError::UnsupportedOperation(_))
+                    ) =>
+                {
+                    miner.restart_mining().await
+                }
+                result => result,
+            },
+            Action::Pause => miner.pause(None).await,
+            Action::Resume => miner.resume(None).await,
+            Action::Led(on) => miner.set_fault_light(*on).await,
+            Action::PowerLimit {
+                limit,
+                unchecked: false,
+            } => miner.set_power_limit(*limit).await,
+            Action::PowerLimit {
+                limit,
+                unchecked: true,
+            } => miner.set_power_limit_unchecked(*limit).await,
+        }
+    }
+}
+
+/// A discovered miner, as printed by `scan`.
+#[derive(Debug, Serialize)]
+struct ScanResult {
+    ip: IpAddr,
+    device_info: DeviceInfo,
+    capabilities: MinerCapabilities,
+}
+
+/// The outcome of a control command on one miner.
+#[derive(Debug, Serialize)]
+struct ControlResult {
+    ip: IpAddr,
+    success: bool,
+    error: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            ExitCode::from(FAILURE)
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<u8> {
+    let json = cli.json;
+    match cli.command {
+        Command::Scan { targets } => scan(factory(&targets)?, json).await,
+        Command::Data { ip } => data(ip, json).await,
+        Command::Watch { targets, interval } => watch(factory(&targets)?, interval, json).await,
+        Command::Reboot(control) => run_control(control, Action::Reboot, json).await,
+        Command::Pause(control) => run_control(control, Action::Pause, json).await,
+        Command::Resume(control) => run_control(control, Action::Resume, json).await,
+        Command::Led { control, state } => {
+            run_control(control, Action::Led(state == LedState::On), json).await
+        }
+        Command::PowerLimit {
+            control,
+            watts,
+            unchecked,
+        } => {
+            if !watts.is_finite() || watts <= 0.0 {
+                bail!("the power limit must be a positive number of watts");
+            }
+            let action = Action::PowerLimit {
+                limit: Power::from_watts(watts),
+                unchecked,
+            };
+            run_control(control, action, json).await
+        }
+    }
+}
+
+/// A factory for the given IPs, ranges, subnets and files listing them one per line.
+fn factory(targets: &[String]) -> Result<MinerFactory> {
+    let mut factory = MinerFactory::new();
+    for target in targets {
+        let path = Path::new(target);
+        if path.is_file() {
+            let contents = std::fs::read_to_string(path)?;
+            for line in contents.lines().map(str::trim) {
+                if !line.is_empty() && !line.starts_with('#') {
+                    factory = add_target(factory, line)?;
+                }
+            }
+        } else {
+            factory = add_target(factory, target)?;
+        }
+    }
+    if factory.is_empty() {
+        bail!("no IPs to scan");
+    }
+    Ok(factory)
+}
+
+fn add_target(factory: MinerFactory, target: &str) -> Result<MinerFactory> {
+    match target.contains('/') {
+        true => factory.with_subnet(target),
+        false => factory.with_range(target),
+    }
+}
+
+/// Parse an interval such as "30s", "5m", "1h" or a plain number of seconds.
+fn parse_interval(interval: &str) -> Result<Duration, String> {
+    let interval = interval.trim();
+    let (value, scale) = match interval.chars().last() {
+        Some('s') => (&interval[..interval.len() - 1], 1),
+        Some('m') => (&interval[..interval.len() - 1], 60),
+        Some('h') => (&interval[..interval.len() - 1], 60 * 60),
+        _ => (interval, 1),
+    };
+    match value.trim().parse::<u64>() {
+        Ok(value) if value > 0 => Ok(Duration::from_secs(value * scale)),
+        _ => Err(format!(
+            "invalid interval {interval:?}, expected e.g. 30s or 5m"
+        )),
+    }
+}
+
+/// The exit code for a command that succeeded on some miners and failed on others.
+fn exit_code(succeeded: usize, failed: usize) -> u8 {
+    match (succeeded, failed) {
+        (0, _) => FAILURE,
+        (_, 0) => SUCCESS,
+        _ => PARTIAL_FAILURE,
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
+
+async fn scan(factory: MinerFactory, json: bool) -> Result<u8> {
+    if !json {
+        println!("{:<15} {:<12} {:<28} FIRMWARE", "IP", "MAKE", "MODEL");
+    }
+    let mut found = 0;
+    let mut miners = factory.scan_stream();
+    while let Some(miner) = miners.next().await {
+        found += 1;
+        let result = ScanResult {
+            ip: miner.get_ip(),
+            device_info: miner.get_device_info(),
+            capabilities: miner.capabilities(),
+        };
+        match json {
+            true => print_json(&result)?,
+            false => println!(
+                "{:<15} {:<12} {:<28} {}",
+                result.ip.to_string(),
+                result.device_info.make.to_string(),
+                result.device_info.model.to_string(),
+                result.device_info.firmware
+            ),
+        }
+    }
+    if found == 0 {
+        eprintln!("no miners found");
+    }
+    Ok(exit_code(found, 0))
+}
+
+async fn data(ip: IpAddr, json: bool) -> Result<u8> {
+    let Some(miner) = MinerFactory::new().get_miner(ip).await? else {
+        bail!("no supported miner found at {ip}");
+    };
+    let data = miner.get_data().await;
+    match json {
+        true => print_json(&data)?,
+        false => println!("{}", serde_json::to_string_pretty(&data)?),
+    }
+    Ok(SUCCESS)
+}
+
+fn print_data_row(data: &MinerData) {
+    let hashrate = data
+        .hashrate
+        .as_ref()
+        .map(|h| format!("{h:.2}"))
+        .unwrap_or_else(|| "-".to_string());
+    let wattage = data
+        .wattage
+        .map(|w| format!("{:.0} W", w.as_watts()))
+        .unwrap_or_else(|| "-".to_string());
+    let temperature = data
+        .average_temperature
+        .map(|t| format!("{:.1} °C", t.as_celsius()))
+        .unwrap_or_else(|| "-".to_string());
+    println!(
+        "{:<15} {:<28} {:>14} {:>8} {:>8} {}",
+        data.ip.to_string(),
+        data.device_info.model.to_string(),
+        hashrate,
+        wattage,
+        temperature,
+        data.status
+    );
+}
+
+async fn watch(factory: MinerFactory, interval: Duration, json: bool) -> Result<u8> {
+    let miners = factory.scan().await?;
+    if miners.is_empty() {
+        eprintln!("no miners found");
+        return Ok(FAILURE);
+    }
+    if !json {
+        println!(
+            "{:<15} {:<28} {:>14} {:>8} {:>8} STATUS",
+            "IP", "MODEL", "HASHRATE", "WATTAGE", "TEMP"
+        );
+    }
+
+    let mut ticker = tokio::time::interval(interval);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        let poll = async {
+            ticker.tick().await;
+            let mut pending: FuturesUnordered<_> = miners.iter().map(|m| m.get_data()).collect();
+            while let Some(data) = pending.next().await {
+                match json {
+                    true => print_json(&data)?,
+                    false => print_data_row(&data),
+                }
+            }
+            anyhow::Ok(())
+        };
+        tokio::select! {
+            _ = &mut ctrl_c => return Ok(SUCCESS),
+            polled = poll => polled?,
+        }
+    }
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    eprint!("{prompt} [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+async fn run_control(control: ControlArgs, action: Action, json: bool) -> Result<u8> {
+    let dry_run = DryRun::new();
+    let mut factory = factory(std::slice::from_ref(&control.targets))?;
+    if control.dry_run {
+        factory = factory.with_dry_run(dry_run.clone());
+    }
+
+    let miners = factory.scan().await?;
+    if miners.is_empty() {
+        eprintln!("no miners found");
+        return Ok(FAILURE);
+    }
+    let prompt = format!("{} {} miner(s)?", action.describe(), miners.len());
+    if !control.yes && !control.dry_run && !confirm(&prompt)? {
+        eprintln!("aborted");
+        return Ok(FAILURE);
+    }
+
+    let mut pending: FuturesUnordered<_> = miners
+        .iter()
+        .map(|miner| async move { (miner.get_ip(), action.run(miner.as_ref()).await) })
+        .collect();
+    let (mut succeeded, mut failed) = (0, 0);
+    while let Some((ip, outcome)) = pending.next().await {
+        let result = match outcome {
+            Ok(success) => ControlResult {
+                ip,
+                success,
+                error: None,
+            },
+            Err(e) => ControlResult {
+                ip,
+                success: false,
+                error: Some(format!("{e:#}")),
+            },
+        };
+        match result.success {
+            true => succeeded += 1,
+            false => failed += 1,
+        }
+        match json {
+            true => print_json(&result)?,
+            false => println!(
+                "{:<15} {}",
+                ip.to_string(),
+                match (&result.error, result.success) {
+                    (Some(error), _) => format!("failed: {error}"),
+                    (None, true) => "ok".to_string(),
+                    (None, false) => "failed".to_string(),
+                }
+            ),
+        }
+    }
+
+    if control.dry_run {
+        print_planned(&dry_run.take_actions(), json)?;
+    }
+    Ok(exit_code(succeeded, failed))
+}
+
+fn print_planned(actions: &[PlannedAction], json: bool) -> Result<()> {
+    if !json {
+        println!("\nplanned commands (not sent):");
+    }
+    for action in actions {
+        match json {
+            true => print_json(action)?,
+            false => println!(
+                "{:<15} {} {}",
+                action.ip.to_string(),
+                action.command,
+                action.payload
+            ),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_interval("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_interval("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_interval("45"), Ok(Duration::from_secs(45)));
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("soon").is_err());
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(exit_code(3, 0), SUCCESS);
+        assert_eq!(exit_code(2, 1), PARTIAL_FAILURE);
+        assert_eq!(exit_code(0, 3), FAILURE);
+        assert_eq!(exit_code(0, 0), FAILURE);
+    }
+
+    #[test]
+    fn test_targets() {
+        let targets = factory(&["10.0.0.1-4".to_string(), "10.0.1.0/30".to_string()]).unwrap();
+        assert_eq!(targets.len(), 6);
+
+        let path = std::env::temp_dir().join(format!("asic-rs-targets-{}", std::process::id()));
+        std::fs::write(&path, "# rack A\n10.0.0.1\n\n10.0.0.10-11\n").unwrap();
+        let from_file = factory(&[path.to_string_lossy().to_string()]);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(from_file.unwrap().len(), 3);
+
+        assert!(factory(&["not-an-ip".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_cli_arguments() {
+        let cli = Cli::try_parse_from([
+            "asic-rs",
+            "power-limit",
+            "10.0.0.1-10",
+            "3000",
+            "--dry-run",
+            "--json",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        match cli.command {
+            Command::PowerLimit {
+                control,
+                watts,
+                unchecked,
+            } => {
+                assert_eq!(control.targets, "10.0.0.1-10");
+                assert!(control.dry_run);
+                assert!(!control.yes);
+                assert_eq!(watts, 3000.0);
+                assert!(!unchecked);
+            }
+            other => panic!("parsed as {other:?}"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["asic-rs", "power-limit", "10.0.0.1", "5000", "--unchecked"])
+                .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::PowerLimit {
+                unchecked: true,
+                ..
+            }
+        ));
+
+        let cli = Cli::try_parse_from(["asic-rs", "led", "10.0.0.1", "on", "--yes"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Led {
+                state: LedState::On,
+                ..
+            }
+        ));
+
+        let cli = Cli::try_parse_from(["asic-rs", "watch", "10.0.0.0/24"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Watch { interval, .. } if interval == Duration::from_secs(30)
+        ));
+
+        assert!(Cli::try_parse_from(["asic-rs", "scan"]).is_err());
+        assert!(Cli::try_parse_from(["asic-rs", "data", "not-an-ip"]).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! Joining plain cgminer commands into one request, as `stats+summary+pools`.
+//!
+//! cgminer answers a joined command with an object holding the usual response of each command,
+//! wrapped in a one element list, under the command's name. Only commands without parameters
+//! can be joined, and some forks reject joined commands altogether.
+
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::OnceLock;
+
+use crate::miners::backends::policy::CommandPolicy;
+use crate::miners::commands::MinerCommand;
+
+/// Send the commands among `commands` that can be joined as one request, returning the response
+/// of each one sent.
+///
+/// `send` writes a request and returns the raw response, `parse` checks the status of the
+/// response to a single command. Whether the miner accepts joined commands is kept in
+/// `supported` after its first answer, once they are rejected nothing is joined again. A request
+/// that gets no answer tells nothing either way, its commands are left to be sent one at a time.
+pub(crate) async fn send_joined<F, Fut, P>(
+    commands: &[MinerCommand],
+    policy: &CommandPolicy,
+    supported: &OnceLock<bool>,
+    send: F,
+    parse: P,
+) -> HashMap<MinerCommand, Result<Value>>
+where
+    F: FnOnce(Value) -> Fut,
+    Fut: Future<Output = Result<String>>,
+    P: Fn(&str) -> Result<Value>,
+{
+    let names: Vec<&'static str> = commands
+        .iter()
+        .filter_map(|command| match command {
+            MinerCommand::RPC {
+                command,
+                parameters: None,
+            } if policy.check(command).is_ok() => Some(*command),
+            _ => None,
+        })
+        .collect();
+    if names.len() < 2 || supported.get() == Some(&false) {
+        return HashMap::new();
+    }
+
+    let Ok(response) = send(json!({ "command": names.join("+") })).await else {
+        return HashMap::new();
+    };
+    let parts = split(&response, &names);
+    let _ = supported.set(parts.is_some());
+
+    names
+        .into_iter()
+        .zip(parts.into_iter().flatten())
+        .map(|(command, part)| {
+            (
+                MinerCommand::RPC {
+                    command,
+                    parameters: None,
+                },
+                parse(&part.to_string()),
+            )
+        })
+        .collect()
+}
+
+/// The response to each of `names` in the response to their joined command, `None` unless all
+/// of them are there.
+fn split(response: &str, names: &[&str]) -> Option<Vec<Value>> {
+    let response: Value = serde_json::from_str(response).ok()?;
+    names
+        .iter()
+        .map(|name| response.get(*name)?.get(0).cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let response = json!({
+            "summary": [{"STATUS": [{"STATUS": "S"}], "SUMMARY": [{"Elapsed": 10}]}],
+            "pools": [{"STATUS": [{"STATUS": "S"}], "POOLS": []}],
+            "id": 1,
+        })
+        .to_string();
+        let parts = split(&response, &["summary", "pools"]).unwrap();
+        assert_eq!(parts[0]["SUMMARY"][0]["Elapsed"], 10);
+        assert_eq!(parts[1]["POOLS"], json!([]));
+
+        // forks without joined commands answer with a single error status
+        let rejected = json!({"STATUS": [{"STATUS": "E", "Msg": "Invalid command"}]}).to_string();
+        assert_eq!(split(&rejected, &["summary", "pools"]), None);
+    }
+}
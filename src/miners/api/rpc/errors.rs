@@ -6,6 +6,8 @@ pub enum RPCError {
     StatusCheckFailed(String),
     DeserializationFailed(serde_json::Error),
     ConnectionFailed,
+    ReadTimeout,
+    ResponseTooLarge(usize),
 }
 
 impl Display for RPCError {
@@ -20,6 +22,12 @@ impl Display for RPCError {
             RPCError::ConnectionFailed => {
                 write!(f, "Failed to connect to RPC API")
             }
+            RPCError::ReadTimeout => {
+                write!(f, "Timed out waiting for data from RPC API")
+            }
+            RPCError::ResponseTooLarge(limit) => {
+                write!(f, "RPC response exceeded the {limit} byte limit")
+            }
         }
     }
 }
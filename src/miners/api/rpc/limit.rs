@@ -0,0 +1,105 @@
+//! Limits on the connections an API client opens to a miner at once.
+//!
+//! Stock WhatsMiner firmware before btminer v3 and stock Avalon firmware answer only one API
+//! connection at a time and reset any other, so commands sent from several tasks at once lose
+//! responses at random. Their clients send one command at a time, other clients allow a few
+//! at once.
+
+use std::sync::Arc;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// The connections allowed at once by clients of firmware without a known limit.
+pub const DEFAULT_CONNECTIONS: usize = 4;
+
+/// How many connections to a miner's API may be open at once.
+///
+/// Clones share their permits, so a limit can cover several clients of the same miner.
+#[derive(Debug, Clone)]
+pub struct ConnectionLimit {
+    max: usize,
+    permits: Arc<Semaphore>,
+}
+
+impl ConnectionLimit {
+    /// Allow `max` connections at once, at least one.
+    pub fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            max,
+            permits: Arc::new(Semaphore::new(max)),
+        }
+    }
+
+    /// Allow one connection at a time.
+    pub fn serial() -> Self {
+        Self::new(1)
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Wait for a connection slot, held until the permit is dropped.
+    pub(crate) async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.permits.acquire().await.ok()
+    }
+
+    /// Wait until no other connection is open, and keep new ones from opening until the permit
+    /// is dropped.
+    pub(crate) async fn acquire_exclusive(&self) -> Option<SemaphorePermit<'_>> {
+        self.permits.acquire_many(self.max as u32).await.ok()
+    }
+}
+
+impl Default for ConnectionLimit {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONNECTIONS)
+    }
+}
+
+impl PartialEq for ConnectionLimit {
+    fn eq(&self, other: &Self) -> bool {
+        self.max == other.max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_serial_limit() {
+        let limit = ConnectionLimit::serial();
+        let held = limit.acquire().await;
+        assert!(held.is_some());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), limit.acquire())
+                .await
+                .is_err()
+        );
+        drop(held);
+        assert!(limit.acquire().await.is_some());
+        assert_eq!(ConnectionLimit::new(0), ConnectionLimit::serial());
+    }
+
+    #[tokio::test]
+    async fn test_exclusive_waits_for_open_connections() {
+        let limit = ConnectionLimit::default();
+        let first = limit.acquire().await;
+        let second = limit.acquire().await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), limit.acquire_exclusive())
+                .await
+                .is_err()
+        );
+        drop((first, second));
+        let exclusive = limit.acquire_exclusive().await;
+        assert!(exclusive.is_some());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), limit.acquire())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -1,2 +1,5 @@
+pub(crate) mod batch;
 pub mod errors;
+pub mod limit;
+pub mod reader;
 pub mod status;
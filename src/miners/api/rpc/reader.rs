@@ -0,0 +1,206 @@
+use crate::miners::api::rpc::errors::RPCError;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::time::timeout;
+
+/// Default cap on a single RPC response, comfortably above the largest `stats` replies.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 512 * 1024;
+/// Default time to wait for the next chunk of a response before giving up.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const READ_CHUNK_SIZE: usize = 8192;
+
+/// Limits applied while reading a response from a miner RPC socket.
+///
+/// Devices that are not miners (or crashed cgminer forks) may accept a connection and then stream
+/// garbage or stall without closing, so every read is bounded in both size and idle time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RPCReadLimits {
+    /// Maximum number of bytes accepted in a single response
+    pub max_response_size: usize,
+    /// Maximum time to wait without receiving any bytes
+    pub idle_timeout: Duration,
+}
+
+impl Default for RPCReadLimits {
+    fn default() -> Self {
+        Self {
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+}
+
+impl RPCReadLimits {
+    pub fn new(max_response_size: usize, idle_timeout: Duration) -> Self {
+        Self {
+            max_response_size,
+            idle_timeout,
+        }
+    }
+
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+}
+
+/// Read until the peer closes the connection.
+pub async fn read_to_end<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    limits: &RPCReadLimits,
+) -> Result<Vec<u8>, RPCError> {
+    read_until(reader, limits, |_| false).await
+}
+
+/// Read until the peer closes the connection or `done` reports the buffer holds a full response.
+pub async fn read_until<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    limits: &RPCReadLimits,
+    done: impl Fn(&[u8]) -> bool,
+) -> Result<Vec<u8>, RPCError> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; READ_CHUNK_SIZE];
+
+    loop {
+        let read = timeout(limits.idle_timeout, reader.read(&mut chunk))
+            .await
+            .map_err(|_| RPCError::ReadTimeout)??;
+        if read == 0 {
+            break;
+        }
+        if buffer.len() + read > limits.max_response_size {
+            return Err(RPCError::ResponseTooLarge(limits.max_response_size));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if done(&buffer) {
+            break;
+        }
+    }
+
+    Ok(buffer)
+}
+
+/// Read a response prefixed with its length as a little endian `u32`, as sent by btminer v3.
+pub async fn read_length_prefixed<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    limits: &RPCReadLimits,
+) -> Result<Vec<u8>, RPCError> {
+    let mut len_buf = [0u8; 4];
+    timeout(limits.idle_timeout, reader.read_exact(&mut len_buf))
+        .await
+        .map_err(|_| RPCError::ReadTimeout)??;
+
+    let response_len = u32::from_le_bytes(len_buf) as usize;
+    if response_len > limits.max_response_size {
+        return Err(RPCError::ResponseTooLarge(limits.max_response_size));
+    }
+
+    let mut buffer = vec![0u8; response_len];
+    let mut filled = 0;
+    while filled < response_len {
+        let read = timeout(limits.idle_timeout, reader.read(&mut buffer[filled..]))
+            .await
+            .map_err(|_| RPCError::ReadTimeout)??;
+        if read == 0 {
+            return Err(RPCError::ConnectionFailed);
+        }
+        filled += read;
+    }
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use std::time::Instant;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Serve a single connection with `respond` on an ephemeral loopback port.
+    async fn serve<F, Fut>(respond: F) -> SocketAddr
+    where
+        F: FnOnce(TcpStream) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            respond(stream).await;
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout_on_dribbling_peer() {
+        let addr = serve(|mut stream| async move {
+            // never finish the response, pausing longer than the idle timeout between bytes
+            while stream.write_all(b"{").await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        })
+        .await;
+
+        let limits = RPCReadLimits::default().with_idle_timeout(Duration::from_millis(100));
+        let started = Instant::now();
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let result = read_to_end(&mut stream, &limits).await;
+
+        assert!(matches!(result, Err(RPCError::ReadTimeout)));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_max_size_on_flooding_peer() {
+        let addr = serve(|mut stream| async move {
+            let _ = stream.write_all(&vec![0u8; 10 * 1024 * 1024]).await;
+        })
+        .await;
+
+        let limits = RPCReadLimits::default().with_max_response_size(256 * 1024);
+        let started = Instant::now();
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let result = read_to_end(&mut stream, &limits).await;
+
+        assert!(matches!(result, Err(RPCError::ResponseTooLarge(_))));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_length_prefix_over_limit() {
+        let addr = serve(|mut stream| async move {
+            let _ = stream.write_all(&u32::MAX.to_le_bytes()).await;
+        })
+        .await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let result = read_length_prefixed(&mut stream, &RPCReadLimits::default()).await;
+
+        assert!(matches!(result, Err(RPCError::ResponseTooLarge(_))));
+    }
+
+    #[tokio::test]
+    async fn test_read_until_terminator() {
+        let addr = serve(|mut stream| async move {
+            let _ = stream.write_all(b"{\"STATUS\":\"S\"}\0").await;
+            // keep the socket open, the terminator alone should end the read
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        })
+        .await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let response = read_until(&mut stream, &RPCReadLimits::default(), |b| b.contains(&0))
+            .await
+            .unwrap();
+
+        assert_eq!(response, b"{\"STATUS\":\"S\"}\0");
+    }
+}
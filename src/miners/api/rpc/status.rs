@@ -48,3 +48,12 @@ impl RPCCommandStatus {
         Ok(Self::from_str(status, message))
     }
 }
+
+/// The `When` of the STATUS header of a cgminer response, the miner's clock at the time it
+/// answered in seconds since the epoch.
+pub fn response_time(response: &Value) -> Option<u64> {
+    let status = response.get("STATUS")?;
+    // most forks send a list of one status, a few the status object itself
+    let status = status.get(0).unwrap_or(status);
+    status.get("When")?.as_u64()
+}
@@ -0,0 +1,697 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::data::board::{BoardData, ChipData};
+use crate::data::device::{
+    DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerMake, MinerModel,
+};
+use crate::data::fan::FanData;
+use crate::data::hashrate::{HashRate, HashRateUnit};
+use crate::data::health::HealthThresholds;
+use crate::data::miner::MinerStatus;
+use crate::data::pool::{PoolConfig, PoolData};
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::RPCReadLimits;
+use crate::miners::backends::antminer::v2020::AntMinerRPCAPI;
+use crate::miners::backends::antminer::{estimated_wattage, parse_pools, sensor_temp};
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
+use crate::miners::backends::traits::*;
+use crate::miners::commands::MinerCommand;
+use crate::miners::data::{
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData, FromValue,
+    get_by_pointer,
+};
+
+/// Chain slots the legacy firmware reports, only the ones with boards plugged in are populated.
+const CHAIN_SLOTS: u8 = 16;
+
+/// Stock firmware built before 2019, as shipped on the S9 and T9.
+///
+/// These releases only serve data over the cgminer RPC API, the web interface is CGI pages with
+/// no JSON equivalent of the newer endpoints. They report no power, so wattage is estimated.
+#[derive(Debug)]
+pub struct AntMinerLegacy {
+    ip: IpAddr,
+    rpc: AntMinerRPCAPI,
+    device_info: DeviceInfo,
+    labels: Option<HashMap<String, String>>,
+    health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
+}
+
+impl AntMinerLegacy {
+    pub fn new(ip: IpAddr, model: MinerModel) -> Self {
+        AntMinerLegacy {
+            ip,
+            rpc: AntMinerRPCAPI::new(ip),
+            device_info: DeviceInfo::new(
+                MinerMake::AntMiner,
+                model,
+                MinerFirmware::Stock,
+                HashAlgorithm::SHA256,
+            ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
+        }
+    }
+
+    /// Limit the connections the API client opens at once, by default
+    /// [`DEFAULT_CONNECTIONS`][`crate::miners::api::rpc::limit::DEFAULT_CONNECTIONS`].
+    pub fn with_connection_limit(mut self, limit: ConnectionLimit) -> Self {
+        self.rpc.connections = limit;
+        self
+    }
+}
+
+/// The chain slots with a board in them, in slot order.
+///
+/// Boards sit in slots 6 to 8 on an S9, so slots are mapped to positions from 0 in order.
+fn populated_chains(stats: &Value) -> Vec<u8> {
+    (1..=CHAIN_SLOTS)
+        .filter(|idx| {
+            stats
+                .get(format!("chain_acs{idx}"))
+                .and_then(|v| v.as_str())
+                .is_some_and(|acs| !acs.trim().is_empty())
+                || stats
+                    .get(format!("chain_acn{idx}"))
+                    .and_then(u64::from_value)
+                    .is_some_and(|acn| acn > 0)
+        })
+        .collect()
+}
+
+/// Chips from a `chain_acs` status string such as ` oooooooo ooxooooo`, where `o` is a working
+/// chip and `x` or `-` a failed one.
+fn parse_chain_acs(acs: &str) -> Vec<ChipData> {
+    acs.chars()
+        .filter(|c| !c.is_whitespace())
+        .enumerate()
+        .map(|(position, status)| ChipData {
+            position: position as u16,
+            working: Some(status == 'o'),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn ghs_to_ths(ghs: f64) -> HashRate {
+    HashRate {
+        value: ghs,
+        unit: HashRateUnit::GigaHash,
+        algo: String::from("SHA256"),
+    }
+    .as_unit(HashRateUnit::TeraHash)
+}
+
+#[async_trait]
+impl APIClient for AntMinerLegacy {
+    async fn get_api_result(&self, command: &MinerCommand) -> Result<Value> {
+        match command {
+            MinerCommand::RPC { .. } => self.rpc.get_api_result(command).await,
+            _ => Err(anyhow!("Unsupported command type for Antminer API")),
+        }
+    }
+
+    async fn get_api_results(
+        &self,
+        commands: &[MinerCommand],
+    ) -> HashMap<MinerCommand, Result<Value>> {
+        self.rpc.get_api_results(commands).await
+    }
+}
+
+impl GetDataLocations for AntMinerLegacy {
+    fn get_locations(&self, data_field: DataField) -> Vec<DataLocation> {
+        let version_cmd = MinerCommand::RPC {
+            command: "version",
+            parameters: None,
+        };
+
+        let stats_cmd = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+
+        let summary_cmd = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+
+        let pools_cmd = MinerCommand::RPC {
+            command: "pools",
+            parameters: None,
+        };
+
+        let pointer = |key: &'static str| DataExtractor {
+            func: get_by_pointer,
+            key: Some(key),
+            tag: None,
+        };
+
+        match data_field {
+            DataField::ApiVersion => vec![(version_cmd, pointer("/VERSION/0/API"))],
+            DataField::FirmwareVersion => vec![(version_cmd, pointer("/VERSION/0/CompileTime"))],
+            DataField::Hashrate => vec![(summary_cmd, pointer("/SUMMARY/0/GHS 5s"))],
+            DataField::AverageHashrate => vec![(summary_cmd, pointer("/SUMMARY/0/GHS av"))],
+            DataField::ExpectedHashrate => {
+                vec![(stats_cmd, pointer("/STATS/1/total_rateideal"))]
+            }
+            DataField::Hashboards | DataField::Fans | DataField::Wattage => {
+                vec![(stats_cmd, pointer("/STATS/1"))]
+            }
+            DataField::Uptime => vec![(stats_cmd, pointer("/STATS/1/Elapsed"))],
+            DataField::Pools => vec![(pools_cmd, pointer("/POOLS"))],
+            _ => vec![],
+        }
+    }
+}
+
+impl GetIP for AntMinerLegacy {
+    fn get_ip(&self) -> IpAddr {
+        self.ip
+    }
+}
+
+impl GetDeviceInfo for AntMinerLegacy {
+    fn get_device_info(&self) -> DeviceInfo {
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for AntMinerLegacy {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for AntMinerLegacy {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for AntMinerLegacy {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for AntMinerLegacy {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for AntMinerLegacy {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.rpc.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        self.rpc.policy = policy.for_miner(self);
+    }
+}
+
+impl GetCapabilities for AntMinerLegacy {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_restart: true,
+            has_chip_telemetry: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl CollectData for AntMinerLegacy {
+    fn get_collector(&self) -> DataCollector<'_> {
+        DataCollector::new(self)
+    }
+}
+
+impl GetMAC for AntMinerLegacy {}
+
+impl GetReportedIp for AntMinerLegacy {}
+
+impl GetSerialNumber for AntMinerLegacy {}
+
+impl GetHostname for AntMinerLegacy {}
+
+impl GetApiVersion for AntMinerLegacy {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
+        data.extract::<String>(DataField::ApiVersion)
+    }
+}
+
+impl GetFirmwareVersion for AntMinerLegacy {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
+        data.extract::<String>(DataField::FirmwareVersion)
+    }
+}
+
+impl GetControlBoardVersion for AntMinerLegacy {}
+
+impl GetHashboards for AntMinerLegacy {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
+        let Some(stats) = data.get(&DataField::Hashboards) else {
+            return vec![];
+        };
+
+        populated_chains(stats)
+            .into_iter()
+            .enumerate()
+            .map(|(position, idx)| {
+                let chips = stats
+                    .get(format!("chain_acs{idx}"))
+                    .and_then(|v| v.as_str())
+                    .map(parse_chain_acs)
+                    .unwrap_or_default();
+                // `chain_acn` counts the chips found, failed ones included
+                let working_chips = if chips.is_empty() {
+                    stats
+                        .get(format!("chain_acn{idx}"))
+                        .and_then(u64::from_value)
+                        .map(|acn| acn as u16)
+                } else {
+                    Some(chips.iter().filter(|c| c.working == Some(true)).count() as u16)
+                };
+                let hashrate = stats
+                    .get(format!("chain_rate{idx}"))
+                    .and_then(f64::from_value)
+                    .map(ghs_to_ths);
+                // `temp2_{n}` and `temp3_{n}` are the chip side sensors, unpopulated ones read 0
+                let sensor_temperatures = [format!("temp2_{idx}"), format!("temp3_{idx}")]
+                    .iter()
+                    .filter_map(|key| stats.get(key))
+                    .filter_map(f64::from_value)
+                    .filter_map(|t| sensor_temp(&self.plausibility_ranges, t))
+                    .map(Temperature::from_celsius)
+                    .collect();
+
+                let working = hashrate.as_ref().is_some_and(|h| h.value > 0.0)
+                    || working_chips.is_some_and(|chips| chips > 0);
+
+                BoardData {
+                    position: position as u8,
+                    hashrate,
+                    board_temperature: stats
+                        .get(format!("temp{idx}"))
+                        .and_then(f64::from_value)
+                        .and_then(|t| sensor_temp(&self.plausibility_ranges, t))
+                        .map(Temperature::from_celsius),
+                    expected_chips: self.device_info.hardware.chips,
+                    working_chips,
+                    chips,
+                    sensor_temperatures,
+                    frequency: stats
+                        .get(format!("freq{idx}"))
+                        .and_then(f64::from_value)
+                        .filter(|f| *f > 0.0)
+                        .map(Frequency::from_megahertz),
+                    tuned: Some(working),
+                    active: Some(working),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+impl GetHashrate for AntMinerLegacy {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::Hashrate, ghs_to_ths)
+    }
+}
+
+impl GetExpectedHashrate for AntMinerLegacy {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::ExpectedHashrate, ghs_to_ths)
+    }
+}
+
+impl GetAverageHashrate for AntMinerLegacy {
+    fn parse_average_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::AverageHashrate, ghs_to_ths)
+    }
+}
+
+impl GetFans for AntMinerLegacy {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
+        let Some(stats) = data.get(&DataField::Fans) else {
+            return vec![];
+        };
+
+        // the S9 reports its two fans in slots `fan3` and `fan6`, empty slots read 0
+        (1..=8)
+            .filter_map(|idx| stats.get(format!("fan{idx}")).and_then(f64::from_value))
+            .filter(|rpm| *rpm > 0.0)
+            .enumerate()
+            .map(|(position, rpm)| FanData {
+                position: position as i16,
+                rpm: Some(AngularVelocity::from_rpm(rpm)),
+            })
+            .collect()
+    }
+}
+
+impl GetPsuFans for AntMinerLegacy {}
+
+impl GetFanControl for AntMinerLegacy {}
+
+impl GetPsu for AntMinerLegacy {}
+
+impl GetPsuRails for AntMinerLegacy {}
+
+impl GetFluidTemperature for AntMinerLegacy {}
+
+impl GetFluidFlow for AntMinerLegacy {}
+
+impl GetFluidPressure for AntMinerLegacy {}
+
+impl GetWattage for AntMinerLegacy {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
+        estimated_wattage(&self.device_info.model, data.get(&DataField::Wattage)?)
+    }
+
+    fn parse_wattage_estimated(&self, data: &FieldData) -> bool {
+        data.get(&DataField::Wattage).is_some()
+    }
+}
+
+impl GetWattageLimit for AntMinerLegacy {}
+
+impl GetFrequency for AntMinerLegacy {}
+
+impl GetVoltage for AntMinerLegacy {}
+
+impl GetWorkMode for AntMinerLegacy {}
+impl GetAutotune for AntMinerLegacy {}
+
+impl GetLightFlashing for AntMinerLegacy {}
+
+impl GetMessages for AntMinerLegacy {}
+
+impl GetUptime for AntMinerLegacy {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
+        data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
+    }
+}
+
+impl GetIsMining for AntMinerLegacy {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
+    }
+}
+
+impl GetStatus for AntMinerLegacy {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        match data.extract::<f64>(DataField::Hashrate) {
+            Some(hr) if hr > 0.0 => MinerStatus::Mining,
+            Some(_) => MinerStatus::Failure,
+            None => MinerStatus::Unknown,
+        }
+    }
+}
+
+impl GetPools for AntMinerLegacy {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
+        data.get(&DataField::Pools)
+            .map(parse_pools)
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl SetFaultLight for AntMinerLegacy {
+    #[allow(unused_variables)]
+    async fn set_fault_light(&self, fault: bool) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the fault light".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPowerLimit for AntMinerLegacy {
+    #[allow(unused_variables)]
+    async fn set_power_limit(&self, limit: Power) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting a power limit".to_string(),
+        ))?
+    }
+}
+
+impl SetCredentials for AntMinerLegacy {}
+
+impl EndpointControl for AntMinerLegacy {
+    fn set_endpoints(&mut self, endpoints: MinerEndpoints) {
+        if let Some(rpc) = endpoints.rpc {
+            self.rpc.set_address(rpc);
+        }
+    }
+}
+
+impl SetRPCReadLimits for AntMinerLegacy {
+    fn set_rpc_read_limits(&mut self, limits: RPCReadLimits) {
+        self.rpc.set_read_limits(limits);
+    }
+}
+
+#[async_trait]
+impl SetPassword for AntMinerLegacy {
+    #[allow(unused_variables)]
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the password".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetHostname for AntMinerLegacy {
+    #[allow(unused_variables)]
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the hostname".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPools for AntMinerLegacy {
+    #[allow(unused_variables)]
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting pools".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for AntMinerLegacy {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl Restart for AntMinerLegacy {
+    async fn restart_mining(&self) -> Result<bool> {
+        execute_control(self, "restart", Value::Null, async {
+            accepted(self.rpc.restart().await)
+        })
+        .await
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "rebooting the system".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl Pause for AntMinerLegacy {
+    #[allow(unused_variables)]
+    async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation("pausing".to_string()))?
+    }
+}
+
+#[async_trait]
+impl Resume for AntMinerLegacy {
+    #[allow(unused_variables)]
+    async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation("resuming".to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::miners::backends::antminer::AntMiner;
+    use crate::test::api::{MockAPIClient, MockedMiner};
+    use crate::test::json::bmminer::antminer_legacy::{
+        AM_LEGACY_POOLS, AM_LEGACY_STATS_S9, AM_LEGACY_SUMMARY_S9, AM_LEGACY_VERSION,
+    };
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_antminer_legacy_s9() {
+        let miner = AntMinerLegacy::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S9),
+        );
+
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        let results = HashMap::from([
+            (rpc("stats"), Value::from_str(AM_LEGACY_STATS_S9).unwrap()),
+            (
+                rpc("summary"),
+                Value::from_str(AM_LEGACY_SUMMARY_S9).unwrap(),
+            ),
+            (rpc("pools"), Value::from_str(AM_LEGACY_POOLS).unwrap()),
+            (rpc("version"), Value::from_str(AM_LEGACY_VERSION).unwrap()),
+        ]);
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+        let miner_data = miner.parse_data(data);
+
+        // boards in slots 6 to 8, with failed chips marked `x`
+        assert_eq!(miner_data.hashboards.len(), 3);
+        let working: Vec<Option<u16>> = miner_data
+            .hashboards
+            .iter()
+            .map(|b| b.working_chips)
+            .collect();
+        assert_eq!(working, vec![Some(63), Some(61), Some(62)]);
+        let board = &miner_data.hashboards[1];
+        assert_eq!(board.position, 1);
+        assert_eq!(board.chips.len(), 63);
+        let failed: Vec<u16> = board
+            .chips
+            .iter()
+            .filter(|c| c.working == Some(false))
+            .map(|c| c.position)
+            .collect();
+        assert_eq!(failed, vec![12, 40]);
+        assert_eq!(
+            board.board_temperature,
+            Some(Temperature::from_celsius(65.0))
+        );
+        assert_eq!(
+            board.sensor_temperatures,
+            vec![Temperature::from_celsius(81.0)]
+        );
+        assert_eq!(board.frequency, Some(Frequency::from_megahertz(650.0)));
+
+        assert_eq!(miner_data.fans.len(), 2);
+        assert_eq!(
+            miner_data.fans[1].rpm,
+            Some(AngularVelocity::from_rpm(5760.0))
+        );
+        assert_eq!(miner_data.pools.len(), 2);
+        assert_eq!(miner_data.pools[0].active, Some(true));
+        assert_eq!(miner_data.uptime, Some(Duration::from_secs(93122)));
+        assert_eq!(miner_data.status, MinerStatus::Mining);
+        assert_eq!(
+            miner_data.firmware_version.as_deref(),
+            Some("Fri Jun  8 10:22:35 CST 2018")
+        );
+
+        // no power is reported, the S9's 1323 W is scaled by the hashrate
+        let wattage = miner_data.wattage.unwrap().as_watts();
+        assert!((wattage - 1323.0 * 13311.54 / 13504.32).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_antminer_legacy_status() {
+        let miner = AntMinerLegacy::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S9),
+        );
+        let summary = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+        let mut stopped = Value::from_str(AM_LEGACY_SUMMARY_S9).unwrap();
+        stopped["SUMMARY"][0]["GHS 5s"] = json!(0.0);
+
+        for (response, status) in [
+            (
+                Value::from_str(AM_LEGACY_SUMMARY_S9).unwrap(),
+                MinerStatus::Mining,
+            ),
+            (stopped, MinerStatus::Failure),
+        ] {
+            let mock_api = MockAPIClient::new(HashMap::from([(summary.clone(), response)]));
+            let mocked = MockedMiner::new(&miner, &mock_api);
+            assert_eq!(mocked.get_status().await, status);
+            assert_eq!(mocked.get_is_mining().await, status.is_mining());
+        }
+    }
+
+    #[test]
+    fn test_antminer_version_dispatch() {
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let model = MinerModel::AntMiner(AntMinerModel::S9);
+        let name = |version| {
+            let miner = AntMiner::new(ip, model.clone(), version);
+            format!("{miner:?}")
+                .split([' ', '{'])
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        };
+        assert_eq!(
+            name(Some(semver::Version::new(2018, 6, 8))),
+            "AntMinerLegacy"
+        );
+        assert_eq!(
+            name(Some(semver::Version::new(2022, 12, 6))),
+            "AntMinerV2020"
+        );
+        assert_eq!(name(None), "AntMinerV2020");
+    }
+}
@@ -1,14 +1,120 @@
+pub mod legacy;
 pub mod v2020;
 
+use measurements::Power;
+use serde_json::Value;
+use std::net::IpAddr;
+
+pub use legacy::AntMinerLegacy;
+pub use v2020::AntMinerV2020;
+
 use crate::data::device::MinerModel;
+use crate::data::pool::{PoolData, PoolURL};
+use crate::data::sanitize::PlausibilityRanges;
 use crate::miners::backends::traits::*;
-use std::net::IpAddr;
-use v2020::AntMinerV2020;
+use crate::miners::data::FromValue;
+
+/// Stock firmware built from this date serves the JSON web API, older firmware uses the legacy
+/// backend.
+pub(crate) const JSON_WEB_API_SINCE: semver::Version = semver::Version::new(2019, 0, 0);
+
+/// The version given to firmware without the JSON web API whose build date is unknown or later
+/// than [`JSON_WEB_API_SINCE`], which routes it to the legacy backend.
+pub(crate) const UNDATED_LEGACY_VERSION: semver::Version = semver::Version::new(0, 0, 0);
 
 pub struct AntMiner;
 
 impl AntMiner {
-    pub fn new(ip: IpAddr, model: MinerModel, _: Option<semver::Version>) -> Box<dyn Miner> {
-        Box::new(AntMinerV2020::new(ip, model))
+    /// All stock firmware from 2020 onward shares one backend, including the 2023+ releases with
+    /// the RPC API disabled, which are detected when the miner is first queried.
+    ///
+    /// Firmware built before 2019, as found on the S9 and T9, uses the legacy backend. Firmware
+    /// without the web API is identified over RPC, whose build date routes it the same way, and
+    /// firmware whose web interface lacks the JSON endpoints is dated before them by discovery.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(ip: IpAddr, model: MinerModel, version: Option<semver::Version>) -> Box<dyn Miner> {
+        match version {
+            Some(version) if version < JSON_WEB_API_SINCE => {
+                Box::new(AntMinerLegacy::new(ip, model))
+            }
+            _ => Box::new(AntMinerV2020::new(ip, model)),
+        }
     }
 }
+
+/// A temperature sensor reading, leaving out 0 for an unpopulated sensor and sentinels like 65535.
+fn sensor_temp(ranges: &PlausibilityRanges, temp: f64) -> Option<f64> {
+    (temp != 0.0 && ranges.temperature.contains(&temp)).then_some(temp)
+}
+
+/// Pools from the RPC `pools` command, or the web API copy of it.
+fn parse_pools(pools: &Value) -> Vec<PoolData> {
+    let Some(pools_array) = pools.as_array() else {
+        return vec![];
+    };
+
+    pools_array
+        .iter()
+        .enumerate()
+        .map(|(idx, pool_info)| {
+            // the web API uses the same fields as the RPC API, but in lowercase
+            let field = |key: &str| {
+                pool_info
+                    .get(key)
+                    .or_else(|| pool_info.get(key.to_lowercase()))
+            };
+
+            let url = field("URL")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .and_then(PoolURL::parse);
+
+            let user = field("User").and_then(|v| v.as_str()).map(String::from);
+
+            let alive = field("Status")
+                .and_then(|v| v.as_str())
+                .map(|s| s == "Alive");
+
+            let active = pool_info.get("Stratum Active").and_then(|v| v.as_bool());
+
+            let accepted_shares = field("Accepted").and_then(|v| v.as_u64());
+
+            let rejected_shares = field("Rejected").and_then(|v| v.as_u64());
+
+            // the web API abbreviates this one
+            let last_share_difficulty = field("Last Share Difficulty")
+                .or_else(|| pool_info.get("lsdiff"))
+                .and_then(|v| v.as_f64());
+
+            PoolData {
+                position: Some(idx as u16),
+                url,
+                accepted_shares,
+                rejected_shares,
+                active,
+                alive,
+                user,
+                password: None,
+                last_share_difficulty,
+                last_share_time: None,
+                group: None,
+                quota: None,
+            }
+        })
+        .collect()
+}
+
+/// The model's nominal wattage scaled by the actual over the ideal hashrate, for firmware that
+/// reports no power.
+fn estimated_wattage(model: &MinerModel, stats: &Value) -> Option<Power> {
+    let MinerModel::AntMiner(model) = model else {
+        return None;
+    };
+    let nominal = model.nominal_wattage()?;
+    let actual = stats.get("GHS 5s").and_then(f64::from_value)?;
+    let ideal = stats
+        .get("total_rateideal")
+        .and_then(f64::from_value)
+        .filter(|ideal| *ideal > 0.0)?;
+    Some(Power::from_watts(nominal.as_watts() * actual / ideal))
+}
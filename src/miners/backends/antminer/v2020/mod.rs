@@ -1,28 +1,42 @@
 use anyhow::{Result, anyhow, bail};
 use async_trait::async_trait;
 use macaddr::MacAddr;
-use measurements::{AngularVelocity, Frequency, Power, Temperature};
+use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::str::FromStr;
 use std::time::Duration;
+use tokio::sync::OnceCell;
 
-use crate::data::board::BoardData;
+use crate::data::board::{BoardData, ChipData};
 use crate::data::device::{
-    DeviceInfo, HashAlgorithm, MinerControlBoard, MinerFirmware, MinerMake, MinerModel,
+    ControlBoardType, DeviceInfo, HashAlgorithm, MinerControlBoard, MinerFirmware, MinerHardware,
+    MinerMake, MinerModel,
 };
-use crate::data::fan::FanData;
+use crate::data::fan::{FanControl, FanData, FanMode};
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::message::{MessageSeverity, MinerMessage};
-use crate::data::pool::{PoolData, PoolURL};
+use crate::data::health::HealthThresholds;
+use crate::data::message::{MessageSeverity, MinerMessage, MinerMessageKind};
+use crate::data::miner::{MinerStatus, WorkMode};
+use crate::data::pool::{PoolConfig, PoolData};
+use crate::data::psu::PsuRail;
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::RPCReadLimits;
+use crate::miners::backends::antminer::{estimated_wattage, parse_pools, sensor_temp};
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::{DryRun, REDACTED};
+use crate::miners::backends::errors::{ControlError, MinerError};
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_pointer,
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData, FromValue,
+    get_by_pointer,
 };
 
-use rpc::AntMinerRPCAPI;
+pub(crate) use rpc::AntMinerRPCAPI;
 use web::AntMinerWebAPI;
 
 mod rpc;
@@ -34,8 +48,18 @@ pub struct AntMinerV2020 {
     pub rpc: AntMinerRPCAPI,
     pub web: AntMinerWebAPI,
     pub device_info: DeviceInfo,
+    pub labels: Option<HashMap<String, String>>,
+    pub health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
+    /// Whether the cgminer RPC socket is enabled, firmware from late 2023 onward only serves
+    /// the web API
+    rpc_available: OnceCell<bool>,
 }
 
+const RPC_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[allow(dead_code)] // the power modes aren't set yet
 enum MinerMode {
     Sleep,
     Low,
@@ -43,13 +67,13 @@ enum MinerMode {
     High,
 }
 
-impl ToString for MinerMode {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for MinerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            MinerMode::Normal => "0".to_string(),
-            MinerMode::Sleep => "1".to_string(),
-            MinerMode::Low => "3".to_string(),
-            _ => "0".to_string(),
+            MinerMode::Normal => f.write_str("0"),
+            MinerMode::Sleep => f.write_str("1"),
+            MinerMode::Low => f.write_str("3"),
+            _ => f.write_str("0"),
         }
     }
 }
@@ -66,9 +90,21 @@ impl AntMinerV2020 {
                 MinerFirmware::Stock,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
+            rpc_available: OnceCell::new(),
         }
     }
 
+    /// Limit the connections the API client opens at once, by default
+    /// [`DEFAULT_CONNECTIONS`][`crate::miners::api::rpc::limit::DEFAULT_CONNECTIONS`].
+    pub fn with_connection_limit(mut self, limit: ConnectionLimit) -> Self {
+        self.rpc.connections = limit;
+        self
+    }
+
     pub fn with_auth(
         ip: IpAddr,
         model: MinerModel,
@@ -86,14 +122,81 @@ impl AntMinerV2020 {
                 firmware,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
+            rpc_available: OnceCell::new(),
+        }
+    }
+
+    /// Check whether the RPC API is reachable, probing the socket once and caching the result.
+    async fn rpc_available(&self) -> bool {
+        *self
+            .rpc_available
+            .get_or_init(|| self.rpc.is_reachable(RPC_PROBE_TIMEOUT))
+            .await
+    }
+
+    /// Pick the RPC or web locations for a field, depending on whether the RPC API is enabled.
+    ///
+    /// Before the RPC socket has been probed both are returned, the probe happens when the RPC
+    /// commands are sent so the extraction afterwards only uses the reachable API.
+    fn rpc_or_web(&self, rpc: DataLocation, web: DataLocation) -> Vec<DataLocation> {
+        match self.rpc_available.get() {
+            Some(true) => vec![rpc],
+            Some(false) => vec![web],
+            None => vec![rpc, web],
+        }
+    }
+
+    /// Locations only available over RPC, dropped once the RPC API is known to be disabled.
+    fn rpc_only(&self, rpc: DataLocation) -> Vec<DataLocation> {
+        match self.rpc_available.get() {
+            Some(false) => vec![],
+            _ => vec![rpc],
         }
     }
 
-    fn parse_temp_string(temp_str: &str) -> Option<Temperature> {
+    /// Hydro models are water cooled, so they have no fans and report coolant temperatures
+    /// alongside their board sensors.
+    fn is_hydro(&self) -> bool {
+        matches!(self.device_info.model, MinerModel::AntMiner(model) if model.is_hydro())
+    }
+
+    /// Coolant inlet and outlet temperatures, reported by hydro boards as the first and third
+    /// PCB sensors.
+    fn coolant_temps(&self, hashboards: &Value) -> Vec<f64> {
+        let pcb_temps: Vec<Vec<f64>> = match hashboards.as_array() {
+            Some(chains) => chains
+                .iter()
+                .filter_map(|chain| chain.get("temp_pcb").and_then(|v| v.as_array()))
+                .map(|temps| temps.iter().map(|t| t.as_f64().unwrap_or(0.0)).collect())
+                .collect(),
+            None => (1..=4)
+                .filter_map(|idx| {
+                    hashboards
+                        .get(format!("temp_pcb{idx}"))
+                        .and_then(|v| v.as_str())
+                })
+                .map(|temps| temps.split('-').map(|t| t.parse().unwrap_or(0.0)).collect())
+                .collect(),
+        };
+
+        pcb_temps
+            .iter()
+            .flat_map(|temps| [temps.first(), temps.get(2)])
+            .flatten()
+            .copied()
+            .filter_map(|t| sensor_temp(&self.plausibility_ranges, t))
+            .collect()
+    }
+
+    fn parse_temp_string(&self, temp_str: &str) -> Option<Temperature> {
         let temps: Vec<f64> = temp_str
             .split('-')
             .filter_map(|s| s.parse().ok())
-            .filter(|&temp| temp > 0.0)
+            .filter_map(|t| sensor_temp(&self.plausibility_ranges, t))
             .collect();
 
         if !temps.is_empty() {
@@ -104,13 +207,16 @@ impl AntMinerV2020 {
         }
     }
 
-    fn _calculate_average_temp_s21_hyd(chain: &Value) -> Option<Temperature> {
+    /// Board temperature of hydro boards, leaving out the coolant sensors.
+    fn calculate_average_temp_hydro(&self, chain: &Value) -> Option<Temperature> {
         let mut temps = Vec::new();
 
         if let Some(temp_pic) = chain.get("temp_pic").and_then(|v| v.as_array()) {
             for i in 1..=3 {
-                if let Some(temp) = temp_pic.get(i).and_then(|v| v.as_f64())
-                    && temp != 0.0
+                if let Some(temp) = temp_pic
+                    .get(i)
+                    .and_then(|v| v.as_f64())
+                    .and_then(|t| sensor_temp(&self.plausibility_ranges, t))
                 {
                     temps.push(temp);
                 }
@@ -118,13 +224,17 @@ impl AntMinerV2020 {
         }
 
         if let Some(temp_pcb) = chain.get("temp_pcb").and_then(|v| v.as_array()) {
-            if let Some(temp) = temp_pcb.get(1).and_then(|v| v.as_f64())
-                && temp != 0.0
+            if let Some(temp) = temp_pcb
+                .get(1)
+                .and_then(|v| v.as_f64())
+                .and_then(|t| sensor_temp(&self.plausibility_ranges, t))
             {
                 temps.push(temp);
             }
-            if let Some(temp) = temp_pcb.get(3).and_then(|v| v.as_f64())
-                && temp != 0.0
+            if let Some(temp) = temp_pcb
+                .get(3)
+                .and_then(|v| v.as_f64())
+                .and_then(|t| sensor_temp(&self.plausibility_ranges, t))
             {
                 temps.push(temp);
             }
@@ -138,12 +248,12 @@ impl AntMinerV2020 {
         }
     }
 
-    fn _calculate_average_temp_pcb(chain: &Value) -> Option<Temperature> {
+    fn calculate_average_temp_pcb(&self, chain: &Value) -> Option<Temperature> {
         if let Some(temp_pcb) = chain.get("temp_pcb").and_then(|v| v.as_array()) {
             let temps: Vec<f64> = temp_pcb
                 .iter()
                 .filter_map(|v| v.as_f64())
-                .filter(|&temp| temp != 0.0)
+                .filter_map(|t| sensor_temp(&self.plausibility_ranges, t))
                 .collect();
 
             if !temps.is_empty() {
@@ -157,12 +267,27 @@ impl AntMinerV2020 {
         }
     }
 
-    fn _calculate_average_temp_chip(chain: &Value) -> Option<Temperature> {
+    /// Chip data from a per-asic temperature array, ordered by chip position.
+    fn parse_asic_temps(&self, asic_temps: &[Value]) -> Vec<ChipData> {
+        asic_temps
+            .iter()
+            .enumerate()
+            .map(|(position, temp)| ChipData {
+                position: position as u16,
+                temperature: f64::from_value(temp)
+                    .and_then(|t| sensor_temp(&self.plausibility_ranges, t))
+                    .map(Temperature::from_celsius),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn _calculate_average_temp_chip(&self, chain: &Value) -> Option<Temperature> {
         if let Some(temp_chip) = chain.get("temp_chip").and_then(|v| v.as_array()) {
             let temps: Vec<f64> = temp_chip
                 .iter()
                 .filter_map(|v| v.as_f64())
-                .filter(|&temp| temp != 0.0)
+                .filter_map(|t| sensor_temp(&self.plausibility_ranges, t))
                 .collect();
 
             if !temps.is_empty() {
@@ -181,11 +306,41 @@ impl AntMinerV2020 {
 impl APIClient for AntMinerV2020 {
     async fn get_api_result(&self, command: &MinerCommand) -> Result<Value> {
         match command {
-            MinerCommand::RPC { .. } => self.rpc.get_api_result(command).await,
+            MinerCommand::RPC { .. } => {
+                if !self.rpc_available().await {
+                    bail!("RPC API is disabled on this miner");
+                }
+                self.rpc.get_api_result(command).await
+            }
             MinerCommand::WebAPI { .. } => self.web.get_api_result(command).await,
             _ => Err(anyhow!("Unsupported command type for Antminer API")),
         }
     }
+
+    async fn get_api_results(
+        &self,
+        commands: &[MinerCommand],
+    ) -> HashMap<MinerCommand, Result<Value>> {
+        if !self.rpc_available().await {
+            return HashMap::new();
+        }
+        self.rpc.get_api_results(commands).await
+    }
+
+    /// Miners converted to Braiins OS with the BOS toolbox keep the stock web UI, so they can be
+    /// identified as stock firmware, but their RPC `version` comes from BOSer or bosminer.
+    async fn verify_firmware(&self) -> Result<()> {
+        let Ok(Ok(version)) = tokio::time::timeout(RPC_PROBE_TIMEOUT, self.rpc.version()).await
+        else {
+            return Ok(());
+        };
+        let _ = self.rpc_available.set(true);
+        let version = version.to_string().to_uppercase();
+        if version.contains("BOSMINER") || version.contains("BOSER") {
+            bail!(MinerError::FirmwareMismatch(MinerFirmware::BraiinsOS));
+        }
+        Ok(())
+    }
 }
 
 impl GetDataLocations for AntMinerV2020 {
@@ -235,31 +390,64 @@ impl GetDataLocations for AntMinerV2020 {
             parameters: None,
         };
 
+        let web_stats_cmd = MinerCommand::WebAPI {
+            command: "stats",
+            parameters: None,
+        };
+
+        let web_pools_cmd = MinerCommand::WebAPI {
+            command: "pools",
+            parameters: None,
+        };
+
+        let network_info_cmd = MinerCommand::WebAPI {
+            command: "get_network_info",
+            parameters: None,
+        };
+
+        let chain_temp_cmd = MinerCommand::WebAPI {
+            command: "chain_temp",
+            parameters: None,
+        };
+
+        let pointer = |key: &'static str| DataExtractor {
+            func: get_by_pointer,
+            key: Some(key),
+            tag: None,
+        };
+
         match data_field {
-            DataField::Mac => vec![(
-                system_info_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/macaddr"),
-                    tag: None,
-                },
-            )],
-            DataField::ApiVersion => vec![(
-                version_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/VERSION/0/API"),
-                    tag: None,
-                },
-            )],
-            DataField::FirmwareVersion => vec![(
-                version_cmd,
+            DataField::Mac => vec![
+                (
+                    system_info_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/macaddr"),
+                        tag: Some("system_info"),
+                    },
+                ),
+                (
+                    network_info_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/macaddr"),
+                        tag: Some("network_info"),
+                    },
+                ),
+            ],
+            DataField::ReportedIp => vec![(
+                network_info_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/VERSION/0/CompileTime"),
+                    key: Some("/ipaddress"),
                     tag: None,
                 },
             )],
+            DataField::ApiVersion => self.rpc_only((version_cmd, pointer("/VERSION/0/API"))),
+            DataField::FirmwareVersion => self.rpc_or_web(
+                (version_cmd, pointer("/VERSION/0/CompileTime")),
+                (system_info_cmd, pointer("/system_filesystem_version")),
+            ),
             DataField::Hostname => vec![(
                 system_info_cmd,
                 DataExtractor {
@@ -268,46 +456,84 @@ impl GetDataLocations for AntMinerV2020 {
                     tag: None,
                 },
             )],
-            DataField::ControlBoardVersion => vec![(
-                web_miner_type_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/subtype"),
-                    tag: None,
-                },
-            )],
-            DataField::Hashrate => vec![(
-                summary_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/SUMMARY/0/GHS 5s"),
-                    tag: None,
-                },
-            )],
-            DataField::ExpectedHashrate => vec![(
-                stats_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/STATS/1/total_rateideal"),
-                    tag: None,
-                },
-            )],
-            DataField::Fans => vec![(
-                stats_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/STATS/1"),
-                    tag: None,
-                },
-            )],
-            DataField::Hashboards => vec![(
-                stats_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/STATS/1"),
-                    tag: None,
-                },
-            )],
+            DataField::ControlBoardVersion => vec![
+                (
+                    web_miner_type_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/subtype"),
+                        tag: Some("subtype"),
+                    },
+                ),
+                (
+                    system_info_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/system_kernel_version"),
+                        tag: Some("kernel"),
+                    },
+                ),
+            ],
+            DataField::Hashrate => self.rpc_or_web(
+                (summary_cmd, pointer("/SUMMARY/0/GHS 5s")),
+                (web_summary_cmd, pointer("/SUMMARY/0/rate_5s")),
+            ),
+            DataField::AverageHashrate => self.rpc_or_web(
+                (summary_cmd, pointer("/SUMMARY/0/GHS 30m")),
+                (web_summary_cmd, pointer("/SUMMARY/0/rate_30m")),
+            ),
+            DataField::ExpectedHashrate => self.rpc_or_web(
+                (stats_cmd, pointer("/STATS/1/total_rateideal")),
+                (web_stats_cmd, pointer("/STATS/0/rate_ideal")),
+            ),
+            DataField::Fans => self.rpc_or_web(
+                (stats_cmd, pointer("/STATS/1")),
+                (web_stats_cmd, pointer("/STATS/0/fan")),
+            ),
+            // tagged, the RPC stats object and web chain array would otherwise overwrite each other
+            DataField::Hashboards => {
+                let mut locations = self.rpc_or_web(
+                    (
+                        stats_cmd,
+                        DataExtractor {
+                            func: get_by_pointer,
+                            key: Some("/STATS/1"),
+                            tag: Some("stats"),
+                        },
+                    ),
+                    (
+                        web_stats_cmd,
+                        DataExtractor {
+                            func: get_by_pointer,
+                            key: Some("/STATS/0/chain"),
+                            tag: Some("chain"),
+                        },
+                    ),
+                );
+                // per-asic temperatures, only served by newer web firmware
+                locations.push((
+                    chain_temp_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/chain"),
+                        tag: Some("chain_temp"),
+                    },
+                ));
+                locations
+            }
+            DataField::FluidTemperature if self.is_hydro() => self.rpc_or_web(
+                (stats_cmd, pointer("/STATS/1")),
+                (web_stats_cmd, pointer("/STATS/0/chain")),
+            ),
+            // only reported by hydro models on some firmware
+            DataField::FluidFlow if self.is_hydro() => self.rpc_or_web(
+                (stats_cmd, pointer("/STATS/1/flow")),
+                (web_stats_cmd, pointer("/STATS/0/flow")),
+            ),
+            DataField::FluidPressure if self.is_hydro() => self.rpc_or_web(
+                (stats_cmd, pointer("/STATS/1/pressure")),
+                (web_stats_cmd, pointer("/STATS/0/pressure")),
+            ),
             DataField::LightFlashing => vec![(
                 blink_status_cmd,
                 DataExtractor {
@@ -324,30 +550,21 @@ impl GetDataLocations for AntMinerV2020 {
                     tag: None,
                 },
             )],
-            DataField::Uptime => vec![(
-                stats_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/STATS/1/Elapsed"),
-                    tag: None,
-                },
-            )],
-            DataField::Pools => vec![(
-                pools_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/POOLS"),
-                    tag: None,
-                },
-            )],
-            DataField::Wattage => vec![(
-                stats_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/STATS/1"),
-                    tag: None,
-                },
-            )],
+            DataField::Frequency => vec![(miner_conf_cmd, pointer("/bitmain-freq"))],
+            DataField::Voltage => vec![(miner_conf_cmd, pointer("/bitmain-voltage"))],
+            DataField::WorkMode => vec![(miner_conf_cmd, pointer("/bitmain-work-mode"))],
+            DataField::FanControl if !self.is_hydro() => vec![(miner_conf_cmd, pointer(""))],
+            DataField::Uptime => self.rpc_or_web(
+                (stats_cmd, pointer("/STATS/1/Elapsed")),
+                (web_stats_cmd, pointer("/STATS/0/elapsed")),
+            ),
+            DataField::Pools => self.rpc_or_web(
+                (pools_cmd, pointer("/POOLS")),
+                (web_pools_cmd, pointer("/POOLS")),
+            ),
+            DataField::Wattage => self.rpc_only((stats_cmd, pointer("/STATS/1"))),
+            // only newer firmware reports the PSU readings
+            DataField::PsuRails => vec![(web_stats_cmd, pointer("/STATS/0/power"))],
             DataField::SerialNumber => vec![(
                 system_info_cmd,
                 DataExtractor {
@@ -377,7 +594,71 @@ impl GetIP for AntMinerV2020 {
 
 impl GetDeviceInfo for AntMinerV2020 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for AntMinerV2020 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for AntMinerV2020 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for AntMinerV2020 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for AntMinerV2020 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for AntMinerV2020 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.rpc.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.rpc.policy = policy.clone();
+        self.web.policy = policy;
+    }
+}
+
+impl GetCapabilities for AntMinerV2020 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_fault_light: true,
+            supports_password: true,
+            supports_hostname: true,
+            supports_pool_update: true,
+            supports_restart: true,
+            supports_pause: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -388,32 +669,43 @@ impl CollectData for AntMinerV2020 {
 }
 
 impl GetMAC for AntMinerV2020 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
-        data.extract::<String>(DataField::Mac)
-            .and_then(|s| MacAddr::from_str(&s).ok())
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
+        // system info reports a placeholder during early boot, the network info is read directly
+        // from the interface
+        ["system_info", "network_info"].iter().find_map(|source| {
+            data.extract_nested::<String>(DataField::Mac, source)
+                .and_then(|s| parse_mac_str(&s))
+        })
+    }
+}
+
+impl GetReportedIp for AntMinerV2020 {
+    fn parse_reported_ip(&self, data: &FieldData) -> Option<IpAddr> {
+        data.extract::<String>(DataField::ReportedIp)
+            .and_then(|s| parse_ip_str(&s))
     }
 }
 
 impl GetHostname for AntMinerV2020 {
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::Hostname)
     }
 }
 
 impl GetApiVersion for AntMinerV2020 {
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::ApiVersion)
     }
 }
 
 impl GetFirmwareVersion for AntMinerV2020 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
 }
 
 impl GetHashboards for AntMinerV2020 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         let mut hashboards: Vec<BoardData> = Vec::new();
         let board_count = self.device_info.hardware.boards.unwrap_or(3);
 
@@ -429,14 +721,90 @@ impl GetHashboards for AntMinerV2020 {
                 working_chips: None,
                 serial_number: None,
                 chips: vec![],
+                sensor_temperatures: vec![],
                 voltage: None,
                 frequency: None,
+                wattage: None,
                 tuned: Some(false),
                 active: Some(false),
+                status: None,
+                extra: None,
             });
         }
 
-        if let Some(stats_data) = data.get(&DataField::Hashboards) {
+        let hashboards_data = data.get(&DataField::Hashboards);
+
+        // the web API reports a `chain` array, the RPC API flat `chain_*{n}` keys
+        if let Some(chains) = hashboards_data
+            .and_then(|v| v.get("chain"))
+            .and_then(|v| v.as_array())
+        {
+            for chain in chains {
+                let Some(board) = chain
+                    .get("index")
+                    .and_then(|v| v.as_u64())
+                    .and_then(|idx| hashboards.get_mut(idx as usize))
+                else {
+                    continue;
+                };
+                let ghs = |key: &str| {
+                    chain.get(key).and_then(f64::from_value).map(|f| {
+                        HashRate {
+                            value: f,
+                            unit: HashRateUnit::GigaHash,
+                            algo: String::from("SHA256"),
+                        }
+                        .as_unit(HashRateUnit::TeraHash)
+                    })
+                };
+
+                board.hashrate = ghs("rate_real");
+                board.expected_hashrate = ghs("rate_ideal");
+                board.working_chips = chain
+                    .get("asic_num")
+                    .and_then(u64::from_value)
+                    .map(|u| u as u16);
+                board.board_temperature = if self.is_hydro() {
+                    self.calculate_average_temp_hydro(chain)
+                } else {
+                    self.calculate_average_temp_pcb(chain)
+                };
+                // chip sensors are ordered from the intake side to the outlet side
+                let chip_temps: Vec<f64> = chain
+                    .get("temp_chip")
+                    .and_then(|v| v.as_array())
+                    .map(|temps| {
+                        temps
+                            .iter()
+                            .filter_map(|t| t.as_f64())
+                            .filter_map(|t| sensor_temp(&self.plausibility_ranges, t))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                board.intake_temperature =
+                    chip_temps.first().copied().map(Temperature::from_celsius);
+                board.outlet_temperature =
+                    chip_temps.last().copied().map(Temperature::from_celsius);
+                board.sensor_temperatures = chip_temps
+                    .into_iter()
+                    .map(Temperature::from_celsius)
+                    .collect();
+                board.frequency = chain
+                    .get("freq_avg")
+                    .and_then(f64::from_value)
+                    .map(Frequency::from_megahertz);
+                board.serial_number = chain
+                    .get("sn")
+                    .and_then(|v| v.as_str())
+                    .filter(|sn| !sn.is_empty())
+                    .map(String::from);
+
+                let working = board.hashrate.as_ref().is_some_and(|h| h.value > 0.0)
+                    || board.working_chips.is_some_and(|chips| chips > 0);
+                board.active = Some(working);
+                board.tuned = Some(working);
+            }
+        } else if let Some(stats_data) = hashboards_data.and_then(|v| v.get("stats")) {
             for idx in 1..=board_count {
                 let board_idx = (idx - 1) as usize;
                 if board_idx >= hashboards.len() {
@@ -445,8 +813,7 @@ impl GetHashboards for AntMinerV2020 {
 
                 if let Some(hashrate) = stats_data
                     .get(format!("chain_rate{}", idx))
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| s.parse::<f64>().ok())
+                    .and_then(f64::from_value)
                     .map(|f| {
                         HashRate {
                             value: f,
@@ -461,7 +828,7 @@ impl GetHashboards for AntMinerV2020 {
 
                 if let Some(working_chips) = stats_data
                     .get(format!("chain_acn{}", idx))
-                    .and_then(|v| v.as_u64())
+                    .and_then(u64::from_value)
                     .map(|u| u as u16)
                 {
                     hashboards[board_idx].working_chips = Some(working_chips);
@@ -470,14 +837,42 @@ impl GetHashboards for AntMinerV2020 {
                 if let Some(board_temp) = stats_data
                     .get(format!("temp_pcb{}", idx))
                     .and_then(|v| v.as_str())
-                    .and_then(Self::parse_temp_string)
+                    .and_then(|temps| {
+                        if self.is_hydro() {
+                            // leave out the coolant inlet and outlet, the first and third sensors
+                            let board_temps = temps.split('-').skip(1).step_by(2);
+                            self.parse_temp_string(&board_temps.collect::<Vec<_>>().join("-"))
+                        } else {
+                            self.parse_temp_string(temps)
+                        }
+                    })
                 {
                     hashboards[board_idx].board_temperature = Some(board_temp);
                 }
 
+                if let Some(temps) = stats_data
+                    .get(format!("temp_chip{}", idx))
+                    .and_then(|v| v.as_str())
+                {
+                    hashboards[board_idx].sensor_temperatures = temps
+                        .split('-')
+                        .filter_map(|t| t.parse().ok())
+                        .filter_map(|t| sensor_temp(&self.plausibility_ranges, t))
+                        .map(Temperature::from_celsius)
+                        .collect();
+                }
+
+                if let Some(wattage) = stats_data
+                    .get(format!("chain_consume{}", idx))
+                    .and_then(f64::from_value)
+                    .map(Power::from_watts)
+                {
+                    hashboards[board_idx].wattage = Some(wattage);
+                }
+
                 if let Some(frequency) = stats_data
                     .get(format!("freq{}", idx))
-                    .and_then(|v| v.as_u64())
+                    .and_then(u64::from_value)
                     .map(|f| Frequency::from_megahertz(f as f64))
                 {
                     hashboards[board_idx].frequency = Some(frequency);
@@ -498,12 +893,32 @@ impl GetHashboards for AntMinerV2020 {
             }
         }
 
+        // only firmware serving per-asic arrays gets chip level data, the 4 sensor
+        // `temp_chip` readings stay in `sensor_temperatures`
+        if let Some(chains) = hashboards_data
+            .and_then(|v| v.get("chain_temp"))
+            .and_then(|v| v.as_array())
+        {
+            for chain in chains {
+                let Some(board) = chain
+                    .get("index")
+                    .and_then(|v| v.as_u64())
+                    .and_then(|idx| hashboards.get_mut(idx as usize))
+                else {
+                    continue;
+                };
+                if let Some(asic_temps) = chain.get("asic_temp").and_then(|v| v.as_array()) {
+                    board.chips = self.parse_asic_temps(asic_temps);
+                }
+            }
+        }
+
         hashboards
     }
 }
 
 impl GetHashrate for AntMinerV2020 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::Hashrate, |f| {
             HashRate {
                 value: f,
@@ -516,7 +931,7 @@ impl GetHashrate for AntMinerV2020 {
 }
 
 impl GetExpectedHashrate for AntMinerV2020 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| {
             HashRate {
                 value: f,
@@ -528,14 +943,41 @@ impl GetExpectedHashrate for AntMinerV2020 {
     }
 }
 
+impl GetAverageHashrate for AntMinerV2020 {
+    fn parse_average_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::AverageHashrate, |f| {
+            HashRate {
+                value: f,
+                unit: HashRateUnit::GigaHash,
+                algo: String::from("SHA256"),
+            }
+            .as_unit(HashRateUnit::TeraHash)
+        })
+    }
+}
+
 impl GetFans for AntMinerV2020 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut fans: Vec<FanData> = Vec::new();
+        if self.is_hydro() {
+            return fans;
+        }
 
-        if let Some(stats_data) = data.get(&DataField::Fans) {
+        // the web API reports a `fan` array, the RPC API flat `fan{n}` keys
+        if let Some(speeds) = data.get(&DataField::Fans).and_then(|v| v.as_array()) {
+            for (i, fan_speed) in speeds.iter().filter_map(f64::from_value).enumerate() {
+                if fan_speed > 0.0 {
+                    fans.push(FanData {
+                        position: i as i16,
+                        rpm: Some(AngularVelocity::from_rpm(fan_speed)),
+                    });
+                }
+            }
+        } else if let Some(stats_data) = data.get(&DataField::Fans) {
             for i in 1..=self.device_info.hardware.fans.unwrap_or(4) {
-                if let Some(fan_speed) =
-                    stats_data.get(format!("fan{}", i)).and_then(|v| v.as_f64())
+                if let Some(fan_speed) = stats_data
+                    .get(format!("fan{}", i))
+                    .and_then(f64::from_value)
                     && fan_speed > 0.0
                 {
                     fans.push(FanData {
@@ -551,163 +993,256 @@ impl GetFans for AntMinerV2020 {
 }
 
 impl GetLightFlashing for AntMinerV2020 {
-    fn parse_light_flashing(&self, data: &HashMap<DataField, Value>) -> Option<bool> {
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
         data.extract::<bool>(DataField::LightFlashing).or_else(|| {
             data.extract::<String>(DataField::LightFlashing)
-                .map(|s| s.to_lowercase() == "true" || s == "1")
+                .and_then(|s| match s.to_lowercase().as_str() {
+                    "true" | "1" => Some(true),
+                    "false" | "0" => Some(false),
+                    _ => None,
+                })
         })
     }
 }
 
 impl GetUptime for AntMinerV2020 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
     }
 }
 
 impl GetIsMining for AntMinerV2020 {
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
-        data.extract::<String>(DataField::IsMining)
-            .map(|status| {
-                let status_lower = status.to_lowercase();
-                status_lower != "stopped" && status_lower != "idle" && status_lower != "sleep"
-            })
-            .or_else(|| data.extract::<f64>(DataField::Hashrate).map(|hr| hr > 0.0))
-            .unwrap_or(false)
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
     }
 }
 
-impl GetPools for AntMinerV2020 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
-        let mut pools: Vec<PoolData> = Vec::new();
-
-        if let Some(pools_data) = data.get(&DataField::Pools)
-            && let Some(pools_array) = pools_data.as_array()
-        {
-            for (idx, pool_info) in pools_array.iter().enumerate() {
-                let url = pool_info
-                    .get("URL")
-                    .and_then(|v| v.as_str())
-                    .map(|s| PoolURL::from(s.to_string()));
-
-                let user = pool_info
-                    .get("User")
-                    .and_then(|v| v.as_str())
-                    .map(String::from);
-
-                let alive = pool_info
-                    .get("Status")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s == "Alive");
-
-                let active = pool_info.get("Stratum Active").and_then(|v| v.as_bool());
-
-                let accepted_shares = pool_info.get("Accepted").and_then(|v| v.as_u64());
-
-                let rejected_shares = pool_info.get("Rejected").and_then(|v| v.as_u64());
-
-                pools.push(PoolData {
-                    position: Some(idx as u16),
-                    url,
-                    accepted_shares,
-                    rejected_shares,
-                    active,
-                    alive,
-                    user,
-                });
-            }
+impl GetStatus for AntMinerV2020 {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        // bitmain-work-mode: 0 -> normal, 1 -> sleep, 2 and 3 -> low power
+        let work_mode = match data.get(&DataField::IsMining) {
+            Some(Value::String(mode)) => Some(mode.to_lowercase()),
+            Some(Value::Number(mode)) => Some(mode.to_string()),
+            _ => None,
+        };
+        let hashrate = data.extract::<f64>(DataField::Hashrate);
+
+        match (work_mode.as_deref(), hashrate) {
+            (Some("1" | "sleep" | "stopped" | "idle"), _) => MinerStatus::Paused,
+            (_, Some(hr)) if hr > 0.0 => MinerStatus::Mining,
+            (_, Some(_)) => MinerStatus::Failure,
+            (Some(_), None) => MinerStatus::Mining,
+            (None, None) => MinerStatus::Unknown,
         }
+    }
+}
 
-        pools
+impl GetPools for AntMinerV2020 {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
+        data.get(&DataField::Pools)
+            .map(parse_pools)
+            .unwrap_or_default()
     }
 }
 
 impl GetSerialNumber for AntMinerV2020 {
-    fn parse_serial_number(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_serial_number(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::SerialNumber)
     }
 }
 
 impl GetControlBoardVersion for AntMinerV2020 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
-        data.extract::<String>(DataField::ControlBoardVersion)
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
+        data.extract_nested::<String>(DataField::ControlBoardVersion, "subtype")
             .and_then(|s| MinerControlBoard::from_str(s.split("_").collect::<Vec<&str>>()[0]).ok())
     }
+
+    fn parse_control_board_type(&self, data: &FieldData) -> Option<ControlBoardType> {
+        let subtype = data.extract_nested::<String>(DataField::ControlBoardVersion, "subtype");
+        // subtypes such as `CVCtrl_BHB56902` name the board, plain hashboard types like `BHB42601` don't
+        let from_subtype = subtype
+            .as_deref()
+            .and_then(|s| s.split('_').next())
+            .and_then(|s| s.parse::<ControlBoardType>().ok())
+            .filter(|t| !matches!(t, ControlBoardType::Unknown(_)));
+        from_subtype
+            .or_else(|| {
+                data.extract_nested::<String>(DataField::ControlBoardVersion, "kernel")
+                    .and_then(|kernel| platform_from_kernel(&kernel))
+            })
+            .or_else(|| subtype.map(ControlBoardType::Unknown))
+    }
 }
 
-impl GetWattage for AntMinerV2020 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
-        if let Some(stats_data) = data.get(&DataField::Wattage) {
-            if let Some(chain_power) = stats_data.get("chain_power")
-                && let Some(power_str) = chain_power.as_str()
-            {
-                // Parse "3250 W" format
-                if let Some(watt_part) = power_str.split_whitespace().next()
-                    && let Ok(watts) = watt_part.parse::<f64>()
-                {
-                    return Some(Power::from_watts(watts));
-                }
-            }
+/// The platform named in a kernel version, such as `Linux 4.6.0-xilinx-gff8137b-dirty`.
+fn platform_from_kernel(kernel: &str) -> Option<ControlBoardType> {
+    let kernel = kernel.to_lowercase();
+    ["xilinx", "amlogic", "cvitek", "beaglebone"]
+        .into_iter()
+        .find(|platform| kernel.contains(platform))
+        .and_then(|platform| platform.parse().ok())
+}
 
-            if let Some(power) = stats_data
-                .get("power")
-                .or_else(|| stats_data.get("Power"))
-                .and_then(|v| v.as_f64())
+impl AntMinerV2020 {
+    /// Power reported by the firmware, either as a total or as per chain consumption.
+    fn measured_wattage(&self, stats: &Value) -> Option<Power> {
+        if let Some(power_str) = stats.get("chain_power").and_then(|v| v.as_str()) {
+            // Parse "3250 W" format
+            if let Some(watt_part) = power_str.split_whitespace().next()
+                && let Ok(watts) = watt_part.parse::<f64>()
             {
-                return Some(Power::from_watts(power));
+                return Some(Power::from_watts(watts));
             }
         }
-        None
+
+        if let Some(power) = stats
+            .get("total_consume")
+            .or_else(|| stats.get("power"))
+            .or_else(|| stats.get("Power"))
+            .and_then(f64::from_value)
+        {
+            return Some(Power::from_watts(power));
+        }
+
+        // 2021 and later builds without a total still report `chain_consume{n}`
+        let board_count = self.device_info.hardware.boards.unwrap_or(3);
+        let chains: Vec<f64> = (1..=board_count)
+            .filter_map(|idx| stats.get(format!("chain_consume{idx}")))
+            .filter_map(f64::from_value)
+            .collect();
+        (!chains.is_empty()).then(|| Power::from_watts(chains.iter().sum()))
+    }
+}
+
+impl GetWattage for AntMinerV2020 {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
+        let stats = data.get(&DataField::Wattage)?;
+        self.measured_wattage(stats)
+            .or_else(|| estimated_wattage(&self.device_info.model, stats))
+    }
+
+    fn parse_wattage_estimated(&self, data: &FieldData) -> bool {
+        data.get(&DataField::Wattage)
+            .is_some_and(|stats| self.measured_wattage(stats).is_none())
     }
 }
 
 impl GetWattageLimit for AntMinerV2020 {}
 
-impl GetFluidTemperature for AntMinerV2020 {
-    fn parse_fluid_temperature(&self, data: &HashMap<DataField, Value>) -> Option<Temperature> {
-        // For S21+ Hyd models, use inlet/outlet temperature average
-        if self.device_info.model.to_string().contains("S21+ Hyd")
-            && let Some(hashboards_data) = data.get(&DataField::Hashboards)
-            && let Some(chains) = hashboards_data.as_array()
-        {
-            let mut temps = Vec::new();
+impl GetFrequency for AntMinerV2020 {
+    fn parse_frequency(&self, data: &FieldData) -> Option<Frequency> {
+        // per chain frequencies are comma separated, those are reported on the boards instead
+        data.extract::<f64>(DataField::Frequency)
+            .filter(|f| *f > 0.0)
+            .map(Frequency::from_megahertz)
+    }
+}
 
-            for chain in chains {
-                if let Some(temp_pcb) = chain.get("temp_pcb").and_then(|v| v.as_array()) {
-                    // Inlet temp (index 0) and outlet temp (index 2)
-                    if let Some(inlet) = temp_pcb.first().and_then(|v| v.as_f64())
-                        && inlet != 0.0
-                    {
-                        temps.push(inlet);
-                    }
-                    if let Some(outlet) = temp_pcb.get(2).and_then(|v| v.as_f64())
-                        && outlet != 0.0
-                    {
-                        temps.push(outlet);
-                    }
-                }
-            }
+impl GetVoltage for AntMinerV2020 {
+    fn parse_voltage(&self, data: &FieldData) -> Option<Voltage> {
+        // stored in hundredths of a volt, e.g. "1400" for 14.0 V
+        let voltage = data
+            .extract::<f64>(DataField::Voltage)
+            .filter(|v| *v > 0.0)?;
+        Some(Voltage::from_volts(match voltage > 100.0 {
+            true => voltage / 100.0,
+            false => voltage,
+        }))
+    }
+}
 
-            if !temps.is_empty() {
-                let avg = temps.iter().sum::<f64>() / temps.len() as f64;
-                return Some(Temperature::from_celsius(avg));
-            }
+impl GetWorkMode for AntMinerV2020 {
+    fn parse_work_mode(&self, data: &FieldData) -> Option<WorkMode> {
+        match data.extract::<u64>(DataField::WorkMode)? {
+            0 => Some(WorkMode::Normal),
+            1 => Some(WorkMode::Sleep),
+            2 | 3 => Some(WorkMode::LowPower),
+            _ => None,
         }
-        None
     }
 }
 
-impl GetPsuFans for AntMinerV2020 {}
-
-impl GetMessages for AntMinerV2020 {
-    fn parse_messages(&self, data: &HashMap<DataField, Value>) -> Vec<MinerMessage> {
-        let mut messages = Vec::new();
+impl GetAutotune for AntMinerV2020 {}
 
-        if let Some(status_data) = data.get(&DataField::Messages)
-            && let Some(status_array) = status_data.as_array()
+impl GetFluidTemperature for AntMinerV2020 {
+    fn parse_fluid_temperature(&self, data: &FieldData) -> Option<Temperature> {
+        if !self.is_hydro() {
+            return None;
+        }
+        // average of the coolant inlet and outlet temperatures across all boards
+        let temps = self.coolant_temps(data.get(&DataField::FluidTemperature)?);
+        if temps.is_empty() {
+            return None;
+        }
+        Some(Temperature::from_celsius(
+            temps.iter().sum::<f64>() / temps.len() as f64,
+        ))
+    }
+}
+
+impl GetFluidFlow for AntMinerV2020 {
+    fn parse_fluid_flow(&self, data: &FieldData) -> Option<f64> {
+        data.extract::<f64>(DataField::FluidFlow)
+    }
+}
+
+impl GetFluidPressure for AntMinerV2020 {
+    fn parse_fluid_pressure(&self, data: &FieldData) -> Option<f64> {
+        data.extract::<f64>(DataField::FluidPressure)
+    }
+}
+
+impl GetPsuFans for AntMinerV2020 {}
+
+impl GetFanControl for AntMinerV2020 {
+    /// Configured, from `bitmain-fan-ctrl` and `bitmain-fan-pwm` in the miner config.
+    fn parse_fan_control(&self, data: &FieldData) -> Option<FanControl> {
+        // the pwm stays in the config after switching back to auto, where it doesn't apply
+        match data.extract_nested::<bool>(DataField::FanControl, "bitmain-fan-ctrl")? {
+            true => Some(FanControl {
+                mode: FanMode::Manual,
+                target_pct: data
+                    .extract_nested::<u64>(DataField::FanControl, "bitmain-fan-pwm")
+                    .filter(|pct| *pct <= 100)
+                    .map(|pct| pct as u8),
+            }),
+            false => Some(FanControl {
+                mode: FanMode::Auto,
+                target_pct: None,
+            }),
+        }
+    }
+}
+
+impl GetPsu for AntMinerV2020 {}
+
+impl GetPsuRails for AntMinerV2020 {
+    fn parse_psu_rails(&self, data: &FieldData) -> Vec<PsuRail> {
+        let reading = |key| data.extract_nested::<f64>(DataField::PsuRails, key);
+        PsuRail::input(
+            reading("input_voltage"),
+            reading("input_current"),
+            reading("input_power"),
+        )
+        .into_iter()
+        .collect()
+    }
+}
+
+/// The kind of each `type` of check in the status list of the web summary.
+const STATUS_KINDS: [(&str, MinerMessageKind); 4] = [
+    ("fans", MinerMessageKind::FanFailure),
+    ("temp", MinerMessageKind::OverTemperature),
+    ("network", MinerMessageKind::NetworkIssue),
+    ("rate", MinerMessageKind::BoardFailure),
+];
+
+impl GetMessages for AntMinerV2020 {
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
+        let mut messages = Vec::new();
+
+        if let Some(status_data) = data.get(&DataField::Messages)
+            && let Some(status_array) = status_data.as_array()
         {
             for (idx, item) in status_array.iter().enumerate() {
                 if let Some(status) = item.get("status").and_then(|v| v.as_str())
@@ -726,11 +1261,28 @@ impl GetMessages for AntMinerV2020 {
                         _ => MessageSeverity::Info,
                     };
 
-                    messages.push(MinerMessage::new(0, idx as u64, message_text, severity));
+                    let kind = item
+                        .get("type")
+                        .and_then(|v| v.as_str())
+                        .and_then(|check| STATUS_KINDS.iter().find(|(name, _)| *name == check))
+                        .map(|(_, kind)| *kind)
+                        .unwrap_or_default();
+                    messages.push(
+                        MinerMessage::new(0, idx as u64, message_text, severity).with_kind(kind),
+                    );
                 }
             }
         }
 
+        if self.web.authentication_failed() {
+            messages.push(MinerMessage::new(
+                0,
+                0,
+                "web API authentication failed — web-only fields unavailable".to_string(),
+                MessageSeverity::Warning,
+            ));
+        }
+
         messages
     }
 }
@@ -739,7 +1291,11 @@ impl GetMessages for AntMinerV2020 {
 impl SetFaultLight for AntMinerV2020 {
     #[allow(unused_variables)]
     async fn set_fault_light(&self, fault: bool) -> Result<bool> {
-        Ok(self.web.blink(fault).await.is_ok())
+        let sent = execute_control(self, "blink", json!({"blink": fault.to_string()}), async {
+            accepted(self.web.blink(fault).await)
+        })
+        .await?;
+        Ok(confirm_fault_light(self, fault, sent).await)
     }
 }
 
@@ -747,14 +1303,114 @@ impl SetFaultLight for AntMinerV2020 {
 impl SetPowerLimit for AntMinerV2020 {
     #[allow(unused_variables)]
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting a power limit".to_string(),
+        ))?
+    }
+}
+
+impl SetCredentials for AntMinerV2020 {
+    fn set_credentials(&mut self, credentials: Vec<Credentials>) {
+        self.web.set_fallback_credentials(credentials);
+    }
+}
+
+impl EndpointControl for AntMinerV2020 {
+    fn set_endpoints(&mut self, endpoints: MinerEndpoints) {
+        if let Some(rpc) = endpoints.rpc {
+            self.rpc.set_address(rpc);
+        }
+        if let Some(web) = endpoints.web {
+            self.web.set_address(web);
+        }
+    }
+}
+
+#[async_trait]
+impl SetPassword for AntMinerV2020 {
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        web::validate_password(new)?;
+        let payload = web::password_payload(REDACTED, REDACTED);
+        execute_control(self, "passwd", payload, async {
+            accepted(self.web.set_password(old, new).await)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SetHostname for AntMinerV2020 {
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        validate_hostname(hostname)?;
+        // the whole network config is sent back, so the plan shows the addressing it keeps
+        let config = self.web.hostname_network_conf(hostname).await?;
+        let applied = execute_control(self, "set_network_conf", config.clone(), async {
+            accepted(self.web.send_network_conf(config).await)
+        })
+        .await?;
+        if !applied {
+            bail!("Failed to set hostname");
+        }
+        Ok(HostnameChange::Applied)
+    }
+}
+
+#[async_trait]
+impl SetPools for AntMinerV2020 {
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        validate_pool_count(&pools, 3)?;
+        // stock firmware always expects all three slots
+        let slots: Vec<Value> = (0..3)
+            .map(|idx| match pools.get(idx) {
+                Some(pool) => json!({
+                    "url": pool.url.to_string(),
+                    "user": pool.user,
+                    "pass": pool.password_or_default(),
+                }),
+                None => json!({"url": "", "user": "", "pass": ""}),
+            })
+            .collect();
+        execute_control(self, "set_miner_conf", json!({ "pools": pools }), async {
+            accepted(self.web.set_miner_conf(json!({ "pools": slots })).await)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for AntMinerV2020 {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
     }
 }
 
 #[async_trait]
 impl Restart for AntMinerV2020 {
-    async fn restart(&self) -> Result<bool> {
-        Ok(self.web.reboot().await.is_ok())
+    async fn restart_mining(&self) -> Result<bool> {
+        if !self.rpc_available().await {
+            Err(ControlError::UnsupportedOperation(
+                "cgminer RPC is disabled, the mining process can't be restarted alone".to_string(),
+            ))?
+        }
+        execute_control(self, "restart", Value::Null, async {
+            accepted(self.rpc.restart().await)
+        })
+        .await
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        execute_control(self, "reboot", Value::Null, async {
+            accepted(self.web.reboot().await)
+        })
+        .await
     }
 }
 
@@ -762,11 +1418,11 @@ impl Restart for AntMinerV2020 {
 impl Pause for AntMinerV2020 {
     #[allow(unused_variables)]
     async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
-        Ok(self
-            .web
-            .set_miner_conf(json!({"miner-mode": MinerMode::Sleep.to_string()}))
-            .await
-            .is_ok())
+        let conf = json!({"miner-mode": MinerMode::Sleep.to_string()});
+        execute_control(self, "set_miner_conf", conf.clone(), async {
+            accepted(self.web.set_miner_conf(conf).await)
+        })
+        .await
     }
 }
 
@@ -774,22 +1430,44 @@ impl Pause for AntMinerV2020 {
 impl Resume for AntMinerV2020 {
     #[allow(unused_variables)]
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
-        Ok(self
-            .web
-            .set_miner_conf(json!({"miner-mode": MinerMode::Normal.to_string()}))
-            .await
-            .is_ok())
+        let conf = json!({"miner-mode": MinerMode::Normal.to_string()});
+        execute_control(self, "set_miner_conf", conf.clone(), async {
+            accepted(self.web.set_miner_conf(conf).await)
+        })
+        .await
+    }
+}
+
+impl SetRPCReadLimits for AntMinerV2020 {
+    fn set_rpc_read_limits(&mut self, limits: RPCReadLimits) {
+        self.rpc.set_read_limits(limits);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::device::models::MinerModelFactory;
     use crate::data::device::models::antminer::AntMinerModel;
-    use crate::test::api::MockAPIClient;
+    use crate::data::miner::{MinerData, WattageLocation};
+    use crate::miners::backends::errors::MinerError;
+    use crate::test::api::{MockAPIClient, MockedMiner};
+    use crate::test::invariants::assert_hashrate_magnitudes;
     use crate::test::json::bmminer::antminer_modern::{
-        AM_DEVS, AM_POOLS, AM_STATS, AM_SUMMARY, AM_VERSION,
+        AM_DEVS, AM_POOLS, AM_STATS, AM_STATS_S9, AM_STATS_S19J_PRO, AM_STATS_S19XP_HYD,
+        AM_STATS_S21, AM_SUMMARY, AM_SUMMARY_S9, AM_VERSION,
     };
+    use crate::test::json::bmminer::antminer_web::{
+        AM_WEB_CHAIN_TEMP, AM_WEB_MINER_CONF_S19_LPM, AM_WEB_MINER_CONF_S19_MANUAL_FAN,
+        AM_WEB_POOLS, AM_WEB_STATS, AM_WEB_STATS_S21_HYD, AM_WEB_STATS_S21_PSU, AM_WEB_SUMMARY,
+        AM_WEB_SYSTEM_INFO,
+    };
+    use measurements::Current;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+    use strum::IntoEnumIterator;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
 
     #[tokio::test]
     async fn test_antminer() {
@@ -837,6 +1515,7 @@ mod tests {
         let data = collector.collect_all().await;
 
         let miner_data = miner.parse_data(data);
+        assert_hashrate_magnitudes(&miner_data);
 
         assert_eq!(miner_data.ip.to_string(), "127.0.0.1".to_owned());
         assert_eq!(miner_data.hashboards.len(), 3);
@@ -859,4 +1538,1100 @@ mod tests {
             }
         );
     }
+
+    #[tokio::test]
+    async fn test_antminer_warmup() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        let parse = async |uptime: u64| {
+            let mut stats = Value::from_str(AM_STATS).unwrap();
+            stats["STATS"][1]["Elapsed"] = json!(uptime);
+            // the third board hasn't come up
+            stats["STATS"][1]["chain_acn3"] = json!(0);
+            stats["STATS"][1]["chain_rate3"] = json!("0.00");
+            let mock_api = MockAPIClient::new(HashMap::from([
+                (rpc("stats"), stats),
+                (rpc("summary"), Value::from_str(AM_SUMMARY).unwrap()),
+                (rpc("pools"), Value::from_str(AM_POOLS).unwrap()),
+            ]));
+            let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+            miner.parse_data(collector.collect_all().await)
+        };
+        let errors = |data: &MinerData| {
+            data.messages
+                .iter()
+                .filter(|m| m.severity == MessageSeverity::Error)
+                .map(|m| m.message.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let warming_up = parse(120).await;
+        assert_eq!(warming_up.status, MinerStatus::Starting);
+        assert!(warming_up.is_mining);
+        assert_eq!(warming_up.hashboards[2].working_chips, Some(0));
+        assert!(errors(&warming_up).is_empty());
+
+        let warmed_up = parse(2 * 60 * 60).await;
+        assert_eq!(warmed_up.status, MinerStatus::Mining);
+        assert_eq!(errors(&warmed_up), vec!["Board 2 has no working chips"]);
+    }
+
+    #[tokio::test]
+    async fn test_antminer_web_only() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S21),
+        );
+        miner.rpc_available.set(false).unwrap();
+
+        for field in DataField::iter() {
+            assert!(
+                miner
+                    .get_locations(field)
+                    .iter()
+                    .all(|(cmd, _)| !matches!(cmd, MinerCommand::RPC { .. })),
+                "{field:?} still uses the RPC API"
+            );
+        }
+
+        let web = |command| MinerCommand::WebAPI {
+            command,
+            parameters: None,
+        };
+        let mut results = HashMap::new();
+        results.insert(web("stats"), Value::from_str(AM_WEB_STATS).unwrap());
+        results.insert(web("pools"), Value::from_str(AM_WEB_POOLS).unwrap());
+        results.insert(web("summary"), Value::from_str(AM_WEB_SUMMARY).unwrap());
+        results.insert(
+            web("get_system_info"),
+            Value::from_str(AM_WEB_SYSTEM_INFO).unwrap(),
+        );
+
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+
+        let miner_data = miner.parse_data(data);
+        assert_hashrate_magnitudes(&miner_data);
+
+        assert_eq!(
+            miner_data.hashrate.unwrap(),
+            HashRate {
+                value: 201.23456,
+                unit: HashRateUnit::TeraHash,
+                algo: "SHA256".to_string(),
+            }
+        );
+        assert_eq!(miner_data.expected_hashrate.unwrap().value, 200.0);
+        assert!(miner_data.average_hashrate.is_some());
+        assert_eq!(miner_data.uptime, Some(Duration::from_secs(86412)));
+        assert_eq!(miner_data.fans.len(), 4);
+        assert_eq!(
+            miner_data.firmware_version.as_deref(),
+            Some("Thu Jul 11 10:29:31 CST 2024")
+        );
+        assert_eq!(miner_data.status, MinerStatus::Mining);
+
+        assert_eq!(miner_data.hashboards.len(), 3);
+        let board = &miner_data.hashboards[0];
+        assert_eq!(board.working_chips, Some(108));
+        assert_eq!(board.serial_number.as_deref(), Some("HKYSS21ABCDE0001"));
+        assert_eq!(
+            board.board_temperature,
+            Some(Temperature::from_celsius(63.0))
+        );
+        assert_eq!(
+            board.intake_temperature,
+            Some(Temperature::from_celsius(61.0))
+        );
+        assert_eq!(board.active, Some(true));
+
+        assert_eq!(miner_data.pools.len(), 2);
+        assert_eq!(miner_data.pools[0].accepted_shares, Some(4521));
+        assert_eq!(miner_data.pools[0].last_share_difficulty, Some(65536.0));
+        assert_eq!(miner_data.pools[0].alive, Some(true));
+        assert_eq!(miner_data.pools[0].user.as_deref(), Some("asic_rs.s21"));
+    }
+
+    #[tokio::test]
+    async fn test_antminer_psu_input() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S21),
+        );
+        let web_stats = MinerCommand::WebAPI {
+            command: "stats",
+            parameters: None,
+        };
+
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            web_stats.clone(),
+            Value::from_str(AM_WEB_STATS_S21_PSU).unwrap(),
+        )]));
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+        let input = miner_data.psu_input().unwrap();
+        assert_eq!(input.voltage, Some(Voltage::from_volts(226.5)));
+        assert_eq!(input.current, Some(Current::from_amperes(15.3)));
+        assert_eq!(input.power, Some(Power::from_watts(3466.0)));
+
+        // older firmware has no PSU readings
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            web_stats,
+            Value::from_str(AM_WEB_STATS).unwrap(),
+        )]));
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+        assert!(miner_data.psu_rails.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_antminer_chip_temps() {
+        let web = |command| MinerCommand::WebAPI {
+            command,
+            parameters: None,
+        };
+        let collect = async |model, rpc_available, results| {
+            let miner = AntMinerV2020::new(IpAddr::from([127, 0, 0, 1]), model);
+            miner.rpc_available.set(rpc_available).unwrap();
+            let mock_api = MockAPIClient::new(results);
+            let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+            let data = collector.collect_all().await;
+            miner.parse_data(data)
+        };
+
+        // newer web firmware serves per-asic temperatures
+        let miner_data = collect(
+            MinerModel::AntMiner(AntMinerModel::S21),
+            false,
+            HashMap::from([
+                (web("stats"), Value::from_str(AM_WEB_STATS).unwrap()),
+                (
+                    web("chain_temp"),
+                    Value::from_str(AM_WEB_CHAIN_TEMP).unwrap(),
+                ),
+            ]),
+        )
+        .await;
+        for board in &miner_data.hashboards {
+            assert_eq!(board.working_chips, Some(108));
+            assert_eq!(board.chips.len(), 108);
+            assert_eq!(board.sensor_temperatures.len(), 4);
+        }
+        let chips = &miner_data.hashboards[0].chips;
+        assert_eq!(chips[0].position, 0);
+        assert_eq!(chips[0].temperature, Some(Temperature::from_celsius(60.0)));
+        assert_eq!(chips[2].temperature, Some(Temperature::from_celsius(74.0)));
+        assert_eq!(chips[107].position, 107);
+
+        // older firmware only reports the 4 chip sensors of each chain
+        let miner_data = collect(
+            MinerModel::AntMiner(AntMinerModel::S21),
+            false,
+            HashMap::from([(web("stats"), Value::from_str(AM_WEB_STATS).unwrap())]),
+        )
+        .await;
+        let board = &miner_data.hashboards[0];
+        assert_eq!(board.working_chips, Some(108));
+        assert!(board.chips.is_empty());
+        assert_eq!(
+            board.sensor_temperatures,
+            [61.0, 61.0, 75.0, 75.0].map(Temperature::from_celsius)
+        );
+
+        let rpc_stats = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+        let miner_data = collect(
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+            true,
+            HashMap::from([(rpc_stats, Value::from_str(AM_STATS).unwrap())]),
+        )
+        .await;
+        let board = &miner_data.hashboards[0];
+        assert_eq!(board.working_chips, Some(114));
+        assert!(board.chips.is_empty());
+        assert_eq!(
+            board.sensor_temperatures,
+            [78.0, 50.0, 69.0, 70.0].map(Temperature::from_celsius)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_antminer_unknown_model() {
+        let model = MinerModelFactory::new()
+            .with_make(MinerMake::AntMiner)
+            .parse_model("Antminer S99 Imaginary")
+            .unwrap();
+        assert_eq!(
+            model,
+            MinerModel::Unknown {
+                make: MinerMake::AntMiner,
+                raw: "Antminer S99 Imaginary".to_string(),
+            }
+        );
+
+        let miner = AntMinerV2020::new(IpAddr::from([127, 0, 0, 1]), model);
+
+        let mut results = HashMap::new();
+        results.insert(
+            MinerCommand::RPC {
+                command: "stats",
+                parameters: None,
+            },
+            Value::from_str(AM_STATS).unwrap(),
+        );
+        results.insert(
+            MinerCommand::RPC {
+                command: "summary",
+                parameters: None,
+            },
+            Value::from_str(AM_SUMMARY).unwrap(),
+        );
+
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+
+        let miner_data = miner.parse_data(data);
+
+        assert_eq!(miner_data.device_info.hardware.boards, None);
+        assert_eq!(miner_data.hashboards.len(), 3);
+        assert!(miner_data.hashrate.is_some());
+
+        let serialized = serde_json::to_value(&miner_data).unwrap();
+        assert_eq!(
+            serialized["device_info"]["model"]["raw"],
+            "Antminer S99 Imaginary"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_antminer_hardware_override() {
+        let mut miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        miner.set_hardware(MinerHardware {
+            fans: Some(0),
+            ..miner.get_device_info().hardware
+        });
+
+        let mut results = HashMap::new();
+        results.insert(
+            MinerCommand::RPC {
+                command: "stats",
+                parameters: None,
+            },
+            Value::from_str(AM_STATS).unwrap(),
+        );
+
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+
+        let miner_data = miner.parse_data(data);
+
+        assert_eq!(miner_data.expected_fans, Some(0));
+        assert!(miner_data.fans.is_empty());
+        assert_eq!(miner_data.expected_hashboards, Some(3));
+    }
+
+    /// Convert numbers sent as strings back to JSON numbers, as newer firmware reports them.
+    fn numeric(value: Value) -> Value {
+        match value {
+            Value::String(s) => match (s.parse::<u64>(), s.parse::<f64>()) {
+                (Ok(u), _) => json!(u),
+                (_, Ok(f)) if f.is_finite() => json!(f),
+                _ => Value::String(s),
+            },
+            Value::Array(values) => Value::Array(values.into_iter().map(numeric).collect()),
+            Value::Object(map) => {
+                Value::Object(map.into_iter().map(|(k, v)| (k, numeric(v))).collect())
+            }
+            other => other,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_antminer_s9_string_values() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S9),
+        );
+
+        let collect = |convert: fn(Value) -> Value| {
+            let mut results = HashMap::new();
+            results.insert(
+                MinerCommand::RPC {
+                    command: "stats",
+                    parameters: None,
+                },
+                convert(Value::from_str(AM_STATS_S9).unwrap()),
+            );
+            results.insert(
+                MinerCommand::RPC {
+                    command: "summary",
+                    parameters: None,
+                },
+                convert(Value::from_str(AM_SUMMARY_S9).unwrap()),
+            );
+            MockAPIClient::new(results)
+        };
+
+        let string_api = collect(|v| v);
+        let mut collector = DataCollector::new_with_client(&miner, &string_api);
+        let strings = miner.parse_data(collector.collect_all().await);
+
+        let numeric_api = collect(numeric);
+        let mut collector = DataCollector::new_with_client(&miner, &numeric_api);
+        let numbers = miner.parse_data(collector.collect_all().await);
+
+        let hashrate = strings.hashrate.clone().unwrap();
+        assert_eq!(hashrate.unit, HashRateUnit::TeraHash);
+        assert!((hashrate.value - 13.50123).abs() < 1e-9);
+        assert_eq!(strings.uptime, Some(Duration::from_secs(86417)));
+        assert_eq!(strings.fans.len(), 2);
+        assert_eq!(strings.fans[0].rpm, Some(AngularVelocity::from_rpm(5880.0)));
+        assert_eq!(strings.hashboards[0].working_chips, Some(63));
+        assert_eq!(
+            strings.hashboards[0].frequency,
+            Some(Frequency::from_megahertz(650.0))
+        );
+        assert!(strings.hashboards.iter().all(|b| b.hashrate.is_some()));
+
+        assert_eq!(strings.hashrate, numbers.hashrate);
+        assert_eq!(strings.expected_hashrate, numbers.expected_hashrate);
+        assert_eq!(strings.uptime, numbers.uptime);
+        assert_eq!(strings.fans, numbers.fans);
+        assert_eq!(strings.hashboards, numbers.hashboards);
+    }
+
+    #[tokio::test]
+    async fn test_antminer_s19xp_hydro() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19XPHydro),
+        );
+
+        let mut results = HashMap::new();
+        results.insert(
+            MinerCommand::RPC {
+                command: "stats",
+                parameters: None,
+            },
+            Value::from_str(AM_STATS_S19XP_HYD).unwrap(),
+        );
+
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+
+        let miner_data = miner.parse_data(data);
+
+        assert_eq!(miner_data.expected_fans, Some(0));
+        assert!(miner_data.fans.is_empty());
+        assert!(miner_data.messages.is_empty());
+        assert_eq!(
+            miner_data.fluid_temperature,
+            Some(Temperature::from_celsius(42.5))
+        );
+        assert_eq!(miner_data.fluid_flow, Some(9.6));
+        assert_eq!(miner_data.fluid_pressure, Some(2.1));
+
+        assert_eq!(miner_data.hashboards.len(), 3);
+        assert_eq!(
+            miner_data.hashboards[0].board_temperature,
+            Some(Temperature::from_celsius(61.0))
+        );
+        assert_eq!(
+            miner_data.hashboards[2].board_temperature,
+            Some(Temperature::from_celsius(63.0))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_antminer_stopped_fan() {
+        async fn parse(miner: &AntMinerV2020, stats: &Value, work_mode: &str) -> MinerData {
+            let web = |command| MinerCommand::WebAPI {
+                command,
+                parameters: None,
+            };
+            let mock_api = MockAPIClient::new(HashMap::from([
+                (web("stats"), stats.clone()),
+                (
+                    web("get_miner_conf"),
+                    json!({ "bitmain-work-mode": work_mode }),
+                ),
+            ]));
+            let mut collector = DataCollector::new_with_client(miner, &mock_api);
+            miner.parse_data(collector.collect_all().await)
+        }
+
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S21),
+        );
+        miner.rpc_available.set(false).unwrap();
+        let mut stats = Value::from_str(AM_WEB_STATS).unwrap();
+        stats["STATS"][0]["fan"][1] = json!(0);
+
+        let miner_data = parse(&miner, &stats, "0").await;
+        assert_eq!(miner_data.expected_fans, Some(4));
+        assert_eq!(miner_data.working_fans, Some(3));
+        let errors: Vec<_> = miner_data
+            .messages
+            .iter()
+            .filter(|m| m.severity == MessageSeverity::Error)
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "3 of 4 fans running, fan 1 stopped");
+        assert_eq!(errors[0].kind, MinerMessageKind::FanFailure);
+
+        // sleep mode stops the fans on purpose
+        let miner_data = parse(&miner, &stats, "1").await;
+        assert_eq!(miner_data.status, MinerStatus::Paused);
+        assert!(
+            miner_data
+                .messages
+                .iter()
+                .all(|m| m.severity != MessageSeverity::Error)
+        );
+    }
+
+    /// A cgminer socket answering from the fixtures, recording the command of every request.
+    ///
+    /// Joined commands are answered unless `reject_joined` is set, as on forks without them.
+    async fn serve_cgminer(reject_joined: bool) -> (SocketAddr, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let fixture = |command: &str| match command {
+            "version" => Value::from_str(AM_VERSION).unwrap(),
+            "stats" => Value::from_str(AM_STATS).unwrap(),
+            "summary" => Value::from_str(AM_SUMMARY).unwrap(),
+            "pools" => Value::from_str(AM_POOLS).unwrap(),
+            "devs" => Value::from_str(AM_DEVS).unwrap(),
+            _ => json!({"STATUS": [{"STATUS": "E", "Msg": "Invalid command"}]}),
+        };
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 1024];
+                let request = loop {
+                    match stream.read(&mut chunk).await {
+                        Ok(0) | Err(_) => break Value::Null,
+                        Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                    }
+                    if let Ok(request) = serde_json::from_slice::<Value>(&buffer) {
+                        break request;
+                    }
+                };
+                // the reachability probe connects without sending anything
+                let Some(command) = request["command"].as_str().map(String::from) else {
+                    continue;
+                };
+                let response = if !command.contains('+') {
+                    fixture(&command)
+                } else if reject_joined {
+                    fixture("")
+                } else {
+                    command
+                        .split('+')
+                        .map(|name| (name.to_string(), json!([fixture(name)])))
+                        .collect::<serde_json::Map<_, _>>()
+                        .into()
+                };
+                recorded.lock().unwrap().push(command);
+                let _ = stream.write_all(format!("{response}\0").as_bytes()).await;
+            }
+        });
+        (addr, requests)
+    }
+
+    #[tokio::test]
+    async fn test_antminer_joined_commands() {
+        // nothing listens on the web API port, everything comes over RPC
+        let closed = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let miner_at = |rpc| {
+            let mut miner = AntMinerV2020::new(
+                IpAddr::from([127, 0, 0, 1]),
+                MinerModel::AntMiner(AntMinerModel::S19jPro),
+            );
+            miner.set_endpoints(MinerEndpoints {
+                rpc: Some(rpc),
+                web: Some(closed),
+            });
+            miner
+        };
+
+        let (addr, requests) = serve_cgminer(false).await;
+        let miner = miner_at(addr);
+        let miner_data = miner.get_data().await;
+        let sent = requests.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        let mut joined: Vec<_> = sent[0].split('+').collect();
+        joined.sort();
+        assert_eq!(joined, vec!["pools", "stats", "summary", "version"]);
+        assert!(miner_data.hashrate.is_some());
+        assert!(!miner_data.hashboards.is_empty());
+        assert!(!miner_data.pools.is_empty());
+
+        // a rejected joined command is sent once, then each command on its own
+        let (addr, requests) = serve_cgminer(true).await;
+        let miner = miner_at(addr);
+        let joined = miner.get_data().await;
+        let sent = std::mem::take(&mut *requests.lock().unwrap());
+        assert_eq!(sent.len(), 5);
+        assert_eq!(sent[0].split('+').count(), 4);
+        assert!(sent[1..].iter().all(|command| !command.contains('+')));
+        assert!(joined.hashrate.is_some());
+        assert_eq!(joined.hashboards.len(), miner_data.hashboards.len());
+        assert_eq!(joined.pools, miner_data.pools);
+
+        miner.get_data().await;
+        assert_eq!(requests.lock().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_antminer_s21_hydro() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S21Hydro),
+        );
+        miner.rpc_available.set(false).unwrap();
+
+        let mut results = HashMap::new();
+        results.insert(
+            MinerCommand::WebAPI {
+                command: "stats",
+                parameters: None,
+            },
+            Value::from_str(AM_WEB_STATS_S21_HYD).unwrap(),
+        );
+
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+
+        let miner_data = miner.parse_data(data);
+        assert_hashrate_magnitudes(&miner_data);
+
+        assert_eq!(miner_data.expected_fans, Some(0));
+        assert!(miner_data.fans.is_empty());
+        assert_eq!(miner_data.working_fans, None);
+        assert!(miner_data.messages.is_empty());
+        assert_eq!(
+            miner_data.fluid_temperature,
+            Some(Temperature::from_celsius(45.0))
+        );
+        // this firmware does not report flow or pressure
+        assert_eq!(miner_data.fluid_flow, None);
+        assert_eq!(miner_data.fluid_pressure, None);
+
+        assert_eq!(miner_data.hashboards.len(), 3);
+        for board in &miner_data.hashboards {
+            assert_eq!(board.working_chips, Some(216));
+            assert_eq!(
+                board.board_temperature,
+                Some(Temperature::from_celsius(65.0))
+            );
+        }
+    }
+
+    #[test]
+    fn test_antminer_labels() {
+        let mut miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+
+        let unlabeled = serde_json::to_value(miner.parse_data(FieldData::new())).unwrap();
+        assert!(unlabeled.get("labels").is_none());
+
+        let labels = HashMap::from([("rack".to_string(), "A1".to_string())]);
+        miner.set_labels(Some(labels.clone()));
+        let miner_data = miner.parse_data(FieldData::new());
+        assert_eq!(miner_data.labels.as_ref(), Some(&labels));
+
+        let json = serde_json::to_string(&miner_data).unwrap();
+        let restored: MinerData = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, miner_data);
+    }
+
+    #[test]
+    fn test_antminer_mac_fallback() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+
+        let booting = FieldData::from([(
+            DataField::Mac,
+            json!({ "system_info": "00:00:00:00:00:00", "network_info": "02:11:22:33:44:55" }),
+        )]);
+        assert_eq!(
+            miner.parse_mac(&booting),
+            Some(MacAddr::from_str("02:11:22:33:44:55").unwrap())
+        );
+
+        let running = FieldData::from([(
+            DataField::Mac,
+            json!({ "system_info": "02:11:22:33:44:66", "network_info": "02:11:22:33:44:55" }),
+        )]);
+        assert_eq!(
+            miner.parse_mac(&running),
+            Some(MacAddr::from_str("02:11:22:33:44:66").unwrap())
+        );
+
+        let unknown = FieldData::from([(DataField::Mac, json!({ "system_info": "unknown" }))]);
+        assert_eq!(miner.parse_mac(&unknown), None);
+    }
+
+    #[test]
+    fn test_antminer_control_board_type() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let parse = |value| {
+            miner.parse_control_board_type(&FieldData::from([(
+                DataField::ControlBoardVersion,
+                value,
+            )]))
+        };
+
+        assert_eq!(
+            parse(json!({ "subtype": "CVCtrl_BHB56902", "kernel": "Linux 4.9.38 #1 SMP PREEMPT" })),
+            Some(ControlBoardType::CVITek)
+        );
+        assert_eq!(
+            parse(
+                json!({ "subtype": "BHB42601", "kernel": "Linux 4.6.0-xilinx-gff8137b-dirty #31 SMP PREEMPT" })
+            ),
+            Some(ControlBoardType::Xilinx)
+        );
+        assert_eq!(
+            parse(json!({ "subtype": "BHB42601", "kernel": "Linux 4.9.38 #1 SMP PREEMPT" })),
+            Some(ControlBoardType::Unknown("BHB42601".to_string()))
+        );
+        assert_eq!(parse(json!({})), None);
+    }
+
+    #[test]
+    fn test_antminer_status() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+
+        let sleeping = FieldData::from([
+            (DataField::IsMining, json!("1")),
+            (DataField::Hashrate, json!(0.0)),
+        ]);
+        assert_eq!(miner.parse_status(&sleeping), MinerStatus::Paused);
+        assert!(!miner.parse_is_mining(&sleeping));
+
+        let failed = FieldData::from([
+            (DataField::IsMining, json!("0")),
+            (DataField::Hashrate, json!(0.0)),
+        ]);
+        assert_eq!(miner.parse_status(&failed), MinerStatus::Failure);
+
+        let mining = FieldData::from([
+            (DataField::IsMining, json!("0")),
+            (DataField::Hashrate, json!(110566.89)),
+        ]);
+        assert_eq!(miner.parse_status(&mining), MinerStatus::Mining);
+    }
+
+    #[tokio::test]
+    async fn test_antminer_status_getters() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let mock_api = MockAPIClient::new(HashMap::from([
+            (
+                MinerCommand::RPC {
+                    command: "summary",
+                    parameters: None,
+                },
+                json!({"SUMMARY": [{"GHS 5s": 0.0}]}),
+            ),
+            (
+                MinerCommand::WebAPI {
+                    command: "get_miner_conf",
+                    parameters: None,
+                },
+                json!({"bitmain-work-mode": "0"}),
+            ),
+        ]));
+
+        // running in normal mode without hashing is a failure, whichever way it's read
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+        assert_eq!(miner_data.status, MinerStatus::Failure);
+        let mocked = MockedMiner::new(&miner, &mock_api);
+        assert_eq!(mocked.get_status().await, MinerStatus::Failure);
+        assert!(!mocked.get_is_mining().await);
+    }
+
+    #[tokio::test]
+    async fn test_antminer_set_password_dry_run() {
+        let mut miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        let error = miner
+            .set_password("root", "new password")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        assert!(dry_run.actions().is_empty());
+
+        assert!(miner.set_password("root", "new_Password1").await.unwrap());
+        let action = &dry_run.actions()[0];
+        assert_eq!(action.command, "passwd");
+        assert_eq!(
+            action.payload,
+            json!({"curPwd": REDACTED, "newPwd": REDACTED, "confirmPwd": REDACTED})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_antminer_restart_commands() {
+        let mut miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        miner.rpc_available.set(true).unwrap();
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        assert!(miner.restart_mining().await.unwrap());
+        assert!(miner.reboot_system().await.unwrap());
+        assert!(miner.restart().await.unwrap());
+
+        let commands: Vec<_> = dry_run.actions().into_iter().map(|a| a.command).collect();
+        assert_eq!(commands, vec!["restart", "reboot", "reboot"]);
+    }
+
+    #[tokio::test]
+    async fn test_antminer_wattage_sources() {
+        let collect = |model, stats: &str| {
+            let miner =
+                AntMinerV2020::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(model));
+            let stats = Value::from_str(stats).unwrap();
+            async move {
+                let mock_api = MockAPIClient::new(HashMap::from([(
+                    MinerCommand::RPC {
+                        command: "stats",
+                        parameters: None,
+                    },
+                    stats,
+                )]));
+                let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+                miner.parse_data(collector.collect_all().await)
+            }
+        };
+
+        // 2024 firmware reports the total, which includes fans and PSU losses
+        let s21 = collect(AntMinerModel::S21, AM_STATS_S21).await;
+        assert_eq!(s21.wattage, Some(Power::from_watts(3496.0)));
+        assert!(!s21.wattage_estimated);
+        let chain_wattage: Vec<_> = s21.hashboards.iter().map(|b| b.wattage).collect();
+        assert_eq!(
+            chain_wattage,
+            [1109.0, 1098.0, 1117.0].map(|w| Some(Power::from_watts(w)))
+        );
+
+        // 2021 firmware only reports the per chain consumption
+        let s19j_pro = collect(AntMinerModel::S19jPro, AM_STATS_S19J_PRO).await;
+        assert_eq!(s19j_pro.wattage, Some(Power::from_watts(3010.0)));
+        assert!(!s19j_pro.wattage_estimated);
+        assert_eq!(s19j_pro.wattage_location, Some(WattageLocation::Wall));
+        assert_eq!(
+            s19j_pro.hashboards[0].wattage,
+            Some(Power::from_watts(1002.0))
+        );
+
+        // older firmware reports no power at all, scale the nominal wattage by the hashrate
+        let s9 = collect(AntMinerModel::S9, AM_STATS_S9).await;
+        let watts = s9.wattage.unwrap().as_watts();
+        assert!((watts - 1323.0 * 13501.23 / 13500.0).abs() < 1e-6);
+        assert!(s9.wattage_estimated);
+        assert_eq!(s9.wattage_location, Some(WattageLocation::Estimated));
+        assert!(s9.hashboards.iter().all(|b| b.wattage.is_none()));
+    }
+
+    #[test]
+    fn test_antminer_sentinel_values() {
+        let mut miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let data = FieldData::from([
+            (
+                DataField::Hashboards,
+                json!({"chain": [
+                    {"index": 0, "temp_pcb": [60, 65535, 62, 0], "temp_chip": [70, 65535]},
+                    {"index": 1, "temp_pcb": [65535, 65535, 65535, 65535]},
+                    {"index": 2, "temp_pcb": [64, 64, 66, 66]},
+                ]}),
+            ),
+            (DataField::Fans, json!({"fan1": -1, "fan2": 6000})),
+            (DataField::Wattage, json!({"Power": 0})),
+        ]);
+
+        let miner_data = miner.parse_data(data.clone());
+
+        assert_eq!(
+            miner_data.hashboards[0].board_temperature,
+            Some(Temperature::from_celsius(61.0))
+        );
+        assert_eq!(
+            miner_data.hashboards[0].outlet_temperature,
+            Some(Temperature::from_celsius(70.0))
+        );
+        assert_eq!(miner_data.hashboards[1].board_temperature, None);
+        assert_eq!(
+            miner_data.average_temperature,
+            Some(Temperature::from_celsius(63.0))
+        );
+        assert_eq!(miner_data.fans.len(), 1);
+        assert_eq!(miner_data.fans[0].position, 1);
+        assert_eq!(miner_data.wattage, None);
+        // the ranges of the miner apply to its sensor readings as well
+        miner.set_plausibility_ranges(PlausibilityRanges {
+            temperature: -40.0..=70_000.0,
+            ..Default::default()
+        });
+        assert_eq!(
+            miner.parse_data(data).hashboards[1].board_temperature,
+            Some(Temperature::from_celsius(65535.0))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_antminer_read_only_policy() {
+        let mut miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        miner.set_command_policy(CommandPolicy::read_only());
+        let policy = miner.get_command_policy();
+
+        let mut results = HashMap::new();
+        for (command, response) in [
+            ("stats", AM_STATS),
+            ("version", AM_VERSION),
+            ("summary", AM_SUMMARY),
+            ("devs", AM_DEVS),
+            ("pools", AM_POOLS),
+        ] {
+            results.insert(
+                MinerCommand::RPC {
+                    command,
+                    parameters: None,
+                },
+                Value::from_str(response).unwrap(),
+            );
+        }
+        let mock_api = MockAPIClient::new(results).with_policy(policy);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+        assert!(collector.stats().policy_skipped.is_empty());
+        let miner_data = miner.parse_data(data);
+        assert_eq!(miner_data.hashboards.len(), 3);
+        assert_eq!(miner_data.fans.len(), 4);
+
+        // control commands fail before anything is sent
+        let denied = |result: Result<bool>| result.unwrap_err().downcast::<MinerError>().unwrap();
+        assert_eq!(
+            denied(miner.reboot_system().await),
+            MinerError::PolicyDenied("reboot".to_string())
+        );
+        assert_eq!(
+            denied(miner.pause(None).await),
+            MinerError::PolicyDenied("set_miner_conf".to_string())
+        );
+        assert!(
+            miner
+                .set_power_limit(Power::from_watts(3000.0))
+                .await
+                .is_err()
+        );
+
+        // a dry run is denied the same, nothing is planned
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+        assert_eq!(
+            denied(miner.reboot_system().await),
+            MinerError::PolicyDenied("reboot".to_string())
+        );
+        assert_eq!(
+            denied(miner.pause(None).await),
+            MinerError::PolicyDenied("set_miner_conf".to_string())
+        );
+        assert!(dry_run.actions().is_empty());
+
+        miner.set_command_policy(CommandPolicy::allow_all().deny(["reboot"]));
+        assert_eq!(
+            denied(miner.reboot_system().await),
+            MinerError::PolicyDenied("reboot".to_string())
+        );
+        assert!(miner.set_fault_light(true).await.unwrap());
+        let commands: Vec<_> = dry_run.actions().into_iter().map(|a| a.command).collect();
+        assert_eq!(commands, vec!["blink"]);
+    }
+
+    #[tokio::test]
+    async fn test_antminer_miner_conf_low_power() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19),
+        );
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            MinerCommand::WebAPI {
+                command: "get_miner_conf",
+                parameters: None,
+            },
+            Value::from_str(AM_WEB_MINER_CONF_S19_LPM).unwrap(),
+        )]));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector
+            .collect(&[
+                DataField::Frequency,
+                DataField::Voltage,
+                DataField::WorkMode,
+            ])
+            .await;
+
+        assert_eq!(
+            miner.parse_frequency(&data),
+            Some(Frequency::from_megahertz(525.0))
+        );
+        assert_eq!(miner.parse_voltage(&data), Some(Voltage::from_volts(13.2)));
+        assert_eq!(miner.parse_work_mode(&data), Some(WorkMode::LowPower));
+
+        let miner_data = miner.parse_data(data);
+        assert_eq!(miner_data.work_mode, Some(WorkMode::LowPower));
+        assert_eq!(miner_data.frequency, Some(Frequency::from_megahertz(525.0)));
+
+        // per chain frequencies are left to the boards
+        let per_chain = FieldData::from([(DataField::Frequency, json!("525,530,525"))]);
+        assert_eq!(miner.parse_frequency(&per_chain), None);
+    }
+
+    #[tokio::test]
+    async fn test_antminer_fan_control() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19),
+        );
+        let conf = |fixture: &str| {
+            MockAPIClient::new(HashMap::from([(
+                MinerCommand::WebAPI {
+                    command: "get_miner_conf",
+                    parameters: None,
+                },
+                Value::from_str(fixture).unwrap(),
+            )]))
+        };
+
+        let mock_api = conf(AM_WEB_MINER_CONF_S19_MANUAL_FAN);
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect(&[DataField::FanControl]).await;
+        assert_eq!(
+            miner.parse_data(data).fan_control,
+            Some(FanControl {
+                mode: FanMode::Manual,
+                target_pct: Some(80),
+            })
+        );
+
+        // the pwm of an earlier manual setting is left in the config
+        let mock_api = conf(AM_WEB_MINER_CONF_S19_LPM);
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect(&[DataField::FanControl]).await;
+        assert_eq!(
+            miner.parse_fan_control(&data),
+            Some(FanControl {
+                mode: FanMode::Auto,
+                target_pct: None,
+            })
+        );
+
+        let hydro = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S21Hydro),
+        );
+        assert!(hydro.get_locations(DataField::FanControl).is_empty());
+    }
+
+    #[test]
+    fn test_antminer_message_kinds() {
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19),
+        );
+        let mut summary = Value::from_str(AM_WEB_SUMMARY).unwrap();
+        let status = summary.pointer_mut("/SUMMARY/0/status").unwrap();
+        status[2]["status"] = json!("e");
+        status[2]["msg"] = json!("Fan 2 lost");
+        status[3]["status"] = json!("w");
+        status[3]["msg"] = json!("temperature is too high");
+        let data = FieldData::from([(DataField::Messages, status.clone())]);
+
+        let messages = miner.parse_messages(&data);
+        let kinds: Vec<_> = messages
+            .iter()
+            .map(|m| (m.message.as_str(), m.severity.clone(), m.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    "Fan 2 lost",
+                    MessageSeverity::Error,
+                    MinerMessageKind::FanFailure
+                ),
+                (
+                    "temperature is too high",
+                    MessageSeverity::Warning,
+                    MinerMessageKind::OverTemperature
+                ),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&messages[0]).unwrap()["kind"],
+            "fan_failure"
+        );
+    }
 }
@@ -1,11 +1,18 @@
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use serde_json::{Value, json};
-use std::net::IpAddr;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
 
+use crate::miners::api::rpc::batch;
 use crate::miners::api::rpc::errors::RPCError;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::{self, RPCReadLimits};
 use crate::miners::api::rpc::status::RPCCommandStatus;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 
@@ -13,11 +20,34 @@ use crate::miners::commands::MinerCommand;
 pub struct AntMinerRPCAPI {
     ip: IpAddr,
     port: u16,
+    pub(crate) policy: CommandPolicy,
+    pub(crate) connections: ConnectionLimit,
+    read_limits: RPCReadLimits,
+    /// Whether the miner accepts joined commands, once it has answered one
+    joined_commands: OnceLock<bool>,
 }
 
 impl AntMinerRPCAPI {
     pub fn new(ip: IpAddr) -> Self {
-        Self { ip, port: 4028 }
+        Self {
+            ip,
+            port: 4028,
+            policy: CommandPolicy::default(),
+            connections: ConnectionLimit::default(),
+            read_limits: RPCReadLimits::default(),
+            joined_commands: OnceLock::new(),
+        }
+    }
+
+    /// Bound the size of and idle time between reads of the miner's responses.
+    pub fn set_read_limits(&mut self, limits: RPCReadLimits) {
+        self.read_limits = limits;
+    }
+
+    /// Connect to `addr` instead of the miner's IP on the default port.
+    pub fn set_address(&mut self, addr: SocketAddr) {
+        self.ip = addr.ip();
+        self.port = addr.port();
     }
 
     async fn send_rpc_command(
@@ -26,10 +56,7 @@ impl AntMinerRPCAPI {
         _privileged: bool,
         parameters: Option<Value>,
     ) -> Result<Value> {
-        let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
-            .await
-            .map_err(|_| RPCError::ConnectionFailed)?;
-
+        self.policy.check(command)?;
         let request = if let Some(params) = parameters {
             json!({
                 "command": command,
@@ -41,30 +68,45 @@ impl AntMinerRPCAPI {
             })
         };
 
+        let response = self.send_request(request).await?;
+        self.parse_rpc_result(&response)
+    }
+
+    /// Write `request` to the cgminer socket and read back the raw response.
+    async fn send_request(&self, request: Value) -> Result<String> {
+        let _connection = self.connections.acquire().await;
+        let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
+            .await
+            .map_err(|_| RPCError::ConnectionFailed)?;
+
         let json_str = request.to_string();
         let message = format!("{}\n", json_str);
 
         stream.write_all(message.as_bytes()).await?;
 
-        let mut response = String::new();
-        let mut buffer = [0; 8192];
-
-        loop {
-            let bytes_read = stream.read(&mut buffer).await?;
-            if bytes_read == 0 {
-                break;
-            }
-
-            let chunk = String::from_utf8_lossy(&buffer[..bytes_read]);
-            response.push_str(&chunk);
+        let buffer = reader::read_until(&mut stream, &self.read_limits, |b| {
+            b.contains(&0) || b.ends_with(b"\n")
+        })
+        .await?;
+        let response = String::from_utf8_lossy(&buffer);
 
-            if response.contains('\0') || response.ends_with('\n') {
-                break;
-            }
-        }
+        Ok(response
+            .trim_end_matches('\0')
+            .trim_end_matches('\n')
+            .to_string())
+    }
 
-        let clean_response = response.trim_end_matches('\0').trim_end_matches('\n');
-        self.parse_rpc_result(clean_response)
+    /// Check whether the cgminer socket accepts connections, newer firmware disables it.
+    pub async fn is_reachable(&self, timeout: Duration) -> bool {
+        let _connection = self.connections.acquire().await;
+        matches!(
+            tokio::time::timeout(
+                timeout,
+                tokio::net::TcpStream::connect((self.ip, self.port))
+            )
+            .await,
+            Ok(Ok(_))
+        )
     }
 
     fn parse_rpc_result(&self, response: &str) -> Result<Value> {
@@ -116,6 +158,10 @@ impl AntMinerRPCAPI {
             .await
     }
 
+    pub async fn restart(&self) -> Result<Value> {
+        self.send_rpc_command("restart", false, None).await
+    }
+
     pub async fn reload(&self) -> Result<Value> {
         self.send_rpc_command("reload", false, Some(json!({"new_api": true})))
             .await
@@ -129,13 +175,27 @@ impl APIClient for AntMinerRPCAPI {
             MinerCommand::RPC {
                 command,
                 parameters,
-            } => self
-                .send_rpc_command(command, false, parameters.clone())
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => {
+                self.send_rpc_command(command, false, parameters.clone())
+                    .await
+            }
             _ => Err(anyhow!("Unsupported command type for RPC client")),
         }
     }
+
+    async fn get_api_results(
+        &self,
+        commands: &[MinerCommand],
+    ) -> HashMap<MinerCommand, Result<Value>> {
+        batch::send_joined(
+            commands,
+            &self.policy,
+            &self.joined_commands,
+            |request| self.send_request(request),
+            |response| self.parse_rpc_result(response),
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -1,10 +1,17 @@
 use anyhow::{Result, anyhow, bail};
 use async_trait::async_trait;
 use diqwest::WithDigestAuth;
-use reqwest::{Client, Method, Response};
+use reqwest::{Client, Method, Response, StatusCode};
 use serde_json::{Value, json};
-use std::{net::IpAddr, time::Duration};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+use tokio::sync::RwLock;
 
+use crate::miners::backends::errors::{ControlError, MinerError};
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 
@@ -14,8 +21,12 @@ pub struct AntMinerWebAPI {
     port: u16,
     client: Client,
     timeout: Duration,
-    username: String,
-    password: String,
+    credentials: RwLock<Credentials>,
+    /// Tried in order when the miner rejects `credentials`
+    fallback_credentials: Vec<Credentials>,
+    /// Whether the last request was rejected with every set of credentials
+    auth_failed: AtomicBool,
+    pub(crate) policy: CommandPolicy,
 }
 
 impl AntMinerWebAPI {
@@ -30,19 +41,36 @@ impl AntMinerWebAPI {
             port: 80,
             client,
             timeout: Duration::from_secs(5),
-            username: "root".to_string(),
-            password: "root".to_string(),
+            credentials: RwLock::new(Credentials::new("root", "root")),
+            fallback_credentials: Vec::new(),
+            auth_failed: AtomicBool::new(false),
+            policy: CommandPolicy::default(),
         }
     }
 
+    /// Connect to `addr` instead of the miner's IP on the default port.
+    pub fn set_address(&mut self, addr: SocketAddr) {
+        self.ip = addr.ip();
+        self.port = addr.port();
+    }
+
     pub fn with_auth(ip: IpAddr, username: String, password: String) -> Self {
         let mut client = Self::new(ip);
         client.port = 80;
-        client.username = username;
-        client.password = password;
+        client.credentials = RwLock::new(Credentials::new(username, password));
         client
     }
 
+    pub fn set_fallback_credentials(&mut self, credentials: Vec<Credentials>) {
+        self.fallback_credentials = credentials;
+    }
+
+    /// Whether the last request was rejected by the miner with every set of credentials, so
+    /// nothing from the web API is available.
+    pub fn authentication_failed(&self) -> bool {
+        self.auth_failed.load(Ordering::Relaxed)
+    }
+
     pub fn with_timeout(ip: IpAddr, timeout: Duration) -> Self {
         let mut client = Self::new(ip);
         client.port = 80;
@@ -57,13 +85,31 @@ impl AntMinerWebAPI {
         parameters: Option<Value>,
         method: Method,
     ) -> Result<Value> {
+        if method == Method::GET {
+            self.policy.check(command)?;
+        } else {
+            self.policy.check_write(command)?;
+        }
         let url = format!("http://{}:{}/cgi-bin/{}.cgi", self.ip, self.port, command);
 
-        let response = self
-            .execute_web_request(&url, &method, parameters.clone())
+        let credentials = self.credentials.read().await.clone();
+        let mut response = self
+            .execute_web_request(&url, &method, parameters.clone(), &credentials)
             .await?;
+        if response.status() == StatusCode::UNAUTHORIZED
+            && let Some(fallback) = self
+                .retry_with_fallback_credentials(&url, &method, parameters, &credentials)
+                .await?
+        {
+            response = fallback;
+        }
 
         let status = response.status();
+        self.auth_failed
+            .store(status == StatusCode::UNAUTHORIZED, Ordering::Relaxed);
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(MinerError::AuthenticationFailed.into());
+        }
         if status.is_success() {
             let json_data = response.json().await.map_err(|e| anyhow!(e.to_string()))?;
             Ok(json_data)
@@ -72,18 +118,41 @@ impl AntMinerWebAPI {
         }
     }
 
+    /// Send the request again with each fallback other than `rejected`, switching over to the
+    /// first credentials the miner accepts.
+    async fn retry_with_fallback_credentials(
+        &self,
+        url: &str,
+        method: &Method,
+        parameters: Option<Value>,
+        rejected: &Credentials,
+    ) -> Result<Option<Response>> {
+        for credentials in self.fallback_credentials.iter().filter(|c| *c != rejected) {
+            let response = self
+                .execute_web_request(url, method, parameters.clone(), credentials)
+                .await?;
+            if response.status() != StatusCode::UNAUTHORIZED {
+                *self.credentials.write().await = credentials.clone();
+                return Ok(Some(response));
+            }
+        }
+        Ok(None)
+    }
+
     async fn execute_web_request(
         &self,
         url: &str,
         method: &Method,
         parameters: Option<Value>,
+        credentials: &Credentials,
     ) -> Result<Response> {
+        let Credentials { username, password } = credentials;
         let response = match *method {
             Method::GET => self
                 .client
                 .get(url)
                 .timeout(self.timeout)
-                .send_with_digest_auth(&self.username, &self.password)
+                .send_with_digest_auth(username, password)
                 .await
                 .map_err(|e| anyhow!(e.to_string()))?,
             Method::POST => {
@@ -92,7 +161,7 @@ impl AntMinerWebAPI {
                     .post(url)
                     .json(&data)
                     .timeout(self.timeout)
-                    .send_with_digest_auth(&self.username, &self.password)
+                    .send_with_digest_auth(username, password)
                     .await
                     .map_err(|e| anyhow!(e.to_string()))?
             }
@@ -147,6 +216,39 @@ impl AntMinerWebAPI {
             .await
     }
 
+    /// Change the web password, authenticating with `old` and switching this client over to
+    /// `new` once the miner accepts it.
+    pub async fn set_password(&self, old: &str, new: &str) -> Result<Value> {
+        validate_password(new)?;
+        self.policy.check_write("passwd")?;
+
+        let url = format!("http://{}:{}/cgi-bin/passwd.cgi", self.ip, self.port);
+        let username = self.credentials.read().await.username.clone();
+        let response = self
+            .client
+            .post(&url)
+            .json(&password_payload(old, new))
+            .timeout(self.timeout)
+            .send_with_digest_auth(&username, old)
+            .await
+            .map_err(|e| anyhow!(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            bail!("HTTP request failed with status code {}", status);
+        }
+        let result: Value = response.json().await.map_err(|e| anyhow!(e.to_string()))?;
+        if result["stats"].as_str() != Some("success") {
+            bail!(
+                "Failed to change password: {}",
+                result["msg"].as_str().unwrap_or("unknown error")
+            );
+        }
+
+        self.credentials.write().await.password = new.to_string();
+        Ok(result)
+    }
+
     pub async fn set_network_conf(
         &self,
         ip: String,
@@ -164,11 +266,75 @@ impl AntMinerWebAPI {
             "ipPro": protocol,
             "ipSub": subnet_mask
         });
+        self.send_network_conf(config).await
+    }
+
+    /// Build the `set_network_conf` payload changing the hostname, from the current network
+    /// config so the addressing is kept as is.
+    pub async fn hostname_network_conf(&self, hostname: &str) -> Result<Value> {
+        let network_info = self.get_network_info().await?;
+        network_conf_with_hostname(&network_info, hostname)
+    }
+
+    /// Send a whole `set_network_conf` payload, such as one built by
+    /// [`Self::hostname_network_conf`].
+    pub async fn send_network_conf(&self, config: Value) -> Result<Value> {
         self.send_web_command("set_network_conf", false, Some(config), Method::POST)
             .await
     }
 }
 
+/// Build a `set_network_conf` payload from `get_network_info`, replacing only the hostname.
+///
+/// The miner replaces its whole network config with the payload, so this fails rather than
+/// guess when the network type is unknown or a static config is missing a field, either would
+/// leave the miner without its address.
+pub(crate) fn network_conf_with_hostname(network_info: &Value, hostname: &str) -> Result<Value> {
+    let field = |key: &str| network_info.get(key).and_then(Value::as_str);
+    let protocol = match field("conf_nettype") {
+        Some(nettype) if nettype.eq_ignore_ascii_case("static") => 2,
+        Some(nettype) if nettype.eq_ignore_ascii_case("dhcp") => 1,
+        nettype => bail!("Unknown Antminer network type {nettype:?}"),
+    };
+    // DHCP leaves the addressing empty
+    let address = |key: &str| match field(key) {
+        Some(value) => Ok(value),
+        None if protocol == 1 => Ok(""),
+        None => Err(anyhow!("Antminer static network config is missing {key}")),
+    };
+    Ok(json!({
+        "ipAddress": address("conf_ipaddress")?,
+        "ipDns": address("conf_dnsservers")?,
+        "ipGateway": address("conf_gateway")?,
+        "ipHost": hostname,
+        "ipPro": protocol,
+        "ipSub": address("conf_netmask")?
+    }))
+}
+
+/// The `passwd.cgi` payload changing the web password from `old` to `new`.
+pub(crate) fn password_payload(old: &str, new: &str) -> Value {
+    json!({"curPwd": old, "newPwd": new, "confirmPwd": new})
+}
+
+/// The stock web UI only accepts alphanumeric passwords, with underscores, of up to 32 characters.
+pub(crate) fn validate_password(password: &str) -> Result<(), ControlError> {
+    if password.is_empty() || password.len() > 32 {
+        return Err(ControlError::InvalidParameter(
+            "Antminer passwords must be between 1 and 32 characters".to_string(),
+        ));
+    }
+    if !password
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(ControlError::InvalidParameter(
+            "Antminer passwords may only contain letters, numbers and underscores".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl APIClient for AntMinerWebAPI {
     async fn get_api_result(&self, command: &MinerCommand) -> Result<Value> {
@@ -176,10 +342,10 @@ impl APIClient for AntMinerWebAPI {
             MinerCommand::WebAPI {
                 command,
                 parameters,
-            } => self
-                .send_web_command(command, false, parameters.clone(), Method::GET)
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => {
+                self.send_web_command(command, false, parameters.clone(), Method::GET)
+                    .await
+            }
             _ => Err(anyhow!("Unsupported command type for Web client")),
         }
     }
@@ -198,3 +364,49 @@ impl WebAPIClient for AntMinerWebAPI {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::json::bmminer::antminer_web::AM_WEB_NETWORK_INFO;
+
+    #[test]
+    fn test_validate_password() {
+        assert!(validate_password("new_Password1").is_ok());
+        assert!(validate_password("").is_err());
+        assert!(validate_password("p@ssword").is_err());
+        assert!(validate_password(&"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_network_conf_with_hostname() {
+        let network_info: Value = serde_json::from_str(AM_WEB_NETWORK_INFO).unwrap();
+
+        let config = network_conf_with_hostname(&network_info, "rack4-slot12").unwrap();
+
+        assert_eq!(
+            config,
+            json!({
+                "ipAddress": "10.0.0.21",
+                "ipDns": "10.0.0.1,1.1.1.1",
+                "ipGateway": "10.0.0.1",
+                "ipHost": "rack4-slot12",
+                "ipPro": 2,
+                "ipSub": "255.255.255.0"
+            })
+        );
+
+        // a static config missing a field or an unknown network type is not sent back
+        let mut partial = network_info.clone();
+        partial.as_object_mut().unwrap().remove("conf_gateway");
+        assert!(network_conf_with_hostname(&partial, "rack4-slot12").is_err());
+        let mut unknown = network_info.clone();
+        unknown.as_object_mut().unwrap().remove("conf_nettype");
+        assert!(network_conf_with_hostname(&unknown, "rack4-slot12").is_err());
+
+        let dhcp = json!({"conf_nettype": "DHCP", "conf_hostname": "Antminer"});
+        let config = network_conf_with_hostname(&dhcp, "rack4-slot12").unwrap();
+        assert_eq!(config["ipPro"], 1);
+        assert_eq!(config["ipAddress"], "");
+    }
+}
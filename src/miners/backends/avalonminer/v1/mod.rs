@@ -0,0 +1,1582 @@
+use anyhow::{Result, anyhow, bail};
+use async_trait::async_trait;
+use macaddr::MacAddr;
+use measurements::{AngularVelocity, Current, Frequency, Power, Temperature, Voltage};
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeSet, HashMap};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::data::board::{BoardData, ChipData};
+use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerModel};
+use crate::data::device::{MinerControlBoard, MinerMake, PowerLimits};
+use crate::data::fan::FanData;
+use crate::data::hashrate::{HashRate, HashRateUnit};
+use crate::data::health::HealthThresholds;
+use crate::data::miner::MinerStatus;
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::psu::PsuData;
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::RPCReadLimits;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
+use crate::miners::backends::traits::*;
+use crate::miners::commands::MinerCommand;
+use crate::miners::data::{
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData, FromValue,
+    get_by_pointer,
+};
+
+use rpc::AvalonMinerRPCAPI;
+
+mod rpc;
+
+/// Canaan AvalonMiner on stock cgminer firmware.
+///
+/// Covers both stats layouts: the A-series/Nano `MM ID0` block with per board arrays, and the
+/// Avalon Q `MM ID0:Summary` block with chip data split out into `HBinfo`.
+#[derive(Debug)]
+pub struct AvalonMinerV1 {
+    ip: IpAddr,
+    rpc: AvalonMinerRPCAPI,
+    device_info: DeviceInfo,
+    labels: Option<HashMap<String, String>>,
+    health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
+}
+
+impl AvalonMinerV1 {
+    pub fn new(ip: IpAddr, model: MinerModel) -> Self {
+        Self {
+            ip,
+            rpc: AvalonMinerRPCAPI::new(ip),
+            device_info: DeviceInfo::new(
+                MinerMake::AvalonMiner,
+                model,
+                MinerFirmware::Stock,
+                HashAlgorithm::SHA256,
+            ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
+        }
+    }
+
+    /// Limit the connections the API client opens at once, the firmware answers one at a time
+    /// so commands are sent one after another by default.
+    pub fn with_connection_limit(mut self, limit: ConnectionLimit) -> Self {
+        self.rpc.connections = limit;
+        self
+    }
+
+    /// Restart cgminer, the firmware can't reboot the system over its API.
+    #[deprecated(note = "use `Restart::restart_mining`, this only restarts the mining process")]
+    pub async fn reboot(&self) -> Result<bool> {
+        self.restart_mining().await
+    }
+
+    /// Apply the power level nearest to `limit`, returning the level applied.
+    async fn write_power_limit(&self, limit: Power) -> Result<Option<Power>> {
+        let stats = self.rpc.send_command("stats", false, None).await?;
+        let help = self
+            .rpc
+            .send_command("ascset", false, Some(json!(["0", "help"])))
+            .await?;
+        let plan = plan_power_limit(&stats, &help, limit)?;
+
+        let applied = execute_control(self, "ascset", plan.parameters.clone(), async {
+            let data = self
+                .rpc
+                .send_command("ascset", false, Some(plan.parameters.clone()))
+                .await?;
+
+            if rpc::status_entry(&data).is_none() {
+                bail!("Failed to set power limit");
+            }
+            Ok(ascset_ok(&data, "ASC 0 set OK"))
+        })
+        .await?;
+
+        Ok(applied.then_some(plan.applied))
+    }
+}
+
+#[async_trait]
+impl APIClient for AvalonMinerV1 {
+    async fn get_api_result(&self, command: &MinerCommand) -> Result<Value> {
+        match command {
+            MinerCommand::RPC { .. } => self.rpc.get_api_result(command).await,
+            _ => Err(anyhow!("Unsupported command type for AvalonMiner API")),
+        }
+    }
+}
+
+#[async_trait]
+impl Restart for AvalonMinerV1 {
+    async fn reboot_system(&self) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "rebooting the system".to_string(),
+        ))?
+    }
+
+    /// cgminer `restart` only restarts the mining process.
+    async fn restart_mining(&self) -> Result<bool> {
+        execute_control(self, "restart", Value::Null, async {
+            let data = self.rpc.send_command("restart", false, None).await?;
+
+            if let Some(status) = data.get("STATUS").and_then(|s| s.as_str()) {
+                return Ok(status == "RESTART");
+            }
+
+            Ok(false)
+        })
+        .await
+    }
+}
+#[async_trait]
+impl Pause for AvalonMinerV1 {
+    async fn pause(&self, after: Option<Duration>) -> Result<bool> {
+        let offset = after.unwrap_or(Duration::from_secs(5));
+        let shutdown_time = SystemTime::now() + offset;
+
+        let timestamp = shutdown_time
+            .duration_since(UNIX_EPOCH)
+            .expect("Shutdown time is before UNIX epoch")
+            .as_secs();
+
+        let parameters = json!(["0", format!("softoff,1:{}", timestamp)]);
+
+        execute_control(self, "ascset", parameters.clone(), async {
+            let data = self
+                .rpc
+                .send_command("ascset", false, Some(parameters))
+                .await?;
+
+            Ok(ascset_ok(&data, "success softoff"))
+        })
+        .await
+    }
+}
+#[async_trait]
+impl Resume for AvalonMinerV1 {
+    async fn resume(&self, after: Option<Duration>) -> Result<bool> {
+        let offset = after.unwrap_or(Duration::from_secs(5));
+        let shutdown_time = SystemTime::now() + offset;
+
+        let timestamp = shutdown_time
+            .duration_since(UNIX_EPOCH)
+            .expect("Shutdown time is before UNIX epoch")
+            .as_secs();
+
+        let parameters = json!(["0", format!("softon,1:{}", timestamp)]);
+
+        execute_control(self, "ascset", parameters.clone(), async {
+            let data = self
+                .rpc
+                .send_command("ascset", false, Some(parameters))
+                .await?;
+
+            Ok(ascset_ok(&data, "success softon"))
+        })
+        .await
+    }
+}
+
+impl SetRPCReadLimits for AvalonMinerV1 {
+    fn set_rpc_read_limits(&mut self, limits: RPCReadLimits) {
+        self.rpc.set_read_limits(limits);
+    }
+}
+#[async_trait]
+impl SetFaultLight for AvalonMinerV1 {
+    async fn set_fault_light(&self, fault: bool) -> Result<bool> {
+        let command = if fault { "1-1" } else { "1-0" };
+        let parameters = json!(["0", "led", command]);
+
+        let sent = execute_control(self, "ascset", parameters.clone(), async {
+            let data = self
+                .rpc
+                .send_command("ascset", false, Some(parameters))
+                .await?;
+
+            if rpc::status_entry(&data).is_none() {
+                bail!("Failed to set fault light to {}", command);
+            }
+            Ok(ascset_ok(&data, "ASC 0 set OK"))
+        })
+        .await?;
+        Ok(confirm_fault_light(self, fault, sent).await)
+    }
+}
+
+#[async_trait]
+impl SetPowerLimit for AvalonMinerV1 {
+    async fn set_power_limit(&self, limit: Power) -> Result<bool> {
+        Ok(self.apply_power_limit(limit).await?.is_some())
+    }
+
+    async fn set_power_limit_unchecked(&self, limit: Power) -> Result<bool> {
+        Ok(self.write_power_limit(limit).await?.is_some())
+    }
+
+    async fn apply_power_limit(&self, limit: Power) -> Result<Option<Power>> {
+        PowerLimits::validate_for(&self.device_info.model, limit)?;
+        self.write_power_limit(limit).await
+    }
+}
+
+/// Whether the miner accepted an `ascset`, answered with `expected_msg_contains` or the shorter
+/// `set OK` and `success` of 25.x firmware, which also sends `STATUS` as an object.
+fn ascset_ok(resp: &Value, expected_msg_contains: &str) -> bool {
+    let Some(status) = rpc::status_entry(resp) else {
+        return false;
+    };
+    let accepted = matches!(
+        status.get("STATUS").and_then(Value::as_str),
+        Some("S" | "I")
+    );
+    let msg = status
+        .get("Msg")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    accepted
+        && (msg.contains(expected_msg_contains)
+            || msg.ends_with("set OK")
+            || msg.eq_ignore_ascii_case("success"))
+}
+
+/// The `ascset` parameters for a power limit, and the wattage they will apply.
+#[derive(Debug, Clone, PartialEq)]
+struct PowerLimitPlan {
+    parameters: Value,
+    applied: Power,
+}
+
+/// Resolve a requested power limit against what the miner supports.
+///
+/// Firmwares listing `mpo` in `ascset 0,help` take a wattage directly. Everything else only has
+/// fixed work levels, so the highest level at or below the request is picked.
+fn plan_power_limit(stats: &Value, help: &Value, limit: Power) -> Result<PowerLimitPlan> {
+    let options = rpc::status_entry(help)
+        .and_then(|status| status.get("Msg"))
+        .and_then(Value::as_str)
+        .and_then(|msg| msg.split_once("set info:"))
+        .map(|(_, options)| options.split('|').map(str::trim).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    if options.contains(&"mpo") {
+        let watts = limit.as_watts().floor();
+        if watts <= 0.0 {
+            Err(ControlError::InvalidParameter(format!(
+                "power limit {watts} W must be positive"
+            )))?
+        }
+        return Ok(PowerLimitPlan {
+            parameters: json!(["0", "mpo,set", (watts as u64).to_string()]),
+            applied: Power::from_watts(watts),
+        });
+    }
+
+    let (setting, levels) = work_levels(stats);
+    if levels.is_empty() {
+        bail!("Could not read the supported work levels");
+    }
+    let Some((level, watts)) = levels
+        .iter()
+        .filter(|(_, watts)| *watts <= limit.as_watts())
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+    else {
+        let lowest = levels.iter().map(|(_, w)| *w).fold(f64::INFINITY, f64::min);
+        return Err(ControlError::InvalidParameter(format!(
+            "power limit {} W is below the lowest work level of {lowest} W",
+            limit.as_watts()
+        ))
+        .into());
+    };
+
+    Ok(PowerLimitPlan {
+        parameters: json!(["0", format!("{setting},set"), level.to_string()]),
+        applied: Power::from_watts(*watts),
+    })
+}
+
+/// The power target in W of an `ATA<n>` work level entry, e.g. `800-65-2264-258-20`.
+///
+/// - `0`: power target in W
+/// - `1`: target temperature in °C
+/// - `2..`: tuning parameters, not decoded since their layout changes between firmware versions
+fn ata_watts(value: &Value) -> Option<f64> {
+    value.as_str()?.split('-').next()?.parse::<f64>().ok()
+}
+
+/// The work level currently applied, `WORKLEVEL` or `WORKMODE` on firmware without levels.
+fn current_level(block: &Map<String, Value>) -> (&'static str, Option<u64>) {
+    match block.get("WORKLEVEL").and_then(Value::as_u64) {
+        Some(level) => ("worklevel", Some(level)),
+        None => ("workmode", block.get("WORKMODE").and_then(Value::as_u64)),
+    }
+}
+
+/// The power target of the applied work level, `MPO` or the wattage of its `ATA<n>` entry on
+/// firmware without `MPO`.
+fn power_target(block: &Map<String, Value>) -> Option<f64> {
+    if let Some(mpo) = block.get("MPO").and_then(f64::from_value) {
+        return Some(mpo);
+    }
+    let level = current_level(block).1?;
+    block.get(&format!("ATA{level}")).and_then(ata_watts)
+}
+
+/// The work level setting and its levels with their approximate wattage.
+///
+/// Each `ATA<n>` entry describes level `n`, see [`ata_watts`].
+/// `MPO` is the power target of the level currently applied. A-series firmware without
+/// `WORKLEVEL` only switches between work modes.
+fn work_levels(stats: &Value) -> (&'static str, Vec<(u64, f64)>) {
+    let Some(block) = stats
+        .pointer("/STATS/0/MM ID0:Summary/STATS")
+        .or_else(|| stats.pointer("/STATS/0/MM ID0"))
+        .and_then(Value::as_object)
+    else {
+        return ("worklevel", vec![]);
+    };
+
+    let mut levels: Vec<(u64, f64)> = block
+        .iter()
+        .filter_map(|(key, value)| {
+            let level = key.strip_prefix("ATA")?.parse::<u64>().ok()?;
+            Some((level, ata_watts(value)?))
+        })
+        .collect();
+
+    let (setting, current) = current_level(block);
+    if let Some(current) = current
+        && let Some(mpo) = block.get("MPO").and_then(Value::as_f64)
+        && !levels.iter().any(|(level, _)| *level == current)
+    {
+        levels.push((current, mpo));
+    }
+    levels.sort_by_key(|(level, _)| *level);
+
+    (setting, levels)
+}
+
+impl SetCredentials for AvalonMinerV1 {}
+
+impl EndpointControl for AvalonMinerV1 {}
+
+#[async_trait]
+impl SetPassword for AvalonMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the password".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetHostname for AvalonMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the hostname".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPools for AvalonMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting pools".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for AvalonMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
+    }
+}
+
+impl GetDataLocations for AvalonMinerV1 {
+    fn get_locations(&self, data_field: DataField) -> Vec<DataLocation> {
+        let version_cmd: MinerCommand = MinerCommand::RPC {
+            command: "version",
+            parameters: None,
+        };
+        let stats_cmd: MinerCommand = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+        let summary_cmd: MinerCommand = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+        let devs_cmd: MinerCommand = MinerCommand::RPC {
+            command: "devs",
+            parameters: None,
+        };
+        let pools_cmd: MinerCommand = MinerCommand::RPC {
+            command: "pools",
+            parameters: None,
+        };
+
+        match data_field {
+            DataField::Mac => vec![(
+                version_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/VERSION/0/MAC"),
+                    tag: None,
+                },
+            )],
+            DataField::ControlBoardVersion => vec![(
+                version_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/VERSION/0/HWTYPE"),
+                    tag: None,
+                },
+            )],
+            DataField::ApiVersion => vec![(
+                version_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/VERSION/0/API"),
+                    tag: None,
+                },
+            )],
+            DataField::FirmwareVersion => vec![
+                (
+                    version_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/VERSION/0/CGMiner"),
+                        tag: None,
+                    },
+                ),
+                (
+                    version_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/VERSION/0/VERSION"),
+                        tag: None,
+                    },
+                ),
+            ],
+            DataField::Hashrate => vec![(
+                devs_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/DEVS/0/MHS 1m"),
+                    tag: None,
+                },
+            )],
+            DataField::ExpectedHashrate => vec![
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0/GHSmm"),
+                        tag: None,
+                    },
+                ),
+                (
+                    stats_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0:Summary/STATS/GHSmm"),
+                        tag: None,
+                    },
+                ),
+            ],
+            DataField::Hashboards => vec![
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0"),
+                        tag: Some("MM"),
+                    },
+                ),
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0:Summary/STATS"),
+                        tag: Some("MM"),
+                    },
+                ),
+                (
+                    stats_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/HBinfo"),
+                        tag: Some("HBinfo"),
+                    },
+                ),
+            ],
+            DataField::Wattage => vec![
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0/PS"),
+                        tag: Some("ps"),
+                    },
+                ),
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0:Summary/STATS/PS"),
+                        tag: Some("ps"),
+                    },
+                ),
+                (
+                    stats_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0:Summary/STATS/WALLPOWER"),
+                        tag: Some("wall"),
+                    },
+                ),
+            ],
+            DataField::Psu => vec![
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0/PS"),
+                        tag: None,
+                    },
+                ),
+                (
+                    stats_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0:Summary/STATS/PS"),
+                        tag: None,
+                    },
+                ),
+            ],
+            DataField::WattageLimit => vec![
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0"),
+                        tag: None,
+                    },
+                ),
+                (
+                    stats_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0:Summary/STATS"),
+                        tag: None,
+                    },
+                ),
+            ],
+            DataField::Fans => vec![
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0"),
+                        tag: None,
+                    },
+                ),
+                (
+                    stats_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0:Summary/STATS"),
+                        tag: None,
+                    },
+                ),
+            ],
+            DataField::LightFlashing => vec![
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0/Led"),
+                        tag: None,
+                    },
+                ),
+                (
+                    stats_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0:Summary/STATS/Led"),
+                        tag: None,
+                    },
+                ),
+            ],
+            DataField::IsMining => vec![
+                (
+                    stats_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0"),
+                        tag: None,
+                    },
+                ),
+                (
+                    stats_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/MM ID0:Summary/STATS"),
+                        tag: None,
+                    },
+                ),
+            ],
+            // some firmwares leave Elapsed out of stats but not summary, stats is listed last so
+            // it wins when both have it
+            DataField::Uptime => vec![
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Elapsed"),
+                        tag: None,
+                    },
+                ),
+                (
+                    stats_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATS/0/Elapsed"),
+                        tag: None,
+                    },
+                ),
+            ],
+            DataField::Pools => vec![(
+                pools_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/POOLS"),
+                    tag: None,
+                },
+            )],
+            _ => vec![],
+        }
+    }
+}
+
+impl GetIP for AvalonMinerV1 {
+    fn get_ip(&self) -> IpAddr {
+        self.ip
+    }
+}
+
+impl GetDeviceInfo for AvalonMinerV1 {
+    fn get_device_info(&self) -> DeviceInfo {
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for AvalonMinerV1 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for AvalonMinerV1 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for AvalonMinerV1 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for AvalonMinerV1 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for AvalonMinerV1 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.rpc.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.rpc.policy = policy;
+    }
+}
+
+impl GetCapabilities for AvalonMinerV1 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_fault_light: true,
+            supports_power_limit: true,
+            supports_restart: true,
+            supports_pause: true,
+            has_chip_telemetry: true,
+            has_psu_telemetry: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl CollectData for AvalonMinerV1 {
+    fn get_collector(&self) -> DataCollector<'_> {
+        DataCollector::new(self)
+    }
+}
+
+impl GetMAC for AvalonMinerV1 {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
+        // Avalon reports the compact 12 digit form
+        data.extract::<String>(DataField::Mac)
+            .and_then(|raw| parse_mac_str(&raw))
+    }
+}
+
+impl GetReportedIp for AvalonMinerV1 {}
+
+impl GetSerialNumber for AvalonMinerV1 {}
+
+impl GetControlBoardVersion for AvalonMinerV1 {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
+        data.extract::<String>(DataField::ControlBoardVersion)
+            .and_then(|s| MinerControlBoard::from_str(&s).ok())
+    }
+}
+
+impl GetHostname for AvalonMinerV1 {}
+
+impl GetApiVersion for AvalonMinerV1 {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
+        data.extract::<String>(DataField::ApiVersion)
+    }
+}
+
+impl GetFirmwareVersion for AvalonMinerV1 {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
+        data.extract::<String>(DataField::FirmwareVersion)
+    }
+}
+
+impl GetHashboards for AvalonMinerV1 {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
+        let hw = &self.device_info.hardware;
+        let board_cnt = hw.boards.unwrap_or(1) as usize;
+        let chips_per = hw.chips.unwrap_or(0);
+
+        let boards = match data.get(&DataField::Hashboards) {
+            Some(v) => v,
+            _ => return Vec::new(),
+        };
+        let summary = &boards["MM"];
+        // Avalon Q splits chip data out per board into HBinfo/HB{idx}
+        let hb_info = &boards["HBinfo"];
+
+        let per_board = |key: &str, idx: usize| summary[key][idx].as_f64();
+        let chip_values = |split_key: &str, idx: usize| -> Vec<f64> {
+            let values = match hb_info.get(format!("HB{idx}")) {
+                Some(board) => &board[format!("{split_key}0")],
+                None => &summary[format!("{split_key}{idx}")],
+            };
+            values
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect())
+                .unwrap_or_default()
+        };
+        let text = |value: &Value| match value {
+            Value::String(s) if !s.is_empty() => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        };
+        let hb_text = |key: &str, idx: usize| {
+            hb_info
+                .get(format!("HB{idx}"))
+                .and_then(|board| board.get(format!("{key}0")))
+                .and_then(text)
+        };
+        // chip bin and core type are reported once for the miner unless HBinfo has them per board
+        let board_text =
+            |key: &str, idx: usize| hb_text(key, idx).or_else(|| summary.get(key).and_then(text));
+
+        (0..board_cnt)
+            .map(|idx| {
+                let board_temp = per_board("MTavg", idx)
+                    .or_else(|| per_board("HBITemp", idx))
+                    .map(Temperature::from_celsius);
+
+                let intake_temp = per_board("ITemp", idx).map(Temperature::from_celsius);
+
+                let hashrate = per_board("MGHS", idx).map(|r| HashRate {
+                    value: r,
+                    unit: HashRateUnit::GigaHash,
+                    algo: "SHA256".into(),
+                });
+
+                let chip_temps = chip_values("PVT_T", idx);
+                let chip_volts = chip_values("PVT_V", idx);
+                let chip_works = chip_values("MW", idx);
+
+                let mut chips = Vec::new();
+                let max_len = chip_temps.len().max(chip_volts.len()).max(chip_works.len());
+
+                for pos in 0..max_len {
+                    let temp = chip_temps.get(pos).copied().unwrap_or(0.0);
+                    let volt = chip_volts.get(pos).copied().unwrap_or(0.0);
+                    let work = chip_works.get(pos).copied().unwrap_or(0.0);
+
+                    if temp == 0.0 {
+                        continue;
+                    }
+
+                    chips.push(ChipData {
+                        position: pos as u16,
+                        temperature: Some(Temperature::from_celsius(temp)),
+                        voltage: Some(Voltage::from_millivolts(volt)),
+                        working: Some(work > 0.0),
+                        ..Default::default()
+                    });
+                }
+
+                let working_chips = chips.len() as u16;
+                let missing = working_chips == 0;
+
+                let extra: HashMap<String, String> = [("bin", "BIN"), ("core", "Core")]
+                    .into_iter()
+                    .filter_map(|(name, key)| Some((name.to_string(), board_text(key, idx)?)))
+                    .collect();
+
+                BoardData {
+                    position: idx as u8,
+                    expected_chips: Some(chips_per),
+                    working_chips: Some(working_chips),
+                    chips,
+                    intake_temperature: intake_temp,
+                    board_temperature: board_temp,
+                    hashrate,
+                    serial_number: hb_text("SN", idx),
+                    active: Some(!missing),
+                    status: None,
+                    extra: (!extra.is_empty()).then_some(extra),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+impl GetHashrate for AvalonMinerV1 {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::Hashrate, |f| HashRate {
+            value: f,
+            unit: HashRateUnit::MegaHash,
+            algo: "SHA256".into(),
+        })
+    }
+}
+
+impl GetExpectedHashrate for AvalonMinerV1 {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| HashRate {
+            value: f,
+            unit: HashRateUnit::GigaHash,
+            algo: "SHA256".into(),
+        })
+    }
+}
+
+impl GetAverageHashrate for AvalonMinerV1 {}
+
+impl GetFans for AvalonMinerV1 {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
+        let Some(stats) = data.get(&DataField::Fans).and_then(Value::as_object) else {
+            return Vec::new();
+        };
+
+        // `Fan<n>` keys only, not `FanR` or `FanErr`. Stopped fans report 0, which is kept.
+        let reported: HashMap<usize, Option<f64>> = stats
+            .iter()
+            .filter_map(|(key, value)| {
+                let idx = key.strip_prefix("Fan")?.parse::<usize>().ok()?;
+                Some((idx, f64::from_value(value)))
+            })
+            .collect();
+
+        // fans the model should have but the firmware doesn't list are reported without a speed
+        let expected_fans = self.device_info.hardware.fans.unwrap_or(0) as usize;
+        let mut positions: BTreeSet<usize> = (1..=expected_fans).collect();
+        positions.extend(reported.keys());
+
+        positions
+            .into_iter()
+            .map(|idx| FanData {
+                position: idx as i16,
+                rpm: reported
+                    .get(&idx)
+                    .copied()
+                    .flatten()
+                    .map(AngularVelocity::from_rpm),
+            })
+            .collect()
+    }
+}
+
+impl GetPsuFans for AvalonMinerV1 {}
+
+/// Decode the `PS` power supply array from the stats blob.
+///
+/// - `0`: PSU error state, 0 while healthy
+/// - `1`: control board supply voltage in V × 100
+/// - `2`: output voltage to the hashboards in V × 100
+/// - `3`: output current in A
+/// - `4`: output power in W
+/// - `5`: output voltage set point in V × 100
+/// - `6`: not decoded
+///
+/// A11 series firmware only reports the first 6 elements, e.g. `PS[0 1205 1239 258 3197 1240]`.
+/// Avalon Q and Nano units fill in the same layout but report no output current or power while
+/// idle, e.g. `PS[0 1222 4 0 0 2245 146]`.
+fn decode_ps(ps: &Value) -> Option<PsuData> {
+    let ps = ps.as_array().filter(|ps| ps.len() >= 6)?;
+    let value = |idx: usize| ps.get(idx).and_then(f64::from_value);
+
+    Some(PsuData {
+        voltage: value(2).map(|v| Voltage::from_volts(v / 100.0)),
+        current: value(3).map(Current::from_amperes),
+        power: value(4).map(Power::from_watts),
+    })
+}
+
+/// The wattage from `WALLPOWER` or the PSU output power, whichever is more plausible.
+///
+/// The wall reading includes the PSU's conversion losses, so it should be at or somewhat above
+/// the output power. A wall reading below the output, or more than a quarter above it, is stale
+/// and the PSU output is used instead.
+fn cross_check_wattage(wall: Option<f64>, psu: Option<f64>) -> Option<f64> {
+    let wall = wall.filter(|w| *w > 0.0);
+    let psu = psu.filter(|p| *p > 0.0);
+    match (wall, psu) {
+        (Some(wall), Some(psu)) if wall < psu || wall > psu * 1.25 => Some(psu),
+        (Some(wall), _) => Some(wall),
+        (None, psu) => psu,
+    }
+}
+
+impl GetFanControl for AvalonMinerV1 {}
+
+impl GetPsu for AvalonMinerV1 {
+    fn parse_psu(&self, data: &FieldData) -> Option<PsuData> {
+        decode_ps(data.get(&DataField::Psu)?)
+    }
+}
+
+impl GetPsuRails for AvalonMinerV1 {}
+
+impl GetWattage for AvalonMinerV1 {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
+        let wattage = data.get(&DataField::Wattage)?;
+        let wall = wattage.get("wall").and_then(f64::from_value);
+        let psu = wattage
+            .get("ps")
+            .and_then(decode_ps)
+            .and_then(|psu| psu.power)
+            .map(|p| p.as_watts());
+        cross_check_wattage(wall, psu).map(Power::from_watts)
+    }
+}
+
+impl GetWattageLimit for AvalonMinerV1 {
+    fn parse_wattage_limit(&self, data: &FieldData) -> Option<Power> {
+        power_target(data.get(&DataField::WattageLimit)?.as_object()?).map(Power::from_watts)
+    }
+}
+
+impl GetFrequency for AvalonMinerV1 {}
+impl GetVoltage for AvalonMinerV1 {}
+impl GetWorkMode for AvalonMinerV1 {}
+impl GetAutotune for AvalonMinerV1 {}
+
+impl GetLightFlashing for AvalonMinerV1 {
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
+        // `Led[0]` or `Led[1]` in the stats string, set with `ascset 0,led,1-1`
+        data.extract::<bool>(DataField::LightFlashing)
+    }
+}
+
+impl GetMessages for AvalonMinerV1 {}
+
+impl GetUptime for AvalonMinerV1 {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
+        data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
+    }
+}
+
+impl GetFluidTemperature for AvalonMinerV1 {}
+
+impl GetFluidFlow for AvalonMinerV1 {}
+
+impl GetFluidPressure for AvalonMinerV1 {}
+
+impl GetIsMining for AvalonMinerV1 {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
+    }
+}
+
+impl GetStatus for AvalonMinerV1 {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        let Some(stats) = data.get(&DataField::IsMining) else {
+            return MinerStatus::Unknown;
+        };
+        let soft_off = stats["SoftOFF"].as_u64().is_some_and(|s| s != 0);
+
+        // e.g. "Work: In Work, Hash Board: 3"
+        let work_state = stats["SYSTEMSTATU"]
+            .as_str()
+            .and_then(|s| s.split(',').next())
+            .and_then(|s| s.trim().strip_prefix("Work: In "));
+
+        match work_state {
+            Some("Idle") => MinerStatus::Paused,
+            Some("Init") => MinerStatus::Starting,
+            Some("Work") if soft_off => MinerStatus::Paused,
+            Some("Work") => MinerStatus::Mining,
+            Some(_) => MinerStatus::Failure,
+            None if soft_off => MinerStatus::Paused,
+            None => MinerStatus::Unknown,
+        }
+    }
+}
+
+impl GetPools for AvalonMinerV1 {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
+        data.get(&DataField::Pools)
+            .and_then(|v| v.as_array())
+            .map(|slice| slice.to_vec())
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, pool)| PoolData {
+                url: pool
+                    .get("URL")
+                    .and_then(|v| v.as_str())
+                    .and_then(PoolURL::parse),
+                user: pool.get("User").and_then(|v| v.as_str()).map(|s| s.into()),
+                password: None,
+                position: Some(idx as u16),
+                alive: pool
+                    .get("Status")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s == "Alive"),
+                active: pool.get("Stratum Active").and_then(|v| v.as_bool()),
+                accepted_shares: pool.get("Accepted").and_then(|v| v.as_u64()),
+                rejected_shares: pool.get("Rejected").and_then(|v| v.as_u64()),
+                last_share_difficulty: pool.get("Last Share Difficulty").and_then(|v| v.as_f64()),
+                last_share_time: None,
+                group: None,
+                quota: None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::avalon::AvalonMinerModel::{
+        Avalon1246, AvalonHomeQ, AvalonNano3,
+    };
+    use crate::test::api::MockAPIClient;
+    use crate::test::invariants::assert_hashrate_magnitudes;
+    use crate::test::json::cgminer::avalon::{
+        ASCSET_HELP_COMMAND, ASCSET_HELP_MPO_COMMAND, AVALON_A_STATS_PARSED, DEVS_COMMAND,
+        NANO3_STATS_PARSED, PARSED_STATS_COMMAND, PARSED_STATS_HBINFO_COMMAND, POOLS_COMMAND,
+        SUMMARY_COMMAND, VERSION_COMMAND,
+    };
+
+    #[tokio::test]
+    async fn test_avalon_a() -> Result<()> {
+        let miner = AvalonMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AvalonMiner(Avalon1246),
+        );
+        let mut results = HashMap::new();
+        let stats_cmd: MinerCommand = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+
+        results.insert(stats_cmd, Value::from_str(AVALON_A_STATS_PARSED)?);
+
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+
+        let miner_data = miner.parse_data(data);
+        assert_hashrate_magnitudes(&miner_data);
+
+        assert_eq!(miner_data.uptime, Some(Duration::from_secs(24684)));
+        assert_eq!(miner_data.wattage, Some(Power::from_watts(3189.0)));
+        assert_eq!(miner_data.wattage_limit, Some(Power::from_watts(3200.0)));
+        let psu = miner_data.psu.unwrap();
+        assert_eq!(psu.voltage, Some(Voltage::from_volts(13.16)));
+        assert_eq!(psu.current, Some(Current::from_amperes(242.0)));
+        assert_eq!(psu.power, Some(Power::from_watts(3189.0)));
+        assert_eq!(miner_data.fans.len(), 4);
+        assert_eq!(miner_data.hashboards[0].chips.len(), 120);
+        assert_eq!(
+            miner_data.average_temperature,
+            Some(Temperature::from_celsius(65.0))
+        );
+        assert_eq!(miner_data.status, MinerStatus::Mining);
+        assert!(miner_data.is_mining);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_avalon_fan_counts() -> Result<()> {
+        let fans = |model, stats: &str| {
+            let miner =
+                AvalonMinerV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AvalonMiner(model));
+            let stats = Value::from_str(stats).unwrap();
+            async move {
+                let mock_api = MockAPIClient::new(HashMap::from([(
+                    MinerCommand::RPC {
+                        command: "stats",
+                        parameters: None,
+                    },
+                    stats,
+                )]));
+                let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+                let data = collector.collect(&[DataField::Fans]).await;
+                miner.parse_fans(&data)
+            }
+        };
+        let rpm = |fans: &[FanData]| {
+            fans.iter()
+                .map(|f| f.rpm.map(|r| r.as_rpm()))
+                .collect::<Vec<_>>()
+        };
+
+        // a sleeping Q keeps listing its fans, stopped
+        let q = fans(AvalonHomeQ, PARSED_STATS_COMMAND).await;
+        assert_eq!(rpm(&q), vec![Some(0.0); 4]);
+
+        // the Nano has a single fan, reported as a string
+        let nano = fans(AvalonNano3, NANO3_STATS_PARSED).await;
+        assert_eq!(rpm(&nano), vec![Some(2040.0)]);
+
+        let a1246 = fans(Avalon1246, AVALON_A_STATS_PARSED).await;
+        assert_eq!(
+            rpm(&a1246),
+            vec![Some(5344.0), Some(5392.0), Some(5349.0), Some(5309.0)]
+        );
+
+        // an expected fan the firmware doesn't list has no speed
+        let mut stats = Value::from_str(AVALON_A_STATS_PARSED)?;
+        stats["STATS"][0]["MM ID0"]
+            .as_object_mut()
+            .unwrap()
+            .remove("Fan4");
+        let missing = fans(Avalon1246, &stats.to_string()).await;
+        assert_eq!(missing.len(), 4);
+        assert_eq!(missing[3].position, 4);
+        assert_eq!(missing[3].rpm, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_avalon_ps_decoding() {
+        // A1166 Pro, 6 elements: PS[0 1205 1239 258 3197 1240]
+        let a1166 = decode_ps(&json!([0, 1205, 1239, 258, 3197, 1240])).unwrap();
+        assert_eq!(a1166.voltage, Some(Voltage::from_volts(12.39)));
+        assert_eq!(a1166.current, Some(Current::from_amperes(258.0)));
+        assert_eq!(a1166.power, Some(Power::from_watts(3197.0)));
+
+        // A1246: PS[0 1214 1316 242 3189 1320 3504]
+        let a1246 = decode_ps(&json!([0, 1214, 1316, 242, 3189, 1320, 3504])).unwrap();
+        assert_eq!(a1246.voltage, Some(Voltage::from_volts(13.16)));
+        assert_eq!(a1246.current, Some(Current::from_amperes(242.0)));
+        assert_eq!(a1246.power, Some(Power::from_watts(3189.0)));
+
+        // Avalon Q while idle: PS[0 1222 4 0 0 2245 146]
+        let q = decode_ps(&json!([0, 1222, 4, 0, 0, 2245, 146])).unwrap();
+        assert_eq!(q.voltage, Some(Voltage::from_volts(0.04)));
+        assert_eq!(q.current, Some(Current::from_amperes(0.0)));
+        assert_eq!(q.power, Some(Power::from_watts(0.0)));
+
+        // truncated arrays are not guessed at
+        assert_eq!(decode_ps(&json!([0, 1214, 1316])), None);
+        assert_eq!(decode_ps(&json!(3189)), None);
+    }
+
+    #[test]
+    fn test_avalon_wattage_cross_check() {
+        // no wall reading, or an idle PSU
+        assert_eq!(cross_check_wattage(None, Some(3189.0)), Some(3189.0));
+        assert_eq!(cross_check_wattage(Some(1630.0), Some(0.0)), Some(1630.0));
+        // the wall reading includes the PSU losses
+        assert_eq!(
+            cross_check_wattage(Some(3420.0), Some(3189.0)),
+            Some(3420.0)
+        );
+        // a stale wall reading below the output, or far above it
+        assert_eq!(cross_check_wattage(Some(146.0), Some(3189.0)), Some(3189.0));
+        assert_eq!(
+            cross_check_wattage(Some(9000.0), Some(3189.0)),
+            Some(3189.0)
+        );
+        assert_eq!(cross_check_wattage(Some(0.0), None), None);
+    }
+
+    #[test]
+    fn test_avalon_ata_power_target() {
+        // Avalon Q: ATA0[800-65-2264-258-20]
+        assert_eq!(ata_watts(&json!("800-65-2264-258-20")), Some(800.0));
+        // A1246 work levels
+        assert_eq!(ata_watts(&json!("1300-72-2264-258-20")), Some(1300.0));
+        assert_eq!(ata_watts(&json!("1674-78-2264-258-20")), Some(1674.0));
+        assert_eq!(ata_watts(&json!("")), None);
+
+        // MPO wins when present, otherwise the ATA entry of the applied level
+        let block = json!({
+            "WORKLEVEL": 1,
+            "ATA0": "800-65-2264-258-20",
+            "ATA1": "1300-72-2264-258-20",
+        });
+        assert_eq!(power_target(block.as_object().unwrap()), Some(1300.0));
+        let mut with_mpo = block.clone();
+        with_mpo["MPO"] = json!(1250);
+        assert_eq!(power_target(with_mpo.as_object().unwrap()), Some(1250.0));
+        let workmode = json!({"WORKMODE": 0, "ATA0": "800-65-2264-258-20"});
+        assert_eq!(power_target(workmode.as_object().unwrap()), Some(800.0));
+    }
+
+    #[tokio::test]
+    async fn test_avalon_home_q() -> Result<()> {
+        let miner = AvalonMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AvalonMiner(AvalonHomeQ),
+        );
+
+        let mut results = HashMap::new();
+        let version_cmd: MinerCommand = MinerCommand::RPC {
+            command: "version",
+            parameters: None,
+        };
+        let stats_cmd: MinerCommand = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+        let devs_cmd: MinerCommand = MinerCommand::RPC {
+            command: "devs",
+            parameters: None,
+        };
+        let pools_cmd: MinerCommand = MinerCommand::RPC {
+            command: "pools",
+            parameters: None,
+        };
+
+        results.insert(stats_cmd, Value::from_str(PARSED_STATS_COMMAND)?);
+        results.insert(devs_cmd, Value::from_str(DEVS_COMMAND)?);
+        results.insert(pools_cmd, Value::from_str(POOLS_COMMAND)?);
+        results.insert(version_cmd, Value::from_str(VERSION_COMMAND)?);
+
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+
+        let miner_data = miner.parse_data(data);
+        assert_hashrate_magnitudes(&miner_data);
+
+        assert_eq!(miner_data.uptime, Some(Duration::from_secs(37819)));
+        assert_eq!(miner_data.wattage_limit, Some(Power::from_watts(800.0)));
+        assert_eq!(miner_data.fans.len(), 4);
+        assert_eq!(miner_data.hashboards[0].chips.len(), 160);
+        assert_eq!(miner_data.firmware_version.as_deref(), Some("4.11.1"));
+        assert_eq!(miner_data.status, MinerStatus::Paused);
+        assert!(!miner_data.is_mining);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_avalon_summary_uptime_and_clock_skew() -> Result<()> {
+        let miner = AvalonMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AvalonMiner(AvalonHomeQ),
+        );
+        let stats_cmd: MinerCommand = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+        let summary_cmd: MinerCommand = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+
+        let mut stats = Value::from_str(PARSED_STATS_COMMAND)?;
+        stats["STATS"][0].as_object_mut().unwrap().remove("Elapsed");
+        stats["STATUS"][0].as_object_mut().unwrap().remove("When");
+        // the miner's clock an hour behind the host's
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let mut summary = Value::from_str(SUMMARY_COMMAND)?;
+        summary["STATUS"][0]["When"] = json!(now - 3600);
+
+        let mut results = HashMap::new();
+        results.insert(stats_cmd.clone(), stats);
+        results.insert(summary_cmd.clone(), summary);
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+        let stats = collector.stats();
+        assert_eq!(
+            stats.get(&summary_cmd).unwrap().miner_time,
+            Some(now - 3600)
+        );
+        assert_eq!(stats.get(&stats_cmd).unwrap().miner_time, None);
+
+        let miner_data = miner.parse_data(data);
+        assert_eq!(miner_data.uptime, Some(Duration::from_secs(37714)));
+        let skew = miner_data.clock_skew.unwrap();
+        assert!((3600..3610).contains(&skew), "skew of {skew}s");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_avalon_q_board_metadata() -> Result<()> {
+        let miner = AvalonMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AvalonMiner(AvalonHomeQ),
+        );
+
+        let mut results = HashMap::new();
+        let stats_cmd: MinerCommand = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+        results.insert(stats_cmd, Value::from_str(PARSED_STATS_HBINFO_COMMAND)?);
+
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect(&[DataField::Hashboards]).await;
+
+        let board = &miner.parse_hashboards(&data)[0];
+        let extra = board.extra.as_ref().unwrap();
+
+        assert_eq!(board.serial_number.as_deref(), Some("Q1HB2435C0000123"));
+        assert_eq!(extra.get("bin").map(String::as_str), Some("36"));
+        assert_eq!(extra.get("core").map(String::as_str), Some("A3197S"));
+
+        let json = serde_json::to_value(board)?;
+        assert_eq!(json["extra"]["core"], "A3197S");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_avalon_failure_status() {
+        let miner = AvalonMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AvalonMiner(Avalon1246),
+        );
+        let data = FieldData::from([(
+            DataField::IsMining,
+            json!({"SYSTEMSTATU": "Work: In Error, Hash Board: 0", "SoftOFF": 0}),
+        )]);
+
+        assert_eq!(miner.parse_status(&data), MinerStatus::Failure);
+    }
+
+    #[tokio::test]
+    async fn test_avalon_restart_commands() {
+        let mut miner = AvalonMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AvalonMiner(Avalon1246),
+        );
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        let err = miner.reboot_system().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ControlError>(),
+            Some(ControlError::UnsupportedOperation(_))
+        ));
+        assert!(miner.restart_mining().await.unwrap());
+        // restart doesn't substitute restarting cgminer for a system reboot
+        let err = miner.restart().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ControlError>(),
+            Some(ControlError::UnsupportedOperation(_))
+        ));
+        #[allow(deprecated)]
+        let rebooted = miner.reboot().await.unwrap();
+        assert!(rebooted);
+
+        let commands: Vec<_> = dry_run.actions().into_iter().map(|a| a.command).collect();
+        assert_eq!(commands, vec!["restart", "restart"]);
+    }
+
+    fn planned(stats: &Value, help: &str, watts: f64) -> Result<(Value, f64)> {
+        let help = Value::from_str(help)?;
+        let plan = plan_power_limit(stats, &help, Power::from_watts(watts))?;
+        Ok((plan.parameters, plan.applied.as_watts()))
+    }
+
+    #[test]
+    fn test_avalon_q_power_limit_levels() -> Result<()> {
+        let mut stats = Value::from_str(PARSED_STATS_HBINFO_COMMAND)?;
+        let block = stats
+            .pointer_mut("/STATS/0/MM ID0:Summary/STATS")
+            .and_then(Value::as_object_mut)
+            .unwrap();
+        block.insert("ATA1".into(), json!("1300-72-2264-258-20"));
+        block.insert("ATA2".into(), json!("1674-78-2264-258-20"));
+
+        assert_eq!(
+            planned(&stats, ASCSET_HELP_COMMAND, 800.0)?,
+            (json!(["0", "worklevel,set", "0"]), 800.0)
+        );
+        assert_eq!(
+            planned(&stats, ASCSET_HELP_COMMAND, 1500.0)?,
+            (json!(["0", "worklevel,set", "1"]), 1300.0)
+        );
+        assert_eq!(
+            planned(&stats, ASCSET_HELP_COMMAND, 3500.0)?,
+            (json!(["0", "worklevel,set", "2"]), 1674.0)
+        );
+
+        let err = planned(&stats, ASCSET_HELP_COMMAND, 500.0).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_avalon_power_limit_bounds() {
+        let miner = AvalonMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AvalonMiner(Avalon1246),
+        );
+        let invalid = |error: anyhow::Error| {
+            matches!(
+                error.downcast_ref::<ControlError>(),
+                Some(ControlError::InvalidParameter(_))
+            )
+        };
+
+        // refused before the power levels are read from the miner
+        let limit = Power::from_watts(6000.0);
+        assert!(invalid(miner.set_power_limit(limit).await.unwrap_err()));
+        assert!(invalid(miner.apply_power_limit(limit).await.unwrap_err()));
+        let limit = Power::from_watts(1000.0);
+        assert!(invalid(miner.set_power_limit(limit).await.unwrap_err()));
+    }
+
+    #[test]
+    fn test_avalon_a1246_power_limit() -> Result<()> {
+        let stats = Value::from_str(AVALON_A_STATS_PARSED)?;
+
+        // direct wattage when the firmware offers it
+        assert_eq!(
+            planned(&stats, ASCSET_HELP_MPO_COMMAND, 3000.0)?,
+            (json!(["0", "mpo,set", "3000"]), 3000.0)
+        );
+        assert_eq!(
+            planned(&stats, ASCSET_HELP_MPO_COMMAND, 2750.5)?,
+            (json!(["0", "mpo,set", "2750"]), 2750.0)
+        );
+
+        // otherwise only the current work mode's wattage is known
+        assert_eq!(
+            planned(&stats, ASCSET_HELP_COMMAND, 3500.0)?,
+            (json!(["0", "workmode,set", "1"]), 3200.0)
+        );
+        assert!(planned(&stats, ASCSET_HELP_COMMAND, 3000.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_avalon_ascset_status() -> Result<()> {
+        // the expected message of pause, resume, set_fault_light and set_power_limit
+        let cases = [
+            ("success softoff", "success softoff:1718000005"),
+            ("success softon", "success softon:1718000005"),
+            ("ASC 0 set OK", "ASC 0 set OK"),
+            ("ASC 0 set OK", "ASC 0 set OK"),
+        ];
+        for (expected, msg) in cases {
+            let array = json!({"STATUS": [{"STATUS": "I", "Code": 118, "Msg": msg}], "id": 1});
+            let object = json!({"STATUS": {"STATUS": "I", "Code": 118, "Msg": msg}, "id": 1});
+            assert!(ascset_ok(&array, expected), "{msg}");
+            assert!(ascset_ok(&object, expected), "{msg}");
+
+            // 25.x firmware messages
+            for msg in ["set OK", "success"] {
+                let object = json!({"STATUS": {"STATUS": "S", "Msg": msg}, "id": 1});
+                assert!(ascset_ok(&object, expected), "{msg}");
+            }
+
+            let failed = json!({"STATUS": {"STATUS": "I", "Msg": "ASC 0 set failed"}});
+            assert!(!ascset_ok(&failed, expected));
+            let error = json!({"STATUS": [{"STATUS": "E", "Msg": msg}]});
+            assert!(!ascset_ok(&error, expected));
+            assert!(!ascset_ok(&json!({"id": 1}), expected));
+        }
+
+        // the power limit options are read from the same STATUS
+        let stats = Value::from_str(AVALON_A_STATS_PARSED)?;
+        let mut help = Value::from_str(ASCSET_HELP_MPO_COMMAND)?;
+        help["STATUS"] = help["STATUS"][0].take();
+        let plan = plan_power_limit(&stats, &help, Power::from_watts(3000.0))?;
+        assert_eq!(plan.parameters, json!(["0", "mpo,set", "3000"]));
+        Ok(())
+    }
+}
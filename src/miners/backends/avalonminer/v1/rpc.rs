@@ -5,10 +5,13 @@ use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::LazyLock;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
 
 use crate::miners::api::rpc::errors::RPCError;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::{self, RPCReadLimits};
 use crate::miners::api::rpc::status::RPCCommandStatus;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 
@@ -20,31 +23,39 @@ static NESTED_STATS_RE: LazyLock<Regex> =
 pub struct AvalonMinerRPCAPI {
     ip: IpAddr,
     port: u16,
+    pub(crate) policy: CommandPolicy,
+    pub(crate) connections: ConnectionLimit,
+    read_limits: RPCReadLimits,
 }
 
 impl AvalonMinerRPCAPI {
     pub fn new(ip: IpAddr) -> Self {
-        Self { ip, port: 4028 }
+        Self {
+            ip,
+            port: 4028,
+            policy: CommandPolicy::default(),
+            connections: ConnectionLimit::serial(),
+            read_limits: RPCReadLimits::default(),
+        }
+    }
+
+    /// Bound the size of and idle time between reads of the miner's responses.
+    pub fn set_read_limits(&mut self, limits: RPCReadLimits) {
+        self.read_limits = limits;
     }
 
     fn parse_rpc_result(&self, response: &str) -> Result<Value> {
         let mut val: Value = serde_json::from_str(response)?;
 
-        let status_array = val
-            .get("STATUS")
-            .and_then(|v| v.as_array())
-            .ok_or_else(|| anyhow!("Missing or invalid STATUS array"))?;
+        let status_entry =
+            status_entry(&val).ok_or_else(|| anyhow!("Missing or invalid STATUS"))?;
 
-        if status_array.is_empty() {
-            bail!("Empty STATUS array");
-        }
-
-        let status_str = status_array[0]
+        let status_str = status_entry
             .get("STATUS")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow!("Missing STATUS field"))?;
 
-        let message = status_array[0].get("Msg").and_then(|v| v.as_str());
+        let message = status_entry.get("Msg").and_then(|v| v.as_str());
         let status = RPCCommandStatus::from_str(status_str, message);
 
         status.into_result().map_err(|e| anyhow!(e))?;
@@ -141,6 +152,16 @@ impl AvalonMinerRPCAPI {
     }
 }
 
+/// The first `STATUS` entry of a response. 25.x firmware answers `ascset` with a single object
+/// instead of a list.
+pub(super) fn status_entry(response: &Value) -> Option<&Value> {
+    match response.get("STATUS")? {
+        Value::Array(entries) => entries.first(),
+        entry @ Value::Object(_) => Some(entry),
+        _ => None,
+    }
+}
+
 #[async_trait]
 impl RPCAPIClient for AvalonMinerRPCAPI {
     async fn send_command(
@@ -149,6 +170,7 @@ impl RPCAPIClient for AvalonMinerRPCAPI {
         _privileged: bool,
         param: Option<Value>,
     ) -> Result<Value> {
+        self.policy.check(command)?;
         let cmd = match param {
             Some(params) => json!({
                 "command": command,
@@ -159,6 +181,7 @@ impl RPCAPIClient for AvalonMinerRPCAPI {
             }),
         };
 
+        let _connection = self.connections.acquire().await;
         let stream = tokio::net::TcpStream::connect(format!("{}:{}", self.ip, self.port))
             .await
             .map_err(|_| RPCError::ConnectionFailed)?;
@@ -167,8 +190,7 @@ impl RPCAPIClient for AvalonMinerRPCAPI {
         let json_str = cmd.to_string();
         stream.write_all(json_str.as_bytes()).await?;
 
-        let mut buffer = Vec::new();
-        stream.read_to_end(&mut buffer).await?;
+        let buffer = reader::read_to_end(&mut stream, &self.read_limits).await?;
 
         if buffer.is_empty() {
             bail!("No data received from miner");
@@ -193,10 +215,7 @@ impl APIClient for AvalonMinerRPCAPI {
             MinerCommand::RPC {
                 command,
                 parameters,
-            } => self
-                .send_command(command, false, parameters.clone())
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => self.send_command(command, false, parameters.clone()).await,
             _ => Err(anyhow!("Cannot send non RPC command to RPC API")),
         }
     }
@@ -270,6 +289,17 @@ mod parse_rpc_result_nested_tests {
         assert_eq!(val.pointer("/STATS/0/HBinfo/HB0"), Some(&json!({})));
     }
 
+    #[test]
+    fn status_object_is_accepted() {
+        let resp =
+            r#"{"STATUS":{"STATUS":"I","When":1718000000,"Code":118,"Msg":"set OK"},"id":1}"#;
+        let val = test_rpc().parse_rpc_result(resp).unwrap();
+        assert_eq!(val.pointer("/STATUS/Msg"), Some(&json!("set OK")));
+
+        let resp = r#"{"STATUS":{"STATUS":"E","Msg":"Invalid parameter"},"id":1}"#;
+        assert!(test_rpc().parse_rpc_result(resp).is_err());
+    }
+
     #[test]
     fn version_command_returns_version() {
         let val = test_rpc().parse_rpc_result(VERSION_COMMAND).unwrap();
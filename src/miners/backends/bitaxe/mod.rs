@@ -1,11 +1,19 @@
+use anyhow::Result;
+use reqwest::Method;
 use semver;
+use serde_json::{Map, Value, json};
 use std::net::IpAddr;
 
 pub use v2_0_0::Bitaxe200;
 pub use v2_9_0::Bitaxe290;
 
 use crate::data::device::MinerModel;
+use crate::data::fan::{FanControl, FanMode};
+use crate::data::pool::{PoolConfig, PoolData, PoolScheme, PoolURL};
+use crate::miners::backends::errors::ControlError;
 use crate::miners::backends::traits::*;
+use crate::miners::data::{DataExtensions, DataField, FieldData};
+use v2_0_0::web::BitaxeWebAPI;
 
 pub mod v2_0_0;
 pub mod v2_9_0;
@@ -31,3 +39,342 @@ impl MinerConstructor for Bitaxe {
         }
     }
 }
+
+/// AxeOS holds a primary pool and, since the fallback stratum was added, a fallback pool.
+const POOL_SLOTS: usize = 2;
+
+/// The `system/info` and `PATCH /api/system` keys of a pool slot.
+struct PoolKeys {
+    url: &'static str,
+    port: &'static str,
+    user: &'static str,
+    password: &'static str,
+}
+
+const PRIMARY: PoolKeys = PoolKeys {
+    url: "stratumURL",
+    port: "stratumPort",
+    user: "stratumUser",
+    password: "stratumPassword",
+};
+
+const FALLBACK: PoolKeys = PoolKeys {
+    url: "fallbackStratumURL",
+    port: "fallbackStratumPort",
+    user: "fallbackStratumUser",
+    password: "fallbackStratumPassword",
+};
+
+/// Whether `system/info` comes from firmware with a fallback stratum.
+fn supports_fallback(info: &Value) -> bool {
+    info.get(FALLBACK.url).is_some()
+}
+
+/// The primary pool, and the fallback pool on firmware that has one configured.
+///
+/// The share counters are for the current session, so they belong to the pool in use.
+fn parse_pools(data: &FieldData) -> Vec<PoolData> {
+    let using_fallback =
+        data.extract_nested_or::<bool>(DataField::Pools, "isUsingFallbackStratum", false);
+    let accepted_shares = data.extract_nested::<u64>(DataField::Pools, "sharesAccepted");
+    let rejected_shares = data.extract_nested::<u64>(DataField::Pools, "sharesRejected");
+
+    let pool = |position: u16, keys: &PoolKeys, active: bool| {
+        let host = data
+            .extract_nested::<String>(DataField::Pools, keys.url)
+            .filter(|host| !host.is_empty())?;
+        let port = data.extract_nested_or::<u64>(DataField::Pools, keys.port, 0);
+        Some(PoolData {
+            position: Some(position),
+            url: Some(PoolURL {
+                scheme: PoolScheme::StratumV1,
+                host,
+                port: port as u16,
+                pubkey: None,
+            }),
+            accepted_shares: accepted_shares.filter(|_| active),
+            rejected_shares: rejected_shares.filter(|_| active),
+            active: Some(active),
+            alive: None,
+            user: data.extract_nested::<String>(DataField::Pools, keys.user),
+            password: None,
+            last_share_difficulty: None,
+            last_share_time: None,
+            group: None,
+            quota: None,
+        })
+    };
+
+    pool(0, &PRIMARY, !using_fallback)
+        .into_iter()
+        .chain(pool(1, &FALLBACK, using_fallback))
+        .collect()
+}
+
+/// The fan mode from `autofanspeed`, with `fanspeed` as the duty cycle, which is the configured
+/// speed in manual mode and the speed AxeOS picked in auto mode.
+fn parse_fan_control(data: &FieldData) -> Option<FanControl> {
+    let mode = match data.extract_nested::<bool>(DataField::FanControl, "autofanspeed")? {
+        true => FanMode::Auto,
+        false => FanMode::Manual,
+    };
+    Some(FanControl {
+        mode,
+        target_pct: data
+            .extract_nested::<u64>(DataField::FanControl, "fanspeed")
+            .filter(|pct| *pct <= 100)
+            .map(|pct| pct as u8),
+    })
+}
+
+/// AxeOS only speaks Stratum V1 over plain TCP, and takes the host and port separately.
+fn validate_pool_url(url: &PoolURL) -> Result<(), ControlError> {
+    if url.scheme != PoolScheme::StratumV1 {
+        return Err(ControlError::InvalidParameter(format!(
+            "{url} is not a stratum+tcp pool, the only kind AxeOS supports"
+        )));
+    }
+    if url.host.is_empty() || url.port == 0 {
+        return Err(ControlError::InvalidParameter(format!(
+            "{url} needs a host and a port"
+        )));
+    }
+    Ok(())
+}
+
+/// The `PATCH /api/system` body that writes `pools`, the first being the primary.
+///
+/// A single pool clears the fallback on firmware that has one, so it doesn't linger.
+fn pools_payload(pools: &[PoolConfig], fallback: bool) -> Result<Value, ControlError> {
+    if pools.len() > 1 && !fallback {
+        return Err(ControlError::UnsupportedOperation(
+            "setting a fallback pool on firmware without a fallback stratum".to_string(),
+        ));
+    }
+
+    let mut payload = Map::new();
+    let mut write = |keys: &PoolKeys, pool: Option<&PoolConfig>| match pool {
+        Some(pool) => {
+            payload.insert(keys.url.into(), json!(pool.url.host));
+            payload.insert(keys.port.into(), json!(pool.url.port));
+            payload.insert(keys.user.into(), json!(pool.user));
+            payload.insert(keys.password.into(), json!(pool.password_or_default()));
+        }
+        None => {
+            payload.insert(keys.url.into(), json!(""));
+            payload.insert(keys.user.into(), json!(""));
+        }
+    };
+    write(&PRIMARY, pools.first());
+    if fallback {
+        write(&FALLBACK, pools.get(1));
+    }
+    Ok(Value::Object(payload))
+}
+
+/// Write the pools through `PATCH /api/system`, checking `system/info` first for whether the
+/// firmware has a fallback stratum.
+async fn set_pools<M: DryRunControl + CommandPolicyControl + Sync>(
+    miner: &M,
+    web: &BitaxeWebAPI,
+    pools: &[PoolConfig],
+) -> Result<bool> {
+    validate_pool_count(pools, POOL_SLOTS)?;
+    validate_unique_pools(pools)?;
+    for pool in pools {
+        validate_pool_url(&pool.url)?;
+    }
+    let info = web
+        .send_command("system/info", false, None, Method::GET)
+        .await?;
+    let payload = pools_payload(pools, supports_fallback(&info))?;
+    execute_control(miner, "system", payload.clone(), async {
+        accepted(web.patch_system(payload.clone()).await)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::bitaxe::BitaxeModel;
+    use crate::test::json::bitaxe::v2_0_0::{SYSTEM_INFO_COMMAND, SYSTEM_INFO_SINGLE_POOL};
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn pools(info: &str) -> Vec<PoolData> {
+        parse_pools(&FieldData::from([(
+            DataField::Pools,
+            Value::from_str(info).unwrap(),
+        )]))
+    }
+
+    fn pool(url: &str, user: &str) -> PoolConfig {
+        PoolConfig::new(PoolURL::from(url.to_string()), user, None)
+    }
+
+    #[test]
+    fn test_bitaxe_pools() {
+        let with_fallback = pools(SYSTEM_INFO_COMMAND);
+        assert_eq!(with_fallback.len(), 2);
+        assert_eq!(with_fallback[0].position, Some(0));
+        assert_eq!(with_fallback[0].active, Some(true));
+        assert_eq!(with_fallback[0].accepted_shares, Some(0));
+        assert_eq!(
+            with_fallback[1].url,
+            Some(PoolURL::from(
+                "stratum+tcp://btc2.example.pool:3333".to_string()
+            ))
+        );
+        assert_eq!(with_fallback[1].active, Some(false));
+        assert_eq!(with_fallback[1].accepted_shares, None);
+
+        let mut info = Value::from_str(SYSTEM_INFO_COMMAND).unwrap();
+        info["isUsingFallbackStratum"] = json!(1);
+        let on_fallback = pools(&info.to_string());
+        assert_eq!(on_fallback[0].active, Some(false));
+        assert_eq!(on_fallback[1].active, Some(true));
+
+        let single = pools(SYSTEM_INFO_SINGLE_POOL);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].active, Some(true));
+        assert_eq!(single[0].user.as_deref(), Some("asic-rs.test"));
+    }
+
+    /// Serve `info` for `GET /api/system/info` and record the body of every `PATCH /api/system`.
+    async fn serve_axeos(info: &'static str) -> (u16, Arc<Mutex<Vec<Value>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let patches = Arc::new(Mutex::new(Vec::new()));
+        let recorded = patches.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let patches = patches.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let body_start = loop {
+                        let n = stream.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                            break end + 4;
+                        }
+                    };
+                    let head = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+                    let length = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .and_then(|length| length.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    while request.len() < body_start + length {
+                        let n = stream.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            break;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    let body = if head.starts_with("patch /api/system ") {
+                        let patch = serde_json::from_slice(&request[body_start..]).unwrap();
+                        patches.lock().unwrap().push(patch);
+                        ""
+                    } else {
+                        info
+                    };
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (port, recorded)
+    }
+
+    #[tokio::test]
+    async fn test_bitaxe_set_pools() -> Result<()> {
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let miner = Bitaxe290::new(ip, MinerModel::Bitaxe(BitaxeModel::Gamma));
+        let primary = pool("stratum+tcp://pool.example.com:3333", "worker.1");
+        let backup = PoolConfig::new(
+            PoolURL::from("stratum+tcp://backup.example.com:443".to_string()),
+            "worker.1",
+            Some("secret".to_string()),
+        );
+
+        let (port, patches) = serve_axeos(SYSTEM_INFO_COMMAND).await;
+        let web = BitaxeWebAPI::new(ip, port);
+        assert!(set_pools(&miner, &web, &[primary.clone(), backup.clone()]).await?);
+        assert!(set_pools(&miner, &web, std::slice::from_ref(&primary)).await?);
+        assert_eq!(
+            *patches.lock().unwrap(),
+            vec![
+                json!({
+                    "stratumURL": "pool.example.com",
+                    "stratumPort": 3333,
+                    "stratumUser": "worker.1",
+                    "stratumPassword": "x",
+                    "fallbackStratumURL": "backup.example.com",
+                    "fallbackStratumPort": 443,
+                    "fallbackStratumUser": "worker.1",
+                    "fallbackStratumPassword": "secret",
+                }),
+                // a single pool clears the fallback
+                json!({
+                    "stratumURL": "pool.example.com",
+                    "stratumPort": 3333,
+                    "stratumUser": "worker.1",
+                    "stratumPassword": "x",
+                    "fallbackStratumURL": "",
+                    "fallbackStratumUser": "",
+                }),
+            ]
+        );
+
+        let error = set_pools(
+            &miner,
+            &web,
+            &[primary.clone(), backup.clone(), primary.clone()],
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        let tls = pool("stratum+ssl://pool.example.com:4443", "worker.1");
+        let error = set_pools(&miner, &web, &[tls]).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+
+        // firmware without a fallback stratum only takes the primary pool
+        let (port, patches) = serve_axeos(SYSTEM_INFO_SINGLE_POOL).await;
+        let web = BitaxeWebAPI::new(ip, port);
+        let error = set_pools(&miner, &web, &[primary.clone(), backup])
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::UnsupportedOperation(_))
+        ));
+        assert!(patches.lock().unwrap().is_empty());
+        assert!(set_pools(&miner, &web, &[primary]).await?);
+        assert_eq!(
+            patches.lock().unwrap()[0],
+            json!({
+                "stratumURL": "pool.example.com",
+                "stratumPort": 3333,
+                "stratumUser": "worker.1",
+                "stratumPassword": "x",
+            })
+        );
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use serde_json::Value;
 use std::{net::IpAddr, time::Duration};
 use tokio::time::timeout;
 
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 
@@ -16,6 +17,7 @@ pub struct BitaxeWebAPI {
     port: u16,
     timeout: Duration,
     retries: u32,
+    pub(crate) policy: CommandPolicy,
 }
 
 #[async_trait]
@@ -53,10 +55,10 @@ impl APIClient for BitaxeWebAPI {
             MinerCommand::WebAPI {
                 command,
                 parameters,
-            } => self
-                .send_command(command, false, parameters.clone(), Method::GET)
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => {
+                self.send_command(command, false, parameters.clone(), Method::GET)
+                    .await
+            }
             _ => Err(anyhow!("Cannot send non web command to web API")),
         }
     }
@@ -72,6 +74,11 @@ impl WebAPIClient for BitaxeWebAPI {
         parameters: Option<Value>,
         method: Method,
     ) -> Result<Value> {
+        if method == Method::GET {
+            self.policy.check(command)?;
+        } else {
+            self.policy.check_write(command)?;
+        }
         let url = format!("http://{}:{}/api/{}", self.ip, self.port, command);
 
         for attempt in 0..=self.retries {
@@ -122,7 +129,21 @@ impl BitaxeWebAPI {
             port,
             timeout: Duration::from_secs(5),
             retries: 1,
+            policy: CommandPolicy::default(),
+        }
+    }
+
+    /// Update system settings, AxeOS replies with an empty body so only the status is checked
+    pub async fn patch_system(&self, config: Value) -> Result<()> {
+        self.policy.check_write("system")?;
+        let url = format!("http://{}:{}/api/system", self.ip, self.port);
+        let response = self
+            .execute_request(&url, &Method::PATCH, Some(config))
+            .await?;
+        if !response.status().is_success() {
+            Err(BitaxeError::HttpError(response.status().as_u16()))?
         }
+        Ok(())
     }
 
     /// Execute the actual HTTP request
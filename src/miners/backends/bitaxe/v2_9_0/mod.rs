@@ -2,23 +2,30 @@ use anyhow::{Result, anyhow, bail};
 use async_trait::async_trait;
 use macaddr::MacAddr;
 use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::str::FromStr;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::data::board::{BoardData, ChipData};
-use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerModel};
+use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerModel};
 use crate::data::device::{MinerControlBoard, MinerMake};
-use crate::data::fan::FanData;
+use crate::data::fan::{FanControl, FanData};
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::message::{MessageSeverity, MinerMessage};
-use crate::data::pool::{PoolData, PoolScheme, PoolURL};
+use crate::data::health::HealthThresholds;
+use crate::data::message::{MessageSeverity, MinerMessage, MinerMessageKind};
+use crate::data::miner::WattageLocation;
+use crate::data::pool::{PoolConfig, PoolData};
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_key,
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData, get_by_key,
     get_by_pointer,
 };
 use web::BitaxeWebAPI;
@@ -30,6 +37,10 @@ pub struct Bitaxe290 {
     ip: IpAddr,
     web: BitaxeWebAPI,
     device_info: DeviceInfo,
+    labels: Option<HashMap<String, String>>,
+    health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
 }
 
 impl Bitaxe290 {
@@ -43,6 +54,10 @@ impl Bitaxe290 {
                 MinerFirmware::Stock,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
         }
     }
 }
@@ -144,6 +159,14 @@ impl GetDataLocations for Bitaxe290 {
                     tag: None,
                 },
             )],
+            DataField::FanControl => vec![(
+                system_info_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some(""),
+                    tag: None,
+                },
+            )],
             DataField::Fans => vec![(
                 system_info_cmd,
                 DataExtractor {
@@ -196,7 +219,67 @@ impl GetIP for Bitaxe290 {
 }
 impl GetDeviceInfo for Bitaxe290 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for Bitaxe290 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for Bitaxe290 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for Bitaxe290 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for Bitaxe290 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for Bitaxe290 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.web.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.web.policy = policy;
+    }
+}
+
+impl GetCapabilities for Bitaxe290 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_hostname: true,
+            supports_pool_update: true,
+            has_chip_telemetry: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -207,41 +290,40 @@ impl CollectData for Bitaxe290 {
 }
 
 impl GetMAC for Bitaxe290 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
         data.extract::<String>(DataField::Mac)
-            .and_then(|s| MacAddr::from_str(&s).ok())
+            .and_then(|s| parse_mac_str(&s))
     }
 }
 
+impl GetReportedIp for Bitaxe290 {}
+
 impl GetSerialNumber for Bitaxe290 {
     // N/A
 }
 impl GetHostname for Bitaxe290 {
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::Hostname)
     }
 }
 impl GetApiVersion for Bitaxe290 {
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::ApiVersion)
     }
 }
 impl GetFirmwareVersion for Bitaxe290 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
 }
 impl GetControlBoardVersion for Bitaxe290 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
         data.extract::<String>(DataField::ControlBoardVersion)
             .and_then(|s| MinerControlBoard::from_str(&s).ok())
     }
 }
 impl GetHashboards for Bitaxe290 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         // Extract nested values with type conversion
         let board_voltage = data.extract_nested_map::<f64, _>(
             DataField::Hashboards,
@@ -290,6 +372,7 @@ impl GetHashboards for Bitaxe290 {
             tuned: Some(true),
             working: Some(true),
             hashrate: board_hashrate.clone(),
+            estimated: false,
         };
 
         let board_data = BoardData {
@@ -303,17 +386,21 @@ impl GetHashboards for Bitaxe290 {
             working_chips: total_chips,
             serial_number: None,
             chips: vec![chip_info],
+            sensor_temperatures: vec![],
             voltage: board_voltage,
             frequency: board_frequency,
+            wattage: None,
             tuned: Some(true),
             active: Some(true),
+            status: None,
+            extra: None,
         };
 
         vec![board_data]
     }
 }
 impl GetHashrate for Bitaxe290 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::Hashrate, |f| HashRate {
             value: f,
             unit: HashRateUnit::GigaHash,
@@ -323,7 +410,7 @@ impl GetHashrate for Bitaxe290 {
 }
 
 impl GetExpectedHashrate for Bitaxe290 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| HashRate {
             value: f,
             unit: HashRateUnit::GigaHash,
@@ -331,8 +418,10 @@ impl GetExpectedHashrate for Bitaxe290 {
         })
     }
 }
+
+impl GetAverageHashrate for Bitaxe290 {}
 impl GetFans for Bitaxe290 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         data.extract_map_or::<f64, _>(DataField::Fans, Vec::new(), |f| {
             vec![FanData {
                 position: 0,
@@ -344,105 +433,83 @@ impl GetFans for Bitaxe290 {
 impl GetPsuFans for Bitaxe290 {
     // N/A
 }
+
+impl GetFanControl for Bitaxe290 {
+    fn parse_fan_control(&self, data: &FieldData) -> Option<FanControl> {
+        super::parse_fan_control(data)
+    }
+}
+
+impl GetPsu for Bitaxe290 {}
+
+impl GetPsuRails for Bitaxe290 {}
 impl GetFluidTemperature for Bitaxe290 {
     // N/A
 }
+
+impl GetFluidFlow for Bitaxe290 {}
+
+impl GetFluidPressure for Bitaxe290 {}
+
 impl GetWattage for Bitaxe290 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<f64, _>(DataField::Wattage, Power::from_watts)
     }
+
+    // measured on the board's DC input, after the external power brick
+    fn parse_wattage_location(&self, _data: &FieldData) -> WattageLocation {
+        WattageLocation::DCBus
+    }
 }
 impl GetWattageLimit for Bitaxe290 {
     // N/A
 }
+impl GetFrequency for Bitaxe290 {}
+impl GetVoltage for Bitaxe290 {}
+impl GetWorkMode for Bitaxe290 {}
+impl GetAutotune for Bitaxe290 {}
 impl GetLightFlashing for Bitaxe290 {
     // N/A
 }
 impl GetMessages for Bitaxe290 {
-    fn parse_messages(&self, data: &HashMap<DataField, Value>) -> Vec<MinerMessage> {
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
         let mut messages = Vec::new();
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
-            .expect("Failed to get system time")
+            .unwrap_or_default()
             .as_secs();
 
         let is_overheating = data.extract_nested::<bool>(DataField::Hashboards, "overheat_mode");
 
         if let Some(true) = is_overheating {
-            messages.push(MinerMessage {
-                timestamp: timestamp as u32,
-                code: 0u64,
-                message: "Overheat Mode is Enabled!".to_string(),
-                severity: MessageSeverity::Warning,
-            });
+            messages.push(
+                MinerMessage::new(
+                    timestamp as u32,
+                    0,
+                    "Overheat Mode is Enabled!".to_string(),
+                    MessageSeverity::Warning,
+                )
+                .with_kind(MinerMessageKind::OverTemperature),
+            );
         };
         messages
     }
 }
 impl GetUptime for Bitaxe290 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
     }
 }
 impl GetIsMining for Bitaxe290 {
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
         let hashrate = self.parse_hashrate(data);
         hashrate.as_ref().is_some_and(|hr| hr.value > 0.0)
     }
 }
+impl GetStatus for Bitaxe290 {}
 impl GetPools for Bitaxe290 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
-        let main_url =
-            data.extract_nested_or::<String>(DataField::Pools, "stratumURL", String::new());
-        let main_port = data.extract_nested_or::<u64>(DataField::Pools, "stratumPort", 0);
-        let accepted_share = data.extract_nested::<u64>(DataField::Pools, "sharesAccepted");
-        let rejected_share = data.extract_nested::<u64>(DataField::Pools, "sharesRejected");
-        let main_user = data.extract_nested::<String>(DataField::Pools, "stratumUser");
-
-        let is_using_fallback =
-            data.extract_nested_or::<bool>(DataField::Pools, "isUsingFallbackStratum", false);
-
-        let main_pool_url = PoolURL {
-            scheme: PoolScheme::StratumV1,
-            host: main_url,
-            port: main_port as u16,
-            pubkey: None,
-        };
-
-        let main_pool_data = PoolData {
-            position: Some(0),
-            url: Some(main_pool_url),
-            accepted_shares: accepted_share,
-            rejected_shares: rejected_share,
-            active: Some(!is_using_fallback),
-            alive: None,
-            user: main_user,
-        };
-
-        // Extract fallback pool data
-        let fallback_url =
-            data.extract_nested_or::<String>(DataField::Pools, "fallbackStratumURL", String::new());
-        let fallback_port =
-            data.extract_nested_or::<u64>(DataField::Pools, "fallbackStratumPort", 0);
-        let fallback_user = data.extract_nested(DataField::Pools, "fallbackStratumUser");
-        let fallback_pool_url = PoolURL {
-            scheme: PoolScheme::StratumV1,
-            host: fallback_url,
-            port: fallback_port as u16,
-            pubkey: None,
-        };
-
-        let fallback_pool_data = PoolData {
-            position: Some(1),
-            url: Some(fallback_pool_url),
-            accepted_shares: accepted_share,
-            rejected_shares: rejected_share,
-            active: Some(is_using_fallback),
-            alive: None,
-            user: fallback_user,
-        };
-
-        vec![main_pool_data, fallback_pool_data]
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
+        super::parse_pools(data)
     }
 }
 
@@ -450,7 +517,9 @@ impl GetPools for Bitaxe290 {
 impl SetFaultLight for Bitaxe290 {
     #[allow(unused_variables)]
     async fn set_fault_light(&self, fault: bool) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting the fault light".to_string(),
+        ))?
     }
 }
 
@@ -458,14 +527,78 @@ impl SetFaultLight for Bitaxe290 {
 impl SetPowerLimit for Bitaxe290 {
     #[allow(unused_variables)]
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting a power limit".to_string(),
+        ))?
+    }
+}
+
+impl SetCredentials for Bitaxe290 {}
+
+impl EndpointControl for Bitaxe290 {}
+
+#[async_trait]
+impl SetPassword for Bitaxe290 {
+    #[allow(unused_variables)]
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the password".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetHostname for Bitaxe290 {
+    /// AxeOS only picks up the new hostname after a restart.
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        validate_hostname(hostname)?;
+        let payload = json!({"hostname": hostname});
+        let applied = execute_control(self, "system", payload.clone(), async {
+            accepted(self.web.patch_system(payload.clone()).await)
+        })
+        .await?;
+        if !applied {
+            bail!("Failed to set hostname");
+        }
+        Ok(HostnameChange::RebootRequired)
+    }
+}
+
+#[async_trait]
+impl SetPools for Bitaxe290 {
+    /// Writes the primary pool and, on firmware with a fallback stratum, the fallback pool.
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        super::set_pools(self, &self.web, &pools).await
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for Bitaxe290 {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
     }
 }
 
 #[async_trait]
 impl Restart for Bitaxe290 {
-    async fn restart(&self) -> Result<bool> {
-        bail!("Unsupported command");
+    async fn restart_mining(&self) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "restarting the mining process".to_string(),
+        ))?
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "rebooting the system".to_string(),
+        ))?
     }
 }
 
@@ -473,7 +606,7 @@ impl Restart for Bitaxe290 {
 impl Pause for Bitaxe290 {
     #[allow(unused_variables)]
     async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation("pausing".to_string()))?
     }
 }
 
@@ -481,6 +614,8 @@ impl Pause for Bitaxe290 {
 impl Resume for Bitaxe290 {
     #[allow(unused_variables)]
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation("resuming".to_string()))?
     }
 }
+
+impl SetRPCReadLimits for Bitaxe290 {}
@@ -1,15 +1,24 @@
 use crate::data::board::BoardData;
 use crate::data::device::{
-    DeviceInfo, HashAlgorithm, MinerControlBoard, MinerFirmware, MinerMake, MinerModel,
+    DeviceInfo, HashAlgorithm, MinerControlBoard, MinerFirmware, MinerHardware, MinerMake,
+    MinerModel, PowerLimits,
 };
 use crate::data::fan::FanData;
 use crate::data::hashrate::{HashRate, HashRateUnit};
+use crate::data::health::HealthThresholds;
 use crate::data::message::{MessageSeverity, MinerMessage};
-use crate::data::pool::{PoolData, PoolURL};
+use crate::data::miner::MinerStatus;
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_pointer,
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData,
+    get_by_pointer,
 };
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
@@ -20,7 +29,6 @@ use reqwest::Method;
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
-use std::str::FromStr;
 use std::time::Duration;
 use web::BraiinsWebAPI;
 
@@ -31,6 +39,10 @@ pub struct BraiinsV2507 {
     pub ip: IpAddr,
     pub web: BraiinsWebAPI,
     pub device_info: DeviceInfo,
+    pub labels: Option<HashMap<String, String>>,
+    pub health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
 }
 
 impl BraiinsV2507 {
@@ -39,11 +51,15 @@ impl BraiinsV2507 {
             ip,
             web: BraiinsWebAPI::new(ip),
             device_info: DeviceInfo::new(
-                MinerMake::from(model),
+                MinerMake::from(&model),
                 model,
                 MinerFirmware::BraiinsOS,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
         }
     }
 }
@@ -202,7 +218,7 @@ impl GetDataLocations for BraiinsV2507 {
                 pools_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/0/pools"), // assuming there is 1 pool group
+                    key: Some(""),
                     tag: None,
                 },
             )],
@@ -251,7 +267,68 @@ impl GetIP for BraiinsV2507 {
 
 impl GetDeviceInfo for BraiinsV2507 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for BraiinsV2507 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for BraiinsV2507 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for BraiinsV2507 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for BraiinsV2507 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for BraiinsV2507 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.web.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.web.policy = policy;
+    }
+}
+
+impl GetCapabilities for BraiinsV2507 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_fault_light: true,
+            supports_power_limit: true,
+            supports_restart: true,
+            supports_pause: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -262,20 +339,22 @@ impl CollectData for BraiinsV2507 {
 }
 
 impl GetMAC for BraiinsV2507 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
         data.extract::<String>(DataField::Mac)
-            .and_then(|s| MacAddr::from_str(&s).ok())
+            .and_then(|s| parse_mac_str(&s))
     }
 }
 
+impl GetReportedIp for BraiinsV2507 {}
+
 impl GetHostname for BraiinsV2507 {
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::Hostname)
     }
 }
 
 impl GetApiVersion for BraiinsV2507 {
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         let major = data.extract_nested::<f64>(DataField::ApiVersion, "major");
         let minor = data.extract_nested::<f64>(DataField::ApiVersion, "minor");
         let patch = data.extract_nested::<f64>(DataField::ApiVersion, "patch");
@@ -285,13 +364,13 @@ impl GetApiVersion for BraiinsV2507 {
 }
 
 impl GetFirmwareVersion for BraiinsV2507 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
 }
 
 impl GetHashboards for BraiinsV2507 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         let mut hashboards: Vec<BoardData> = Vec::new();
 
         let chains_data = data.get(&DataField::Hashboards).and_then(|v| v.as_array());
@@ -356,10 +435,14 @@ impl GetHashboards for BraiinsV2507 {
                     working_chips,
                     serial_number,
                     chips: Vec::new(),
+                    sensor_temperatures: vec![],
                     voltage,
                     frequency,
+                    wattage: None,
                     tuned: None, // Can maybe be parsed later from tuner status endpoint
                     active,
+                    status: None,
+                    extra: None,
                 });
             }
         }
@@ -369,7 +452,7 @@ impl GetHashboards for BraiinsV2507 {
 }
 
 impl GetHashrate for BraiinsV2507 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::Hashrate, |f| HashRate {
             value: f,
             unit: HashRateUnit::GigaHash,
@@ -379,7 +462,7 @@ impl GetHashrate for BraiinsV2507 {
 }
 
 impl GetExpectedHashrate for BraiinsV2507 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| HashRate {
             value: f,
             unit: HashRateUnit::GigaHash,
@@ -388,8 +471,10 @@ impl GetExpectedHashrate for BraiinsV2507 {
     }
 }
 
+impl GetAverageHashrate for BraiinsV2507 {}
+
 impl GetFans for BraiinsV2507 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut fans: Vec<FanData> = Vec::new();
 
         if let Some(fans_data) = data.get(&DataField::Fans)
@@ -414,83 +499,149 @@ impl GetFans for BraiinsV2507 {
 }
 
 impl GetLightFlashing for BraiinsV2507 {
-    fn parse_light_flashing(&self, data: &HashMap<DataField, Value>) -> Option<bool> {
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
         data.extract::<bool>(DataField::LightFlashing)
     }
 }
 
 impl GetUptime for BraiinsV2507 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
     }
 }
 
 impl GetIsMining for BraiinsV2507 {
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
+    }
+}
+
+impl GetStatus for BraiinsV2507 {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
         // 1 -> Not Started
         // 2 -> Normal
         // 3 -> Paused
         // 4 -> Suspended
         // See: https://github.com/braiins/bos-plus-api/blob/ef28e752f80711c54d5587ec8f2cd838fdb34042/proto/bos/v1/miner.proto#L117-L124
-        data.extract::<u64>(DataField::IsMining) == Some(2)
+        match data.extract::<u64>(DataField::IsMining) {
+            Some(1) => MinerStatus::Starting,
+            Some(2) => MinerStatus::Mining,
+            Some(3 | 4) => MinerStatus::Paused,
+            _ => MinerStatus::Unknown,
+        }
     }
 }
 
 impl GetPools for BraiinsV2507 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
-        let mut pools: Vec<PoolData> = Vec::new();
-
-        if let Some(pools_data) = data.get(&DataField::Pools)
-            && let Some(pools_array) = pools_data.as_array()
-        {
-            for (idx, pool) in pools_array.iter().enumerate() {
-                let url = pool
-                    .pointer("/url")
-                    .and_then(|v| v.as_str())
-                    .map(String::from)
-                    .map(PoolURL::from);
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
+        let Some(groups) = data.get(&DataField::Pools).and_then(|v| v.as_array()) else {
+            return vec![];
+        };
 
-                let user = pool
-                    .pointer("/user")
-                    .and_then(|v| v.as_str())
-                    .map(String::from);
-
-                let accepted_shares = pool
-                    .pointer("/stats/accepted_shares")
-                    .and_then(|v| v.as_u64());
-                let rejected_shares = pool
-                    .pointer("/stats/rejected_shares")
-                    .and_then(|v| v.as_u64());
-                let active = pool.pointer("/active").and_then(|v| v.as_bool());
-                let alive = pool.pointer("/alive").and_then(|v| v.as_bool());
-
-                pools.push(PoolData {
-                    position: Some(idx as u16),
-                    url,
-                    accepted_shares,
-                    rejected_shares,
-                    active,
-                    alive,
-                    user,
-                });
-            }
+        // older releases list the pools of the only group without the group around them
+        if !groups.iter().any(|g| g.get("pools").is_some()) {
+            return parse_pool_group(groups, None, None, false, 0);
         }
 
+        // with more than one group, hashrate is split between them by quota and a pool of every
+        // group can be mining at once
+        let balanced = groups.len() > 1;
+        let mut pools = Vec::new();
+        for group in groups {
+            let name = group.get("name").and_then(|v| v.as_str()).map(String::from);
+            let quota = group
+                .pointer("/quota/value")
+                .or_else(|| group.get("quota"))
+                .and_then(|v| v.as_u64())
+                .map(|q| q as u32);
+            let group_pools = group
+                .get("pools")
+                .and_then(|v| v.as_array())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            pools.extend(parse_pool_group(
+                group_pools,
+                name,
+                quota,
+                balanced,
+                pools.len(),
+            ));
+        }
         pools
     }
 }
 
+/// The pools of one group, positioned from `offset` on.
+///
+/// In `balanced` setups a pool counts as active once it has received work in this session,
+/// rather than going by the firmware's flag for the group's current pool.
+fn parse_pool_group(
+    pools: &[Value],
+    group: Option<String>,
+    quota: Option<u32>,
+    balanced: bool,
+    offset: usize,
+) -> Vec<PoolData> {
+    pools
+        .iter()
+        .enumerate()
+        .map(|(idx, pool)| {
+            let url = pool
+                .pointer("/url")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .and_then(PoolURL::parse);
+
+            let user = pool
+                .pointer("/user")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+
+            let accepted_shares = pool
+                .pointer("/stats/accepted_shares")
+                .and_then(|v| v.as_u64());
+            let rejected_shares = pool
+                .pointer("/stats/rejected_shares")
+                .and_then(|v| v.as_u64());
+            let received_work = pool
+                .pointer("/stats/generated_work")
+                .and_then(|v| v.as_u64())
+                .map(|work| work > 0 || accepted_shares.is_some_and(|shares| shares > 0));
+            let active = match (balanced, received_work) {
+                (true, Some(received_work)) => Some(received_work),
+                _ => pool.pointer("/active").and_then(|v| v.as_bool()),
+            };
+            let alive = pool.pointer("/alive").and_then(|v| v.as_bool());
+            let last_share_difficulty = pool
+                .pointer("/stats/last_difficulty")
+                .and_then(|v| v.as_f64());
+
+            PoolData {
+                position: Some((offset + idx) as u16),
+                url,
+                accepted_shares,
+                rejected_shares,
+                active,
+                alive,
+                user,
+                password: None,
+                last_share_difficulty,
+                last_share_time: None,
+                group: group.clone(),
+                quota,
+            }
+        })
+        .collect()
+}
+
 impl GetSerialNumber for BraiinsV2507 {
-    fn parse_serial_number(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_serial_number(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::SerialNumber)
     }
 }
 
 impl GetControlBoardVersion for BraiinsV2507 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
         let cb_type = data.extract::<u64>(DataField::ControlBoardVersion)?;
         match cb_type {
             0 => Some(MinerControlBoard::Unknown("".to_string())),
@@ -505,23 +656,38 @@ impl GetControlBoardVersion for BraiinsV2507 {
 }
 
 impl GetWattage for BraiinsV2507 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<i64, _>(DataField::Wattage, |w| Power::from_watts(w as f64))
     }
 }
 
 impl GetWattageLimit for BraiinsV2507 {
-    fn parse_wattage_limit(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage_limit(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<i64, _>(DataField::WattageLimit, |w| Power::from_watts(w as f64))
     }
 }
 
+impl GetFrequency for BraiinsV2507 {}
+impl GetVoltage for BraiinsV2507 {}
+impl GetWorkMode for BraiinsV2507 {}
+impl GetAutotune for BraiinsV2507 {}
+
 impl GetFluidTemperature for BraiinsV2507 {}
 
+impl GetFluidFlow for BraiinsV2507 {}
+
+impl GetFluidPressure for BraiinsV2507 {}
+
 impl GetPsuFans for BraiinsV2507 {}
 
+impl GetFanControl for BraiinsV2507 {}
+
+impl GetPsu for BraiinsV2507 {}
+
+impl GetPsuRails for BraiinsV2507 {}
+
 impl GetMessages for BraiinsV2507 {
-    fn parse_messages(&self, data: &HashMap<DataField, Value>) -> Vec<MinerMessage> {
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
         let mut messages: Vec<MinerMessage> = Vec::new();
 
         if let Some(errors_data) = data.get(&DataField::Messages)
@@ -552,38 +718,109 @@ impl GetMessages for BraiinsV2507 {
 #[async_trait]
 impl SetFaultLight for BraiinsV2507 {
     async fn set_fault_light(&self, fault: bool) -> Result<bool> {
-        Ok(self
-            .web
-            .send_command("actions/locate", true, Some(json!(fault)), Method::PUT)
-            .await
-            .is_ok())
+        let sent = execute_control(self, "actions/locate", json!(fault), async {
+            accepted(
+                self.web
+                    .send_command("actions/locate", true, Some(json!(fault)), Method::PUT)
+                    .await,
+            )
+        })
+        .await?;
+        Ok(confirm_fault_light(self, fault, sent).await)
     }
 }
 
 #[async_trait]
 impl SetPowerLimit for BraiinsV2507 {
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
-        Ok(self
-            .web
-            .send_command(
-                "performance/power-target",
-                true,
-                Some(json!({"watt": limit.as_watts() as u64})),
-                Method::PUT,
+        PowerLimits::validate_for(&self.device_info.model, limit)?;
+        self.set_power_limit_unchecked(limit).await
+    }
+
+    async fn set_power_limit_unchecked(&self, limit: Power) -> Result<bool> {
+        let target = json!({"watt": limit.as_watts() as u64});
+        execute_control(self, "performance/power-target", target.clone(), async {
+            accepted(
+                self.web
+                    .send_command("performance/power-target", true, Some(target), Method::PUT)
+                    .await,
             )
-            .await
-            .is_ok())
+        })
+        .await
+    }
+}
+
+impl SetCredentials for BraiinsV2507 {}
+
+impl EndpointControl for BraiinsV2507 {}
+
+#[async_trait]
+impl SetPassword for BraiinsV2507 {
+    #[allow(unused_variables)]
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the password".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetHostname for BraiinsV2507 {
+    #[allow(unused_variables)]
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the hostname".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPools for BraiinsV2507 {
+    #[allow(unused_variables)]
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting pools".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for BraiinsV2507 {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
     }
 }
 
 #[async_trait]
 impl Restart for BraiinsV2507 {
-    async fn restart(&self) -> Result<bool> {
-        Ok(self
-            .web
-            .send_command("actions/reboot", true, None, Method::PUT)
-            .await
-            .is_ok())
+    async fn restart_mining(&self) -> Result<bool> {
+        execute_control(self, "actions/restart", Value::Null, async {
+            accepted(
+                self.web
+                    .send_command("actions/restart", true, None, Method::PUT)
+                    .await,
+            )
+        })
+        .await
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        execute_control(self, "actions/reboot", Value::Null, async {
+            accepted(
+                self.web
+                    .send_command("actions/reboot", true, None, Method::PUT)
+                    .await,
+            )
+        })
+        .await
     }
 }
 
@@ -591,11 +828,14 @@ impl Restart for BraiinsV2507 {
 impl Pause for BraiinsV2507 {
     #[allow(unused_variables)]
     async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
-        Ok(self
-            .web
-            .send_command("actions/pause", true, None, Method::PUT)
-            .await
-            .is_ok())
+        execute_control(self, "actions/pause", Value::Null, async {
+            accepted(
+                self.web
+                    .send_command("actions/pause", true, None, Method::PUT)
+                    .await,
+            )
+        })
+        .await
     }
 }
 
@@ -603,10 +843,90 @@ impl Pause for BraiinsV2507 {
 impl Resume for BraiinsV2507 {
     #[allow(unused_variables)]
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
-        Ok(self
-            .web
-            .send_command("actions/resume", true, None, Method::PUT)
+        execute_control(self, "actions/resume", Value::Null, async {
+            accepted(
+                self.web
+                    .send_command("actions/resume", true, None, Method::PUT)
+                    .await,
+            )
+        })
+        .await
+    }
+}
+
+impl SetRPCReadLimits for BraiinsV2507 {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::test::json::braiins::v25_07::POOLS_TWO_GROUPS;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_braiins_power_limit_bounds() -> Result<()> {
+        let mut miner = BraiinsV2507::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19jPro),
+        );
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        let error = miner
+            .set_power_limit(Power::from_watts(6000.0))
             .await
-            .is_ok())
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        assert!(dry_run.actions().is_empty());
+
+        assert!(miner.set_power_limit(Power::from_watts(2500.0)).await?);
+        assert!(
+            miner
+                .set_power_limit_unchecked(Power::from_watts(6000.0))
+                .await?
+        );
+        let payloads: Vec<_> = dry_run.actions().into_iter().map(|a| a.payload).collect();
+        assert_eq!(payloads, vec![json!({"watt": 2500}), json!({"watt": 6000})]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_braiins_pool_groups() {
+        let miner = BraiinsV2507::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19jPro),
+        );
+        let groups = Value::from_str(POOLS_TWO_GROUPS).unwrap();
+
+        let data = FieldData::from([(DataField::Pools, groups.clone())]);
+        let pools = miner.parse_pools(&data);
+        assert_eq!(pools.len(), 3);
+        let positions: Vec<Option<u16>> = pools.iter().map(|p| p.position).collect();
+        assert_eq!(positions, vec![Some(0), Some(1), Some(2)]);
+        let groups_and_quotas: Vec<(Option<&str>, Option<u32>)> = pools
+            .iter()
+            .map(|p| (p.group.as_deref(), p.quota))
+            .collect();
+        assert_eq!(
+            groups_and_quotas,
+            vec![
+                (Some("Main"), Some(3)),
+                (Some("Main"), Some(3)),
+                (Some("Hosting fee"), Some(1)),
+            ]
+        );
+        // the firmware flags every pool active, only the ones that received work are mining
+        let active: Vec<Option<bool>> = pools.iter().map(|p| p.active).collect();
+        assert_eq!(active, vec![Some(true), Some(false), Some(true)]);
+        assert_eq!(pools[0].last_share_difficulty, Some(65536.0));
+
+        // a single group falls back to the firmware's flag for its current pool
+        let single = FieldData::from([(DataField::Pools, Value::Array(vec![groups[0].clone()]))]);
+        let pools = miner.parse_pools(&single);
+        assert_eq!(pools[1].active, Some(true));
+        assert_eq!(pools[1].group.as_deref(), Some("Main"));
     }
 }
@@ -5,6 +5,7 @@ use serde_json::Value;
 use std::{net::IpAddr, time::Duration};
 use tokio::sync::RwLock;
 
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 
@@ -18,6 +19,7 @@ pub struct BraiinsWebAPI {
     bearer_token: RwLock<Option<String>>,
     username: Option<String>,
     password: Option<String>,
+    pub(crate) policy: CommandPolicy,
 }
 
 #[async_trait]
@@ -27,10 +29,10 @@ impl APIClient for BraiinsWebAPI {
             MinerCommand::WebAPI {
                 command,
                 parameters,
-            } => self
-                .send_command(command, false, parameters.clone(), Method::GET)
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => {
+                self.send_command(command, false, parameters.clone(), Method::GET)
+                    .await
+            }
             _ => Err(anyhow!("Cannot send non web command to web API")),
         }
     }
@@ -46,6 +48,11 @@ impl WebAPIClient for BraiinsWebAPI {
         parameters: Option<Value>,
         method: Method,
     ) -> Result<Value> {
+        if method == Method::GET {
+            self.policy.check(command)?;
+        } else {
+            self.policy.check_write(command)?;
+        }
         // Ensure we're authenticated before making the request
         if let Err(e) = self.ensure_authenticated().await {
             return Err(anyhow!("Failed to authenticate: {}", e));
@@ -84,6 +91,7 @@ impl BraiinsWebAPI {
             bearer_token: RwLock::new(None),
             username: Some("root".to_string()), // Default user
             password: Some("root".to_string()), // Default password
+            policy: CommandPolicy::default(),
         }
     }
 
@@ -104,7 +112,8 @@ impl BraiinsWebAPI {
         Ok(())
     }
     async fn authenticate(&self, password: &str) -> Result<String, BraiinsError> {
-        let unlock_payload = serde_json::json!({ "password": password, "username": "root" });
+        let username = self.username.as_deref().unwrap_or("root");
+        let unlock_payload = serde_json::json!({ "password": password, "username": username });
         let url = format!("http://{}:{}/api/v1/auth/login", self.ip, self.port);
 
         let response = self
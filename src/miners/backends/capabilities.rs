@@ -0,0 +1,217 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+/// The control functions and telemetry a miner backend supports.
+///
+/// Filled in per backend, so it is known without talking to the miner. A supported function can
+/// still fail at runtime, such as when the firmware has its API disabled or is locked by
+/// autotuning.
+#[cfg_attr(feature = "python", pyclass(get_all, module = "asic_rs"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MinerCapabilities {
+    /// Whether the fault light can be turned on and off
+    pub supports_fault_light: bool,
+    /// Whether a power limit can be set
+    pub supports_power_limit: bool,
+    /// Whether the login password can be changed
+    pub supports_password: bool,
+    /// Whether the hostname can be changed
+    pub supports_hostname: bool,
+    /// Whether the configured pools can be replaced
+    pub supports_pool_update: bool,
+    /// Whether per-board frequency and voltage can be set
+    pub supports_board_tuning: bool,
+    /// Whether the miner can be rebooted or have its mining process restarted
+    pub supports_restart: bool,
+    /// Whether hashing can be paused and resumed
+    pub supports_pause: bool,
+    /// Whether per-chip data such as chip temperatures is reported on the boards
+    pub has_chip_telemetry: bool,
+    /// Whether power supply data such as PSU fans, input voltage or output power is reported
+    pub has_psu_telemetry: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::device::MinerModel;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::device::models::avalon::AvalonMinerModel;
+    use crate::data::device::models::bitaxe::BitaxeModel;
+    use crate::data::device::models::whatsminer::WhatsMinerModel;
+    use crate::data::pool::{PoolConfig, PoolURL};
+    #[cfg(feature = "antminer")]
+    use crate::miners::backends::antminer::{AntMinerLegacy, AntMinerV2020};
+    #[cfg(feature = "avalon")]
+    use crate::miners::backends::avalonminer::AvalonMinerV1;
+    #[cfg(feature = "bitaxe")]
+    use crate::miners::backends::bitaxe::{Bitaxe200, Bitaxe290};
+    #[cfg(feature = "braiins")]
+    use crate::miners::backends::braiins::v25_07::BraiinsV2507;
+    #[cfg(feature = "cgminer")]
+    use crate::miners::backends::cgminer::GenericCGMiner;
+    use crate::miners::backends::dry_run::DryRun;
+    #[cfg(feature = "epic")]
+    use crate::miners::backends::epic::PowerPlayV1;
+    use crate::miners::backends::errors::ControlError;
+    #[cfg(feature = "luxos")]
+    use crate::miners::backends::luxminer::v1::LuxMinerV1;
+    #[cfg(feature = "marathon")]
+    use crate::miners::backends::marathon::MaraV1;
+    use crate::miners::backends::traits::*;
+    #[cfg(feature = "vnish")]
+    use crate::miners::backends::vnish::VnishV120;
+    #[cfg(feature = "whatsminer")]
+    use crate::miners::backends::whatsminer::{WhatsMinerV1, WhatsMinerV2, WhatsMinerV3};
+    use crate::miners::data::DataField;
+    use anyhow::Result;
+    use measurements::{Frequency, Power};
+    use std::net::IpAddr;
+
+    fn backends() -> Vec<Box<dyn Miner>> {
+        // commands that read from the miner before planning fail fast with a refused connection
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let antminer = MinerModel::AntMiner(AntMinerModel::S19jPro);
+        let whatsminer = MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30);
+        let bitaxe = MinerModel::Bitaxe(BitaxeModel::Supra);
+        let avalon = MinerModel::AvalonMiner(AvalonMinerModel::Avalon1246);
+        let mut miners: Vec<Box<dyn Miner>> = Vec::new();
+        #[cfg(feature = "antminer")]
+        miners.extend([
+            Box::new(AntMinerLegacy::new(ip, antminer.clone())) as Box<dyn Miner>,
+            Box::new(AntMinerV2020::new(ip, antminer.clone())),
+        ]);
+        #[cfg(feature = "avalon")]
+        miners.push(Box::new(AvalonMinerV1::new(ip, avalon.clone())));
+        #[cfg(feature = "bitaxe")]
+        miners.extend([
+            Box::new(Bitaxe200::new(ip, bitaxe.clone())) as Box<dyn Miner>,
+            Box::new(Bitaxe290::new(ip, bitaxe.clone())),
+        ]);
+        #[cfg(feature = "braiins")]
+        miners.push(Box::new(BraiinsV2507::new(ip, antminer.clone())));
+        #[cfg(feature = "cgminer")]
+        miners.push(Box::new(GenericCGMiner::new(
+            ip,
+            MinerModel::Unknown {
+                make: crate::data::device::MinerMake::Unknown,
+                raw: "cgminer".to_string(),
+            },
+        )));
+        #[cfg(feature = "epic")]
+        miners.push(Box::new(PowerPlayV1::new(ip, antminer.clone())));
+        #[cfg(feature = "luxos")]
+        miners.push(Box::new(LuxMinerV1::new(ip, antminer.clone())));
+        #[cfg(feature = "marathon")]
+        miners.push(Box::new(MaraV1::new(ip, antminer.clone())));
+        #[cfg(feature = "vnish")]
+        miners.push(Box::new(VnishV120::new(ip, antminer.clone())));
+        #[cfg(feature = "whatsminer")]
+        miners.extend([
+            Box::new(WhatsMinerV1::new(ip, whatsminer.clone())) as Box<dyn Miner>,
+            Box::new(WhatsMinerV2::new(ip, whatsminer.clone())),
+            Box::new(WhatsMinerV3::new(ip, whatsminer.clone())),
+        ]);
+        for miner in miners.iter_mut() {
+            miner.set_dry_run(Some(DryRun::new()));
+        }
+        miners
+    }
+
+    /// Whether a control function is one of the placeholder implementations.
+    fn unsupported<T>(result: &Result<T>) -> bool {
+        result.as_ref().is_err_and(|e| {
+            matches!(
+                e.downcast_ref::<ControlError>(),
+                Some(ControlError::UnsupportedOperation(_))
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_match_implementations() {
+        let pools = vec![PoolConfig::new(
+            PoolURL::from("stratum+tcp://pool.example.com:3333".to_string()),
+            "worker",
+            None,
+        )];
+
+        for miner in backends() {
+            let name = format!("{miner:?}");
+            let name = name.split([' ', '{']).next().unwrap_or_default();
+            let capabilities = miner.capabilities();
+
+            let checks = [
+                (
+                    "supports_fault_light",
+                    capabilities.supports_fault_light,
+                    unsupported(&miner.set_fault_light(true).await),
+                ),
+                (
+                    "supports_power_limit",
+                    capabilities.supports_power_limit,
+                    unsupported(&miner.set_power_limit(Power::from_watts(3000.0)).await),
+                ),
+                (
+                    "supports_password",
+                    capabilities.supports_password,
+                    unsupported(&miner.set_password("admin", "miner").await),
+                ),
+                (
+                    "supports_hostname",
+                    capabilities.supports_hostname,
+                    unsupported(&miner.set_hostname("miner-01").await),
+                ),
+                (
+                    "supports_pool_update",
+                    capabilities.supports_pool_update,
+                    unsupported(&miner.set_pools(pools.clone()).await),
+                ),
+                (
+                    "supports_board_tuning",
+                    capabilities.supports_board_tuning,
+                    unsupported(
+                        &miner
+                            .set_board_tuning(0, Some(Frequency::from_megahertz(500.0)), None)
+                            .await,
+                    ),
+                ),
+                (
+                    "supports_restart",
+                    capabilities.supports_restart,
+                    unsupported(&miner.reboot_system().await)
+                        && unsupported(&miner.restart_mining().await),
+                ),
+                (
+                    "supports_pause",
+                    capabilities.supports_pause,
+                    unsupported(&miner.pause(None).await) || unsupported(&miner.resume(None).await),
+                ),
+            ];
+            for (capability, claimed, placeholder) in checks {
+                assert_eq!(
+                    claimed, !placeholder,
+                    "{name} {capability} does not match its implementation"
+                );
+            }
+
+            // telemetry needs a data location behind it, the parsers can't be told apart
+            if capabilities.has_psu_telemetry {
+                assert!(
+                    !miner.get_locations(DataField::PsuFans).is_empty()
+                        || !miner.get_locations(DataField::Psu).is_empty()
+                        || !miner.get_locations(DataField::PsuRails).is_empty(),
+                    "{name}"
+                );
+            }
+            if capabilities.has_chip_telemetry {
+                assert!(
+                    !miner.get_locations(DataField::Hashboards).is_empty(),
+                    "{name}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,739 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::data::board::BoardData;
+use crate::data::device::{
+    DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerMake, MinerModel,
+};
+use crate::data::fan::FanData;
+use crate::data::hashrate::{HashRate, HashRateUnit};
+use crate::data::health::HealthThresholds;
+use crate::data::miner::MinerStatus;
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::RPCReadLimits;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
+use crate::miners::backends::traits::*;
+use crate::miners::commands::MinerCommand;
+use crate::miners::data::{
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData, FromValue,
+    get_by_pointer,
+};
+
+pub(crate) use rpc::CGMinerRPCAPI;
+
+mod rpc;
+
+/// Scrypt hardware tops out around 16 GH/s, SHA256 hardware still running cgminer hashes at
+/// hundreds of GH/s or more, so an average below this is taken to be Scrypt.
+const SCRYPT_CEILING_MHS: f64 = 50_000.0;
+
+/// Fan slots looked for in each `stats` entry.
+const FAN_SLOTS: u8 = 8;
+
+/// Any miner answering the cgminer RPC API that no other backend recognized.
+///
+/// Only the fields every cgminer build reports are read: hashrate, pools and uptime from
+/// `summary` and `pools`, per device hashrate and temperature from `devs`, and fans if the
+/// drivers put `fanN` keys in `stats`. The algorithm isn't reported, so it is guessed from the
+/// hashrate.
+#[derive(Debug)]
+pub struct GenericCGMiner {
+    ip: IpAddr,
+    rpc: CGMinerRPCAPI,
+    device_info: DeviceInfo,
+    labels: Option<HashMap<String, String>>,
+    health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
+}
+
+impl GenericCGMiner {
+    pub fn new(ip: IpAddr, model: MinerModel) -> Self {
+        GenericCGMiner {
+            ip,
+            rpc: CGMinerRPCAPI::new(ip),
+            device_info: DeviceInfo::new(
+                MinerMake::Unknown,
+                model,
+                MinerFirmware::Stock,
+                HashAlgorithm::SHA256,
+            ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
+        }
+    }
+
+    /// Limit the connections the API client opens at once, by default
+    /// [`DEFAULT_CONNECTIONS`][`crate::miners::api::rpc::limit::DEFAULT_CONNECTIONS`].
+    pub fn with_connection_limit(mut self, limit: ConnectionLimit) -> Self {
+        self.rpc.connections = limit;
+        self
+    }
+
+    /// The algorithm guessed from the average hashrate, SHA256 if there is none.
+    fn algorithm(&self, data: &FieldData) -> HashAlgorithm {
+        match data.extract::<f64>(DataField::AverageHashrate) {
+            Some(mhs) if mhs > 0.0 && mhs < SCRYPT_CEILING_MHS => HashAlgorithm::Scrypt,
+            _ => HashAlgorithm::SHA256,
+        }
+    }
+}
+
+/// `mhs` in the unit rates of `algo` are usually quoted in.
+fn mhs_to_hashrate(mhs: f64, algo: HashAlgorithm) -> HashRate {
+    HashRate {
+        value: mhs,
+        unit: HashRateUnit::MegaHash,
+        algo: algo.to_string(),
+    }
+    .as_unit(algo.default_unit())
+}
+
+/// The most recent hashrate of a `summary` or `devs` entry, cgminer names the key after its
+/// log interval, which is 5 seconds unless configured otherwise.
+fn current_mhs(entry: &Value) -> Option<f64> {
+    entry
+        .get("MHS 5s")
+        .or_else(|| entry.get("MHS av"))
+        .and_then(f64::from_value)
+}
+
+#[async_trait]
+impl APIClient for GenericCGMiner {
+    async fn get_api_result(&self, command: &MinerCommand) -> Result<Value> {
+        match command {
+            MinerCommand::RPC { .. } => self.rpc.get_api_result(command).await,
+            _ => Err(anyhow!("Unsupported command type for cgminer API")),
+        }
+    }
+
+    async fn get_api_results(
+        &self,
+        commands: &[MinerCommand],
+    ) -> HashMap<MinerCommand, Result<Value>> {
+        self.rpc.get_api_results(commands).await
+    }
+}
+
+impl GetDataLocations for GenericCGMiner {
+    fn get_locations(&self, data_field: DataField) -> Vec<DataLocation> {
+        let version_cmd = MinerCommand::RPC {
+            command: "version",
+            parameters: None,
+        };
+
+        let summary_cmd = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+
+        let devs_cmd = MinerCommand::RPC {
+            command: "devs",
+            parameters: None,
+        };
+
+        let pools_cmd = MinerCommand::RPC {
+            command: "pools",
+            parameters: None,
+        };
+
+        let stats_cmd = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+
+        let pointer = |key: &'static str| DataExtractor {
+            func: get_by_pointer,
+            key: Some(key),
+            tag: None,
+        };
+
+        match data_field {
+            DataField::ApiVersion => vec![(version_cmd, pointer("/VERSION/0/API"))],
+            DataField::FirmwareVersion => vec![(version_cmd, pointer("/VERSION/0/CGMiner"))],
+            DataField::Hashrate => vec![(summary_cmd, pointer("/SUMMARY/0"))],
+            DataField::AverageHashrate => vec![(summary_cmd, pointer("/SUMMARY/0/MHS av"))],
+            DataField::Hashboards => vec![(devs_cmd, pointer("/DEVS"))],
+            DataField::Fans => vec![(stats_cmd, pointer("/STATS"))],
+            DataField::Uptime => vec![(summary_cmd, pointer("/SUMMARY/0/Elapsed"))],
+            DataField::Pools => vec![(pools_cmd, pointer("/POOLS"))],
+            _ => vec![],
+        }
+    }
+}
+
+impl GetIP for GenericCGMiner {
+    fn get_ip(&self) -> IpAddr {
+        self.ip
+    }
+}
+
+impl GetDeviceInfo for GenericCGMiner {
+    fn get_device_info(&self) -> DeviceInfo {
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+    fn parse_device_info(&self, data: &FieldData) -> DeviceInfo {
+        DeviceInfo {
+            algo: self.algorithm(data),
+            ..self.get_device_info()
+        }
+    }
+}
+
+impl GetLabels for GenericCGMiner {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for GenericCGMiner {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for GenericCGMiner {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for GenericCGMiner {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for GenericCGMiner {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.rpc.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        self.rpc.policy = policy.for_miner(self);
+    }
+}
+
+impl GetCapabilities for GenericCGMiner {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_restart: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl CollectData for GenericCGMiner {
+    fn get_collector(&self) -> DataCollector<'_> {
+        DataCollector::new(self)
+    }
+}
+
+impl GetMAC for GenericCGMiner {}
+
+impl GetReportedIp for GenericCGMiner {}
+
+impl GetSerialNumber for GenericCGMiner {}
+
+impl GetHostname for GenericCGMiner {}
+
+impl GetApiVersion for GenericCGMiner {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
+        data.extract::<String>(DataField::ApiVersion)
+    }
+}
+
+impl GetFirmwareVersion for GenericCGMiner {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
+        data.extract::<String>(DataField::FirmwareVersion)
+    }
+}
+
+impl GetControlBoardVersion for GenericCGMiner {}
+
+impl GetHashboards for GenericCGMiner {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
+        let Some(devs) = data.get(&DataField::Hashboards).and_then(|v| v.as_array()) else {
+            return vec![];
+        };
+        let algo = self.algorithm(data);
+
+        // each ASIC or FPGA device cgminer drives is reported as a board
+        devs.iter()
+            .filter(|dev| dev.get("ASC").or_else(|| dev.get("PGA")).is_some())
+            .enumerate()
+            .map(|(position, dev)| {
+                let active = dev.get("Enabled").and_then(|v| v.as_str()) == Some("Y")
+                    && dev.get("Status").and_then(|v| v.as_str()) == Some("Alive");
+                BoardData {
+                    position: position as u8,
+                    hashrate: current_mhs(dev).map(|mhs| mhs_to_hashrate(mhs, algo)),
+                    // drivers without a sensor report 0
+                    board_temperature: dev
+                        .get("Temperature")
+                        .and_then(f64::from_value)
+                        .filter(|temp| *temp != 0.0)
+                        .map(Temperature::from_celsius),
+                    active: Some(active),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+impl GetHashrate for GenericCGMiner {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        let mhs = data.get(&DataField::Hashrate).and_then(current_mhs)?;
+        Some(mhs_to_hashrate(mhs, self.algorithm(data)))
+    }
+}
+
+impl GetExpectedHashrate for GenericCGMiner {}
+
+impl GetAverageHashrate for GenericCGMiner {
+    fn parse_average_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        let algo = self.algorithm(data);
+        data.extract_map::<f64, _>(DataField::AverageHashrate, |mhs| mhs_to_hashrate(mhs, algo))
+    }
+}
+
+impl GetFans for GenericCGMiner {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
+        let Some(stats) = data.get(&DataField::Fans).and_then(|v| v.as_array()) else {
+            return vec![];
+        };
+
+        // only some drivers report fans, as `fanN` keys in their device's entry, empty slots read 0
+        let fans = |entry: &Value| {
+            (1..=FAN_SLOTS)
+                .filter_map(|idx| entry.get(format!("fan{idx}")).and_then(f64::from_value))
+                .collect::<Vec<_>>()
+        };
+        stats
+            .iter()
+            .flat_map(fans)
+            .filter(|rpm| *rpm > 0.0)
+            .enumerate()
+            .map(|(position, rpm)| FanData {
+                position: position as i16,
+                rpm: Some(AngularVelocity::from_rpm(rpm)),
+            })
+            .collect()
+    }
+}
+
+impl GetPsuFans for GenericCGMiner {}
+
+impl GetFanControl for GenericCGMiner {}
+
+impl GetPsu for GenericCGMiner {}
+
+impl GetPsuRails for GenericCGMiner {}
+
+impl GetFluidTemperature for GenericCGMiner {}
+
+impl GetFluidFlow for GenericCGMiner {}
+
+impl GetFluidPressure for GenericCGMiner {}
+
+impl GetWattage for GenericCGMiner {}
+
+impl GetWattageLimit for GenericCGMiner {}
+
+impl GetFrequency for GenericCGMiner {}
+
+impl GetVoltage for GenericCGMiner {}
+
+impl GetWorkMode for GenericCGMiner {}
+impl GetAutotune for GenericCGMiner {}
+
+impl GetLightFlashing for GenericCGMiner {}
+
+impl GetMessages for GenericCGMiner {}
+
+impl GetUptime for GenericCGMiner {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
+        data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
+    }
+}
+
+impl GetIsMining for GenericCGMiner {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
+    }
+}
+
+impl GetStatus for GenericCGMiner {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        match self.parse_hashrate(data) {
+            Some(hr) if hr.value > 0.0 => MinerStatus::Mining,
+            Some(_) => MinerStatus::Failure,
+            None => MinerStatus::Unknown,
+        }
+    }
+}
+
+impl GetPools for GenericCGMiner {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
+        let Some(pools) = data.get(&DataField::Pools).and_then(|v| v.as_array()) else {
+            return vec![];
+        };
+
+        pools
+            .iter()
+            .enumerate()
+            .map(|(idx, pool)| PoolData {
+                position: Some(idx as u16),
+                url: pool
+                    .get("URL")
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .and_then(PoolURL::parse),
+                accepted_shares: pool.get("Accepted").and_then(|v| v.as_u64()),
+                rejected_shares: pool.get("Rejected").and_then(|v| v.as_u64()),
+                active: pool.get("Stratum Active").and_then(|v| v.as_bool()),
+                alive: pool
+                    .get("Status")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s == "Alive"),
+                user: pool.get("User").and_then(|v| v.as_str()).map(String::from),
+                password: None,
+                last_share_difficulty: pool.get("Last Share Difficulty").and_then(|v| v.as_f64()),
+                // 0 until the first share
+                last_share_time: pool
+                    .get("Last Share Time")
+                    .and_then(u64::from_value)
+                    .filter(|t| *t > 0),
+                group: None,
+                quota: None,
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SetFaultLight for GenericCGMiner {
+    #[allow(unused_variables)]
+    async fn set_fault_light(&self, fault: bool) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the fault light".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPowerLimit for GenericCGMiner {
+    #[allow(unused_variables)]
+    async fn set_power_limit(&self, limit: Power) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting a power limit".to_string(),
+        ))?
+    }
+}
+
+impl SetCredentials for GenericCGMiner {}
+
+impl EndpointControl for GenericCGMiner {
+    fn set_endpoints(&mut self, endpoints: MinerEndpoints) {
+        if let Some(rpc) = endpoints.rpc {
+            self.rpc.set_address(rpc);
+        }
+    }
+}
+
+impl SetRPCReadLimits for GenericCGMiner {
+    fn set_rpc_read_limits(&mut self, limits: RPCReadLimits) {
+        self.rpc.set_read_limits(limits);
+    }
+}
+
+#[async_trait]
+impl SetPassword for GenericCGMiner {
+    #[allow(unused_variables)]
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the password".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetHostname for GenericCGMiner {
+    #[allow(unused_variables)]
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the hostname".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPools for GenericCGMiner {
+    #[allow(unused_variables)]
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting pools".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for GenericCGMiner {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl Restart for GenericCGMiner {
+    async fn restart_mining(&self) -> Result<bool> {
+        execute_control(self, "restart", Value::Null, async {
+            accepted(self.rpc.restart().await)
+        })
+        .await
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "rebooting the system".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl Pause for GenericCGMiner {
+    #[allow(unused_variables)]
+    async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation("pausing".to_string()))?
+    }
+}
+
+#[async_trait]
+impl Resume for GenericCGMiner {
+    #[allow(unused_variables)]
+    async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation("resuming".to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::message::{MessageSeverity, MinerMessageKind};
+    use crate::test::api::{MockAPIClient, MockedMiner};
+    use crate::test::json::cgminer::generic::{
+        CGMINER_DEVS, CGMINER_POOLS, CGMINER_POOLS_SOLO, CGMINER_POOLS_WEDGED, CGMINER_STATS,
+        CGMINER_SUMMARY, CGMINER_VERSION,
+    };
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_generic_cgminer() {
+        let miner = GenericCGMiner::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::Unknown {
+                make: MinerMake::Unknown,
+                raw: "cgminer".to_string(),
+            },
+        );
+
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        let results = HashMap::from([
+            (rpc("version"), Value::from_str(CGMINER_VERSION).unwrap()),
+            (rpc("summary"), Value::from_str(CGMINER_SUMMARY).unwrap()),
+            (rpc("devs"), Value::from_str(CGMINER_DEVS).unwrap()),
+            (rpc("pools"), Value::from_str(CGMINER_POOLS).unwrap()),
+            (rpc("stats"), Value::from_str(CGMINER_STATS).unwrap()),
+        ]);
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+        let miner_data = miner.parse_data(data);
+
+        // hundreds of GH/s is well above any Scrypt hardware
+        assert_eq!(miner_data.device_info.algo, HashAlgorithm::SHA256);
+        assert_eq!(miner_data.device_info.make, MinerMake::Unknown);
+        let hashrate = miner_data.hashrate.unwrap();
+        assert_eq!(hashrate.unit, HashRateUnit::TeraHash);
+        assert!((hashrate.value - 0.59876543).abs() < 1e-6);
+        assert_eq!(miner_data.uptime, Some(Duration::from_secs(86413)));
+        assert_eq!(miner_data.pools.len(), 2);
+        assert_eq!(miner_data.pools[0].active, Some(true));
+        assert_eq!(miner_data.pools[0].accepted_shares, Some(15234));
+        assert_eq!(miner_data.pools[1].user.as_deref(), Some("worker.stick1"));
+        assert_eq!(miner_data.firmware_version.as_deref(), Some("4.12.1"));
+        assert_eq!(miner_data.status, MinerStatus::Mining);
+
+        // the second device has no temperature sensor
+        assert_eq!(miner_data.hashboards.len(), 2);
+        assert_eq!(
+            miner_data.hashboards[0].board_temperature,
+            Some(Temperature::from_celsius(58.5))
+        );
+        assert_eq!(miner_data.hashboards[1].board_temperature, None);
+        assert_eq!(miner_data.fans.len(), 1);
+
+        // a few GH/s can only be Scrypt
+        let scrypt: FieldData = [(DataField::AverageHashrate, serde_json::json!(9500.0))]
+            .into_iter()
+            .collect();
+        assert_eq!(miner.algorithm(&scrypt), HashAlgorithm::Scrypt);
+        assert_eq!(
+            miner.parse_average_hashrate(&scrypt).unwrap().unit,
+            HashRateUnit::MegaHash
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generic_cgminer_status() {
+        let miner = GenericCGMiner::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::Unknown {
+                make: MinerMake::Unknown,
+                raw: "cgminer".to_string(),
+            },
+        );
+        let summary = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+        let mut stopped = Value::from_str(CGMINER_SUMMARY).unwrap();
+        stopped["SUMMARY"][0]["MHS 5s"] = serde_json::json!(0.0);
+
+        for (response, status) in [
+            (
+                Value::from_str(CGMINER_SUMMARY).unwrap(),
+                MinerStatus::Mining,
+            ),
+            (stopped, MinerStatus::Failure),
+        ] {
+            let mock_api = MockAPIClient::new(HashMap::from([(summary.clone(), response)]));
+            let mocked = MockedMiner::new(&miner, &mock_api);
+            assert_eq!(mocked.get_status().await, status);
+            assert_eq!(mocked.get_is_mining().await, status.is_mining());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generic_cgminer_read_limits() {
+        use crate::miners::api::rpc::errors::RPCError;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream.write_all(CGMINER_VERSION.as_bytes()).await;
+            }
+        });
+        let mut miner = GenericCGMiner::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::Unknown {
+                make: MinerMake::Unknown,
+                raw: "cgminer".to_string(),
+            },
+        );
+        miner.set_endpoints(MinerEndpoints {
+            rpc: Some(addr),
+            web: None,
+        });
+        assert!(miner.rpc.send_command("version", false, None).await.is_ok());
+
+        miner.set_rpc_read_limits(RPCReadLimits::default().with_max_response_size(16));
+        let error = miner
+            .rpc
+            .send_command("version", false, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(RPCError::ResponseTooLarge(16))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_generic_cgminer_stratum_wedged() {
+        let miner = GenericCGMiner::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::Unknown {
+                make: MinerMake::Unknown,
+                raw: "cgminer".to_string(),
+            },
+        );
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        let thresholds = miner.get_health_thresholds();
+
+        for (pools, wedged) in [
+            // the last share on the active pool is 40 minutes old, while the miner hashes
+            (CGMINER_POOLS_WEDGED, true),
+            (CGMINER_POOLS, false),
+            // a solo pool has no shares yet, which is normal
+            (CGMINER_POOLS_SOLO, false),
+        ] {
+            let mock_api = MockAPIClient::new(HashMap::from([
+                (rpc("summary"), Value::from_str(CGMINER_SUMMARY).unwrap()),
+                (rpc("pools"), Value::from_str(pools).unwrap()),
+            ]));
+            let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+            let miner_data = miner.parse_data(collector.collect_all().await);
+
+            assert!(miner_data.hashrate.is_some());
+            assert_eq!(thresholds.is_stratum_wedged(&miner_data), wedged);
+            let pool_errors: Vec<_> = miner_data
+                .messages
+                .iter()
+                .filter(|m| m.kind == MinerMessageKind::PoolIssue)
+                .collect();
+            assert_eq!(pool_errors.len(), wedged as usize);
+            if wedged {
+                assert_eq!(pool_errors[0].severity, MessageSeverity::Error);
+                assert!(pool_errors[0].message.starts_with("Hashing, but no share"));
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+use tokio::io::AsyncWriteExt;
+
+use crate::miners::api::rpc::batch;
+use crate::miners::api::rpc::errors::RPCError;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::{self, RPCReadLimits};
+use crate::miners::api::rpc::status::RPCCommandStatus;
+use crate::miners::backends::policy::CommandPolicy;
+use crate::miners::backends::traits::*;
+use crate::miners::commands::MinerCommand;
+
+/// The plain cgminer RPC API, without any of the vendor extensions.
+#[derive(Debug)]
+pub struct CGMinerRPCAPI {
+    ip: IpAddr,
+    port: u16,
+    pub(crate) policy: CommandPolicy,
+    pub(crate) connections: ConnectionLimit,
+    read_limits: RPCReadLimits,
+    /// Whether the miner accepts joined commands, once it has answered one
+    joined_commands: OnceLock<bool>,
+}
+
+impl CGMinerRPCAPI {
+    pub fn new(ip: IpAddr) -> Self {
+        Self {
+            ip,
+            port: 4028,
+            policy: CommandPolicy::default(),
+            connections: ConnectionLimit::default(),
+            read_limits: RPCReadLimits::default(),
+            joined_commands: OnceLock::new(),
+        }
+    }
+
+    /// Bound the size of and idle time between reads of the miner's responses.
+    pub fn set_read_limits(&mut self, limits: RPCReadLimits) {
+        self.read_limits = limits;
+    }
+
+    /// Connect to `addr` instead of the miner's IP on the default port.
+    pub fn set_address(&mut self, addr: SocketAddr) {
+        self.ip = addr.ip();
+        self.port = addr.port();
+    }
+
+    async fn send_rpc_command(&self, command: &str, parameters: Option<Value>) -> Result<Value> {
+        self.policy.check(command)?;
+        let request = match parameters {
+            Some(params) => json!({
+                "command": command,
+                "parameter": params
+            }),
+            None => json!({
+                "command": command
+            }),
+        };
+
+        let response = self.send_request(request).await?;
+        self.parse_rpc_result(&response)
+    }
+
+    /// Write `request` to the cgminer socket and read back the raw response.
+    async fn send_request(&self, request: Value) -> Result<String> {
+        let _connection = self.connections.acquire().await;
+        let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
+            .await
+            .map_err(|_| RPCError::ConnectionFailed)?;
+
+        stream.write_all(format!("{request}\n").as_bytes()).await?;
+
+        let buffer = reader::read_until(&mut stream, &self.read_limits, |b| {
+            b.contains(&0) || b.ends_with(b"\n")
+        })
+        .await?;
+        let response = String::from_utf8_lossy(&buffer);
+
+        Ok(response
+            .trim_end_matches('\0')
+            .trim_end_matches('\n')
+            .to_string())
+    }
+
+    fn parse_rpc_result(&self, response: &str) -> Result<Value> {
+        let value: Value = serde_json::from_str(response)?;
+        if let Some(status) = value.pointer("/STATUS/0/STATUS").and_then(|v| v.as_str()) {
+            let message = value.pointer("/STATUS/0/Msg").and_then(|v| v.as_str());
+            RPCCommandStatus::from_str(status, message).into_result()?;
+        }
+        Ok(value)
+    }
+
+    pub async fn restart(&self) -> Result<Value> {
+        self.send_rpc_command("restart", None).await
+    }
+}
+
+#[async_trait]
+impl APIClient for CGMinerRPCAPI {
+    async fn get_api_result(&self, command: &MinerCommand) -> Result<Value> {
+        match command {
+            MinerCommand::RPC {
+                command,
+                parameters,
+            } => self.send_rpc_command(command, parameters.clone()).await,
+            _ => Err(anyhow!("Unsupported command type for RPC client")),
+        }
+    }
+
+    async fn get_api_results(
+        &self,
+        commands: &[MinerCommand],
+    ) -> HashMap<MinerCommand, Result<Value>> {
+        batch::send_joined(
+            commands,
+            &self.policy,
+            &self.joined_commands,
+            |request| self.send_request(request),
+            |response| self.parse_rpc_result(response),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl RPCAPIClient for CGMinerRPCAPI {
+    async fn send_command(
+        &self,
+        command: &str,
+        _privileged: bool,
+        parameters: Option<Value>,
+    ) -> Result<Value> {
+        self.send_rpc_command(command, parameters).await
+    }
+}
@@ -0,0 +1,18 @@
+use semver;
+use std::net::IpAddr;
+
+pub use generic::GenericCGMiner;
+
+use crate::data::device::MinerModel;
+use crate::miners::backends::traits::*;
+
+pub mod generic;
+
+pub struct CGMiner;
+
+impl MinerConstructor for CGMiner {
+    #[allow(clippy::new_ret_no_self)]
+    fn new(ip: IpAddr, model: MinerModel, _: Option<semver::Version>) -> Box<dyn Miner> {
+        Box::new(GenericCGMiner::new(ip, model))
+    }
+}
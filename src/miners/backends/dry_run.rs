@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+/// A control command that was built but not sent, because the miner was in dry-run mode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedAction {
+    /// The IP address of the miner the command was meant for
+    pub ip: IpAddr,
+    /// The RPC command or web route
+    pub command: String,
+    /// The payload that would have been sent, encrypted where the firmware encrypts it, with
+    /// passwords replaced by [`REDACTED`]
+    pub payload: Value,
+}
+
+/// Stands in for passwords in planned payloads and debug output.
+pub const REDACTED: &str = "<redacted>";
+
+/// Log of planned control commands.
+///
+/// While a miner has a `DryRun` attached, its control functions record what they would send
+/// here and report success without writing to the miner, reads are unaffected.
+/// Clones share the same log, so one `DryRun` can be attached to a whole fleet.
+#[derive(Debug, Clone, Default)]
+pub struct DryRun {
+    actions: Arc<Mutex<Vec<PlannedAction>>>,
+}
+
+impl DryRun {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All actions planned so far, in the order they were planned.
+    pub fn actions(&self) -> Vec<PlannedAction> {
+        self.actions.lock().expect("dry-run log poisoned").clone()
+    }
+
+    /// Remove and return all actions planned so far.
+    pub fn take_actions(&self) -> Vec<PlannedAction> {
+        std::mem::take(&mut *self.actions.lock().expect("dry-run log poisoned"))
+    }
+
+    pub(crate) fn record(&self, action: PlannedAction) {
+        self.actions
+            .lock()
+            .expect("dry-run log poisoned")
+            .push(action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_dry_run_shared_log() {
+        let dry_run = DryRun::new();
+        let shared = dry_run.clone();
+        shared.record(PlannedAction {
+            ip: IpAddr::from([10, 0, 0, 1]),
+            command: "reboot".to_string(),
+            payload: json!(null),
+        });
+
+        assert_eq!(dry_run.actions().len(), 1);
+        assert_eq!(dry_run.take_actions()[0].command, "reboot");
+        assert!(shared.actions().is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use macaddr::MacAddr;
 use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
@@ -6,19 +6,28 @@ use reqwest::Method;
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
-use std::str::FromStr;
 use std::time::Duration;
 
 use crate::data::board::{BoardData, ChipData};
-use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerModel};
-use crate::data::device::{MinerControlBoard, MinerMake};
-use crate::data::fan::FanData;
+use crate::data::device::{ControlBoardType, MinerControlBoard, MinerMake};
+use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerModel};
+use crate::data::fan::{FanControl, FanData, FanMode};
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::pool::{PoolData, PoolURL};
+use crate::data::health::HealthThresholds;
+use crate::data::miner::{MinerStatus, WattageLocation};
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::psu::PsuRail;
+use crate::data::rolling::HistoryPoint;
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_pointer,
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData,
+    get_by_pointer,
 };
 
 use web::PowerPlayWebAPI;
@@ -30,21 +39,71 @@ pub struct PowerPlayV1 {
     ip: IpAddr,
     web: PowerPlayWebAPI,
     device_info: DeviceInfo,
+    labels: Option<HashMap<String, String>>,
+    health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
 }
 
 impl PowerPlayV1 {
     pub fn new(ip: IpAddr, model: MinerModel) -> Self {
+        let algo = HashAlgorithm::from(&model);
         PowerPlayV1 {
             ip,
             web: PowerPlayWebAPI::new(ip, 4028),
-            device_info: DeviceInfo::new(
-                MinerMake::from(model),
-                model,
-                MinerFirmware::EPic,
-                HashAlgorithm::SHA256,
-            ),
+            device_info: DeviceInfo::new(MinerMake::from(&model), model, MinerFirmware::EPic, algo),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
         }
     }
+
+    /// Read the short-term hashrate history PowerPlay keeps, oldest point first.
+    ///
+    /// The history spans hours of samples, so it isn't part of the data collected by
+    /// `get_data`. Merge it into [`RollingMinerStats`](crate::data::rolling::RollingMinerStats)
+    /// with `ingest_history` to seed averages without waiting for polls.
+    pub async fn get_history(&self) -> Result<Vec<HistoryPoint>> {
+        let history = self
+            .web
+            .get_api_result(&MinerCommand::WebAPI {
+                command: "history",
+                parameters: None,
+            })
+            .await?;
+        Ok(parse_history(&history, &self.device_info.algo))
+    }
+}
+
+/// Older PowerPlay wraps the history under `History`, newer firmware returns the bare array.
+/// Each entry is `[timestamp, hashrate in MH/s, accepted, rejected]`.
+fn parse_history(history: &Value, algo: &HashAlgorithm) -> Vec<HistoryPoint> {
+    let entries = history
+        .get("History")
+        .unwrap_or(history)
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut points: Vec<HistoryPoint> = entries
+        .iter()
+        .filter_map(|entry| {
+            let field = |idx: usize| entry.get(idx).and_then(Value::as_f64);
+            Some(HistoryPoint {
+                timestamp: field(0)? as u64,
+                hashrate: HashRate {
+                    value: field(1)?,
+                    unit: HashRateUnit::MegaHash,
+                    algo: algo.to_string(),
+                }
+                .as_unit(algo.default_unit()),
+                accepted: field(2)? as u64,
+                rejected: field(3)? as u64,
+            })
+        })
+        .collect();
+    points.sort_by_key(|p| p.timestamp);
+    points
 }
 
 #[async_trait]
@@ -84,6 +143,14 @@ impl GetDataLocations for PowerPlayV1 {
                     tag: None,
                 },
             )],
+            DataField::ReportedIp => vec![(
+                network_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some(""),
+                    tag: None,
+                },
+            )],
             DataField::Hostname => vec![(
                 summary_cmd,
                 DataExtractor {
@@ -104,7 +171,23 @@ impl GetDataLocations for PowerPlayV1 {
                 summary_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/Power Supply Stats/Input Power"),
+                    key: Some("/Power Supply Stats"),
+                    tag: None,
+                },
+            )],
+            DataField::PsuRails => vec![(
+                summary_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/Power Supply Stats"),
+                    tag: None,
+                },
+            )],
+            DataField::FanControl => vec![(
+                summary_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/Fans"),
                     tag: None,
                 },
             )],
@@ -251,7 +334,124 @@ impl GetIP for PowerPlayV1 {
 
 impl GetDeviceInfo for PowerPlayV1 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+    fn parse_device_info(&self, data: &FieldData) -> DeviceInfo {
+        let mut device_info = self.get_device_info();
+        if let Some(algo) = parse_algorithm(data) {
+            device_info.algo = algo;
+        }
+        device_info
+    }
+}
+
+impl PowerPlayV1 {
+    fn algorithm(&self, data: &FieldData) -> HashAlgorithm {
+        self.parse_device_info(data).algo
+    }
+}
+
+/// PowerPlay also runs on units mining other coins, `capabilities` lists the coins the hardware
+/// supports and the summary the one it is currently mining.
+fn parse_algorithm(data: &FieldData) -> Option<HashAlgorithm> {
+    let capabilities_coin = data
+        .get(&DataField::Hashboards)
+        .and_then(|v| v.pointer("/Capabilities/Coins/0"))
+        .and_then(Value::as_str);
+    let summary = data.get(&DataField::Pools);
+    let mining_coin = summary
+        .and_then(|v| v.pointer("/Mining/Coin"))
+        .and_then(Value::as_str);
+    let mining_algorithm = summary
+        .and_then(|v| v.pointer("/Mining/Algorithm"))
+        .and_then(Value::as_str);
+
+    mining_coin
+        .or(capabilities_coin)
+        .and_then(algorithm_from_coin)
+        .or_else(|| mining_algorithm.and_then(algorithm_from_name))
+}
+
+fn algorithm_from_coin(coin: &str) -> Option<HashAlgorithm> {
+    match coin.to_uppercase().as_str() {
+        "BTC" | "BCH" | "BSV" => Some(HashAlgorithm::SHA256),
+        "LTC" | "DOGE" => Some(HashAlgorithm::Scrypt),
+        "DASH" => Some(HashAlgorithm::X11),
+        "KDA" => Some(HashAlgorithm::Kadena),
+        _ => None,
+    }
+}
+
+fn algorithm_from_name(name: &str) -> Option<HashAlgorithm> {
+    match name.to_uppercase().replace('-', "").as_str() {
+        "SHA256" => Some(HashAlgorithm::SHA256),
+        "SCRYPT" => Some(HashAlgorithm::Scrypt),
+        "X11" => Some(HashAlgorithm::X11),
+        "BLAKE2S" | "BLAKE2S256" => Some(HashAlgorithm::Blake2S256),
+        "KADENA" => Some(HashAlgorithm::Kadena),
+        _ => None,
+    }
+}
+
+impl GetLabels for PowerPlayV1 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for PowerPlayV1 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for PowerPlayV1 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for PowerPlayV1 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for PowerPlayV1 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.web.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.web.policy = policy;
+    }
+}
+
+impl GetCapabilities for PowerPlayV1 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_fault_light: true,
+            supports_restart: true,
+            supports_pause: true,
+            has_chip_telemetry: true,
+            has_psu_telemetry: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -262,7 +462,7 @@ impl CollectData for PowerPlayV1 {
 }
 
 impl GetMAC for PowerPlayV1 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
         match serde_json::from_value::<HashMap<String, Value>>(data.get(&DataField::Mac)?.clone())
             .ok()
             .and_then(|inner| inner.get("dhcp").or_else(|| inner.get("static")).cloned())
@@ -271,51 +471,66 @@ impl GetMAC for PowerPlayV1 {
                     .and_then(|v| v.as_str())
                     .map(String::from)
             }) {
-            Some(mac_str) => MacAddr::from_str(&mac_str).ok(),
+            Some(mac_str) => parse_mac_str(&mac_str),
             None => None,
         }
     }
 }
 
+impl GetReportedIp for PowerPlayV1 {
+    fn parse_reported_ip(&self, data: &FieldData) -> Option<IpAddr> {
+        let network = data.get(&DataField::ReportedIp)?;
+        network
+            .get("dhcp")
+            .or_else(|| network.get("static"))?
+            .get("address")?
+            .as_str()
+            .and_then(parse_ip_str)
+    }
+}
+
 impl GetSerialNumber for PowerPlayV1 {
-    fn parse_serial_number(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_serial_number(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::SerialNumber)
     }
 }
 
 impl GetHostname for PowerPlayV1 {
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::Hostname)
     }
 }
 
 impl GetApiVersion for PowerPlayV1 {
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::ApiVersion)
     }
 }
 
 impl GetFirmwareVersion for PowerPlayV1 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
 }
 
 impl GetControlBoardVersion for PowerPlayV1 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
         let cb_type = data.extract::<String>(DataField::ControlBoardVersion)?;
         match cb_type.as_str() {
             s if s.to_uppercase().contains("AMLOGIC") => Some(MinerControlBoard::AMLogic),
             _ => Some(MinerControlBoard::EPicUMC),
         }
     }
+
+    fn parse_control_board_type(&self, data: &FieldData) -> Option<ControlBoardType> {
+        data.extract::<String>(DataField::ControlBoardVersion)
+            .and_then(|s| s.parse().ok())
+    }
 }
 
 impl GetHashboards for PowerPlayV1 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
+        let algo = self.algorithm(data);
         let mut hashboards: Vec<BoardData> = Vec::new();
         for _ in 0..self.device_info.hardware.boards.unwrap_or_default() {
             hashboards.push(BoardData {
@@ -329,10 +544,14 @@ impl GetHashboards for PowerPlayV1 {
                 working_chips: None,
                 serial_number: None,
                 chips: vec![],
+                sensor_temperatures: vec![],
                 voltage: None,
                 frequency: None,
+                wattage: None,
                 tuned: None,
                 active: None,
+                status: None,
+                extra: None,
             });
         }
 
@@ -367,6 +586,7 @@ impl GetHashboards for PowerPlayV1 {
                         frequency: None,
                         tuned: None,
                         working: None,
+                        estimated: false,
                     };
                     self.device_info.hardware.chips.unwrap_or_default() as usize
                 ];
@@ -408,11 +628,14 @@ impl GetHashboards for PowerPlayV1 {
                                 .and_then(|v| v.as_array())
                                 .and_then(|v| v.first().and_then(|f| f.as_f64()))
                             {
-                                hashboard.hashrate = Some(HashRate {
-                                    value: h,
-                                    unit: HashRateUnit::MegaHash,
-                                    algo: String::from("SHA256"),
-                                })
+                                hashboard.hashrate = Some(
+                                    HashRate {
+                                        value: h,
+                                        unit: HashRateUnit::MegaHash,
+                                        algo: algo.to_string(),
+                                    }
+                                    .as_unit(algo.default_unit()),
+                                )
                             };
 
                             // ExpectedHashrate
@@ -425,12 +648,17 @@ impl GetHashboards for PowerPlayV1 {
                                         v.get(1).and_then(|f| f.as_f64())?,
                                     ))
                                 })
+                                && h.1 > 0.0
                             {
-                                hashboard.expected_hashrate = Some(HashRate {
-                                    value: h.0 / h.1,
-                                    unit: HashRateUnit::MegaHash,
-                                    algo: String::from("SHA256"),
-                                })
+                                // the second value is the percentage of the ideal rate
+                                hashboard.expected_hashrate = Some(
+                                    HashRate {
+                                        value: h.0 / (h.1 / 100.0),
+                                        unit: HashRateUnit::MegaHash,
+                                        algo: algo.to_string(),
+                                    }
+                                    .as_unit(algo.default_unit()),
+                                )
                             };
 
                             //Frequency
@@ -463,9 +691,7 @@ impl GetHashboards for PowerPlayV1 {
                             // Outlet Temperature
                             if let Some(h) = board.get("Data").and_then(|v| {
                                 v.as_array().and_then(|arr| {
-                                    arr.iter()
-                                        .filter_map(|v| v.as_f64())
-                                        .max_by(|a, b| a.partial_cmp(b).unwrap())
+                                    arr.iter().filter_map(|v| v.as_f64()).max_by(f64::total_cmp)
                                 })
                             }) {
                                 hashboard.outlet_temperature = Some(Temperature::from_celsius(h));
@@ -473,9 +699,7 @@ impl GetHashboards for PowerPlayV1 {
 
                             if let Some(h) = board.get("Data").and_then(|v| {
                                 v.as_array().and_then(|arr| {
-                                    arr.iter()
-                                        .filter_map(|v| v.as_f64())
-                                        .min_by(|a, b| a.partial_cmp(b).unwrap())
+                                    arr.iter().filter_map(|v| v.as_f64()).min_by(f64::total_cmp)
                                 })
                             }) {
                                 hashboard.intake_temperature = Some(Temperature::from_celsius(h));
@@ -583,7 +807,7 @@ impl GetHashboards for PowerPlayV1 {
                                         .map(|hr| HashRate {
                                             value: hr,
                                             unit: HashRateUnit::MegaHash,
-                                            algo: String::from("SHA256"),
+                                            algo: algo.to_string(),
                                         })
                                         .collect::<Vec<HashRate>>()
                                 })
@@ -605,46 +829,57 @@ impl GetHashboards for PowerPlayV1 {
 }
 
 impl GetHashrate for PowerPlayV1 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
-        let mut total_hashrate: f64 = 0.0;
-
-        data.get(&DataField::Hashrate).and_then(|v| {
-            v.as_array().map(|boards| {
-                boards.iter().for_each(|board| {
-                    if let Some(_idx) = board.get("Index").and_then(|v| v.as_u64()) {
-                        // Hashrate
-                        if let Some(h) = board
-                            .get("Hashrate")
-                            .and_then(|v| v.as_array())
-                            .and_then(|v| v.first().and_then(|f| f.as_f64()))
-                        {
-                            total_hashrate += h;
-                        };
-                    }
-                })
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        // no board list at all is a missing reading, not a miner hashing at zero
+        let boards = data.get(&DataField::Hashrate)?.as_array()?;
+        let total_hashrate: f64 = boards
+            .iter()
+            .filter(|board| board.get("Index").and_then(|v| v.as_u64()).is_some())
+            .filter_map(|board| {
+                board
+                    .get("Hashrate")
+                    .and_then(|v| v.as_array())
+                    .and_then(|v| v.first().and_then(|f| f.as_f64()))
             })
-        });
+            .sum();
 
-        Some(HashRate {
-            value: total_hashrate,
-            unit: HashRateUnit::MegaHash,
-            algo: String::from("SHA256"),
-        })
+        let algo = self.algorithm(data);
+        Some(
+            HashRate {
+                value: total_hashrate,
+                unit: HashRateUnit::MegaHash,
+                algo: algo.to_string(),
+            }
+            .as_unit(algo.default_unit()),
+        )
     }
 }
 
 impl GetExpectedHashrate for PowerPlayV1 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        let algo = self.algorithm(data);
         data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| HashRate {
             value: f,
-            unit: HashRateUnit::TeraHash,
-            algo: String::from("SHA256"),
+            unit: default_hashrate_unit(f),
+            algo: algo.to_string(),
         })
     }
 }
 
+/// `Default Hashrate` is in TH/s on Antminers, but BlockMiner units report it in GH/s. No single
+/// machine hashes at 10 PH/s, so larger values can only be GH/s.
+fn default_hashrate_unit(value: f64) -> HashRateUnit {
+    if value > 10_000.0 {
+        HashRateUnit::GigaHash
+    } else {
+        HashRateUnit::TeraHash
+    }
+}
+
+impl GetAverageHashrate for PowerPlayV1 {}
+
 impl GetFans for PowerPlayV1 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut fans: Vec<FanData> = Vec::new();
 
         if let Some(fans_data) = data.get(&DataField::Fans)
@@ -671,18 +906,93 @@ impl GetFans for PowerPlayV1 {
 
 impl GetPsuFans for PowerPlayV1 {}
 
+impl GetFanControl for PowerPlayV1 {
+    /// The mode is configured, from `Fan Mode` in the summary. The speed is `Fans Speed`, which
+    /// is the configured speed in manual mode and the speed PowerPlay picked in auto mode.
+    fn parse_fan_control(&self, data: &FieldData) -> Option<FanControl> {
+        let fans = data.get(&DataField::FanControl)?;
+        // an object keyed by the mode with its settings, or just the name of the mode
+        let mode = match fans.get("Fan Mode")? {
+            Value::Object(mode) => mode.keys().next()?.as_str(),
+            Value::String(mode) => mode.as_str(),
+            _ => return None,
+        };
+        let mode = match mode {
+            "Auto" => FanMode::Auto,
+            "Manual" => FanMode::Manual,
+            _ => return None,
+        };
+        Some(FanControl {
+            mode,
+            target_pct: fans
+                .get("Fans Speed")
+                .and_then(Value::as_u64)
+                .filter(|pct| *pct <= 100)
+                .map(|pct| pct as u8),
+        })
+    }
+}
+
+impl GetPsu for PowerPlayV1 {}
+
+impl GetPsuRails for PowerPlayV1 {
+    /// The PSU's input, the wall on AC fed units and the DC bus on units fed by an external PSU.
+    fn parse_psu_rails(&self, data: &FieldData) -> Vec<PsuRail> {
+        let reading = |key| data.extract_nested::<f64>(DataField::PsuRails, key);
+        PsuRail::input(
+            reading("Input Voltage"),
+            reading("Input Current"),
+            reading("Input Power"),
+        )
+        .into_iter()
+        .collect()
+    }
+}
+
 impl GetFluidTemperature for PowerPlayV1 {}
 
+impl GetFluidFlow for PowerPlayV1 {}
+
+impl GetFluidPressure for PowerPlayV1 {}
+
+/// Lowest input voltage taken for an AC feed, DC fed units report their bus voltage.
+const AC_INPUT_MIN_VOLTAGE: f64 = 90.0;
+
+impl PowerPlayV1 {
+    /// Wall power of units with an AC input, from the PSU's input voltage and current.
+    fn ac_input_watts(psu_stats: &Value) -> Option<f64> {
+        let voltage = psu_stats["Input Voltage"].as_f64()?;
+        let current = psu_stats["Input Current"].as_f64()?;
+        (voltage > AC_INPUT_MIN_VOLTAGE && current > 0.0).then_some(voltage * current)
+    }
+}
+
 impl GetWattage for PowerPlayV1 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
-        data.extract_map::<f64, _>(DataField::Wattage, Power::from_watts)
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
+        let psu_stats = data.get(&DataField::Wattage)?;
+        // `Input Power` is DC side on units fed by an external PSU
+        Self::ac_input_watts(psu_stats)
+            .or_else(|| psu_stats["Input Power"].as_f64())
+            .map(Power::from_watts)
+    }
+
+    fn parse_wattage_location(&self, data: &FieldData) -> WattageLocation {
+        match data.get(&DataField::Wattage).and_then(Self::ac_input_watts) {
+            Some(_) => WattageLocation::Wall,
+            None => WattageLocation::DCBus,
+        }
     }
 }
 
 impl GetWattageLimit for PowerPlayV1 {}
 
+impl GetFrequency for PowerPlayV1 {}
+impl GetVoltage for PowerPlayV1 {}
+impl GetWorkMode for PowerPlayV1 {}
+impl GetAutotune for PowerPlayV1 {}
+
 impl GetLightFlashing for PowerPlayV1 {
-    fn parse_light_flashing(&self, data: &HashMap<DataField, Value>) -> Option<bool> {
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
         data.extract::<bool>(DataField::LightFlashing)
     }
 }
@@ -690,22 +1000,35 @@ impl GetLightFlashing for PowerPlayV1 {
 impl GetMessages for PowerPlayV1 {}
 
 impl GetUptime for PowerPlayV1 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract::<u64>(DataField::Uptime)
             .map(Duration::from_secs)
     }
 }
 
 impl GetIsMining for PowerPlayV1 {
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
-        data.extract::<String>(DataField::IsMining)
-            .map(|state| state != "Idling")
-            .unwrap_or(false)
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
+    }
+}
+
+impl GetStatus for PowerPlayV1 {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        match data.extract::<String>(DataField::IsMining) {
+            Some(state) => match state.as_str() {
+                "Idling" => MinerStatus::Paused,
+                "Initializing" | "Starting" | "Loading" => MinerStatus::Starting,
+                s if s.contains("Error") || s.contains("Fail") => MinerStatus::Failure,
+                // every other operating state (mining, tuning) is hashing
+                _ => MinerStatus::Mining,
+            },
+            None => MinerStatus::Unknown,
+        }
     }
 }
 
 impl GetPools for PowerPlayV1 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
         let mut pools_vec: Vec<PoolData> = Vec::new();
 
         if let Some(configs) = data
@@ -718,7 +1041,7 @@ impl GetPools for PowerPlayV1 {
                     if s.is_empty() {
                         None
                     } else {
-                        Some(PoolURL::from(s.to_string()))
+                        PoolURL::parse(s)
                     }
                 });
                 let user = config
@@ -733,6 +1056,11 @@ impl GetPools for PowerPlayV1 {
                     active: Some(false),
                     alive: None,
                     user,
+                    password: None,
+                    last_share_difficulty: None,
+                    last_share_time: None,
+                    group: None,
+                    quota: None,
                 });
             }
         }
@@ -761,7 +1089,7 @@ impl GetPools for PowerPlayV1 {
                             if s.is_empty() {
                                 None
                             } else {
-                                Some(PoolURL::from(s.to_string()))
+                                PoolURL::parse(s)
                             }
                         });
 
@@ -786,15 +1114,15 @@ impl GetPools for PowerPlayV1 {
 impl SetFaultLight for PowerPlayV1 {
     #[allow(unused_variables)]
     async fn set_fault_light(&self, fault: bool) -> Result<bool> {
-        self.web
-            .send_command(
-                "identify",
-                false,
-                Some(json!({ "param": fault })),
-                Method::POST,
-            )
-            .await
-            .map(|v| v.get("result").and_then(Value::as_bool).unwrap_or(false))
+        let payload = json!({ "param": fault });
+        let sent = execute_control(self, "identify", payload.clone(), async {
+            self.web
+                .send_command("identify", false, Some(payload), Method::POST)
+                .await
+                .map(|v| v.get("result").and_then(Value::as_bool).unwrap_or(false))
+        })
+        .await?;
+        Ok(confirm_fault_light(self, fault, sent).await)
     }
 }
 
@@ -802,17 +1130,89 @@ impl SetFaultLight for PowerPlayV1 {
 impl SetPowerLimit for PowerPlayV1 {
     #[allow(unused_variables)]
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting a power limit".to_string(),
+        ))?
+    }
+}
+
+impl SetCredentials for PowerPlayV1 {}
+
+impl EndpointControl for PowerPlayV1 {
+    fn set_endpoints(&mut self, endpoints: MinerEndpoints) {
+        if let Some(web) = endpoints.web {
+            self.web.set_address(web);
+        }
+    }
+}
+
+#[async_trait]
+impl SetPassword for PowerPlayV1 {
+    #[allow(unused_variables)]
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the password".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetHostname for PowerPlayV1 {
+    #[allow(unused_variables)]
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the hostname".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPools for PowerPlayV1 {
+    #[allow(unused_variables)]
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting pools".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for PowerPlayV1 {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
     }
 }
 
 #[async_trait]
 impl Restart for PowerPlayV1 {
-    async fn restart(&self) -> Result<bool> {
-        self.web
-            .send_command("reboot", false, Some(json!({"param": "0"})), Method::POST)
-            .await
-            .map(|v| v.get("result").and_then(Value::as_bool).unwrap_or(false))
+    async fn restart_mining(&self) -> Result<bool> {
+        let payload = json!({"param": "0"});
+        execute_control(self, "softreboot", payload.clone(), async {
+            self.web
+                .send_command("softreboot", false, Some(payload), Method::POST)
+                .await
+                .map(|v| v.get("result").and_then(Value::as_bool).unwrap_or(false))
+        })
+        .await
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        let payload = json!({"param": "0"});
+        execute_control(self, "reboot", payload.clone(), async {
+            self.web
+                .send_command("reboot", false, Some(payload), Method::POST)
+                .await
+                .map(|v| v.get("result").and_then(Value::as_bool).unwrap_or(false))
+        })
+        .await
     }
 }
 
@@ -820,10 +1220,14 @@ impl Restart for PowerPlayV1 {
 impl Pause for PowerPlayV1 {
     #[allow(unused_variables)]
     async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
-        self.web
-            .send_command("miner", false, Some(json!({"param": "Stop"})), Method::POST)
-            .await
-            .map(|v| v.get("result").and_then(Value::as_bool).unwrap_or(false))
+        let payload = json!({"param": "Stop"});
+        execute_control(self, "miner", payload.clone(), async {
+            self.web
+                .send_command("miner", false, Some(payload), Method::POST)
+                .await
+                .map(|v| v.get("result").and_then(Value::as_bool).unwrap_or(false))
+        })
+        .await
     }
 }
 
@@ -831,25 +1235,31 @@ impl Pause for PowerPlayV1 {
 impl Resume for PowerPlayV1 {
     #[allow(unused_variables)]
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
-        self.web
-            .send_command(
-                "miner",
-                false,
-                Some(json!({ "param": "Autostart" })),
-                Method::POST,
-            )
-            .await
-            .map(|v| v.get("result").and_then(Value::as_bool).unwrap_or(false))
+        let payload = json!({ "param": "Autostart" });
+        execute_control(self, "miner", payload.clone(), async {
+            self.web
+                .send_command("miner", false, Some(payload), Method::POST)
+                .await
+                .map(|v| v.get("result").and_then(Value::as_bool).unwrap_or(false))
+        })
+        .await
     }
 }
 
+impl SetRPCReadLimits for PowerPlayV1 {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::device::models::antminer::AntMinerModel::S19XP;
+    use crate::data::device::models::MinerModelFactory;
+    use crate::data::device::models::antminer::AntMinerModel::{KA3, S19XP};
+    use crate::data::device::models::epic::EPicModel;
+    use crate::data::rolling::RollingMinerStats;
     use crate::test::api::MockAPIClient;
+    use crate::test::invariants::assert_hashrate_magnitudes;
     use crate::test::json::epic::v1::*;
     use anyhow::Result;
+    use std::str::FromStr;
 
     #[tokio::test]
     async fn parse_data_test_antminer_s19xp() -> Result<()> {
@@ -882,12 +1292,27 @@ mod tests {
         let data = collector.collect_all().await;
 
         let miner_data = miner.parse_data(data);
+        assert_hashrate_magnitudes(&miner_data);
 
         assert_eq!(miner_data.uptime, Some(Duration::from_secs(23170)));
+        assert_eq!(
+            miner_data.fan_control,
+            Some(FanControl {
+                mode: FanMode::Auto,
+                target_pct: Some(100),
+            })
+        );
+        assert_eq!(miner_data.reported_ip, Some(IpAddr::from([10, 0, 81, 19])));
         assert_eq!(miner_data.wattage, Some(Power::from_watts(2166.6174)));
         assert_eq!(miner_data.hashboards.len(), 3);
         assert_eq!(miner_data.hashboards[0].active, Some(false));
         assert_eq!(miner_data.hashboards[1].chips.len(), 110);
+        // estimated chips never replace the chips ePIC reports
+        for board in &miner_data.hashboards {
+            let mut estimated = board.clone();
+            estimated.fill_estimated_chips();
+            assert_eq!(&estimated, board);
+        }
         assert_eq!(
             miner_data.hashboards[1].chips[69].hashrate,
             Some(HashRate {
@@ -904,7 +1329,192 @@ mod tests {
                 algo: String::from("SHA256"),
             })
         );
+        assert_eq!(miner_data.status, MinerStatus::Mining);
+        assert_eq!(miner_data.device_info.algo, HashAlgorithm::SHA256);
+        assert_eq!(
+            miner_data.control_board_type,
+            Some(ControlBoardType::Unknown(
+                "STM32 (Device Tree Support)".to_string()
+            ))
+        );
+        let hashrate = miner_data.hashrate.unwrap();
+        assert_eq!(hashrate.unit, HashRateUnit::TeraHash);
+        assert_eq!(hashrate.algo, "SHA256");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn parse_data_test_antminer_ka3() -> Result<()> {
+        let miner = PowerPlayV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(KA3));
+
+        let mut results = HashMap::new();
+        for (command, data) in [("summary", SUMMARY_KA3), ("capabilities", CAPABILITIES_KA3)] {
+            let cmd: MinerCommand = MinerCommand::WebAPI {
+                command,
+                parameters: None,
+            };
+            results.insert(cmd, Value::from_str(data)?);
+        }
+
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+
+        let miner_data = miner.parse_data(data);
+
+        assert_eq!(miner_data.device_info.algo, HashAlgorithm::Kadena);
+        let hashrate = miner_data.hashrate.unwrap();
+        assert_eq!(hashrate.unit, HashRateUnit::MegaHash);
+        assert_eq!(hashrate.algo, "Kadena");
+        assert_eq!(hashrate.value, 165120000.0);
+
+        let expected = miner_data.expected_hashrate.unwrap();
+        assert_eq!(expected.unit, HashRateUnit::MegaHash);
+        assert_eq!(expected.value, 166000000.0);
+
+        let board = miner_data.hashboards[1].hashrate.clone().unwrap();
+        assert_eq!(board.unit, HashRateUnit::MegaHash);
+        assert_eq!(board.algo, "Kadena");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_epic_wattage_location() -> Result<()> {
+        let summary = MinerCommand::WebAPI {
+            command: "summary",
+            parameters: None,
+        };
+        let cases = [
+            // integrated AC PSU, the wall draw is the input voltage times current
+            (
+                MinerModel::AntMiner(S19XP),
+                SUMMARY_AC,
+                228.4 * 14.35,
+                WattageLocation::Wall,
+            ),
+            // DC fed BlockMiner, only the DC side power is known
+            (
+                MinerModel::EPic(EPicModel::BM520i),
+                SUMMARY_BM520I,
+                1125.4,
+                WattageLocation::DCBus,
+            ),
+            (
+                MinerModel::AntMiner(S19XP),
+                SUMMARY,
+                2166.6174,
+                WattageLocation::DCBus,
+            ),
+        ];
+
+        for (model, fixture, watts, location) in cases {
+            let miner = PowerPlayV1::new(IpAddr::from([127, 0, 0, 1]), model);
+            let mock_api = MockAPIClient::new(HashMap::from([(
+                summary.clone(),
+                Value::from_str(fixture)?,
+            )]));
+            let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+            let miner_data = miner.parse_data(collector.collect(&[DataField::Wattage]).await);
+
+            assert_eq!(miner_data.wattage, Some(Power::from_watts(watts)));
+            assert_eq!(miner_data.wattage_location, Some(location));
+            assert!(!miner_data.wattage_estimated);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_epic_algorithm_from_summary() {
+        // the coin currently being mined wins over the model default
+        let miner = PowerPlayV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19XP));
+
+        let data = FieldData::from([(
+            DataField::Pools,
+            json!({"Mining": {"Coin": "LTC", "Algorithm": "Scrypt"}}),
+        )]);
+        assert_eq!(miner.parse_device_info(&data).algo, HashAlgorithm::Scrypt);
+
+        let data = FieldData::from([(
+            DataField::Pools,
+            json!({"Mining": {"Algorithm": "SHA-256"}}),
+        )]);
+        assert_eq!(miner.parse_device_info(&data).algo, HashAlgorithm::SHA256);
+    }
+
+    #[test]
+    fn test_epic_blockminer_models() {
+        let mut factory = MinerModelFactory::new();
+        factory.with_firmware(MinerFirmware::EPic);
+        assert_eq!(
+            factory.parse_model("BLOCKMINER 520I"),
+            Some(MinerModel::EPic(EPicModel::BM520i))
+        );
+        assert_eq!(
+            factory.parse_model("BLOCKMINER 720I"),
+            Some(MinerModel::EPic(EPicModel::BM720i))
+        );
+    }
+
+    #[test]
+    fn test_epic_status() {
+        let miner = PowerPlayV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19XP));
+
+        let idling = FieldData::from([(DataField::IsMining, json!("Idling"))]);
+        assert_eq!(miner.parse_status(&idling), MinerStatus::Paused);
+        assert!(!miner.parse_is_mining(&idling));
+
+        let failed = FieldData::from([(DataField::IsMining, json!("Error"))]);
+        assert_eq!(miner.parse_status(&failed), MinerStatus::Failure);
+    }
+
+    #[test]
+    fn test_epic_default_hashrate_units() {
+        let miner = PowerPlayV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19XP));
+
+        let antminer = FieldData::from([(DataField::ExpectedHashrate, json!(143))]);
+        assert_eq!(
+            miner.parse_expected_hashrate(&antminer),
+            Some(HashRate {
+                value: 143.0,
+                unit: HashRateUnit::TeraHash,
+                algo: String::from("SHA256"),
+            })
+        );
+
+        // BlockMiner capabilities report GH/s
+        let blockminer = FieldData::from([(DataField::ExpectedHashrate, json!(110000))]);
+        assert_eq!(
+            miner.parse_expected_hashrate(&blockminer),
+            Some(HashRate {
+                value: 110.0,
+                unit: HashRateUnit::TeraHash,
+                algo: String::from("SHA256"),
+            })
+        );
+    }
 
+    #[test]
+    fn test_epic_history_envelopes() -> Result<()> {
+        let bare = parse_history(&Value::from_str(HISTORY)?, &HashAlgorithm::SHA256);
+        let legacy = parse_history(&Value::from_str(HISTORY_LEGACY)?, &HashAlgorithm::SHA256);
+        assert_eq!(bare.len(), 4);
+        assert_eq!(bare, legacy);
+        assert_eq!(bare[0].timestamp, 1700000000);
+        assert_eq!(bare[3].accepted, 5133);
+        assert_eq!(bare[2].rejected, 4);
+        assert_eq!(bare[1].hashrate.unit, HashRateUnit::TeraHash);
+        assert!((bare[1].hashrate.value - 109.875).abs() < 1e-6);
+
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let mut stats = RollingMinerStats::new(Duration::from_secs(600));
+        stats.ingest_history(ip, &legacy);
+        let averages = stats.averages(&ip).unwrap();
+        assert_eq!(averages.samples, 4);
+        assert_eq!(averages.accepted_shares_per_minute, Some(13.0 / 3.0));
         Ok(())
     }
 }
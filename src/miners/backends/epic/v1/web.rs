@@ -2,8 +2,12 @@ use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use reqwest::{Client, Method, Response};
 use serde_json::{Value, json};
-use std::{net::IpAddr, time::Duration};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 
@@ -15,6 +19,7 @@ pub struct PowerPlayWebAPI {
     port: u16,
     timeout: Duration,
     password: Option<String>,
+    pub(crate) policy: CommandPolicy,
 }
 
 #[async_trait]
@@ -24,10 +29,10 @@ impl APIClient for PowerPlayWebAPI {
             MinerCommand::WebAPI {
                 command,
                 parameters,
-            } => self
-                .send_command(command, false, parameters.clone(), Method::GET)
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => {
+                self.send_command(command, false, parameters.clone(), Method::GET)
+                    .await
+            }
             _ => Err(anyhow!("Cannot send non web command to web API")),
         }
     }
@@ -43,6 +48,11 @@ impl WebAPIClient for PowerPlayWebAPI {
         parameters: Option<Value>,
         method: Method,
     ) -> Result<Value> {
+        if method == Method::GET {
+            self.policy.check(command)?;
+        } else {
+            self.policy.check_write(command)?;
+        }
         let url = format!("http://{}:{}/{}", self.ip, self.port, command);
 
         let response = self
@@ -76,9 +86,16 @@ impl PowerPlayWebAPI {
             port,
             timeout: Duration::from_secs(5),
             password: Some("letmein".to_string()), // Default password
+            policy: CommandPolicy::default(),
         }
     }
 
+    /// Connect to `addr` instead of the miner's IP on the default port.
+    pub fn set_address(&mut self, addr: SocketAddr) {
+        self.ip = addr.ip();
+        self.port = addr.port();
+    }
+
     /// Execute the actual HTTP request
     async fn execute_request(
         &self,
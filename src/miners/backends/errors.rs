@@ -0,0 +1,67 @@
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::data::device::MinerFirmware;
+
+/// Errors returned by control commands before anything is sent to the miner.
+///
+/// Control functions return `anyhow::Result`, callers can `downcast_ref::<ControlError>()` to
+/// tell a refused request apart from a transport failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlError {
+    /// The firmware, or the mode it is currently in, does not allow this operation
+    UnsupportedOperation(String),
+    /// A parameter is outside of the range the miner accepts
+    InvalidParameter(String),
+}
+
+impl Display for ControlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlError::UnsupportedOperation(message) => {
+                write!(f, "Unsupported operation: {message}")
+            }
+            ControlError::InvalidParameter(message) => {
+                write!(f, "Invalid parameter: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ControlError {}
+
+/// Errors raised by a miner's API clients, the factory and data collection that are not
+/// transport failures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MinerError {
+    /// The [`CommandPolicy`][`super::policy::CommandPolicy`] does not allow this command
+    PolicyDenied(String),
+    /// The operation was stopped by its cancellation token
+    Cancelled,
+    /// The miner rejected every set of credentials the client has
+    AuthenticationFailed,
+    /// The miner was identified, but the cargo feature of its backend is disabled
+    BackendNotCompiled(&'static str),
+    /// The miner runs another firmware than the one it was identified as, such as Braiins OS
+    /// installed over stock firmware whose web UI was kept
+    FirmwareMismatch(MinerFirmware),
+}
+
+impl Display for MinerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MinerError::PolicyDenied(command) => {
+                write!(f, "Command {command} is denied by the command policy")
+            }
+            MinerError::Cancelled => write!(f, "Operation was cancelled"),
+            MinerError::AuthenticationFailed => write!(f, "Authentication failed"),
+            MinerError::BackendNotCompiled(feature) => {
+                write!(f, "Backend not compiled in, enable the `{feature}` feature")
+            }
+            MinerError::FirmwareMismatch(firmware) => {
+                write!(f, "Miner runs {firmware} firmware")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MinerError {}
@@ -0,0 +1,310 @@
+//! Inspection of firmware images before they are uploaded.
+//!
+//! Flashing an image built for another model can brick a control board, so uploads identify the
+//! model and build date an image targets and refuse mismatches or downgrades unless forced.
+//!
+//! - Bitmain images are named `Antminer-<model>-<variant>-<YYYYMMDDhhmmss>.tar.gz`, the same name
+//!   is kept in the gzip header and in the entries of uncompressed tar images.
+//! - WhatsMiner `.bin` images start with an ASCII descriptor naming the model family, e.g.
+//!   `M50S`, and the build date.
+
+use chrono::NaiveDate;
+use regex::Regex;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use crate::data::device::models::antminer::AntMinerModel;
+use crate::data::device::{DeviceInfo, MinerMake, MinerModel};
+use crate::miners::backends::errors::ControlError;
+
+static WHATSMINER_FAMILY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bM\d{2}S?(\+\+|\+)?").expect("valid regex"));
+static BUILD_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[^0-9])(20\d{6})").expect("valid regex"));
+
+/// Size of a tar header block.
+const TAR_BLOCK: usize = 512;
+
+/// The model and build date a firmware image was made for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirmwareImage {
+    pub make: MinerMake,
+    /// The model as named by the image, e.g. `S19j Pro` or `M50S`
+    pub model: String,
+    pub date: Option<NaiveDate>,
+}
+
+impl FirmwareImage {
+    /// Identify an image from its file name and leading bytes, the first few KiB are enough.
+    pub fn inspect(file_name: &str, header: &[u8]) -> Result<Self, ControlError> {
+        let mut names = vec![file_name.to_string()];
+        names.extend(gzip_file_name(header));
+        names.extend(tar_entry_names(header));
+        if let Some(image) = names.iter().find_map(|name| Self::from_bitmain_name(name)) {
+            return Ok(image);
+        }
+
+        let descriptor = String::from_utf8_lossy(&header[..header.len().min(TAR_BLOCK)]);
+        if let Some(image) = Self::from_whatsminer_descriptor(&descriptor)
+            .or_else(|| Self::from_whatsminer_descriptor(file_name))
+        {
+            return Ok(image);
+        }
+
+        Err(ControlError::InvalidParameter(format!(
+            "could not identify the model firmware image {file_name} is built for"
+        )))
+    }
+
+    fn from_bitmain_name(name: &str) -> Option<Self> {
+        let name = name.rsplit('/').next()?;
+        let tokens: Vec<&str> = name.split(['-', '_', '.']).collect();
+        let start = tokens
+            .iter()
+            .position(|t| t.eq_ignore_ascii_case("antminer"))?
+            + 1;
+        let rest = &tokens[start..];
+
+        // take the longest run of tokens that names a model, e.g. `S19j-Pro` over `S19j`
+        let model = (1..=rest.len()).rev().find_map(|n| {
+            let model = rest[..n].join(" ");
+            AntMinerModel::from_str(&format!("ANTMINER {}", model.to_uppercase()))
+                .ok()
+                .map(|_| model)
+        })?;
+
+        Some(Self {
+            make: MinerMake::AntMiner,
+            model,
+            date: build_date(name),
+        })
+    }
+
+    fn from_whatsminer_descriptor(descriptor: &str) -> Option<Self> {
+        let family = WHATSMINER_FAMILY.find(descriptor)?.as_str().to_string();
+        Some(Self {
+            make: MinerMake::WhatsMiner,
+            model: family,
+            date: build_date(descriptor),
+        })
+    }
+
+    /// Whether this image is built for `model`.
+    pub fn targets(&self, model: &MinerModel) -> bool {
+        match (&self.make, model) {
+            (MinerMake::AntMiner, MinerModel::AntMiner(m)) => {
+                AntMinerModel::from_str(&format!("ANTMINER {}", self.model.to_uppercase()))
+                    .is_ok_and(|image_model| &image_model == m)
+            }
+            (MinerMake::WhatsMiner, MinerModel::WhatsMiner(m)) => {
+                whatsminer_family(&m.to_string()) == whatsminer_family(&self.model)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Refuse to flash `image` onto a miner it isn't built for, or one running newer firmware.
+///
+/// `current_firmware` is the firmware version reported by the miner, the check is skipped when
+/// its build date can't be read. Passing `force` skips all checks.
+pub fn check_image(
+    device: &DeviceInfo,
+    current_firmware: Option<&str>,
+    image: &FirmwareImage,
+    force: bool,
+) -> Result<(), ControlError> {
+    if force {
+        return Ok(());
+    }
+    if !image.targets(&device.model) {
+        return Err(ControlError::InvalidParameter(format!(
+            "firmware image is built for {} {}, the miner is {} {}",
+            image.make, image.model, device.make, device.model
+        )));
+    }
+    if let (Some(image_date), Some(current_date)) =
+        (image.date, current_firmware.and_then(firmware_date))
+        && image_date < current_date
+    {
+        return Err(ControlError::InvalidParameter(format!(
+            "firmware image from {image_date} is older than the installed firmware from {current_date}"
+        )));
+    }
+    Ok(())
+}
+
+/// Read the build date from a firmware version, either a `YYYYMMDD` stamp as used by
+/// WhatsMiner and Bitmain file names or the `date` output Antminers report, e.g.
+/// `Thu Jul 11 10:29:31 CST 2024`.
+pub fn firmware_date(version: &str) -> Option<NaiveDate> {
+    build_date(version).or_else(|| {
+        let tokens: Vec<&str> = version.split_whitespace().collect();
+        let [_, month, day, .., year] = tokens.as_slice() else {
+            return None;
+        };
+        NaiveDate::parse_from_str(&format!("{month} {day} {year}"), "%b %d %Y").ok()
+    })
+}
+
+fn build_date(text: &str) -> Option<NaiveDate> {
+    BUILD_DATE
+        .captures_iter(text)
+        .find_map(|c| NaiveDate::parse_from_str(&c[1], "%Y%m%d").ok())
+}
+
+/// The family of a WhatsMiner model, e.g. `M50S++` for both `M50S++VK20` and `M50SPlusPlusVK20`.
+fn whatsminer_family(model: &str) -> Option<String> {
+    let model = model.replace("PlusPlus", "++").replace("Plus", "+");
+    WHATSMINER_FAMILY
+        .find(&model)
+        .map(|m| m.as_str().to_string())
+}
+
+/// The original file name stored in a gzip header, if any.
+fn gzip_file_name(data: &[u8]) -> Option<String> {
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+
+    if data.len() < 10 || data[..2] != [0x1f, 0x8b] {
+        return None;
+    }
+    let flags = data[3];
+    if flags & FNAME == 0 {
+        return None;
+    }
+    let mut offset = 10;
+    if flags & FEXTRA != 0 {
+        let extra = u16::from_le_bytes([*data.get(10)?, *data.get(11)?]) as usize;
+        offset += 2 + extra;
+    }
+    let name = data.get(offset..)?;
+    let end = name.iter().position(|&b| b == 0)?;
+    Some(String::from_utf8_lossy(&name[..end]).into_owned())
+}
+
+/// The entry names of an uncompressed tar archive, as far as `data` reaches.
+fn tar_entry_names(data: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut offset = 0;
+    while let Some(header) = data.get(offset..offset + TAR_BLOCK) {
+        if header[257..262] != *b"ustar" {
+            break;
+        }
+        let name = &header[..100];
+        let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+        names.push(String::from_utf8_lossy(&name[..end]).into_owned());
+
+        let size = std::str::from_utf8(&header[124..136])
+            .ok()
+            .map(|s| s.trim_matches(|c: char| c == '\0' || c.is_whitespace()))
+            .and_then(|s| usize::from_str_radix(s, 8).ok())
+            .unwrap_or(0);
+        offset += TAR_BLOCK + size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::whatsminer::WhatsMinerModel;
+    use crate::data::device::{HashAlgorithm, MinerFirmware};
+
+    fn device(model: MinerModel) -> DeviceInfo {
+        let make = match model {
+            MinerModel::WhatsMiner(_) => MinerMake::WhatsMiner,
+            _ => MinerMake::AntMiner,
+        };
+        DeviceInfo::new(make, model, MinerFirmware::Stock, HashAlgorithm::SHA256)
+    }
+
+    fn tar_header(name: &str) -> Vec<u8> {
+        let mut header = vec![0u8; TAR_BLOCK];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[124..135].copy_from_slice(b"00000000000");
+        header[257..263].copy_from_slice(b"ustar\0");
+        header
+    }
+
+    fn gzip_header(name: &str) -> Vec<u8> {
+        let mut header = vec![0x1f, 0x8b, 0x08, 0x08, 0, 0, 0, 0, 0, 0x03];
+        header.extend_from_slice(name.as_bytes());
+        header.push(0);
+        header
+    }
+
+    #[test]
+    fn test_inspect_bitmain_images() {
+        let image =
+            FirmwareImage::inspect("Antminer-S19j-Pro-merge-release-20221226124238.tar.gz", &[])
+                .unwrap();
+        assert_eq!(image.make, MinerMake::AntMiner);
+        assert_eq!(image.model, "S19j Pro");
+        assert_eq!(image.date, NaiveDate::from_ymd_opt(2022, 12, 26));
+
+        // renamed downloads are identified by the name kept in the archive
+        let gzip = gzip_header("Antminer-S19-merge-release-20230301110000.tar");
+        let image = FirmwareImage::inspect("firmware.tar.gz", &gzip).unwrap();
+        assert_eq!(image.model, "S19");
+
+        let mut tar = tar_header("runme.sh");
+        tar.extend(tar_header("Antminer-S19-XP-user-release-20240105.bmu"));
+        let image = FirmwareImage::inspect("upload.tar", &tar).unwrap();
+        assert_eq!(image.model, "S19 XP");
+        assert_eq!(image.date, NaiveDate::from_ymd_opt(2024, 1, 5));
+    }
+
+    #[test]
+    fn test_inspect_whatsminer_images() {
+        let descriptor = b"WhatsMiner M50S firmware h616 build 20230615.16.REL\n\0\0\0";
+        let image = FirmwareImage::inspect("upgrade.bin", descriptor).unwrap();
+        assert_eq!(image.make, MinerMake::WhatsMiner);
+        assert_eq!(image.model, "M50S");
+        assert_eq!(image.date, NaiveDate::from_ymd_opt(2023, 6, 15));
+
+        assert!(image.targets(&MinerModel::WhatsMiner(WhatsMinerModel::M50SVH20)));
+        assert!(!image.targets(&MinerModel::WhatsMiner(WhatsMinerModel::M50SPlusPlusVK20)));
+
+        assert!(FirmwareImage::inspect("upgrade.bin", b"\x7fELF").is_err());
+    }
+
+    #[test]
+    fn test_check_image_model_mismatch() {
+        let image = FirmwareImage::inspect("Antminer-S19-merge-release-20221226124238.tar.gz", &[])
+            .unwrap();
+        let s19j_pro = device(MinerModel::AntMiner(AntMinerModel::S19jPro));
+
+        assert_eq!(
+            check_image(&s19j_pro, None, &image, false),
+            Err(ControlError::InvalidParameter(
+                "firmware image is built for AntMiner S19, the miner is AntMiner S19jPro"
+                    .to_string()
+            ))
+        );
+        assert!(check_image(&s19j_pro, None, &image, true).is_ok());
+
+        let s19 = device(MinerModel::AntMiner(AntMinerModel::S19));
+        assert!(check_image(&s19, None, &image, false).is_ok());
+    }
+
+    #[test]
+    fn test_check_image_downgrade() {
+        let image =
+            FirmwareImage::inspect("Antminer-S19j-Pro-merge-release-20221226124238.tar.gz", &[])
+                .unwrap();
+        let s19j_pro = device(MinerModel::AntMiner(AntMinerModel::S19jPro));
+
+        let newer = Some("Thu Jul 11 10:29:31 CST 2024");
+        assert!(check_image(&s19j_pro, newer, &image, false).is_err());
+        assert!(check_image(&s19j_pro, newer, &image, true).is_ok());
+
+        let older = Some("Mon Mar  7 12:00:00 CST 2022");
+        assert!(check_image(&s19j_pro, older, &image, false).is_ok());
+
+        assert_eq!(
+            firmware_date("20230615.16.REL"),
+            NaiveDate::from_ymd_opt(2023, 6, 15)
+        );
+    }
+}
@@ -1,22 +1,33 @@
 use crate::data::board::{BoardData, ChipData};
 use crate::data::device::{
-    DeviceInfo, HashAlgorithm, MinerControlBoard, MinerFirmware, MinerMake, MinerModel,
+    ControlBoardType, DeviceInfo, HashAlgorithm, MinerControlBoard, MinerFirmware, MinerHardware,
+    MinerMake, MinerModel, TuningLimits,
 };
 use crate::data::fan::FanData;
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::message::{MessageSeverity, MinerMessage};
-use crate::data::pool::{PoolData, PoolURL};
+use crate::data::health::HealthThresholds;
+use crate::data::message::{MessageSeverity, MinerMessage, MinerMessageKind};
+use crate::data::miner::AutotuneProfileBounds;
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::RPCReadLimits;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_pointer,
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData, FromValue,
+    get_by_pointer,
 };
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use macaddr::MacAddr;
 use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
 use rpc::LUXMinerRPCAPI;
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::str::FromStr;
@@ -29,22 +40,55 @@ pub struct LuxMinerV1 {
     pub ip: IpAddr,
     pub rpc: LUXMinerRPCAPI,
     pub device_info: DeviceInfo,
+    pub labels: Option<HashMap<String, String>>,
+    pub health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
 }
 
 impl LuxMinerV1 {
     pub fn new(ip: IpAddr, model: MinerModel) -> Self {
         LuxMinerV1 {
             ip,
-            rpc: LUXMinerRPCAPI::new(ip),
+            rpc: LUXMinerRPCAPI::new(ip, None),
             device_info: DeviceInfo::new(
                 MinerMake::AntMiner,
                 model,
                 MinerFirmware::LuxOS,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
         }
     }
 
+    /// Limit the connections the API client opens at once, by default
+    /// [`DEFAULT_CONNECTIONS`][`crate::miners::api::rpc::limit::DEFAULT_CONNECTIONS`].
+    pub fn with_connection_limit(mut self, limit: ConnectionLimit) -> Self {
+        self.rpc.connections = limit;
+        self
+    }
+
+    fn board_count(&self) -> usize {
+        self.device_info.hardware.boards.unwrap_or(3) as usize
+    }
+
+    /// Boards whose `healthchipget` query failed, e.g. a dead board, if hashboards were collected.
+    fn failed_chip_queries(&self, data: &FieldData) -> Vec<usize> {
+        let Some(boards) = data.get(&DataField::Hashboards) else {
+            return vec![];
+        };
+        CHIP_TAGS
+            .iter()
+            .take(self.board_count())
+            .enumerate()
+            .filter(|(_, tag)| boards.get(**tag).is_none())
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
     fn parse_temp_string(temp_str: &str) -> Option<Temperature> {
         let temps: Vec<f64> = temp_str
             .split('-')
@@ -71,6 +115,25 @@ impl APIClient for LuxMinerV1 {
     }
 }
 
+/// Tags for the per board `healthchipget` and `voltageget` responses, LuxOS runs on Antminers
+/// with at most four boards.
+const CHIP_TAGS: [&str; 4] = ["CHIPS_0", "CHIPS_1", "CHIPS_2", "CHIPS_3"];
+const VOLTAGE_TAGS: [&str; 4] = ["VOLTAGE_0", "VOLTAGE_1", "VOLTAGE_2", "VOLTAGE_3"];
+
+/// Words in the text of LuxOS status messages and the kind of problem they point to, first
+/// match wins.
+const MESSAGE_KINDS: [(&str, MinerMessageKind); 9] = [
+    ("fan", MinerMessageKind::FanFailure),
+    ("overheat", MinerMessageKind::OverTemperature),
+    ("temp", MinerMessageKind::OverTemperature),
+    ("psu", MinerMessageKind::PsuFailure),
+    ("power supply", MinerMessageKind::PsuFailure),
+    ("pool", MinerMessageKind::PoolIssue),
+    ("network", MinerMessageKind::NetworkIssue),
+    ("tun", MinerMessageKind::TuningIssue),
+    ("board", MinerMessageKind::BoardFailure),
+];
+
 impl GetDataLocations for LuxMinerV1 {
     fn get_locations(&self, data_field: DataField) -> Vec<DataLocation> {
         let version_cmd = MinerCommand::RPC {
@@ -123,6 +186,11 @@ impl GetDataLocations for LuxMinerV1 {
             parameters: None,
         };
 
+        let atm_cmd = MinerCommand::RPC {
+            command: "atm",
+            parameters: None,
+        };
+
         match data_field {
             DataField::Mac => vec![(
                 config_cmd,
@@ -164,117 +232,76 @@ impl GetDataLocations for LuxMinerV1 {
                     tag: None,
                 },
             )],
-            DataField::Hashboards => vec![
-                (
-                    MinerCommand::RPC {
-                        command: "healthchipget",
-                        parameters: Some(Value::String("0".to_string())),
-                    },
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some("/CHIPS"),
-                        tag: Some("CHIPS_0"),
-                    },
-                ),
-                (
-                    MinerCommand::RPC {
-                        command: "healthchipget",
-                        parameters: Some(Value::String("1".to_string())),
-                    },
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some("/CHIPS"),
-                        tag: Some("CHIPS_1"),
-                    },
-                ),
-                (
-                    MinerCommand::RPC {
-                        command: "healthchipget",
-                        parameters: Some(Value::String("2".to_string())),
-                    },
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some("/CHIPS"),
-                        tag: Some("CHIPS_2"),
-                    },
-                ),
-                (
-                    stats_cmd,
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some("/STATS/1"),
-                        tag: Some("STATS"),
-                    },
-                ),
-                (
-                    temps_cmd.clone(),
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some(""),
-                        tag: None,
-                    },
-                ),
-                (
-                    MinerCommand::RPC {
-                        command: "voltageget",
-                        parameters: Some(Value::String("0".to_string())),
-                    },
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some("/VOLTAGE"),
-                        tag: Some("VOLTAGE_0"),
-                    },
-                ),
-                (
-                    MinerCommand::RPC {
-                        command: "voltageget",
-                        parameters: Some(Value::String("1".to_string())),
-                    },
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some("/VOLTAGE"),
-                        tag: Some("VOLTAGE_1"),
-                    },
-                ),
-                (
-                    MinerCommand::RPC {
-                        command: "voltageget",
-                        parameters: Some(Value::String("2".to_string())),
-                    },
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some("/VOLTAGE"),
-                        tag: Some("VOLTAGE_2"),
-                    },
-                ),
-                (
-                    MinerCommand::RPC {
-                        command: "voltageget",
-                        parameters: Some(Value::String("0".to_string())),
-                    },
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some("/VOLTAGE"),
-                        tag: Some("VOLTAGE_PSU"),
-                    },
-                ),
-                (
-                    temps_cmd,
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some(""),
-                        tag: Some("TEMPS"),
-                    },
-                ),
-                (
-                    devs_cmd,
-                    DataExtractor {
-                        func: get_by_pointer,
-                        key: Some("/DEVS"),
-                        tag: Some("DEVS"),
-                    },
-                ),
-            ],
+            DataField::Hashboards => {
+                let per_board =
+                    |command: &'static str, key: &'static str, tags: [&'static str; 4]| {
+                        tags.into_iter().take(self.board_count()).enumerate().map(
+                            move |(idx, tag)| {
+                                (
+                                    MinerCommand::RPC {
+                                        command,
+                                        parameters: Some(Value::String(idx.to_string())),
+                                    },
+                                    DataExtractor {
+                                        func: get_by_pointer,
+                                        key: Some(key),
+                                        tag: Some(tag),
+                                    },
+                                )
+                            },
+                        )
+                    };
+
+                let mut locations: Vec<DataLocation> =
+                    per_board("healthchipget", "/CHIPS", CHIP_TAGS).collect();
+                locations.extend(per_board("voltageget", "/VOLTAGE", VOLTAGE_TAGS));
+                locations.extend([
+                    (
+                        stats_cmd,
+                        DataExtractor {
+                            func: get_by_pointer,
+                            key: Some("/STATS/1"),
+                            tag: Some("STATS"),
+                        },
+                    ),
+                    (
+                        temps_cmd.clone(),
+                        DataExtractor {
+                            func: get_by_pointer,
+                            key: Some(""),
+                            tag: None,
+                        },
+                    ),
+                    (
+                        MinerCommand::RPC {
+                            command: "voltageget",
+                            parameters: Some(Value::String("0".to_string())),
+                        },
+                        DataExtractor {
+                            func: get_by_pointer,
+                            key: Some("/VOLTAGE"),
+                            tag: Some("VOLTAGE_PSU"),
+                        },
+                    ),
+                    (
+                        temps_cmd,
+                        DataExtractor {
+                            func: get_by_pointer,
+                            key: Some(""),
+                            tag: Some("TEMPS"),
+                        },
+                    ),
+                    (
+                        devs_cmd,
+                        DataExtractor {
+                            func: get_by_pointer,
+                            key: Some("/DEVS"),
+                            tag: Some("DEVS"),
+                        },
+                    ),
+                ]);
+                locations
+            }
             DataField::LightFlashing => vec![(
                 config_cmd,
                 DataExtractor {
@@ -332,23 +359,66 @@ impl GetDataLocations for LuxMinerV1 {
                         tag: Some("Profiles"),
                     },
                 ),
+                (
+                    atm_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/ATM/0"),
+                        tag: Some("ATM"),
+                    },
+                ),
             ],
-            DataField::SerialNumber => vec![(
-                config_cmd,
+            DataField::Autotune => vec![(
+                atm_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/CONFIG/0/SerialNumber"),
+                    key: Some("/ATM/0"),
                     tag: None,
                 },
             )],
-            DataField::Messages => vec![(
-                summary_cmd,
+            DataField::SerialNumber => vec![(
+                config_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/STATUS"),
+                    key: Some("/CONFIG/0/SerialNumber"),
                     tag: None,
                 },
             )],
+            // the profiles and ATM settings tell whether ATM has scaled the miner down
+            DataField::Messages => vec![
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/STATUS"),
+                        tag: Some("STATUS"),
+                    },
+                ),
+                (
+                    config_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/CONFIG/0/Profile"),
+                        tag: Some("Profile"),
+                    },
+                ),
+                (
+                    profiles_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/PROFILES"),
+                        tag: Some("Profiles"),
+                    },
+                ),
+                (
+                    atm_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/ATM/0"),
+                        tag: Some("ATM"),
+                    },
+                ),
+            ],
             DataField::ControlBoardVersion => vec![(
                 config_cmd,
                 DataExtractor {
@@ -365,6 +435,14 @@ impl GetDataLocations for LuxMinerV1 {
                     tag: None,
                 },
             )],
+            DataField::AverageHashrate => vec![(
+                summary_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/SUMMARY/0/GHS 30m"),
+                    tag: None,
+                },
+            )],
             DataField::ExpectedHashrate => vec![(
                 devs_cmd,
                 DataExtractor {
@@ -394,7 +472,69 @@ impl GetIP for LuxMinerV1 {
 
 impl GetDeviceInfo for LuxMinerV1 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for LuxMinerV1 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for LuxMinerV1 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for LuxMinerV1 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for LuxMinerV1 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for LuxMinerV1 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.rpc.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.rpc.policy = policy;
+    }
+}
+
+impl GetCapabilities for LuxMinerV1 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_fault_light: true,
+            supports_board_tuning: true,
+            supports_restart: true,
+            supports_pause: true,
+            has_chip_telemetry: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -405,26 +545,28 @@ impl CollectData for LuxMinerV1 {
 }
 
 impl GetMAC for LuxMinerV1 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
         data.extract::<String>(DataField::Mac)
-            .and_then(|s| MacAddr::from_str(&s.to_uppercase()).ok())
+            .and_then(|s| parse_mac_str(&s))
     }
 }
 
+impl GetReportedIp for LuxMinerV1 {}
+
 impl GetHostname for LuxMinerV1 {
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::Hostname)
     }
 }
 
 impl GetApiVersion for LuxMinerV1 {
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::ApiVersion)
     }
 }
 
 impl GetFluidTemperature for LuxMinerV1 {
-    fn parse_fluid_temperature(&self, data: &HashMap<DataField, Value>) -> Option<Temperature> {
+    fn parse_fluid_temperature(&self, data: &FieldData) -> Option<Temperature> {
         let temps_response = data.get(&DataField::FluidTemperature)?;
 
         let metadata = temps_response.get("METADATA")?.as_array()?;
@@ -489,14 +631,18 @@ impl GetFluidTemperature for LuxMinerV1 {
     }
 }
 
+impl GetFluidFlow for LuxMinerV1 {}
+
+impl GetFluidPressure for LuxMinerV1 {}
+
 impl GetFirmwareVersion for LuxMinerV1 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
 }
 
 impl GetHashboards for LuxMinerV1 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         let mut boards: Vec<BoardData> = Vec::new();
         let board_count = self.device_info.hardware.boards.unwrap_or(3);
         for idx in 0..board_count {
@@ -511,10 +657,14 @@ impl GetHashboards for LuxMinerV1 {
                 working_chips: None,
                 serial_number: None,
                 chips: vec![],
+                sensor_temperatures: vec![],
                 voltage: None,
                 frequency: None,
+                wattage: None,
                 tuned: Some(false),
                 active: Some(false),
+                status: None,
+                extra: None,
             });
         }
 
@@ -524,18 +674,18 @@ impl GetHashboards for LuxMinerV1 {
             .and_then(|obj| obj.get("DEVS"))
             .and_then(|v| v.as_array())
         {
-            for (idx, dev) in devs_data.iter().enumerate() {
+            for (dev, board) in devs_data.iter().zip(boards.iter_mut()) {
                 if let Some(dev_object) = dev.as_object() {
                     if let Some(serial_number) =
                         dev_object.get("SerialNumber").and_then(|v| v.as_str())
                     {
-                        boards[idx].serial_number = Some(serial_number.to_string());
+                        board.serial_number = Some(serial_number.to_string());
                     }
 
                     if let Some(expected_hashrate) =
                         dev_object.get("Nominal MHS").and_then(|v| v.as_f64())
                     {
-                        boards[idx].expected_hashrate = Some(
+                        board.expected_hashrate = Some(
                             HashRate {
                                 value: expected_hashrate,
                                 unit: HashRateUnit::MegaHash,
@@ -641,11 +791,13 @@ impl GetHashboards for LuxMinerV1 {
         }
 
         if let Some(voltage_data) = data.get(&DataField::Hashboards) {
-            for (idx, tag) in (0..3).map(|i| (i, format!("/VOLTAGE_{}/0", i))) {
-                if let Some(voltage_object) = voltage_data.pointer(&tag).and_then(|v| v.as_object())
+            for (board, tag) in boards.iter_mut().zip(VOLTAGE_TAGS) {
+                if let Some(voltage_object) = voltage_data
+                    .pointer(&format!("/{tag}/0"))
+                    .and_then(|v| v.as_object())
                     && let Some(voltage) = voltage_object.get("Voltage").and_then(|v| v.as_f64())
                 {
-                    boards[idx].voltage = match voltage {
+                    board.voltage = match voltage {
                         0.0 => voltage_data
                             .pointer("/VOLTAGE_PSU/0/Voltage")
                             .and_then(|v| v.as_f64())
@@ -657,32 +809,47 @@ impl GetHashboards for LuxMinerV1 {
         }
 
         if let Some(chips_data) = data.get(&DataField::Hashboards) {
-            for (idx, tag) in (0..3).map(|i| (i, format!("CHIPS_{}", i))) {
-                if let Some(arr) = chips_data.get(&tag).and_then(|v| v.as_array()) {
-                    boards[idx].chips = arr
+            for (board, tag) in boards.iter_mut().zip(CHIP_TAGS) {
+                if let Some(arr) = chips_data.get(tag).and_then(|v| v.as_array()) {
+                    board.chips = arr
                         .iter()
                         .filter_map(|v| v.as_object())
-                        .map(|o| ChipData {
-                            position: o.get("Chip").and_then(|v| v.as_u64()).unwrap() as u16,
-                            temperature: None,
-                            hashrate: o.get("GHS 1m").and_then(|v| v.as_f64()).map(|hr| HashRate {
-                                value: hr,
-                                unit: HashRateUnit::GigaHash,
-                                algo: "SHA256".into(),
-                            }),
-                            frequency: o
-                                .get("Frequency")
-                                .and_then(|v| v.as_f64())
-                                .map(Frequency::from_megahertz),
-                            tuned: o.get("Healthy").and_then(|v| v.as_str()).map(|s| s == "Y"),
-                            working: o.get("Healthy").and_then(|v| v.as_str()).map(|s| s == "Y"),
-                            voltage: None,
+                        .filter_map(|o| {
+                            Some(ChipData {
+                                position: o.get("Chip")?.as_u64()? as u16,
+                                temperature: None,
+                                hashrate: o.get("GHS 1m").and_then(|v| v.as_f64()).map(|hr| {
+                                    HashRate {
+                                        value: hr,
+                                        unit: HashRateUnit::GigaHash,
+                                        algo: "SHA256".into(),
+                                    }
+                                }),
+                                frequency: o
+                                    .get("Frequency")
+                                    .and_then(|v| v.as_f64())
+                                    .map(Frequency::from_megahertz),
+                                tuned: o.get("Healthy").and_then(|v| v.as_str()).map(|s| s == "Y"),
+                                working: o
+                                    .get("Healthy")
+                                    .and_then(|v| v.as_str())
+                                    .map(|s| s == "Y"),
+                                voltage: None,
+                                estimated: false,
+                            })
                         })
                         .collect();
                 }
             }
         }
 
+        // a failed chip query means the board didn't answer, not that it wasn't asked
+        for idx in self.failed_chip_queries(data) {
+            if let Some(board) = boards.get_mut(idx) {
+                board.active = Some(false);
+            }
+        }
+
         for b in &mut boards {
             if !b.chips.is_empty() {
                 b.working_chips = Some(
@@ -730,7 +897,7 @@ impl GetHashboards for LuxMinerV1 {
 }
 
 impl GetHashrate for LuxMinerV1 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::Hashrate, |f| {
             HashRate {
                 value: f,
@@ -743,7 +910,7 @@ impl GetHashrate for LuxMinerV1 {
 }
 
 impl GetExpectedHashrate for LuxMinerV1 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         let data = data
             .get(&DataField::ExpectedHashrate)
             .and_then(|v| v.as_array())?;
@@ -751,11 +918,8 @@ impl GetExpectedHashrate for LuxMinerV1 {
 
         let mut expected_hashrate = 0.0;
 
-        for idx in 0..expected_boards {
-            if let Some(hashrate) = data[idx as usize]
-                .get("Nominal MHS")
-                .and_then(|v| v.as_f64())
-            {
+        for board in data.iter().take(expected_boards as usize) {
+            if let Some(hashrate) = board.get("Nominal MHS").and_then(|v| v.as_f64()) {
                 expected_hashrate += hashrate;
             }
         }
@@ -771,15 +935,28 @@ impl GetExpectedHashrate for LuxMinerV1 {
     }
 }
 
+impl GetAverageHashrate for LuxMinerV1 {
+    fn parse_average_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::AverageHashrate, |f| {
+            HashRate {
+                value: f,
+                unit: HashRateUnit::GigaHash,
+                algo: String::from("SHA256"),
+            }
+            .as_unit(HashRateUnit::TeraHash)
+        })
+    }
+}
+
 impl GetFans for LuxMinerV1 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         data.get(&DataField::Fans)
             .and_then(|v| v.as_array())
             .into_iter()
             .flatten()
             .enumerate()
             .filter_map(|(idx, fan_info)| {
-                let rpm = fan_info.get("RPM")?.as_f64()?;
+                let rpm = f64::from_value(fan_info.get("RPM")?)?;
                 Some(FanData {
                     position: idx as i16,
                     rpm: Some(AngularVelocity::from_rpm(rpm)),
@@ -790,28 +967,35 @@ impl GetFans for LuxMinerV1 {
 }
 
 impl GetLightFlashing for LuxMinerV1 {
-    fn parse_light_flashing(&self, data: &HashMap<DataField, Value>) -> Option<bool> {
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
+        // "auto" leaves the red LED to show faults, "blink" is the locate pattern
         data.extract::<String>(DataField::LightFlashing)
-            .map(|s| s.to_lowercase() != "auto")
+            .and_then(|led| match led.to_lowercase().as_str() {
+                "auto" | "off" => Some(false),
+                "blink" | "on" => Some(true),
+                _ => None,
+            })
     }
 }
 
 impl GetUptime for LuxMinerV1 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
     }
 }
 
 impl GetIsMining for LuxMinerV1 {
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
         data.extract::<f64>(DataField::IsMining)
             .map(|hr| hr > 0.0)
             .unwrap_or(false)
     }
 }
 
+impl GetStatus for LuxMinerV1 {}
+
 impl GetPools for LuxMinerV1 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
         data.get(&DataField::Pools)
             .and_then(|v| v.as_array())
             .into_iter()
@@ -822,8 +1006,9 @@ impl GetPools for LuxMinerV1 {
                 url: pool
                     .get("URL")
                     .and_then(|v| v.as_str())
-                    .map(|s| PoolURL::from(s.to_string())),
+                    .and_then(PoolURL::parse),
                 user: pool.get("User").and_then(|v| v.as_str()).map(String::from),
+                password: None,
                 alive: pool
                     .get("Status")
                     .and_then(|v| v.as_str())
@@ -831,13 +1016,17 @@ impl GetPools for LuxMinerV1 {
                 active: pool.get("Stratum Active").and_then(|v| v.as_bool()),
                 accepted_shares: pool.get("Accepted").and_then(|v| v.as_u64()),
                 rejected_shares: pool.get("Rejected").and_then(|v| v.as_u64()),
+                last_share_difficulty: pool.get("Last Share Difficulty").and_then(|v| v.as_f64()),
+                last_share_time: None,
+                group: None,
+                quota: None,
             })
             .collect()
     }
 }
 
 impl GetSerialNumber for LuxMinerV1 {
-    fn parse_serial_number(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_serial_number(&self, data: &FieldData) -> Option<String> {
         match data.extract::<String>(DataField::SerialNumber) {
             Some(s) if !s.is_empty() => Some(s),
             _ => None,
@@ -846,42 +1035,102 @@ impl GetSerialNumber for LuxMinerV1 {
 }
 
 impl GetControlBoardVersion for LuxMinerV1 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
         data.extract::<String>(DataField::ControlBoardVersion)
             .and_then(|s| MinerControlBoard::from_str(&s).ok())
     }
+
+    fn parse_control_board_type(&self, data: &FieldData) -> Option<ControlBoardType> {
+        data.extract::<String>(DataField::ControlBoardVersion)
+            .and_then(|s| s.parse().ok())
+    }
 }
 
 impl GetWattage for LuxMinerV1 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<f64, _>(DataField::Wattage, Power::from_watts)
     }
 }
 
 impl GetWattageLimit for LuxMinerV1 {
-    fn parse_wattage_limit(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    /// The wattage of the current profile, or of the ATM max profile while ATM is enabled, as
+    /// ATM only lowers the profile temporarily.
+    fn parse_wattage_limit(&self, data: &FieldData) -> Option<Power> {
         let wattage_limit_data = data.get(&DataField::WattageLimit)?;
-        let profile_name = wattage_limit_data.get("Profile")?.as_str()?;
-        let profiles = wattage_limit_data.get("Profiles")?.as_array()?;
+        let profile_name = match atm_max_profile(wattage_limit_data) {
+            Some(max_profile) => max_profile,
+            None => wattage_limit_data.get("Profile")?.as_str()?,
+        };
+        let watts = profile_watts(wattage_limit_data, profile_name)?;
 
-        let profile = profiles
-            .iter()
-            .find(|item| item.get("Profile Name").and_then(|v| v.as_str()) == Some(profile_name))?;
+        Some(Power::from_watts(watts))
+    }
+}
 
-        let watts = profile.get("Watts")?.as_f64()?;
+/// The profile ATM scales up to, if ATM is enabled, from a value with an `ATM` tag.
+fn atm_max_profile(data: &Value) -> Option<&str> {
+    let atm = data.get("ATM")?;
+    atm.get("Enabled")?
+        .as_bool()
+        .filter(|enabled| *enabled)
+        .and_then(|_| atm.get("MaxProfile")?.as_str())
+}
 
-        Some(Power::from_watts(watts))
+/// The wattage of the profile named `name`, from a value with a `Profiles` tag.
+fn profile_watts(data: &Value, name: &str) -> Option<f64> {
+    data.get("Profiles")?
+        .as_array()?
+        .iter()
+        .find(|item| item.get("Profile Name").and_then(|v| v.as_str()) == Some(name))?
+        .get("Watts")?
+        .as_f64()
+}
+
+impl GetFrequency for LuxMinerV1 {}
+impl GetVoltage for LuxMinerV1 {}
+impl GetWorkMode for LuxMinerV1 {}
+
+impl GetAutotune for LuxMinerV1 {
+    fn parse_autotune_enabled(&self, data: &FieldData) -> Option<bool> {
+        data.extract_nested::<bool>(DataField::Autotune, "Enabled")
+    }
+
+    fn parse_autotune_profile_bounds(&self, data: &FieldData) -> Option<AutotuneProfileBounds> {
+        Some(AutotuneProfileBounds {
+            min: data.extract_nested::<String>(DataField::Autotune, "MinProfile")?,
+            max: data.extract_nested::<String>(DataField::Autotune, "MaxProfile")?,
+        })
     }
 }
 
 impl GetPsuFans for LuxMinerV1 {}
 
+impl GetFanControl for LuxMinerV1 {}
+
+impl GetPsu for LuxMinerV1 {}
+
+impl GetPsuRails for LuxMinerV1 {}
+
 impl GetMessages for LuxMinerV1 {
-    fn parse_messages(&self, data: &HashMap<DataField, Value>) -> Vec<MinerMessage> {
-        data.get(&DataField::Messages)
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
+        let messages_data = data.get(&DataField::Messages);
+        // ATM lowering the profile explains a hashrate dip, so it is worth a note
+        let atm_downscaled = messages_data.and_then(|messages_data| {
+            let max_profile = atm_max_profile(messages_data)?;
+            let profile = messages_data.get("Profile")?.as_str()?;
+            (profile_watts(messages_data, profile)? < profile_watts(messages_data, max_profile)?)
+                .then(|| {
+                    MinerMessage::new(
+                        0,
+                        0,
+                        format!("ATM has scaled the profile down from {max_profile} to {profile}"),
+                        MessageSeverity::Info,
+                    )
+                    .with_kind(MinerMessageKind::TuningIssue)
+                })
+        });
+        messages_data
+            .and_then(|v| v.get("STATUS"))
             .and_then(|v| v.as_array())
             .into_iter()
             .flatten()
@@ -899,8 +1148,19 @@ impl GetMessages for LuxMinerV1 {
                         _ => MessageSeverity::Info,
                     };
                     MinerMessage::new(0, idx as u64, text.to_string(), severity)
+                        .with_kind(MinerMessageKind::from_text(text, &MESSAGE_KINDS))
                 })
             })
+            .chain(self.failed_chip_queries(data).into_iter().map(|idx| {
+                MinerMessage::new(
+                    0,
+                    0,
+                    format!("Could not read the chips of board {idx}"),
+                    MessageSeverity::Warning,
+                )
+                .with_kind(MinerMessageKind::BoardFailure)
+            }))
+            .chain(atm_downscaled)
             .collect()
     }
 }
@@ -912,7 +1172,11 @@ impl SetFaultLight for LuxMinerV1 {
             true => "blink",
             false => "auto",
         };
-        Ok(self.rpc.ledset("red", mode).await.is_ok())
+        let sent = execute_control(self, "ledset", json!(format!("red,{mode}")), async {
+            accepted(self.rpc.ledset("red", mode).await)
+        })
+        .await?;
+        Ok(confirm_fault_light(self, fault, sent).await)
     }
 }
 
@@ -920,14 +1184,118 @@ impl SetFaultLight for LuxMinerV1 {
 impl SetPowerLimit for LuxMinerV1 {
     #[allow(unused_variables)]
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting a power limit".to_string(),
+        ))?
+    }
+}
+
+impl SetCredentials for LuxMinerV1 {}
+
+impl EndpointControl for LuxMinerV1 {}
+
+#[async_trait]
+impl SetPassword for LuxMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the password".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetHostname for LuxMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the hostname".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPools for LuxMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting pools".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for LuxMinerV1 {
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        TuningLimits::validate_for(&self.device_info.model, frequency, voltage)?;
+        if let Some(boards) = self.device_info.hardware.boards
+            && board >= boards
+        {
+            Err(ControlError::InvalidParameter(format!(
+                "board {board} does not exist, the miner has {boards} boards"
+            )))?
+        }
+
+        // ATM scales the boards itself and would immediately override a manual setting
+        let atm_enabled = self
+            .rpc
+            .atm()
+            .await
+            .ok()
+            .and_then(|atm| atm.pointer("/ATM/0/Enabled").and_then(|v| v.as_bool()))
+            .unwrap_or(false);
+        if atm_enabled {
+            Err(ControlError::UnsupportedOperation(
+                "ATM is enabled, manual board tuning is locked".to_string(),
+            ))?
+        }
+
+        if let Some(frequency) = frequency {
+            let frequency = frequency.as_megahertz().round() as u64;
+            let set = execute_control(
+                self,
+                "frequencyset",
+                json!(format!("{board},{frequency}")),
+                async { accepted(self.rpc.frequencyset(board, frequency).await) },
+            )
+            .await?;
+            if !set {
+                return Ok(false);
+            }
+        }
+        if let Some(voltage) = voltage {
+            let voltage = (voltage.as_volts() * 100.0).round() / 100.0;
+            return execute_control(
+                self,
+                "voltageset",
+                json!(format!("{board},{voltage}")),
+                async { accepted(self.rpc.voltageset(board, voltage).await) },
+            )
+            .await;
+        }
+        Ok(true)
     }
 }
 
 #[async_trait]
 impl Restart for LuxMinerV1 {
-    async fn restart(&self) -> Result<bool> {
-        Ok(self.rpc.reboot_device().await.is_ok())
+    async fn restart_mining(&self) -> Result<bool> {
+        execute_control(self, "resetminer", Value::Null, async {
+            accepted(self.rpc.reset_miner().await)
+        })
+        .await
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        execute_control(self, "rebootdevice", Value::Null, async {
+            accepted(self.rpc.reboot_device().await)
+        })
+        .await
     }
 }
 
@@ -935,7 +1303,10 @@ impl Restart for LuxMinerV1 {
 impl Pause for LuxMinerV1 {
     #[allow(unused_variables)]
     async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
-        Ok(self.rpc.sleep().await.is_ok())
+        execute_control(self, "curtail", json!("sleep"), async {
+            accepted(self.rpc.sleep().await)
+        })
+        .await
     }
 }
 
@@ -943,7 +1314,16 @@ impl Pause for LuxMinerV1 {
 impl Resume for LuxMinerV1 {
     #[allow(unused_variables)]
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
-        Ok(self.rpc.wakeup().await.is_ok())
+        execute_control(self, "curtail", json!("wakeup"), async {
+            accepted(self.rpc.wakeup().await)
+        })
+        .await
+    }
+}
+
+impl SetRPCReadLimits for LuxMinerV1 {
+    fn set_rpc_read_limits(&mut self, limits: RPCReadLimits) {
+        self.rpc.set_read_limits(limits);
     }
 }
 
@@ -951,17 +1331,21 @@ impl Resume for LuxMinerV1 {
 mod tests {
     use super::*;
     use crate::data::device::models::antminer::AntMinerModel::S19KPro;
+    use crate::data::miner::MinerData;
     use crate::test::api::MockAPIClient;
+    use crate::test::invariants::assert_hashrate_magnitudes;
     use crate::test::json::luxminer::v1::{
-        CONFIG, DEVS, FANS, HEALTHCHIPGET_0, HEALTHCHIPGET_1, HEALTHCHIPGET_2, POOLS, POWER,
-        PROFILES, STATS, SUMMARY, TEMPS, VERSION, VOLTAGEGET_0, VOLTAGEGET_1, VOLTAGEGET_2,
+        ATM_DISABLED, ATM_DOWNSCALED, CONFIG, DEVS, FANS, HEALTHCHIPGET_0, HEALTHCHIPGET_1,
+        HEALTHCHIPGET_2, POOLS, POWER, PROFILES, STATS, SUMMARY, TEMPS, VERSION, VOLTAGEGET_0,
+        VOLTAGEGET_1, VOLTAGEGET_2,
     };
+    use serde_json::json;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
 
-    #[tokio::test]
-
-    async fn test_luxminer_v1() -> Result<()> {
-        let miner = LuxMinerV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19KPro));
-
+    fn luxminer_results() -> Result<HashMap<MinerCommand, Value>> {
         let mut results = HashMap::new();
         let version_cmd = MinerCommand::RPC {
             command: "version",
@@ -1067,12 +1451,23 @@ mod tests {
             Value::from_str(HEALTHCHIPGET_2)?,
         );
 
+        Ok(results)
+    }
+
+    #[tokio::test]
+
+    async fn test_luxminer_v1() -> Result<()> {
+        let miner = LuxMinerV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19KPro));
+
+        let results = luxminer_results()?;
+
         let mock_api = MockAPIClient::new(results);
 
         let mut collector = DataCollector::new_with_client(&miner, &mock_api);
         let data = collector.collect_all().await;
 
         let miner_data = miner.parse_data(data);
+        assert_hashrate_magnitudes(&miner_data);
 
         assert_eq!(
             miner_data.mac,
@@ -1092,6 +1487,10 @@ mod tests {
             miner_data.control_board_version,
             Some(MinerControlBoard::CVITek)
         );
+        assert_eq!(
+            miner_data.control_board_type,
+            Some(ControlBoardType::CVITek)
+        );
         assert_eq!(miner_data.wattage, Some(Power::from_watts(1051f64)));
         assert_eq!(miner_data.wattage_limit, Some(Power::from_watts(1188f64)));
         assert_eq!(miner_data.fans.len(), 4);
@@ -1100,4 +1499,263 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_luxminer_v1_atm() -> Result<()> {
+        let miner = LuxMinerV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19KPro));
+        let atm_cmd = MinerCommand::RPC {
+            command: "atm",
+            parameters: None,
+        };
+        let bounds = Some(AutotuneProfileBounds {
+            min: "190MHz".to_string(),
+            max: "415MHz".to_string(),
+        });
+        let atm_message = |data: &MinerData| {
+            data.messages
+                .iter()
+                .find(|m| m.message.starts_with("ATM"))
+                .cloned()
+        };
+
+        // ATM has scaled the 415MHz max profile down to the configured 290MHz
+        let mut results = luxminer_results()?;
+        results.insert(atm_cmd.clone(), Value::from_str(ATM_DOWNSCALED)?);
+        let mock_api = MockAPIClient::new(results);
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+
+        assert_eq!(miner_data.autotune_enabled, Some(true));
+        assert_eq!(miner_data.autotune_profile_bounds, bounds);
+        assert_eq!(miner_data.wattage_limit, Some(Power::from_watts(1785f64)));
+        let message = atm_message(&miner_data).unwrap();
+        assert_eq!(message.severity, MessageSeverity::Info);
+        assert_eq!(message.kind, MinerMessageKind::TuningIssue);
+        assert!(message.message.contains("415MHz to 290MHz"));
+
+        // with ATM off the configured profile is the limit
+        let mut results = luxminer_results()?;
+        results.insert(atm_cmd, Value::from_str(ATM_DISABLED)?);
+        let mock_api = MockAPIClient::new(results);
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+
+        assert_eq!(miner_data.autotune_enabled, Some(false));
+        assert_eq!(miner_data.autotune_profile_bounds, bounds);
+        assert_eq!(miner_data.wattage_limit, Some(Power::from_watts(1188f64)));
+        assert_eq!(atm_message(&miner_data), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_luxminer_v1_failed_chip_query() -> Result<()> {
+        let miner = LuxMinerV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19KPro));
+
+        let mut results = luxminer_results()?;
+        results.remove(&MinerCommand::RPC {
+            command: "healthchipget",
+            parameters: Some(Value::String("1".to_string())),
+        });
+
+        let mock_api = MockAPIClient::new(results);
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+
+        assert_eq!(miner_data.hashboards.len(), 3);
+        assert_eq!(miner_data.hashboards[0].chips.len(), 77);
+        assert!(miner_data.hashboards[1].chips.is_empty());
+        assert_eq!(miner_data.hashboards[1].active, Some(false));
+        assert!(!miner_data.hashboards[2].chips.is_empty());
+        assert!(miner_data.hashboards[1].serial_number.is_some());
+        assert!(miner_data.messages.iter().any(|m| {
+            m.message == "Could not read the chips of board 1"
+                && m.severity == MessageSeverity::Warning
+        }));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_luxminer_v1_four_boards() -> Result<()> {
+        let mut miner =
+            LuxMinerV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19KPro));
+        miner.set_hardware(MinerHardware {
+            boards: Some(4),
+            ..miner.device_info.hardware
+        });
+
+        let commands: Vec<MinerCommand> = miner
+            .get_locations(DataField::Hashboards)
+            .into_iter()
+            .map(|(command, _)| command)
+            .collect();
+        for command in ["healthchipget", "voltageget"] {
+            assert!(commands.contains(&MinerCommand::RPC {
+                command,
+                parameters: Some(Value::String("3".to_string())),
+            }));
+        }
+
+        let mut results = luxminer_results()?;
+        results.insert(
+            MinerCommand::RPC {
+                command: "healthchipget",
+                parameters: Some(Value::String("3".to_string())),
+            },
+            Value::from_str(HEALTHCHIPGET_2)?,
+        );
+        results.insert(
+            MinerCommand::RPC {
+                command: "voltageget",
+                parameters: Some(Value::String("3".to_string())),
+            },
+            Value::from_str(VOLTAGEGET_2)?,
+        );
+
+        let mock_api = MockAPIClient::new(results);
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+
+        assert_eq!(miner_data.hashboards.len(), 4);
+        assert!(!miner_data.hashboards[3].chips.is_empty());
+        assert!(miner_data.hashboards[3].voltage.is_some());
+        assert!(
+            !miner_data
+                .messages
+                .iter()
+                .any(|m| m.message.starts_with("Could not read the chips"))
+        );
+
+        Ok(())
+    }
+
+    /// Serve LuxOS RPC on an ephemeral port, recording every request sent to it.
+    async fn serve_luxos(atm_enabled: bool) -> (SocketAddr, Arc<Mutex<Vec<Value>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 1024];
+                while !buffer.ends_with(b"\n") {
+                    match stream.read(&mut chunk).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                    }
+                }
+                let request: Value = serde_json::from_slice(&buffer).unwrap_or(Value::Null);
+                let status = json!([{ "STATUS": "S", "Msg": "OK" }]);
+                let response = match request["command"].as_str() {
+                    Some("session") => {
+                        json!({ "STATUS": status, "SESSION": [{ "SessionID": "" }] })
+                    }
+                    Some("logon") => {
+                        json!({ "STATUS": status, "SESSION": [{ "SessionID": "session-1" }] })
+                    }
+                    Some("atm") => json!({ "STATUS": status, "ATM": [{ "Enabled": atm_enabled }] }),
+                    _ => json!({ "STATUS": status }),
+                };
+                recorded.lock().unwrap().push(request);
+                let _ = stream.write_all(format!("{response}\0").as_bytes()).await;
+            }
+        });
+        (addr, requests)
+    }
+
+    fn tuning_requests(requests: &Mutex<Vec<Value>>) -> Vec<Value> {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| matches!(r["command"].as_str(), Some("frequencyset" | "voltageset")))
+            .cloned()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_luxminer_set_board_tuning() {
+        let (addr, requests) = serve_luxos(false).await;
+        let miner = LuxMinerV1 {
+            rpc: LUXMinerRPCAPI::new(addr.ip(), Some(addr.port())),
+            ..LuxMinerV1::new(addr.ip(), MinerModel::AntMiner(S19KPro))
+        };
+
+        let result = miner
+            .set_board_tuning(
+                1,
+                Some(Frequency::from_megahertz(650.0)),
+                Some(Voltage::from_volts(13.5)),
+            )
+            .await
+            .unwrap();
+
+        assert!(result);
+        assert_eq!(
+            tuning_requests(&requests),
+            vec![
+                json!({ "command": "frequencyset", "parameter": "session-1,1,650" }),
+                json!({ "command": "voltageset", "parameter": "session-1,1,13.5" }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_luxminer_set_board_tuning_atm_locked() {
+        let (addr, requests) = serve_luxos(true).await;
+        let miner = LuxMinerV1 {
+            rpc: LUXMinerRPCAPI::new(addr.ip(), Some(addr.port())),
+            ..LuxMinerV1::new(addr.ip(), MinerModel::AntMiner(S19KPro))
+        };
+
+        let err = miner
+            .set_board_tuning(0, Some(Frequency::from_megahertz(650.0)), None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<ControlError>(),
+            Some(ControlError::UnsupportedOperation(_))
+        ));
+        assert!(tuning_requests(&requests).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_luxminer_set_board_tuning_out_of_range() {
+        let miner = LuxMinerV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19KPro));
+
+        let err = miner
+            .set_board_tuning(3, Some(Frequency::from_megahertz(650.0)), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+
+        let err = miner
+            .set_board_tuning(0, None, Some(Voltage::from_volts(20.0)))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_luxminer_restart_commands() {
+        let mut miner =
+            LuxMinerV1::new(IpAddr::from([127, 0, 0, 1]), MinerModel::AntMiner(S19KPro));
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        assert!(miner.restart_mining().await.unwrap());
+        assert!(miner.reboot_system().await.unwrap());
+
+        let commands: Vec<_> = dry_run.actions().into_iter().map(|a| a.command).collect();
+        assert_eq!(commands, vec!["resetminer", "rebootdevice"]);
+    }
 }
@@ -2,10 +2,13 @@ use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use serde_json::{Value, json};
 use std::net::IpAddr;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
 
 use crate::miners::api::rpc::errors::RPCError;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::{self, RPCReadLimits};
 use crate::miners::api::rpc::status::RPCCommandStatus;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 
@@ -14,17 +17,28 @@ pub struct LUXMinerRPCAPI {
     ip: IpAddr,
     port: u16,
     session_token: Option<String>,
+    pub(crate) policy: CommandPolicy,
+    pub(crate) connections: ConnectionLimit,
+    read_limits: RPCReadLimits,
 }
 
 impl LUXMinerRPCAPI {
-    pub fn new(ip: IpAddr) -> Self {
+    pub fn new(ip: IpAddr, port: Option<u16>) -> Self {
         Self {
             ip,
-            port: 4028,
+            port: port.unwrap_or(4028),
             session_token: None,
+            policy: CommandPolicy::default(),
+            connections: ConnectionLimit::default(),
+            read_limits: RPCReadLimits::default(),
         }
     }
 
+    /// Bound the size of and idle time between reads of the miner's responses.
+    pub fn set_read_limits(&mut self, limits: RPCReadLimits) {
+        self.read_limits = limits;
+    }
+
     fn parse_rpc_result(&self, response: &str) -> Result<Value> {
         let status = RPCCommandStatus::from_luxminer(response)?;
         match status.into_result() {
@@ -180,6 +194,24 @@ impl LUXMinerRPCAPI {
             .await
     }
 
+    pub async fn frequencyset(&self, board: u8, frequency: u64) -> Result<Value> {
+        self.send_command(
+            "frequencyset",
+            true,
+            Some(Value::String(format!("{},{}", board, frequency))),
+        )
+        .await
+    }
+
+    pub async fn voltageset(&self, board: u8, voltage: f64) -> Result<Value> {
+        self.send_command(
+            "voltageset",
+            true,
+            Some(Value::String(format!("{},{}", board, voltage))),
+        )
+        .await
+    }
+
     // ATM (Advanced Thermal Management) commands
     pub async fn atm(&self) -> Result<Value> {
         self.send_command("atm", false, None).await
@@ -352,10 +384,7 @@ impl APIClient for LUXMinerRPCAPI {
             MinerCommand::RPC {
                 command,
                 parameters,
-            } => self
-                .send_command(command, false, parameters.clone())
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => self.send_command(command, false, parameters.clone()).await,
             _ => Err(anyhow!("Unsupported command type for LuxMiner RPC API")),
         }
     }
@@ -369,10 +398,7 @@ impl RPCAPIClient for LUXMinerRPCAPI {
         privileged: bool,
         parameters: Option<Value>,
     ) -> Result<Value> {
-        let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
-            .await
-            .map_err(|_| RPCError::ConnectionFailed)?;
-
+        self.policy.check(command)?;
         let mut request = json!({
             "command": command
         });
@@ -393,27 +419,22 @@ impl RPCAPIClient for LUXMinerRPCAPI {
             request["parameter"] = params;
         }
 
+        // connect once the session token is known, logging on takes a connection of its own
+        let _connection = self.connections.acquire().await;
+        let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
+            .await
+            .map_err(|_| RPCError::ConnectionFailed)?;
+
         let json_str = request.to_string();
         let message = format!("{}\n", json_str);
 
         stream.write_all(message.as_bytes()).await?;
 
-        let mut response = String::new();
-        let mut buffer = [0; 8192];
-
-        loop {
-            let bytes_read = stream.read(&mut buffer).await?;
-            if bytes_read == 0 {
-                break;
-            }
-
-            let chunk = String::from_utf8_lossy(&buffer[..bytes_read]);
-            response.push_str(&chunk);
-
-            if response.contains('\0') || response.ends_with('\n') {
-                break;
-            }
-        }
+        let buffer = reader::read_until(&mut stream, &self.read_limits, |b| {
+            b.contains(&0) || b.ends_with(b"\n")
+        })
+        .await?;
+        let response = String::from_utf8_lossy(&buffer);
 
         let clean_response = response.trim_end_matches('\0').trim_end_matches('\n');
         self.parse_rpc_result(clean_response)
@@ -1,19 +1,27 @@
 use crate::data::board::{BoardData, ChipData};
-use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerModel};
-use crate::data::device::{MinerControlBoard, MinerMake};
+use crate::data::device::models::MinerModelFactory;
+use crate::data::device::{ControlBoardType, MinerControlBoard, MinerMake};
+use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerModel};
 use crate::data::fan::FanData;
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::pool::{PoolData, PoolURL};
+use crate::data::health::HealthThresholds;
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_pointer,
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData,
+    get_by_pointer,
 };
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use macaddr::MacAddr;
 use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::str::FromStr;
@@ -29,6 +37,10 @@ pub struct MaraV1 {
     ip: IpAddr,
     web: MaraWebAPI,
     device_info: DeviceInfo,
+    labels: Option<HashMap<String, String>>,
+    health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
 }
 
 impl MaraV1 {
@@ -37,11 +49,15 @@ impl MaraV1 {
             ip,
             web: MaraWebAPI::new(ip, 80),
             device_info: DeviceInfo::new(
-                MinerMake::from(model),
+                MinerMake::from(&model),
                 model,
                 MinerFirmware::Marathon,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
         }
     }
 }
@@ -85,6 +101,22 @@ impl GetDataLocations for MaraV1 {
                     tag: None,
                 },
             )],
+            DataField::DeviceInfo => vec![(
+                overview_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/miner_type"),
+                    tag: None,
+                },
+            )],
+            DataField::ApiVersion => vec![(
+                overview_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/version_api"),
+                    tag: None,
+                },
+            )],
             DataField::FirmwareVersion => vec![(
                 overview_cmd,
                 DataExtractor {
@@ -93,14 +125,40 @@ impl GetDataLocations for MaraV1 {
                     tag: None,
                 },
             )],
-            DataField::ControlBoardVersion => vec![(
+            DataField::FirmwareBuildTime => vec![(
+                overview_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/build_time"),
+                    tag: None,
+                },
+            )],
+            DataField::BaseFirmware => vec![(
                 overview_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/control_board"),
+                    key: Some("/vendor_firmware"),
                     tag: None,
                 },
             )],
+            DataField::ControlBoardVersion => vec![
+                (
+                    overview_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/control_board"),
+                        tag: Some("board"),
+                    },
+                ),
+                (
+                    overview_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/platform"),
+                        tag: Some("platform"),
+                    },
+                ),
+            ],
             DataField::Hostname => vec![(
                 network_config_cmd,
                 DataExtractor {
@@ -191,14 +249,24 @@ impl GetDataLocations for MaraV1 {
                     tag: None,
                 },
             )],
-            DataField::Pools => vec![(
-                pools_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some(""),
-                    tag: None,
-                },
-            )],
+            DataField::Pools => vec![
+                (
+                    pools_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some(""),
+                        tag: Some("pools"),
+                    },
+                ),
+                (
+                    miner_config_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/pools"),
+                        tag: Some("config"),
+                    },
+                ),
+            ],
             DataField::Messages => vec![(
                 messages_cmd,
                 DataExtractor {
@@ -220,7 +288,87 @@ impl GetIP for MaraV1 {
 
 impl GetDeviceInfo for MaraV1 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+    /// MaraFW runs on AntMiner and AvalonMiner hardware, the make follows the `miner_type` it
+    /// reports in case the miner was built without a known model.
+    fn parse_device_info(&self, data: &FieldData) -> DeviceInfo {
+        let model = data
+            .extract::<String>(DataField::DeviceInfo)
+            .and_then(|miner_type| {
+                MinerModelFactory::new()
+                    .with_firmware(MinerFirmware::Marathon)
+                    .parse_model(&miner_type.to_uppercase())
+            })
+            .filter(|model| !matches!(model, MinerModel::Unknown { .. }));
+        match model {
+            Some(model) if model != self.device_info.model => DeviceInfo::new(
+                MinerMake::from(&model),
+                model,
+                MinerFirmware::Marathon,
+                HashAlgorithm::SHA256,
+            ),
+            _ => self.get_device_info(),
+        }
+    }
+}
+
+impl GetLabels for MaraV1 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for MaraV1 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for MaraV1 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for MaraV1 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for MaraV1 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.web.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.web.policy = policy;
+    }
+}
+
+impl GetCapabilities for MaraV1 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_pool_update: true,
+            has_chip_telemetry: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -231,40 +379,59 @@ impl CollectData for MaraV1 {
 }
 
 impl GetMAC for MaraV1 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
         data.extract::<String>(DataField::Mac)
-            .and_then(|mac_str| MacAddr::from_str(&mac_str.to_uppercase()).ok())
+            .and_then(|mac_str| parse_mac_str(&mac_str))
     }
 }
 
+impl GetReportedIp for MaraV1 {}
+
 impl GetSerialNumber for MaraV1 {}
 
 impl GetHostname for MaraV1 {
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::Hostname)
     }
 }
 
-impl GetApiVersion for MaraV1 {}
+impl GetApiVersion for MaraV1 {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
+        data.extract::<String>(DataField::ApiVersion)
+    }
+}
 
 impl GetFirmwareVersion for MaraV1 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
+
+    fn parse_firmware_build_time(&self, data: &FieldData) -> Option<String> {
+        data.extract::<String>(DataField::FirmwareBuildTime)
+    }
+
+    fn parse_base_firmware(&self, data: &FieldData) -> Option<String> {
+        data.extract::<String>(DataField::BaseFirmware)
+            .filter(|firmware| !firmware.is_empty())
+    }
 }
 
 impl GetControlBoardVersion for MaraV1 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
-        let cb = data.extract::<String>(DataField::ControlBoardVersion)?;
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
+        let cb = data.extract_nested::<String>(DataField::ControlBoardVersion, "board")?;
         if cb.starts_with("MaraCB") {
             // Ignore version (eg `MaraCB_v1.4`)
             return Some(MinerControlBoard::MaraCB);
         };
         MinerControlBoard::from_str(cb.as_str()).ok()
     }
+
+    /// The platform, e.g. `xil`, falling back to the board name on older releases without it.
+    fn parse_control_board_type(&self, data: &FieldData) -> Option<ControlBoardType> {
+        data.extract_nested::<String>(DataField::ControlBoardVersion, "platform")
+            .or_else(|| data.extract_nested::<String>(DataField::ControlBoardVersion, "board"))
+            .and_then(|s| s.parse().ok())
+    }
 }
 
 impl MaraV1 {
@@ -309,6 +476,7 @@ impl MaraV1 {
                             frequency,
                             tuned: None,
                             working,
+                            estimated: false,
                         })
                     })
                     .collect()
@@ -318,7 +486,7 @@ impl MaraV1 {
 }
 
 impl GetHashboards for MaraV1 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         let mut hashboards: Vec<BoardData> = Vec::new();
 
         if let Some(expected_boards) = self.device_info.hardware.boards {
@@ -334,10 +502,14 @@ impl GetHashboards for MaraV1 {
                     working_chips: None,
                     serial_number: None,
                     chips: vec![],
+                    sensor_temperatures: vec![],
                     voltage: None,
                     frequency: None,
+                    wattage: None,
                     tuned: None,
                     active: None,
+                    status: None,
+                    extra: None,
                 });
             }
         }
@@ -436,7 +608,7 @@ impl GetHashboards for MaraV1 {
 }
 
 impl GetHashrate for MaraV1 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract::<f64>(DataField::Hashrate)
             .map(|rate| HashRate {
                 value: rate,
@@ -447,7 +619,7 @@ impl GetHashrate for MaraV1 {
 }
 
 impl GetExpectedHashrate for MaraV1 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract::<f64>(DataField::ExpectedHashrate)
             .map(|rate| HashRate {
                 value: rate,
@@ -457,8 +629,10 @@ impl GetExpectedHashrate for MaraV1 {
     }
 }
 
+impl GetAverageHashrate for MaraV1 {}
+
 impl GetFans for MaraV1 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut fans: Vec<FanData> = Vec::new();
 
         if let Some(fans_data) = data.get(&DataField::Fans)
@@ -491,30 +665,45 @@ impl GetFans for MaraV1 {
 
 impl GetPsuFans for MaraV1 {}
 
+impl GetFanControl for MaraV1 {}
+
+impl GetPsu for MaraV1 {}
+
+impl GetPsuRails for MaraV1 {}
+
 impl GetFluidTemperature for MaraV1 {}
 
+impl GetFluidFlow for MaraV1 {}
+
+impl GetFluidPressure for MaraV1 {}
+
 impl GetWattage for MaraV1 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
         data.extract::<f64>(DataField::Wattage)
             .map(Power::from_watts)
     }
 }
 
 impl GetWattageLimit for MaraV1 {
-    fn parse_wattage_limit(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage_limit(&self, data: &FieldData) -> Option<Power> {
         data.extract::<f64>(DataField::WattageLimit)
             .map(Power::from_watts)
     }
 }
 
+impl GetFrequency for MaraV1 {}
+impl GetVoltage for MaraV1 {}
+impl GetWorkMode for MaraV1 {}
+impl GetAutotune for MaraV1 {}
+
 impl GetLightFlashing for MaraV1 {
-    fn parse_light_flashing(&self, data: &HashMap<DataField, Value>) -> Option<bool> {
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
         data.extract::<bool>(DataField::LightFlashing)
     }
 }
 
 impl GetMessages for MaraV1 {
-    fn parse_messages(&self, data: &HashMap<DataField, Value>) -> Vec<MinerMessage> {
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
         let messages = data.get(&DataField::Messages).and_then(|v| v.as_array());
         let mut result = vec![];
         if let Some(m) = messages {
@@ -540,12 +729,7 @@ impl GetMessages for MaraV1 {
                     .and_then(|v| v.as_u64())
                     .unwrap_or(0);
 
-                let m_msg = MinerMessage {
-                    timestamp: timestamp as u32,
-                    code: 0,
-                    message: message_text,
-                    severity: level,
-                };
+                let m_msg = MinerMessage::new(timestamp as u32, 0, message_text, level);
 
                 result.push(m_msg);
             }
@@ -555,27 +739,32 @@ impl GetMessages for MaraV1 {
     }
 }
 impl GetUptime for MaraV1 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract::<u64>(DataField::Uptime)
             .map(Duration::from_secs)
     }
 }
 
 impl GetIsMining for MaraV1 {
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
         data.extract::<String>(DataField::IsMining)
             .map(|status| status == "Mining")
             .unwrap_or(false)
     }
 }
 
+impl GetStatus for MaraV1 {}
+
 impl GetPools for MaraV1 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
         let mut pools_vec: Vec<PoolData> = Vec::new();
 
         if let Some(pools_data) = data.get(&DataField::Pools)
-            && let Some(pools_array) = pools_data.as_array()
+            && let Some(pools_array) = pools_data.get("pools").and_then(|v| v.as_array())
         {
+            // the configured pools hold the passwords, in the order of the pool indices
+            let configured = pools_data.get("config").and_then(|v| v.as_array());
+
             let mut active_pool_index = None;
             let mut highest_priority = i32::MAX;
 
@@ -597,7 +786,7 @@ impl GetPools for MaraV1 {
                     .get("url")
                     .and_then(|v| v.as_str())
                     .filter(|s| !s.is_empty())
-                    .map(|s| PoolURL::from(s.to_string()));
+                    .and_then(PoolURL::parse);
 
                 let index = pool_info
                     .get("index")
@@ -614,6 +803,11 @@ impl GetPools for MaraV1 {
                     .get("status")
                     .and_then(|v| v.as_str())
                     .map(|s| s == "Alive");
+                let password = index
+                    .and_then(|i| configured?.get(i as usize))
+                    .and_then(|pool| pool.get("pass"))
+                    .and_then(|v| v.as_str())
+                    .map(String::from);
 
                 pools_vec.push(PoolData {
                     position: index,
@@ -623,6 +817,11 @@ impl GetPools for MaraV1 {
                     active: Some(active),
                     alive,
                     user,
+                    password,
+                    last_share_difficulty: None,
+                    last_share_time: None,
+                    group: None,
+                    quota: None,
                 });
             }
         }
@@ -635,7 +834,9 @@ impl GetPools for MaraV1 {
 impl SetFaultLight for MaraV1 {
     #[allow(unused_variables)]
     async fn set_fault_light(&self, fault: bool) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting the fault light".to_string(),
+        ))?
     }
 }
 
@@ -643,14 +844,89 @@ impl SetFaultLight for MaraV1 {
 impl SetPowerLimit for MaraV1 {
     #[allow(unused_variables)]
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting a power limit".to_string(),
+        ))?
+    }
+}
+
+impl SetCredentials for MaraV1 {}
+
+impl EndpointControl for MaraV1 {}
+
+#[async_trait]
+impl SetPassword for MaraV1 {
+    #[allow(unused_variables)]
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the password".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetHostname for MaraV1 {
+    #[allow(unused_variables)]
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the hostname".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPools for MaraV1 {
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        validate_pool_count(&pools, 3)?;
+        let slots: Vec<Value> = pools
+            .iter()
+            .map(|pool| {
+                json!({
+                    "url": pool.url.to_string(),
+                    "user": pool.user,
+                    "pass": pool.password_or_default(),
+                })
+            })
+            .collect();
+        execute_control(self, "miner_config", json!({ "pools": pools }), async {
+            let mut config = self.web.get_miner_config().await?;
+            config
+                .as_object_mut()
+                .ok_or_else(|| anyhow!("Unexpected miner_config response"))?
+                .insert("pools".to_string(), Value::Array(slots));
+            accepted(self.web.set_miner_config(config).await)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for MaraV1 {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
     }
 }
 
 #[async_trait]
 impl Restart for MaraV1 {
-    async fn restart(&self) -> Result<bool> {
-        bail!("Unsupported command");
+    async fn restart_mining(&self) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "restarting the mining process".to_string(),
+        ))?
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "rebooting the system".to_string(),
+        ))?
     }
 }
 
@@ -658,7 +934,7 @@ impl Restart for MaraV1 {
 impl Pause for MaraV1 {
     #[allow(unused_variables)]
     async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation("pausing".to_string()))?
     }
 }
 
@@ -666,6 +942,99 @@ impl Pause for MaraV1 {
 impl Resume for MaraV1 {
     #[allow(unused_variables)]
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation("resuming".to_string()))?
+    }
+}
+
+impl SetRPCReadLimits for MaraV1 {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::device::models::avalon::AvalonMinerModel;
+    use crate::data::miner::MinerData;
+    use crate::test::api::MockAPIClient;
+    use crate::test::json::marathon::v1::{OVERVIEW_AVALON_1246, OVERVIEW_S19J_PRO};
+
+    async fn parse_overview(miner: &MaraV1, overview: &str) -> MinerData {
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            MinerCommand::WebAPI {
+                command: "overview",
+                parameters: None,
+            },
+            Value::from_str(overview).unwrap(),
+        )]));
+        let mut collector = DataCollector::new_with_client(miner, &mock_api);
+        miner.parse_data(collector.collect_all().await)
+    }
+
+    #[tokio::test]
+    async fn test_mara_overview_s19() {
+        let miner = MaraV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19jPro),
+        );
+        let miner_data = parse_overview(&miner, OVERVIEW_S19J_PRO).await;
+
+        assert_eq!(miner_data.device_info.make, MinerMake::AntMiner);
+        assert_eq!(
+            miner_data.device_info.model,
+            MinerModel::AntMiner(AntMinerModel::S19jPro)
+        );
+        assert_eq!(miner_data.api_version.as_deref(), Some("1.0.2"));
+        assert_eq!(
+            miner_data.firmware_version.as_deref(),
+            Some("2024.11.06-2250")
+        );
+        assert_eq!(
+            miner_data.firmware_build_time.as_deref(),
+            Some("2024-11-06T22:50:31Z")
+        );
+        assert_eq!(
+            miner_data.base_firmware.as_deref(),
+            Some("Antminer-S19j-Pro-xilinx-201221-release")
+        );
+        assert_eq!(
+            miner_data.control_board_version,
+            Some(MinerControlBoard::MaraCB)
+        );
+        assert_eq!(
+            miner_data.control_board_type,
+            Some(ControlBoardType::Xilinx)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mara_overview_avalon() {
+        // identified before MaraFW's AvalonMiner models were known
+        let miner = MaraV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::Unknown {
+                make: MinerMake::AntMiner,
+                raw: "AVALON 1246".to_string(),
+            },
+        );
+        let miner_data = parse_overview(&miner, OVERVIEW_AVALON_1246).await;
+
+        assert_eq!(miner_data.device_info.make, MinerMake::AvalonMiner);
+        assert_eq!(
+            miner_data.device_info.model,
+            MinerModel::AvalonMiner(AvalonMinerModel::Avalon1246)
+        );
+        assert_eq!(miner_data.device_info.firmware, MinerFirmware::Marathon);
+        assert_eq!(miner_data.expected_hashboards, Some(3));
+        assert_eq!(
+            miner_data.base_firmware.as_deref(),
+            Some("21042001_4ec6bb0_61407fa")
+        );
+        assert_eq!(
+            miner_data.control_board_version,
+            Some(MinerControlBoard::MM3v1X3)
+        );
+        assert_eq!(
+            miner_data.control_board_type,
+            Some(ControlBoardType::Unknown("K210".to_string()))
+        );
     }
 }
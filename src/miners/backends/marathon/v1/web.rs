@@ -1,3 +1,4 @@
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::{APIClient, WebAPIClient};
 use crate::miners::commands::MinerCommand;
 use anyhow::{Result, anyhow};
@@ -15,6 +16,7 @@ pub struct MaraWebAPI {
     client: Client,
     username: String,
     password: String,
+    pub(crate) policy: CommandPolicy,
 }
 
 impl MaraWebAPI {
@@ -30,6 +32,7 @@ impl MaraWebAPI {
             client,
             username: "root".to_string(),
             password: "root".to_string(),
+            policy: CommandPolicy::default(),
         }
     }
 
@@ -39,6 +42,11 @@ impl MaraWebAPI {
         method: Method,
         parameters: Option<Value>,
     ) -> Result<Value> {
+        if method == Method::GET {
+            self.policy.check(endpoint)?;
+        } else {
+            self.policy.check_write(endpoint)?;
+        }
         let url = format!("http://{}:{}/kaonsu/v1/{}", self.ip, self.port, endpoint);
 
         let mut request_builder = match method {
@@ -71,6 +79,15 @@ impl MaraWebAPI {
             ))
         }
     }
+
+    pub async fn get_miner_config(&self) -> Result<Value> {
+        self.make_request("miner_config", Method::GET, None).await
+    }
+
+    pub async fn set_miner_config(&self, config: Value) -> Result<Value> {
+        self.make_request("miner_config", Method::POST, Some(config))
+            .await
+    }
 }
 
 #[async_trait]
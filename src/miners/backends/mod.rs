@@ -1,12 +1,31 @@
 //! Miner specific implementations of control functionality and data gathering.
+//!
+//! Each backend family is compiled in by the cargo feature of the same name, see
+//! [`MinerError::BackendNotCompiled`][`errors::MinerError::BackendNotCompiled`].
 
+#[cfg(feature = "antminer")]
 pub mod antminer;
+#[cfg(feature = "avalon")]
 pub mod avalonminer;
+#[cfg(feature = "bitaxe")]
 pub mod bitaxe;
+#[cfg(feature = "braiins")]
 pub mod braiins;
+pub mod capabilities;
+#[cfg(feature = "cgminer")]
+pub mod cgminer;
+pub mod dry_run;
+#[cfg(feature = "epic")]
 pub mod epic;
+pub mod errors;
+pub mod firmware;
+#[cfg(feature = "luxos")]
 pub mod luxminer;
+#[cfg(feature = "marathon")]
 pub mod marathon;
+pub mod policy;
 pub mod traits;
+#[cfg(feature = "vnish")]
 pub mod vnish;
+#[cfg(feature = "whatsminer")]
 pub mod whatsminer;
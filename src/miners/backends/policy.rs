@@ -0,0 +1,129 @@
+use std::collections::BTreeSet;
+
+use strum::IntoEnumIterator;
+
+use crate::miners::backends::errors::MinerError;
+use crate::miners::backends::traits::GetDataLocations;
+use crate::miners::commands::MinerCommand;
+use crate::miners::data::DataField;
+
+/// Restricts the commands a miner's API clients are allowed to send.
+///
+/// The policy is checked by the RPC and web clients themselves, so every command is covered,
+/// including the ones sent by control functions. Commands are matched by their RPC command name
+/// or web endpoint, denied commands fail with [`MinerError::PolicyDenied`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandPolicy {
+    read_only: bool,
+    allow: Option<BTreeSet<String>>,
+    deny: BTreeSet<String>,
+    /// Commands the backend collects data with, filled in when attached to a miner
+    reads: BTreeSet<String>,
+}
+
+impl CommandPolicy {
+    /// Allow every command, the default.
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Only allow the commands the backend uses to collect data, blocking all control commands.
+    pub fn read_only() -> Self {
+        Self {
+            read_only: true,
+            ..Self::default()
+        }
+    }
+
+    /// Only allow the given commands.
+    pub fn allow_only<S: Into<String>>(commands: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            allow: Some(commands.into_iter().map(Into::into).collect()),
+            ..Self::default()
+        }
+    }
+
+    /// Additionally block the given commands.
+    pub fn deny<S: Into<String>>(mut self, commands: impl IntoIterator<Item = S>) -> Self {
+        self.deny.extend(commands.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Whether the policy allows sending every command.
+    pub fn is_unrestricted(&self) -> bool {
+        !self.read_only && self.allow.is_none() && self.deny.is_empty()
+    }
+
+    /// Check whether `command` may be sent.
+    pub fn check(&self, command: &str) -> Result<(), MinerError> {
+        let allowed = !self.deny.contains(command)
+            && self
+                .allow
+                .as_ref()
+                .is_none_or(|allow| allow.contains(command))
+            && (!self.read_only || self.reads.contains(command));
+        match allowed {
+            true => Ok(()),
+            false => Err(MinerError::PolicyDenied(command.to_string())),
+        }
+    }
+
+    /// Check whether `command` may be sent as a write, web endpoints are often read and written
+    /// under the same name so a read only policy blocks every write.
+    pub(crate) fn check_write(&self, command: &str) -> Result<(), MinerError> {
+        match self.read_only {
+            true => Err(MinerError::PolicyDenied(command.to_string())),
+            false => self.check(command),
+        }
+    }
+
+    /// Resolve the commands `miner` reads data with, for a read only policy.
+    pub(crate) fn for_miner<M: GetDataLocations + ?Sized>(mut self, miner: &M) -> Self {
+        self.reads = DataField::iter()
+            .flat_map(|field| miner.get_locations(field))
+            .filter_map(|(command, _)| command_name(&command))
+            .map(String::from)
+            .collect();
+        self
+    }
+}
+
+/// The name a command is matched against policies with.
+pub(crate) fn command_name(command: &MinerCommand) -> Option<&'static str> {
+    match command {
+        MinerCommand::RPC { command, .. }
+        | MinerCommand::WebAPI { command, .. }
+        | MinerCommand::GRPC { command }
+        | MinerCommand::GraphQL { command } => Some(*command),
+        MinerCommand::SSH { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_policy() {
+        let policy = CommandPolicy::allow_only(["summary", "stats", "reboot"]).deny(["reboot"]);
+
+        assert!(policy.check("summary").is_ok());
+        assert_eq!(
+            policy.check("reboot"),
+            Err(MinerError::PolicyDenied("reboot".to_string()))
+        );
+        assert!(policy.check("set_miner_conf").is_err());
+        assert!(CommandPolicy::allow_all().is_unrestricted());
+        assert!(!policy.is_unrestricted());
+    }
+
+    #[test]
+    fn test_read_only_unresolved() {
+        // nothing is known to be a read until the policy is attached to a miner
+        assert!(CommandPolicy::read_only().check("summary").is_err());
+    }
+}
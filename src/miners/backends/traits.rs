@@ -1,37 +1,90 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use macaddr::MacAddr;
-use measurements::{Power, Temperature};
+use macaddr::{MacAddr, MacAddr6};
+use measurements::{Frequency, Power, Temperature, Voltage};
 use reqwest::Method;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::fmt::Debug;
-use std::net::IpAddr;
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::data::board::BoardData;
-use crate::data::device::{DeviceInfo, MinerControlBoard, MinerModel};
-use crate::data::fan::FanData;
+use crate::data::board::{BoardData, BoardStatus, estimate_chips};
+use crate::data::device::{
+    ControlBoardType, DeviceInfo, MinerControlBoard, MinerHardware, MinerModel,
+};
+use crate::data::fan::{FanControl, FanData};
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::message::MinerMessage;
-use crate::data::pool::PoolData;
+use crate::data::health::HealthThresholds;
+use crate::data::message::{MessageSeverity, MinerMessage, MinerMessageKind};
+use crate::data::pool::{PoolConfig, PoolData};
+use crate::data::psu::{PsuData, PsuRail};
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::{DryRun, PlannedAction};
+use crate::miners::backends::errors::{ControlError, MinerError};
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::commands::MinerCommand;
 
-use crate::data::miner::MinerData;
-use crate::miners::data::{DataCollector, DataField, DataLocation};
+use crate::data::miner::{
+    AutotuneProfileBounds, MinerData, MinerStatus, WattageLocation, WorkMode,
+};
+use crate::miners::api::rpc::reader::RPCReadLimits;
+use crate::miners::data::{CommandGate, DataCollector, DataField, DataLocation, FieldData};
 
 pub(crate) trait MinerConstructor {
     #[allow(clippy::new_ret_no_self)]
     fn new(ip: IpAddr, model: MinerModel, version: Option<semver::Version>) -> Box<dyn Miner>;
 }
 
-pub trait Miner: GetMinerData + HasMinerControl {}
+pub trait Miner: GetMinerData + HasMinerControl + GetCapabilities {}
 
-impl<T: GetMinerData + HasMinerControl> Miner for T {}
+impl<T: GetMinerData + HasMinerControl + GetCapabilities> Miner for T {}
 
-pub trait HasMinerControl: SetFaultLight + SetPowerLimit + Restart + Resume + Pause {}
+pub trait GetCapabilities {
+    /// Returns the control functions and telemetry this backend supports, without contacting
+    /// the miner.
+    fn capabilities(&self) -> MinerCapabilities;
+}
 
-impl<T: SetFaultLight + SetPowerLimit + Restart + Resume + Pause> HasMinerControl for T {}
+pub trait HasMinerControl:
+    DryRunControl
+    + CommandPolicyControl
+    + SetFaultLight
+    + SetPowerLimit
+    + SetPassword
+    + SetCredentials
+    + EndpointControl
+    + SetHostname
+    + SetPools
+    + SetBoardTuning
+    + Restart
+    + Resume
+    + Pause
+    + SetRPCReadLimits
+{
+}
+
+impl<
+    T: DryRunControl
+        + CommandPolicyControl
+        + SetFaultLight
+        + SetPowerLimit
+        + SetPassword
+        + SetCredentials
+        + EndpointControl
+        + SetHostname
+        + SetPools
+        + SetBoardTuning
+        + Restart
+        + Resume
+        + Pause
+        + SetRPCReadLimits,
+> HasMinerControl for T
+{
+}
 
 /// Trait that every miner backend must implement to provide miner data.
 #[async_trait]
@@ -40,10 +93,14 @@ pub trait GetMinerData:
     + MinerInterface
     + GetIP
     + GetDeviceInfo
+    + GetLabels
+    + GetHealthThresholds
+    + GetPlausibilityRanges
     + GetExpectedHashboards
     + GetExpectedChips
     + GetExpectedFans
     + GetMAC
+    + GetReportedIp
     + GetSerialNumber
     + GetHostname
     + GetApiVersion
@@ -52,21 +109,32 @@ pub trait GetMinerData:
     + GetHashboards
     + GetHashrate
     + GetExpectedHashrate
+    + GetAverageHashrate
     + GetFans
     + GetPsuFans
+    + GetFanControl
+    + GetPsu
+    + GetPsuRails
     + GetFluidTemperature
+    + GetFluidFlow
+    + GetFluidPressure
     + GetWattage
     + GetWattageLimit
+    + GetFrequency
+    + GetVoltage
+    + GetWorkMode
+    + GetAutotune
     + GetLightFlashing
     + GetMessages
     + GetUptime
     + GetIsMining
+    + GetStatus
     + GetPools
 {
     /// Asynchronously retrieves standardized information about a miner,
     /// returning it as a `MinerData` struct.
     async fn get_data(&self) -> MinerData;
-    fn parse_data(&self, data: HashMap<DataField, Value>) -> MinerData;
+    fn parse_data(&self, data: FieldData) -> MinerData;
 }
 
 pub trait CollectData: GetDataLocations {
@@ -87,16 +155,25 @@ pub trait GetDataLocations: Send + Sync + Debug {
     /// This associates API commands (routes) with `DataExtractor` structs,
     /// describing how to extract the data for a given `DataField`.
     fn get_locations(&self, data_field: DataField) -> Vec<DataLocation>;
+
+    /// Returns the command that decides whether the others are worth sending, if any.
+    fn command_gate(&self) -> Option<CommandGate> {
+        None
+    }
 }
 
 #[async_trait]
 impl<
     T: GetIP
         + GetDeviceInfo
+        + GetLabels
+        + GetHealthThresholds
+        + GetPlausibilityRanges
         + GetExpectedHashboards
         + GetExpectedChips
         + GetExpectedFans
         + GetMAC
+        + GetReportedIp
         + GetSerialNumber
         + GetHostname
         + GetApiVersion
@@ -105,15 +182,26 @@ impl<
         + GetHashboards
         + GetHashrate
         + GetExpectedHashrate
+        + GetAverageHashrate
         + GetFans
         + GetPsuFans
+        + GetFanControl
+        + GetPsu
+        + GetPsuRails
         + GetFluidTemperature
+        + GetFluidFlow
+        + GetFluidPressure
         + GetWattage
         + GetWattageLimit
+        + GetFrequency
+        + GetVoltage
+        + GetWorkMode
+        + GetAutotune
         + GetLightFlashing
         + GetMessages
         + GetUptime
         + GetIsMining
+        + GetStatus
         + GetPools
         + MinerInterface,
 > GetMinerData for T
@@ -123,47 +211,100 @@ impl<
         let data = collector.collect_all().await;
         self.parse_data(data)
     }
-    fn parse_data(&self, data: HashMap<DataField, Value>) -> MinerData {
+    fn parse_data(&self, data: FieldData) -> MinerData {
         let schema_version = env!("CARGO_PKG_VERSION").to_string();
-        let timestamp = SystemTime::now()
+        // stamped with when collection started, rather than after every command has returned
+        let timestamp = data
+            .collected_at()
+            .unwrap_or_else(SystemTime::now)
             .duration_since(UNIX_EPOCH)
-            .expect("Failed to get system time")
+            .unwrap_or_default()
             .as_secs();
+        let collection_duration = data.collection_duration().unwrap_or_default();
 
         let ip = self.get_ip();
+        let labels = self.get_labels();
+        let device_info = self.parse_device_info(&data);
         let mac = self.parse_mac(&data);
+        let reported_ip = self.parse_reported_ip(&data);
         let serial_number = self.parse_serial_number(&data);
         let hostname = self.parse_hostname(&data);
         let api_version = self.parse_api_version(&data);
         let firmware_version = self.parse_firmware_version(&data);
+        let firmware_build_time = self.parse_firmware_build_time(&data);
+        let base_firmware = self.parse_base_firmware(&data);
         let control_board_version = self.parse_control_board_version(&data);
+        let control_board_type = self.parse_control_board_type(&data);
         let uptime = self.parse_uptime(&data);
-        let hashrate = self.parse_hashrate(&data);
-        let expected_hashrate = self.parse_expected_hashrate(&data);
-        let wattage = self.parse_wattage(&data);
-        let wattage_limit = self.parse_wattage_limit(&data);
-        let fluid_temperature = self.parse_fluid_temperature(&data);
-        let fans = self.parse_fans(&data);
-        let psu_fans = self.parse_psu_fans(&data);
-        let hashboards = self.parse_hashboards(&data);
+
+        // drop sentinel and out of range readings before they reach the averages
+        let ranges = self.get_plausibility_ranges();
+        let hashrate = ranges.hashrate(self.parse_hashrate(&data), "hashrate");
+        // firmwares report the expected rate in assorted units, keep it comparable across makes
+        let expected_hashrate = ranges
+            .hashrate(self.parse_expected_hashrate(&data), "expected_hashrate")
+            .map(|h| h.as_unit(device_info.algo.default_unit()));
+        let average_hashrate =
+            ranges.hashrate(self.parse_average_hashrate(&data), "average_hashrate");
+        let wattage = ranges.wattage(self.parse_wattage(&data), "wattage");
+        let wattage_location = wattage
+            .is_some()
+            .then(|| self.parse_wattage_location(&data));
+        let wattage_estimated = wattage_location == Some(WattageLocation::Estimated);
+        let wattage_limit = ranges.wattage(self.parse_wattage_limit(&data), "wattage_limit");
+        let fluid_temperature =
+            ranges.temperature(self.parse_fluid_temperature(&data), "fluid_temperature");
+        let frequency = self.parse_frequency(&data);
+        let voltage = self.parse_voltage(&data);
+        let work_mode = self.parse_work_mode(&data);
+        let autotune_enabled = self.parse_autotune_enabled(&data);
+        let autotune_profile_bounds = self.parse_autotune_profile_bounds(&data);
+        let fluid_flow = self.parse_fluid_flow(&data);
+        let fluid_pressure = self.parse_fluid_pressure(&data);
+        let fans = ranges.fans(self.parse_fans(&data), "fans");
+        let psu_fans = ranges.fans(self.parse_psu_fans(&data), "psu_fans");
+        let fan_control = self.parse_fan_control(&data);
+        let working_fans = (!fans.is_empty())
+            .then(|| self.get_health_thresholds().working_fans(&fans).len() as u8);
+        let psu = self.parse_psu(&data).map(|psu| PsuData {
+            power: ranges.wattage(psu.power, "psu.power"),
+            ..psu
+        });
+        let psu_rails = ranges.psu_rails(self.parse_psu_rails(&data));
+        let estimate_chips = estimate_chips();
+        let hashboards = ranges
+            .hashboards(self.parse_hashboards(&data))
+            .into_iter()
+            .map(|mut board| {
+                board.expected_hashrate = board
+                    .expected_hashrate
+                    .map(|h| h.as_unit(device_info.algo.default_unit()));
+                if estimate_chips {
+                    board.fill_estimated_chips();
+                }
+                board
+            })
+            .collect::<Vec<BoardData>>();
         let light_flashing = self.parse_light_flashing(&data);
-        let is_mining = self.parse_is_mining(&data);
+        let status = self.parse_status(&data);
+        let is_mining = status.is_mining();
+        // right after boot the readings are no sign of how the miner is doing yet
+        let status = self.get_health_thresholds().status(status, uptime);
         let messages = self.parse_messages(&data);
         let pools = self.parse_pools(&data);
-        let device_info = self.get_device_info();
+        let hardware = device_info.hardware;
 
         // computed fields
         let total_chips = hashboards.clone().iter().map(|b| b.working_chips).sum();
         let average_temperature = {
             let board_temps = hashboards
                 .iter()
-                .map(|b| b.board_temperature)
-                .filter(|x| x.is_some())
-                .map(|x| x.unwrap().as_celsius())
+                .filter_map(|b| b.board_temperature)
+                .map(|t| t.as_celsius())
                 .collect::<Vec<f64>>();
             if !board_temps.is_empty() {
                 Some(Temperature::from_celsius(
-                    board_temps.iter().sum::<f64>() / hashboards.len() as f64,
+                    board_temps.iter().sum::<f64>() / board_temps.len() as f64,
                 ))
             } else {
                 None
@@ -177,14 +318,18 @@ impl<
             _ => None,
         };
 
-        MinerData {
+        let mut miner_data = MinerData {
             // Version information
             schema_version,
             timestamp,
+            collection_duration,
+            clock_skew: data.clock_skew(),
 
             // Network identification
             ip,
+            reported_ip,
             mac,
+            labels,
 
             // Device identification
             device_info,
@@ -194,47 +339,128 @@ impl<
             // Version information
             api_version,
             firmware_version,
+            firmware_build_time,
+            base_firmware,
             control_board_version,
+            control_board_type,
 
             // Hashboard information
-            expected_hashboards: device_info.hardware.boards,
+            expected_hashboards: hardware.boards,
             hashboards,
             hashrate,
             expected_hashrate,
+            average_hashrate,
 
             // Chip information
             expected_chips: Some(
-                device_info.hardware.chips.unwrap_or(0)
-                    * device_info.hardware.boards.map(|u| u as u16).unwrap_or(0),
+                hardware.chips.unwrap_or(0) * hardware.boards.map(|u| u as u16).unwrap_or(0),
             ),
             total_chips,
 
             // Cooling information
-            expected_fans: device_info.hardware.fans,
+            expected_fans: hardware.fans,
+            working_fans,
             fans,
             psu_fans,
+            fan_control,
             average_temperature,
             fluid_temperature,
+            fluid_flow,
+            fluid_pressure,
 
             // Power information
             wattage,
+            wattage_estimated,
+            wattage_location,
             wattage_limit,
             efficiency,
+            psu,
+            psu_rails,
+
+            // Tuning information
+            frequency,
+            voltage,
+            work_mode,
+            autotune_enabled,
+            autotune_profile_bounds,
 
             // Status information
             light_flashing,
             messages,
             uptime,
+            status,
             is_mining,
 
             pools,
+        };
+        // warnings derived from the readings, against the thresholds of this site
+        let health = self.get_health_thresholds().messages(&miner_data);
+        miner_data.messages.extend(health);
+        // a throttled board still hashes, so nothing else explains the missing hashrate
+        let throttled: Vec<_> = miner_data
+            .hashboards
+            .iter()
+            .filter_map(|board| {
+                let (reason, kind) = match board.status.as_ref()? {
+                    BoardStatus::ThermalThrottled => {
+                        ("thermally throttled", MinerMessageKind::OverTemperature)
+                    }
+                    BoardStatus::PowerLimited => ("power limited", MinerMessageKind::TuningIssue),
+                    _ => return None,
+                };
+                Some(
+                    MinerMessage::new(
+                        timestamp as u32,
+                        0,
+                        format!("Hashboard {} is {reason}", board.position),
+                        MessageSeverity::Warning,
+                    )
+                    .with_kind(kind),
+                )
+            })
+            .collect();
+        miner_data.messages.extend(throttled);
+        // behind a NAT or port-forward, `ip` alone doesn't tell miners on one gateway apart
+        if let Some(reported_ip) = reported_ip.filter(|reported| *reported != miner_data.ip) {
+            miner_data.messages.push(
+                MinerMessage::new(
+                    timestamp as u32,
+                    0,
+                    format!(
+                        "Miner reports IP {reported_ip}, but is reached at {}",
+                        miner_data.ip
+                    ),
+                    MessageSeverity::Info,
+                )
+                .with_kind(MinerMessageKind::NetworkIssue),
+            );
         }
+        miner_data
     }
 }
 
 #[async_trait]
 pub trait APIClient: Send + Sync {
     async fn get_api_result(&self, command: &MinerCommand) -> Result<Value>;
+    /// Send as many of `commands` as the API can answer in a single request, returning the
+    /// response of each one sent. The rest are sent one at a time with `get_api_result`.
+    ///
+    /// Only cgminer RPC APIs join commands, the default sends none.
+    #[allow(unused_variables)]
+    async fn get_api_results(
+        &self,
+        commands: &[MinerCommand],
+    ) -> HashMap<MinerCommand, Result<Value>> {
+        HashMap::new()
+    }
+    /// Close any connection kept open across the commands of one data collection.
+    async fn end_session(&self) {}
+    /// Check the miner runs the firmware this backend was picked for, failing with
+    /// [`MinerError::FirmwareMismatch`][`super::errors::MinerError::FirmwareMismatch`] when
+    /// it answers like another one. The default trusts the identification.
+    async fn verify_firmware(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -267,6 +493,35 @@ pub trait GetIP: Send + Sync {
 pub trait GetDeviceInfo: Send + Sync {
     /// Returns information about the miner.
     fn get_device_info(&self) -> DeviceInfo;
+    /// Override the expected hardware of the miner, such as for boards repaired with fewer
+    /// chips or immersion conversions with the fans removed.
+    fn set_hardware(&mut self, hardware: MinerHardware);
+    /// Returns information about the miner, refined with anything the collected data reveals,
+    /// such as the algorithm a multi-coin firmware is mining.
+    fn parse_device_info(&self, _data: &FieldData) -> DeviceInfo {
+        self.get_device_info()
+    }
+}
+
+pub trait GetLabels: Send + Sync {
+    /// Returns the user supplied labels attached to this miner, such as its rack or slot.
+    fn get_labels(&self) -> Option<HashMap<String, String>>;
+    /// Attach labels to this miner, which are copied onto all `MinerData` it produces.
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>);
+}
+
+pub trait GetHealthThresholds: Send + Sync {
+    /// Returns the thresholds the health warnings of this miner are derived with.
+    fn get_health_thresholds(&self) -> HealthThresholds;
+    /// Replace the thresholds, e.g. with [`HealthThresholds::immersion`] for immersion cooled miners.
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds);
+}
+
+pub trait GetPlausibilityRanges: Send + Sync {
+    /// Returns the ranges outside of which the readings of this miner are dropped.
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges;
+    /// Replace the ranges, for setups outside of the defaults such as immersion cooling.
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges);
 }
 
 pub trait GetExpectedHashboards: GetDeviceInfo {
@@ -302,11 +557,60 @@ pub trait GetMAC: CollectData {
         self.parse_mac(&data)
     }
     #[allow(unused_variables)]
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
+        None
+    }
+}
+
+/// Parse a MAC address reported by a miner, treating placeholder values as missing.
+///
+/// Accepts colon, dash or unseparated hex in either case. Firmware reports all-zero or broadcast
+/// addresses (or an empty string) while the network is still coming up, none of which identify
+/// the miner, so these are rejected along with anything that isn't 6 bytes of hex.
+pub fn parse_mac_str(raw: &str) -> Option<MacAddr> {
+    let hex: String = raw
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-'))
+        .collect();
+    if hex.len() != 12 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let mut bytes = [0u8; 6];
+    for (idx, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[idx * 2..idx * 2 + 2], 16).ok()?;
+    }
+    if bytes == [0x00; 6] || bytes == [0xff; 6] {
+        return None;
+    }
+    Some(MacAddr::V6(MacAddr6::from(bytes)))
+}
+
+// Reported IP
+#[async_trait]
+pub trait GetReportedIp: CollectData {
+    /// Returns the IP address the miner reports in its own network settings.
+    async fn get_reported_ip(&self) -> Option<IpAddr> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::ReportedIp]).await;
+        self.parse_reported_ip(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_reported_ip(&self, data: &FieldData) -> Option<IpAddr> {
         None
     }
 }
 
+/// Parse an IP address reported by a miner, treating the unspecified address as missing, as
+/// firmware reports `0.0.0.0` until DHCP has assigned one.
+pub fn parse_ip_str(raw: &str) -> Option<IpAddr> {
+    raw.trim()
+        .parse::<IpAddr>()
+        .ok()
+        .filter(|ip| !ip.is_unspecified())
+}
+
 // Serial Number
 #[async_trait]
 pub trait GetSerialNumber: CollectData {
@@ -316,7 +620,7 @@ pub trait GetSerialNumber: CollectData {
         self.parse_serial_number(&data)
     }
     #[allow(unused_variables)]
-    fn parse_serial_number(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_serial_number(&self, data: &FieldData) -> Option<String> {
         None
     }
 }
@@ -330,7 +634,7 @@ pub trait GetHostname: CollectData {
         self.parse_hostname(&data)
     }
     #[allow(unused_variables)]
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         None
     }
 }
@@ -344,7 +648,7 @@ pub trait GetApiVersion: CollectData {
         self.parse_api_version(&data)
     }
     #[allow(unused_variables)]
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         None
     }
 }
@@ -358,7 +662,17 @@ pub trait GetFirmwareVersion: CollectData {
         self.parse_firmware_version(&data)
     }
     #[allow(unused_variables)]
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
+        None
+    }
+    /// The build time of the firmware, in whatever format the firmware reports it.
+    #[allow(unused_variables)]
+    fn parse_firmware_build_time(&self, data: &FieldData) -> Option<String> {
+        None
+    }
+    /// The vendor firmware a converted miner was built from, for firmware that replaces it.
+    #[allow(unused_variables)]
+    fn parse_base_firmware(&self, data: &FieldData) -> Option<String> {
         None
     }
 }
@@ -372,12 +686,15 @@ pub trait GetControlBoardVersion: CollectData {
         self.parse_control_board_version(&data)
     }
     #[allow(unused_variables)]
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
         None
     }
+    /// The platform of the control board, derived from its version unless the backend has the
+    /// raw platform string.
+    fn parse_control_board_type(&self, data: &FieldData) -> Option<ControlBoardType> {
+        self.parse_control_board_version(data)
+            .map(|cb| ControlBoardType::from(&cb))
+    }
 }
 // Hashboards
 #[async_trait]
@@ -388,7 +705,7 @@ pub trait GetHashboards: CollectData {
         self.parse_hashboards(&data)
     }
     #[allow(unused_variables)]
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         vec![]
     }
 }
@@ -403,7 +720,7 @@ pub trait GetHashrate: CollectData {
             .map(|hr| hr.as_unit(HashRateUnit::default()))
     }
     #[allow(unused_variables)]
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         None
     }
 }
@@ -418,7 +735,22 @@ pub trait GetExpectedHashrate: CollectData {
             .map(|hr| hr.as_unit(HashRateUnit::default()))
     }
     #[allow(unused_variables)]
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        None
+    }
+}
+
+// Average Hashrate
+#[async_trait]
+pub trait GetAverageHashrate: CollectData {
+    async fn get_average_hashrate(&self) -> Option<HashRate> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::AverageHashrate]).await;
+        self.parse_average_hashrate(&data)
+            .map(|hr| hr.as_unit(HashRateUnit::default()))
+    }
+    #[allow(unused_variables)]
+    fn parse_average_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         None
     }
 }
@@ -432,7 +764,7 @@ pub trait GetFans: CollectData {
         self.parse_fans(&data)
     }
     #[allow(unused_variables)]
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         vec![]
     }
 }
@@ -446,7 +778,49 @@ pub trait GetPsuFans: CollectData {
         self.parse_psu_fans(&data)
     }
     #[allow(unused_variables)]
-    fn parse_psu_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_psu_fans(&self, data: &FieldData) -> Vec<FanData> {
+        vec![]
+    }
+}
+
+// Fan Control
+#[async_trait]
+pub trait GetFanControl: CollectData {
+    async fn get_fan_control(&self) -> Option<FanControl> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::FanControl]).await;
+        self.parse_fan_control(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_fan_control(&self, data: &FieldData) -> Option<FanControl> {
+        None
+    }
+}
+
+// PSU
+#[async_trait]
+pub trait GetPsu: CollectData {
+    async fn get_psu(&self) -> Option<PsuData> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::Psu]).await;
+        self.parse_psu(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_psu(&self, data: &FieldData) -> Option<PsuData> {
+        None
+    }
+}
+
+// PSU Rails
+#[async_trait]
+pub trait GetPsuRails: CollectData {
+    async fn get_psu_rails(&self) -> Vec<PsuRail> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::PsuRails]).await;
+        self.parse_psu_rails(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_psu_rails(&self, data: &FieldData) -> Vec<PsuRail> {
         vec![]
     }
 }
@@ -460,7 +834,37 @@ pub trait GetFluidTemperature: CollectData {
         self.parse_fluid_temperature(&data)
     }
     #[allow(unused_variables)]
-    fn parse_fluid_temperature(&self, data: &HashMap<DataField, Value>) -> Option<Temperature> {
+    fn parse_fluid_temperature(&self, data: &FieldData) -> Option<Temperature> {
+        None
+    }
+}
+
+// Fluid Flow
+#[async_trait]
+pub trait GetFluidFlow: CollectData {
+    /// Returns the coolant flow rate in litres per minute.
+    async fn get_fluid_flow(&self) -> Option<f64> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::FluidFlow]).await;
+        self.parse_fluid_flow(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_fluid_flow(&self, data: &FieldData) -> Option<f64> {
+        None
+    }
+}
+
+// Fluid Pressure
+#[async_trait]
+pub trait GetFluidPressure: CollectData {
+    /// Returns the coolant pressure in bar.
+    async fn get_fluid_pressure(&self) -> Option<f64> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::FluidPressure]).await;
+        self.parse_fluid_pressure(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_fluid_pressure(&self, data: &FieldData) -> Option<f64> {
         None
     }
 }
@@ -474,9 +878,22 @@ pub trait GetWattage: CollectData {
         self.parse_wattage(&data)
     }
     #[allow(unused_variables)]
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
         None
     }
+    /// Whether the wattage from `parse_wattage` is an estimate rather than a measurement.
+    #[allow(unused_variables)]
+    fn parse_wattage_estimated(&self, data: &FieldData) -> bool {
+        false
+    }
+    /// Where the wattage from `parse_wattage` is measured, the wall unless it is an estimate.
+    fn parse_wattage_location(&self, data: &FieldData) -> WattageLocation {
+        if self.parse_wattage_estimated(data) {
+            WattageLocation::Estimated
+        } else {
+            WattageLocation::Wall
+        }
+    }
 }
 
 // Wattage Limit
@@ -488,12 +905,84 @@ pub trait GetWattageLimit: CollectData {
         self.parse_wattage_limit(&data)
     }
     #[allow(unused_variables)]
-    fn parse_wattage_limit(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage_limit(&self, data: &FieldData) -> Option<Power> {
+        None
+    }
+}
+
+// Frequency
+#[async_trait]
+pub trait GetFrequency: CollectData {
+    /// Returns the configured chip frequency, when it is the same across all boards.
+    async fn get_frequency(&self) -> Option<Frequency> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::Frequency]).await;
+        self.parse_frequency(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_frequency(&self, data: &FieldData) -> Option<Frequency> {
+        None
+    }
+}
+
+// Voltage
+#[async_trait]
+pub trait GetVoltage: CollectData {
+    /// Returns the configured hashboard supply voltage.
+    async fn get_voltage(&self) -> Option<Voltage> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::Voltage]).await;
+        self.parse_voltage(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_voltage(&self, data: &FieldData) -> Option<Voltage> {
+        None
+    }
+}
+
+// Work Mode
+#[async_trait]
+pub trait GetWorkMode: CollectData {
+    async fn get_work_mode(&self) -> Option<WorkMode> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::WorkMode]).await;
+        self.parse_work_mode(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_work_mode(&self, data: &FieldData) -> Option<WorkMode> {
+        None
+    }
+}
+
+// Autotune
+#[async_trait]
+pub trait GetAutotune: CollectData {
+    async fn get_autotune_enabled(&self) -> Option<bool> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::Autotune]).await;
+        self.parse_autotune_enabled(&data)
+    }
+    async fn get_autotune_profile_bounds(&self) -> Option<AutotuneProfileBounds> {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&[DataField::Autotune]).await;
+        self.parse_autotune_profile_bounds(&data)
+    }
+    #[allow(unused_variables)]
+    fn parse_autotune_enabled(&self, data: &FieldData) -> Option<bool> {
+        None
+    }
+    #[allow(unused_variables)]
+    fn parse_autotune_profile_bounds(&self, data: &FieldData) -> Option<AutotuneProfileBounds> {
         None
     }
 }
 
 // Light Flashing
+/// The state of the locate light, used to find a miner on the rack.
+///
+/// `Some(true)` means the locate light is active, `Some(false)` that the firmware confirmed it
+/// isn't, and `None` that the state is unknown, either because the firmware doesn't report it or
+/// because it reported a value that isn't understood.
 #[async_trait]
 pub trait GetLightFlashing: CollectData {
     async fn get_light_flashing(&self) -> Option<bool> {
@@ -502,25 +991,342 @@ pub trait GetLightFlashing: CollectData {
         self.parse_light_flashing(&data)
     }
     #[allow(unused_variables)]
-    fn parse_light_flashing(&self, data: &HashMap<DataField, Value>) -> Option<bool> {
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
         None
     }
 }
 
 // Setters
+pub trait DryRunControl: GetIP {
+    /// Returns the dry-run log control commands are recorded to, if dry-run is enabled.
+    fn get_dry_run(&self) -> Option<DryRun>;
+    /// Enable dry-run by attaching a log, or send control commands again with `None`.
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>);
+}
+
+/// Run a control command, or only record it when the miner is in dry-run mode.
+///
+/// `send` performs the command and interprets the response, it is never polled in dry-run, so
+/// nothing is written to the miner and the command is reported as successful. A command the
+/// command policy blocks fails with [`MinerError::PolicyDenied`] in dry-run too, as it would
+/// when sent.
+pub(crate) async fn execute_control<M, F>(
+    miner: &M,
+    command: &str,
+    payload: Value,
+    send: F,
+) -> Result<bool>
+where
+    M: DryRunControl + CommandPolicyControl + ?Sized,
+    F: Future<Output = Result<bool>>,
+{
+    match miner.get_dry_run() {
+        Some(dry_run) => {
+            miner.get_command_policy().check_write(command)?;
+            dry_run.record(PlannedAction {
+                ip: miner.get_ip(),
+                command: command.to_string(),
+                payload,
+            });
+            Ok(true)
+        }
+        None => send.await,
+    }
+}
+
+pub trait CommandPolicyControl {
+    /// Returns the policy the miner's API clients check commands against.
+    fn get_command_policy(&self) -> CommandPolicy;
+    /// Restrict the commands sent to the miner, [`CommandPolicy::allow_all`] lifts restrictions.
+    fn set_command_policy(&mut self, policy: CommandPolicy);
+}
+
+/// Whether a control command was accepted by the miner.
+///
+/// Failures are reported as `false`, except for commands blocked by the command policy, which
+/// are returned as [`MinerError::PolicyDenied`] so callers can tell them apart.
+pub(crate) fn accepted<T>(result: Result<T>) -> Result<bool> {
+    match result {
+        Err(e) if e.is::<MinerError>() => Err(e),
+        result => Ok(result.is_ok()),
+    }
+}
+
 #[async_trait]
 pub trait SetFaultLight {
     async fn set_fault_light(&self, fault: bool) -> Result<bool>;
 }
 
+/// Confirm a fault light change by reading the light back.
+///
+/// Firmware that doesn't report the light state, or a miner in dry-run mode, keeps the result
+/// of the command.
+pub(crate) async fn confirm_fault_light<M>(miner: &M, fault: bool, sent: bool) -> bool
+where
+    M: GetLightFlashing + DryRunControl + Sync,
+{
+    if !sent || miner.get_dry_run().is_some() {
+        return sent;
+    }
+    match miner.get_light_flashing().await {
+        Some(flashing) => flashing == fault,
+        None => sent,
+    }
+}
+
 #[async_trait]
 pub trait SetPowerLimit {
+    /// Set the power limit, refusing limits outside of the model's
+    /// [`PowerLimits`][`crate::data::device::PowerLimits`] before anything is sent.
     async fn set_power_limit(&self, limit: Power) -> Result<bool>;
+    /// Set the power limit without checking it against the model's range, for experimental
+    /// tuning.
+    ///
+    /// The firmware may clamp a limit out of range, or stop hashing until it is reset.
+    async fn set_power_limit_unchecked(&self, limit: Power) -> Result<bool> {
+        self.set_power_limit(limit).await
+    }
+    /// Set the power limit, returning the limit the miner actually applied.
+    ///
+    /// Firmwares that only offer fixed power levels apply the nearest level at or below the
+    /// request, so the result can be lower than `limit`. `None` means nothing was applied.
+    async fn apply_power_limit(&self, limit: Power) -> Result<Option<Power>> {
+        Ok(self.set_power_limit(limit).await?.then_some(limit))
+    }
+}
+
+#[async_trait]
+pub trait SetPassword {
+    /// Change the password used to access the miner, updating the stored credentials so later
+    /// commands keep working.
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool>;
+}
+
+/// A username and password for a miner's authenticated API.
+///
+/// The password is redacted from the `Debug` output, so factories and miners can be logged.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Credentials {
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+}
+
+pub trait SetCredentials {
+    /// Credentials to fall back to, in order, when the miner rejects the ones in use.
+    ///
+    /// Backends without an authenticated API, or that don't retry yet, ignore them.
+    #[allow(unused_variables)]
+    fn set_credentials(&mut self, credentials: Vec<Credentials>) {}
+}
+
+/// Where to reach each of a miner's APIs, for miners behind a NAT or port-forward.
+///
+/// `None` keeps the backend's default, the miner's IP on the standard port of that API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinerEndpoints {
+    /// Address of the RPC API, normally port 4028, or 4433 on btminer 3.x
+    pub rpc: Option<SocketAddr>,
+    /// Address of the web API, port 80 on most firmware
+    pub web: Option<SocketAddr>,
+}
+
+pub trait EndpointControl {
+    /// Connect to the APIs of the miner at `endpoints` instead of their default addresses.
+    ///
+    /// The miner keeps its IP, which is still what its `MinerData` reports. Backends that
+    /// don't support this yet ignore it.
+    #[allow(unused_variables)]
+    fn set_endpoints(&mut self, endpoints: MinerEndpoints) {}
+}
+
+/// Whether a new hostname is already in use, or only after the miner reboots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostnameChange {
+    Applied,
+    RebootRequired,
+}
+
+#[async_trait]
+pub trait SetHostname {
+    /// Change the hostname the miner announces on the network.
+    ///
+    /// Hostnames are validated against RFC 1123 before anything is sent, other network settings
+    /// are left unchanged.
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange>;
+}
+
+/// Check a hostname against RFC 1123: at most 253 characters, made up of dot separated labels of
+/// 1 to 63 letters, digits and hyphens, which don't start or end with a hyphen.
+pub(crate) fn validate_hostname(hostname: &str) -> Result<(), ControlError> {
+    let invalid = |reason: &str| {
+        Err(ControlError::InvalidParameter(format!(
+            "hostname {hostname:?} {reason}"
+        )))
+    };
+    if hostname.is_empty() || hostname.len() > 253 {
+        return invalid("must be between 1 and 253 characters");
+    }
+    for label in hostname.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return invalid("has a label that is not between 1 and 63 characters");
+        }
+        if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return invalid("may only contain letters, digits and hyphens");
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return invalid("has a label starting or ending with a hyphen");
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+pub trait SetPools {
+    /// Replace the pools configured on the miner, in order of priority.
+    ///
+    /// Slots the firmware has beyond the given pools are cleared. Dry-run records the pools as
+    /// serialized, so passwords are left out unless
+    /// [`set_serialize_secrets`][`crate::data::pool::set_serialize_secrets`] is enabled.
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool>;
+}
+
+/// Check that between one and `slots` pools are being written.
+pub(crate) fn validate_pool_count(pools: &[PoolConfig], slots: usize) -> Result<(), ControlError> {
+    if pools.is_empty() || pools.len() > slots {
+        return Err(ControlError::InvalidParameter(format!(
+            "{} pools given, the miner supports between 1 and {slots}",
+            pools.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Check that no pool is given twice with the same URL and user, which would only shift the
+/// priority of the pools after it.
+pub(crate) fn validate_unique_pools(pools: &[PoolConfig]) -> Result<(), ControlError> {
+    for (idx, pool) in pools.iter().enumerate() {
+        if pools[..idx]
+            .iter()
+            .any(|p| p.url == pool.url && p.user == pool.user)
+        {
+            return Err(ControlError::InvalidParameter(format!(
+                "pool {idx} repeats {} for user {}",
+                pool.url, pool.user
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// A pool the firmware stored differently than it was written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolRewrite {
+    /// Priority of the pool, 0 is the primary
+    pub position: u16,
+    pub requested: PoolConfig,
+    pub applied: PoolData,
+}
+
+/// How a miner stored a pool update, read back once the update was applied.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PoolUpdate {
+    /// Pools stored at their priority with a different URL or user, firmware normalizes some URLs
+    pub rewritten: Vec<PoolRewrite>,
+    /// Pools missing from their priority slot
+    pub rejected: Vec<PoolConfig>,
+    /// Pools still configured in the slots past the written ones
+    pub stale: Vec<PoolData>,
+}
+
+impl PoolUpdate {
+    /// Compare the pools read back from the miner against the ones written, by priority.
+    pub(crate) fn compare(requested: &[PoolConfig], applied: &[PoolData]) -> Self {
+        let mut update = Self::default();
+        for (idx, pool) in requested.iter().enumerate() {
+            let stored = applied
+                .iter()
+                .find(|p| p.position == Some(idx as u16) && p.url.is_some());
+            match stored {
+                None => update.rejected.push(pool.clone()),
+                Some(stored)
+                    if stored.url.as_ref() != Some(&pool.url)
+                        || stored.user.as_deref() != Some(pool.user.as_str()) =>
+                {
+                    update.rewritten.push(PoolRewrite {
+                        position: idx as u16,
+                        requested: pool.clone(),
+                        applied: stored.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        update.stale = applied
+            .iter()
+            .filter(|p| {
+                p.url.is_some() && p.position.is_some_and(|p| p as usize >= requested.len())
+            })
+            .cloned()
+            .collect();
+        update
+    }
+
+    /// Whether every pool was stored at its priority and no other pool is left configured.
+    pub fn is_applied(&self) -> bool {
+        self.rejected.is_empty() && self.stale.is_empty()
+    }
+}
+
+#[async_trait]
+pub trait SetBoardTuning {
+    /// Set the frequency and/or voltage of a single hashboard, leaving the other value unchanged
+    /// when `None`.
+    ///
+    /// Values outside of the model's safe tuning range are rejected before anything is sent.
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool>;
 }
 
 #[async_trait]
 pub trait Restart {
-    async fn restart(&self) -> Result<bool>;
+    /// Restart the miner with a full system reboot, like [`Self::reboot_system`].
+    ///
+    /// Firmware that can't reboot the system returns
+    /// [`ControlError::UnsupportedOperation`] instead of restarting only the mining process,
+    /// call [`Self::restart_mining`] for that.
+    ///
+    /// This changed from earlier releases, where `restart` on Avalon miners restarted cgminer,
+    /// it now fails there. Call [`Self::restart_mining`] for the old behavior, or fall back to it
+    /// on [`ControlError::UnsupportedOperation`].
+    async fn restart(&self) -> Result<bool> {
+        self.reboot_system().await
+    }
+    /// Restart only the mining process, keeping the control board and network up.
+    async fn restart_mining(&self) -> Result<bool>;
+    /// Reboot the whole control board.
+    async fn reboot_system(&self) -> Result<bool>;
 }
 
 #[async_trait]
@@ -533,6 +1339,14 @@ pub trait Resume {
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool>;
 }
 
+pub trait SetRPCReadLimits {
+    /// Bound the size of and idle time between reads of the miner's RPC responses.
+    ///
+    /// Backends without an RPC API ignore them.
+    #[allow(unused_variables)]
+    fn set_rpc_read_limits(&mut self, limits: RPCReadLimits) {}
+}
+
 // Messages
 #[async_trait]
 pub trait GetMessages: CollectData {
@@ -542,7 +1356,7 @@ pub trait GetMessages: CollectData {
         self.parse_messages(&data)
     }
     #[allow(unused_variables)]
-    fn parse_messages(&self, data: &HashMap<DataField, Value>) -> Vec<MinerMessage> {
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
         vec![]
     }
 }
@@ -556,25 +1370,47 @@ pub trait GetUptime: CollectData {
         self.parse_uptime(&data)
     }
     #[allow(unused_variables)]
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         None
     }
 }
 
 // Is Mining
+/// The fields the mining state is parsed from. Backends tell a miner that runs but doesn't hash
+/// from a mining one by its hashrate, so `get_is_mining` and `get_status` agree with `get_data`.
+const MINING_STATE_FIELDS: [DataField; 2] = [DataField::IsMining, DataField::Hashrate];
+
 #[async_trait]
 pub trait GetIsMining: CollectData {
     async fn get_is_mining(&self) -> bool {
         let mut collector = self.get_collector();
-        let data = collector.collect(&[DataField::IsMining]).await;
+        let data = collector.collect(&MINING_STATE_FIELDS).await;
         self.parse_is_mining(&data)
     }
     #[allow(unused_variables)]
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
         true
     }
 }
 
+// Status
+#[async_trait]
+pub trait GetStatus: CollectData + GetIsMining {
+    async fn get_status(&self) -> MinerStatus {
+        let mut collector = self.get_collector();
+        let data = collector.collect(&MINING_STATE_FIELDS).await;
+        self.parse_status(&data)
+    }
+    /// Backends that can't tell why a miner is stopped fall back to `parse_is_mining`.
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        if self.parse_is_mining(data) {
+            MinerStatus::Mining
+        } else {
+            MinerStatus::Unknown
+        }
+    }
+}
+
 // Pools
 #[async_trait]
 pub trait GetPools: CollectData {
@@ -584,7 +1420,224 @@ pub trait GetPools: CollectData {
         self.parse_pools(&data)
     }
     #[allow(unused_variables)]
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
         vec![]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parse_mac_str() {
+        let expected = MacAddr::from_str("AA:BB:CC:DD:EE:01").ok();
+        assert_eq!(parse_mac_str("AA:BB:CC:DD:EE:01"), expected);
+        assert_eq!(parse_mac_str("aa-bb-cc-dd-ee-01"), expected);
+        assert_eq!(parse_mac_str(" aabbccddee01 "), expected);
+    }
+
+    #[test]
+    fn test_parse_mac_str_placeholders() {
+        for raw in [
+            "00:00:00:00:00:00",
+            "000000000000",
+            "FF:FF:FF:FF:FF:FF",
+            "ff:ff:ff:ff:ff:ff",
+            "",
+            "unknown",
+            "zz:zz:zz:zz:zz:zz",
+            "AA:BB:CC:DD:EE",
+        ] {
+            assert_eq!(parse_mac_str(raw), None, "{raw:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn test_validate_hostname() {
+        for valid in ["miner-01", "S21-rack4-slot12", "miner.site.example", "a"] {
+            assert!(
+                validate_hostname(valid).is_ok(),
+                "{valid:?} should be valid"
+            );
+        }
+        let long_label = "a".repeat(64);
+        let long_hostname = ["a"; 128].join(".");
+        for invalid in [
+            "",
+            "-miner",
+            "miner-",
+            "miner_01",
+            "miner 01",
+            "miner..site",
+            long_label.as_str(),
+            long_hostname.as_str(),
+        ] {
+            assert!(
+                matches!(
+                    validate_hostname(invalid),
+                    Err(ControlError::InvalidParameter(_))
+                ),
+                "{invalid:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_light_flashing_tri_state() {
+        use crate::miners::data::{DataField, FieldData};
+        use serde_json::json;
+
+        type Parse = Box<dyn Fn(&FieldData) -> Option<bool>>;
+        type Case = (&'static str, Parse, Vec<(Value, Option<bool>)>);
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let mut cases: Vec<Case> = Vec::new();
+        #[cfg(feature = "antminer")]
+        {
+            use crate::data::device::models::antminer::AntMinerModel::S19Pro;
+            use crate::miners::backends::antminer::v2020::AntMinerV2020;
+            let antminer = AntMinerV2020::new(ip, MinerModel::AntMiner(S19Pro));
+            cases.push((
+                "AntMiner",
+                Box::new(move |d| antminer.parse_light_flashing(d)),
+                vec![
+                    (json!(true), Some(true)),
+                    (json!(false), Some(false)),
+                    (json!("true"), Some(true)),
+                    (json!("0"), Some(false)),
+                    (json!("blinking"), None),
+                ],
+            ));
+        }
+        #[cfg(feature = "avalon")]
+        {
+            use crate::data::device::models::avalon::AvalonMinerModel::Avalon1246;
+            use crate::miners::backends::avalonminer::AvalonMinerV1;
+            let avalon = AvalonMinerV1::new(ip, MinerModel::AvalonMiner(Avalon1246));
+            cases.push((
+                "AvalonMiner",
+                Box::new(move |d| avalon.parse_light_flashing(d)),
+                vec![(json!(0), Some(false)), (json!(1), Some(true))],
+            ));
+        }
+        #[cfg(feature = "braiins")]
+        {
+            use crate::data::device::models::antminer::AntMinerModel::S19Pro;
+            use crate::miners::backends::braiins::v25_07::BraiinsV2507;
+            let braiins = BraiinsV2507::new(ip, MinerModel::AntMiner(S19Pro));
+            cases.push((
+                "Braiins",
+                Box::new(move |d| braiins.parse_light_flashing(d)),
+                vec![(json!(true), Some(true)), (json!(false), Some(false))],
+            ));
+        }
+        #[cfg(feature = "epic")]
+        {
+            use crate::data::device::models::antminer::AntMinerModel::S19Pro;
+            use crate::miners::backends::epic::PowerPlayV1;
+            let epic = PowerPlayV1::new(ip, MinerModel::AntMiner(S19Pro));
+            cases.push((
+                "ePIC",
+                Box::new(move |d| epic.parse_light_flashing(d)),
+                vec![(json!(true), Some(true)), (json!(false), Some(false))],
+            ));
+        }
+        #[cfg(feature = "luxos")]
+        {
+            use crate::data::device::models::antminer::AntMinerModel::S19Pro;
+            use crate::miners::backends::luxminer::v1::LuxMinerV1;
+            let luxminer = LuxMinerV1::new(ip, MinerModel::AntMiner(S19Pro));
+            cases.push((
+                "LuxMiner",
+                Box::new(move |d| luxminer.parse_light_flashing(d)),
+                vec![
+                    (json!("auto"), Some(false)),
+                    (json!("off"), Some(false)),
+                    (json!("blink"), Some(true)),
+                    (json!("on"), Some(true)),
+                    (json!("rainbow"), None),
+                ],
+            ));
+        }
+        #[cfg(feature = "marathon")]
+        {
+            use crate::data::device::models::antminer::AntMinerModel::S19Pro;
+            use crate::miners::backends::marathon::MaraV1;
+            let marathon = MaraV1::new(ip, MinerModel::AntMiner(S19Pro));
+            cases.push((
+                "Marathon",
+                Box::new(move |d| marathon.parse_light_flashing(d)),
+                vec![(json!(true), Some(true)), (json!(false), Some(false))],
+            ));
+        }
+        #[cfg(feature = "vnish")]
+        {
+            use crate::data::device::models::antminer::AntMinerModel::S19Pro;
+            use crate::miners::backends::vnish::VnishV120;
+            let vnish = VnishV120::new(ip, MinerModel::AntMiner(S19Pro));
+            cases.push((
+                "Vnish",
+                Box::new(move |d| vnish.parse_light_flashing(d)),
+                vec![(json!(true), Some(true)), (json!(false), Some(false))],
+            ));
+        }
+        #[cfg(feature = "whatsminer")]
+        {
+            use crate::data::device::models::whatsminer::WhatsMinerModel::{M20SV10, M60SVK30};
+            use crate::miners::backends::whatsminer::{WhatsMinerV1, WhatsMinerV2, WhatsMinerV3};
+            let whatsminer_v1 = WhatsMinerV1::new(ip, MinerModel::WhatsMiner(M20SV10));
+            let whatsminer_v2 = WhatsMinerV2::new(ip, MinerModel::WhatsMiner(M20SV10));
+            let whatsminer_v3 = WhatsMinerV3::new(ip, MinerModel::WhatsMiner(M60SVK30));
+            cases.push((
+                "WhatsMiner V1",
+                Box::new(move |d| whatsminer_v1.parse_light_flashing(d)),
+                vec![(json!("auto"), None)],
+            ));
+            cases.push((
+                "WhatsMiner V2",
+                Box::new(move |d| whatsminer_v2.parse_light_flashing(d)),
+                vec![
+                    (json!("auto"), Some(false)),
+                    (json!("off"), Some(false)),
+                    (json!("on"), Some(true)),
+                    (json!("manual"), Some(true)),
+                    (json!("red"), None),
+                ],
+            ));
+            cases.push((
+                "WhatsMiner V3",
+                Box::new(move |d| whatsminer_v3.parse_light_flashing(d)),
+                vec![
+                    (json!("auto"), Some(false)),
+                    (json!("off"), Some(false)),
+                    (json!("on"), Some(true)),
+                    (json!("red"), None),
+                ],
+            ));
+        }
+        #[cfg(feature = "bitaxe")]
+        {
+            use crate::data::device::models::bitaxe::BitaxeModel::Supra;
+            use crate::miners::backends::bitaxe::Bitaxe290;
+            let bitaxe = Bitaxe290::new(ip, MinerModel::Bitaxe(Supra));
+            cases.push((
+                "Bitaxe",
+                Box::new(move |d| bitaxe.parse_light_flashing(d)),
+                vec![(json!(true), None)],
+            ));
+        }
+
+        for (backend, parse, table) in cases {
+            for (raw, expected) in table {
+                let data = FieldData::from([(DataField::LightFlashing, raw.clone())]);
+                assert_eq!(parse(&data), expected, "{backend} with {raw}");
+            }
+            assert_eq!(
+                parse(&FieldData::new()),
+                None,
+                "{backend} without a reading"
+            );
+        }
+    }
+}
@@ -2,22 +2,31 @@ use anyhow::{Result, anyhow, bail};
 use async_trait::async_trait;
 use macaddr::MacAddr;
 use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::str::FromStr;
 use std::time::Duration;
 
 use crate::data::board::{BoardData, ChipData};
-use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerModel};
-use crate::data::device::{MinerControlBoard, MinerMake};
+use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerModel};
+use crate::data::device::{MinerControlBoard, MinerMake, TuningLimits};
 use crate::data::fan::FanData;
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::pool::{PoolData, PoolURL};
+use crate::data::health::HealthThresholds;
+use crate::data::message::{MessageSeverity, MinerMessage, MinerMessageKind};
+use crate::data::miner::MinerStatus;
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::{DryRun, REDACTED};
+use crate::miners::backends::errors::{ControlError, MinerError};
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_pointer,
+    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData,
+    get_by_pointer,
 };
 
 use web::VnishWebAPI;
@@ -29,6 +38,10 @@ pub struct VnishV120 {
     ip: IpAddr,
     web: VnishWebAPI,
     device_info: DeviceInfo,
+    labels: Option<HashMap<String, String>>,
+    health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
 }
 
 impl VnishV120 {
@@ -37,11 +50,15 @@ impl VnishV120 {
             ip,
             web: VnishWebAPI::new(ip, 80),
             device_info: DeviceInfo::new(
-                MinerMake::from(model),
+                MinerMake::from(&model),
                 model,
                 MinerFirmware::VNish,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
         }
     }
 }
@@ -70,6 +87,8 @@ impl GetDataLocations for VnishV120 {
         let summary_cmd = cmd("summary");
         let chains_cmd = cmd("chains");
         let factory_info_cmd = cmd("chains/factory-info");
+        let settings_cmd = cmd("settings");
+        let autotune_cmd = cmd("autotune/status");
 
         match data_field {
             DataField::Mac => vec![(
@@ -186,7 +205,7 @@ impl GetDataLocations for VnishV120 {
                     DataExtractor {
                         func: get_by_pointer,
                         key: Some("/miner/chains"),
-                        tag: None,
+                        tag: Some("summary"),
                     },
                 ),
                 (
@@ -194,18 +213,44 @@ impl GetDataLocations for VnishV120 {
                     DataExtractor {
                         func: get_by_pointer,
                         key: Some(""),
-                        tag: None,
+                        tag: Some("chains"),
+                    },
+                ),
+                (
+                    settings_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/miner/overclock/chains"),
+                        tag: Some("overclock"),
+                    },
+                ),
+                (
+                    autotune_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/chains"),
+                        tag: Some("autotune"),
+                    },
+                ),
+            ],
+            DataField::Pools => vec![
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/miner/pools"),
+                        tag: Some("pools"),
+                    },
+                ),
+                (
+                    settings_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/miner/pools"),
+                        tag: Some("config"),
                     },
                 ),
             ],
-            DataField::Pools => vec![(
-                summary_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/miner/pools"),
-                    tag: None,
-                },
-            )],
             DataField::IsMining => vec![(
                 status_cmd,
                 DataExtractor {
@@ -222,6 +267,24 @@ impl GetDataLocations for VnishV120 {
                     tag: None,
                 },
             )],
+            DataField::Messages => vec![
+                (
+                    status_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/miner_state"),
+                        tag: Some("state"),
+                    },
+                ),
+                (
+                    autotune_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some(""),
+                        tag: Some("autotune"),
+                    },
+                ),
+            ],
             _ => vec![],
         }
     }
@@ -235,7 +298,69 @@ impl GetIP for VnishV120 {
 
 impl GetDeviceInfo for VnishV120 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for VnishV120 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for VnishV120 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for VnishV120 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for VnishV120 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for VnishV120 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.web.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.web.policy = policy;
+    }
+}
+
+impl GetCapabilities for VnishV120 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_password: true,
+            supports_hostname: true,
+            supports_board_tuning: true,
+            supports_restart: true,
+            has_chip_telemetry: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -246,88 +371,132 @@ impl CollectData for VnishV120 {
 }
 
 impl GetMAC for VnishV120 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
         data.extract::<String>(DataField::Mac)
-            .and_then(|s| MacAddr::from_str(&s).ok())
+            .and_then(|s| parse_mac_str(&s))
     }
 }
 
+impl GetReportedIp for VnishV120 {}
+
 impl GetSerialNumber for VnishV120 {
-    fn parse_serial_number(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_serial_number(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::SerialNumber)
     }
 }
 
 impl GetHostname for VnishV120 {
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::Hostname)
     }
 }
 
 impl GetApiVersion for VnishV120 {
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::ApiVersion)
     }
 }
 
 impl GetFirmwareVersion for VnishV120 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
 }
 
 impl GetControlBoardVersion for VnishV120 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
         data.extract::<String>(DataField::ControlBoardVersion)
             .and_then(|s| MinerControlBoard::from_str(&s).ok())
     }
 }
 
 impl GetHashboards for VnishV120 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         let mut hashboards: Vec<BoardData> = Vec::new();
 
-        let chains_data = data.get(&DataField::Hashboards).and_then(|v| v.as_array());
-
-        if let Some(chains_array) = chains_data {
-            for (idx, chain) in chains_array.iter().enumerate() {
-                let hashrate = Self::extract_hashrate(chain, &["/hashrate_rt", "/hr_realtime"]);
-                let expected_hashrate =
-                    Self::extract_hashrate(chain, &["/hashrate_ideal", "/hr_nominal"]);
-
-                let frequency = Self::extract_frequency(chain);
-                let voltage = Self::extract_voltage(chain);
-                let (board_temperature, chip_temperature) = Self::extract_temperatures(chain);
-
-                let working_chips = Self::extract_working_chips(chain);
-                let active = Self::extract_chain_active_status(chain, &hashrate);
-                let serial_number = Self::extract_chain_serial(chain, data);
-                let tuned = Self::extract_tuned_status(chain, data);
-                let chips = Self::extract_chips(chain);
-
-                hashboards.push(BoardData {
-                    position: chain
-                        .pointer("/id")
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(idx as u64) as u8,
-                    hashrate,
-                    expected_hashrate,
-                    board_temperature,
-                    intake_temperature: chip_temperature,
-                    outlet_temperature: chip_temperature,
-                    expected_chips: self.device_info.hardware.chips,
-                    working_chips,
-                    serial_number,
-                    chips,
-                    voltage,
-                    frequency,
-                    tuned,
-                    active,
-                });
-            }
+        let chains_array: Vec<&Value> = ["/summary", "/chains"]
+            .iter()
+            .filter_map(|tag| data.get(&DataField::Hashboards)?.pointer(tag)?.as_array())
+            .flatten()
+            .collect();
+        let overclock = data
+            .get(&DataField::Hashboards)
+            .and_then(|v| v.pointer("/overclock"))
+            .and_then(|v| v.as_array());
+        let autotune = data
+            .get(&DataField::Hashboards)
+            .and_then(|v| v.pointer("/autotune"))
+            .and_then(|v| v.as_array());
+
+        for (idx, chain) in chains_array.into_iter().enumerate() {
+            let hashrate = Self::extract_hashrate(chain, &["/hashrate_rt", "/hr_realtime"]);
+            let expected_hashrate =
+                Self::extract_hashrate(chain, &["/hashrate_ideal", "/hr_nominal"]);
+
+            let position = chain
+                .pointer("/id")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(idx as u64) as u8;
+            // prefer the applied overclock set points over the live readings
+            let tuning = overclock.and_then(|chains| chains.get(position as usize));
+            let frequency = tuning
+                .and_then(Self::extract_tuned_frequency)
+                .or_else(|| Self::extract_frequency(chain));
+            let voltage = tuning
+                .and_then(Self::extract_tuned_voltage)
+                .or_else(|| Self::extract_voltage(chain));
+            let (board_temperature, chip_temperature) = Self::extract_temperatures(chain);
+            let autotune_chain = autotune.and_then(|chains| {
+                chains
+                    .iter()
+                    .find(|c| c.pointer("/id").and_then(|v| v.as_u64()) == Some(position as u64))
+            });
+
+            let active = Self::extract_chain_active_status(chain, &hashrate);
+            let serial_number = Self::extract_chain_serial(chain, data);
+            let tuned = autotune_chain
+                .and_then(|c| c.pointer("/status"))
+                .and_then(|v| v.as_str())
+                .and_then(Self::parse_autotune_state)
+                .or_else(|| Self::extract_tuned_status(chain, data));
+            let wattage = autotune_chain
+                .and_then(|c| c.pointer("/power_consumption"))
+                .or_else(|| chain.pointer("/power_consumption"))
+                .and_then(|v| v.as_f64())
+                .map(Power::from_watts);
+
+            let mut chips = Self::extract_chips(chain);
+            let grades = autotune_chain
+                .and_then(|c| c.pointer("/chip_grades"))
+                .and_then(|v| v.as_array());
+            let working_chips = match grades {
+                Some(grades) => {
+                    Self::apply_chip_grades(&mut chips, grades);
+                    Some(chips.iter().filter(|c| c.working == Some(true)).count() as u16)
+                }
+                None => Self::extract_working_chips(chain),
+            };
+
+            hashboards.push(BoardData {
+                position,
+                hashrate,
+                expected_hashrate,
+                board_temperature,
+                intake_temperature: chip_temperature,
+                outlet_temperature: chip_temperature,
+                expected_chips: self.device_info.hardware.chips,
+                working_chips,
+                serial_number,
+                chips,
+                sensor_temperatures: vec![],
+                voltage,
+                frequency,
+                wattage,
+                tuned,
+                active,
+                status: None,
+                extra: None,
+            });
         }
 
         hashboards
@@ -335,7 +504,7 @@ impl GetHashboards for VnishV120 {
 }
 
 impl GetHashrate for VnishV120 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::Hashrate, |f| HashRate {
             value: f,
             unit: HashRateUnit::GigaHash,
@@ -345,7 +514,7 @@ impl GetHashrate for VnishV120 {
 }
 
 impl GetExpectedHashrate for VnishV120 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| HashRate {
             value: f,
             unit: HashRateUnit::GigaHash,
@@ -354,8 +523,10 @@ impl GetExpectedHashrate for VnishV120 {
     }
 }
 
+impl GetAverageHashrate for VnishV120 {}
+
 impl GetFans for VnishV120 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut fans: Vec<FanData> = Vec::new();
 
         if let Some(fans_data) = data.get(&DataField::Fans)
@@ -377,26 +548,97 @@ impl GetFans for VnishV120 {
 
 impl GetPsuFans for VnishV120 {}
 
+impl GetFanControl for VnishV120 {}
+
+impl GetPsu for VnishV120 {}
+
+impl GetPsuRails for VnishV120 {}
+
 impl GetFluidTemperature for VnishV120 {}
 
+impl GetFluidFlow for VnishV120 {}
+
+impl GetFluidPressure for VnishV120 {}
+
 impl GetWattage for VnishV120 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<i64, _>(DataField::Wattage, |w| Power::from_watts(w as f64))
     }
 }
 
 impl GetWattageLimit for VnishV120 {}
 
+impl GetFrequency for VnishV120 {}
+impl GetVoltage for VnishV120 {}
+impl GetWorkMode for VnishV120 {}
+impl GetAutotune for VnishV120 {}
+
 impl GetLightFlashing for VnishV120 {
-    fn parse_light_flashing(&self, data: &HashMap<DataField, Value>) -> Option<bool> {
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
         data.extract::<bool>(DataField::LightFlashing)
     }
 }
 
-impl GetMessages for VnishV120 {}
+impl GetMessages for VnishV120 {
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
+        let mut messages: Vec<MinerMessage> = Vec::new();
+        let Some(messages_data) = data.get(&DataField::Messages) else {
+            return messages;
+        };
+        let chains = messages_data
+            .pointer("/autotune/chains")
+            .and_then(|v| v.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        fn chain_state(chain: &Value) -> Option<&str> {
+            chain.pointer("/status").and_then(|v| v.as_str())
+        }
+
+        let tuning = messages_data.pointer("/state").and_then(|v| v.as_str())
+            == Some("auto-tuning")
+            || messages_data
+                .pointer("/autotune/status")
+                .and_then(|v| v.as_str())
+                == Some("tuning")
+            || chains.iter().any(|c| chain_state(c) == Some("tuning"));
+        if tuning {
+            let message = match messages_data
+                .pointer("/autotune/progress")
+                .and_then(|v| v.as_u64())
+            {
+                Some(progress) => format!("Autotuning in progress ({progress}%)"),
+                None => String::from("Autotuning in progress"),
+            };
+            messages.push(
+                MinerMessage::new(0, 0, message, MessageSeverity::Info)
+                    .with_kind(MinerMessageKind::TuningIssue),
+            );
+        }
+
+        for (idx, chain) in chains.iter().enumerate() {
+            if chain_state(chain) == Some("failed") {
+                let id = chain
+                    .pointer("/id")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(idx as u64);
+                messages.push(
+                    MinerMessage::new(
+                        0,
+                        0,
+                        format!("Autotuning failed on chain {id}"),
+                        MessageSeverity::Warning,
+                    )
+                    .with_kind(MinerMessageKind::TuningIssue),
+                );
+            }
+        }
+
+        messages
+    }
+}
 
 impl GetUptime for VnishV120 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract::<String>(DataField::Uptime)
             .and_then(|uptime_str| {
                 // Parse uptime strings like "10 days, 18:00"
@@ -435,26 +677,38 @@ impl GetUptime for VnishV120 {
 }
 
 impl GetIsMining for VnishV120 {
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
-        data.extract::<String>(DataField::IsMining)
-            .map(|state| state == "mining")
-            .unwrap_or(false)
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
+    }
+}
+
+impl GetStatus for VnishV120 {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        match data.extract::<String>(DataField::IsMining).as_deref() {
+            Some("mining") => MinerStatus::Mining,
+            Some("stopped" | "shutting-down") => MinerStatus::Paused,
+            Some("starting" | "initializing" | "restarting" | "auto-tuning") => {
+                MinerStatus::Starting
+            }
+            Some("failure") => MinerStatus::Failure,
+            _ => MinerStatus::Unknown,
+        }
     }
 }
 
 impl GetPools for VnishV120 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
         let mut pools: Vec<PoolData> = Vec::new();
 
         if let Some(pools_data) = data.get(&DataField::Pools)
-            && let Some(pools_array) = pools_data.as_array()
+            && let Some(pools_array) = pools_data.get("pools").and_then(|v| v.as_array())
         {
+            let configured = pools_data.get("config").and_then(|v| v.as_array());
             for (idx, pool) in pools_array.iter().enumerate() {
                 let url = pool
                     .pointer("/url")
                     .and_then(|v| v.as_str())
-                    .map(String::from)
-                    .map(PoolURL::from);
+                    .and_then(PoolURL::parse);
 
                 let user = pool
                     .pointer("/user")
@@ -465,6 +719,11 @@ impl GetPools for VnishV120 {
                 let rejected_shares = pool.pointer("/rejected").and_then(|v| v.as_u64());
                 let pool_status = pool.pointer("/status").and_then(|v| v.as_str());
                 let (active, alive) = Self::parse_pool_status(pool_status);
+                let password = configured
+                    .and_then(|pools| pools.get(idx))
+                    .and_then(|pool| pool.pointer("/pass"))
+                    .and_then(|v| v.as_str())
+                    .map(String::from);
 
                 pools.push(PoolData {
                     position: Some(idx as u16),
@@ -474,6 +733,11 @@ impl GetPools for VnishV120 {
                     active,
                     alive,
                     user,
+                    password,
+                    last_share_difficulty: None,
+                    last_share_time: None,
+                    group: None,
+                    quota: None,
                 });
             }
         }
@@ -510,6 +774,22 @@ impl VnishV120 {
             .map(|v| Voltage::from_millivolts(v as f64))
     }
 
+    fn extract_tuned_frequency(tuning: &Value) -> Option<Frequency> {
+        tuning
+            .pointer("/freq")
+            .and_then(|v| v.as_f64())
+            .filter(|f| *f > 0.0)
+            .map(Frequency::from_megahertz)
+    }
+
+    fn extract_tuned_voltage(tuning: &Value) -> Option<Voltage> {
+        tuning
+            .pointer("/volt")
+            .and_then(|v| v.as_f64())
+            .filter(|v| *v > 0.0)
+            .map(Voltage::from_millivolts)
+    }
+
     fn extract_temperatures(chain: &Value) -> (Option<Temperature>, Option<Temperature>) {
         let board_temp = chain
             .pointer("/pcb_temp/max")
@@ -563,7 +843,7 @@ impl VnishV120 {
         }
     }
 
-    fn extract_chain_serial(chain: &Value, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn extract_chain_serial(chain: &Value, data: &FieldData) -> Option<String> {
         // Try to get serial from chain-specific data first (factory-info)
         chain
             .pointer("/serial")
@@ -575,7 +855,7 @@ impl VnishV120 {
             })
     }
 
-    fn extract_tuned_status(_chain: &Value, data: &HashMap<DataField, Value>) -> Option<bool> {
+    fn extract_tuned_status(_chain: &Value, data: &FieldData) -> Option<bool> {
         // Check miner state to determine tuning status
         if let Some(miner_state) = data.extract::<String>(DataField::IsMining) {
             match miner_state.as_str() {
@@ -588,6 +868,51 @@ impl VnishV120 {
         }
     }
 
+    /// Whether a chain is tuned, from its `/api/v1/autotune/status` phase.
+    fn parse_autotune_state(state: &str) -> Option<bool> {
+        match state {
+            "tuned" => Some(true),
+            "tuning" | "failed" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Working and tuned status of a chip from its autotune health grade.
+    ///
+    /// Green chips reached their target, orange chips are hashing below it, red chips are
+    /// failing and grey chips are not responding.
+    fn parse_chip_grade(grade: &str) -> (Option<bool>, Option<bool>) {
+        match grade {
+            "green" => (Some(true), Some(true)),
+            "orange" => (Some(true), Some(false)),
+            "red" | "grey" => (Some(false), Some(false)),
+            _ => (None, None),
+        }
+    }
+
+    /// Fill in the working and tuned status of a chain's chips from their grades, adding the
+    /// chips that the summary did not list.
+    fn apply_chip_grades(chips: &mut Vec<ChipData>, grades: &[Value]) {
+        for (idx, grade) in grades.iter().enumerate() {
+            let (working, tuned) = grade
+                .as_str()
+                .map(Self::parse_chip_grade)
+                .unwrap_or_default();
+            match chips.iter_mut().find(|c| c.position as usize == idx) {
+                Some(chip) => {
+                    chip.working = working.or(chip.working);
+                    chip.tuned = tuned;
+                }
+                None => chips.push(ChipData {
+                    position: idx as u16,
+                    working,
+                    tuned,
+                    ..Default::default()
+                }),
+            }
+        }
+    }
+
     fn extract_chips(chain: &Value) -> Vec<ChipData> {
         let mut chips: Vec<ChipData> = Vec::new();
 
@@ -630,6 +955,7 @@ impl VnishV120 {
                     frequency,
                     tuned: None,
                     working,
+                    estimated: false,
                 });
             }
         }
@@ -642,7 +968,9 @@ impl VnishV120 {
 impl SetFaultLight for VnishV120 {
     #[allow(unused_variables)]
     async fn set_fault_light(&self, fault: bool) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting the fault light".to_string(),
+        ))?
     }
 }
 
@@ -650,14 +978,109 @@ impl SetFaultLight for VnishV120 {
 impl SetPowerLimit for VnishV120 {
     #[allow(unused_variables)]
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting a power limit".to_string(),
+        ))?
+    }
+}
+
+impl SetCredentials for VnishV120 {}
+
+impl EndpointControl for VnishV120 {}
+
+#[async_trait]
+impl SetPassword for VnishV120 {
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        web::validate_password(new)?;
+        execute_control(self, "settings", json!({ "password": REDACTED }), async {
+            accepted(self.web.set_password(old, new).await)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SetHostname for VnishV120 {
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        validate_hostname(hostname)?;
+        let settings = json!({ "network": { "hostname": hostname } });
+        let mut restart_required = false;
+        let applied = execute_control(self, "settings", settings.clone(), async {
+            let response = self.web.update_settings(settings.clone()).await;
+            restart_required = response
+                .as_ref()
+                .ok()
+                .and_then(|r| r.get("restart_required"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            accepted(response)
+        })
+        .await?;
+        if !applied {
+            bail!("Failed to set hostname");
+        }
+        Ok(if restart_required {
+            HostnameChange::RebootRequired
+        } else {
+            HostnameChange::Applied
+        })
+    }
+}
+
+#[async_trait]
+impl SetPools for VnishV120 {
+    #[allow(unused_variables)]
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting pools".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for VnishV120 {
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        TuningLimits::validate_for(&self.device_info.model, frequency, voltage)?;
+        let settings = self
+            .web
+            .chain_tuning_settings(
+                board as usize,
+                frequency.map(|f| f.as_megahertz().round() as u64),
+                voltage.map(|v| v.as_millivolts().round() as u64),
+            )
+            .await;
+        match settings {
+            Ok(settings) => {
+                execute_control(self, "settings", settings.clone(), async {
+                    accepted(self.web.update_settings(settings).await)
+                })
+                .await
+            }
+            Err(e) if e.is::<ControlError>() || e.is::<MinerError>() => Err(e),
+            Err(_) => Ok(false),
+        }
     }
 }
 
 #[async_trait]
 impl Restart for VnishV120 {
-    async fn restart(&self) -> Result<bool> {
-        bail!("Unsupported command");
+    async fn restart_mining(&self) -> Result<bool> {
+        execute_control(self, "mining/restart", Value::Null, async {
+            accepted(self.web.post_action("mining/restart").await)
+        })
+        .await
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        execute_control(self, "system/reboot", Value::Null, async {
+            accepted(self.web.post_action("system/reboot").await)
+        })
+        .await
     }
 }
 
@@ -665,7 +1088,7 @@ impl Restart for VnishV120 {
 impl Pause for VnishV120 {
     #[allow(unused_variables)]
     async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation("pausing".to_string()))?
     }
 }
 
@@ -673,6 +1096,254 @@ impl Pause for VnishV120 {
 impl Resume for VnishV120 {
     #[allow(unused_variables)]
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation("resuming".to_string()))?
+    }
+}
+
+impl SetRPCReadLimits for VnishV120 {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::antminer::AntMinerModel;
+    use crate::data::miner::MinerData;
+    use crate::test::api::MockAPIClient;
+    use crate::test::json::vnish::v1_2_0::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_vnish_status() {
+        let miner = VnishV120::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+
+        let stopped = FieldData::from([(DataField::IsMining, json!("stopped"))]);
+        assert_eq!(miner.parse_status(&stopped), MinerStatus::Paused);
+        assert!(!miner.parse_is_mining(&stopped));
+
+        let failed = FieldData::from([(DataField::IsMining, json!("failure"))]);
+        assert_eq!(miner.parse_status(&failed), MinerStatus::Failure);
+
+        let mining = FieldData::from([(DataField::IsMining, json!("mining"))]);
+        assert!(miner.parse_is_mining(&mining));
+    }
+
+    #[test]
+    fn test_vnish_pool_passwords() {
+        let miner = VnishV120::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+
+        let data = FieldData::from([(
+            DataField::Pools,
+            json!({
+                "pools": [
+                    { "url": "stratum+tcp://btc.example.com:3333", "user": "worker.1", "status": "active" },
+                    { "url": "stratum+tcp://backup.example.com:3333", "user": "worker.1", "status": "working" }
+                ],
+                "config": [
+                    { "url": "stratum+tcp://btc.example.com:3333", "user": "worker.1", "pass": "x" },
+                    { "url": "stratum+tcp://backup.example.com:3333", "user": "worker.1", "pass": "secret" }
+                ]
+            }),
+        )]);
+        let pools = miner.parse_pools(&data);
+
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].password, Some("x".to_string()));
+        assert_eq!(pools[1].password, Some("secret".to_string()));
+    }
+
+    #[test]
+    fn test_vnish_tuning_readback() {
+        let miner = VnishV120::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+
+        let data = FieldData::from([(
+            DataField::Hashboards,
+            json!({
+                "summary": [
+                    { "id": 0, "frequency": 580, "voltage": 13700 },
+                    { "id": 1, "frequency": 580, "voltage": 13700 }
+                ],
+                "overclock": [
+                    { "freq": 650, "volt": 13500 },
+                    { "freq": 0, "volt": 0 }
+                ]
+            }),
+        )]);
+        let boards = miner.parse_hashboards(&data);
+
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].frequency, Some(Frequency::from_megahertz(650.0)));
+        assert_eq!(boards[0].voltage, Some(Voltage::from_millivolts(13500.0)));
+        // chains left on the global settings report their live values
+        assert_eq!(boards[1].frequency, Some(Frequency::from_megahertz(580.0)));
+        assert_eq!(boards[1].voltage, Some(Voltage::from_millivolts(13700.0)));
+    }
+
+    #[tokio::test]
+    async fn test_vnish_tuning_out_of_range() {
+        let miner = VnishV120::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+
+        for (frequency, voltage) in [
+            (Some(Frequency::from_megahertz(950.0)), None),
+            (None, Some(Voltage::from_volts(17.0))),
+            (None, None),
+        ] {
+            let err = miner
+                .set_board_tuning(0, frequency, voltage)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<ControlError>(),
+                Some(ControlError::InvalidParameter(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vnish_set_password_dry_run() {
+        let mut miner = VnishV120::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        let error = miner.set_password("admin", "").await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        assert!(dry_run.actions().is_empty());
+
+        assert!(miner.set_password("admin", "hunter22").await.unwrap());
+        let action = &dry_run.actions()[0];
+        assert_eq!(action.command, "settings");
+        assert_eq!(action.payload, json!({ "password": REDACTED }));
+    }
+
+    #[tokio::test]
+    async fn test_vnish_restart_commands() {
+        let mut miner = VnishV120::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        assert!(miner.restart_mining().await.unwrap());
+        assert!(miner.reboot_system().await.unwrap());
+
+        let commands: Vec<_> = dry_run.actions().into_iter().map(|a| a.command).collect();
+        assert_eq!(commands, vec!["mining/restart", "system/reboot"]);
+    }
+
+    async fn parse_fixture(summary: &str, status: &str, autotune: &str) -> MinerData {
+        let miner = VnishV120::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19KPro),
+        );
+
+        let mut results = HashMap::new();
+        for (command, data) in [
+            ("summary", summary),
+            ("status", status),
+            ("autotune/status", autotune),
+        ] {
+            let cmd = MinerCommand::WebAPI {
+                command,
+                parameters: None,
+            };
+            results.insert(cmd, Value::from_str(data).unwrap());
+        }
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+        miner.parse_data(data)
+    }
+
+    #[tokio::test]
+    async fn test_vnish_autotune_tuning() {
+        let miner_data = parse_fixture(
+            SUMMARY_S19K_PRO_TUNING,
+            STATUS_S19K_PRO_TUNING,
+            AUTOTUNE_STATUS_S19K_PRO_TUNING,
+        )
+        .await;
+        let boards = &miner_data.hashboards;
+
+        assert_eq!(boards.len(), 3);
+        assert_eq!(boards[0].tuned, Some(true));
+        assert_eq!(boards[1].tuned, Some(false));
+        assert_eq!(boards[2].tuned, Some(false));
+        assert_eq!(boards[0].wattage, Some(Power::from_watts(935.0)));
+        assert_eq!(boards[2].wattage, Some(Power::from_watts(902.0)));
+
+        assert_eq!(boards[1].chips.len(), 77);
+        assert_eq!(boards[1].chips[9].working, Some(true));
+        assert_eq!(boards[1].chips[9].tuned, Some(false));
+        assert_eq!(boards[1].chips[33].working, Some(false));
+        assert_eq!(boards[1].working_chips, Some(76));
+        assert_eq!(boards[2].working_chips, Some(74));
+
+        assert!(
+            miner_data.messages.contains(
+                &MinerMessage::new(
+                    0,
+                    0,
+                    String::from("Autotuning in progress (64%)"),
+                    MessageSeverity::Info,
+                )
+                .with_kind(MinerMessageKind::TuningIssue)
+            )
+        );
+        assert!(
+            miner_data.messages.contains(
+                &MinerMessage::new(
+                    0,
+                    0,
+                    String::from("Autotuning failed on chain 2"),
+                    MessageSeverity::Warning,
+                )
+                .with_kind(MinerMessageKind::TuningIssue)
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_vnish_autotune_tuned() {
+        let miner_data = parse_fixture(
+            SUMMARY_S19K_PRO_TUNED,
+            STATUS_S19K_PRO_TUNED,
+            AUTOTUNE_STATUS_S19K_PRO_TUNED,
+        )
+        .await;
+        let boards = &miner_data.hashboards;
+
+        assert!(boards.iter().all(|b| b.tuned == Some(true)));
+        assert_eq!(
+            boards
+                .iter()
+                .filter_map(|b| b.wattage)
+                .map(|w| w.as_watts())
+                .sum::<f64>(),
+            2826.0
+        );
+        assert!(boards[0].chips.iter().all(|c| c.tuned == Some(true)));
+        assert_eq!(boards[1].chips[20].tuned, Some(false));
+        assert_eq!(boards[1].chips[20].working, Some(true));
+        assert_eq!(boards[2].chips[76].working, Some(false));
+        assert_eq!(boards[2].working_chips, Some(76));
+        assert!(miner_data.messages.is_empty());
     }
 }
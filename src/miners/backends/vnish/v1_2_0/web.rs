@@ -1,10 +1,12 @@
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use reqwest::{Client, Method, Response};
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::{net::IpAddr, time::Duration};
 use tokio::sync::RwLock;
 
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 
@@ -16,7 +18,18 @@ pub struct VnishWebAPI {
     port: u16,
     timeout: Duration,
     bearer_token: RwLock<Option<String>>,
-    password: Option<String>,
+    password: RwLock<Option<String>>,
+    pub(crate) policy: CommandPolicy,
+}
+
+/// VNish accepts any password but an empty one.
+pub(crate) fn validate_password(password: &str) -> Result<(), ControlError> {
+    if password.is_empty() {
+        return Err(ControlError::InvalidParameter(
+            "VNish passwords must not be empty".to_string(),
+        ));
+    }
+    Ok(())
 }
 
 #[async_trait]
@@ -26,10 +39,10 @@ impl APIClient for VnishWebAPI {
             MinerCommand::WebAPI {
                 command,
                 parameters,
-            } => self
-                .send_command(command, false, parameters.clone(), Method::GET)
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => {
+                self.send_command(command, false, parameters.clone(), Method::GET)
+                    .await
+            }
             _ => Err(anyhow!("Cannot send non web command to web API")),
         }
     }
@@ -45,6 +58,11 @@ impl WebAPIClient for VnishWebAPI {
         parameters: Option<Value>,
         method: Method,
     ) -> Result<Value> {
+        if method == Method::GET {
+            self.policy.check(command)?;
+        } else {
+            self.policy.check_write(command)?;
+        }
         // Ensure we're authenticated before making the request
         if let Err(e) = self.ensure_authenticated().await {
             return Err(anyhow!("Failed to authenticate: {}", e));
@@ -81,31 +99,126 @@ impl VnishWebAPI {
             port,
             timeout: Duration::from_secs(5),
             bearer_token: RwLock::new(None),
-            password: Some("admin".to_string()), // Default password
+            password: RwLock::new(Some("admin".to_string())), // Default password
+            policy: CommandPolicy::default(),
         }
     }
 
     /// Ensure authentication token is present, authenticate if needed
     async fn ensure_authenticated(&self) -> Result<(), VnishError> {
-        if self.bearer_token.read().await.is_none() && self.password.is_some() {
-            if let Some(ref password) = self.password {
-                match self.authenticate(password).await {
-                    Ok(token) => {
-                        *self.bearer_token.write().await = Some(token);
-                        Ok(())
-                    }
-                    Err(e) => Err(e),
-                }
-            } else {
-                Err(VnishError::AuthenticationFailed)
-            }
-        } else {
-            Ok(())
+        if self.bearer_token.read().await.is_some() {
+            return Ok(());
+        }
+        let password = self.password.read().await.clone();
+        if let Some(ref password) = password {
+            let token = self.authenticate(password).await?;
+            *self.bearer_token.write().await = Some(token);
+        }
+        Ok(())
+    }
+
+    /// Change the web password, switching this client over to `new` once the miner accepts it.
+    pub async fn set_password(&self, old: &str, new: &str) -> Result<Value> {
+        validate_password(new)?;
+        self.policy.check_write("settings")?;
+
+        // unlock with the old password explicitly, the stored one may already be stale
+        let token = self.authenticate(old).await?;
+        let url = format!("http://{}:{}/api/v1/settings", self.ip, self.port);
+        let response = self
+            .client
+            .post(&url)
+            .json(&json!({ "password": new }))
+            .header("Authorization", format!("Bearer {token}"))
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(|e| VnishError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            Err(VnishError::HttpError(status.as_u16()))?
         }
+
+        *self.password.write().await = Some(new.to_string());
+        // tokens issued for the old password are revoked by the change
+        *self.bearer_token.write().await = None;
+
+        Ok(response.json().await.unwrap_or(Value::Null))
+    }
+
+    /// Build the settings update setting the frequency (MHz) and/or voltage (mV) of one chain
+    /// in the overclock settings, from the current settings, without writing it.
+    ///
+    /// The chain settings are owned by the autotuner while a preset is selected, so manual
+    /// tuning is refused until the preset is disabled.
+    pub async fn chain_tuning_settings(
+        &self,
+        chain: usize,
+        frequency: Option<u64>,
+        voltage: Option<u64>,
+    ) -> Result<Value> {
+        let settings = self
+            .send_command("settings", false, None, Method::GET)
+            .await?;
+
+        if let Some(preset) = settings
+            .pointer("/miner/overclock/preset")
+            .and_then(|v| v.as_str())
+            && !preset.is_empty()
+            && preset != "disabled"
+        {
+            Err(ControlError::UnsupportedOperation(format!(
+                "autotune preset {preset} is active, manual board tuning is locked"
+            )))?
+        }
+
+        let mut chains = settings
+            .pointer("/miner/overclock/chains")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        let chain_count = chains.len();
+        let Some(target) = chains.get_mut(chain) else {
+            return Err(ControlError::InvalidParameter(format!(
+                "chain {chain} does not exist, the miner has {chain_count} chains"
+            ))
+            .into());
+        };
+        if let Some(frequency) = frequency {
+            target["freq"] = json!(frequency);
+        }
+        if let Some(voltage) = voltage {
+            target["volt"] = json!(voltage);
+        }
+
+        Ok(json!({ "miner": { "overclock": { "chains": chains } } }))
+    }
+
+    /// Trigger an action that replies with an empty body, e.g. `mining/restart`.
+    pub async fn post_action(&self, route: &str) -> Result<()> {
+        self.policy.check_write(route)?;
+        if let Err(e) = self.ensure_authenticated().await {
+            return Err(anyhow!("Failed to authenticate: {}", e));
+        }
+
+        let url = format!("http://{}:{}/api/v1/{}", self.ip, self.port, route);
+        let response = self.execute_request(&url, &Method::POST, None).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            Err(VnishError::HttpError(status.as_u16()))?
+        }
+        Ok(())
+    }
+
+    pub async fn update_settings(&self, settings: Value) -> Result<Value> {
+        self.send_command("settings", true, Some(settings), Method::POST)
+            .await
     }
 
     async fn authenticate(&self, password: &str) -> Result<String, VnishError> {
-        let unlock_payload = serde_json::json!({ "pw": password });
+        let unlock_payload = json!({ "pw": password });
         let url = format!("http://{}:{}/api/v1/unlock", self.ip, self.port);
 
         let response = self
@@ -221,3 +334,227 @@ impl std::fmt::Display for VnishError {
 }
 
 impl std::error::Error for VnishError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashSet;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// A VNish web API that revokes every token and rejects the old password after a change.
+    #[derive(Default)]
+    struct MockVnish {
+        password: String,
+        tokens: HashSet<String>,
+        issued: usize,
+        settings: Value,
+        updates: Vec<Value>,
+    }
+
+    impl MockVnish {
+        fn handle(
+            &mut self,
+            method: &str,
+            path: &str,
+            token: Option<&str>,
+            body: Value,
+        ) -> (u16, Value) {
+            let authorized = token.is_some_and(|t| self.tokens.contains(t));
+            match path {
+                "/api/v1/unlock" if body["pw"] == self.password => {
+                    self.issued += 1;
+                    let token = format!("token-{}", self.issued);
+                    self.tokens.insert(token.clone());
+                    (200, json!({ "token": token }))
+                }
+                "/api/v1/settings" if authorized && method == "GET" => (200, self.settings.clone()),
+                "/api/v1/settings" if authorized && body.get("password").is_some() => {
+                    self.password = body["password"].as_str().unwrap().to_string();
+                    self.tokens.clear();
+                    (200, json!({}))
+                }
+                "/api/v1/settings" if authorized => {
+                    self.updates.push(body);
+                    (200, json!({ "restart_required": false }))
+                }
+                "/api/v1/info" if authorized => (200, json!({ "miner": "Antminer S19" })),
+                _ => (401, json!({ "err": "unauthorized" })),
+            }
+        }
+    }
+
+    async fn serve(mut stream: TcpStream, mock: Arc<Mutex<MockVnish>>) {
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let header_end = loop {
+            let Ok(read) = stream.read(&mut chunk).await else {
+                return;
+            };
+            if read == 0 {
+                return;
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+            if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+        let head = String::from_utf8_lossy(&buffer[..header_end]).into_owned();
+        let header = |name: &str| {
+            head.lines()
+                .filter_map(|l| l.split_once(':'))
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.trim().to_string())
+        };
+        let content_length = header("content-length")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+        while buffer.len() < header_end + content_length {
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+            }
+        }
+
+        let method = head.split_whitespace().next().unwrap_or("GET").to_string();
+        let path = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+        let token =
+            header("authorization").and_then(|a| a.strip_prefix("Bearer ").map(String::from));
+        let body = serde_json::from_slice(&buffer[header_end..]).unwrap_or(Value::Null);
+        let (status, response) =
+            mock.lock()
+                .unwrap()
+                .handle(&method, &path, token.as_deref(), body);
+
+        let response = response.to_string();
+        let _ = stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 {status} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                )
+                .as_bytes(),
+            )
+            .await;
+    }
+
+    async fn start(mock: MockVnish) -> SocketAddr {
+        start_shared(Arc::new(Mutex::new(mock))).await
+    }
+
+    async fn start_shared(mock: Arc<Mutex<MockVnish>>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, mock.clone()));
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_set_password_rotates_session_credentials() {
+        let addr = start(MockVnish {
+            password: "admin".to_string(),
+            ..Default::default()
+        })
+        .await;
+        let api = VnishWebAPI::new(addr.ip(), addr.port());
+
+        // establish a session with the default password first
+        assert!(
+            api.send_command("info", false, None, Method::GET)
+                .await
+                .is_ok()
+        );
+
+        api.set_password("admin", "hunter22").await.unwrap();
+
+        // the old token was revoked, the client must unlock again with the new password
+        let info = api
+            .send_command("info", false, None, Method::GET)
+            .await
+            .unwrap();
+        assert_eq!(info["miner"], "Antminer S19");
+
+        // the miner no longer accepts the old password
+        assert!(api.authenticate("admin").await.is_err());
+        assert!(api.set_password("admin", "other").await.is_err());
+    }
+
+    fn overclock_settings(preset: &str) -> Value {
+        json!({
+            "miner": {
+                "overclock": {
+                    "preset": preset,
+                    "globals": { "freq": 600, "volt": 13800 },
+                    "chains": [
+                        { "freq": 600, "volt": 13800, "disabled": false },
+                        { "freq": 600, "volt": 13800, "disabled": false },
+                        { "freq": 600, "volt": 13800, "disabled": false }
+                    ]
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_chain_tuning_payload() {
+        let mock = Arc::new(Mutex::new(MockVnish {
+            password: "admin".to_string(),
+            settings: overclock_settings("disabled"),
+            ..Default::default()
+        }));
+        let addr = start_shared(mock.clone()).await;
+        let api = VnishWebAPI::new(addr.ip(), addr.port());
+
+        let settings = api
+            .chain_tuning_settings(1, Some(650), Some(13500))
+            .await
+            .unwrap();
+        api.update_settings(settings).await.unwrap();
+
+        // every chain is sent back, only the requested one is changed
+        let updates = mock.lock().unwrap().updates.clone();
+        assert_eq!(
+            updates,
+            vec![json!({
+                "miner": {
+                    "overclock": {
+                        "chains": [
+                            { "freq": 600, "volt": 13800, "disabled": false },
+                            { "freq": 650, "volt": 13500, "disabled": false },
+                            { "freq": 600, "volt": 13800, "disabled": false }
+                        ]
+                    }
+                }
+            })]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chain_tuning_locked_by_preset() {
+        let mock = Arc::new(Mutex::new(MockVnish {
+            password: "admin".to_string(),
+            settings: overclock_settings("3400"),
+            ..Default::default()
+        }));
+        let addr = start_shared(mock.clone()).await;
+        let api = VnishWebAPI::new(addr.ip(), addr.port());
+
+        let err = api
+            .chain_tuning_settings(0, Some(650), None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<ControlError>(),
+            Some(ControlError::UnsupportedOperation(_))
+        ));
+        assert!(mock.lock().unwrap().updates.is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+//! Descriptions for the error codes reported by WhatsMiner firmware.
+//!
+//! Codes ending in a hashboard slot (`SM0`..`SM2`) share a description, with the slot taken
+//! from the last digit of the code.
+
+use chrono::NaiveDateTime;
+use serde_json::Value;
+
+use crate::data::message::{MessageSeverity, MinerMessage, MinerMessageKind};
+
+/// Inclusive ranges of error codes and their kind, the first match wins.
+const KINDS: [(u64, u64, MinerMessageKind); 8] = [
+    (100, 199, MinerMessageKind::FanFailure),
+    (200, 299, MinerMessageKind::PsuFailure),
+    // the SMx temperature protection and high temperature codes
+    (350, 369, MinerMessageKind::OverTemperature),
+    (300, 349, MinerMessageKind::BoardFailure),
+    (400, 599, MinerMessageKind::BoardFailure),
+    (600, 619, MinerMessageKind::OverTemperature),
+    (700, 799, MinerMessageKind::BoardFailure),
+    (2000, 2999, MinerMessageKind::PoolIssue),
+];
+
+fn board_description(code: u64) -> Option<String> {
+    let slot = code % 10;
+    if slot > 2 {
+        return None;
+    }
+    let description = match code - slot {
+        300 => "temperature sensor detection error",
+        310 => "temperature sensor error",
+        320 => "temperature reading error",
+        350 => "temperature protecting",
+        410 => "eeprom detection error",
+        420 => "eeprom parsing error",
+        430 => "eeprom chip number inconsistent",
+        450 => "eeprom xfer error",
+        510 => "miner type error",
+        520 => "miner serial number error",
+        530 => "was not found",
+        540 => "reading chip id error",
+        550 => "is abnormal",
+        560 => "found fewer chips than expected",
+        600 => "environment temperature is too high",
+        610 => "high temperature protection",
+        710 => "control board communication error",
+        _ => return None,
+    };
+    Some(format!("SM{slot} {description}"))
+}
+
+/// Look up the documented description for a WhatsMiner error code.
+pub(crate) fn describe(code: u64) -> Option<String> {
+    let description = match code {
+        110 => "Intake fan speed error",
+        111 => "Exhaust fan speed error",
+        120 => "Intake fan speed error, deviation above 2000 RPM",
+        121 => "Exhaust fan speed error, deviation above 2000 RPM",
+        130 => "Intake fan speed error, deviation above 3000 RPM",
+        131 => "Exhaust fan speed error, deviation above 3000 RPM",
+        140 => "Fan speed too high",
+        200 => "Power supply probing error, no power supply found",
+        201 => "Power supply and configuration file do not match",
+        202 => "Power supply output voltage error",
+        203 => "Power supply protecting due to high environment temperature",
+        204 => "Power supply current protecting due to high environment temperature",
+        205 => "Power supply current error",
+        206 => "Power supply input low voltage error",
+        207 => "Power supply input current protecting due to bad power input",
+        210 => "Power supply error",
+        213 => "Power supply input voltage and current do not match output",
+        216 => "Power supply remained unchanged for a long time",
+        217 => "Power supply set enable error",
+        218 => "Power supply input voltage is too low for high power mode",
+        233 => "Power supply output high temperature protection",
+        329 => "Control board temperature sensor communication error",
+        360 => "Hashboard high temperature error",
+        2010 => "All pools are disabled",
+        2020 => "Pool 0 connection failed",
+        2021 => "Pool 1 connection failed",
+        2022 => "Pool 2 connection failed",
+        2030 => "High rejection rate on pool",
+        2040 => "Pool does not support asicboost mode",
+        _ => return board_description(code),
+    };
+    Some(description.to_string())
+}
+
+/// Infer the severity of a WhatsMiner error code from the range it falls in.
+pub(crate) fn severity(code: u64) -> MessageSeverity {
+    match code {
+        // fans, eeprom, environment temperature and pool codes degrade but don't stop mining
+        100..=199 | 400..=499 | 600..=699 | 2000..=2999 => MessageSeverity::Warning,
+        _ => MessageSeverity::Error,
+    }
+}
+
+/// What a WhatsMiner error code is about, from the range it falls in.
+pub(crate) fn kind(code: u64) -> MinerMessageKind {
+    KINDS
+        .iter()
+        .find(|(first, last, _)| (*first..=*last).contains(&code))
+        .map(|(_, _, kind)| *kind)
+        .unwrap_or_default()
+}
+
+/// Build a message for a WhatsMiner error code, falling back to the bare code when undocumented.
+pub(crate) fn message(timestamp: u32, code: u64) -> MinerMessage {
+    let text = describe(code).unwrap_or_else(|| format!("Unknown error code {code}"));
+    MinerMessage::new(timestamp, code, text, severity(code)).with_kind(kind(code))
+}
+
+/// Parse the error code entries returned by btminer into messages.
+///
+/// Depending on firmware these are bare codes, or `{"<code>": "<time>"}` objects with the time
+/// the error was raised.
+pub(crate) fn messages_from_value(value: &Value) -> Vec<MinerMessage> {
+    let entries = match value {
+        Value::Array(entries) => entries.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    let mut messages = Vec::new();
+    for entry in entries {
+        match entry {
+            Value::Object(obj) => {
+                for (code, time) in obj {
+                    if let Ok(code) = code.trim().parse::<u64>() {
+                        let timestamp = time.as_str().map(parse_timestamp).unwrap_or(0);
+                        messages.push(message(timestamp, code));
+                    }
+                }
+            }
+            Value::String(code) => {
+                if let Ok(code) = code.trim().parse::<u64>() {
+                    messages.push(message(0, code));
+                }
+            }
+            other => {
+                if let Some(code) = other.as_u64() {
+                    messages.push(message(0, code));
+                }
+            }
+        }
+    }
+    messages
+}
+
+fn parse_timestamp(time: &str) -> u32 {
+    NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S")
+        .map(|t| t.and_utc().timestamp() as u32)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_known_and_board_codes() {
+        assert_eq!(describe(110).as_deref(), Some("Intake fan speed error"));
+        assert_eq!(describe(531).as_deref(), Some("SM1 was not found"));
+        assert_eq!(severity(110), MessageSeverity::Warning);
+        assert_eq!(severity(205), MessageSeverity::Error);
+    }
+
+    #[test]
+    fn test_kinds() {
+        assert_eq!(kind(111), MinerMessageKind::FanFailure);
+        assert_eq!(kind(205), MinerMessageKind::PsuFailure);
+        assert_eq!(kind(351), MinerMessageKind::OverTemperature);
+        assert_eq!(kind(360), MinerMessageKind::OverTemperature);
+        assert_eq!(kind(311), MinerMessageKind::BoardFailure);
+        assert_eq!(kind(531), MinerMessageKind::BoardFailure);
+        assert_eq!(kind(600), MinerMessageKind::OverTemperature);
+        assert_eq!(kind(2021), MinerMessageKind::PoolIssue);
+        assert_eq!(kind(9999), MinerMessageKind::Other);
+
+        let msg = message(0, 531);
+        assert_eq!(msg.kind, MinerMessageKind::BoardFailure);
+        assert_eq!(msg.code, 531);
+        assert_eq!(msg.message, "SM1 was not found");
+    }
+
+    #[test]
+    fn test_unknown_code() {
+        let msg = message(0, 9999);
+        assert_eq!(msg.message, "Unknown error code 9999");
+        assert_eq!(msg.severity, MessageSeverity::Error);
+    }
+
+    #[test]
+    fn test_messages_from_value() {
+        let messages = messages_from_value(&json!([110, "205", {"531": "2025-08-19 10:00:00"}]));
+        let codes: Vec<u64> = messages.iter().map(|m| m.code).collect();
+
+        assert_eq!(codes, vec![110, 205, 531]);
+        assert_eq!(messages[0].timestamp, 0);
+        assert_eq!(messages[2].timestamp, 1755597600);
+    }
+}
@@ -1,4 +1,7 @@
+use anyhow::Result;
+use measurements::Temperature;
 use semver;
+use serde_json::Value;
 use std::net::IpAddr;
 
 pub use v1::WhatsMinerV1;
@@ -6,8 +9,16 @@ pub use v2::WhatsMinerV2;
 pub use v3::WhatsMinerV3;
 
 use crate::data::device::MinerModel;
+use crate::data::hashrate::{HashRate, HashRateUnit};
+use crate::data::message::{MessageSeverity, MinerMessage};
+use crate::data::miner::{MinerStatus, WorkMode};
+use crate::data::psu::PsuRail;
+use crate::miners::backends::errors::ControlError;
 use crate::miners::backends::traits::*;
+use crate::miners::commands::MinerCommand;
+use crate::miners::data::{DataExtensions, DataField, FieldData, FromValue};
 
+mod error_codes;
 pub mod v1;
 pub mod v2;
 pub mod v3;
@@ -36,3 +47,319 @@ impl MinerConstructor for WhatsMiner {
         }
     }
 }
+
+/// Skips the commands that only answer while btminer is running, once `status` says it is
+/// powered off. `status` and `get_version` keep answering either way.
+fn skip_while_btminer_off(status: &Value, command: &MinerCommand) -> bool {
+    let off = miner_off(status) == Some(true);
+    off && matches!(
+        command,
+        MinerCommand::RPC {
+            command: "summary" | "devs" | "pools",
+            ..
+        }
+    )
+}
+
+/// Where btminer v1 and v2 builds report that mining is stopped, first found wins: `btmineroff`
+/// in the `status` message, `mineroff` on builds that renamed it, and `btmineroff` in the summary
+/// on builds that moved it there.
+const MINER_OFF_POINTERS: [&str; 3] = ["/Msg/btmineroff", "/Msg/mineroff", "/SUMMARY/0/btmineroff"];
+
+/// Whether a `status` or `summary` response says mining is stopped, sent as `"true"`/`"false"`
+/// or as a bool depending on the build. `None` if it has none of the known keys.
+fn miner_off(response: &Value) -> Option<bool> {
+    MINER_OFF_POINTERS
+        .iter()
+        .filter_map(|pointer| response.pointer(pointer))
+        .find_map(|off| match off {
+            Value::Bool(off) => Some(*off),
+            Value::String(off) => off.trim().to_lowercase().parse().ok(),
+            _ => None,
+        })
+}
+
+/// The status of btminer v1 and v2, from the `status` and `summary` responses collected under
+/// the `status` and `summary` tags.
+///
+/// A stop flag from either response wins, then the summary hashrate. A `status` that answered
+/// without a stop flag and no hashrate to go by is taken as not mining, while a `status` that
+/// failed as well leaves the status unknown.
+fn btminer_status(data: &FieldData) -> MinerStatus {
+    let status = data.get(&DataField::IsMining).and_then(|v| v.get("status"));
+    let summary = data
+        .get(&DataField::IsMining)
+        .and_then(|v| v.get("summary"));
+    let off = status
+        .and_then(miner_off)
+        .or_else(|| summary.and_then(miner_off));
+    let hashrate = summary
+        .and_then(|summary| summary.pointer("/SUMMARY/0/HS RT"))
+        .and_then(f64::from_value);
+
+    match (off, hashrate) {
+        (Some(true), _) => MinerStatus::Paused,
+        (_, Some(hr)) if hr > 0.0 => MinerStatus::Mining,
+        (_, Some(_)) => MinerStatus::Failure,
+        (Some(false), None) => MinerStatus::Mining,
+        (None, None) if status.is_some() => MinerStatus::Failure,
+        (None, None) => MinerStatus::Unknown,
+    }
+}
+
+/// The input side of the PSU from `get_psu` on btminer v1 and v2, which reports `vin` and `iin`
+/// in hundredths of a volt and an amp. PSUs without the readings leave them out.
+fn btminer_psu_input(data: &FieldData) -> Option<PsuRail> {
+    let hundredths = |key| {
+        data.extract_nested::<f64>(DataField::PsuRails, key)
+            .map(|value| value / 100.0)
+    };
+    PsuRail::input(hundredths("vin"), hundredths("iin"), None)
+}
+
+/// btminer only accepts passwords of up to 8 letters, numbers or underscores.
+fn validate_password(password: &str) -> Result<(), ControlError> {
+    if password.is_empty() || password.len() > 8 {
+        return Err(ControlError::InvalidParameter(
+            "WhatsMiner passwords must be between 1 and 8 characters".to_string(),
+        ));
+    }
+    if !password
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(ControlError::InvalidParameter(
+            "WhatsMiner passwords may only contain letters, numbers and underscores".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The realtime hashrate from a btminer summary.
+///
+/// `HS RT` is in MH/s on most firmware but in TH/s on some, so its unit is whichever lands closer
+/// to `Factory GHS`, or to `MHS 5s` (always MH/s) when the factory hashrate is missing. `MHS 5s`
+/// is used if `HS RT` is missing altogether.
+fn summary_hashrate(
+    hs_rt: Option<f64>,
+    mhs_5s: Option<f64>,
+    factory_ghs: Option<f64>,
+) -> Option<HashRate> {
+    let from_megahash = |value| {
+        HashRate {
+            value,
+            unit: HashRateUnit::MegaHash,
+            algo: String::from("SHA256"),
+        }
+        .as_unit(HashRateUnit::TeraHash)
+    };
+
+    let Some(hs_rt) = hs_rt else {
+        return mhs_5s.map(from_megahash);
+    };
+    let reference = factory_ghs
+        .map(|ghs| ghs / 1000.0)
+        .or(mhs_5s.map(|mhs| mhs / 1_000_000.0))
+        .filter(|th| *th > 0.0);
+    let in_terahash = match reference {
+        // compare on a log scale, the two readings are a factor of a million apart
+        Some(th) if hs_rt > 0.0 => (hs_rt / th).ln().abs() < (hs_rt / 1_000_000.0 / th).ln().abs(),
+        // no btminer hashes less than 10 TH/s, which is 10,000,000 MH/s
+        _ => hs_rt < 10_000.0,
+    };
+    match in_terahash {
+        true => Some(HashRate {
+            value: hs_rt,
+            unit: HashRateUnit::TeraHash,
+            algo: String::from("SHA256"),
+        }),
+        false => Some(from_megahash(hs_rt)),
+    }
+}
+
+/// The locate light state from a btminer `ledstat`/`ledstatus`.
+///
+/// `auto` is normal operation, where the LEDs only show the miner's status, a manually set
+/// blink pattern is reported as `manual` or `on`.
+fn led_flashing(status: &str) -> Option<bool> {
+    match status.to_lowercase().as_str() {
+        "auto" | "off" => Some(false),
+        "on" | "manual" => Some(true),
+        _ => None,
+    }
+}
+
+/// The btminer name of a power mode, used in both the v2 and v3 commands that set it.
+///
+/// btminer has no sleep mode, powering the hashboards down is done with [`Pause`].
+fn power_mode_name(mode: WorkMode) -> Result<&'static str, ControlError> {
+    match mode {
+        WorkMode::LowPower => Ok("low"),
+        WorkMode::Normal => Ok("normal"),
+        WorkMode::HighPerformance => Ok("high"),
+        WorkMode::Sleep => Err(ControlError::InvalidParameter(
+            "WhatsMiner has no sleep power mode".to_string(),
+        )),
+    }
+}
+
+/// The power mode reported by btminer, `Low`, `Normal` or `High` on v2 and lowercase on v3.
+fn parse_power_mode(mode: &str) -> Option<WorkMode> {
+    match mode.to_lowercase().as_str() {
+        "low" => Some(WorkMode::LowPower),
+        "normal" => Some(WorkMode::Normal),
+        "high" => Some(WorkMode::HighPerformance),
+        _ => None,
+    }
+}
+
+/// Env temperatures at or below this are a sensor fault, btminer reports -273 for a missing sensor.
+const SENSOR_FAULT_CELSIUS: f64 = -100.0;
+
+/// The intake temperature of a btminer summary, from the `env` and `chip_min` readings that
+/// both [`DataField::FluidTemperature`] and [`DataField::Messages`] collect.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EnvTemperature {
+    /// Read from the env sensor, negative on cold deployments
+    Measured(f64),
+    /// The env sensor reported a fault
+    Fault(f64),
+    /// The summary has no env temperature, this is the lowest chip temperature instead
+    Derived(f64),
+}
+
+impl EnvTemperature {
+    fn from_data(data: &FieldData, field: DataField) -> Option<Self> {
+        let env = data.extract_nested::<f64>(field, "env");
+        let chip_min = data.extract_nested::<f64>(field, "chip_min");
+        match env {
+            Some(temp) if temp <= SENSOR_FAULT_CELSIUS => Some(Self::Fault(temp)),
+            Some(temp) => Some(Self::Measured(temp)),
+            None => chip_min
+                .filter(|temp| *temp > SENSOR_FAULT_CELSIUS)
+                .map(Self::Derived),
+        }
+    }
+
+    fn temperature(self) -> Option<Temperature> {
+        match self {
+            Self::Measured(temp) | Self::Derived(temp) => Some(Temperature::from_celsius(temp)),
+            Self::Fault(_) => None,
+        }
+    }
+
+    fn message(self) -> Option<MinerMessage> {
+        match self {
+            Self::Measured(_) => None,
+            Self::Fault(temp) => Some(MinerMessage::new(
+                0,
+                0,
+                format!("Env temperature sensor fault, reported {temp}°C"),
+                MessageSeverity::Warning,
+            )),
+            Self::Derived(temp) => Some(MinerMessage::new(
+                0,
+                0,
+                format!("No env temperature, using the lowest chip temperature of {temp}°C"),
+                MessageSeverity::Info,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_password() {
+        assert!(validate_password("admin_1").is_ok());
+        assert!(validate_password("").is_err());
+        assert!(validate_password("longer_than_8").is_err());
+        assert!(validate_password("pa$$").is_err());
+    }
+
+    #[test]
+    fn test_power_mode() {
+        for mode in [
+            WorkMode::LowPower,
+            WorkMode::Normal,
+            WorkMode::HighPerformance,
+        ] {
+            let name = power_mode_name(mode).unwrap();
+            assert_eq!(parse_power_mode(name), Some(mode));
+        }
+        assert_eq!(parse_power_mode("High"), Some(WorkMode::HighPerformance));
+        assert_eq!(parse_power_mode("turbo"), None);
+        assert!(matches!(
+            power_mode_name(WorkMode::Sleep),
+            Err(ControlError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_summary_hashrate_units() {
+        let th = |hr: Option<HashRate>| hr.map(|hr| hr.value.round());
+
+        assert_eq!(
+            th(summary_hashrate(
+                Some(67394800.97),
+                Some(70668114.52),
+                Some(68796.0)
+            )),
+            Some(67.0)
+        );
+        assert_eq!(
+            th(summary_hashrate(
+                Some(67.39),
+                Some(70668114.52),
+                Some(68796.0)
+            )),
+            Some(67.0)
+        );
+        assert_eq!(
+            th(summary_hashrate(Some(67.39), Some(70668114.52), None)),
+            Some(67.0)
+        );
+        assert_eq!(th(summary_hashrate(Some(86.2), None, None)), Some(86.0));
+        assert_eq!(
+            th(summary_hashrate(Some(86200000.0), None, None)),
+            Some(86.0)
+        );
+        assert_eq!(
+            th(summary_hashrate(None, Some(70668114.52), Some(68796.0))),
+            Some(71.0)
+        );
+        assert_eq!(
+            th(summary_hashrate(Some(0.0), None, Some(68796.0))),
+            Some(0.0)
+        );
+        assert_eq!(summary_hashrate(None, None, Some(68796.0)), None);
+    }
+
+    #[test]
+    fn test_env_temperature() {
+        let env = |value| {
+            EnvTemperature::from_data(
+                &FieldData::from([(DataField::FluidTemperature, value)]),
+                DataField::FluidTemperature,
+            )
+        };
+
+        assert_eq!(
+            env(serde_json::json!({ "env": "-12.50", "chip_min": 41.0 })),
+            Some(EnvTemperature::Measured(-12.5))
+        );
+        assert_eq!(
+            env(serde_json::json!({ "env": -273, "chip_min": 41.0 })),
+            Some(EnvTemperature::Fault(-273.0))
+        );
+        assert_eq!(
+            env(serde_json::json!({ "chip_min": 41.0 })),
+            Some(EnvTemperature::Derived(41.0))
+        );
+        assert_eq!(env(serde_json::json!({ "chip_min": -273.0 })), None);
+        assert_eq!(EnvTemperature::Fault(-273.0).temperature(), None);
+        assert_eq!(EnvTemperature::Measured(-12.5).message(), None);
+    }
+}
@@ -1,7 +1,7 @@
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use macaddr::MacAddr;
-use measurements::{AngularVelocity, Frequency, Power, Temperature};
+use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::net::IpAddr;
@@ -9,16 +9,30 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use crate::data::board::BoardData;
-use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerModel};
-use crate::data::device::{MinerControlBoard, MinerMake};
+use crate::data::device::{ControlBoardType, MinerControlBoard, MinerMake};
+use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerModel};
 use crate::data::fan::FanData;
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::message::{MessageSeverity, MinerMessage};
-use crate::data::pool::{PoolData, PoolURL};
+use crate::data::health::HealthThresholds;
+use crate::data::message::MinerMessage;
+use crate::data::miner::MinerStatus;
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::psu::PsuRail;
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::RPCReadLimits;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
+use crate::miners::backends::whatsminer::{
+    EnvTemperature, btminer_psu_input, btminer_status, error_codes, skip_while_btminer_off,
+};
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_pointer,
+    CommandGate, DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData,
+    FromValue, get_by_pointer,
 };
 
 use rpc::WhatsMinerRPCAPI;
@@ -30,6 +44,10 @@ pub struct WhatsMinerV1 {
     pub ip: IpAddr,
     pub rpc: WhatsMinerRPCAPI,
     pub device_info: DeviceInfo,
+    pub labels: Option<HashMap<String, String>>,
+    pub health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
 }
 
 impl WhatsMinerV1 {
@@ -43,8 +61,19 @@ impl WhatsMinerV1 {
                 MinerFirmware::Stock,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
         }
     }
+
+    /// Limit the connections the API client opens at once, the firmware answers one at a time
+    /// so commands are sent one after another by default.
+    pub fn with_connection_limit(mut self, limit: ConnectionLimit) -> Self {
+        self.rpc.connections = limit;
+        self
+    }
 }
 
 #[async_trait]
@@ -141,6 +170,14 @@ impl GetDataLocations for WhatsMinerV1 {
                     tag: None,
                 },
             )],
+            DataField::PsuRails => vec![(
+                get_psu_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/Msg"),
+                    tag: None,
+                },
+            )],
             DataField::Hashboards => vec![(
                 devs_cmd,
                 DataExtractor {
@@ -173,11 +210,37 @@ impl GetDataLocations for WhatsMinerV1 {
                     tag: None,
                 },
             )],
-            DataField::Hashrate => vec![(
+            DataField::Hashrate => vec![
+                (
+                    summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/HS RT"),
+                        tag: Some("HS RT"),
+                    },
+                ),
+                (
+                    summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/MHS 5s"),
+                        tag: Some("MHS 5s"),
+                    },
+                ),
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Factory GHS"),
+                        tag: Some("Factory GHS"),
+                    },
+                ),
+            ],
+            DataField::AverageHashrate => vec![(
                 summary_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/SUMMARY/0/HS RT"),
+                    key: Some("/SUMMARY/0/MHS 5m"),
                     tag: None,
                 },
             )],
@@ -189,33 +252,83 @@ impl GetDataLocations for WhatsMinerV1 {
                     tag: None,
                 },
             )],
-            DataField::FluidTemperature => vec![(
-                summary_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/SUMMARY/0/Env Temp"),
-                    tag: None,
-                },
-            )],
-            DataField::IsMining => vec![(
-                status_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/SUMMARY/0/btmineroff"),
-                    tag: None,
-                },
-            )],
-            DataField::Messages => vec![(
-                summary_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/SUMMARY/0"),
-                    tag: None,
-                },
-            )],
+            DataField::FluidTemperature => vec![
+                (
+                    summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Env Temp"),
+                        tag: Some("env"),
+                    },
+                ),
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Chip Temp Min"),
+                        tag: Some("chip_min"),
+                    },
+                ),
+            ],
+            // the whole responses, builds differ in where they report mining stopped
+            DataField::IsMining => vec![
+                (
+                    status_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some(""),
+                        tag: Some("status"),
+                    },
+                ),
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some(""),
+                        tag: Some("summary"),
+                    },
+                ),
+            ],
+            // the env temperature fault or its stand-in is reported as a message too
+            DataField::Messages => vec![
+                (
+                    summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0"),
+                        tag: None,
+                    },
+                ),
+                (
+                    summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Env Temp"),
+                        tag: Some("env"),
+                    },
+                ),
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Chip Temp Min"),
+                        tag: Some("chip_min"),
+                    },
+                ),
+            ],
             _ => vec![],
         }
     }
+
+    fn command_gate(&self) -> Option<CommandGate> {
+        Some(CommandGate {
+            probe: MinerCommand::RPC {
+                command: "status",
+                parameters: None,
+            },
+            skip: skip_while_btminer_off,
+        })
+    }
 }
 
 impl GetIP for WhatsMinerV1 {
@@ -225,7 +338,65 @@ impl GetIP for WhatsMinerV1 {
 }
 impl GetDeviceInfo for WhatsMinerV1 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for WhatsMinerV1 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for WhatsMinerV1 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for WhatsMinerV1 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for WhatsMinerV1 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for WhatsMinerV1 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.rpc.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.rpc.policy = policy;
+    }
+}
+
+impl GetCapabilities for WhatsMinerV1 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            has_psu_telemetry: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -236,38 +407,42 @@ impl CollectData for WhatsMinerV1 {
 }
 
 impl GetMAC for WhatsMinerV1 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
         data.extract::<String>(DataField::Mac)
-            .and_then(|s| MacAddr::from_str(&s).ok())
+            .and_then(|s| parse_mac_str(&s))
     }
 }
 
+impl GetReportedIp for WhatsMinerV1 {}
+
 impl GetSerialNumber for WhatsMinerV1 {}
 impl GetHostname for WhatsMinerV1 {}
 impl GetApiVersion for WhatsMinerV1 {
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::ApiVersion)
             .and_then(|s| Some(s.strip_prefix("whatsminer v")?.to_string()))
     }
 }
 impl GetFirmwareVersion for WhatsMinerV1 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
 }
 impl GetControlBoardVersion for WhatsMinerV1 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
         data.extract::<String>(DataField::ControlBoardVersion)
             .and_then(|s| {
                 MinerControlBoard::from_str(s.to_uppercase().strip_prefix("ALLWINNER_")?).ok()
             })
     }
+
+    fn parse_control_board_type(&self, data: &FieldData) -> Option<ControlBoardType> {
+        data.extract::<String>(DataField::ControlBoardVersion)
+            .and_then(|s| s.parse().ok())
+    }
 }
 impl GetHashboards for WhatsMinerV1 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         let mut hashboards: Vec<BoardData> = Vec::new();
         let board_count = self.device_info.hardware.boards.unwrap_or(3);
         let hashboard_data = data.get(&DataField::Hashboards);
@@ -332,33 +507,46 @@ impl GetHashboards for WhatsMinerV1 {
                 working_chips,
                 serial_number,
                 chips: vec![],
+                sensor_temperatures: vec![],
                 voltage: None, // TODO
                 frequency,
+                wattage: None,
                 tuned: Some(true),
                 active,
+                status: None,
+                extra: None,
             });
         }
         hashboards
     }
 }
 impl GetHashrate for WhatsMinerV1 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
-        data.extract_map::<f64, _>(DataField::Hashrate, |f| {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        super::summary_hashrate(
+            data.extract_nested(DataField::Hashrate, "HS RT"),
+            data.extract_nested(DataField::Hashrate, "MHS 5s"),
+            data.extract_nested(DataField::Hashrate, "Factory GHS"),
+        )
+    }
+}
+impl GetExpectedHashrate for WhatsMinerV1 {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| {
             HashRate {
                 value: f,
-                unit: HashRateUnit::MegaHash,
+                unit: HashRateUnit::GigaHash,
                 algo: String::from("SHA256"),
             }
             .as_unit(HashRateUnit::TeraHash)
         })
     }
 }
-impl GetExpectedHashrate for WhatsMinerV1 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
-        data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| {
+impl GetAverageHashrate for WhatsMinerV1 {
+    fn parse_average_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::AverageHashrate, |f| {
             HashRate {
                 value: f,
-                unit: HashRateUnit::GigaHash,
+                unit: HashRateUnit::MegaHash,
                 algo: String::from("SHA256"),
             }
             .as_unit(HashRateUnit::TeraHash)
@@ -366,7 +554,7 @@ impl GetExpectedHashrate for WhatsMinerV1 {
     }
 }
 impl GetFans for WhatsMinerV1 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut fans: Vec<FanData> = Vec::new();
         for (idx, direction) in ["In", "Out"].iter().enumerate() {
             let fan = data.extract_nested_map::<f64, _>(
@@ -385,37 +573,61 @@ impl GetFans for WhatsMinerV1 {
     }
 }
 impl GetPsuFans for WhatsMinerV1 {
-    fn parse_psu_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_psu_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut psu_fans: Vec<FanData> = Vec::new();
 
-        let psu_fan = data.extract_map::<String, _>(DataField::PsuFans, |rpm| FanData {
-            position: 0i16,
-            rpm: Some(AngularVelocity::from_rpm(rpm.parse().unwrap())),
-        });
+        let psu_fan = data
+            .extract::<String>(DataField::PsuFans)
+            .and_then(|rpm| rpm.parse().ok())
+            .map(|rpm| FanData {
+                position: 0i16,
+                rpm: Some(AngularVelocity::from_rpm(rpm)),
+            });
         if let Some(f) = psu_fan {
             psu_fans.push(f)
         }
         psu_fans
     }
 }
+
+impl GetFanControl for WhatsMinerV1 {}
+
+impl GetPsu for WhatsMinerV1 {}
+
+impl GetPsuRails for WhatsMinerV1 {
+    fn parse_psu_rails(&self, data: &FieldData) -> Vec<PsuRail> {
+        btminer_psu_input(data).into_iter().collect()
+    }
+}
+
 impl GetFluidTemperature for WhatsMinerV1 {
-    fn parse_fluid_temperature(&self, data: &HashMap<DataField, Value>) -> Option<Temperature> {
-        data.extract_map::<f64, _>(DataField::FluidTemperature, Temperature::from_celsius)
+    fn parse_fluid_temperature(&self, data: &FieldData) -> Option<Temperature> {
+        EnvTemperature::from_data(data, DataField::FluidTemperature)
+            .and_then(EnvTemperature::temperature)
     }
 }
+
+impl GetFluidFlow for WhatsMinerV1 {}
+
+impl GetFluidPressure for WhatsMinerV1 {}
+
 impl GetWattage for WhatsMinerV1 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<f64, _>(DataField::Wattage, Power::from_watts)
     }
 }
 impl GetWattageLimit for WhatsMinerV1 {
-    fn parse_wattage_limit(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage_limit(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<f64, _>(DataField::WattageLimit, Power::from_watts)
     }
 }
+impl GetFrequency for WhatsMinerV1 {}
+impl GetVoltage for WhatsMinerV1 {}
+impl GetWorkMode for WhatsMinerV1 {}
+impl GetAutotune for WhatsMinerV1 {}
 impl GetLightFlashing for WhatsMinerV1 {}
 impl GetMessages for WhatsMinerV1 {
-    fn parse_messages(&self, data: &HashMap<DataField, Value>) -> Vec<MinerMessage> {
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
         let mut messages = Vec::new();
 
         let error_count = data
@@ -431,31 +643,35 @@ impl GetMessages for WhatsMinerV1 {
                 .and_then(|val| val.pointer(&format!("/Error Code {}", idx)))
                 .and_then(|val| val.as_u64());
             if let Some(code) = e_code {
-                messages.push(MinerMessage::new(
-                    0,
-                    code,
-                    "".to_string(), // TODO: parse message from mapping
-                    MessageSeverity::Error,
-                ));
+                messages.push(error_codes::message(0, code));
             }
         }
+        messages.extend(
+            EnvTemperature::from_data(data, DataField::Messages).and_then(EnvTemperature::message),
+        );
 
         messages
     }
 }
 impl GetUptime for WhatsMinerV1 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
     }
 }
 impl GetIsMining for WhatsMinerV1 {
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
-        data.extract_map::<String, _>(DataField::IsMining, |l| l != "false")
-            .unwrap_or(true)
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
+    }
+}
+
+impl GetStatus for WhatsMinerV1 {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        btminer_status(data)
     }
 }
+
 impl GetPools for WhatsMinerV1 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
         let mut pools: Vec<PoolData> = Vec::new();
         let pools_raw = data.get(&DataField::Pools);
         if let Some(pools_response) = pools_raw {
@@ -480,7 +696,7 @@ impl GetPools for WhatsMinerV1 {
 
                 let url = pools_raw
                     .and_then(|val| val.pointer(&format!("/{}/URL", idx)))
-                    .map(|val| PoolURL::from(String::from(val.as_str().unwrap_or(""))));
+                    .and_then(|val| PoolURL::parse(val.as_str().unwrap_or("")));
 
                 let accepted_shares = pools_raw
                     .and_then(|val| val.pointer(&format!("/{}/Accepted", idx)))
@@ -490,6 +706,16 @@ impl GetPools for WhatsMinerV1 {
                     .and_then(|val| val.pointer(&format!("/{}/Rejected", idx)))
                     .and_then(|val| val.as_u64());
 
+                let last_share_difficulty = pools_raw
+                    .and_then(|val| val.pointer(&format!("/{}/Last Share Difficulty", idx)))
+                    .and_then(|val| val.as_f64());
+
+                // 0 until the first share
+                let last_share_time = pools_raw
+                    .and_then(|val| val.pointer(&format!("/{}/Last Share Time", idx)))
+                    .and_then(u64::from_value)
+                    .filter(|t| *t > 0);
+
                 pools.push(PoolData {
                     position: Some(idx as u16),
                     url,
@@ -498,6 +724,11 @@ impl GetPools for WhatsMinerV1 {
                     active,
                     alive,
                     user,
+                    password: None,
+                    last_share_difficulty,
+                    last_share_time,
+                    group: None,
+                    quota: None,
                 });
             }
         }
@@ -509,7 +740,9 @@ impl GetPools for WhatsMinerV1 {
 impl SetFaultLight for WhatsMinerV1 {
     #[allow(unused_variables)]
     async fn set_fault_light(&self, fault: bool) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting the fault light".to_string(),
+        ))?
     }
 }
 
@@ -517,14 +750,73 @@ impl SetFaultLight for WhatsMinerV1 {
 impl SetPowerLimit for WhatsMinerV1 {
     #[allow(unused_variables)]
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation(
+            "setting a power limit".to_string(),
+        ))?
+    }
+}
+
+impl SetCredentials for WhatsMinerV1 {}
+
+impl EndpointControl for WhatsMinerV1 {}
+
+#[async_trait]
+impl SetPassword for WhatsMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the password".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetHostname for WhatsMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the hostname".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPools for WhatsMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "setting pools".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for WhatsMinerV1 {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
     }
 }
 
 #[async_trait]
 impl Restart for WhatsMinerV1 {
-    async fn restart(&self) -> Result<bool> {
-        bail!("Unsupported command");
+    async fn restart_mining(&self) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "restarting the mining process".to_string(),
+        ))?
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "rebooting the system".to_string(),
+        ))?
     }
 }
 
@@ -532,7 +824,7 @@ impl Restart for WhatsMinerV1 {
 impl Pause for WhatsMinerV1 {
     #[allow(unused_variables)]
     async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation("pausing".to_string()))?
     }
 }
 
@@ -540,7 +832,13 @@ impl Pause for WhatsMinerV1 {
 impl Resume for WhatsMinerV1 {
     #[allow(unused_variables)]
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
-        bail!("Unsupported command");
+        Err(ControlError::UnsupportedOperation("resuming".to_string()))?
+    }
+}
+
+impl SetRPCReadLimits for WhatsMinerV1 {
+    fn set_rpc_read_limits(&mut self, limits: RPCReadLimits) {
+        self.rpc.set_read_limits(limits);
     }
 }
 
@@ -548,11 +846,17 @@ impl Resume for WhatsMinerV1 {
 mod tests {
     use super::*;
     use crate::data::device::models::whatsminer::WhatsMinerModel;
-    use crate::test::api::MockAPIClient;
+    use crate::data::message::MessageSeverity;
+    use crate::data::miner::MinerData;
+    use crate::test::api::{MockAPIClient, MockResponse};
+    use crate::test::completeness::assert_fields_extracted;
+    use crate::test::invariants::assert_hashrate_magnitudes;
     use crate::test::json::btminer::v1::{
         DEVS_COMMAND, GET_PSU_COMMAND, GET_VERSION_COMMAND, POOLS_COMMAND, STATUS_COMMAND,
-        SUMMARY_COMMAND,
+        STATUS_MINING_COMMAND, SUMMARY_20200917_COMMAND, SUMMARY_COMMAND,
+        SUMMARY_ENV_FAULT_COMMAND, SUMMARY_ENV_NEGATIVE_COMMAND,
     };
+    use serde_json::json;
 
     #[tokio::test]
     async fn test_whatsminer_v1_data_parsers() -> Result<()> {
@@ -587,18 +891,27 @@ mod tests {
         };
 
         results.insert(summary_command, Value::from_str(SUMMARY_COMMAND)?);
-        results.insert(status_command, Value::from_str(STATUS_COMMAND)?);
+        results.insert(status_command, Value::from_str(STATUS_MINING_COMMAND)?);
         results.insert(pools_command, Value::from_str(POOLS_COMMAND)?);
         results.insert(devs_command, Value::from_str(DEVS_COMMAND)?);
         results.insert(get_version_command, Value::from_str(GET_VERSION_COMMAND)?);
         results.insert(get_psu_command, Value::from_str(GET_PSU_COMMAND)?);
 
+        // this PSU firmware doesn't report its fan speed
+        assert_fields_extracted(
+            &miner,
+            results.clone(),
+            &[DataField::PsuFans, DataField::PsuRails],
+        )
+        .await;
+
         let mock_api = MockAPIClient::new(results);
 
         let mut collector = DataCollector::new_with_client(&miner, &mock_api);
         let data = collector.collect_all().await;
 
         let miner_data = miner.parse_data(data);
+        assert_hashrate_magnitudes(&miner_data);
 
         assert_eq!(&miner_data.ip, &miner.ip);
         assert_eq!(
@@ -614,6 +927,10 @@ mod tests {
             miner_data.control_board_version,
             Some(MinerControlBoard::H3)
         );
+        assert_eq!(
+            miner_data.control_board_type,
+            Some(ControlBoardType::Allwinner)
+        );
         assert_eq!(
             miner_data.hashrate,
             Some(HashRate {
@@ -635,7 +952,302 @@ mod tests {
         assert_eq!(miner_data.uptime, Some(Duration::from_secs(10154)));
         assert_eq!(miner_data.fans.len(), 2);
         assert_eq!(miner_data.pools.len(), 3);
+        assert_eq!(miner_data.status, MinerStatus::Mining);
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_whatsminer_v1_powered_off() -> Result<()> {
+        let miner = WhatsMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+        );
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        // summary, devs and pools are never answered while btminer is off
+        let mock_api = MockAPIClient::new(HashMap::from([
+            (rpc("status"), Value::from_str(STATUS_COMMAND)?),
+            (rpc("get_version"), Value::from_str(GET_VERSION_COMMAND)?),
+            (rpc("get_psu"), Value::from_str(GET_PSU_COMMAND)?),
+        ]));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+
+        let stats = collector.stats();
+        for command in ["summary", "devs", "pools"] {
+            assert!(stats.gated.contains(&rpc(command)), "{command}");
+            mock_api.assert_not_called(command);
+        }
+        assert_eq!(mock_api.calls()[0], rpc("status"));
+        mock_api.assert_called_once("status");
+
+        assert_eq!(miner_data.status, MinerStatus::Paused);
+        assert!(!miner_data.is_mining);
+        assert_eq!(miner_data.uptime, None);
+        assert_eq!(miner_data.hashrate, None);
+        assert_eq!(miner_data.api_version, Some("1.4.0".to_string()));
+        assert_eq!(
+            miner_data.firmware_version,
+            Some("20210322.22.REL".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v1_powered_on() -> Result<()> {
+        let miner = WhatsMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M20SV10),
+        );
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        // btminer is off for the first collection and mining by the second
+        let mock_api = MockAPIClient::new(HashMap::from([
+            (rpc("summary"), Value::from_str(SUMMARY_COMMAND)?),
+            (rpc("devs"), Value::from_str(DEVS_COMMAND)?),
+            (rpc("pools"), Value::from_str(POOLS_COMMAND)?),
+            (rpc("get_version"), Value::from_str(GET_VERSION_COMMAND)?),
+            (rpc("get_psu"), Value::from_str(GET_PSU_COMMAND)?),
+        ]))
+        .with_sequence(
+            rpc("status"),
+            [
+                Value::from_str(STATUS_COMMAND)?.into(),
+                Value::from_str(STATUS_MINING_COMMAND)?.into(),
+            ],
+        );
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let off = miner.parse_data(collector.collect_all().await);
+        assert_eq!(off.status, MinerStatus::Paused);
+        mock_api.assert_not_called("summary");
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let on = miner.parse_data(collector.collect_all().await);
+        assert_eq!(on.status, MinerStatus::Mining);
+        assert!(on.hashrate.is_some());
+        mock_api.assert_call_count("status", 2);
+        mock_api.assert_called_once("summary");
+        mock_api.assert_called_in_order(&["status", "status", "summary"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whatsminer_v1_failure_status() {
+        let miner = WhatsMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M20SV10),
+        );
+        let data = FieldData::from([(
+            DataField::IsMining,
+            json!({
+                "status": {"Msg": {"btmineroff": "false"}},
+                "summary": {"SUMMARY": [{"HS RT": 0.0}]},
+            }),
+        )]);
+
+        assert_eq!(miner.parse_status(&data), MinerStatus::Failure);
+        assert!(!miner.parse_is_mining(&data));
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v1_terahash_hs_rt() -> Result<()> {
+        let miner = WhatsMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M20SV10),
+        );
+        // firmware 20200917 reports HS RT in TH/s, the MHS fields are still MH/s
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            MinerCommand::RPC {
+                command: "summary",
+                parameters: None,
+            },
+            Value::from_str(SUMMARY_20200917_COMMAND)?,
+        )]));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+        assert_hashrate_magnitudes(&miner_data);
+
+        assert_eq!(
+            miner_data.hashrate,
+            Some(HashRate {
+                value: 67.61,
+                unit: HashRateUnit::TeraHash,
+                algo: String::from("SHA256"),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v1_sentinel_values() -> Result<()> {
+        let miner = WhatsMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M20SV10),
+        );
+
+        let mut devs = Value::from_str(DEVS_COMMAND)?;
+        devs["DEVS"][0]["Temperature"] = json!(65535);
+        let mut summary = Value::from_str(SUMMARY_COMMAND)?;
+        summary["SUMMARY"][0]["Fan Speed In"] = json!(-1);
+        summary["SUMMARY"][0]["Power"] = json!(0);
+
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        let mock_api = MockAPIClient::new(HashMap::from([
+            (rpc("devs"), devs),
+            (rpc("summary"), summary),
+        ]));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+
+        assert_eq!(miner_data.hashboards[0].board_temperature, None);
+        assert_eq!(
+            miner_data.hashboards[1].board_temperature,
+            Some(Temperature::from_celsius(69.5))
+        );
+        // the rejected board is left out of the average
+        assert_eq!(
+            miner_data.average_temperature,
+            Some(Temperature::from_celsius((69.5 + 71.5) / 2.0))
+        );
+        assert_eq!(miner_data.fans.len(), 2);
+        assert_eq!(miner_data.fans[0].rpm, None);
+        assert_eq!(
+            miner_data.fans[1].rpm,
+            Some(AngularVelocity::from_rpm(6480.0))
+        );
+        assert_eq!(miner_data.wattage, None);
+        assert_eq!(miner_data.efficiency, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v1_env_temperature() -> Result<()> {
+        let miner = WhatsMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M20SV10),
+        );
+        let summary_command: MinerCommand = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+        let cases = [
+            (SUMMARY_ENV_NEGATIVE_COMMAND, Some(-12.5), None),
+            (
+                SUMMARY_ENV_FAULT_COMMAND,
+                None,
+                Some(MessageSeverity::Warning),
+            ),
+            // without an env temperature the lowest chip temperature stands in
+            (SUMMARY_COMMAND, Some(71.0), Some(MessageSeverity::Info)),
+        ];
+
+        let responses = cases
+            .iter()
+            .map(|(summary, _, _)| Value::from_str(summary).map(MockResponse::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mock_api = MockAPIClient::new(HashMap::new()).with_sequence(summary_command, responses);
+
+        // each collection gets the summary of the next case
+        for (_, temperature, severity) in cases {
+            let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+            let data = collector
+                .collect(&[DataField::FluidTemperature, DataField::Messages])
+                .await;
+
+            assert_eq!(
+                miner.parse_fluid_temperature(&data),
+                temperature.map(Temperature::from_celsius)
+            );
+            let severities: Vec<_> = miner
+                .parse_messages(&data)
+                .into_iter()
+                .filter(|m| m.code == 0)
+                .map(|m| m.severity)
+                .collect();
+            assert_eq!(severities, Vec::from_iter(severity));
+        }
+        mock_api.assert_call_count("summary", 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v1_env_fault_message() -> Result<()> {
+        let miner = WhatsMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M20SV10),
+        );
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            MinerCommand::RPC {
+                command: "summary",
+                parameters: None,
+            },
+            Value::from_str(SUMMARY_ENV_FAULT_COMMAND)?,
+        )]));
+
+        // the fault is reported without collecting the fluid temperature
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect(&[DataField::Messages]).await;
+        assert!(miner.parse_messages(&data).iter().any(|m| m.code == 0
+            && m.severity == MessageSeverity::Warning
+            && m.message.starts_with("Env temperature sensor fault")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v1_health_thresholds() -> Result<()> {
+        let mut miner = WhatsMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M20SV10),
+        );
+        let devs_command: MinerCommand = MinerCommand::RPC {
+            command: "devs",
+            parameters: None,
+        };
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            devs_command,
+            Value::from_str(DEVS_COMMAND)?,
+        )]));
+        let overtemp = |miner_data: &MinerData| {
+            miner_data
+                .messages
+                .iter()
+                .filter(|m| m.message.contains("chip temperature"))
+                .map(|m| m.message.clone())
+                .collect::<Vec<_>>()
+        };
+
+        // boards 1 and 2 report 90.11 °C and 92.5 °C chips, over the air cooled limit
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect(&[DataField::Hashboards]).await);
+        assert_eq!(
+            overtemp(&miner_data),
+            vec![
+                "Board 1 chip temperature 90.1 °C is above 90 °C",
+                "Board 2 chip temperature 92.5 °C is above 90 °C",
+            ]
+        );
+
+        miner.set_health_thresholds(HealthThresholds::immersion());
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect(&[DataField::Hashboards]).await);
+        assert!(overtemp(&miner_data).is_empty());
+        Ok(())
+    }
 }
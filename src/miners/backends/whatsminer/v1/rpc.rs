@@ -2,10 +2,13 @@ use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use serde_json::{Value, json};
 use std::net::IpAddr;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
 
 use crate::miners::api::rpc::errors::RPCError;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::{self, RPCReadLimits};
 use crate::miners::api::rpc::status::RPCCommandStatus;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
 
@@ -13,6 +16,9 @@ use crate::miners::commands::MinerCommand;
 pub struct WhatsMinerRPCAPI {
     ip: IpAddr,
     port: u16,
+    pub(crate) policy: CommandPolicy,
+    pub(crate) connections: ConnectionLimit,
+    read_limits: RPCReadLimits,
 }
 
 #[async_trait]
@@ -22,10 +28,7 @@ impl APIClient for WhatsMinerRPCAPI {
             MinerCommand::RPC {
                 command,
                 parameters,
-            } => self
-                .send_command(command, false, parameters.clone())
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => self.send_command(command, false, parameters.clone()).await,
             _ => Err(anyhow!("Cannot send non RPC command to RPC API")),
         }
     }
@@ -70,6 +73,8 @@ impl RPCAPIClient for WhatsMinerRPCAPI {
         _privileged: bool,
         parameters: Option<Value>,
     ) -> Result<Value> {
+        self.policy.check(command)?;
+        let _connection = self.connections.acquire().await;
         let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
             .await
             .map_err(|_| RPCError::ConnectionFailed)?;
@@ -92,10 +97,9 @@ impl RPCAPIClient for WhatsMinerRPCAPI {
         let json_str = request.to_string();
         let json_bytes = json_str.as_bytes();
 
-        stream.write_all(json_bytes).await.unwrap();
+        stream.write_all(json_bytes).await?;
 
-        let mut buffer = Vec::new();
-        stream.read_to_end(&mut buffer).await.unwrap();
+        let buffer = reader::read_to_end(&mut stream, &self.read_limits).await?;
 
         let response = String::from_utf8_lossy(&buffer)
             .into_owned()
@@ -112,9 +116,17 @@ impl WhatsMinerRPCAPI {
         Self {
             ip,
             port: port.unwrap_or(4028),
+            policy: CommandPolicy::default(),
+            connections: ConnectionLimit::serial(),
+            read_limits: RPCReadLimits::default(),
         }
     }
 
+    /// Bound the size of and idle time between reads of the miner's responses.
+    pub fn set_read_limits(&mut self, limits: RPCReadLimits) {
+        self.read_limits = limits;
+    }
+
     fn parse_rpc_result(&self, response: &str) -> Result<Value> {
         let status = RPCCommandStatus::from_btminer_v1(response)?;
         match status.into_result() {
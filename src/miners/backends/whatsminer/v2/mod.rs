@@ -1,26 +1,40 @@
 use crate::data::board::BoardData;
-use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerModel};
-use crate::data::device::{MinerControlBoard, MinerMake};
-use crate::data::fan::FanData;
+use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerModel};
+use crate::data::device::{MinerControlBoard, MinerMake, PowerLimits};
+use crate::data::fan::{FanControl, FanData, FanMode};
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::pool::{PoolData, PoolURL};
+use crate::data::health::HealthThresholds;
+use crate::data::miner::{MinerStatus, WorkMode};
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::psu::PsuRail;
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::RPCReadLimits;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::{DryRun, REDACTED};
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
+use crate::miners::backends::whatsminer::{
+    EnvTemperature, btminer_psu_input, btminer_status, error_codes, led_flashing, parse_power_mode,
+    power_mode_name, skip_while_btminer_off, validate_password,
+};
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_pointer,
+    CommandGate, DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData,
+    FromValue, get_by_pointer,
 };
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
-use chrono::{DateTime, NaiveDateTime, Utc};
 use macaddr::MacAddr;
-use measurements::{AngularVelocity, Frequency, Power, Temperature};
+use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::str::FromStr;
 use std::time::Duration;
 
-use crate::data::message::{MessageSeverity, MinerMessage};
+use crate::data::message::MinerMessage;
 use rpc::WhatsMinerRPCAPI;
 
 mod rpc;
@@ -30,6 +44,10 @@ pub struct WhatsMinerV2 {
     pub ip: IpAddr,
     pub rpc: WhatsMinerRPCAPI,
     pub device_info: DeviceInfo,
+    pub labels: Option<HashMap<String, String>>,
+    pub health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
 }
 
 impl WhatsMinerV2 {
@@ -43,6 +61,54 @@ impl WhatsMinerV2 {
                 MinerFirmware::Stock,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
+        }
+    }
+
+    /// Limit the connections the API client opens at once, the firmware answers one at a time
+    /// so commands are sent one after another by default.
+    pub fn with_connection_limit(mut self, limit: ConnectionLimit) -> Self {
+        self.rpc.connections = limit;
+        self
+    }
+
+    /// The power mode btminer is set to, read from the summary.
+    pub async fn get_power_mode(&self) -> Option<WorkMode> {
+        self.get_work_mode().await
+    }
+
+    /// Switch btminer to one of its power modes, which unlike a power limit only picks between
+    /// settings that are safe for the model. [`WorkMode::Sleep`] is rejected.
+    ///
+    /// btminer restarts to apply the mode, so the connection may drop right after the command is
+    /// accepted, and the uptime starts over.
+    pub async fn set_power_mode(&self, mode: WorkMode) -> Result<bool> {
+        let command = format!("set_{}_power", power_mode_name(mode)?);
+        let payload = self.dry_run_payload(&command, None).await?;
+        execute_control(self, &command, payload, async {
+            accepted(self.rpc.send_restarting_command(&command, None).await)
+        })
+        .await
+    }
+
+    /// Send a privileged command, in dry-run recording the encrypted request instead.
+    async fn execute_privileged(&self, command: &str, parameters: Option<Value>) -> Result<bool> {
+        let payload = self.dry_run_payload(command, parameters.clone()).await?;
+        execute_control(self, command, payload, async {
+            accepted(self.rpc.send_command(command, true, parameters).await)
+        })
+        .await
+    }
+
+    /// The encrypted request recorded for a command in dry-run.
+    async fn dry_run_payload(&self, command: &str, parameters: Option<Value>) -> Result<Value> {
+        match self.dry_run {
+            // encrypted with a fresh token each time, so only built when it is recorded
+            Some(_) => self.rpc.encrypted_request(command, parameters).await,
+            None => Ok(Value::Null),
         }
     }
 }
@@ -71,6 +137,10 @@ impl GetDataLocations for WhatsMinerV2 {
             command: "devs",
             parameters: None,
         };
+        let devdetails_cmd: MinerCommand = MinerCommand::RPC {
+            command: "devdetails",
+            parameters: None,
+        };
         let pools_cmd: MinerCommand = MinerCommand::RPC {
             command: "pools",
             parameters: None,
@@ -93,14 +163,24 @@ impl GetDataLocations for WhatsMinerV2 {
         };
 
         match data_field {
-            DataField::Mac => vec![(
-                get_miner_info_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/Msg/mac"),
-                    tag: None,
-                },
-            )],
+            DataField::Mac => vec![
+                (
+                    get_miner_info_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/Msg/mac"),
+                        tag: Some("miner_info"),
+                    },
+                ),
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/MAC"),
+                        tag: Some("summary"),
+                    },
+                ),
+            ],
             DataField::ApiVersion => vec![(
                 get_version_cmd,
                 DataExtractor {
@@ -125,6 +205,14 @@ impl GetDataLocations for WhatsMinerV2 {
                     tag: None,
                 },
             )],
+            DataField::ReportedIp => vec![(
+                get_miner_info_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/Msg/ip"),
+                    tag: None,
+                },
+            )],
             DataField::Hostname => vec![(
                 get_miner_info_cmd,
                 DataExtractor {
@@ -149,6 +237,14 @@ impl GetDataLocations for WhatsMinerV2 {
                     tag: None,
                 },
             )],
+            DataField::WorkMode => vec![(
+                summary_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/SUMMARY/0/Power Mode"),
+                    tag: None,
+                },
+            )],
             DataField::Fans => vec![(
                 summary_cmd,
                 DataExtractor {
@@ -157,6 +253,14 @@ impl GetDataLocations for WhatsMinerV2 {
                     tag: None,
                 },
             )],
+            DataField::FanControl => vec![(
+                summary_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/SUMMARY/0/Liquid Cooling"),
+                    tag: None,
+                },
+            )],
             DataField::PsuFans => vec![(
                 get_psu_cmd,
                 DataExtractor {
@@ -165,14 +269,32 @@ impl GetDataLocations for WhatsMinerV2 {
                     tag: None,
                 },
             )],
-            DataField::Hashboards => vec![(
-                devs_cmd,
+            DataField::PsuRails => vec![(
+                get_psu_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some(""),
+                    key: Some("/Msg"),
                     tag: None,
                 },
             )],
+            DataField::Hashboards => vec![
+                (
+                    devs_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some(""),
+                        tag: None,
+                    },
+                ),
+                (
+                    devdetails_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/DEVDETAILS"),
+                        tag: Some("devdetails"),
+                    },
+                ),
+            ],
             DataField::Pools => vec![(
                 pools_cmd,
                 DataExtractor {
@@ -197,11 +319,37 @@ impl GetDataLocations for WhatsMinerV2 {
                     tag: None,
                 },
             )],
-            DataField::Hashrate => vec![(
+            DataField::Hashrate => vec![
+                (
+                    summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/HS RT"),
+                        tag: Some("HS RT"),
+                    },
+                ),
+                (
+                    summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/MHS 5s"),
+                        tag: Some("MHS 5s"),
+                    },
+                ),
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Factory GHS"),
+                        tag: Some("Factory GHS"),
+                    },
+                ),
+            ],
+            DataField::AverageHashrate => vec![(
                 summary_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/SUMMARY/0/HS RT"),
+                    key: Some("/SUMMARY/0/MHS 5m"),
                     tag: None,
                 },
             )],
@@ -213,33 +361,83 @@ impl GetDataLocations for WhatsMinerV2 {
                     tag: None,
                 },
             )],
-            DataField::FluidTemperature => vec![(
-                summary_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/SUMMARY/0/Env Temp"),
-                    tag: None,
-                },
-            )],
-            DataField::IsMining => vec![(
-                status_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/SUMMARY/0/btmineroff"),
-                    tag: None,
-                },
-            )],
-            DataField::Messages => vec![(
-                get_error_code_cmd,
-                DataExtractor {
-                    func: get_by_pointer,
-                    key: Some("/Msg/error_code"),
-                    tag: None,
-                },
-            )],
+            DataField::FluidTemperature => vec![
+                (
+                    summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Env Temp"),
+                        tag: Some("env"),
+                    },
+                ),
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Chip Temp Min"),
+                        tag: Some("chip_min"),
+                    },
+                ),
+            ],
+            // the whole responses, builds differ in where they report mining stopped
+            DataField::IsMining => vec![
+                (
+                    status_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some(""),
+                        tag: Some("status"),
+                    },
+                ),
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some(""),
+                        tag: Some("summary"),
+                    },
+                ),
+            ],
+            // the env temperature fault or its stand-in is reported as a message too
+            DataField::Messages => vec![
+                (
+                    get_error_code_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/Msg/error_code"),
+                        tag: Some("error_code"),
+                    },
+                ),
+                (
+                    summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Env Temp"),
+                        tag: Some("env"),
+                    },
+                ),
+                (
+                    summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/SUMMARY/0/Chip Temp Min"),
+                        tag: Some("chip_min"),
+                    },
+                ),
+            ],
             _ => vec![],
         }
     }
+
+    fn command_gate(&self) -> Option<CommandGate> {
+        Some(CommandGate {
+            probe: MinerCommand::RPC {
+                command: "status",
+                parameters: None,
+            },
+            skip: skip_while_btminer_off,
+        })
+    }
 }
 
 impl GetIP for WhatsMinerV2 {
@@ -249,7 +447,71 @@ impl GetIP for WhatsMinerV2 {
 }
 impl GetDeviceInfo for WhatsMinerV2 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for WhatsMinerV2 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for WhatsMinerV2 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for WhatsMinerV2 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for WhatsMinerV2 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for WhatsMinerV2 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.rpc.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.rpc.policy = policy;
+    }
+}
+
+impl GetCapabilities for WhatsMinerV2 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_fault_light: true,
+            supports_power_limit: true,
+            supports_password: true,
+            supports_pool_update: true,
+            supports_restart: true,
+            supports_pause: true,
+            has_psu_telemetry: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -260,39 +522,45 @@ impl CollectData for WhatsMinerV2 {
 }
 
 impl GetMAC for WhatsMinerV2 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
-        data.extract::<String>(DataField::Mac)
-            .and_then(|s| MacAddr::from_str(&s).ok())
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
+        ["miner_info", "summary"].iter().find_map(|source| {
+            data.extract_nested::<String>(DataField::Mac, source)
+                .and_then(|s| parse_mac_str(&s))
+        })
+    }
+}
+
+impl GetReportedIp for WhatsMinerV2 {
+    fn parse_reported_ip(&self, data: &FieldData) -> Option<IpAddr> {
+        data.extract::<String>(DataField::ReportedIp)
+            .and_then(|s| parse_ip_str(&s))
     }
 }
 
 impl GetSerialNumber for WhatsMinerV2 {}
 impl GetHostname for WhatsMinerV2 {
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::Hostname)
     }
 }
 impl GetApiVersion for WhatsMinerV2 {
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::ApiVersion)
     }
 }
 impl GetFirmwareVersion for WhatsMinerV2 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
 }
 impl GetControlBoardVersion for WhatsMinerV2 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
         data.extract::<String>(DataField::ControlBoardVersion)
             .and_then(|s| MinerControlBoard::from_str(&s).ok())
     }
 }
 impl GetHashboards for WhatsMinerV2 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         let mut hashboards: Vec<BoardData> = Vec::new();
         let board_count = self.device_info.hardware.boards.unwrap_or(3);
         let hashboard_data = data.get(&DataField::Hashboards);
@@ -332,13 +600,19 @@ impl GetHashboards for WhatsMinerV2 {
                 .and_then(|val| val.pointer(&format!("/DEVS/{idx}/Chip Temp Max")))
                 .and_then(|val| val.as_f64())
                 .map(Temperature::from_celsius);
-            let serial_number = hashboard_data
-                .and_then(|val| val.pointer(&format!("/DEVS/{idx}/PCB SN")))
+            // newer firmware only reports the board serial and chip count in `devdetails`
+            let board_value = |key: &str| {
+                hashboard_data.and_then(|val| {
+                    val.pointer(&format!("/DEVS/{idx}/{key}"))
+                        .or_else(|| val.pointer(&format!("/devdetails/{idx}/{key}")))
+                })
+            };
+            let serial_number = board_value("PCB SN")
                 .and_then(|val| val.as_str())
+                .filter(|sn| !sn.is_empty())
                 .map(String::from);
-            let working_chips = hashboard_data
-                .and_then(|val| val.pointer(&format!("/DEVS/{idx}/Effective Chips")))
-                .and_then(|val| val.as_u64())
+            let working_chips = board_value("Effective Chips")
+                .and_then(u64::from_value)
                 .map(|u| u as u16);
             let frequency = hashboard_data
                 .and_then(|val| val.pointer(&format!("/DEVS/{idx}/Frequency")))
@@ -357,33 +631,46 @@ impl GetHashboards for WhatsMinerV2 {
                 working_chips,
                 serial_number,
                 chips: vec![],
+                sensor_temperatures: vec![],
                 voltage: None, // TODO
                 frequency,
+                wattage: None,
                 tuned: Some(true),
                 active,
+                status: None,
+                extra: None,
             });
         }
         hashboards
     }
 }
 impl GetHashrate for WhatsMinerV2 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
-        data.extract_map::<f64, _>(DataField::Hashrate, |f| {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        super::summary_hashrate(
+            data.extract_nested(DataField::Hashrate, "HS RT"),
+            data.extract_nested(DataField::Hashrate, "MHS 5s"),
+            data.extract_nested(DataField::Hashrate, "Factory GHS"),
+        )
+    }
+}
+impl GetExpectedHashrate for WhatsMinerV2 {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| {
             HashRate {
                 value: f,
-                unit: HashRateUnit::MegaHash,
+                unit: HashRateUnit::GigaHash,
                 algo: String::from("SHA256"),
             }
             .as_unit(HashRateUnit::TeraHash)
         })
     }
 }
-impl GetExpectedHashrate for WhatsMinerV2 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
-        data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| {
+impl GetAverageHashrate for WhatsMinerV2 {
+    fn parse_average_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::AverageHashrate, |f| {
             HashRate {
                 value: f,
-                unit: HashRateUnit::GigaHash,
+                unit: HashRateUnit::MegaHash,
                 algo: String::from("SHA256"),
             }
             .as_unit(HashRateUnit::TeraHash)
@@ -391,7 +678,7 @@ impl GetExpectedHashrate for WhatsMinerV2 {
     }
 }
 impl GetFans for WhatsMinerV2 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut fans: Vec<FanData> = Vec::new();
         for (idx, direction) in ["In", "Out"].iter().enumerate() {
             let fan = data.extract_nested_map::<f64, _>(
@@ -410,12 +697,12 @@ impl GetFans for WhatsMinerV2 {
     }
 }
 impl GetPsuFans for WhatsMinerV2 {
-    fn parse_psu_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_psu_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut psu_fans: Vec<FanData> = Vec::new();
 
-        let psu_fan = data.extract_map::<String, _>(DataField::PsuFans, |rpm| FanData {
+        let psu_fan = data.extract_map::<f64, _>(DataField::PsuFans, |rpm| FanData {
             position: 0i16,
-            rpm: Some(AngularVelocity::from_rpm(rpm.parse().unwrap())),
+            rpm: Some(AngularVelocity::from_rpm(rpm)),
         });
         if let Some(f) = psu_fan {
             psu_fans.push(f)
@@ -423,76 +710,100 @@ impl GetPsuFans for WhatsMinerV2 {
         psu_fans
     }
 }
+
+impl GetFanControl for WhatsMinerV2 {
+    /// Configured, from `Liquid Cooling` in the summary. btminer has no fixed fan speed, it
+    /// always regulates the fans itself unless they are off for liquid cooling.
+    fn parse_fan_control(&self, data: &FieldData) -> Option<FanControl> {
+        let mode = match data.extract::<bool>(DataField::FanControl)? {
+            true => FanMode::Off,
+            false => FanMode::Auto,
+        };
+        Some(FanControl {
+            mode,
+            target_pct: None,
+        })
+    }
+}
+
+impl GetPsu for WhatsMinerV2 {}
+
+impl GetPsuRails for WhatsMinerV2 {
+    fn parse_psu_rails(&self, data: &FieldData) -> Vec<PsuRail> {
+        btminer_psu_input(data).into_iter().collect()
+    }
+}
+
 impl GetFluidTemperature for WhatsMinerV2 {
-    fn parse_fluid_temperature(&self, data: &HashMap<DataField, Value>) -> Option<Temperature> {
-        data.extract_map::<f64, _>(DataField::FluidTemperature, Temperature::from_celsius)
+    fn parse_fluid_temperature(&self, data: &FieldData) -> Option<Temperature> {
+        EnvTemperature::from_data(data, DataField::FluidTemperature)
+            .and_then(EnvTemperature::temperature)
     }
 }
+
+impl GetFluidFlow for WhatsMinerV2 {}
+
+impl GetFluidPressure for WhatsMinerV2 {}
+
 impl GetWattage for WhatsMinerV2 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<f64, _>(DataField::Wattage, Power::from_watts)
     }
 }
 impl GetWattageLimit for WhatsMinerV2 {
-    fn parse_wattage_limit(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage_limit(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<f64, _>(DataField::WattageLimit, Power::from_watts)
     }
 }
+impl GetFrequency for WhatsMinerV2 {}
+impl GetVoltage for WhatsMinerV2 {}
+impl GetWorkMode for WhatsMinerV2 {
+    fn parse_work_mode(&self, data: &FieldData) -> Option<WorkMode> {
+        data.extract::<String>(DataField::WorkMode)
+            .and_then(|mode| parse_power_mode(&mode))
+    }
+}
+
+impl GetAutotune for WhatsMinerV2 {}
+
 impl GetLightFlashing for WhatsMinerV2 {
-    fn parse_light_flashing(&self, data: &HashMap<DataField, Value>) -> Option<bool> {
-        data.extract_map::<String, _>(DataField::LightFlashing, |l| l != "auto")
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
+        data.extract::<String>(DataField::LightFlashing)
+            .and_then(|status| led_flashing(&status))
     }
 }
 impl GetMessages for WhatsMinerV2 {
-    fn parse_messages(&self, data: &HashMap<DataField, Value>) -> Vec<MinerMessage> {
-        let mut messages = Vec::new();
-
-        let errors_raw = data.get(&DataField::Messages);
-
-        if let Some(errors_response) = errors_raw {
-            for obj in errors_response.as_array().unwrap_or(&Vec::new()).iter() {
-                let object = obj.as_object();
-                if let Some(obj) = object {
-                    for (code, time) in obj.iter() {
-                        dbg!(time);
-                        let timestamp = NaiveDateTime::parse_from_str(
-                            time.as_str().unwrap(),
-                            "%Y-%m-%d %H:%M:%S",
-                        )
-                        .map(|t| DateTime::<Utc>::from_naive_utc_and_offset(t, Utc))
-                        .map(|dt| dt.timestamp_millis() as u32);
-
-                        dbg!(&timestamp);
-
-                        if let Ok(ts) = timestamp {
-                            messages.push(MinerMessage {
-                                timestamp: ts,
-                                code: code.parse::<u64>().unwrap_or(0),
-                                message: "".to_string(),
-                                severity: MessageSeverity::Error,
-                            })
-                        }
-                    }
-                }
-            }
-        }
-
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
+        let mut messages = data
+            .get(&DataField::Messages)
+            .and_then(|messages| messages.get("error_code"))
+            .map(error_codes::messages_from_value)
+            .unwrap_or_default();
+        messages.extend(
+            EnvTemperature::from_data(data, DataField::Messages).and_then(EnvTemperature::message),
+        );
         messages
     }
 }
 impl GetUptime for WhatsMinerV2 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
     }
 }
 impl GetIsMining for WhatsMinerV2 {
-    fn parse_is_mining(&self, data: &HashMap<DataField, Value>) -> bool {
-        data.extract_map::<String, _>(DataField::IsMining, |l| l != "false")
-            .unwrap_or(true)
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
+    }
+}
+
+impl GetStatus for WhatsMinerV2 {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        btminer_status(data)
     }
 }
+
 impl GetPools for WhatsMinerV2 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
         let mut pools: Vec<PoolData> = Vec::new();
         let pools_raw = data.get(&DataField::Pools);
         if let Some(pools_response) = pools_raw {
@@ -517,7 +828,7 @@ impl GetPools for WhatsMinerV2 {
 
                 let url = pools_raw
                     .and_then(|val| val.pointer(&format!("/{idx}/URL")))
-                    .map(|val| PoolURL::from(String::from(val.as_str().unwrap_or(""))));
+                    .and_then(|val| PoolURL::parse(val.as_str().unwrap_or("")));
 
                 let accepted_shares = pools_raw
                     .and_then(|val| val.pointer(&format!("/{idx}/Accepted")))
@@ -527,6 +838,16 @@ impl GetPools for WhatsMinerV2 {
                     .and_then(|val| val.pointer(&format!("/{idx}/Rejected")))
                     .and_then(|val| val.as_u64());
 
+                let last_share_difficulty = pools_raw
+                    .and_then(|val| val.pointer(&format!("/{idx}/Last Share Difficulty")))
+                    .and_then(|val| val.as_f64());
+
+                // 0 until the first share
+                let last_share_time = pools_raw
+                    .and_then(|val| val.pointer(&format!("/{idx}/Last Share Time")))
+                    .and_then(u64::from_value)
+                    .filter(|t| *t > 0);
+
                 pools.push(PoolData {
                     position: Some(idx as u16),
                     url,
@@ -535,6 +856,11 @@ impl GetPools for WhatsMinerV2 {
                     active,
                     alive,
                     user,
+                    password: None,
+                    last_share_difficulty,
+                    last_share_time,
+                    group: None,
+                    quota: None,
                 });
             }
         }
@@ -554,28 +880,107 @@ impl SetFaultLight for WhatsMinerV2 {
             ),
         };
 
-        let data = self.rpc.send_command("set_led", true, parameters).await;
-        Ok(data.is_ok())
+        let sent = self.execute_privileged("set_led", parameters).await?;
+        Ok(confirm_fault_light(self, fault, sent).await)
     }
 }
 
 #[async_trait]
 impl SetPowerLimit for WhatsMinerV2 {
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
+        PowerLimits::validate_for(&self.device_info.model, limit)?;
+        self.set_power_limit_unchecked(limit).await
+    }
+
+    async fn set_power_limit_unchecked(&self, limit: Power) -> Result<bool> {
         let parameters = Some(json!({"power_limit": limit.as_watts().to_string()}));
-        let data = self
-            .rpc
-            .send_command("adjust_power_limit", true, parameters)
-            .await;
-        Ok(data.is_ok())
+        self.execute_privileged("adjust_power_limit", parameters)
+            .await
+    }
+}
+
+impl SetCredentials for WhatsMinerV2 {}
+
+impl EndpointControl for WhatsMinerV2 {
+    fn set_endpoints(&mut self, endpoints: MinerEndpoints) {
+        if let Some(rpc) = endpoints.rpc {
+            self.rpc.set_address(rpc);
+        }
+    }
+}
+
+#[async_trait]
+impl SetPassword for WhatsMinerV2 {
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        validate_password(new)?;
+        // the encrypted request would carry both passwords, record its parameters redacted
+        let payload = WhatsMinerRPCAPI::password_parameters(REDACTED, REDACTED);
+        execute_control(self, "update_pwd", payload, async {
+            accepted(self.rpc.set_password(old, new).await)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SetHostname for WhatsMinerV2 {
+    #[allow(unused_variables)]
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        Err(ControlError::UnsupportedOperation(
+            "setting the hostname".to_string(),
+        ))?
+    }
+}
+
+#[async_trait]
+impl SetPools for WhatsMinerV2 {
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        validate_pool_count(&pools, 3)?;
+        let mut parameters = serde_json::Map::new();
+        for idx in 0..3 {
+            let pool = pools.get(idx);
+            let slot = idx + 1;
+            parameters.insert(
+                format!("pool{slot}"),
+                json!(pool.map(|p| p.url.to_string()).unwrap_or_default()),
+            );
+            parameters.insert(
+                format!("worker{slot}"),
+                json!(pool.map(|p| p.user.as_str()).unwrap_or_default()),
+            );
+            parameters.insert(
+                format!("passwd{slot}"),
+                json!(pool.map(|p| p.password_or_default()).unwrap_or_default()),
+            );
+        }
+        self.execute_privileged("update_pools", Some(Value::Object(parameters)))
+            .await
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for WhatsMinerV2 {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
     }
 }
 
 #[async_trait]
 impl Restart for WhatsMinerV2 {
-    async fn restart(&self) -> Result<bool> {
-        let data = self.rpc.send_command("reboot", true, None).await;
-        Ok(data.is_ok())
+    async fn restart_mining(&self) -> Result<bool> {
+        self.execute_privileged("restart_btminer", None).await
+    }
+
+    async fn reboot_system(&self) -> Result<bool> {
+        self.execute_privileged("reboot", None).await
     }
 }
 
@@ -583,11 +988,9 @@ impl Restart for WhatsMinerV2 {
 impl Pause for WhatsMinerV2 {
     #[allow(unused_variables)]
     async fn pause(&self, at_time: Option<Duration>) -> Result<bool> {
-        let data = self
-            .rpc
-            .send_command("power_off", true, Some(json!({"respbefore": "true"}))) // Has to be string for some reason
-            .await;
-        Ok(data.is_ok())
+        // Has to be string for some reason
+        self.execute_privileged("power_off", Some(json!({"respbefore": "true"})))
+            .await
     }
 }
 
@@ -595,7 +998,613 @@ impl Pause for WhatsMinerV2 {
 impl Resume for WhatsMinerV2 {
     #[allow(unused_variables)]
     async fn resume(&self, at_time: Option<Duration>) -> Result<bool> {
-        let data = self.rpc.send_command("power_on", true, None).await;
-        Ok(data.is_ok())
+        self.execute_privileged("power_on", None).await
+    }
+}
+
+impl SetRPCReadLimits for WhatsMinerV2 {
+    fn set_rpc_read_limits(&mut self, limits: RPCReadLimits) {
+        self.rpc.set_read_limits(limits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::whatsminer::WhatsMinerModel;
+    use crate::data::message::MessageSeverity;
+    use crate::data::miner::MinerData;
+    use crate::miners::backends::errors::MinerError;
+    use crate::miners::backends::whatsminer::WhatsMinerV3;
+    use crate::test::api::{MockAPIClient, MockResponse};
+    use crate::test::completeness::assert_fields_extracted;
+    use crate::test::invariants::assert_hashrate_magnitudes;
+    use crate::test::json::btminer::v2::{
+        DEVDETAILS_20230302_COMMAND, DEVS_20230302_COMMAND, GET_ERROR_CODE_20230302_COMMAND,
+        GET_MINER_INFO_20230302_COMMAND, GET_PSU_20230302_COMMAND, GET_VERSION_20230302_COMMAND,
+        POOLS_20230302_COMMAND, STATUS_20230302_COMMAND, STATUS_MINEROFF_20230911_COMMAND,
+        STATUS_MINEROFF_STOPPED_20230911_COMMAND, STATUS_NO_MINEROFF_20221111_COMMAND,
+        SUMMARY_20230302_COMMAND, SUMMARY_ENV_FAULT_COMMAND, SUMMARY_ENV_NEGATIVE_COMMAND,
+    };
+    use crate::test::json::btminer::v3::{
+        GET_DEVICE_INFO_COMMAND, GET_MINER_STATUS_EDEVS_COMMAND, SUMMARY_COMMAND,
+    };
+    use md5crypt::md5crypt;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const SALT: &str = "BQ5hoXV9";
+
+    /// Serve the BTMiner API on an ephemeral port, recording every request sent to it.
+    async fn serve_btminer() -> (SocketAddr, Arc<Mutex<Vec<Value>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 1024];
+                let request = loop {
+                    match stream.read(&mut chunk).await {
+                        Ok(0) | Err(_) => break Value::Null,
+                        Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                    }
+                    if let Ok(request) = serde_json::from_slice::<Value>(&buffer) {
+                        break request;
+                    }
+                };
+                let response = match request["command"].as_str() {
+                    Some("get_token") => json!({
+                        "STATUS": "S",
+                        "Code": 134,
+                        "Msg": {"time": "1720000000", "salt": SALT, "newsalt": "jbzkfQls"},
+                    }),
+                    Some("get_miner_info") => {
+                        Value::from_str(GET_MINER_INFO_20230302_COMMAND).unwrap()
+                    }
+                    _ => json!({ "STATUS": "S", "Msg": "OK" }),
+                };
+                // encrypted writes are only recorded, the connection is closed without a reply
+                let encrypted = request.get("enc").is_some();
+                recorded.lock().unwrap().push(request);
+                if !encrypted {
+                    let _ = stream.write_all(response.to_string().as_bytes()).await;
+                }
+            }
+        });
+        (addr, requests)
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_dry_run() {
+        let (addr, requests) = serve_btminer().await;
+        let mut miner = WhatsMinerV2::new(
+            addr.ip(),
+            MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+        );
+        miner.rpc = WhatsMinerRPCAPI::new(addr.ip(), Some(addr.port()));
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        assert!(miner.restart_mining().await.unwrap());
+        assert!(miner.restart().await.unwrap());
+        assert!(
+            miner
+                .set_power_limit(Power::from_watts(3000.0))
+                .await
+                .unwrap()
+        );
+
+        // building the encrypted requests only needs a token, nothing is written
+        let sent = requests.lock().unwrap().clone();
+        assert!(!sent.is_empty());
+        assert!(sent.iter().all(|r| r["command"] == "get_token"));
+
+        let actions = dry_run.actions();
+        let commands: Vec<_> = actions.iter().map(|a| a.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            vec!["restart_btminer", "reboot", "adjust_power_limit"]
+        );
+        assert!(actions.iter().all(|a| a.ip == addr.ip()));
+
+        let crypted = md5crypt(b"admin", SALT.as_bytes());
+        let key = String::from_utf8_lossy(&crypted)
+            .split('$')
+            .nth(3)
+            .unwrap()
+            .to_string();
+        let payload = &actions[2].payload;
+        assert_eq!(payload["enc"], 1);
+        let request: Value =
+            serde_json::from_str(&rpc::aes_ecb_dec(&key, payload["data"].as_str().unwrap()))
+                .unwrap();
+        assert_eq!(request["command"], "adjust_power_limit");
+        assert_eq!(request["power_limit"], "3000");
+        assert!(request["token"].is_string());
+
+        // commands are sent again once dry-run is turned off
+        miner.set_dry_run(None);
+        let _ = miner.restart().await;
+        assert!(requests.lock().unwrap().iter().any(|r| r["enc"] == 1));
+        assert_eq!(dry_run.actions().len(), 3);
+    }
+
+    #[test]
+    fn test_whatsminer_v2_plausibility_ranges() {
+        let model = MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10);
+        let data = FieldData::from([
+            (DataField::Wattage, json!(3250.0)),
+            (DataField::WattageLimit, json!(3600.0)),
+        ]);
+        let watts = |miner: &WhatsMinerV2| {
+            let parsed = miner.parse_data(data.clone());
+            (
+                parsed.wattage.map(|w| w.as_watts()),
+                parsed.wattage_limit.map(|w| w.as_watts()),
+            )
+        };
+
+        // a lower ceiling drops the readings above it, without affecting other miners
+        let default = WhatsMinerV2::new(IpAddr::from([127, 0, 0, 1]), model.clone());
+        let mut limited = WhatsMinerV2::new(IpAddr::from([127, 0, 0, 2]), model);
+        limited.set_plausibility_ranges(PlausibilityRanges {
+            wattage: f64::MIN_POSITIVE..=3_500.0,
+            ..Default::default()
+        });
+        assert_eq!(watts(&limited), (Some(3250.0), None));
+        assert_eq!(watts(&default), (Some(3250.0), Some(3600.0)));
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_set_password_dry_run() {
+        let mut miner = WhatsMinerV2::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+        );
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        let error = miner.set_password("admin", "pa$$").await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        assert!(dry_run.actions().is_empty());
+
+        assert!(miner.set_password("admin", "secret_1").await.unwrap());
+        let action = &dry_run.actions()[0];
+        assert_eq!(action.command, "update_pwd");
+        assert_eq!(action.payload, json!({"old": REDACTED, "new": REDACTED}));
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_endpoints() {
+        let (addr, _) = serve_btminer().await;
+        let mut miner = WhatsMinerV2::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+        );
+        miner.set_endpoints(MinerEndpoints {
+            rpc: Some(addr),
+            web: None,
+        });
+
+        // reached on a forwarded port, while the miner has its own address on the far side
+        let miner_data = miner.get_data().await;
+        assert_eq!(miner_data.ip, IpAddr::from([127, 0, 0, 1]));
+        assert_eq!(miner_data.reported_ip, Some(IpAddr::from([10, 0, 0, 43])));
+        assert_eq!(miner_data.hostname.as_deref(), Some("WhatsMiner"));
+        assert!(
+            miner_data.messages.iter().any(|m| {
+                m.severity == MessageSeverity::Info && m.message.contains("10.0.0.43")
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_set_power_mode() {
+        let (addr, requests) = serve_btminer().await;
+        let mut miner = WhatsMinerV2::new(
+            addr.ip(),
+            MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+        );
+        miner.rpc = WhatsMinerRPCAPI::new(addr.ip(), Some(addr.port()));
+
+        // the mock closes the connection without a reply, like btminer restarting
+        assert!(miner.set_power_mode(WorkMode::LowPower).await.unwrap());
+
+        let crypted = md5crypt(b"admin", SALT.as_bytes());
+        let key = String::from_utf8_lossy(&crypted)
+            .split('$')
+            .nth(3)
+            .unwrap()
+            .to_string();
+        let sent = requests.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(sent["enc"], 1);
+        let request: Value =
+            serde_json::from_str(&rpc::aes_ecb_dec(&key, sent["data"].as_str().unwrap())).unwrap();
+        assert_eq!(request["command"], "set_low_power");
+        assert!(request["token"].is_string());
+
+        let sent = requests.lock().unwrap().len();
+        let error = miner.set_power_mode(WorkMode::Sleep).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        assert_eq!(requests.lock().unwrap().len(), sent);
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_read_only_policy() {
+        let (addr, requests) = serve_btminer().await;
+        let mut miner = WhatsMinerV2::new(
+            addr.ip(),
+            MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+        );
+        miner.rpc = WhatsMinerRPCAPI::new(addr.ip(), Some(addr.port()));
+        miner.set_command_policy(CommandPolicy::read_only());
+
+        let mut collector = miner.get_collector();
+        collector.collect_all().await;
+        assert!(collector.stats().policy_skipped.is_empty());
+        assert!(collector.stats().commands.iter().all(|c| c.success));
+
+        let denied = |result: Result<bool>| result.unwrap_err().downcast::<MinerError>().unwrap();
+        assert_eq!(
+            denied(miner.set_power_limit(Power::from_watts(3000.0)).await),
+            MinerError::PolicyDenied("adjust_power_limit".to_string())
+        );
+        assert_eq!(
+            denied(miner.reboot_system().await),
+            MinerError::PolicyDenied("reboot".to_string())
+        );
+
+        // denied before a token is requested
+        let sent = requests.lock().unwrap().clone();
+        assert!(!sent.is_empty());
+        assert!(
+            sent.iter()
+                .all(|r| r["command"] != "get_token" && r.get("enc").is_none())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_power_limit_bounds() {
+        let (addr, requests) = serve_btminer().await;
+        let mut miner = WhatsMinerV2::new(
+            addr.ip(),
+            MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+        );
+        miner.rpc = WhatsMinerRPCAPI::new(addr.ip(), Some(addr.port()));
+
+        let error = miner
+            .set_power_limit(Power::from_watts(800.0))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<ControlError>(),
+            Some(&ControlError::InvalidParameter(
+                "power limit 800 W is outside of the allowed range 1736-3819 W".to_string()
+            ))
+        );
+        // refused before a token is requested
+        assert!(requests.lock().unwrap().is_empty());
+
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+        assert!(
+            miner
+                .set_power_limit_unchecked(Power::from_watts(800.0))
+                .await
+                .unwrap()
+        );
+        assert_eq!(dry_run.actions()[0].command, "adjust_power_limit");
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_summary_hashrate() -> Result<()> {
+        let miner = WhatsMinerV2::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M50SVH20),
+        );
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            MinerCommand::RPC {
+                command: "summary",
+                parameters: None,
+            },
+            Value::from_str(SUMMARY_20230302_COMMAND)?,
+        )]));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+        assert_hashrate_magnitudes(&miner_data);
+
+        let hashrate = miner_data.hashrate.expect("hashrate");
+        assert_eq!(hashrate.unit, HashRateUnit::TeraHash);
+        assert!((hashrate.value - 117.95421064).abs() < 1e-6);
+        assert_eq!(
+            miner_data.expected_hashrate.map(|hr| hr.value),
+            Some(118.218)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_status_shapes() -> Result<()> {
+        let miner = WhatsMinerV2::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M50SVH20),
+        );
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        let cases = [
+            // `btmineroff`, `mineroff` and neither, which leaves it to the hashrate
+            (Some(STATUS_20230302_COMMAND), true, MinerStatus::Mining),
+            (
+                Some(STATUS_MINEROFF_20230911_COMMAND),
+                true,
+                MinerStatus::Mining,
+            ),
+            (
+                Some(STATUS_NO_MINEROFF_20221111_COMMAND),
+                true,
+                MinerStatus::Mining,
+            ),
+            // stopped by the user, which also gates the summary
+            (
+                Some(STATUS_MINEROFF_STOPPED_20230911_COMMAND),
+                true,
+                MinerStatus::Paused,
+            ),
+            // `status` answered without saying, and there is no hashrate to go by
+            (
+                Some(STATUS_NO_MINEROFF_20221111_COMMAND),
+                false,
+                MinerStatus::Failure,
+            ),
+            (None, true, MinerStatus::Mining),
+            (None, false, MinerStatus::Unknown),
+        ];
+        for (status, summary, expected) in cases {
+            let mut responses = HashMap::new();
+            if let Some(status) = status {
+                responses.insert(rpc("status"), Value::from_str(status)?);
+            }
+            if summary {
+                responses.insert(rpc("summary"), Value::from_str(SUMMARY_20230302_COMMAND)?);
+            }
+            let mock_api = MockAPIClient::new(responses);
+            let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+            let miner_data = miner.parse_data(collector.collect_all().await);
+            assert_eq!(miner_data.status, expected, "{status:?}, summary {summary}");
+            assert_eq!(miner_data.is_mining, expected.is_mining());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_env_temperature() -> Result<()> {
+        let miner = WhatsMinerV2::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+        );
+        let summary_command: MinerCommand = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+        let cases = [
+            (SUMMARY_ENV_NEGATIVE_COMMAND, Some(-12.5), None),
+            (
+                SUMMARY_ENV_FAULT_COMMAND,
+                None,
+                Some(MessageSeverity::Warning),
+            ),
+            // without an env temperature the lowest chip temperature stands in
+            (
+                SUMMARY_20230302_COMMAND,
+                Some(71.0),
+                Some(MessageSeverity::Info),
+            ),
+        ];
+
+        let responses = cases
+            .iter()
+            .map(|(summary, _, _)| Value::from_str(summary).map(MockResponse::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mock_api = MockAPIClient::new(HashMap::new()).with_sequence(summary_command, responses);
+
+        // each collection gets the summary of the next case
+        for (_, temperature, severity) in cases {
+            let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+            let data = collector
+                .collect(&[DataField::FluidTemperature, DataField::Messages])
+                .await;
+
+            assert_eq!(
+                miner.parse_fluid_temperature(&data),
+                temperature.map(Temperature::from_celsius)
+            );
+            let severities: Vec<_> = miner
+                .parse_messages(&data)
+                .into_iter()
+                .filter(|m| m.code == 0)
+                .map(|m| m.severity)
+                .collect();
+            assert_eq!(severities, Vec::from_iter(severity));
+        }
+        mock_api.assert_call_count("summary", 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_env_fault_message() -> Result<()> {
+        let miner = WhatsMinerV2::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+        );
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            MinerCommand::RPC {
+                command: "summary",
+                parameters: None,
+            },
+            Value::from_str(SUMMARY_ENV_FAULT_COMMAND)?,
+        )]));
+
+        // the fault is reported without collecting the fluid temperature
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect(&[DataField::Messages]).await;
+        assert!(miner.parse_messages(&data).iter().any(|m| m.code == 0
+            && m.severity == MessageSeverity::Warning
+            && m.message.starts_with("Env temperature sensor fault")));
+
+        Ok(())
+    }
+
+    fn responses_20230302() -> Result<HashMap<MinerCommand, Value>> {
+        let rpc = |command| MinerCommand::RPC {
+            command,
+            parameters: None,
+        };
+        Ok(HashMap::from([
+            (rpc("summary"), Value::from_str(SUMMARY_20230302_COMMAND)?),
+            (rpc("devs"), Value::from_str(DEVS_20230302_COMMAND)?),
+            (
+                rpc("devdetails"),
+                Value::from_str(DEVDETAILS_20230302_COMMAND)?,
+            ),
+            (rpc("pools"), Value::from_str(POOLS_20230302_COMMAND)?),
+            (rpc("status"), Value::from_str(STATUS_20230302_COMMAND)?),
+            (
+                rpc("get_version"),
+                Value::from_str(GET_VERSION_20230302_COMMAND)?,
+            ),
+            (rpc("get_psu"), Value::from_str(GET_PSU_20230302_COMMAND)?),
+            (
+                rpc("get_miner_info"),
+                Value::from_str(GET_MINER_INFO_20230302_COMMAND)?,
+            ),
+            (
+                rpc("get_error_code"),
+                Value::from_str(GET_ERROR_CODE_20230302_COMMAND)?,
+            ),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_data_parsers() -> Result<()> {
+        let miner = WhatsMinerV2::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M50SVH20),
+        );
+        let mock_api = MockAPIClient::new(responses_20230302()?);
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+        assert_hashrate_magnitudes(&miner_data);
+
+        let serials: Vec<_> = miner_data
+            .hashboards
+            .iter()
+            .map(|b| b.serial_number.as_deref())
+            .collect();
+        assert_eq!(
+            serials,
+            vec![
+                Some("HEM30SPPVE40K2310014"),
+                Some("HEM30SPPVE40K2310027"),
+                Some("HEM30SPPVE40K2310031")
+            ]
+        );
+        let chips: Vec<_> = miner_data
+            .hashboards
+            .iter()
+            .map(|b| b.working_chips)
+            .collect();
+        assert_eq!(chips, vec![Some(215), Some(215), Some(214)]);
+        assert_eq!(miner_data.total_chips, Some(644));
+        assert_eq!(
+            miner_data.psu_fans,
+            vec![FanData {
+                position: 0,
+                rpm: Some(AngularVelocity::from_rpm(9060.0)),
+            }]
+        );
+        // `get_psu` reports the input in hundredths of a volt and an amp
+        let input = miner_data.psu_input().unwrap();
+        assert_eq!(input.voltage.map(|v| v.as_volts()), Some(223.75));
+        assert_eq!(input.current.map(|c| c.as_amperes()), Some(15.68));
+        assert_eq!(input.power, None);
+        assert_eq!(miner_data.light_flashing, Some(false));
+        assert_eq!(miner_data.work_mode, Some(WorkMode::Normal));
+        assert_eq!(
+            miner_data.fan_control,
+            Some(FanControl {
+                mode: FanMode::Auto,
+                target_pct: None,
+            })
+        );
+        assert_eq!(miner_data.hostname.as_deref(), Some("WhatsMiner"));
+        assert_eq!(miner_data.pools.len(), 3);
+        assert_eq!(miner_data.status, MinerStatus::Mining);
+
+        assert_fields_extracted(&miner, responses_20230302()?, &[]).await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v2_v3_parity() -> Result<()> {
+        let model = MinerModel::WhatsMiner(WhatsMinerModel::M50SVH20);
+        let ip = IpAddr::from([127, 0, 0, 1]);
+
+        let v2 = WhatsMinerV2::new(ip, model.clone());
+        let mock_api = MockAPIClient::new(responses_20230302()?);
+        let mut collector = DataCollector::new_with_client(&v2, &mock_api);
+        let v2_data = v2.parse_data(collector.collect_all().await);
+
+        let v3 = WhatsMinerV3::new(ip, model);
+        let status = |parameter: &str| MinerCommand::RPC {
+            command: "get.miner.status",
+            parameters: Some(json!(parameter)),
+        };
+        let mock_api = MockAPIClient::new(HashMap::from([
+            (
+                MinerCommand::RPC {
+                    command: "get.device.info",
+                    parameters: None,
+                },
+                Value::from_str(GET_DEVICE_INFO_COMMAND)?,
+            ),
+            (status("summary"), Value::from_str(SUMMARY_COMMAND)?),
+            (
+                status("edevs"),
+                Value::from_str(GET_MINER_STATUS_EDEVS_COMMAND)?,
+            ),
+        ]));
+        let mut collector = DataCollector::new_with_client(&v3, &mock_api);
+        let v3_data = v3.parse_data(collector.collect_all().await);
+
+        let boards = |data: &MinerData| {
+            data.hashboards
+                .iter()
+                .map(|b| (b.position, b.serial_number.clone(), b.working_chips))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(boards(&v2_data), boards(&v3_data));
+        assert_eq!(v2_data.total_chips, v3_data.total_chips);
+        assert_eq!(v2_data.psu_fans, v3_data.psu_fans);
+        let input_voltage = |data: &MinerData| data.psu_input().and_then(|rail| rail.voltage);
+        assert_eq!(input_voltage(&v2_data), input_voltage(&v3_data));
+        assert_eq!(v2_data.light_flashing, v3_data.light_flashing);
+        assert_eq!(v2_data.mac, v3_data.mac);
+
+        Ok(())
     }
 }
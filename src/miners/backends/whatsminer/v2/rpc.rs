@@ -7,12 +7,17 @@ use ecb::cipher::block_padding::ZeroPadding;
 use md5crypt::md5crypt;
 use serde_json::{Value, json};
 use sha2::{Digest, Sha256};
-use std::net::IpAddr;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::net::{IpAddr, SocketAddr};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
 
 use crate::miners::api::rpc::errors::RPCError;
+use crate::miners::api::rpc::limit::ConnectionLimit;
+use crate::miners::api::rpc::reader::{self, RPCReadLimits};
 use crate::miners::api::rpc::status::RPCCommandStatus;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
+use crate::miners::backends::whatsminer::validate_password;
 use crate::miners::commands::MinerCommand;
 
 type Aes256EcbDec = ecb::Decryptor<Aes256>;
@@ -38,7 +43,10 @@ pub struct WhatsMinerRPCAPI {
     ip: IpAddr,
     port: u16,
     user: String,
-    password: String,
+    password: RwLock<String>,
+    pub(crate) policy: CommandPolicy,
+    pub(crate) connections: ConnectionLimit,
+    read_limits: RPCReadLimits,
 }
 
 #[async_trait]
@@ -48,10 +56,7 @@ impl APIClient for WhatsMinerRPCAPI {
             MinerCommand::RPC {
                 command,
                 parameters,
-            } => self
-                .send_command(command, false, parameters.clone())
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } => self.send_command(command, false, parameters.clone()).await,
             _ => Err(anyhow!("Cannot send non RPC command to RPC API")),
         }
     }
@@ -81,7 +86,7 @@ fn aes_ecb_enc(key: &str, data: &str) -> String {
     BASE64_STANDARD.encode(enc).replace('\n', "")
 }
 
-fn aes_ecb_dec(key: &str, data: &str) -> String {
+pub(super) fn aes_ecb_dec(key: &str, data: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(key.as_bytes());
     let hashed_key = format!("{:x}", hasher.finalize());
@@ -136,10 +141,12 @@ impl RPCAPIClient for WhatsMinerRPCAPI {
         _privileged: bool,
         parameters: Option<Value>,
     ) -> Result<Value> {
+        self.policy.check(command)?;
         if _privileged || command.starts_with("set_") {
             return self.send_privileged_command(command, parameters).await;
         }
 
+        let _connection = self.connections.acquire().await;
         let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
             .await
             .map_err(|_| RPCError::ConnectionFailed)?;
@@ -164,8 +171,7 @@ impl RPCAPIClient for WhatsMinerRPCAPI {
 
         stream.write_all(json_bytes).await?;
 
-        let mut buffer = Vec::new();
-        stream.read_to_end(&mut buffer).await?;
+        let buffer = reader::read_to_end(&mut stream, &self.read_limits).await?;
 
         let response = String::from_utf8_lossy(&buffer)
             .into_owned()
@@ -181,10 +187,24 @@ impl WhatsMinerRPCAPI {
             ip,
             port: port.unwrap_or(4028),
             user: "admin".to_string(),
-            password: "admin".to_string(),
+            password: RwLock::new("admin".to_string()),
+            policy: CommandPolicy::default(),
+            connections: ConnectionLimit::serial(),
+            read_limits: RPCReadLimits::default(),
         }
     }
 
+    /// Bound the size of and idle time between reads of the miner's responses.
+    pub fn set_read_limits(&mut self, limits: RPCReadLimits) {
+        self.read_limits = limits;
+    }
+
+    /// Connect to `addr` instead of the miner's IP on the default port.
+    pub fn set_address(&mut self, addr: SocketAddr) {
+        self.ip = addr.ip();
+        self.port = addr.port();
+    }
+
     fn parse_rpc_result(&self, response: &str) -> Result<Value> {
         let status = RPCCommandStatus::from_btminer_v2(response)?;
         match status.into_result() {
@@ -200,7 +220,7 @@ impl WhatsMinerRPCAPI {
         self.parse_rpc_result(&result)
     }
 
-    async fn get_token_data(&self) -> Result<TokenData> {
+    async fn get_token_data(&self, password: &str) -> Result<TokenData> {
         let api_token = self.send_command("get_token", false, None).await?;
         let salt = api_token
             .get("Msg")
@@ -221,7 +241,7 @@ impl WhatsMinerRPCAPI {
             .as_str()
             .unwrap();
 
-        let crypted = md5crypt(self.password.as_bytes(), salt.as_bytes());
+        let crypted = md5crypt(password.as_bytes(), salt.as_bytes());
         let full_password = String::from_utf8_lossy(&crypted);
         let host_password_md5 = full_password.split("$").nth(3).unwrap();
 
@@ -243,12 +263,61 @@ impl WhatsMinerRPCAPI {
         command: &str,
         parameters: Option<Value>,
     ) -> Result<Value> {
-        let token_data = self.get_token_data().await?;
+        let password = self.password.read().await.clone();
+        self.send_privileged_command_as(command, parameters, &password, false)
+            .await
+    }
 
-        let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
+    /// Send a privileged command that restarts btminer to apply it.
+    ///
+    /// btminer may drop the connection before its reply is read, which is taken as the command
+    /// being accepted, and answers with [`Value::Null`].
+    pub async fn send_restarting_command(
+        &self,
+        command: &str,
+        parameters: Option<Value>,
+    ) -> Result<Value> {
+        let password = self.password.read().await.clone();
+        self.send_privileged_command_as(command, parameters, &password, true)
             .await
-            .map_err(|_| RPCError::ConnectionFailed)?;
+    }
+
+    /// Change the API password, switching this client over to `new` once the miner accepts it.
+    pub async fn set_password(&self, old: &str, new: &str) -> Result<Value> {
+        validate_password(new)?;
+
+        let result = self
+            .send_privileged_command_as(
+                "update_pwd",
+                Some(Self::password_parameters(old, new)),
+                old,
+                false,
+            )
+            .await?;
+
+        *self.password.write().await = new.to_string();
+        Ok(result)
+    }
+
+    /// The `update_pwd` parameters changing the password from `old` to `new`.
+    pub fn password_parameters(old: &str, new: &str) -> Value {
+        json!({"old": old, "new": new})
+    }
+
+    /// Build the encrypted request for a privileged command without sending it.
+    ///
+    /// This fetches a fresh token from the miner, which does not change any settings.
+    pub async fn encrypted_request(
+        &self,
+        command: &str,
+        parameters: Option<Value>,
+    ) -> Result<Value> {
+        let password = self.password.read().await.clone();
+        let token_data = self.get_token_data(&password).await?;
+        Ok(Self::encrypt_request(&token_data, command, parameters))
+    }
 
+    fn encrypt_request(token_data: &TokenData, command: &str, parameters: Option<Value>) -> Value {
         let request = match parameters {
             Some(Value::Object(mut obj)) => {
                 // Use the existing object as the base
@@ -266,14 +335,36 @@ impl WhatsMinerRPCAPI {
             }
         };
         let enc = aes_ecb_enc(&token_data.host_password_md5, &request.to_string());
-        let command = json!({"enc": 1, "data": enc});
-        let json_str = command.to_string();
+        json!({"enc": 1, "data": enc})
+    }
+
+    async fn send_privileged_command_as(
+        &self,
+        command: &str,
+        parameters: Option<Value>,
+        password: &str,
+        restarts: bool,
+    ) -> Result<Value> {
+        self.policy.check(command)?;
+        let token_data = self.get_token_data(password).await?;
+
+        let _connection = self.connections.acquire().await;
+        let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
+            .await
+            .map_err(|_| RPCError::ConnectionFailed)?;
+
+        let request = Self::encrypt_request(&token_data, command, parameters);
+        let json_str = request.to_string();
         let json_bytes = json_str.as_bytes();
 
         stream.write_all(json_bytes).await?;
 
-        let mut buffer = Vec::new();
-        stream.read_to_end(&mut buffer).await?;
+        let buffer = match reader::read_to_end(&mut stream, &self.read_limits).await {
+            // the request was written, btminer went down to restart before replying
+            Ok(buffer) if restarts && buffer.is_empty() => return Ok(Value::Null),
+            Err(RPCError::ConnectionFailed) if restarts => return Ok(Value::Null),
+            result => result?,
+        };
 
         let response = String::from_utf8_lossy(&buffer)
             .into_owned()
@@ -1,35 +1,106 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 use async_trait::async_trait;
 use macaddr::MacAddr;
-use measurements::{AngularVelocity, Frequency, Power, Temperature};
+use measurements::{AngularVelocity, Frequency, Power, Temperature, Voltage};
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::str::FromStr;
 use std::time::Duration;
 
-use crate::data::board::BoardData;
-use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerModel};
-use crate::data::device::{MinerControlBoard, MinerMake};
-use crate::data::fan::FanData;
+use crate::data::board::{BoardData, BoardStatus};
+use crate::data::device::{ControlBoardType, MinerControlBoard, MinerMake, PowerLimits};
+use crate::data::device::{DeviceInfo, HashAlgorithm, MinerFirmware, MinerHardware, MinerModel};
+use crate::data::fan::{FanControl, FanData, FanMode};
 use crate::data::hashrate::{HashRate, HashRateUnit};
-use crate::data::pool::{PoolData, PoolURL};
+use crate::data::health::HealthThresholds;
+use crate::data::message::MinerMessage;
+use crate::data::miner::{MinerStatus, WorkMode};
+use crate::data::pool::{PoolConfig, PoolData, PoolURL};
+use crate::data::psu::PsuRail;
+use crate::data::sanitize::PlausibilityRanges;
+use crate::miners::api::rpc::reader::RPCReadLimits;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::dry_run::{DryRun, REDACTED};
+use crate::miners::backends::errors::ControlError;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
+use crate::miners::backends::whatsminer::{
+    EnvTemperature, error_codes, led_flashing, parse_power_mode, power_mode_name, validate_password,
+};
 use crate::miners::commands::MinerCommand;
 use crate::miners::data::{
-    DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, get_by_key,
-    get_by_pointer,
+    CommandGate, DataCollector, DataExtensions, DataExtractor, DataField, DataLocation, FieldData,
+    FromValue, get_by_key, get_by_pointer,
 };
 
 pub(crate) use rpc::WhatsMinerRPCAPI;
 
 mod rpc;
 
+/// Pool slots of btminer, `set.miner.pools` always writes all of them.
+const POOL_SLOTS: usize = 3;
+
+/// Keywords of the `edevs` board status strings, checked in order against the lowercased status.
+const BOARD_STATUSES: &[(&str, BoardStatus)] = &[
+    ("temp", BoardStatus::ThermalThrottled),
+    ("hot", BoardStatus::ThermalThrottled),
+    ("power", BoardStatus::PowerLimited),
+    ("limit", BoardStatus::PowerLimited),
+    ("init", BoardStatus::Initializing),
+    ("upfreq", BoardStatus::Initializing),
+    ("start", BoardStatus::Initializing),
+    ("dead", BoardStatus::Failed),
+    ("fail", BoardStatus::Failed),
+    ("fault", BoardStatus::Failed),
+    ("error", BoardStatus::Failed),
+    ("alive", BoardStatus::Normal),
+    ("normal", BoardStatus::Normal),
+    ("running", BoardStatus::Normal),
+];
+
+/// The state of one `edevs` board, from its status string and temperature protection flag.
+///
+/// btminer keeps reporting `alive` for boards it has throttled, so the `temp-protect` flag wins
+/// over the status string, and a healthy board counts as initializing until the miner-wide
+/// frequency ramp reports complete.
+fn board_status(edev: &Value, upfreq_complete: Option<bool>) -> Option<BoardStatus> {
+    let status = edev
+        .get("status")
+        .and_then(Value::as_str)
+        .map(|status| status.trim().to_lowercase())
+        .filter(|status| !status.is_empty())
+        .map(|status| {
+            BOARD_STATUSES
+                .iter()
+                .find(|(keyword, _)| status.contains(keyword))
+                .map(|(_, board_status)| board_status.clone())
+                .unwrap_or(BoardStatus::Unknown(status))
+        });
+    let temp_protect = edev
+        .get("temp-protect")
+        .and_then(u64::from_value)
+        .is_some_and(|flag| flag != 0);
+
+    match status {
+        Some(BoardStatus::Failed) => Some(BoardStatus::Failed),
+        _ if temp_protect => Some(BoardStatus::ThermalThrottled),
+        Some(BoardStatus::Normal) if upfreq_complete == Some(false) => {
+            Some(BoardStatus::Initializing)
+        }
+        status => status,
+    }
+}
+
 #[derive(Debug)]
 pub struct WhatsMinerV3 {
     pub ip: IpAddr,
     pub rpc: WhatsMinerRPCAPI,
     pub device_info: DeviceInfo,
+    pub labels: Option<HashMap<String, String>>,
+    pub health_thresholds: HealthThresholds,
+    plausibility_ranges: PlausibilityRanges,
+    dry_run: Option<DryRun>,
 }
 
 impl WhatsMinerV3 {
@@ -43,8 +114,96 @@ impl WhatsMinerV3 {
                 MinerFirmware::Stock,
                 HashAlgorithm::SHA256,
             ),
+            labels: None,
+            health_thresholds: HealthThresholds::default(),
+            plausibility_ranges: PlausibilityRanges::default(),
+            dry_run: None,
         }
     }
+
+    /// Reuse one RPC connection for all commands of a data collection, on by default.
+    pub fn set_keepalive(&mut self, keepalive: bool) {
+        self.rpc.set_keepalive(keepalive);
+    }
+
+    /// Replace the pools, index 0 being the primary, and read them back to confirm the order.
+    ///
+    /// `set.miner.pools` replaces the whole pool array, so all slots are written and the ones
+    /// past the given pools are cleared. Duplicate pools are rejected before anything is sent.
+    pub async fn set_pools_confirmed(&self, pools: Vec<PoolConfig>) -> Result<PoolUpdate> {
+        validate_pool_count(&pools, POOL_SLOTS)?;
+        validate_unique_pools(&pools)?;
+        let empty = json!({"pool": "", "worker": "", "passwd": ""});
+        let param: Vec<Value> = pools
+            .iter()
+            .map(|pool| {
+                json!({
+                    "pool": pool.url.to_string(),
+                    "worker": pool.user,
+                    "passwd": pool.password_or_default(),
+                })
+            })
+            .chain(std::iter::repeat(empty))
+            .take(POOL_SLOTS)
+            .collect();
+        let sent = execute_control(self, "set.miner.pools", json!({ "param": pools }), async {
+            accepted(
+                self.rpc
+                    .send_command("set.miner.pools", true, Some(json!({ "param": param })))
+                    .await,
+            )
+        })
+        .await?;
+        if !sent {
+            return Ok(PoolUpdate {
+                rejected: pools,
+                ..Default::default()
+            });
+        }
+        if self.get_dry_run().is_some() {
+            return Ok(PoolUpdate::default());
+        }
+
+        let response = self
+            .rpc
+            .send_command("get.miner.status", false, Some(json!("pools")))
+            .await?;
+        let data = FieldData::from([(
+            DataField::Pools,
+            response.pointer("/msg/pools").cloned().unwrap_or_default(),
+        )]);
+        Ok(PoolUpdate::compare(&pools, &self.parse_pools(&data)))
+    }
+
+    /// The power mode btminer is set to, read from `get.miner.setting`.
+    pub async fn get_power_mode(&self) -> Option<WorkMode> {
+        self.get_work_mode().await
+    }
+
+    /// Switch btminer to one of its power modes, which unlike a power limit only picks between
+    /// settings that are safe for the model. [`WorkMode::Sleep`] is rejected.
+    ///
+    /// btminer restarts to apply the mode, so the connection may drop right after the command is
+    /// accepted, and the uptime starts over.
+    pub async fn set_power_mode(&self, mode: WorkMode) -> Result<bool> {
+        let parameters = json!(power_mode_name(mode)?);
+        execute_control(self, "set.miner.power_mode", parameters.clone(), async {
+            accepted(
+                self.rpc
+                    .send_restarting_command("set.miner.power_mode", Some(parameters))
+                    .await,
+            )
+        })
+        .await
+    }
+
+    async fn execute_privileged(&self, command: &str, parameters: Option<Value>) -> Result<bool> {
+        let payload = parameters.clone().unwrap_or(Value::Null);
+        execute_control(self, command, payload, async {
+            accepted(self.rpc.send_command(command, true, parameters).await)
+        })
+        .await
+    }
 }
 
 #[async_trait]
@@ -55,6 +214,25 @@ impl APIClient for WhatsMinerV3 {
             _ => Err(anyhow!("Unsupported command type for WhatsMiner API")),
         }
     }
+    async fn end_session(&self) {
+        self.rpc.end_session().await
+    }
+}
+
+/// Skips `get.miner.status` once `get.device.info` says the miner is powered off, since btminer
+/// stops answering it while the device info keeps working.
+fn skip_while_powered_off(device_info: &Value, command: &MinerCommand) -> bool {
+    let off = device_info
+        .pointer("/msg/miner/working")
+        .and_then(Value::as_str)
+        == Some("false");
+    off && matches!(
+        command,
+        MinerCommand::RPC {
+            command: "get.miner.status",
+            ..
+        }
+    )
 }
 
 impl GetDataLocations for WhatsMinerV3 {
@@ -75,6 +253,14 @@ impl GetDataLocations for WhatsMinerV3 {
             command: "get.miner.status",
             parameters: Some(json!("edevs")),
         };
+        let get_miner_setting_cmd: MinerCommand = MinerCommand::RPC {
+            command: "get.miner.setting",
+            parameters: None,
+        };
+        let get_fan_setting_cmd: MinerCommand = MinerCommand::RPC {
+            command: "get.fan.setting",
+            parameters: None,
+        };
 
         match data_field {
             DataField::Mac => vec![(
@@ -85,6 +271,14 @@ impl GetDataLocations for WhatsMinerV3 {
                     tag: None,
                 },
             )],
+            DataField::ReportedIp => vec![(
+                get_device_info_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/msg/network/ip"),
+                    tag: None,
+                },
+            )],
             DataField::ApiVersion => vec![(
                 get_device_info_cmd,
                 DataExtractor {
@@ -141,6 +335,22 @@ impl GetDataLocations for WhatsMinerV3 {
                     tag: None,
                 },
             )],
+            DataField::WorkMode => vec![(
+                get_miner_setting_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/msg/power-mode"),
+                    tag: None,
+                },
+            )],
+            DataField::FanControl => vec![(
+                get_fan_setting_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/msg/fan-zero-speed"),
+                    tag: None,
+                },
+            )],
             DataField::Fans => vec![(
                 get_miner_status_summary_cmd,
                 DataExtractor {
@@ -157,6 +367,14 @@ impl GetDataLocations for WhatsMinerV3 {
                     tag: None,
                 },
             )],
+            DataField::PsuRails => vec![(
+                get_device_info_cmd,
+                DataExtractor {
+                    func: get_by_pointer,
+                    key: Some("/msg/power"),
+                    tag: None,
+                },
+            )],
             DataField::Hashboards => vec![
                 (
                     get_device_info_cmd,
@@ -174,6 +392,14 @@ impl GetDataLocations for WhatsMinerV3 {
                         tag: None,
                     },
                 ),
+                (
+                    get_miner_status_summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/msg/summary/upfreq-complete"),
+                        tag: Some("upfreq-complete"),
+                    },
+                ),
             ],
             DataField::Pools => vec![(
                 get_miner_status_pools_cmd,
@@ -207,25 +433,98 @@ impl GetDataLocations for WhatsMinerV3 {
                     tag: None,
                 },
             )],
-            DataField::ExpectedHashrate => vec![(
+            DataField::AverageHashrate => vec![(
                 get_miner_status_summary_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/msg/summary/factory-hash"),
+                    key: Some("/msg/summary/hash-5min"),
                     tag: None,
                 },
             )],
-            DataField::FluidTemperature => vec![(
+            DataField::ExpectedHashrate => vec![(
                 get_miner_status_summary_cmd,
                 DataExtractor {
                     func: get_by_pointer,
-                    key: Some("/msg/summary/environment-temperature"),
+                    key: Some("/msg/summary/factory-hash"),
                     tag: None,
                 },
             )],
+            DataField::FluidTemperature => vec![
+                (
+                    get_miner_status_summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/msg/summary/environment-temperature"),
+                        tag: Some("env"),
+                    },
+                ),
+                (
+                    get_miner_status_summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/msg/summary/chip-temp-min"),
+                        tag: Some("chip_min"),
+                    },
+                ),
+            ],
+            DataField::IsMining => vec![
+                (
+                    get_device_info_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/msg/miner/working"),
+                        tag: Some("working"),
+                    },
+                ),
+                (
+                    get_miner_status_summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/msg/summary/hash-realtime"),
+                        tag: Some("hashrate"),
+                    },
+                ),
+            ],
+            // the env temperature fault or its stand-in is reported as a message too
+            DataField::Messages => vec![
+                (
+                    get_miner_status_summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/msg/summary/error-code"),
+                        tag: Some("error_code"),
+                    },
+                ),
+                (
+                    get_miner_status_summary_cmd.clone(),
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/msg/summary/environment-temperature"),
+                        tag: Some("env"),
+                    },
+                ),
+                (
+                    get_miner_status_summary_cmd,
+                    DataExtractor {
+                        func: get_by_pointer,
+                        key: Some("/msg/summary/chip-temp-min"),
+                        tag: Some("chip_min"),
+                    },
+                ),
+            ],
             _ => vec![],
         }
     }
+
+    fn command_gate(&self) -> Option<CommandGate> {
+        Some(CommandGate {
+            probe: MinerCommand::RPC {
+                command: "get.device.info",
+                parameters: None,
+            },
+            skip: skip_while_powered_off,
+        })
+    }
 }
 
 impl GetIP for WhatsMinerV3 {
@@ -235,7 +534,72 @@ impl GetIP for WhatsMinerV3 {
 }
 impl GetDeviceInfo for WhatsMinerV3 {
     fn get_device_info(&self) -> DeviceInfo {
-        self.device_info
+        self.device_info.clone()
+    }
+    fn set_hardware(&mut self, hardware: MinerHardware) {
+        self.device_info.hardware = hardware;
+    }
+}
+
+impl GetLabels for WhatsMinerV3 {
+    fn get_labels(&self) -> Option<HashMap<String, String>> {
+        self.labels.clone()
+    }
+    fn set_labels(&mut self, labels: Option<HashMap<String, String>>) {
+        self.labels = labels;
+    }
+}
+
+impl GetHealthThresholds for WhatsMinerV3 {
+    fn get_health_thresholds(&self) -> HealthThresholds {
+        self.health_thresholds
+    }
+    fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health_thresholds = thresholds;
+    }
+}
+
+impl GetPlausibilityRanges for WhatsMinerV3 {
+    fn get_plausibility_ranges(&self) -> &PlausibilityRanges {
+        &self.plausibility_ranges
+    }
+    fn set_plausibility_ranges(&mut self, ranges: PlausibilityRanges) {
+        self.plausibility_ranges = ranges;
+    }
+}
+
+impl DryRunControl for WhatsMinerV3 {
+    fn get_dry_run(&self) -> Option<DryRun> {
+        self.dry_run.clone()
+    }
+    fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl CommandPolicyControl for WhatsMinerV3 {
+    fn get_command_policy(&self) -> CommandPolicy {
+        self.rpc.policy.clone()
+    }
+    fn set_command_policy(&mut self, policy: CommandPolicy) {
+        let policy = policy.for_miner(self);
+        self.rpc.policy = policy;
+    }
+}
+
+impl GetCapabilities for WhatsMinerV3 {
+    fn capabilities(&self) -> MinerCapabilities {
+        MinerCapabilities {
+            supports_fault_light: true,
+            supports_power_limit: true,
+            supports_password: true,
+            supports_hostname: true,
+            supports_pool_update: true,
+            supports_restart: true,
+            supports_pause: true,
+            has_psu_telemetry: true,
+            ..Default::default()
+        }
     }
 }
 
@@ -246,41 +610,53 @@ impl CollectData for WhatsMinerV3 {
 }
 
 impl GetMAC for WhatsMinerV3 {
-    fn parse_mac(&self, data: &HashMap<DataField, Value>) -> Option<MacAddr> {
+    fn parse_mac(&self, data: &FieldData) -> Option<MacAddr> {
         data.extract::<String>(DataField::Mac)
-            .and_then(|s| MacAddr::from_str(&s).ok())
+            .and_then(|s| parse_mac_str(&s))
+    }
+}
+
+impl GetReportedIp for WhatsMinerV3 {
+    fn parse_reported_ip(&self, data: &FieldData) -> Option<IpAddr> {
+        data.extract::<String>(DataField::ReportedIp)
+            .and_then(|s| parse_ip_str(&s))
     }
 }
 
 impl GetSerialNumber for WhatsMinerV3 {}
 impl GetHostname for WhatsMinerV3 {
-    fn parse_hostname(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_hostname(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::Hostname)
     }
 }
 impl GetApiVersion for WhatsMinerV3 {
-    fn parse_api_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_api_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::ApiVersion)
     }
 }
 impl GetFirmwareVersion for WhatsMinerV3 {
-    fn parse_firmware_version(&self, data: &HashMap<DataField, Value>) -> Option<String> {
+    fn parse_firmware_version(&self, data: &FieldData) -> Option<String> {
         data.extract::<String>(DataField::FirmwareVersion)
     }
 }
 impl GetControlBoardVersion for WhatsMinerV3 {
-    fn parse_control_board_version(
-        &self,
-        data: &HashMap<DataField, Value>,
-    ) -> Option<MinerControlBoard> {
+    fn parse_control_board_version(&self, data: &FieldData) -> Option<MinerControlBoard> {
         data.extract::<String>(DataField::ControlBoardVersion)
             .and_then(|s| MinerControlBoard::from_str(&s).ok())
     }
+
+    fn parse_control_board_type(&self, data: &FieldData) -> Option<ControlBoardType> {
+        data.extract::<String>(DataField::ControlBoardVersion)
+            .and_then(|s| s.parse().ok())
+    }
 }
 impl GetHashboards for WhatsMinerV3 {
-    fn parse_hashboards(&self, data: &HashMap<DataField, Value>) -> Vec<BoardData> {
+    fn parse_hashboards(&self, data: &FieldData) -> Vec<BoardData> {
         let mut hashboards: Vec<BoardData> = Vec::new();
         let board_count = self.device_info.hardware.boards.unwrap_or(3);
+        let upfreq_complete = data
+            .extract_nested::<u64>(DataField::Hashboards, "upfreq-complete")
+            .map(|complete| complete != 0);
         for idx in 0..board_count {
             let hashrate = data
                 .get(&DataField::Hashboards)
@@ -330,6 +706,10 @@ impl GetHashboards for WhatsMinerV3 {
                 .map(Frequency::from_megahertz);
 
             let active = Some(hashrate.clone().map(|h| h.value).unwrap_or(0f64) > 0f64);
+            let status = data
+                .get(&DataField::Hashboards)
+                .and_then(|val| val.pointer(&format!("/edevs/{idx}")))
+                .and_then(|edev| board_status(edev, upfreq_complete));
             hashboards.push(BoardData {
                 hashrate,
                 position: idx,
@@ -341,17 +721,21 @@ impl GetHashboards for WhatsMinerV3 {
                 working_chips,
                 serial_number,
                 chips: vec![],
+                sensor_temperatures: vec![],
                 voltage: None, // TODO
                 frequency,
+                wattage: None,
                 tuned: Some(true),
                 active,
+                status,
+                extra: None,
             });
         }
         hashboards
     }
 }
 impl GetHashrate for WhatsMinerV3 {
-    fn parse_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::Hashrate, |f| HashRate {
             value: f,
             unit: HashRateUnit::TeraHash,
@@ -360,7 +744,7 @@ impl GetHashrate for WhatsMinerV3 {
     }
 }
 impl GetExpectedHashrate for WhatsMinerV3 {
-    fn parse_expected_hashrate(&self, data: &HashMap<DataField, Value>) -> Option<HashRate> {
+    fn parse_expected_hashrate(&self, data: &FieldData) -> Option<HashRate> {
         data.extract_map::<f64, _>(DataField::ExpectedHashrate, |f| HashRate {
             value: f,
             unit: HashRateUnit::TeraHash,
@@ -368,8 +752,17 @@ impl GetExpectedHashrate for WhatsMinerV3 {
         })
     }
 }
+impl GetAverageHashrate for WhatsMinerV3 {
+    fn parse_average_hashrate(&self, data: &FieldData) -> Option<HashRate> {
+        data.extract_map::<f64, _>(DataField::AverageHashrate, |f| HashRate {
+            value: f,
+            unit: HashRateUnit::TeraHash,
+            algo: String::from("SHA256"),
+        })
+    }
+}
 impl GetFans for WhatsMinerV3 {
-    fn parse_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut fans: Vec<FanData> = Vec::new();
         for (idx, direction) in ["in", "out"].iter().enumerate() {
             let fan = data.extract_nested_map::<f64, _>(
@@ -388,7 +781,7 @@ impl GetFans for WhatsMinerV3 {
     }
 }
 impl GetPsuFans for WhatsMinerV3 {
-    fn parse_psu_fans(&self, data: &HashMap<DataField, Value>) -> Vec<FanData> {
+    fn parse_psu_fans(&self, data: &FieldData) -> Vec<FanData> {
         let mut psu_fans: Vec<FanData> = Vec::new();
 
         let psu_fan = data.extract_map::<f64, _>(DataField::PsuFans, |rpm| FanData {
@@ -401,36 +794,116 @@ impl GetPsuFans for WhatsMinerV3 {
         psu_fans
     }
 }
+
+impl GetFanControl for WhatsMinerV3 {
+    /// Configured, from the zero speed setting in `get.fan.setting`, which stops the fans for
+    /// immersion. Otherwise btminer always regulates the fans itself.
+    fn parse_fan_control(&self, data: &FieldData) -> Option<FanControl> {
+        let zero_speed = data
+            .extract::<String>(DataField::FanControl)
+            .map(|setting| setting == "enable")
+            .or_else(|| data.extract::<bool>(DataField::FanControl))?;
+        Some(FanControl {
+            mode: match zero_speed {
+                true => FanMode::Off,
+                false => FanMode::Auto,
+            },
+            target_pct: None,
+        })
+    }
+}
+
+impl GetPsu for WhatsMinerV3 {}
+
+impl GetPsuRails for WhatsMinerV3 {
+    fn parse_psu_rails(&self, data: &FieldData) -> Vec<PsuRail> {
+        let reading = |key| data.extract_nested::<f64>(DataField::PsuRails, key);
+        PsuRail::input(reading("vin"), reading("iin"), reading("pin"))
+            .into_iter()
+            .collect()
+    }
+}
 impl GetFluidTemperature for WhatsMinerV3 {
-    fn parse_fluid_temperature(&self, data: &HashMap<DataField, Value>) -> Option<Temperature> {
-        data.extract_map::<f64, _>(DataField::FluidTemperature, Temperature::from_celsius)
+    fn parse_fluid_temperature(&self, data: &FieldData) -> Option<Temperature> {
+        EnvTemperature::from_data(data, DataField::FluidTemperature)
+            .and_then(EnvTemperature::temperature)
     }
 }
+
+impl GetFluidFlow for WhatsMinerV3 {}
+
+impl GetFluidPressure for WhatsMinerV3 {}
+
 impl GetWattage for WhatsMinerV3 {
-    fn parse_wattage(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<f64, _>(DataField::Wattage, Power::from_watts)
     }
 }
 impl GetWattageLimit for WhatsMinerV3 {
-    fn parse_wattage_limit(&self, data: &HashMap<DataField, Value>) -> Option<Power> {
+    fn parse_wattage_limit(&self, data: &FieldData) -> Option<Power> {
         data.extract_map::<String, _>(DataField::WattageLimit, |p| p.parse::<f64>().ok())?
             .map(Power::from_watts)
     }
 }
+impl GetFrequency for WhatsMinerV3 {}
+impl GetVoltage for WhatsMinerV3 {}
+impl GetWorkMode for WhatsMinerV3 {
+    fn parse_work_mode(&self, data: &FieldData) -> Option<WorkMode> {
+        data.extract::<String>(DataField::WorkMode)
+            .and_then(|mode| parse_power_mode(&mode))
+    }
+}
+
+impl GetAutotune for WhatsMinerV3 {}
+
 impl GetLightFlashing for WhatsMinerV3 {
-    fn parse_light_flashing(&self, data: &HashMap<DataField, Value>) -> Option<bool> {
-        data.extract_map::<String, _>(DataField::LightFlashing, |l| l != "auto")
+    fn parse_light_flashing(&self, data: &FieldData) -> Option<bool> {
+        data.extract::<String>(DataField::LightFlashing)
+            .and_then(|status| led_flashing(&status))
+    }
+}
+impl GetMessages for WhatsMinerV3 {
+    fn parse_messages(&self, data: &FieldData) -> Vec<MinerMessage> {
+        let mut messages = data
+            .get(&DataField::Messages)
+            .and_then(|messages| messages.get("error_code"))
+            .map(error_codes::messages_from_value)
+            .unwrap_or_default();
+        messages.extend(
+            EnvTemperature::from_data(data, DataField::Messages).and_then(EnvTemperature::message),
+        );
+        messages
     }
 }
-impl GetMessages for WhatsMinerV3 {}
 impl GetUptime for WhatsMinerV3 {
-    fn parse_uptime(&self, data: &HashMap<DataField, Value>) -> Option<Duration> {
+    fn parse_uptime(&self, data: &FieldData) -> Option<Duration> {
         data.extract_map::<u64, _>(DataField::Uptime, Duration::from_secs)
     }
 }
-impl GetIsMining for WhatsMinerV3 {}
+impl GetIsMining for WhatsMinerV3 {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.parse_status(data).is_mining()
+    }
+}
+impl GetStatus for WhatsMinerV3 {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        let Some(status) = data.get(&DataField::IsMining) else {
+            return MinerStatus::Unknown;
+        };
+        let working = status["working"].as_str().map(|w| w == "true");
+        let hashrate = status["hashrate"].as_f64();
+
+        match (working, hashrate) {
+            (Some(false), _) => MinerStatus::Paused,
+            (_, Some(hr)) if hr > 0.0 => MinerStatus::Mining,
+            (_, Some(_)) => MinerStatus::Failure,
+            (Some(true), None) => MinerStatus::Mining,
+            (None, None) => MinerStatus::Unknown,
+        }
+    }
+}
 impl GetPools for WhatsMinerV3 {
-    fn parse_pools(&self, data: &HashMap<DataField, Value>) -> Vec<PoolData> {
+    fn parse_pools(&self, data: &FieldData) -> Vec<PoolData> {
         let mut pools: Vec<PoolData> = Vec::new();
         let pools_raw = data.get(&DataField::Pools);
         if let Some(pools_response) = pools_raw {
@@ -459,7 +932,10 @@ impl GetPools for WhatsMinerV3 {
                 let url = data
                     .get(&DataField::Pools)
                     .and_then(|val| val.pointer(&format!("/{idx}/url")))
-                    .map(|val| PoolURL::from(String::from(val.as_str().unwrap_or(""))));
+                    .and_then(|val| val.as_str())
+                    // cleared slots are reported with an empty URL
+                    .filter(|url| !url.is_empty())
+                    .and_then(PoolURL::parse);
 
                 pools.push(PoolData {
                     position: Some(idx as u16),
@@ -469,6 +945,11 @@ impl GetPools for WhatsMinerV3 {
                     active,
                     alive,
                     user,
+                    password: None,
+                    last_share_difficulty: None,
+                    last_share_time: None,
+                    group: None,
+                    quota: None,
                 });
             }
         }
@@ -479,38 +960,100 @@ impl GetPools for WhatsMinerV3 {
 #[async_trait]
 impl SetFaultLight for WhatsMinerV3 {
     async fn set_fault_light(&self, fault: bool) -> Result<bool> {
+        // "auto" hands the LEDs back to the miner's status display
         let parameters = match fault {
-            true => Some(json!("auto")),
-            false => Some(json!([{"color": "red", "period": 60, "duration": 20, "start": 0}])),
+            true => Some(json!([{"color": "red", "period": 60, "duration": 20, "start": 0}])),
+            false => Some(json!("auto")),
         };
 
-        let data = self
-            .rpc
-            .send_command("set.system.led", true, parameters)
-            .await;
-
-        Ok(data.is_ok())
+        let sent = self
+            .execute_privileged("set.system.led", parameters)
+            .await?;
+        Ok(confirm_fault_light(self, fault, sent).await)
     }
 }
 
 #[async_trait]
 impl SetPowerLimit for WhatsMinerV3 {
     async fn set_power_limit(&self, limit: Power) -> Result<bool> {
-        let data = self
-            .rpc
-            .send_command("set.miner.power_limit", true, Some(json!(limit)))
-            .await;
+        PowerLimits::validate_for(&self.device_info.model, limit)?;
+        self.set_power_limit_unchecked(limit).await
+    }
 
-        Ok(data.is_ok())
+    async fn set_power_limit_unchecked(&self, limit: Power) -> Result<bool> {
+        self.execute_privileged("set.miner.power_limit", Some(json!(limit)))
+            .await
+    }
+}
+
+impl SetCredentials for WhatsMinerV3 {}
+
+impl EndpointControl for WhatsMinerV3 {
+    fn set_endpoints(&mut self, endpoints: MinerEndpoints) {
+        if let Some(rpc) = endpoints.rpc {
+            self.rpc.set_address(rpc);
+        }
+    }
+}
+
+#[async_trait]
+impl SetPassword for WhatsMinerV3 {
+    async fn set_password(&self, old: &str, new: &str) -> Result<bool> {
+        validate_password(new)?;
+        let payload = self.rpc.password_parameters(REDACTED, REDACTED);
+        execute_control(self, "set.system.password", payload, async {
+            accepted(self.rpc.set_password(old, new).await)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SetHostname for WhatsMinerV3 {
+    async fn set_hostname(&self, hostname: &str) -> Result<HostnameChange> {
+        validate_hostname(hostname)?;
+        let parameters = json!({"param": hostname});
+        if !self
+            .execute_privileged("set.system.hostname", Some(parameters))
+            .await?
+        {
+            bail!("Failed to set hostname");
+        }
+        Ok(HostnameChange::Applied)
+    }
+}
+
+#[async_trait]
+impl SetPools for WhatsMinerV3 {
+    async fn set_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        Ok(self.set_pools_confirmed(pools).await?.is_applied())
+    }
+}
+
+#[async_trait]
+impl SetBoardTuning for WhatsMinerV3 {
+    #[allow(unused_variables)]
+    async fn set_board_tuning(
+        &self,
+        board: u8,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<bool> {
+        Err(ControlError::UnsupportedOperation(
+            "board tuning".to_string(),
+        ))?
     }
 }
 
 #[async_trait]
 impl Restart for WhatsMinerV3 {
-    async fn restart(&self) -> Result<bool> {
-        let data = self.rpc.send_command("set.system.reboot", true, None).await;
+    async fn restart_mining(&self) -> Result<bool> {
+        self.execute_privileged("set.miner.service", Some(json!("restart")))
+            .await
+    }
 
-        Ok(data.is_ok())
+    async fn reboot_system(&self) -> Result<bool> {
+        self.execute_privileged("set.system.reboot", None).await
     }
 }
 
@@ -519,23 +1062,574 @@ impl Pause for WhatsMinerV3 {
     async fn pause(&self, _at_time: Option<Duration>) -> Result<bool> {
         // might not work as intended, if issues are found then switch to "enable" + "disable"
         // see api docs - https://apidoc.whatsminer.com/#api-Miner-btminer_service_set
-        let data = self
-            .rpc
-            .send_command("set.miner.service", true, Some(json!("stop")))
-            .await;
-
-        Ok(data.is_ok())
+        self.execute_privileged("set.miner.service", Some(json!("stop")))
+            .await
     }
 }
 
 #[async_trait]
 impl Resume for WhatsMinerV3 {
     async fn resume(&self, _at_time: Option<Duration>) -> Result<bool> {
-        let data = self
-            .rpc
-            .send_command("set.miner.service", true, Some(json!("start")))
-            .await;
+        self.execute_privileged("set.miner.service", Some(json!("start")))
+            .await
+    }
+}
+
+impl SetRPCReadLimits for WhatsMinerV3 {
+    fn set_rpc_read_limits(&mut self, limits: RPCReadLimits) {
+        self.rpc.set_read_limits(limits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::whatsminer::WhatsMinerModel;
+    use crate::data::message::{MessageSeverity, MinerMessageKind};
+    use crate::miners::backends::errors::ControlError;
+    use crate::test::api::{MockAPIClient, MockResponse};
+    use crate::test::json::btminer::v3::{
+        GET_DEVICE_INFO_POWER_OFF_COMMAND, GET_FAN_SETTING_COMMAND, GET_MINER_SETTING_COMMAND,
+        GET_MINER_STATUS_EDEVS_STATUS_COMMAND, SUMMARY_COMMAND, SUMMARY_ENV_FAULT_COMMAND,
+        SUMMARY_ENV_MISSING_COMMAND, SUMMARY_ENV_NEGATIVE_COMMAND,
+    };
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_powered_off() -> Result<()> {
+        let miner = WhatsMinerV3::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30),
+        );
+        let device_info_command = MinerCommand::RPC {
+            command: "get.device.info",
+            parameters: None,
+        };
+        let summary_command = MinerCommand::RPC {
+            command: "get.miner.status",
+            parameters: Some(json!("summary")),
+        };
+        let setting_command = MinerCommand::RPC {
+            command: "get.miner.setting",
+            parameters: None,
+        };
+        let mock_api = MockAPIClient::new(HashMap::from([
+            (
+                device_info_command,
+                Value::from_str(GET_DEVICE_INFO_POWER_OFF_COMMAND)?,
+            ),
+            (setting_command, Value::from_str(GET_MINER_SETTING_COMMAND)?),
+        ]));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+
+        // the settings keep answering while btminer is powered off
+        assert_eq!(mock_api.calls().len(), 3);
+        mock_api.assert_called_once("get.device.info");
+        mock_api.assert_called_once("get.miner.setting");
+        mock_api.assert_called_once("get.fan.setting");
+        assert!(collector.stats().gated.contains(&summary_command));
+        assert_eq!(miner_data.work_mode, Some(WorkMode::LowPower));
+
+        assert_eq!(miner_data.status, MinerStatus::Paused);
+        assert!(!miner_data.is_mining);
+        assert_eq!(miner_data.uptime, None);
+        assert_eq!(miner_data.hashrate, None);
+        assert_eq!(
+            miner_data.mac,
+            Some(MacAddr::from_str("C8:11:0B:00:2A:6F")?)
+        );
+        assert_eq!(miner_data.api_version, Some("3.0.1".to_string()));
+        assert_eq!(
+            miner_data.firmware_version,
+            Some("20250214.16.1.REL".to_string())
+        );
+        assert_eq!(miner_data.hostname, Some("WhatsMiner".to_string()));
+        assert_eq!(miner_data.wattage_limit, Some(Power::from_watts(7000.0)));
+        assert_eq!(
+            miner_data.hashboards[0].serial_number,
+            Some("HEK2A3B45C67D8901".to_string())
+        );
+        // the feed is still live, the PSU just isn't drawing from it
+        let input = miner_data.psu_input().unwrap();
+        assert_eq!(input.voltage, Some(Voltage::from_volts(224.5)));
+        assert_eq!((input.current, input.power), (None, None));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_messages() -> Result<()> {
+        let miner = WhatsMinerV3::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30),
+        );
+        let summary_command: MinerCommand = MinerCommand::RPC {
+            command: "get.miner.status",
+            parameters: Some(json!("summary")),
+        };
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            summary_command,
+            Value::from_str(SUMMARY_COMMAND)?,
+        )]));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect(&[DataField::Messages]).await;
+        let messages = miner.parse_messages(&data);
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].code, 110);
+        assert_eq!(messages[0].message, "Intake fan speed error");
+        assert_eq!(messages[0].severity, MessageSeverity::Warning);
+        assert_eq!(messages[1].code, 9999);
+        assert_eq!(messages[1].message, "Unknown error code 9999");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_board_status() -> Result<()> {
+        let miner = WhatsMinerV3::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30),
+        );
+        let edevs_command = MinerCommand::RPC {
+            command: "get.miner.status",
+            parameters: Some(json!("edevs")),
+        };
+        let summary_command = MinerCommand::RPC {
+            command: "get.miner.status",
+            parameters: Some(json!("summary")),
+        };
+        let mock_api = MockAPIClient::new(HashMap::from([
+            (
+                edevs_command,
+                Value::from_str(GET_MINER_STATUS_EDEVS_STATUS_COMMAND)?,
+            ),
+            (summary_command, Value::from_str(SUMMARY_COMMAND)?),
+        ]));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let miner_data = miner.parse_data(collector.collect_all().await);
+
+        let statuses: Vec<_> = miner_data
+            .hashboards
+            .iter()
+            .map(|board| board.status.clone())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Some(BoardStatus::Initializing),
+                // btminer still reports the board alive while temperature protection holds it back
+                Some(BoardStatus::ThermalThrottled),
+                Some(BoardStatus::Failed),
+            ]
+        );
+
+        let throttled: Vec<_> = miner_data
+            .messages
+            .iter()
+            .filter(|m| m.message.starts_with("Hashboard"))
+            .collect();
+        assert_eq!(throttled.len(), 1);
+        assert_eq!(throttled[0].message, "Hashboard 1 is thermally throttled");
+        assert_eq!(throttled[0].severity, MessageSeverity::Warning);
+        assert_eq!(throttled[0].kind, MinerMessageKind::OverTemperature);
+
+        // healthy boards count as initializing until the frequency ramp completes
+        let alive = json!({ "status": "alive", "temp-protect": 0 });
+        assert_eq!(
+            board_status(&alive, Some(false)),
+            Some(BoardStatus::Initializing)
+        );
+        assert_eq!(board_status(&alive, Some(true)), Some(BoardStatus::Normal));
+        assert_eq!(
+            board_status(&json!({ "status": "power-limit" }), None),
+            Some(BoardStatus::PowerLimited)
+        );
+        assert_eq!(
+            board_status(&json!({ "status": "Sleeping" }), None),
+            Some(BoardStatus::Unknown("sleeping".to_string()))
+        );
+        assert_eq!(board_status(&json!({ "slot": 0 }), None), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_set_password_dry_run() -> Result<()> {
+        let mut miner = WhatsMinerV3::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30),
+        );
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+
+        let error = miner.set_password("admin", "too_long_1").await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        assert!(dry_run.actions().is_empty());
+
+        assert!(miner.set_password("admin", "secret_1").await?);
+        let action = &dry_run.actions()[0];
+        assert_eq!(action.command, "set.system.password");
+        assert_eq!(
+            action.payload,
+            json!({"param": {"account": "super", "old": REDACTED, "new": REDACTED}})
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_env_temperature() -> Result<()> {
+        let miner = WhatsMinerV3::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30),
+        );
+        let summary_command: MinerCommand = MinerCommand::RPC {
+            command: "get.miner.status",
+            parameters: Some(json!("summary")),
+        };
+        let cases = [
+            (SUMMARY_ENV_NEGATIVE_COMMAND, Some(-12.5), None),
+            (
+                SUMMARY_ENV_FAULT_COMMAND,
+                None,
+                Some(MessageSeverity::Warning),
+            ),
+            // without an env temperature the lowest chip temperature stands in
+            (
+                SUMMARY_ENV_MISSING_COMMAND,
+                Some(58.0),
+                Some(MessageSeverity::Info),
+            ),
+        ];
+
+        let responses = cases
+            .iter()
+            .map(|(summary, _, _)| Value::from_str(summary).map(MockResponse::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mock_api = MockAPIClient::new(HashMap::new()).with_sequence(summary_command, responses);
+
+        // each collection gets the summary of the next case
+        for (_, temperature, severity) in cases {
+            let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+            let data = collector
+                .collect(&[DataField::FluidTemperature, DataField::Messages])
+                .await;
+
+            assert_eq!(
+                miner.parse_fluid_temperature(&data),
+                temperature.map(Temperature::from_celsius)
+            );
+            let severities: Vec<_> = miner
+                .parse_messages(&data)
+                .into_iter()
+                .filter(|m| m.code == 0)
+                .map(|m| m.severity)
+                .collect();
+            assert_eq!(severities, Vec::from_iter(severity));
+        }
+        mock_api.assert_call_count("get.miner.status", 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_env_fault_message() -> Result<()> {
+        let miner = WhatsMinerV3::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30),
+        );
+        let mock_api = MockAPIClient::new(HashMap::from([(
+            MinerCommand::RPC {
+                command: "get.miner.status",
+                parameters: Some(json!("summary")),
+            },
+            Value::from_str(SUMMARY_ENV_FAULT_COMMAND)?,
+        )]));
+
+        // the fault is reported without collecting the fluid temperature
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect(&[DataField::Messages]).await;
+        assert!(miner.parse_messages(&data).iter().any(|m| m.code == 0
+            && m.severity == MessageSeverity::Warning
+            && m.message.starts_with("Env temperature sensor fault")));
+
+        Ok(())
+    }
+
+    /// Serve btminer v3 like firmware that restarts on every `set.` command, closing the
+    /// connection without a reply, and record the requests.
+    async fn serve_restarting() -> (u16, Arc<Mutex<Vec<Value>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut len = [0u8; 4];
+                if stream.read_exact(&mut len).await.is_err() {
+                    continue;
+                }
+                let mut request = vec![0u8; u32::from_le_bytes(len) as usize];
+                if stream.read_exact(&mut request).await.is_err() {
+                    continue;
+                }
+                let request: Value = serde_json::from_slice(&request).unwrap();
+                let restarts = request["cmd"].as_str().unwrap().starts_with("set.");
+                recorded.lock().unwrap().push(request);
+                if !restarts {
+                    let body = json!({"code": 0, "msg": {"salt": "abc"}}).to_string();
+                    let mut framed = (body.len() as u32).to_le_bytes().to_vec();
+                    framed.extend_from_slice(body.as_bytes());
+                    let _ = stream.write_all(&framed).await;
+                }
+            }
+        });
+        (port, requests)
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_fan_control() -> Result<()> {
+        let miner = WhatsMinerV3::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30),
+        );
+        let fan_setting = |response: Value| {
+            MockAPIClient::new(HashMap::from([(
+                MinerCommand::RPC {
+                    command: "get.fan.setting",
+                    parameters: None,
+                },
+                response,
+            )]))
+        };
+
+        let mock_api = fan_setting(Value::from_str(GET_FAN_SETTING_COMMAND)?);
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect(&[DataField::FanControl]).await;
+        assert_eq!(
+            miner.parse_fan_control(&data),
+            Some(FanControl {
+                mode: FanMode::Auto,
+                target_pct: None,
+            })
+        );
+
+        let mock_api = fan_setting(json!({"code": 0, "msg": {"fan-zero-speed": "enable"}}));
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect(&[DataField::FanControl]).await;
+        assert_eq!(
+            miner
+                .parse_data(data)
+                .fan_control
+                .map(|control| control.mode),
+            Some(FanMode::Off)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_set_power_mode() -> Result<()> {
+        let (port, requests) = serve_restarting().await;
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let mut miner = WhatsMinerV3::new(ip, MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30));
+        miner.rpc = WhatsMinerRPCAPI::new(ip, Some(port));
+
+        // btminer restarting before it replies still counts as accepted
+        assert!(miner.set_power_mode(WorkMode::LowPower).await?);
+        let sent = requests.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(sent["cmd"], "set.miner.power_mode");
+        assert_eq!(sent["param"], "low");
+        assert_eq!(sent["account"], "super");
+        assert!(sent["token"].is_string());
+
+        let error = miner.set_power_mode(WorkMode::Sleep).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+        assert!(miner.set_power_mode(WorkMode::HighPerformance).await?);
+        assert_eq!(dry_run.actions()[0].command, "set.miner.power_mode");
+        assert_eq!(dry_run.actions()[0].payload, json!("high"));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_power_limit_bounds() -> Result<()> {
+        let (port, requests) = serve_restarting().await;
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let mut miner = WhatsMinerV3::new(ip, MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30));
+        miner.rpc = WhatsMinerRPCAPI::new(ip, Some(port));
+
+        for watts in [800.0, 6000.0] {
+            let error = miner
+                .set_power_limit(Power::from_watts(watts))
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<ControlError>(),
+                Some(ControlError::InvalidParameter(_))
+            ));
+        }
+        assert!(requests.lock().unwrap().is_empty());
+
+        let dry_run = DryRun::new();
+        miner.set_dry_run(Some(dry_run.clone()));
+        assert!(miner.set_power_limit(Power::from_watts(3000.0)).await?);
+        assert!(
+            miner
+                .set_power_limit_unchecked(Power::from_watts(6000.0))
+                .await?
+        );
+        let commands: Vec<_> = dry_run.actions().into_iter().map(|a| a.command).collect();
+        assert_eq!(
+            commands,
+            vec!["set.miner.power_limit", "set.miner.power_limit"]
+        );
+        assert!(requests.lock().unwrap().is_empty());
+        Ok(())
+    }
+
+    /// Serve btminer v3 pool commands like firmware that drops Stratum V2 pools and rewrites
+    /// TLS pools to plain TCP, returning the stored pool slots.
+    async fn serve_pools(slots: Vec<Value>) -> (u16, Arc<Mutex<Vec<Value>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let stored = Arc::new(Mutex::new(slots));
+        let pools = stored.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let pools = pools.clone();
+                tokio::spawn(async move {
+                    let mut len = [0u8; 4];
+                    if stream.read_exact(&mut len).await.is_err() {
+                        return;
+                    }
+                    let mut request = vec![0u8; u32::from_le_bytes(len) as usize];
+                    if stream.read_exact(&mut request).await.is_err() {
+                        return;
+                    }
+                    let request: Value = serde_json::from_slice(&request).unwrap();
+                    let response = match request["cmd"].as_str() {
+                        Some("get.device.info") => json!({"code": 0, "msg": {"salt": "abc"}}),
+                        Some("set.miner.pools") => {
+                            let written = request["param"].as_array().unwrap().iter().map(|p| {
+                                let url = p["pool"].as_str().unwrap();
+                                match url {
+                                    u if u.starts_with("stratum2+tcp") => {
+                                        json!({"pool": "", "worker": "", "passwd": ""})
+                                    }
+                                    u => {
+                                        let mut pool = p.clone();
+                                        pool["pool"] =
+                                            json!(u.replace("stratum+ssl", "stratum+tcp"));
+                                        pool
+                                    }
+                                }
+                            });
+                            *pools.lock().unwrap() = written.collect();
+                            json!({"code": 0, "msg": "ok"})
+                        }
+                        _ => {
+                            let status: Vec<Value> = pools
+                                .lock()
+                                .unwrap()
+                                .iter()
+                                .enumerate()
+                                .map(|(idx, p)| {
+                                    json!({
+                                        "id": idx + 1,
+                                        "url": p["pool"],
+                                        "account": p["worker"],
+                                        "status": "alive",
+                                        "stratum-active": idx == 0,
+                                    })
+                                })
+                                .collect();
+                            json!({"code": 0, "msg": {"pools": status}})
+                        }
+                    };
+                    let body = response.to_string().into_bytes();
+                    let mut framed = (body.len() as u32).to_le_bytes().to_vec();
+                    framed.extend_from_slice(&body);
+                    let _ = stream.write_all(&framed).await;
+                });
+            }
+        });
+        (port, stored)
+    }
+
+    fn pool(url: &str, user: &str) -> PoolConfig {
+        PoolConfig::new(PoolURL::from(url.to_string()), user, None)
+    }
+
+    #[tokio::test]
+    async fn test_whatsminer_v3_set_pools() -> Result<()> {
+        let stale =
+            json!({"pool": "stratum+tcp://old.example.com:3333", "worker": "old", "passwd": "x"});
+        let (port, stored) = serve_pools(vec![stale.clone(), stale.clone(), stale]).await;
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let mut miner = WhatsMinerV3::new(ip, MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30));
+        miner.rpc = WhatsMinerRPCAPI::new(ip, Some(port));
+
+        // the firmware rewrites the TLS pool and drops the Stratum V2 one, the last slot is cleared
+        let tls = pool("stratum+ssl://pool.example.com:4444", "worker.1");
+        let sv2 = pool(
+            "stratum2+tcp://pool.example.com:3336/9awtMD5KQgvRUh2yFbjVeT7b6hjipWcAsQHd6wEhgtDT9soosna",
+            "worker.1",
+        );
+        let update = miner
+            .set_pools_confirmed(vec![tls.clone(), sv2.clone()])
+            .await?;
+        assert_eq!(update.rewritten.len(), 1);
+        assert_eq!(update.rewritten[0].position, 0);
+        assert_eq!(update.rewritten[0].requested, tls);
+        assert_eq!(
+            update.rewritten[0].applied.url,
+            Some(PoolURL::from(
+                "stratum+tcp://pool.example.com:4444".to_string()
+            ))
+        );
+        assert_eq!(update.rejected, vec![sv2]);
+        assert!(update.stale.is_empty());
+        assert!(!update.is_applied());
+        assert_eq!(stored.lock().unwrap()[2]["pool"], json!(""));
+
+        // a single pool clears both backup slots
+        let primary = pool("stratum+tcp://pool.example.com:3333", "worker.1");
+        assert!(miner.set_pools(vec![primary.clone()]).await?);
+        let slots: Vec<Value> = stored
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| p["pool"].clone())
+            .collect();
+        assert_eq!(
+            slots,
+            vec![json!(primary.url.to_string()), json!(""), json!("")]
+        );
 
-        Ok(data.is_ok())
+        // duplicates would silently shift the priority of the pools after them
+        let error = miner
+            .set_pools(vec![primary.clone(), primary])
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ControlError>(),
+            Some(ControlError::InvalidParameter(_))
+        ));
+        Ok(())
     }
 }
@@ -4,12 +4,17 @@ use base64::prelude::*;
 use chrono::Utc;
 use serde_json::{Value, json};
 use sha2::{Digest, Sha256};
-use std::net::IpAddr;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::net::{IpAddr, SocketAddr};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, RwLock};
 
 use crate::miners::api::rpc::errors::RPCError;
+use crate::miners::api::rpc::reader::{self, RPCReadLimits};
 use crate::miners::api::rpc::status::RPCCommandStatus;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
+use crate::miners::backends::whatsminer::validate_password;
 use crate::miners::commands::MinerCommand;
 
 #[derive(Debug)]
@@ -17,7 +22,12 @@ pub struct WhatsMinerRPCAPI {
     ip: IpAddr,
     port: u16,
     user: String,
-    password: String,
+    password: RwLock<String>,
+    pub(crate) policy: CommandPolicy,
+    read_limits: RPCReadLimits,
+    /// Reuse one connection for the reads of a data collection.
+    keepalive: bool,
+    session: Mutex<Option<TcpStream>>,
 }
 
 #[async_trait]
@@ -27,13 +37,59 @@ impl APIClient for WhatsMinerRPCAPI {
             MinerCommand::RPC {
                 command,
                 parameters,
-            } => self
-                .send_command(command, false, parameters.clone())
-                .await
-                .map_err(|e| anyhow!(e.to_string())),
+            } if self.keepalive && !command.starts_with("set.") => {
+                self.policy.check(command)?;
+                let request = frame_request(command, parameters.clone());
+                let response = self.exchange_in_session(&request).await?;
+                self.parse_rpc_result(&String::from_utf8_lossy(&response))
+            }
+            MinerCommand::RPC {
+                command,
+                parameters,
+            } => self.send_command(command, false, parameters.clone()).await,
             _ => Err(anyhow!("Cannot send non RPC command to RPC API")),
         }
     }
+
+    async fn end_session(&self) {
+        if let Some(mut stream) = self.session.lock().await.take() {
+            let _ = stream.shutdown().await;
+        }
+    }
+}
+
+/// A length prefixed BTMiner v3 request.
+fn frame_request(command: &str, parameters: Option<Value>) -> Vec<u8> {
+    let request = match parameters {
+        Some(Value::Object(mut obj)) => {
+            // Use the existing object as the base
+            obj.insert("cmd".to_string(), json!(command));
+            Value::Object(obj)
+        }
+        Some(other) => {
+            // Wrap non-objects into the "param" key
+            json!({ "cmd": command, "param": other })
+        }
+        None => {
+            // No parameters at all
+            json!({ "cmd": command })
+        }
+    };
+    let json_bytes = request.to_string().into_bytes();
+    let length = json_bytes.len() as u32;
+
+    let mut framed = length.to_le_bytes().to_vec();
+    framed.extend(json_bytes);
+    framed
+}
+
+async fn exchange(
+    stream: &mut TcpStream,
+    request: &[u8],
+    limits: &RPCReadLimits,
+) -> Result<Vec<u8>> {
+    stream.write_all(request).await?;
+    Ok(reader::read_length_prefixed(stream, limits).await?)
 }
 
 impl RPCCommandStatus {
@@ -73,46 +129,20 @@ impl RPCAPIClient for WhatsMinerRPCAPI {
         _privileged: bool,
         parameters: Option<Value>,
     ) -> Result<Value> {
+        self.policy.check(command)?;
         if _privileged || command.starts_with("set.") {
             return self.send_privileged_command(command, parameters).await;
         }
 
-        let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
-            .await
-            .map_err(|_| RPCError::ConnectionFailed)?;
-
-        let request = match parameters {
-            Some(Value::Object(mut obj)) => {
-                // Use the existing object as the base
-                obj.insert("cmd".to_string(), json!(command));
-                Value::Object(obj)
-            }
-            Some(other) => {
-                // Wrap non-objects into the "param" key
-                json!({ "cmd": command, "param": other })
-            }
-            None => {
-                // No parameters at all
-                json!({ "cmd": command })
-            }
-        };
-        let json_str = request.to_string();
-        let json_bytes = json_str.as_bytes();
-        let length = json_bytes.len() as u32;
-
-        stream.write_all(&length.to_le_bytes()).await?;
-        stream.write_all(json_bytes).await?;
-
-        let mut len_buf = [0u8; 4];
-        stream.read_exact(&mut len_buf).await?;
-        let response_len = u32::from_le_bytes(len_buf) as usize;
-
-        let mut resp_buf = vec![0u8; response_len];
-        stream.read_exact(&mut resp_buf).await?;
-
-        let response_str = String::from_utf8_lossy(&resp_buf).into_owned();
+        let mut stream = self.connect().await?;
+        let response = exchange(
+            &mut stream,
+            &frame_request(command, parameters),
+            &self.read_limits,
+        )
+        .await?;
 
-        self.parse_rpc_result(&response_str)
+        self.parse_rpc_result(&String::from_utf8_lossy(&response))
     }
 }
 
@@ -122,8 +152,58 @@ impl WhatsMinerRPCAPI {
             ip,
             port: port.unwrap_or(4433),
             user: "super".to_string(),
-            password: "super".to_string(),
+            password: RwLock::new("super".to_string()),
+            policy: CommandPolicy::default(),
+            keepalive: true,
+            session: Mutex::new(None),
+            read_limits: RPCReadLimits::default(),
+        }
+    }
+
+    /// Bound the size of and idle time between reads of the miner's responses.
+    pub fn set_read_limits(&mut self, limits: RPCReadLimits) {
+        self.read_limits = limits;
+    }
+
+    /// Connect to `addr` instead of the miner's IP on the default port.
+    pub fn set_address(&mut self, addr: SocketAddr) {
+        self.ip = addr.ip();
+        self.port = addr.port();
+    }
+
+    /// Keep the connection open across the commands of a data collection, on by default.
+    ///
+    /// Firmwares that close the connection after every response still work, each command then
+    /// reconnects as it would without keepalive.
+    pub fn set_keepalive(&mut self, keepalive: bool) {
+        self.keepalive = keepalive;
+    }
+
+    async fn connect(&self) -> Result<TcpStream> {
+        Ok(TcpStream::connect((self.ip, self.port))
+            .await
+            .map_err(|_| RPCError::ConnectionFailed)?)
+    }
+
+    async fn exchange_in_session(&self, request: &[u8]) -> Result<Vec<u8>> {
+        let mut session = self.session.lock().await;
+        if let Some(stream) = session.as_mut() {
+            match exchange(stream, request, &self.read_limits).await {
+                Ok(response) => return Ok(response),
+                // a slow miner would be just as slow on a new connection
+                Err(e) if matches!(e.downcast_ref(), Some(RPCError::ReadTimeout)) => {
+                    *session = None;
+                    return Err(e);
+                }
+                // the miner closed the connection after its last response
+                Err(_) => *session = None,
+            }
         }
+
+        let mut stream = self.connect().await?;
+        let response = exchange(&mut stream, request, &self.read_limits).await?;
+        *session = Some(stream);
+        Ok(response)
     }
 
     fn parse_rpc_result(&self, response: &str) -> Result<Value> {
@@ -139,19 +219,61 @@ impl WhatsMinerRPCAPI {
         command: &str,
         parameters: Option<Value>,
     ) -> Result<Value> {
+        let password = self.password.read().await.clone();
+        self.send_privileged_command_as(command, parameters, &password, false)
+            .await
+    }
+
+    /// Send a privileged command that restarts btminer to apply it.
+    ///
+    /// btminer may drop the connection before its reply is read, which is taken as the command
+    /// being accepted, and answers with [`Value::Null`].
+    pub async fn send_restarting_command(
+        &self,
+        command: &str,
+        parameters: Option<Value>,
+    ) -> Result<Value> {
+        let password = self.password.read().await.clone();
+        self.send_privileged_command_as(command, parameters, &password, true)
+            .await
+    }
+
+    /// Change the API password, switching this client over to `new` once the miner accepts it.
+    pub async fn set_password(&self, old: &str, new: &str) -> Result<Value> {
+        validate_password(new)?;
+
+        let parameters = self.password_parameters(old, new);
+        let result = self
+            .send_privileged_command_as("set.system.password", Some(parameters), old, false)
+            .await?;
+
+        *self.password.write().await = new.to_string();
+        Ok(result)
+    }
+
+    /// The `set.system.password` parameters changing the password of this client's account.
+    pub fn password_parameters(&self, old: &str, new: &str) -> Value {
+        json!({"param": {"account": self.user, "old": old, "new": new}})
+    }
+
+    async fn send_privileged_command_as(
+        &self,
+        command: &str,
+        parameters: Option<Value>,
+        password: &str,
+        restarts: bool,
+    ) -> Result<Value> {
+        self.policy.check(command)?;
         let salt = self.get_salt().await;
         if salt.is_none() {
             bail!("Could not get salt for privileged command.");
         };
 
-        let mut stream = tokio::net::TcpStream::connect((self.ip, self.port))
-            .await
-            .map_err(|_| RPCError::ConnectionFailed)?;
+        let mut stream = self.connect().await?;
 
         let timestamp = Utc::now().timestamp();
 
-        let tokenized_command =
-            format!("{}{}{}{}", command, self.password, salt.unwrap(), timestamp);
+        let tokenized_command = format!("{}{}{}{}", command, password, salt.unwrap(), timestamp);
 
         let hashed_command = Sha256::digest(tokenized_command.as_bytes());
         let encoded_command = BASE64_STANDARD.encode(hashed_command);
@@ -200,12 +322,11 @@ impl WhatsMinerRPCAPI {
         stream.write_all(&length.to_le_bytes()).await?;
         stream.write_all(json_bytes).await?;
 
-        let mut len_buf = [0u8; 4];
-        stream.read_exact(&mut len_buf).await?;
-        let response_len = u32::from_le_bytes(len_buf) as usize;
-
-        let mut resp_buf = vec![0u8; response_len];
-        stream.read_exact(&mut resp_buf).await?;
+        let resp_buf = match reader::read_length_prefixed(&mut stream, &self.read_limits).await {
+            // the request was written, btminer went down to restart before replying
+            Err(RPCError::ConnectionFailed) if restarts => return Ok(Value::Null),
+            result => result?,
+        };
 
         let response_str = String::from_utf8_lossy(&resp_buf).into_owned();
 
@@ -219,3 +340,115 @@ impl WhatsMinerRPCAPI {
             .and_then(|s| s["msg"]["salt"].as_str().map(|s| s.to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::MinerModel;
+    use crate::data::device::models::whatsminer::WhatsMinerModel;
+    use crate::miners::backends::whatsminer::v3::WhatsMinerV3;
+    use crate::miners::data::DataCollector;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    /// Answer every request with an empty success, counting the connections accepted.
+    async fn serve(close_after_response: bool) -> (u16, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    loop {
+                        let mut len = [0u8; 4];
+                        if stream.read_exact(&mut len).await.is_err() {
+                            return;
+                        }
+                        let mut request = vec![0u8; u32::from_le_bytes(len) as usize];
+                        if stream.read_exact(&mut request).await.is_err() {
+                            return;
+                        }
+                        let response = br#"{"code":0,"msg":{}}"#;
+                        let mut framed = (response.len() as u32).to_le_bytes().to_vec();
+                        framed.extend_from_slice(response);
+                        if stream.write_all(&framed).await.is_err() || close_after_response {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        (port, connections)
+    }
+
+    fn status_command(parameter: &'static str) -> MinerCommand {
+        MinerCommand::RPC {
+            command: "get.miner.status",
+            parameters: Some(json!(parameter)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_reuses_connection() {
+        let (port, connections) = serve(false).await;
+        let rpc = WhatsMinerRPCAPI::new(IpAddr::from([127, 0, 0, 1]), Some(port));
+
+        for parameter in ["summary", "pools", "edevs"] {
+            rpc.get_api_result(&status_command(parameter))
+                .await
+                .unwrap();
+        }
+        rpc.end_session().await;
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        // a new collection opens a new session
+        rpc.get_api_result(&status_command("summary"))
+            .await
+            .unwrap();
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_reconnects_when_closed() {
+        let (port, connections) = serve(true).await;
+        let rpc = WhatsMinerRPCAPI::new(IpAddr::from([127, 0, 0, 1]), Some(port));
+
+        for parameter in ["summary", "pools", "edevs"] {
+            rpc.get_api_result(&status_command(parameter))
+                .await
+                .unwrap();
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_disabled() {
+        let (port, connections) = serve(false).await;
+        let mut rpc = WhatsMinerRPCAPI::new(IpAddr::from([127, 0, 0, 1]), Some(port));
+        rpc.set_keepalive(false);
+
+        for parameter in ["summary", "pools"] {
+            rpc.get_api_result(&status_command(parameter))
+                .await
+                .unwrap();
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_collection_uses_one_connection() {
+        let (port, connections) = serve(false).await;
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let miner = WhatsMinerV3::new(ip, MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30));
+        let rpc = WhatsMinerRPCAPI::new(ip, Some(port));
+
+        DataCollector::new_with_client(&miner, &rpc)
+            .collect_all()
+            .await;
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,559 @@
+//! Operations run across many miners at once.
+//!
+//! A pool change with a typo in the stratum URL takes every miner it reaches offline, so
+//! [`PoolSwitchover`] writes the new pools to a few canaries first and only continues once they
+//! have had shares accepted on them. [`find_wedged`] looks for miners that hash without any
+//! pool getting their work.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::{Instant, sleep};
+
+use crate::data::health::HealthThresholds;
+use crate::data::pool::{PoolConfig, PoolData};
+use crate::miners::backends::traits::Miner;
+use crate::miners::data::DataField;
+
+/// The pool access a [`PoolSwitchover`] needs, implemented for every [`Miner`].
+#[async_trait]
+pub trait PoolTarget: Send + Sync {
+    /// The IP address the switchover reports the miner under.
+    fn target_ip(&self) -> IpAddr;
+    /// The pools the miner reports, with their share counts.
+    async fn read_pools(&self) -> Vec<PoolData>;
+    /// Replace the pools configured on the miner, in order of priority.
+    async fn write_pools(&self, pools: Vec<PoolConfig>) -> Result<bool>;
+}
+
+#[async_trait]
+impl<T: Miner + ?Sized> PoolTarget for T {
+    fn target_ip(&self) -> IpAddr {
+        self.get_ip()
+    }
+
+    async fn read_pools(&self) -> Vec<PoolData> {
+        self.get_pools().await
+    }
+
+    async fn write_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+        self.set_pools(pools).await
+    }
+}
+
+/// How a [`PoolSwitchover`] ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwitchoverOutcome {
+    /// The canaries validated and the new pools were written to the rest, see the
+    /// [`MinerSwitchover`] of each miner for failures among them
+    Completed,
+    /// The pools of a canary, or their passwords, couldn't be read, so it couldn't be rolled
+    /// back and nothing was changed
+    Aborted(String),
+    /// A canary failed validation, the canaries were rolled back and the rest left untouched
+    RolledBack(String),
+}
+
+/// What a [`PoolSwitchover`] did to one miner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MinerSwitchover {
+    /// The new pools were written, and accepted shares if the miner was a canary
+    Switched,
+    /// Left on its previous pools, because the switchover didn't get to it
+    Untouched,
+    /// Writing the new pools failed
+    Failed(String),
+    /// A canary that was restored to its previous pools
+    RolledBack,
+    /// A canary whose previous pools couldn't be restored
+    RollbackFailed(String),
+}
+
+/// The result of a [`PoolSwitchover`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwitchoverReport {
+    pub outcome: SwitchoverOutcome,
+    /// The miners the new pools were validated on, in the order they were given
+    pub canaries: Vec<IpAddr>,
+    pub miners: BTreeMap<IpAddr, MinerSwitchover>,
+}
+
+impl SwitchoverReport {
+    /// The miners that didn't end up where the outcome intended, either on the new pools after
+    /// a completed switchover or on their previous pools after a rollback.
+    pub fn failures(&self) -> Vec<(IpAddr, &str)> {
+        self.miners
+            .iter()
+            .filter_map(|(ip, result)| match result {
+                MinerSwitchover::Failed(error) | MinerSwitchover::RollbackFailed(error) => {
+                    Some((*ip, error.as_str()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Display for SwitchoverOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SwitchoverOutcome::Completed => write!(f, "completed"),
+            SwitchoverOutcome::Aborted(reason) => write!(f, "aborted: {reason}"),
+            SwitchoverOutcome::RolledBack(reason) => write!(f, "rolled back: {reason}"),
+        }
+    }
+}
+
+/// Replace the pools of many miners, validating the new pools on a few canaries first.
+///
+/// The switchover runs in phases:
+/// 1. the pools of the canaries, the first miners given, are read so they can be restored,
+/// 2. the new pools are written to the canaries,
+/// 3. the canaries are polled until each has had more shares accepted on one of the new pools
+///    than before the write,
+/// 4. the new pools are written to the rest of the miners.
+///
+/// If writing to a canary fails, or a canary has no accepted shares on the new pools when the
+/// validation timeout runs out, every canary is rolled back to its previous pools and the rest
+/// of the miners are left untouched.
+///
+/// Most firmware doesn't report pool passwords, and a rollback without them would overwrite
+/// the real passwords. The switchover aborts before writing anything when a canary doesn't
+/// report them, unless [`PoolSwitchover::with_placeholder_rollback`] allows rolling back with
+/// the placeholder password.
+#[derive(Debug, Clone)]
+pub struct PoolSwitchover {
+    pools: Vec<PoolConfig>,
+    canaries: usize,
+    validation_timeout: Duration,
+    poll_interval: Duration,
+    placeholder_rollback: bool,
+}
+
+impl PoolSwitchover {
+    pub fn new(pools: Vec<PoolConfig>) -> Self {
+        Self {
+            pools,
+            canaries: 1,
+            validation_timeout: Duration::from_secs(300),
+            poll_interval: Duration::from_secs(15),
+            placeholder_rollback: false,
+        }
+    }
+
+    /// Validate on the first `canaries` miners, by default 1.
+    pub fn with_canaries(mut self, canaries: usize) -> Self {
+        self.canaries = canaries.max(1);
+        self
+    }
+
+    /// How long the canaries get to have shares accepted on the new pools, by default 5 minutes.
+    pub fn with_validation_timeout(mut self, timeout: Duration) -> Self {
+        self.validation_timeout = timeout;
+        self
+    }
+
+    /// How often the canaries are polled while validating, by default every 15 seconds.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Roll canaries whose firmware doesn't report pool passwords back with the placeholder
+    /// password instead of aborting, off by default. Only safe where the previous pools don't
+    /// need a password.
+    pub fn with_placeholder_rollback(mut self, enabled: bool) -> Self {
+        self.placeholder_rollback = enabled;
+        self
+    }
+
+    pub async fn run<M: PoolTarget + ?Sized>(&self, miners: &[Box<M>]) -> SwitchoverReport {
+        let (canaries, rest) = miners.split_at(self.canaries.min(miners.len()));
+        let mut report = SwitchoverReport {
+            outcome: SwitchoverOutcome::Completed,
+            canaries: canaries.iter().map(|m| m.target_ip()).collect(),
+            miners: miners
+                .iter()
+                .map(|m| (m.target_ip(), MinerSwitchover::Untouched))
+                .collect(),
+        };
+
+        let baselines = join_all(canaries.iter().map(|m| m.read_pools())).await;
+        let mut previous = Vec::with_capacity(canaries.len());
+        for (miner, pools) in canaries.iter().zip(&baselines) {
+            match self.rollback_pools(pools) {
+                Ok(pools) => previous.push(pools),
+                Err(reason) => {
+                    report.outcome = SwitchoverOutcome::Aborted(format!(
+                        "{reason} of canary {}",
+                        miner.target_ip()
+                    ));
+                    return report;
+                }
+            }
+        }
+
+        let validations = join_all(
+            canaries
+                .iter()
+                .zip(&baselines)
+                .map(|(miner, baseline)| self.validate(miner.as_ref(), baseline)),
+        )
+        .await;
+        let failure = canaries
+            .iter()
+            .zip(&validations)
+            .find_map(|(miner, result)| {
+                let error = result.as_ref().err()?;
+                Some(format!("canary {}: {error}", miner.target_ip()))
+            });
+        if let Some(reason) = failure {
+            tracing::warn!(%reason, "pool switchover failed validation, rolling back canaries");
+            let rollbacks = join_all(
+                canaries
+                    .iter()
+                    .zip(previous)
+                    .map(|(miner, pools)| write(miner.as_ref(), pools)),
+            )
+            .await;
+            for (miner, result) in canaries.iter().zip(rollbacks) {
+                let state = match result {
+                    Ok(()) => MinerSwitchover::RolledBack,
+                    Err(error) => MinerSwitchover::RollbackFailed(error),
+                };
+                report.miners.insert(miner.target_ip(), state);
+            }
+            report.outcome = SwitchoverOutcome::RolledBack(reason);
+            return report;
+        }
+        for miner in canaries {
+            report
+                .miners
+                .insert(miner.target_ip(), MinerSwitchover::Switched);
+        }
+
+        let rollout = join_all(rest.iter().map(|m| write(m.as_ref(), self.pools.clone()))).await;
+        for (miner, result) in rest.iter().zip(rollout) {
+            let state = match result {
+                Ok(()) => MinerSwitchover::Switched,
+                Err(error) => MinerSwitchover::Failed(error),
+            };
+            report.miners.insert(miner.target_ip(), state);
+        }
+        report
+    }
+
+    /// The pools to restore a canary to, in order of priority, from the pools it reported.
+    fn rollback_pools(&self, pools: &[PoolData]) -> Result<Vec<PoolConfig>, String> {
+        let mut pools: Vec<&PoolData> = pools.iter().collect();
+        pools.sort_by_key(|pool| pool.position);
+        let previous: Vec<PoolConfig> = pools
+            .into_iter()
+            .filter_map(|pool| {
+                Some(PoolConfig::new(
+                    pool.url.clone()?,
+                    pool.user.clone()?,
+                    pool.password.clone(),
+                ))
+            })
+            .collect();
+        if previous.is_empty() {
+            return Err("could not read the pools".to_string());
+        }
+        if !self.placeholder_rollback && previous.iter().any(|pool| pool.password.is_none()) {
+            return Err("could not read the pool passwords".to_string());
+        }
+        Ok(previous)
+    }
+
+    /// Write the new pools to a canary and wait for shares to be accepted on them, beyond the
+    /// `baseline` the canary reported before the write.
+    async fn validate<M: PoolTarget + ?Sized>(
+        &self,
+        miner: &M,
+        baseline: &[PoolData],
+    ) -> Result<(), String> {
+        write(miner, self.pools.clone()).await?;
+        let deadline = Instant::now() + self.validation_timeout;
+        loop {
+            let pools = miner.read_pools().await;
+            if pools
+                .iter()
+                .any(|pool| self.accepted_on_new_pool(pool, baseline))
+            {
+                return Ok(());
+            }
+            if Instant::now() + self.poll_interval > deadline {
+                return Err("no shares accepted on the new pools".to_string());
+            }
+            sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Whether `pool` is one of the new pools and has had shares accepted since `baseline`.
+    ///
+    /// A new pool that was already configured carries the shares from before the switch. Its
+    /// count only goes down when the miner restarted mining, after which every share is new.
+    fn accepted_on_new_pool(&self, pool: &PoolData, baseline: &[PoolData]) -> bool {
+        let before = baseline
+            .iter()
+            .find(|old| old.url == pool.url && old.user == pool.user)
+            .and_then(|old| old.accepted_shares)
+            .unwrap_or(0);
+        pool.accepted_shares
+            .is_some_and(|accepted| accepted > 0 && accepted != before)
+            && self.pools.iter().any(|new| {
+                pool.url.as_ref() == Some(&new.url) && pool.user.as_deref() == Some(&new.user)
+            })
+    }
+}
+
+/// Replace the pools of `miners` with `pools` using the defaults of [`PoolSwitchover`].
+pub async fn switch_pools<M: PoolTarget + ?Sized>(
+    miners: &[Box<M>],
+    pools: Vec<PoolConfig>,
+) -> SwitchoverReport {
+    PoolSwitchover::new(pools).run(miners).await
+}
+
+/// The miners of `miners` whose stratum connection is wedged, with the reason for each.
+///
+/// Only the hashrate, uptime and pools of each miner are read, rather than all of its data. A
+/// miner is wedged when it hashes while every pool is dead, or while no active pool has had a
+/// share for longer than `max_share_age`. The other thresholds of each miner still apply, so
+/// miners warming up are skipped, and pools registered with
+/// [`set_solo_pools`][`crate::data::health::set_solo_pools`] are left out.
+pub async fn find_wedged<M: Miner + ?Sized>(
+    miners: &[Box<M>],
+    max_share_age: Duration,
+) -> Vec<(IpAddr, String)> {
+    let checks = miners.iter().map(|miner| async move {
+        let thresholds = HealthThresholds {
+            max_share_age_seconds: Some(max_share_age.as_secs()),
+            ..miner.get_health_thresholds()
+        };
+        let mut collector = miner.get_collector();
+        let data = collector
+            .collect(&[DataField::Hashrate, DataField::Uptime, DataField::Pools])
+            .await;
+        let collected_at = data
+            .collected_at()
+            .unwrap_or_else(SystemTime::now)
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let now = (collected_at.as_secs() as i64 - data.clock_skew().unwrap_or(0)).max(0) as u64;
+        let reason = thresholds.stratum_wedged_at(
+            &miner.parse_pools(&data),
+            miner.parse_hashrate(&data).as_ref(),
+            miner.parse_uptime(&data),
+            now,
+        )?;
+        Some((miner.get_ip(), reason))
+    });
+    join_all(checks).await.into_iter().flatten().collect()
+}
+
+async fn write<M: PoolTarget + ?Sized>(miner: &M, pools: Vec<PoolConfig>) -> Result<(), String> {
+    match miner.write_pools(pools).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("the miner did not accept the pools".to_string()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::pool::PoolURL;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// A miner that gets a share accepted on each of its pools between reads, unless the pools
+    /// are dead. The pools it starts with already have shares accepted.
+    #[derive(Debug)]
+    struct MockMiner {
+        ip: IpAddr,
+        pools: Mutex<Vec<PoolConfig>>,
+        accepted: Mutex<HashMap<String, u64>>,
+        dead_pool: bool,
+    }
+
+    impl MockMiner {
+        fn new(host: u8, pools: Vec<PoolConfig>, dead_pool: bool) -> Box<Self> {
+            let accepted = pools.iter().map(|pool| (pool.url.to_string(), 500));
+            Box::new(Self {
+                ip: IpAddr::from([10, 0, 0, host]),
+                accepted: Mutex::new(accepted.collect()),
+                pools: Mutex::new(pools),
+                dead_pool,
+            })
+        }
+
+        fn pools(&self) -> Vec<PoolConfig> {
+            self.pools.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl PoolTarget for MockMiner {
+        fn target_ip(&self) -> IpAddr {
+            self.ip
+        }
+
+        async fn read_pools(&self) -> Vec<PoolData> {
+            let mut accepted = self.accepted.lock().unwrap();
+            self.pools()
+                .into_iter()
+                .enumerate()
+                .map(|(idx, pool)| {
+                    let shares = accepted.entry(pool.url.to_string()).or_default();
+                    if !self.dead_pool {
+                        *shares += 1;
+                    }
+                    PoolData {
+                        position: Some(idx as u16),
+                        url: Some(pool.url),
+                        accepted_shares: Some(*shares),
+                        rejected_shares: Some(0),
+                        active: Some(idx == 0),
+                        alive: Some(!self.dead_pool),
+                        user: Some(pool.user),
+                        password: pool.password,
+                        last_share_difficulty: None,
+                        last_share_time: None,
+                        group: None,
+                        quota: None,
+                    }
+                })
+                .collect()
+        }
+
+        async fn write_pools(&self, pools: Vec<PoolConfig>) -> Result<bool> {
+            *self.pools.lock().unwrap() = pools;
+            Ok(true)
+        }
+    }
+
+    fn pool(url: &str, user: &str) -> PoolConfig {
+        PoolConfig::new(PoolURL::from(url.to_string()), user, Some("x".to_string()))
+    }
+
+    fn switchover(pools: Vec<PoolConfig>) -> PoolSwitchover {
+        PoolSwitchover::new(pools)
+            .with_canaries(2)
+            .with_validation_timeout(Duration::from_millis(100))
+            .with_poll_interval(Duration::from_millis(10))
+    }
+
+    #[tokio::test]
+    async fn test_switchover_rolls_out() {
+        let old = vec![pool("stratum+tcp://old.example.com:3333", "fleet.1")];
+        let new = vec![pool("stratum+tcp://new.example.com:3333", "fleet.1")];
+        let miners: Vec<Box<MockMiner>> = (1..=4)
+            .map(|host| MockMiner::new(host, old.clone(), false))
+            .collect();
+
+        let report = switchover(new.clone()).run(&miners).await;
+        assert_eq!(report.outcome, SwitchoverOutcome::Completed);
+        assert_eq!(report.canaries, vec![miners[0].ip, miners[1].ip]);
+        assert!(
+            report
+                .miners
+                .values()
+                .all(|state| *state == MinerSwitchover::Switched)
+        );
+        assert!(report.failures().is_empty());
+        assert!(miners.iter().all(|miner| miner.pools() == new));
+    }
+
+    #[tokio::test]
+    async fn test_switchover_canary_rollback() {
+        let old = vec![
+            pool("stratum+tcp://old.example.com:3333", "fleet.1"),
+            pool("stratum+tcp://backup.example.com:3333", "fleet.1"),
+        ];
+        // a typo in the host, the canaries never get a share accepted
+        let new = vec![pool("stratum+tcp://nwe.example.com:3333", "fleet.1")];
+        let mut miners: Vec<Box<MockMiner>> = (1..=2)
+            .map(|host| MockMiner::new(host, old.clone(), true))
+            .collect();
+        miners.push(MockMiner::new(3, old.clone(), false));
+
+        let report = switchover(new).run(&miners).await;
+        assert!(matches!(report.outcome, SwitchoverOutcome::RolledBack(_)));
+        assert_eq!(report.miners[&miners[0].ip], MinerSwitchover::RolledBack);
+        assert_eq!(report.miners[&miners[1].ip], MinerSwitchover::RolledBack);
+        assert_eq!(report.miners[&miners[2].ip], MinerSwitchover::Untouched);
+        // the canaries are back on their previous pools, in the same order
+        assert!(miners.iter().all(|miner| miner.pools() == old));
+    }
+
+    #[tokio::test]
+    async fn test_switchover_baseline() {
+        let old = vec![
+            pool("stratum+tcp://old.example.com:3333", "fleet.1"),
+            pool("stratum+tcp://backup.example.com:3333", "fleet.1"),
+        ];
+        // promoting the backup pool, whose shares from before the switch don't validate it
+        let new = vec![old[1].clone()];
+        let miners = vec![
+            MockMiner::new(1, old.clone(), true),
+            MockMiner::new(2, old.clone(), false),
+        ];
+
+        let report = switchover(new.clone()).with_canaries(1).run(&miners).await;
+        assert!(matches!(report.outcome, SwitchoverOutcome::RolledBack(_)));
+        assert_eq!(report.miners[&miners[1].ip], MinerSwitchover::Untouched);
+        assert!(miners.iter().all(|miner| miner.pools() == old));
+
+        let miners = vec![MockMiner::new(1, old.clone(), false)];
+        let report = switchover(new.clone()).run(&miners).await;
+        assert_eq!(report.outcome, SwitchoverOutcome::Completed);
+        assert_eq!(miners[0].pools(), new);
+    }
+
+    #[tokio::test]
+    async fn test_switchover_aborts_without_passwords() {
+        let url = PoolURL::from("stratum+tcp://old.example.com:3333".to_string());
+        let old = vec![PoolConfig::new(url, "fleet.1", None)];
+        let new = vec![pool("stratum+tcp://nwe.example.com:3333", "fleet.1")];
+        let miners = vec![
+            MockMiner::new(1, old.clone(), true),
+            MockMiner::new(2, old.clone(), true),
+        ];
+
+        let report = switchover(new.clone()).run(&miners).await;
+        assert!(matches!(report.outcome, SwitchoverOutcome::Aborted(_)));
+        assert!(
+            report
+                .miners
+                .values()
+                .all(|state| *state == MinerSwitchover::Untouched)
+        );
+        assert!(miners.iter().all(|miner| miner.pools() == old));
+
+        let report = switchover(new)
+            .with_placeholder_rollback(true)
+            .run(&miners)
+            .await;
+        assert!(matches!(report.outcome, SwitchoverOutcome::RolledBack(_)));
+        assert!(miners.iter().all(|miner| miner.pools() == old));
+    }
+
+    #[tokio::test]
+    async fn test_switchover_aborts_without_previous_pools() {
+        let new = vec![pool("stratum+tcp://new.example.com:3333", "fleet.1")];
+        let miners = vec![
+            MockMiner::new(1, vec![], false),
+            MockMiner::new(2, vec![], false),
+        ];
+
+        let report = switchover(new).run(&miners).await;
+        assert!(matches!(report.outcome, SwitchoverOutcome::Aborted(_)));
+        assert!(miners.iter().all(|miner| miner.pools().is_empty()));
+    }
+}
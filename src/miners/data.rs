@@ -1,10 +1,16 @@
 use crate::miners::{
+    api::rpc::status::response_time,
+    backends::errors::MinerError,
     backends::traits::{APIClient, MinerInterface},
     commands::MinerCommand,
 };
 use serde_json::{Value, json};
 use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use strum::{EnumIter, IntoEnumIterator};
+use tokio_util::sync::CancellationToken;
 
 /// Represents the individual pieces of data that can be queried from a miner device.
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Copy, EnumIter)]
@@ -15,6 +21,8 @@ pub enum DataField {
     Timestamp,
     /// IP address of the miner.
     Ip,
+    /// IP address the miner reports for itself, which differs from `Ip` behind a NAT.
+    ReportedIp,
     /// MAC address of the miner.
     Mac,
     /// Information about the miner's device.
@@ -27,6 +35,10 @@ pub enum DataField {
     ApiVersion,
     /// Firmware version of the miner.
     FirmwareVersion,
+    /// Build time of the miner's firmware.
+    FirmwareBuildTime,
+    /// Stock firmware a converted miner was built from.
+    BaseFirmware,
     /// Control board version of the miner.
     ControlBoardVersion,
     /// Details about the hashboards (e.g., temperatures, chips, etc.).
@@ -35,20 +47,40 @@ pub enum DataField {
     Hashrate,
     /// Expected hashrate for the miner.
     ExpectedHashrate,
+    /// Hashrate averaged by the miner over a longer window.
+    AverageHashrate,
     /// Fan speed or fan configuration.
     Fans,
     /// PSU fan speed or configuration.
     PsuFans,
+    /// Fan mode and target speed.
+    FanControl,
     /// Average temperature reported by the miner.
     AverageTemperature,
     /// Fluid temperature reported by the miner.
     FluidTemperature,
+    /// Coolant flow rate reported by water cooled miners.
+    FluidFlow,
+    /// Coolant pressure reported by water cooled miners.
+    FluidPressure,
     /// Current power consumption in watts.
     Wattage,
+    /// Output voltage, current and power of the PSU.
+    Psu,
+    /// Input side voltage, current and power of the PSU.
+    PsuRails,
     /// Configured power limit in watts.
     WattageLimit,
     /// Efficiency of the miner (e.g., J/TH).
     Efficiency,
+    /// Configured chip frequency.
+    Frequency,
+    /// Configured hashboard supply voltage.
+    Voltage,
+    /// Configured performance mode.
+    WorkMode,
+    /// Automatic tuning state and the profiles it scales between.
+    Autotune,
     /// Whether the fault or alert light is flashing.
     LightFlashing,
     /// Messages reported by the miner (e.g., errors or warnings).
@@ -81,6 +113,235 @@ pub struct DataExtractor {
 /// Alias for a tuple describing the API command and the extractor used to parse its result.
 pub type DataLocation = (MinerCommand, DataExtractor);
 
+/// The value collected for one field.
+#[derive(Clone)]
+enum FieldValue {
+    /// Found in a single response, located again with the same extractor instead of being
+    /// copied out of it.
+    Shared {
+        response: Arc<Value>,
+        extractor: DataExtractor,
+    },
+    /// Merged from several responses, or moved under a tag.
+    Owned(Value),
+}
+
+impl FieldValue {
+    fn get(&self) -> Option<&Value> {
+        match self {
+            FieldValue::Shared {
+                response,
+                extractor,
+            } => (extractor.func)(response, extractor.key),
+            FieldValue::Owned(value) => Some(value),
+        }
+    }
+}
+
+/// The collected value of each field, as handed to a backend's parsers.
+///
+/// Values point into the responses cached by the collector where possible, so a large
+/// response that many fields read, such as cgminer `stats`, is held once rather than once per
+/// field.
+#[derive(Clone, Default)]
+pub struct FieldData {
+    fields: HashMap<DataField, FieldValue>,
+    /// When the collector started sending commands, and how long it took
+    timing: Option<(SystemTime, Duration)>,
+    /// See [`CollectionStats::clock_skew`]
+    clock_skew: Option<i64>,
+}
+
+impl FieldData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The value collected for `field`.
+    pub fn get(&self, field: &DataField) -> Option<&Value> {
+        self.fields.get(field).and_then(FieldValue::get)
+    }
+
+    pub fn contains_key(&self, field: &DataField) -> bool {
+        self.fields.contains_key(field)
+    }
+
+    /// Set the value of `field`, replacing any collected one.
+    pub fn insert(&mut self, field: DataField, value: Value) {
+        self.fields.insert(field, FieldValue::Owned(value));
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&DataField, &Value)> {
+        self.fields
+            .iter()
+            .filter_map(|(field, value)| value.get().map(|value| (field, value)))
+    }
+
+    /// The wall clock time the collector sent its first command, `None` for data that wasn't
+    /// collected from a miner.
+    pub fn collected_at(&self) -> Option<SystemTime> {
+        self.timing.map(|(started, _)| started)
+    }
+
+    /// How long collecting this data took, measured on a monotonic clock so it can't be skewed
+    /// by the system clock being stepped.
+    pub fn collection_duration(&self) -> Option<Duration> {
+        self.timing.map(|(_, duration)| duration)
+    }
+
+    /// How far the host's clock is ahead of the miner's in seconds, `None` unless the miner
+    /// reported its time with a response.
+    pub fn clock_skew(&self) -> Option<i64> {
+        self.clock_skew
+    }
+}
+
+impl Debug for FieldData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for FieldData {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(field, value)| other.get(field) == Some(value))
+    }
+}
+
+impl FromIterator<(DataField, Value)> for FieldData {
+    fn from_iter<I: IntoIterator<Item = (DataField, Value)>>(iter: I) -> Self {
+        Self {
+            fields: iter
+                .into_iter()
+                .map(|(field, value)| (field, FieldValue::Owned(value)))
+                .collect(),
+            timing: None,
+            clock_skew: None,
+        }
+    }
+}
+
+impl<const N: usize> From<[(DataField, Value); N]> for FieldData {
+    fn from(values: [(DataField, Value); N]) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl From<HashMap<DataField, Value>> for FieldData {
+    fn from(values: HashMap<DataField, Value>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+/// A command whose response tells the collector that some of the other commands would fail,
+/// such as the status of a miner whose mining process is powered off.
+///
+/// When the probe is needed for a collection it is sent first, and every command `skip` returns
+/// true for is left unsent.
+#[derive(Clone)]
+pub struct CommandGate {
+    /// Command sent before all others.
+    pub probe: MinerCommand,
+    /// Whether to skip a command, given the probe's response.
+    pub skip: fn(&Value, &MinerCommand) -> bool,
+}
+
+/// Why a field's extractor found no value, from [`DataCollector::diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtractionFailure {
+    /// The command returned an error, or was never sent.
+    CommandFailed {
+        command: MinerCommand,
+        reason: String,
+    },
+    /// The miner rejected the credentials the command was sent with.
+    AuthenticationFailed { command: MinerCommand },
+    /// The response has nothing at `path`, the first part of the key that could not be followed.
+    PathMissing { command: MinerCommand, path: String },
+    /// The key leads through the value at `path`, which is not the object or array it expects.
+    TypeMismatch {
+        command: MinerCommand,
+        path: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+impl ExtractionFailure {
+    /// Follow `key` through `response` to find where it stops, or `None` if it doesn't.
+    ///
+    /// Keys starting with `/` are JSON pointers as used by [`get_by_pointer`], anything else is a
+    /// single key as used by [`get_by_key`].
+    fn diagnose(command: &MinerCommand, response: &Value, key: Option<&str>) -> Option<Self> {
+        let tokens: Vec<String> = match key {
+            None => {
+                return Some(Self::PathMissing {
+                    command: command.clone(),
+                    path: String::new(),
+                });
+            }
+            Some(pointer) if pointer.is_empty() || pointer.starts_with('/') => pointer
+                .split('/')
+                .skip(1)
+                .map(|token| token.replace("~1", "/").replace("~0", "~"))
+                .collect(),
+            Some(key) => vec![key.to_string()],
+        };
+
+        let mut current = response;
+        let mut path = String::new();
+        for token in tokens {
+            let index = token.parse::<usize>().ok();
+            let next = match (current, index) {
+                (Value::Object(map), _) => map.get(&token),
+                (Value::Array(items), Some(index)) => items.get(index),
+                (other, _) => {
+                    return Some(Self::TypeMismatch {
+                        command: command.clone(),
+                        path,
+                        expected: if index.is_some() { "array" } else { "object" },
+                        found: json_type(other),
+                    });
+                }
+            };
+            path.push('/');
+            path.push_str(&token);
+            match next {
+                Some(value) => current = value,
+                None => {
+                    return Some(Self::PathMissing {
+                        command: command.clone(),
+                        path,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
 /// Extracts a value from a JSON object using a key (flat lookup).
 ///
 /// Returns `None` if the key is `None` or not found in the object.
@@ -108,21 +369,46 @@ impl FromValue for String {
     }
 }
 
+/// Parses a number sent as a string, as older cgminer firmware does for fields like `GHS 5s`.
+///
+/// Only finite decimal numbers are accepted, so `"nan"`, `"inf"` or `""` yield `None`.
+fn parse_numeric_str(value: &Value) -> Option<f64> {
+    let s = value.as_str()?.trim();
+    if s.is_empty() || !s.bytes().any(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse::<f64>().ok().filter(|f| f.is_finite())
+}
+
 impl FromValue for f64 {
     fn from_value(value: &Value) -> Option<Self> {
-        value.as_f64()
+        value.as_f64().or_else(|| parse_numeric_str(value))
     }
 }
 
 impl FromValue for u64 {
     fn from_value(value: &Value) -> Option<Self> {
-        value.as_u64()
+        value.as_u64().or_else(|| {
+            let s = value.as_str()?.trim();
+            s.parse::<u64>().ok().or_else(|| {
+                parse_numeric_str(value)
+                    .filter(|f| *f >= 0.0 && f.fract() == 0.0 && *f <= u64::MAX as f64)
+                    .map(|f| f as u64)
+            })
+        })
     }
 }
 
 impl FromValue for i64 {
     fn from_value(value: &Value) -> Option<Self> {
-        value.as_i64()
+        value.as_i64().or_else(|| {
+            let s = value.as_str()?.trim();
+            s.parse::<i64>().ok().or_else(|| {
+                parse_numeric_str(value)
+                    .filter(|f| f.fract() == 0.0 && f.abs() <= i64::MAX as f64)
+                    .map(|f| f as i64)
+            })
+        })
     }
 }
 
@@ -145,7 +431,7 @@ impl<T: FromValue> FromValue for Vec<T> {
     }
 }
 
-/// Extension trait for HashMap<DataField, &Value> to provide cleaner value extraction.
+/// Extension trait for [`FieldData`] to provide cleaner value extraction.
 pub trait DataExtensions {
     /// Extract a value of type T from the data map for the given field.
     fn extract<T: FromValue>(&self, field: DataField) -> Option<T>;
@@ -188,7 +474,7 @@ pub trait DataExtensions {
     ) -> U;
 }
 
-impl DataExtensions for HashMap<DataField, Value> {
+impl DataExtensions for FieldData {
     fn extract<T: FromValue>(&self, field: DataField) -> Option<T> {
         self.get(&field).and_then(|v| T::from_value(v))
     }
@@ -242,68 +528,352 @@ impl DataExtensions for HashMap<DataField, Value> {
     }
 }
 
+/// Timing of a single command sent by a `DataCollector`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandStats {
+    /// The command that was sent.
+    pub command: MinerCommand,
+    /// Wall time from sending the command to having its parsed response, commands joined into
+    /// one request each get the time of the whole request.
+    pub duration: Duration,
+    /// Whether the command returned a response.
+    pub success: bool,
+    /// The miner's clock when it answered, in seconds since the epoch, from the `When` in the
+    /// STATUS header of a cgminer response.
+    pub miner_time: Option<u64>,
+    /// How far the host's clock was ahead of the miner's when the response arrived, in seconds.
+    pub clock_skew: Option<i64>,
+}
+
+/// Timings of the commands sent by a `DataCollector`, for finding what dominates a poll cycle.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CollectionStats {
+    /// Backend type the commands were sent by, e.g. `AntMinerV2020`.
+    pub backend: &'static str,
+    /// Every command sent, in the order they were sent.
+    pub commands: Vec<CommandStats>,
+    /// Total wall time spent sending commands.
+    pub total: Duration,
+    /// Fields that are missing data because the command policy blocked one of their commands.
+    /// Blocked commands are never sent, so they are not part of `commands`.
+    pub policy_skipped: Vec<DataField>,
+    /// Commands left unsent because the backend's [`CommandGate`] said they would fail.
+    pub gated: Vec<MinerCommand>,
+}
+
+impl CollectionStats {
+    /// Stats for the last time the given command was sent.
+    pub fn get(&self, command: &MinerCommand) -> Option<&CommandStats> {
+        self.commands.iter().rev().find(|c| &c.command == command)
+    }
+
+    /// The command that took the longest.
+    pub fn slowest(&self) -> Option<&CommandStats> {
+        self.commands.iter().max_by_key(|c| c.duration)
+    }
+
+    /// How far the host's clock is ahead of the miner's in seconds, from the last response that
+    /// reported the miner's time.
+    pub fn clock_skew(&self) -> Option<i64> {
+        self.commands.iter().rev().find_map(|c| c.clock_skew)
+    }
+}
+
 /// A utility for collecting structured miner data from an API backend.
 pub struct DataCollector<'a> {
     /// Backend-specific data mapping logic.
     miner: &'a dyn MinerInterface,
     client: &'a dyn APIClient,
-    /// Cache of command responses keyed by command string.
-    cache: HashMap<MinerCommand, Value>,
+    /// Cache of command responses keyed by command, shared with the fields read from them.
+    cache: HashMap<MinerCommand, Arc<Value>>,
+    /// Why each command without a response failed.
+    errors: HashMap<MinerCommand, String>,
+    /// Commands the miner refused for lack of valid credentials.
+    auth_failed: HashSet<MinerCommand>,
+    /// Timings of the commands sent so far.
+    stats: CollectionStats,
+    /// Stops sending commands once cancelled.
+    cancellation: Option<CancellationToken>,
+    /// When the last collection started and how long it took.
+    timing: Option<(SystemTime, Duration)>,
+}
+
+/// Name of a backend type without its module path.
+fn backend_name<M>() -> &'static str {
+    let name = std::any::type_name::<M>();
+    name.rsplit("::").next().unwrap_or(name)
 }
 
 impl<'a> DataCollector<'a> {
     /// Constructs a new `DataCollector` with the given backend and API client.
-    pub fn new(miner: &'a dyn MinerInterface) -> Self {
+    pub fn new<M: MinerInterface>(miner: &'a M) -> Self {
         Self {
             miner,
             client: miner,
             cache: HashMap::new(),
+            errors: HashMap::new(),
+            auth_failed: HashSet::new(),
+            stats: CollectionStats {
+                backend: backend_name::<M>(),
+                ..Default::default()
+            },
+            cancellation: None,
+            timing: None,
         }
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn new_with_client(
-        miner: &'a dyn MinerInterface,
-        client: &'a dyn APIClient,
-    ) -> Self {
+    /// Constructs a `DataCollector` that sends the commands of `miner` through `client`, such as
+    /// a mock from the `test-util` feature.
+    pub fn new_with_client<M: MinerInterface>(miner: &'a M, client: &'a dyn APIClient) -> Self {
         Self {
             miner,
             client,
             cache: HashMap::new(),
+            errors: HashMap::new(),
+            auth_failed: HashSet::new(),
+            stats: CollectionStats {
+                backend: backend_name::<M>(),
+                ..Default::default()
+            },
+            cancellation: None,
+            timing: None,
         }
     }
 
+    /// Stop sending commands once `token` is cancelled, aborting the command in flight.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Timings of the commands sent by this collector so far.
+    pub fn stats(&self) -> &CollectionStats {
+        &self.stats
+    }
+
     /// Collects **all** available fields from the miner and returns a map of results.
-    pub async fn collect_all(&mut self) -> HashMap<DataField, Value> {
+    pub async fn collect_all(&mut self) -> FieldData {
         self.collect(DataField::iter().collect::<Vec<_>>().as_slice())
             .await
     }
 
     /// Collects only the specified fields from the miner and returns a map of results.
     ///
-    /// This method sends only the minimum required set of API commands.
-    pub async fn collect(&mut self, fields: &[DataField]) -> HashMap<DataField, Value> {
-        let mut results = HashMap::new();
-        let required_commands = self.get_required_commands(fields);
+    /// This method sends only the minimum required set of API commands. If the collector is
+    /// cancelled, the fields that could be extracted from the responses received so far are
+    /// returned.
+    pub async fn collect(&mut self, fields: &[DataField]) -> FieldData {
+        match self.try_collect(fields).await {
+            Ok(results) => results,
+            Err(_) => self.extract_fields(fields),
+        }
+    }
+
+    /// Like [`collect_all`][`Self::collect_all`], but returns [`MinerError::Cancelled`] if the
+    /// collector was cancelled before every command was sent.
+    pub async fn try_collect_all(&mut self) -> Result<FieldData, MinerError> {
+        self.try_collect(DataField::iter().collect::<Vec<_>>().as_slice())
+            .await
+    }
+
+    /// Like [`collect`][`Self::collect`], but returns [`MinerError::Cancelled`] if the
+    /// collector was cancelled before every command was sent.
+    pub async fn try_collect(&mut self, fields: &[DataField]) -> Result<FieldData, MinerError> {
+        let gate = self.miner.command_gate();
+        let mut required_commands: Vec<MinerCommand> =
+            self.get_required_commands(fields).into_iter().collect();
+        if let Some(gate) = &gate
+            && let Some(idx) = required_commands.iter().position(|c| c == &gate.probe)
+        {
+            let probe = required_commands.remove(idx);
+            required_commands.insert(0, probe);
+        }
+        let mut denied = HashSet::new();
+        let mut cancelled = false;
+        // cgminer APIs answer several commands in one request, sent once the gate has answered
+        let mut joined = HashMap::new();
+        let mut join_attempted = false;
+        // the wall clock is only read once, the duration comes from the monotonic clock
+        let started_at = SystemTime::now();
+        let started = Instant::now();
+
+        for (idx, command) in required_commands.iter().enumerate() {
+            if self.is_gated(gate.as_ref(), command) {
+                self.errors
+                    .insert(command.clone(), "skipped by the command gate".to_string());
+                self.stats.gated.push(command.clone());
+                continue;
+            }
+            if !join_attempted && gate.as_ref().is_none_or(|gate| command != &gate.probe) {
+                join_attempted = true;
+                let pending: Vec<MinerCommand> = required_commands[idx..]
+                    .iter()
+                    .filter(|command| !self.is_gated(gate.as_ref(), command))
+                    .cloned()
+                    .collect();
+                let start = Instant::now();
+                let Some(responses) = self
+                    .cancellable(self.client.get_api_results(&pending))
+                    .await
+                else {
+                    cancelled = true;
+                    break;
+                };
+                if !responses.is_empty() {
+                    let duration = start.elapsed();
+                    self.stats.total += duration;
+                    joined = responses
+                        .into_iter()
+                        .map(|(command, response)| (command, (response, duration)))
+                        .collect();
+                }
+            }
+
+            let (response, duration) = match joined.remove(command) {
+                Some(joined) => joined,
+                None => {
+                    let start = Instant::now();
+                    let Some(response) =
+                        self.cancellable(self.client.get_api_result(command)).await
+                    else {
+                        cancelled = true;
+                        break;
+                    };
+                    if let Err(e) = &response
+                        && matches!(e.downcast_ref(), Some(MinerError::PolicyDenied(_)))
+                    {
+                        self.errors.insert(command.clone(), e.to_string());
+                        denied.insert(command.clone());
+                        continue;
+                    }
+                    let duration = start.elapsed();
+                    self.stats.total += duration;
+                    (response, duration)
+                }
+            };
+            let miner_time = match command {
+                MinerCommand::RPC { .. } => response.as_ref().ok().and_then(response_time),
+                _ => None,
+            };
+            let stats = CommandStats {
+                command: command.clone(),
+                duration,
+                success: response.is_ok(),
+                miner_time,
+                clock_skew: miner_time.map(|time| {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default();
+                    now.as_secs() as i64 - time as i64
+                }),
+            };
+            #[cfg(feature = "metrics")]
+            crate::miners::metrics::record(self.stats.backend, &stats);
+            self.stats.commands.push(stats);
 
-        for command in required_commands {
-            if let Ok(response) = self.client.get_api_result(&command).await {
-                self.cache.insert(command, response);
+            match response {
+                Ok(response) => {
+                    self.cache.insert(command.clone(), Arc::new(response));
+                }
+                Err(e) => {
+                    if matches!(e.downcast_ref(), Some(MinerError::AuthenticationFailed)) {
+                        self.auth_failed.insert(command.clone());
+                    }
+                    self.errors.insert(command.clone(), e.to_string());
+                }
             }
         }
 
-        // Extract the data for each field using the cached responses.
+        self.client.end_session().await;
+        self.timing = Some((started_at, started.elapsed()));
+
         for &field in fields {
-            if let Some(value) = self.extract_field(field) {
-                results.insert(field, value);
+            if !self.stats.policy_skipped.contains(&field)
+                && self
+                    .miner
+                    .get_locations(field)
+                    .iter()
+                    .any(|(command, _)| denied.contains(command))
+            {
+                self.stats.policy_skipped.push(field);
             }
         }
 
-        results
+        if cancelled {
+            return Err(MinerError::Cancelled);
+        }
+        Ok(self.extract_fields(fields))
+    }
+
+    /// Whether `gate` says `command` would fail, going by the response to its probe.
+    fn is_gated(&self, gate: Option<&CommandGate>, command: &MinerCommand) -> bool {
+        gate.is_some_and(|gate| {
+            command != &gate.probe
+                && self
+                    .cache
+                    .get(&gate.probe)
+                    .is_some_and(|probe| (gate.skip)(probe, command))
+        })
+    }
+
+    /// Run `future` unless the collector is cancelled first, `None` if it was.
+    async fn cancellable<F: Future>(&self, future: F) -> Option<F::Output> {
+        match &self.cancellation {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => None,
+                output = future => Some(output),
+            },
+            None => Some(future.await),
+        }
+    }
+
+    /// Why each of `fields` that the backend has locations for came back without a value, with
+    /// a failure for each of its locations.
+    ///
+    /// Meant for debugging a backend after a collection, it does not send any commands.
+    pub fn diagnostics(&self, fields: &[DataField]) -> HashMap<DataField, Vec<ExtractionFailure>> {
+        let mut report = HashMap::new();
+        for &field in fields {
+            let locations = self.miner.get_locations(field);
+            if locations.is_empty() || self.extract_field(field).is_some() {
+                continue;
+            }
+            let failures = locations
+                .iter()
+                .filter_map(|(command, extractor)| match self.cache.get(command) {
+                    Some(response) => ExtractionFailure::diagnose(command, response, extractor.key),
+                    None if self.auth_failed.contains(command) => {
+                        Some(ExtractionFailure::AuthenticationFailed {
+                            command: command.clone(),
+                        })
+                    }
+                    None => Some(ExtractionFailure::CommandFailed {
+                        command: command.clone(),
+                        reason: self
+                            .errors
+                            .get(command)
+                            .cloned()
+                            .unwrap_or_else(|| "not sent".to_string()),
+                    }),
+                })
+                .collect();
+            report.insert(field, failures);
+        }
+        report
     }
 
-    fn merge(&self, a: &mut Value, b: Value) {
-        Self::merge_values(a, b);
+    /// Extract the data for each field using the cached responses.
+    fn extract_fields(&self, fields: &[DataField]) -> FieldData {
+        let mut results = FieldData::new();
+        for &field in fields {
+            if let Some(value) = self.extract_field(field) {
+                results.fields.insert(field, value);
+            }
+        }
+        results.timing = self.timing;
+        results.clock_skew = self.stats.clock_skew();
+        results
     }
 
     fn merge_values(a: &mut Value, b: Value) {
@@ -337,32 +907,338 @@ impl<'a> DataCollector<'a> {
 
     /// Attempts to extract the value for a specific field from the cached command responses.
     ///
-    /// Uses the extractor function and key associated with the field for parsing.
-    fn extract_field(&self, field: DataField) -> Option<Value> {
-        let mut success: Vec<Value> = Vec::new();
+    /// Uses the extractor function and key associated with the field for parsing. A value found
+    /// in a single response without a tag keeps pointing into it, anything else is merged into
+    /// a new value.
+    fn extract_field(&self, field: DataField) -> Option<FieldValue> {
+        let mut found = Vec::new();
         for (command, extractor) in self.miner.get_locations(field) {
-            if let Some(response_data) = self.cache.get(&command)
-                && let Some(value) = (extractor.func)(response_data, extractor.key)
+            if let Some(response) = self.cache.get(&command)
+                && let Some(value) = (extractor.func)(response, extractor.key)
             {
-                match extractor.tag {
-                    Some(tag) => {
-                        let tag = tag.to_string();
-                        success.push(json!({ tag: value.clone() }).clone());
-                    }
-                    None => {
-                        success.push(value.clone());
-                    }
-                }
+                found.push((response, extractor, value));
             }
         }
-        if success.is_empty() {
-            None
-        } else {
-            let mut response = json!({});
-            for value in success {
-                self.merge(&mut response, value)
+        match found.as_slice() {
+            [] => None,
+            [(response, extractor, _)] if extractor.tag.is_none() => Some(FieldValue::Shared {
+                response: Arc::clone(response),
+                extractor: *extractor,
+            }),
+            _ => {
+                let mut merged = json!({});
+                for &(_, extractor, value) in &found {
+                    let value = match extractor.tag {
+                        Some(tag) => {
+                            let tag = tag.to_string();
+                            json!({ tag: value.clone() })
+                        }
+                        None => value.clone(),
+                    };
+                    Self::merge_values(&mut merged, value);
+                }
+                Some(FieldValue::Owned(merged))
             }
-            Some(response)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers_as_strings() {
+        assert_eq!(f64::from_value(&json!("13501.23")), Some(13501.23));
+        assert_eq!(f64::from_value(&json!(" 42 ")), Some(42.0));
+        assert_eq!(u64::from_value(&json!("86417")), Some(86417));
+        assert_eq!(u64::from_value(&json!("650.00")), Some(650));
+        assert_eq!(i64::from_value(&json!("-5")), Some(-5));
+
+        for value in ["", "abc", "nan", "inf", "-", "62-62-62-62", "3250 W"] {
+            assert_eq!(f64::from_value(&json!(value)), None, "{value:?}");
+            assert_eq!(u64::from_value(&json!(value)), None, "{value:?}");
+        }
+        assert_eq!(u64::from_value(&json!("-1")), None);
+        assert_eq!(u64::from_value(&json!("1.5")), None);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "antminer")]
+    async fn test_collection_stats() {
+        use crate::data::device::MinerModel;
+        use crate::data::device::models::antminer::AntMinerModel;
+        use crate::miners::backends::antminer::v2020::AntMinerV2020;
+        use crate::test::api::MockAPIClient;
+        use crate::test::json::bmminer::antminer_modern::{AM_STATS, AM_SUMMARY};
+        use std::net::IpAddr;
+        use std::str::FromStr;
+
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let stats_cmd = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+        let summary_cmd = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+
+        let mut results = HashMap::new();
+        results.insert(stats_cmd.clone(), Value::from_str(AM_STATS).unwrap());
+        results.insert(summary_cmd.clone(), Value::from_str(AM_SUMMARY).unwrap());
+        let mock_api =
+            MockAPIClient::new(results).with_delay(stats_cmd.clone(), Duration::from_millis(50));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        collector.collect_all().await;
+        let stats = collector.stats();
+
+        assert_eq!(stats.backend, "AntMinerV2020");
+        let required = collector.get_required_commands(&DataField::iter().collect::<Vec<_>>());
+        assert_eq!(stats.commands.len(), required.len());
+        assert!(required.iter().all(|command| stats.get(command).is_some()));
+
+        assert!(stats.get(&stats_cmd).unwrap().success);
+        assert!(stats.get(&stats_cmd).unwrap().duration >= Duration::from_millis(50));
+        assert!(stats.get(&summary_cmd).unwrap().duration < Duration::from_millis(50));
+        assert_eq!(stats.slowest().unwrap().command, stats_cmd);
+        assert!(stats.total >= Duration::from_millis(50));
+        // commands missing from the mock are recorded as failures
+        assert!(stats.commands.iter().any(|c| !c.success));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "antminer")]
+    async fn test_collection_timing() {
+        use crate::data::device::MinerModel;
+        use crate::data::device::models::antminer::AntMinerModel;
+        use crate::miners::backends::antminer::v2020::AntMinerV2020;
+        use crate::miners::backends::traits::GetMinerData;
+        use crate::test::api::MockAPIClient;
+        use crate::test::json::bmminer::antminer_modern::{AM_STATS, AM_SUMMARY};
+        use std::net::IpAddr;
+        use std::str::FromStr;
+        use std::time::UNIX_EPOCH;
+
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let stats_cmd = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+        let summary_cmd = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+        let results = HashMap::from([
+            (stats_cmd.clone(), Value::from_str(AM_STATS).unwrap()),
+            (summary_cmd.clone(), Value::from_str(AM_SUMMARY).unwrap()),
+        ]);
+        let mock_api = MockAPIClient::new(results)
+            .with_delay(stats_cmd, Duration::from_millis(100))
+            .with_delay(summary_cmd, Duration::from_millis(100));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+        let completed = SystemTime::now();
+
+        let collected_at = data.collected_at().unwrap();
+        let duration = data.collection_duration().unwrap();
+        assert!(duration >= Duration::from_millis(200));
+        assert!(collected_at + duration <= completed);
+
+        let miner_data = miner.parse_data(data);
+        assert_eq!(miner_data.collection_duration, duration);
+        assert_eq!(
+            miner_data.timestamp,
+            collected_at.duration_since(UNIX_EPOCH).unwrap().as_secs()
+        );
+        assert!(miner_data.timestamp <= completed.duration_since(UNIX_EPOCH).unwrap().as_secs());
+
+        // data that wasn't collected is stamped when parsed
+        let parsed = miner.parse_data(FieldData::new());
+        assert_eq!(parsed.collection_duration, Duration::ZERO);
+        assert!(parsed.timestamp >= miner_data.timestamp);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "whatsminer")]
+    async fn test_extraction_diagnostics() {
+        use crate::data::device::MinerModel;
+        use crate::data::device::models::whatsminer::WhatsMinerModel;
+        use crate::miners::backends::whatsminer::WhatsMinerV3;
+        use crate::test::completeness::unextracted_fields;
+        use std::net::IpAddr;
+
+        let miner = WhatsMinerV3::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30),
+        );
+        let device_info_cmd = MinerCommand::RPC {
+            command: "get.device.info",
+            parameters: None,
+        };
+        let pools_cmd = MinerCommand::RPC {
+            command: "get.miner.status",
+            parameters: Some(json!("pools")),
+        };
+        let device_info = json!({"msg": {"network": "eth0", "system": {"api": "3.0.1"}}});
+
+        let report = unextracted_fields(
+            &miner,
+            HashMap::from([(device_info_cmd.clone(), device_info)]),
+        )
+        .await;
+
+        assert!(!report.contains_key(&DataField::ApiVersion));
+        // fields the backend has no locations for are not failures
+        assert!(!report.contains_key(&DataField::FluidFlow));
+        assert_eq!(
+            report[&DataField::Mac],
+            vec![ExtractionFailure::TypeMismatch {
+                command: device_info_cmd.clone(),
+                path: "/msg/network".to_string(),
+                expected: "object",
+                found: "string",
+            }]
+        );
+        assert_eq!(
+            report[&DataField::FirmwareVersion],
+            vec![ExtractionFailure::PathMissing {
+                command: device_info_cmd,
+                path: "/msg/system/fwversion".to_string(),
+            }]
+        );
+        assert_eq!(
+            report[&DataField::Pools],
+            vec![ExtractionFailure::CommandFailed {
+                command: pools_cmd,
+                reason: "Command not found".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "antminer")]
+    async fn test_collection_policy_skipped() {
+        use crate::data::device::MinerModel;
+        use crate::data::device::models::antminer::AntMinerModel;
+        use crate::miners::backends::antminer::v2020::AntMinerV2020;
+        use crate::miners::backends::policy::CommandPolicy;
+        use crate::test::api::MockAPIClient;
+        use crate::test::json::bmminer::antminer_modern::{AM_STATS, AM_SUMMARY};
+        use std::net::IpAddr;
+        use std::str::FromStr;
+
+        let miner = AntMinerV2020::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AntMiner(AntMinerModel::S19Pro),
+        );
+        let stats_cmd = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+        let summary_cmd = MinerCommand::RPC {
+            command: "summary",
+            parameters: None,
+        };
+
+        let mut results = HashMap::new();
+        results.insert(stats_cmd.clone(), Value::from_str(AM_STATS).unwrap());
+        results.insert(summary_cmd.clone(), Value::from_str(AM_SUMMARY).unwrap());
+        let mock_api =
+            MockAPIClient::new(results).with_policy(CommandPolicy::allow_all().deny(["stats"]));
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector
+            .collect(&[DataField::Hashrate, DataField::Fans, DataField::Hashboards])
+            .await;
+        let stats = collector.stats();
+
+        // stats is never sent, so it is neither a success nor a failure
+        assert!(stats.get(&stats_cmd).is_none());
+        assert!(stats.get(&summary_cmd).unwrap().success);
+        assert!(stats.policy_skipped.contains(&DataField::Fans));
+        assert!(stats.policy_skipped.contains(&DataField::Hashboards));
+        assert!(!data.contains_key(&DataField::Fans));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "avalon")]
+    async fn test_fields_share_responses() {
+        use crate::data::device::MinerModel;
+        use crate::data::device::models::avalon::AvalonMinerModel;
+        use crate::miners::backends::avalonminer::AvalonMinerV1;
+        use crate::test::api::MockAPIClient;
+        use crate::test::json::cgminer::avalon::{
+            DEVS_COMMAND, PARSED_STATS_COMMAND, POOLS_COMMAND, VERSION_COMMAND,
+        };
+        use std::net::IpAddr;
+        use std::str::FromStr;
+
+        let miner = AvalonMinerV1::new(
+            IpAddr::from([127, 0, 0, 1]),
+            MinerModel::AvalonMiner(AvalonMinerModel::Avalon1246),
+        );
+        let stats_cmd = MinerCommand::RPC {
+            command: "stats",
+            parameters: None,
+        };
+        let results = HashMap::from(
+            [
+                ("stats", PARSED_STATS_COMMAND),
+                ("devs", DEVS_COMMAND),
+                ("pools", POOLS_COMMAND),
+                ("version", VERSION_COMMAND),
+            ]
+            .map(|(command, response)| {
+                (
+                    MinerCommand::RPC {
+                        command,
+                        parameters: None,
+                    },
+                    Value::from_str(response).unwrap(),
+                )
+            }),
+        );
+        let mock_api = MockAPIClient::new(results);
+
+        let mut collector = DataCollector::new_with_client(&miner, &mock_api);
+        let data = collector.collect_all().await;
+        let stats = Arc::clone(&collector.cache[&stats_cmd]);
+        let summary = stats.pointer("/STATS/0/MM ID0:Summary/STATS").unwrap();
+
+        // every field reading the summary points into the one cached response
+        let shared = [
+            DataField::Fans,
+            DataField::WattageLimit,
+            DataField::IsMining,
+        ];
+        for field in shared {
+            assert!(
+                std::ptr::eq(data.get(&field).unwrap(), summary),
+                "{field:?}"
+            );
+        }
+        let copy = data.clone();
+        assert!(std::ptr::eq(copy.get(&DataField::Fans).unwrap(), summary));
+        // tagged fields are merged into values of their own
+        assert!(!std::ptr::eq(
+            data.get(&DataField::Hashboards).unwrap(),
+            summary
+        ));
+
+        // the cache, this handle, and every shared field of both copies
+        assert!(Arc::strong_count(&stats) >= 2 + 2 * shared.len());
+        drop((data, copy));
+        assert_eq!(Arc::strong_count(&stats), 2);
+        drop(collector);
+        assert_eq!(Arc::strong_count(&stats), 1);
+    }
+}
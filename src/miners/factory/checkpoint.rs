@@ -0,0 +1,292 @@
+//! Checkpoints of scan progress, so an interrupted scan can be resumed without probing the
+//! hosts it already finished.
+//!
+//! A checkpoint is a JSON lines log. The first line is a header with the format version and
+//! the scanned hosts, every following line records one host finishing a scan phase:
+//!
+//! ```text
+//! {"record":"header","version":1,"hosts":["10.0.0.1","10.0.0.2"]}
+//! {"record":"reachability","ip":"10.0.0.1","reachable":true}
+//! {"record":"identification","ip":"10.0.0.1","identity":{"model":"S19Pro","firmware":"Stock","version":null}}
+//! {"record":"reachability","ip":"10.0.0.2","reachable":false}
+//! ```
+//!
+//! Records are only ever appended, a write cut short by a crash leaves at most one truncated
+//! last line, which is ignored when loading and dropped when the file is reopened to append to.
+//!
+//! Only the hosts and their progress are stored, not the settings of the factory that scanned
+//! them, see [`MinerFactory::resume_scan`][`crate::miners::factory::MinerFactory::resume_scan`].
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::data::device::{MinerFirmware, MinerModel};
+
+/// Version of the checkpoint format, checkpoints written with another version can't be resumed.
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+/// What identification found on a host, enough to rebuild its backend without probing it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MinerIdentity {
+    pub model: MinerModel,
+    pub firmware: Option<MinerFirmware>,
+    /// The firmware version used to pick the backend, in semver form
+    pub version: Option<String>,
+}
+
+/// One line of a checkpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+pub enum CheckpointRecord {
+    /// Written once, when a scan starts
+    Header { version: u32, hosts: Vec<IpAddr> },
+    /// The port check of a host completed
+    Reachability { ip: IpAddr, reachable: bool },
+    /// Identification of a host completed, `identity` is `None` if it isn't a supported miner
+    Identification {
+        ip: IpAddr,
+        identity: Option<MinerIdentity>,
+    },
+}
+
+/// Destination for checkpoint records, written as each host finishes a scan phase.
+pub trait CheckpointWriter: Debug + Send + Sync {
+    fn write(&self, record: &CheckpointRecord) -> Result<()>;
+}
+
+/// Appends checkpoint records to a file, one JSON object per line.
+#[derive(Debug)]
+pub struct FileCheckpoint {
+    file: Mutex<File>,
+}
+
+impl FileCheckpoint {
+    /// Open `path` for appending, creating it if it doesn't exist.
+    ///
+    /// Pass the path of a loaded checkpoint to keep appending to it when resuming. A truncated
+    /// last line left by an interrupted scan is removed first, so the records appended after it
+    /// start on a line of their own.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open checkpoint {}", path.display()))?;
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        if contents.last().is_some_and(|&byte| byte != b'\n') {
+            let complete = contents
+                .iter()
+                .rposition(|&byte| byte == b'\n')
+                .map_or(0, |newline| newline + 1);
+            file.set_len(complete as u64)
+                .with_context(|| format!("failed to truncate checkpoint {}", path.display()))?;
+        }
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl CheckpointWriter for FileCheckpoint {
+    fn write(&self, record: &CheckpointRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let mut file = self.file.lock().unwrap();
+        // a single write per record, so lines from concurrent hosts don't interleave
+        file.write_all(&line)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// The progress recorded by an interrupted scan.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Checkpoint {
+    /// The hosts of the original scan, in scan order
+    pub hosts: Vec<IpAddr>,
+    reachable: HashMap<IpAddr, bool>,
+    identified: HashMap<IpAddr, Option<MinerIdentity>>,
+}
+
+impl Checkpoint {
+    /// Load a checkpoint file, see [`Checkpoint::parse`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path.as_ref())
+            .with_context(|| format!("failed to read checkpoint {}", path.as_ref().display()))?;
+        Self::parse(&contents)
+    }
+
+    /// Parse checkpoint lines, failing on a missing or mismatched version header.
+    ///
+    /// A truncated last line, left by a write that was cut short, is ignored.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut lines = contents.split_inclusive('\n').enumerate().peekable();
+        let Some((_, header)) = lines.next() else {
+            bail!("checkpoint is empty");
+        };
+        let mut checkpoint = match serde_json::from_str(header) {
+            Ok(CheckpointRecord::Header { version, hosts }) => {
+                if version != CHECKPOINT_VERSION {
+                    bail!(
+                        "unsupported checkpoint version {version}, expected {CHECKPOINT_VERSION}"
+                    );
+                }
+                Checkpoint {
+                    hosts,
+                    ..Default::default()
+                }
+            }
+            _ => bail!("checkpoint does not start with a header"),
+        };
+
+        while let Some((number, line)) = lines.next() {
+            let record = match serde_json::from_str(line) {
+                Ok(record) => record,
+                Err(_) if lines.peek().is_none() && !line.ends_with('\n') => break,
+                Err(e) => bail!("invalid checkpoint line {}: {e}", number + 1),
+            };
+            checkpoint.apply(record);
+        }
+        Ok(checkpoint)
+    }
+
+    fn apply(&mut self, record: CheckpointRecord) {
+        match record {
+            CheckpointRecord::Header { .. } => {}
+            CheckpointRecord::Reachability { ip, reachable } => {
+                self.reachable.insert(ip, reachable);
+            }
+            CheckpointRecord::Identification { ip, identity } => {
+                self.identified.insert(ip, identity);
+            }
+        }
+    }
+
+    /// Whether the port check of `ip` completed, and its result.
+    pub fn reachable(&self, ip: &IpAddr) -> Option<bool> {
+        self.reachable.get(ip).copied()
+    }
+
+    /// The identification result of `ip`, `None` if identification didn't complete.
+    pub fn identity(&self, ip: &IpAddr) -> Option<Option<&MinerIdentity>> {
+        self.identified.get(ip).map(Option::as_ref)
+    }
+
+    /// Whether `ip` needs no further probing.
+    pub fn is_complete(&self, ip: &IpAddr) -> bool {
+        self.identified.contains_key(ip) || self.reachable(ip) == Some(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::models::antminer::AntMinerModel;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let ip = IpAddr::from([10, 0, 0, 1]);
+        let dead = IpAddr::from([10, 0, 0, 2]);
+        let identity = MinerIdentity {
+            model: MinerModel::AntMiner(AntMinerModel::S19Pro),
+            firmware: Some(MinerFirmware::Stock),
+            version: Some("2022.12.6".to_string()),
+        };
+        let records = [
+            CheckpointRecord::Header {
+                version: CHECKPOINT_VERSION,
+                hosts: vec![ip, dead],
+            },
+            CheckpointRecord::Reachability {
+                ip,
+                reachable: true,
+            },
+            CheckpointRecord::Identification {
+                ip,
+                identity: Some(identity.clone()),
+            },
+            CheckpointRecord::Reachability {
+                ip: dead,
+                reachable: false,
+            },
+        ];
+        let contents: String = records
+            .iter()
+            .map(|r| serde_json::to_string(r).unwrap() + "\n")
+            .collect();
+
+        let checkpoint = Checkpoint::parse(&contents).unwrap();
+        assert_eq!(checkpoint.hosts, vec![ip, dead]);
+        assert_eq!(checkpoint.identity(&ip), Some(Some(&identity)));
+        assert!(checkpoint.is_complete(&ip));
+        assert!(checkpoint.is_complete(&dead));
+
+        // a crash mid-write leaves a truncated last line
+        let truncated = format!("{contents}{{\"record\":\"reachab");
+        assert_eq!(Checkpoint::parse(&truncated).unwrap(), checkpoint);
+
+        let corrupted = contents.replacen("\n", "\n{\"record\n", 1);
+        assert!(Checkpoint::parse(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_resumed_twice() {
+        let path = std::env::temp_dir().join(format!(
+            "asic-rs-checkpoint-resumed-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let hosts: Vec<IpAddr> = (1..=3).map(|host| IpAddr::from([10, 0, 0, host])).collect();
+        let interrupt = || {
+            let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+            file.write_all(b"{\"record\":\"reachab").unwrap();
+        };
+
+        let writer = FileCheckpoint::new(&path).unwrap();
+        writer
+            .write(&CheckpointRecord::Header {
+                version: CHECKPOINT_VERSION,
+                hosts: hosts.clone(),
+            })
+            .unwrap();
+        for ip in &hosts {
+            // each scan finishes one host before it is interrupted mid-write
+            let writer = FileCheckpoint::new(&path).unwrap();
+            assert!(!Checkpoint::load(&path).unwrap().is_complete(ip));
+            writer
+                .write(&CheckpointRecord::Reachability {
+                    ip: *ip,
+                    reachable: false,
+                })
+                .unwrap();
+            interrupt();
+        }
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.hosts, hosts);
+        assert!(hosts.iter().all(|ip| checkpoint.is_complete(ip)));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_checkpoint_version() {
+        let header = r#"{"record":"header","version":99,"hosts":[]}"#;
+        let error = Checkpoint::parse(header).unwrap_err();
+        assert!(error.to_string().contains("version 99"));
+
+        let headerless = r#"{"record":"reachability","ip":"10.0.0.1","reachable":true}"#;
+        assert!(Checkpoint::parse(headerless).is_err());
+        assert!(Checkpoint::parse("").is_err());
+    }
+}
@@ -1,10 +1,12 @@
-use crate::data::device::MinerHardware;
 use crate::data::device::models::antminer::AntMinerModel;
 use crate::data::device::models::avalon::AvalonMinerModel;
 use crate::data::device::models::bitaxe::BitaxeModel;
 use crate::data::device::models::braiins::BraiinsModel;
 use crate::data::device::models::epic::EPicModel;
 use crate::data::device::models::whatsminer::WhatsMinerModel;
+use crate::data::device::{MinerHardware, MinerModel, PowerLimits, TuningLimits};
+use crate::miners::backends::errors::ControlError;
+use measurements::{Frequency, Power, Voltage};
 
 impl From<&AvalonMinerModel> for MinerHardware {
     fn from(value: &AvalonMinerModel) -> Self {
@@ -1938,6 +1940,12 @@ impl From<&BraiinsModel> for MinerHardware {
                 fans: Some(1),
                 boards: Some(1),
             },
+            // the frame is unknown when only the control board is reported
+            BraiinsModel::BCB100 => Self {
+                chips: None,
+                fans: None,
+                boards: None,
+            },
         }
     }
 }
@@ -1950,6 +1958,12 @@ impl From<&EPicModel> for MinerHardware {
                 fans: Some(4),
                 boards: Some(3),
             },
+            EPicModel::BM720i => Self {
+                // chip count varies with the boards the unit was built from
+                chips: None,
+                fans: Some(4),
+                boards: Some(3),
+            },
             EPicModel::S19JProDual => Self {
                 chips: Some(126),
                 fans: Some(8),
@@ -2152,6 +2166,11 @@ impl From<&AntMinerModel> for MinerHardware {
                 fans: Some(4),
                 boards: Some(3),
             },
+            AntMinerModel::S19XPHydro => Self {
+                chips: Some(110),
+                fans: Some(0),
+                boards: Some(3),
+            },
             AntMinerModel::S19a => Self {
                 chips: Some(72),
                 fans: Some(4),
@@ -2230,3 +2249,330 @@ impl From<&AntMinerModel> for MinerHardware {
         }
     }
 }
+
+impl AntMinerModel {
+    /// The stock wall power of this model at its rated hashrate, per Bitmain's specifications.
+    ///
+    /// Used to estimate consumption on firmware that does not report it.
+    pub fn nominal_wattage(&self) -> Option<Power> {
+        let watts = match self {
+            AntMinerModel::S9 | AntMinerModel::S9i => 1323.0,
+            AntMinerModel::S9j => 1350.0,
+            AntMinerModel::T9 => 1450.0,
+            AntMinerModel::S17 => 2520.0,
+            AntMinerModel::S17Plus => 2920.0,
+            AntMinerModel::S17Pro => 2094.0,
+            AntMinerModel::S17e => 2880.0,
+            AntMinerModel::T17 => 2200.0,
+            AntMinerModel::T17Plus => 3150.0,
+            AntMinerModel::T17e => 2915.0,
+            AntMinerModel::S19 | AntMinerModel::S19Pro => 3250.0,
+            AntMinerModel::S19L => 3150.0,
+            AntMinerModel::S19j | AntMinerModel::S19jNoPIC => 3100.0,
+            AntMinerModel::S19i => 3010.0,
+            AntMinerModel::S19Plus => 3000.0,
+            AntMinerModel::S19ProPlus | AntMinerModel::S19jProPlus => 3355.0,
+            AntMinerModel::S19jPro => 3050.0,
+            AntMinerModel::S19XP => 3010.0,
+            AntMinerModel::S19XPHydro => 5304.0,
+            AntMinerModel::S19a | AntMinerModel::S19aPro => 3250.0,
+            AntMinerModel::S19Hydro => 5216.0,
+            AntMinerModel::S19ProHydro | AntMinerModel::S19ProPlusHydro => 5445.0,
+            AntMinerModel::S19KPro => 2760.0,
+            AntMinerModel::S19jXP => 3250.0,
+            AntMinerModel::T19 => 3150.0,
+            AntMinerModel::S21 => 3500.0,
+            AntMinerModel::S21Plus => 3564.0,
+            AntMinerModel::S21Pro => 3510.0,
+            AntMinerModel::S21XP => 3645.0,
+            AntMinerModel::S21Hydro => 5360.0,
+            AntMinerModel::S21PlusHydro => 4785.0,
+            AntMinerModel::T21 => 3610.0,
+            _ => return None,
+        };
+        Some(Power::from_watts(watts))
+    }
+}
+
+impl WhatsMinerModel {
+    /// The typical wall power of this model's series at its rated hashrate.
+    ///
+    /// The hardware revisions of a series share a power envelope, so only the series is matched.
+    pub fn nominal_wattage(&self) -> Option<Power> {
+        let name = self.to_string();
+        // `M30SPlusPlusVH10` is revision VH10 of the M30S++
+        let series = &name[..name.rfind('V')?];
+        let watts = match series {
+            "M20" | "M20P" | "M20S" | "M20SPlus" | "M21" | "M21S" | "M21SPlus" => 3360.0,
+            "M29" => 3300.0,
+            "M30" | "M30K" | "M30L" | "M30S" | "M30SPlus" => 3400.0,
+            "M30SPlusPlus" => 3472.0,
+            "M31" | "M31L" | "M31S" | "M31SE" => 3220.0,
+            "M31SPlus" => 3360.0,
+            "M31H" => 5000.0,
+            "M32" => 3344.0,
+            "M33" | "M33S" | "M33SPlus" | "M33SPlusPlus" => 7500.0,
+            "M34SPlus" => 17280.0,
+            "M36S" | "M36SPlus" | "M36SPlusPlus" => 7500.0,
+            "M39" => 6500.0,
+            "M50" | "M50S" | "M50SPlus" | "M50SPlusPlus" => 3300.0,
+            "M52S" | "M52SPlusPlus" => 5400.0,
+            "M53" | "M53H" | "M53S" | "M53SPlus" | "M53SPlusPlus" => 6600.0,
+            "M54SPlusPlus" => 7000.0,
+            "M56" | "M56S" | "M56SPlus" | "M56SPlusPlus" => 5800.0,
+            "M59" => 5550.0,
+            "M60" | "M60S" | "M60SPlus" | "M60SPlusPlus" => 3400.0,
+            "M61" | "M61S" | "M61SPlus" => 3800.0,
+            "M62SPlus" => 6000.0,
+            "M63" | "M63S" | "M63SPlus" | "M63SPlusPlus" => 7400.0,
+            "M64" | "M64S" => 7000.0,
+            "M65S" | "M65SPlus" => 8000.0,
+            "M66" | "M66S" | "M66SPlus" | "M66SPlusPlus" => 5500.0,
+            "M67S" => 6000.0,
+            "M70" => 3800.0,
+            _ => return None,
+        };
+        Some(Power::from_watts(watts))
+    }
+}
+
+impl AvalonMinerModel {
+    /// The wall power of this model at its rated hashrate, per Canaan's specifications.
+    pub fn nominal_wattage(&self) -> Option<Power> {
+        let watts = match self {
+            AvalonMinerModel::Avalon721 => 1100.0,
+            AvalonMinerModel::Avalon741 => 1150.0,
+            AvalonMinerModel::Avalon761 => 1320.0,
+            AvalonMinerModel::Avalon821 => 1200.0,
+            AvalonMinerModel::Avalon841 => 1290.0,
+            AvalonMinerModel::Avalon851 => 1450.0,
+            AvalonMinerModel::Avalon921 => 1700.0,
+            AvalonMinerModel::Avalon1026 => 2550.0,
+            AvalonMinerModel::Avalon1047 => 2380.0,
+            AvalonMinerModel::Avalon1066 => 3250.0,
+            AvalonMinerModel::Avalon1126Pro
+            | AvalonMinerModel::Avalon1166Pro
+            | AvalonMinerModel::Avalon1246
+            | AvalonMinerModel::Avalon1566 => 3420.0,
+            AvalonMinerModel::AvalonNano3 | AvalonMinerModel::AvalonNano3s => 140.0,
+            AvalonMinerModel::AvalonHomeQ => 1674.0,
+        };
+        Some(Power::from_watts(watts))
+    }
+}
+
+impl PowerLimits {
+    /// The power limits a model accepts, from its published range where one is known and derived
+    /// from its nominal wattage otherwise.
+    pub fn for_model(model: &MinerModel) -> Option<Self> {
+        let nominal = match model {
+            // the eco level of the Home Q is well below half of its full power
+            MinerModel::AvalonMiner(AvalonMinerModel::AvalonHomeQ) => {
+                return Some(Self::new(800.0, 1700.0));
+            }
+            MinerModel::AntMiner(model) => model.nominal_wattage(),
+            MinerModel::WhatsMiner(model) => model.nominal_wattage(),
+            MinerModel::AvalonMiner(model) => model.nominal_wattage(),
+            MinerModel::EPic(EPicModel::BM520i) => Some(Power::from_watts(3400.0)),
+            MinerModel::EPic(EPicModel::BM720i) => Some(Power::from_watts(3600.0)),
+            MinerModel::EPic(EPicModel::S19JProDual) => Some(Power::from_watts(6100.0)),
+            MinerModel::Braiins(BraiinsModel::BMM100 | BraiinsModel::BMM101) => {
+                Some(Power::from_watts(40.0))
+            }
+            MinerModel::Braiins(BraiinsModel::BCB100)
+            | MinerModel::Bitaxe(_)
+            | MinerModel::Unknown { .. } => None,
+        };
+        nominal.map(Self::from_nominal)
+    }
+
+    /// Validate a requested power limit for `model`.
+    ///
+    /// Models with no known range are left to the checks of their firmware.
+    pub fn validate_for(model: &MinerModel, limit: Power) -> Result<(), ControlError> {
+        match Self::for_model(model) {
+            Some(limits) => limits.validate(limit),
+            None => Ok(()),
+        }
+    }
+}
+
+impl TuningLimits {
+    /// Conservative per-board tuning envelope for a model, grouped by hashboard chip generation.
+    ///
+    /// Only models that run third party firmware with manual board tuning are listed.
+    pub fn for_model(model: &MinerModel) -> Option<Self> {
+        let MinerModel::AntMiner(model) = model else {
+            return None;
+        };
+        match model {
+            AntMinerModel::S9 | AntMinerModel::S9i | AntMinerModel::S9j | AntMinerModel::T9 => {
+                Some(Self::new((100.0, 800.0), (7.8, 9.6)))
+            }
+            AntMinerModel::S17
+            | AntMinerModel::S17Plus
+            | AntMinerModel::S17Pro
+            | AntMinerModel::S17e
+            | AntMinerModel::T17
+            | AntMinerModel::T17Plus
+            | AntMinerModel::T17e => Some(Self::new((100.0, 800.0), (16.0, 21.0))),
+            AntMinerModel::S19
+            | AntMinerModel::S19L
+            | AntMinerModel::S19Pro
+            | AntMinerModel::S19i
+            | AntMinerModel::S19Plus
+            | AntMinerModel::S19ProPlus
+            | AntMinerModel::S19a
+            | AntMinerModel::S19aPro
+            | AntMinerModel::S19Hydro
+            | AntMinerModel::S19ProHydro
+            | AntMinerModel::S19ProPlusHydro
+            | AntMinerModel::T19 => Some(Self::new((300.0, 800.0), (12.0, 15.5))),
+            AntMinerModel::S19j
+            | AntMinerModel::S19jNoPIC
+            | AntMinerModel::S19jPro
+            | AntMinerModel::S19jProPlus
+            | AntMinerModel::S19KPro => Some(Self::new((300.0, 750.0), (12.0, 15.0))),
+            AntMinerModel::S19XP | AntMinerModel::S19XPHydro | AntMinerModel::S19jXP => {
+                Some(Self::new((300.0, 750.0), (11.5, 15.0)))
+            }
+            AntMinerModel::S21
+            | AntMinerModel::S21Plus
+            | AntMinerModel::S21Pro
+            | AntMinerModel::S21XP
+            | AntMinerModel::S21Hydro
+            | AntMinerModel::S21PlusHydro
+            | AntMinerModel::T21 => Some(Self::new((300.0, 750.0), (11.0, 14.8))),
+            _ => None,
+        }
+    }
+
+    /// Validate a requested board tuning for `model`, refusing models with no known safe range.
+    pub fn validate_for(
+        model: &MinerModel,
+        frequency: Option<Frequency>,
+        voltage: Option<Voltage>,
+    ) -> Result<(), ControlError> {
+        if frequency.is_none() && voltage.is_none() {
+            return Err(ControlError::InvalidParameter(
+                "either a frequency or a voltage must be given".to_string(),
+            ));
+        }
+        Self::for_model(model)
+            .ok_or_else(|| {
+                ControlError::UnsupportedOperation(format!(
+                    "no safe tuning range is known for the {model}"
+                ))
+            })?
+            .validate(frequency, voltage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::device::MinerMake;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_every_model_has_hardware() {
+        let models = AntMinerModel::iter()
+            .map(MinerModel::AntMiner)
+            .chain(WhatsMinerModel::iter().map(MinerModel::WhatsMiner))
+            .chain(AvalonMinerModel::iter().map(MinerModel::AvalonMiner))
+            .chain(BitaxeModel::iter().map(MinerModel::Bitaxe))
+            .chain(BraiinsModel::iter().map(MinerModel::Braiins))
+            .chain(EPicModel::iter().map(MinerModel::EPic))
+            // a lone control board says nothing about the frame it is in
+            .filter(|m| *m != MinerModel::Braiins(BraiinsModel::BCB100));
+
+        let missing: Vec<String> = models
+            .filter(|m| {
+                let hardware = MinerHardware::from(m);
+                hardware.boards.is_none() || hardware.fans.is_none()
+            })
+            .map(|m| m.to_string())
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "models without board or fan counts: {missing:?}"
+        );
+    }
+
+    #[test]
+    fn test_every_model_has_power_limits() {
+        let models = AntMinerModel::iter()
+            // altcoin models only run stock firmware, which can't set a power limit
+            .filter(|m| {
+                !matches!(
+                    m,
+                    AntMinerModel::D3
+                        | AntMinerModel::HS3
+                        | AntMinerModel::L3Plus
+                        | AntMinerModel::KA3
+                        | AntMinerModel::KS3
+                        | AntMinerModel::DR5
+                        | AntMinerModel::KS5
+                        | AntMinerModel::KS5Pro
+                        | AntMinerModel::L7
+                        | AntMinerModel::K7
+                        | AntMinerModel::D7
+                        | AntMinerModel::E9Pro
+                        | AntMinerModel::D9
+                        | AntMinerModel::L9
+                        | AntMinerModel::Z15
+                        | AntMinerModel::Z15Pro
+                )
+            })
+            .map(MinerModel::AntMiner)
+            .chain(WhatsMinerModel::iter().map(MinerModel::WhatsMiner))
+            .chain(AvalonMinerModel::iter().map(MinerModel::AvalonMiner))
+            .chain(BraiinsModel::iter().map(MinerModel::Braiins))
+            .chain(EPicModel::iter().map(MinerModel::EPic))
+            .filter(|m| *m != MinerModel::Braiins(BraiinsModel::BCB100));
+
+        let missing: Vec<String> = models
+            .filter(|m| PowerLimits::for_model(m).is_none())
+            .map(|m| m.to_string())
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "models without power limits: {missing:?}"
+        );
+    }
+
+    #[test]
+    fn test_power_limits() {
+        let m30s = MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusVH10);
+        let limits = PowerLimits::for_model(&m30s).unwrap();
+        assert_eq!(limits.min, Power::from_watts(1736.0));
+        assert!((limits.max.as_watts() - 3819.2).abs() < 1e-6);
+        assert!(PowerLimits::validate_for(&m30s, Power::from_watts(3000.0)).is_ok());
+        let error = PowerLimits::validate_for(&m30s, Power::from_watts(800.0)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid parameter: power limit 800 W is outside of the allowed range 1736-3819 W"
+        );
+
+        let s19 = MinerModel::AntMiner(AntMinerModel::S19);
+        assert!(PowerLimits::validate_for(&s19, Power::from_watts(6000.0)).is_err());
+        assert!(PowerLimits::validate_for(&s19, Power::from_watts(f64::NAN)).is_err());
+
+        let home_q = MinerModel::AvalonMiner(AvalonMinerModel::AvalonHomeQ);
+        assert!(PowerLimits::validate_for(&home_q, Power::from_watts(800.0)).is_ok());
+
+        // nothing is known of these, so the firmware decides
+        let unknown = MinerModel::Unknown {
+            make: MinerMake::WhatsMiner,
+            raw: "M99SVZ10".to_string(),
+        };
+        assert!(PowerLimits::validate_for(&unknown, Power::from_watts(6000.0)).is_ok());
+    }
+
+    #[test]
+    fn test_hydro_models_have_no_fans() {
+        for model in AntMinerModel::iter().filter(AntMinerModel::is_hydro) {
+            assert_eq!(MinerHardware::from(&model).fans, Some(0), "{model}");
+        }
+    }
+}
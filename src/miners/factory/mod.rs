@@ -1,47 +1,95 @@
+pub mod checkpoint;
 mod commands;
 mod hardware;
-mod model;
+pub(crate) mod model;
+pub mod order;
+pub mod services;
 mod traits;
 
 use anyhow::Result;
-use futures::future::FutureExt;
-use futures::{Stream, StreamExt, pin_mut, stream};
+use futures::{Stream, StreamExt, stream};
 use ipnet::IpNet;
-use rand::seq::SliceRandom;
+use macaddr::MacAddr;
 use reqwest::StatusCode;
 use reqwest::header::HeaderMap;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::net::IpAddr;
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 use std::time::Duration;
 use tokio::net::TcpStream;
 use tokio::task::JoinSet;
-use tokio::time::timeout;
+use tokio::time::{Instant, timeout, timeout_at};
+use tokio_util::sync::CancellationToken;
 
+use super::api::rpc::limit::ConnectionLimit;
+use super::api::rpc::reader::RPCReadLimits;
 use super::commands::MinerCommand;
-use super::util::{send_rpc_command, send_web_command};
-use crate::data::device::{MinerFirmware, MinerMake, MinerModel};
+use super::util::{send_rpc_command_with_limits, send_web_command};
+use crate::data::device::{MinerFirmware, MinerHardware, MinerMake, MinerModel};
+use crate::data::health::HealthThresholds;
+use crate::data::sanitize::PlausibilityRanges;
+#[cfg(feature = "antminer")]
 use crate::miners::backends::antminer::AntMiner;
+#[cfg(feature = "avalon")]
 use crate::miners::backends::avalonminer::AvalonMiner;
+#[cfg(feature = "bitaxe")]
 use crate::miners::backends::bitaxe::Bitaxe;
+#[cfg(feature = "braiins")]
 use crate::miners::backends::braiins::Braiins;
+#[cfg(feature = "cgminer")]
+use crate::miners::backends::cgminer::CGMiner;
+use crate::miners::backends::dry_run::DryRun;
+#[cfg(feature = "epic")]
 use crate::miners::backends::epic::PowerPlay;
+use crate::miners::backends::errors::MinerError;
+#[cfg(feature = "luxos")]
 use crate::miners::backends::luxminer::LuxMiner;
+#[cfg(feature = "marathon")]
 use crate::miners::backends::marathon::Marathon;
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::*;
+#[cfg(feature = "vnish")]
 use crate::miners::backends::vnish::Vnish;
+#[cfg(feature = "whatsminer")]
 use crate::miners::backends::whatsminer::WhatsMiner;
-use crate::miners::factory::traits::VersionSelection;
+use checkpoint::{
+    CHECKPOINT_VERSION, Checkpoint, CheckpointRecord, CheckpointWriter, MinerIdentity,
+};
+use model::ProbeResponses;
+use order::ScanOrder;
+use services::{Service, probe_services};
 use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
-use traits::{DiscoveryCommands, ModelSelection};
+use traits::{BackendFeature, DiscoveryCommands, IdentitySelection, feature_enabled};
 
 const IDENTIFICATION_TIMEOUT: Duration = Duration::from_secs(10);
+const PROBE_SETTLE_WINDOW: Duration = Duration::from_millis(250);
 const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(1);
 const CONNECTIVITY_RETRIES: u32 = 3;
 
+/// Markers of the Avalon web UI, the make name alone shows up on unrelated admin pages.
+const AVALON_WEB_MARKERS: [&str; 3] = ["<title>Avalon", "Avalon Device", "AvalonMiner"];
+/// Path of the WhatsMiner LuCI interface, required in the web root or its redirect.
+const WHATSMINER_WEB_PATH: &str = "/cgi-bin/luci";
+/// Makes whose stock firmware answers only one API connection at a time.
+const SERIAL_API_MAKES: [MinerMake; 2] = [MinerMake::WhatsMiner, MinerMake::AvalonMiner];
+
+/// The address after `ip`, or `None` at the end of the address space.
+fn next_ip(ip: IpAddr) -> Option<IpAddr> {
+    match ip {
+        IpAddr::V4(ip) => u32::from(ip)
+            .checked_add(1)
+            .map(|n| IpAddr::from(Ipv4Addr::from(n))),
+        IpAddr::V6(ip) => u128::from(ip)
+            .checked_add(1)
+            .map(|n| IpAddr::from(Ipv6Addr::from(n))),
+    }
+}
+
 fn calculate_optimal_concurrency(ip_count: usize) -> usize {
     // Adaptive concurrency based on scale
     match ip_count {
@@ -73,28 +121,80 @@ async fn check_port_open(ip: IpAddr, port: u16, connectivity_timeout: Duration)
 async fn get_miner_type_from_command(
     ip: IpAddr,
     command: MinerCommand,
-) -> Option<(Option<MinerMake>, Option<MinerFirmware>)> {
+    rpc_read_limits: RPCReadLimits,
+    connections: ConnectionLimit,
+) -> Option<ProbeHit> {
     match command {
         MinerCommand::RPC {
             command,
             parameters: _,
         } => {
-            let response = send_rpc_command(&ip, command).await?;
-            parse_type_from_socket(response)
+            let response = {
+                let _connection = connections.acquire().await;
+                send_rpc_command_with_limits(&ip, command, &rpc_read_limits).await?
+            };
+            // a cgminer API that matches no known make is kept for the generic fallback
+            let (make, firmware) = parse_type_from_socket(&response).unwrap_or((None, None));
+            Some(ProbeHit {
+                make,
+                firmware,
+                transport: ProbeTransport::Socket,
+                response: Some((command, response)),
+            })
         }
         MinerCommand::WebAPI {
             command,
             parameters: _,
         } => {
             let response = send_web_command(&ip, command).await?;
-            parse_type_from_web(response)
+            let miner_info = parse_type_from_web(response)?;
+            let (make, firmware) =
+                confirm_web_identity(ip, miner_info, &rpc_read_limits, &connections).await?;
+            Some(ProbeHit {
+                make,
+                firmware,
+                transport: ProbeTransport::Web,
+                response: None,
+            })
         }
         _ => None,
     }
 }
 
+/// RPC command that has to confirm a make identified from its web UI, for makes whose web
+/// heuristics also match other devices.
+fn web_confirmation_command(make: MinerMake) -> Option<&'static str> {
+    match make {
+        MinerMake::WhatsMiner => Some("devdetails"),
+        MinerMake::AvalonMiner => Some("version"),
+        _ => None,
+    }
+}
+
+/// Confirm a make identified from the web UI with one RPC command, so that devices which only
+/// look like a miner from their admin page are not admitted.
+///
+/// The makes confirmed this way answer one API connection at a time, so the command waits for
+/// the socket probes to the host to finish.
+async fn confirm_web_identity(
+    ip: IpAddr,
+    miner_info: (Option<MinerMake>, Option<MinerFirmware>),
+    rpc_read_limits: &RPCReadLimits,
+    connections: &ConnectionLimit,
+) -> Option<(Option<MinerMake>, Option<MinerFirmware>)> {
+    let Some(command) = miner_info.0.and_then(web_confirmation_command) else {
+        return Some(miner_info);
+    };
+    let response = {
+        let _connection = connections.acquire_exclusive().await;
+        send_rpc_command_with_limits(&ip, command, rpc_read_limits).await?
+    };
+    let (make, _) = parse_type_from_socket(&response)?;
+    (make == miner_info.0).then_some(miner_info)
+}
+
 fn parse_type_from_socket(
-    response: serde_json::Value,
+    response: &serde_json::Value,
 ) -> Option<(Option<MinerMake>, Option<MinerFirmware>)> {
     let json_string = response.to_string().to_uppercase();
     match () {
@@ -148,12 +248,17 @@ fn parse_type_from_web(
             Some((Some(MinerMake::Bitaxe), Some(MinerFirmware::Stock)))
         }
         _ if resp_text.contains("Miner Web Dashboard") => Some((None, Some(MinerFirmware::EPic))),
-        _ if resp_text.contains("Avalon") => {
+        _ if AVALON_WEB_MARKERS
+            .iter()
+            .any(|marker| resp_text.contains(marker)) =>
+        {
             Some((Some(MinerMake::AvalonMiner), Some(MinerFirmware::Stock)))
         }
         _ if resp_text.contains("AnthillOS") => Some((None, Some(MinerFirmware::VNish))),
-        _ if redirect_header.contains("https://") && resp_status == 307
-            || resp_text.contains("/cgi-bin/luci") =>
+        _ if redirect_header.starts_with("https://")
+            && redirect_header.contains(WHATSMINER_WEB_PATH)
+            && resp_status == 307
+            || resp_text.contains(WHATSMINER_WEB_PATH) =>
         {
             Some((Some(MinerMake::WhatsMiner), Some(MinerFirmware::Stock)))
         }
@@ -161,44 +266,328 @@ fn parse_type_from_web(
     }
 }
 
+/// Default ranking of firmwares when several discovery probes match the same device.
+///
+/// Aftermarket firmwares run on stock hardware and often keep parts of its web UI, so their
+/// signatures are more specific than a stock make's and win over it.
+pub const DEFAULT_FIRMWARE_PRECEDENCE: [MinerFirmware; 8] = [
+    MinerFirmware::BraiinsOS,
+    MinerFirmware::LuxOS,
+    MinerFirmware::VNish,
+    MinerFirmware::EPic,
+    MinerFirmware::Marathon,
+    MinerFirmware::HiveOS,
+    MinerFirmware::MSKMiner,
+    MinerFirmware::Stock,
+];
+
+/// How a discovery probe reached the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ProbeTransport {
+    Socket,
+    Web,
+}
+
+/// A discovery probe that got an answer, recognizing the device unless both make and firmware
+/// are `None`.
+#[derive(Debug, Clone, PartialEq)]
+struct ProbeHit {
+    make: Option<MinerMake>,
+    firmware: Option<MinerFirmware>,
+    transport: ProbeTransport,
+    /// The RPC command and its response, kept so the model lookup can reuse it
+    response: Option<(&'static str, serde_json::Value)>,
+}
+
+/// The classification of a device, along with the RPC responses its discovery probes received.
+struct Identification {
+    make: Option<MinerMake>,
+    firmware: Option<MinerFirmware>,
+    responses: ProbeResponses,
+}
+
+impl ProbeHit {
+    fn is_classified(&self) -> bool {
+        self.make.is_some() || self.firmware.is_some()
+    }
+}
+
+/// Join probes until `deadline`, or until `settle_window` after the first hit that recognized
+/// the device, so that probes answering slightly later still get a say in the classification.
+async fn collect_probe_hits(
+    tasks: &mut JoinSet<Option<ProbeHit>>,
+    deadline: Instant,
+    settle_window: Duration,
+) -> Vec<ProbeHit> {
+    let mut hits: Vec<ProbeHit> = Vec::new();
+    let mut deadline = deadline;
+    while let Ok(Some(result)) = timeout_at(deadline, tasks.join_next()).await {
+        if let Ok(Some(hit)) = result {
+            if hit.is_classified() && !hits.iter().any(ProbeHit::is_classified) {
+                deadline = deadline.min(Instant::now() + settle_window);
+            }
+            hits.push(hit);
+        }
+    }
+    hits
+}
+
+/// Pick the classification among the probe hits that recognized the device: the firmware ranked
+/// first in `precedence` wins, firmwares missing from it rank after the listed ones, and on a tie
+/// a socket answer beats a web one. The make breaks any remaining tie so the result doesn't
+/// depend on which probe finished first.
+///
+/// A web UI claiming stock firmware is ignored when the RPC socket reports a custom firmware,
+/// the mining software knows what it is while the web UI may be left over from the stock
+/// firmware, as on miners converted with the BOS toolbox.
+fn resolve_probe_hits(
+    hits: &[ProbeHit],
+    precedence: &[MinerFirmware],
+) -> Option<(Option<MinerMake>, Option<MinerFirmware>)> {
+    let custom_socket = hits.iter().any(|hit| {
+        hit.transport == ProbeTransport::Socket
+            && hit
+                .firmware
+                .is_some_and(|firmware| firmware != MinerFirmware::Stock)
+    });
+    let rank = |hit: &ProbeHit| {
+        let firmware = hit
+            .firmware
+            .and_then(|firmware| precedence.iter().position(|f| *f == firmware))
+            .unwrap_or(precedence.len());
+        (
+            firmware,
+            hit.transport,
+            hit.make.map(|make| make.to_string()),
+        )
+    };
+    hits.iter()
+        .filter(|hit| hit.is_classified())
+        .filter(|hit| {
+            !(custom_socket
+                && hit.transport == ProbeTransport::Web
+                && hit.firmware == Some(MinerFirmware::Stock))
+        })
+        .min_by_key(|hit| rank(hit))
+        .map(|hit| (hit.make, hit.firmware))
+}
+
+/// The disabled feature of the backend of a make and firmware, if it is disabled.
+fn missing_backend_feature(
+    make: Option<MinerMake>,
+    firmware: Option<MinerFirmware>,
+) -> Option<&'static str> {
+    let feature = match firmware {
+        Some(MinerFirmware::Stock) | None => make.and_then(|make| make.backend_feature()),
+        Some(firmware) => firmware.backend_feature(),
+    };
+    feature.filter(|feature| !feature_enabled(feature))
+}
+
+/// Fail with [`MinerError::BackendNotCompiled`] if the backend of a make and firmware has its
+/// feature disabled.
+fn ensure_backend_compiled(make: Option<MinerMake>, firmware: Option<MinerFirmware>) -> Result<()> {
+    match missing_backend_feature(make, firmware) {
+        Some(feature) => Err(MinerError::BackendNotCompiled(feature).into()),
+        None => Ok(()),
+    }
+}
+
+#[allow(unused_variables)] // without any backend features every miner is unsupported
 fn select_backend(
     ip: IpAddr,
     model: Option<MinerModel>,
     firmware: Option<MinerFirmware>,
     version: Option<semver::Version>,
-) -> Option<Box<dyn Miner>> {
-    match (model, firmware) {
-        (Some(MinerModel::WhatsMiner(_)), Some(MinerFirmware::Stock)) => {
-            Some(WhatsMiner::new(ip, model?, version))
-        }
-        (Some(MinerModel::Bitaxe(_)), Some(MinerFirmware::Stock)) => {
-            Some(Bitaxe::new(ip, model?, version))
+) -> Result<Option<Box<dyn Miner>>> {
+    let Some(model) = model else {
+        return Ok(None);
+    };
+    let make = MinerMake::from(&model);
+    ensure_backend_compiled(Some(make), firmware)?;
+    Ok(match (make, firmware) {
+        #[cfg(feature = "whatsminer")]
+        (MinerMake::WhatsMiner, Some(MinerFirmware::Stock)) => {
+            Some(WhatsMiner::new(ip, model, version))
         }
-        (Some(MinerModel::AvalonMiner(_)), Some(MinerFirmware::Stock)) => {
-            Some(AvalonMiner::new(ip, model?, version))
+        #[cfg(feature = "bitaxe")]
+        (MinerMake::Bitaxe, Some(MinerFirmware::Stock)) => Some(Bitaxe::new(ip, model, version)),
+        #[cfg(feature = "avalon")]
+        (MinerMake::AvalonMiner, Some(MinerFirmware::Stock)) => {
+            Some(AvalonMiner::new(ip, model, version))
         }
-        (Some(MinerModel::AntMiner(_)), Some(MinerFirmware::Stock)) => {
-            Some(AntMiner::new(ip, model?, version))
+        #[cfg(feature = "antminer")]
+        (MinerMake::AntMiner, Some(MinerFirmware::Stock)) => {
+            Some(AntMiner::new(ip, model, version))
         }
-        (Some(_), Some(MinerFirmware::VNish)) => Some(Vnish::new(ip, model?, version)),
-        (Some(_), Some(MinerFirmware::EPic)) => Some(PowerPlay::new(ip, model?, version)),
-        (Some(_), Some(MinerFirmware::Marathon)) => Some(Marathon::new(ip, model?, version)),
-        (Some(_), Some(MinerFirmware::LuxOS)) => Some(LuxMiner::new(ip, model?, version)),
-        (Some(_), Some(MinerFirmware::BraiinsOS)) => Some(Braiins::new(ip, model?, version)),
+        #[cfg(feature = "cgminer")]
+        (MinerMake::Unknown, Some(MinerFirmware::Stock)) => Some(CGMiner::new(ip, model, version)),
+        #[cfg(feature = "vnish")]
+        (_, Some(MinerFirmware::VNish)) => Some(Vnish::new(ip, model, version)),
+        #[cfg(feature = "epic")]
+        (_, Some(MinerFirmware::EPic)) => Some(PowerPlay::new(ip, model, version)),
+        #[cfg(feature = "marathon")]
+        (_, Some(MinerFirmware::Marathon)) => Some(Marathon::new(ip, model, version)),
+        #[cfg(feature = "luxos")]
+        (_, Some(MinerFirmware::LuxOS)) => Some(LuxMiner::new(ip, model, version)),
+        #[cfg(feature = "braiins")]
+        (_, Some(MinerFirmware::BraiinsOS)) => Some(Braiins::new(ip, model, version)),
         _ => None,
+    })
+}
+
+/// Identifies a single miner by either its IP or MAC address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MinerIdentifier {
+    IP(IpAddr),
+    MAC(MacAddr),
+}
+
+impl From<IpAddr> for MinerIdentifier {
+    fn from(ip: IpAddr) -> Self {
+        MinerIdentifier::IP(ip)
     }
 }
 
+impl From<MacAddr> for MinerIdentifier {
+    fn from(mac: MacAddr) -> Self {
+        MinerIdentifier::MAC(mac)
+    }
+}
+
+/// A miner found by [`MinerFactory::scan_detailed`], with what else was found on its host.
+#[derive(Debug)]
+pub struct DetailedScanResult {
+    pub ip: IpAddr,
+    pub miner: Box<dyn Miner>,
+    /// The services with an open port on the host, empty unless probed with
+    /// [`MinerFactory::with_service_probe`].
+    pub open_services: Vec<Service>,
+}
+
+/// A host [`MinerFactory::scan_detailed`] identified as a miner, but couldn't build a backend
+/// for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedMiner {
+    pub ip: IpAddr,
+    pub make: Option<MinerMake>,
+    pub firmware: Option<MinerFirmware>,
+    /// The model the miner reported, as it reported it if it isn't in the model tables.
+    pub raw_model: Option<String>,
+    pub reason: UnsupportedReason,
+}
+
+/// Why no backend was built for an [`UnsupportedMiner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsupportedReason {
+    /// The backend exists, but its feature is disabled.
+    BackendNotCompiled(&'static str),
+    /// No backend handles the make and firmware.
+    NoBackend,
+    /// The model couldn't be read from the miner.
+    UnknownModel,
+}
+
+impl fmt::Display for UnsupportedReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnsupportedReason::BackendNotCompiled(feature) => {
+                write!(f, "backend not compiled, enable the `{feature}` feature")
+            }
+            UnsupportedReason::NoBackend => write!(f, "no backend for this make and firmware"),
+            UnsupportedReason::UnknownModel => write!(f, "model could not be read"),
+        }
+    }
+}
+
+/// Everything [`MinerFactory::scan_detailed`] found.
+#[derive(Debug, Default)]
+pub struct DetailedScan {
+    /// The miners a backend was built for.
+    pub miners: Vec<DetailedScanResult>,
+    /// The miners identified, but without a backend.
+    pub unsupported: Vec<UnsupportedMiner>,
+    /// The number of hosts scanned.
+    pub scanned: usize,
+    /// The number of hosts that answered on a miner port, or all hosts scanned when the port
+    /// check is disabled.
+    pub alive: usize,
+}
+
+impl DetailedScan {
+    /// The number of hosts identified as miners, supported or not.
+    pub fn identified(&self) -> usize {
+        self.miners.len() + self.unsupported.len()
+    }
+
+    /// The number of hosts a backend was built for.
+    pub fn supported(&self) -> usize {
+        self.miners.len()
+    }
+}
+
+/// What scanning a single host found.
+#[derive(Debug)]
+enum HostScan {
+    /// No miner port is open.
+    Unreachable,
+    /// Something answered, but no probe recognized it.
+    Unidentified,
+    Unsupported(UnsupportedMiner),
+    Miner(DetailedScanResult),
+}
+
+impl HostScan {
+    /// The miner found, failing with [`MinerError::BackendNotCompiled`] for a miner whose
+    /// backend is disabled, like a scan always has.
+    fn into_miner(self) -> Result<Option<Box<dyn Miner>>> {
+        match self {
+            HostScan::Miner(found) => Ok(Some(found.miner)),
+            HostScan::Unsupported(UnsupportedMiner {
+                reason: UnsupportedReason::BackendNotCompiled(feature),
+                ..
+            }) => Err(MinerError::BackendNotCompiled(feature).into()),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Finds and identifies miners, then builds the backend for each of them.
+///
+/// Cloning a factory copies its configuration, so one template can be cloned and given its
+/// own range per subnet. Clones share the checkpoint writer, dry run recorder and
+/// cancellation token they were configured with.
 #[derive(Debug, Clone)]
 pub struct MinerFactory {
     search_makes: Option<Vec<MinerMake>>,
     search_firmwares: Option<Vec<MinerFirmware>>,
     ips: Vec<IpAddr>,
+    scan_order: ScanOrder,
+    scan_seed: Option<u64>,
+    make_hints: HashMap<IpAddr, Vec<MinerMake>>,
+    hint_fallback: bool,
+    generic_fallback: bool,
     identification_timeout: Duration,
     connectivity_timeout: Duration,
     connectivity_retries: u32,
     concurrent: Option<usize>,
     check_port: bool,
+    service_probe: bool,
+    service_ports: HashMap<Service, u16>,
+    rpc_read_limits: RPCReadLimits,
+    hardware_overrides: HashMap<MinerIdentifier, MinerHardware>,
+    labels: HashMap<IpAddr, HashMap<String, String>>,
+    endpoints: HashMap<IpAddr, MinerEndpoints>,
+    dry_run: Option<DryRun>,
+    command_policy: CommandPolicy,
+    cancellation: Option<CancellationToken>,
+    settle_window: Duration,
+    firmware_precedence: Vec<MinerFirmware>,
+    checkpoint: Option<Arc<dyn CheckpointWriter>>,
+    resume: Option<Arc<Checkpoint>>,
+    credentials: Vec<Credentials>,
+    health_thresholds: Option<HealthThresholds>,
+    plausibility_ranges: Option<PlausibilityRanges>,
 }
 
 impl Default for MinerFactory {
@@ -209,50 +598,348 @@ impl Default for MinerFactory {
 
 impl MinerFactory {
     pub async fn scan_miner(&self, ip: IpAddr) -> Result<Option<Box<dyn Miner>>> {
+        self.cancellable(self.check_host(ip)).await?.into_miner()
+    }
+
+    async fn check_host(&self, ip: IpAddr) -> Result<HostScan> {
+        // hosts finished by an interrupted scan are restored instead of probed again
+        if let Some(resume) = &self.resume {
+            if let Some(identity) = resume.identity(&ip) {
+                return match identity {
+                    Some(identity) => self.restore_miner(ip, identity).await,
+                    None => Ok(HostScan::Unidentified),
+                };
+            }
+            match resume.reachable(&ip) {
+                Some(true) => return self.cancellable(self.discover_miner(ip)).await,
+                Some(false) => return Ok(HostScan::Unreachable),
+                None => {}
+            }
+        }
+
         // Quick port check first to avoid wasting time on dead IPs
         if (1..self.connectivity_retries).next().is_some() {
             if !self.check_port {
-                return self.get_miner(ip).await;
+                return self.cancellable(self.discover_miner(ip)).await;
             }
-            // Check for web UI
-            if check_port_open(ip, 80, self.connectivity_timeout).await {
-                return self.get_miner(ip).await;
+            let reachable = self.is_reachable(ip).await;
+            self.record(CheckpointRecord::Reachability { ip, reachable });
+            if reachable {
+                return self.cancellable(self.discover_miner(ip)).await;
             }
+        }
+        Ok(HostScan::Unreachable)
+    }
+
+    async fn is_reachable(&self, ip: IpAddr) -> bool {
+        // Check for web UI
+        check_port_open(ip, 80, self.connectivity_timeout).await
             // Check for CGMiner RPC API
-            if check_port_open(ip, 4028, self.connectivity_timeout).await {
-                return self.get_miner(ip).await;
-            }
+            || check_port_open(ip, 4028, self.connectivity_timeout).await
             // Check for alternate CGMiner RPC API
-            if check_port_open(ip, 4029, self.connectivity_timeout).await {
-                return self.get_miner(ip).await;
-            }
+            || check_port_open(ip, 4029, self.connectivity_timeout).await
             // Check for whatsminer tool API
-            if check_port_open(ip, 8889, self.connectivity_timeout).await {
-                return self.get_miner(ip).await;
+            || check_port_open(ip, 8889, self.connectivity_timeout).await
+    }
+
+    /// Identify the miner at `ip` like [`MinerFactory::scan_miner`], then probe its host for
+    /// services if enabled.
+    async fn scan_host(&self, ip: IpAddr) -> Result<HostScan> {
+        let found = self.cancellable(self.check_host(ip)).await?;
+        let HostScan::Miner(DetailedScanResult { miner, .. }) = found else {
+            return Ok(found);
+        };
+        let open_services = match self.service_probe {
+            true => {
+                let ports: Vec<(Service, u16)> = Service::ALL
+                    .into_iter()
+                    .map(|service| {
+                        let port = self.service_ports.get(&service).copied();
+                        (service, port.unwrap_or(service.default_port()))
+                    })
+                    .collect();
+                self.cancellable(async {
+                    Ok(probe_services(ip, &ports, self.connectivity_timeout).await)
+                })
+                .await?
             }
-        }
-        Ok(None)
+            false => Vec::new(),
+        };
+        Ok(HostScan::Miner(DetailedScanResult {
+            ip,
+            miner,
+            open_services,
+        }))
     }
 
     pub async fn get_miner(&self, ip: IpAddr) -> Result<Option<Box<dyn Miner>>> {
-        let search_makes = self.search_makes.clone().unwrap_or(vec![
-            MinerMake::AntMiner,
-            MinerMake::WhatsMiner,
-            MinerMake::AvalonMiner,
-            MinerMake::EPic,
-            MinerMake::Braiins,
-            MinerMake::Bitaxe,
-        ]);
-        let search_firmwares = self.search_firmwares.clone().unwrap_or(vec![
-            MinerFirmware::Stock,
-            MinerFirmware::BraiinsOS,
-            MinerFirmware::VNish,
-            MinerFirmware::EPic,
-            MinerFirmware::HiveOS,
-            MinerFirmware::LuxOS,
-            MinerFirmware::Marathon,
-            MinerFirmware::MSKMiner,
-        ]);
+        self.cancellable(self.discover_miner(ip))
+            .await?
+            .into_miner()
+    }
+
+    async fn discover_miner(&self, ip: IpAddr) -> Result<HostScan> {
+        let rpc_read_limits = self.rpc_read_limits;
+        let miner_info = self
+            .identify(ip, move |command, connections| {
+                get_miner_type_from_command(ip, command, rpc_read_limits, connections)
+            })
+            .await;
+        let Some(identification) = miner_info else {
+            return Ok(HostScan::Unidentified);
+        };
+        if let Some(feature) = missing_backend_feature(identification.make, identification.firmware)
+        {
+            return Ok(HostScan::Unsupported(UnsupportedMiner {
+                ip,
+                make: identification.make,
+                firmware: identification.firmware,
+                raw_model: None,
+                reason: UnsupportedReason::BackendNotCompiled(feature),
+            }));
+        }
+        let make = identification.make;
+        // the firmware was already read from the socket's version answer, no need to check it
+        let verify_firmware = !identification.responses.contains_key("version");
+
+        let (model, firmware, version) = match identification {
+            Identification {
+                make: Some(make),
+                firmware: Some(MinerFirmware::Stock),
+                responses,
+            } => {
+                let (model, version) = make.get_identity(ip, &responses).await;
+                (model, Some(MinerFirmware::Stock), version)
+            }
+            Identification {
+                firmware: Some(firmware),
+                responses,
+                ..
+            } => {
+                let (model, version) = firmware.get_identity(ip, &responses).await;
+                (model, Some(firmware), version)
+            }
+            Identification {
+                make: Some(make),
+                firmware,
+                responses,
+            } => {
+                let (model, version) = make.get_identity(ip, &responses).await;
+                (model, firmware, version)
+            }
+            _ => (None, None, None),
+        };
+
+        self.record(CheckpointRecord::Identification {
+            ip,
+            identity: model.clone().map(|model| MinerIdentity {
+                model,
+                firmware,
+                version: version.as_ref().map(ToString::to_string),
+            }),
+        });
+        self.build_host(ip, make, model, firmware, version, verify_firmware)
+            .await
+    }
+
+    /// Rebuild a miner from the identity an earlier scan recorded, without probing it.
+    async fn restore_miner(&self, ip: IpAddr, identity: &MinerIdentity) -> Result<HostScan> {
+        let version = identity
+            .version
+            .as_deref()
+            .and_then(|version| semver::Version::parse(version).ok());
+        let model = identity.model.clone();
+        self.build_host(ip, None, Some(model), identity.firmware, version, true)
+            .await
+    }
+
+    /// Build the miner of an identified host, or say why there is no backend for it.
+    ///
+    /// With `verify_firmware`, the backend checks the miner runs the firmware it was built for,
+    /// see [`APIClient::verify_firmware`].
+    async fn build_host(
+        &self,
+        ip: IpAddr,
+        make: Option<MinerMake>,
+        model: Option<MinerModel>,
+        firmware: Option<MinerFirmware>,
+        version: Option<semver::Version>,
+        verify_firmware: bool,
+    ) -> Result<HostScan> {
+        let unsupported = |reason| {
+            HostScan::Unsupported(UnsupportedMiner {
+                ip,
+                make: model.as_ref().map(MinerMake::from).or(make),
+                firmware,
+                raw_model: model.as_ref().map(ToString::to_string),
+                reason,
+            })
+        };
+        let built = match self.build_miner(ip, model.clone(), firmware, version).await {
+            Ok(Some(miner)) if verify_firmware => {
+                miner.verify_firmware().await.map(|_| Some(miner))
+            }
+            built => built,
+        };
+        match built {
+            Ok(Some(miner)) => Ok(HostScan::Miner(DetailedScanResult {
+                ip,
+                miner,
+                open_services: Vec::new(),
+            })),
+            Ok(None) if model.is_none() => Ok(unsupported(UnsupportedReason::UnknownModel)),
+            Ok(None) => Ok(unsupported(UnsupportedReason::NoBackend)),
+            Err(error) => match error.downcast_ref::<MinerError>() {
+                Some(&MinerError::BackendNotCompiled(feature)) => {
+                    Ok(unsupported(UnsupportedReason::BackendNotCompiled(feature)))
+                }
+                Some(MinerError::FirmwareMismatch(actual)) => {
+                    Box::pin(self.redispatch(ip, model, firmware, *actual)).await
+                }
+                _ => Err(error),
+            },
+        }
+    }
+
+    /// Build the miner again for the firmware its backend found it actually runs, recording the
+    /// corrected identity so a resumed scan doesn't repeat the detour.
+    async fn redispatch(
+        &self,
+        ip: IpAddr,
+        model: Option<MinerModel>,
+        identified: Option<MinerFirmware>,
+        actual: MinerFirmware,
+    ) -> Result<HostScan> {
+        tracing::debug!(%ip, ?identified, %actual, "backend found another firmware, rebuilding");
+        let (reported, version) = actual.get_identity(ip, &ProbeResponses::new()).await;
+        let model = reported.or(model);
+        self.record(CheckpointRecord::Identification {
+            ip,
+            identity: model.clone().map(|model| MinerIdentity {
+                model,
+                firmware: Some(actual),
+                version: version.as_ref().map(ToString::to_string),
+            }),
+        });
+        let make = model.as_ref().map(MinerMake::from);
+        self.build_host(ip, make, model, Some(actual), version, true)
+            .await
+    }
+
+    async fn build_miner(
+        &self,
+        ip: IpAddr,
+        model: Option<MinerModel>,
+        firmware: Option<MinerFirmware>,
+        version: Option<semver::Version>,
+    ) -> Result<Option<Box<dyn Miner>>> {
+        let Some(miner) = select_backend(ip, model, firmware, version)? else {
+            return Ok(None);
+        };
+        let mut miner = self.apply_hardware_override(miner).await;
+        miner.set_rpc_read_limits(self.rpc_read_limits);
+        if let Some(labels) = self.labels.get(&ip) {
+            miner.set_labels(Some(labels.clone()));
+        }
+        if let Some(endpoints) = self.endpoints.get(&ip) {
+            miner.set_endpoints(*endpoints);
+        }
+        if let Some(dry_run) = &self.dry_run {
+            miner.set_dry_run(Some(dry_run.clone()));
+        }
+        if !self.command_policy.is_unrestricted() {
+            miner.set_command_policy(self.command_policy.clone());
+        }
+        if !self.credentials.is_empty() {
+            miner.set_credentials(self.credentials.clone());
+        }
+        if let Some(thresholds) = self.health_thresholds {
+            miner.set_health_thresholds(thresholds);
+        }
+        if let Some(ranges) = &self.plausibility_ranges {
+            miner.set_plausibility_ranges(ranges.clone());
+        }
+        Ok(Some(miner))
+    }
+
+    /// Append `record` to the checkpoint, if the factory has one.
+    ///
+    /// A failed write only costs the resume its progress, so it doesn't fail the scan.
+    fn record(&self, record: CheckpointRecord) {
+        if let Some(checkpoint) = &self.checkpoint
+            && let Err(error) = checkpoint.write(&record)
+        {
+            tracing::warn!(%error, "failed to write scan checkpoint");
+        }
+    }
+
+    /// Start a checkpoint of the configured hosts, unless this scan resumes an earlier one.
+    fn start_checkpoint(&self) -> Result<()> {
+        match &self.checkpoint {
+            Some(checkpoint) if self.resume.is_none() => {
+                checkpoint.write(&CheckpointRecord::Header {
+                    version: CHECKPOINT_VERSION,
+                    hosts: self.ips.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Run `future` until it completes or the cancellation token fires, whichever is first.
+    ///
+    /// Cancelling drops `future`, which aborts its open connections and discovery tasks.
+    async fn cancellable<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        match &self.cancellation {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(MinerError::Cancelled.into()),
+                result = future => result,
+            },
+            None => future.await,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    }
+
+    /// Discovery commands for every searched make and firmware.
+    ///
+    /// Unless set explicitly, only the makes and firmwares with a compiled in backend are
+    /// searched.
+    fn full_discovery_commands(&self) -> HashSet<MinerCommand> {
+        let compiled = |feature: Option<&'static str>| feature.is_none_or(feature_enabled);
+        let search_makes = self.search_makes.clone().unwrap_or_else(|| {
+            [
+                MinerMake::AntMiner,
+                MinerMake::WhatsMiner,
+                MinerMake::AvalonMiner,
+                MinerMake::EPic,
+                MinerMake::Braiins,
+                MinerMake::Bitaxe,
+            ]
+            .into_iter()
+            .filter(|make| compiled(make.backend_feature()))
+            .collect()
+        });
+        let search_firmwares = self.search_firmwares.clone().unwrap_or_else(|| {
+            [
+                MinerFirmware::Stock,
+                MinerFirmware::BraiinsOS,
+                MinerFirmware::VNish,
+                MinerFirmware::EPic,
+                MinerFirmware::HiveOS,
+                MinerFirmware::LuxOS,
+                MinerFirmware::Marathon,
+                MinerFirmware::MSKMiner,
+            ]
+            .into_iter()
+            .filter(|firmware| compiled(firmware.backend_feature()))
+            .collect()
+        });
         let mut commands: HashSet<MinerCommand> = HashSet::new();
 
         for make in search_makes {
@@ -265,68 +952,113 @@ impl MinerFactory {
                 commands.insert(command);
             }
         }
+        commands
+    }
 
-        let mut discovery_tasks = JoinSet::new();
-        for command in commands {
-            let _ = discovery_tasks.spawn(get_miner_type_from_command(ip, command));
+    /// Discovery commands to send to an IP, limited to the hinted makes' probes if it has any.
+    fn discovery_commands(&self, ip: IpAddr) -> HashSet<MinerCommand> {
+        match self.make_hints.get(&ip) {
+            Some(makes) => makes
+                .iter()
+                .flat_map(|make| make.get_discovery_commands())
+                .collect(),
+            None => self.full_discovery_commands(),
         }
+    }
 
-        let timeout = tokio::time::sleep(self.identification_timeout).fuse();
-        let tasks = tokio::spawn(async move {
-            loop {
-                if discovery_tasks.is_empty() {
-                    return None;
-                };
-                match discovery_tasks.join_next().await.unwrap_or(Ok(None)) {
-                    Ok(Some(result)) => {
-                        return Some(result);
-                    }
-                    _ => continue,
-                };
+    /// Identify the device at `ip` from its discovery commands, each sent with `probe`.
+    ///
+    /// If a make hint found nothing and the fallback is enabled, the commands the hint skipped
+    /// are sent after.
+    async fn identify<P, F>(&self, ip: IpAddr, probe: P) -> Option<Identification>
+    where
+        P: Fn(MinerCommand, ConnectionLimit) -> F,
+        F: Future<Output = Option<ProbeHit>> + Send + 'static,
+    {
+        let commands = self.discovery_commands(ip);
+        let miner_info = self.send_probes(ip, commands.clone(), &probe).await;
+
+        // a hint that didn't match, retry with the probes that were skipped
+        if miner_info.is_none() && self.hint_fallback && self.make_hints.contains_key(&ip) {
+            let remaining: HashSet<MinerCommand> = self
+                .full_discovery_commands()
+                .difference(&commands)
+                .cloned()
+                .collect();
+            if !remaining.is_empty() {
+                return self.send_probes(ip, remaining, &probe).await;
             }
-        });
-
-        pin_mut!(timeout, tasks);
+        }
+        miner_info
+    }
 
-        let miner_info = tokio::select!(
-            Ok(miner_info) = &mut tasks => {
-                miner_info
-            },
-            _ = &mut timeout => {
-                None
+    async fn send_probes<P, F>(
+        &self,
+        ip: IpAddr,
+        commands: HashSet<MinerCommand>,
+        probe: &P,
+    ) -> Option<Identification>
+    where
+        P: Fn(MinerCommand, ConnectionLimit) -> F,
+        F: Future<Output = Option<ProbeHit>> + Send + 'static,
+    {
+        let deadline = Instant::now() + self.identification_timeout;
+        // hosts hinted to be of a make that takes one connection at a time get one probe at a time
+        let connections = match self.make_hints.get(&ip) {
+            Some(makes) if makes.iter().any(|make| SERIAL_API_MAKES.contains(make)) => {
+                ConnectionLimit::serial()
             }
-        );
+            _ => ConnectionLimit::default(),
+        };
+        let mut discovery_tasks = JoinSet::new();
+        for command in commands {
+            let _ = discovery_tasks.spawn(probe(command, connections.clone()));
+        }
 
-        match miner_info {
-            Some((Some(make), Some(MinerFirmware::Stock))) => {
-                let model = make.get_model(ip).await;
-                let version = make.get_version(ip).await;
-
-                Ok(select_backend(
-                    ip,
-                    model,
-                    Some(MinerFirmware::Stock),
-                    version,
-                ))
+        // polled in place rather than spawned, so dropping this future (e.g. when a scan
+        // stream is dropped) aborts the outstanding probes along with the JoinSet
+        let hits = collect_probe_hits(&mut discovery_tasks, deadline, self.settle_window).await;
+        let (make, firmware) = match resolve_probe_hits(&hits, &self.firmware_precedence) {
+            Some(classification) => classification,
+            // the cgminer API answered, but like no make or firmware known
+            None if self.generic_fallback && hits.iter().any(|hit| hit.response.is_some()) => {
+                (Some(MinerMake::Unknown), Some(MinerFirmware::Stock))
             }
-            Some((_, Some(firmware))) => {
-                let model = firmware.get_model(ip).await;
-                let version = firmware.get_version(ip).await;
+            None => return None,
+        };
+        Some(Identification {
+            make,
+            firmware,
+            responses: hits.into_iter().filter_map(|hit| hit.response).collect(),
+        })
+    }
 
-                if let Some(model) = model {
-                    return Ok(select_backend(ip, Some(model), Some(firmware), version));
-                }
+    async fn apply_hardware_override(&self, mut miner: Box<dyn Miner>) -> Box<dyn Miner> {
+        if self.hardware_overrides.is_empty() {
+            return miner;
+        }
 
-                Ok(select_backend(ip, model, Some(firmware), version))
-            }
-            Some((Some(make), firmware)) => {
-                let model = make.get_model(ip).await;
-                let version = make.get_version(ip).await;
+        let mut hardware = self
+            .hardware_overrides
+            .get(&MinerIdentifier::IP(miner.get_ip()))
+            .copied();
+        if hardware.is_none()
+            && self
+                .hardware_overrides
+                .keys()
+                .any(|id| matches!(id, MinerIdentifier::MAC(_)))
+            && let Some(mac) = miner.get_mac().await
+        {
+            hardware = self
+                .hardware_overrides
+                .get(&MinerIdentifier::MAC(mac))
+                .copied();
+        }
 
-                Ok(select_backend(ip, model, firmware, version))
-            }
-            _ => Ok(None),
+        if let Some(hardware) = hardware {
+            miner.set_hardware(hardware);
         }
+        miner
     }
 
     pub fn new() -> MinerFactory {
@@ -334,20 +1066,78 @@ impl MinerFactory {
             search_makes: None,
             search_firmwares: None,
             ips: Vec::new(),
+            scan_order: ScanOrder::default(),
+            scan_seed: None,
+            make_hints: HashMap::new(),
+            hint_fallback: false,
+            generic_fallback: false,
             identification_timeout: IDENTIFICATION_TIMEOUT,
             connectivity_timeout: CONNECTIVITY_TIMEOUT,
             connectivity_retries: CONNECTIVITY_RETRIES,
             concurrent: None,
             check_port: true, // Enable port checking by default
+            service_probe: false,
+            service_ports: HashMap::new(),
+            rpc_read_limits: RPCReadLimits::default(),
+            hardware_overrides: HashMap::new(),
+            labels: HashMap::new(),
+            endpoints: HashMap::new(),
+            dry_run: None,
+            command_policy: CommandPolicy::default(),
+            cancellation: None,
+            settle_window: PROBE_SETTLE_WINDOW,
+            firmware_precedence: DEFAULT_FIRMWARE_PRECEDENCE.to_vec(),
+            checkpoint: None,
+            resume: None,
+            credentials: Vec::new(),
+            health_thresholds: None,
+            plausibility_ranges: None,
         }
     }
 
+    /// Record scan progress to `checkpoint` as each host is checked and identified, so the
+    /// scan can be picked up again with [`MinerFactory::resume_scan`] if it is interrupted.
+    pub fn with_checkpoint(mut self, checkpoint: Arc<dyn CheckpointWriter>) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Continue an interrupted scan: scans the hosts of `checkpoint`, skipping the port check
+    /// of hosts found reachable and rebuilding identified miners without probing them again.
+    ///
+    /// To keep recording, pass a [`checkpoint::FileCheckpoint`] appending to the same file to
+    /// [`MinerFactory::with_checkpoint`], no new header is written when resuming.
+    ///
+    /// Only the hosts and their progress are restored. The checkpoint doesn't store the other
+    /// settings of the interrupted scan, such as the searched makes and firmwares, timeouts,
+    /// credentials or command policy, so configure this factory the same way before resuming.
+    pub fn resume_scan(mut self, checkpoint: Checkpoint) -> Self {
+        self.ips = checkpoint.hosts.clone();
+        self.resume = Some(Arc::new(checkpoint));
+        self
+    }
+
     // Port checking
     pub fn with_port_check(mut self, enabled: bool) -> Self {
         self.check_port = enabled;
         self
     }
 
+    /// Probe the host of each miner found by [`MinerFactory::scan_detailed`] for the ports of
+    /// the [`Service`]s, off by default since some sites consider port scans sensitive.
+    ///
+    /// The probe only starts once the miner is identified, with the port check's timeout.
+    pub fn with_service_probe(mut self, enabled: bool) -> Self {
+        self.service_probe = enabled;
+        self
+    }
+
+    /// Probe for `service` on `port` rather than its default port.
+    pub fn with_service_port(mut self, service: Service, port: u16) -> Self {
+        self.service_ports.insert(service, port);
+        self
+    }
+
     // Concurrency limiting
     pub fn with_concurrent_limit(mut self, limit: usize) -> Self {
         self.concurrent = Some(limit);
@@ -359,10 +1149,11 @@ impl MinerFactory {
         self
     }
 
-    pub fn update_adaptive_concurrency(&mut self) {
+    pub fn update_adaptive_concurrency(&mut self) -> &mut Self {
         if self.concurrent.is_none() {
             self.concurrent = Some(calculate_optimal_concurrency(self.ips.len()));
         }
+        self
     }
 
     // Timeout
@@ -376,6 +1167,37 @@ impl MinerFactory {
         self
     }
 
+    /// How long to keep listening for other discovery probes after the first one identifies
+    /// the device, 250ms by default.
+    ///
+    /// Devices such as BraiinsOS on an Antminer answer both as their firmware and as the stock
+    /// make, waiting lets the more specific answer win regardless of which probe is faster.
+    /// A zero window takes the first answer.
+    pub fn with_settle_window(mut self, window: Duration) -> Self {
+        self.settle_window = window;
+        self
+    }
+
+    /// Build a [`GenericCGMiner`][`crate::miners::backends::cgminer::GenericCGMiner`] for hosts
+    /// whose cgminer API answers but matches no known make or firmware, off by default.
+    ///
+    /// Such hosts are otherwise left unidentified. The generic backend reads only the standard
+    /// cgminer fields and needs the `cgminer` feature, without it the host is reported as
+    /// unsupported.
+    pub fn with_generic_fallback(mut self, enabled: bool) -> Self {
+        self.generic_fallback = enabled;
+        self
+    }
+
+    /// Ranking of firmwares used to classify a device several probes matched, first wins.
+    ///
+    /// Firmwares missing from the list rank after the listed ones, and a stock web UI never
+    /// outranks a custom firmware reported over RPC. Defaults to [`DEFAULT_FIRMWARE_PRECEDENCE`].
+    pub fn with_firmware_precedence(mut self, precedence: Vec<MinerFirmware>) -> Self {
+        self.firmware_precedence = precedence;
+        self
+    }
+
     pub fn with_connectivity_timeout(mut self, timeout: Duration) -> Self {
         self.connectivity_timeout = timeout;
         self
@@ -391,6 +1213,99 @@ impl MinerFactory {
         self
     }
 
+    /// Bound the size of and idle time between reads of RPC responses, during discovery and on
+    /// the RPC APIs of the miners this factory returns. During discovery, responses over these
+    /// limits are treated as coming from something that isn't a miner.
+    pub fn with_rpc_read_limits(mut self, limits: RPCReadLimits) -> Self {
+        self.rpc_read_limits = limits;
+        self
+    }
+
+    // Hardware
+    /// Override the expected hardware of a miner, matched by IP or MAC address.
+    /// IP overrides take precedence, MAC overrides require an extra request to the miner.
+    pub fn with_hardware_override(
+        mut self,
+        miner: impl Into<MinerIdentifier>,
+        hardware: MinerHardware,
+    ) -> Self {
+        self.hardware_overrides.insert(miner.into(), hardware);
+        self
+    }
+
+    // Labels
+    /// Attach labels, such as rack, row or slot, to the miner at an IP address.
+    /// These are copied onto every `MinerData` the miner produces.
+    pub fn with_labels(mut self, ip: IpAddr, labels: HashMap<String, String>) -> Self {
+        self.labels.insert(ip, labels);
+        self
+    }
+
+    // Endpoints
+    /// Reach the APIs of the miner at an IP address through `endpoints`, such as the ports a
+    /// gateway forwards to it. The miner's data still reports `ip`, see `reported_ip` for the
+    /// address the miner has on its own network.
+    ///
+    /// Identification still probes `ip` on the default ports, so the miner has to answer there,
+    /// or be restored from a checkpoint with [`MinerFactory::resume_scan`].
+    pub fn with_endpoints(mut self, ip: IpAddr, endpoints: MinerEndpoints) -> Self {
+        self.endpoints.insert(ip, endpoints);
+        self
+    }
+
+    // Dry-run
+    /// Record control commands in `dry_run` instead of sending them, for every miner this
+    /// factory returns. Reads are still sent to the miners.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = Some(dry_run);
+        self
+    }
+
+    // Credentials
+    /// Credentials for miners to fall back to, in order, when they reject their defaults.
+    pub fn with_credentials(mut self, credentials: Vec<Credentials>) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    // Health thresholds
+    /// Derive the health warnings of every miner this factory returns with `thresholds`, e.g.
+    /// [`HealthThresholds::immersion`] for immersion cooled sites.
+    pub fn with_health_thresholds(mut self, thresholds: HealthThresholds) -> Self {
+        self.health_thresholds = Some(thresholds);
+        self
+    }
+
+    // Plausibility ranges
+    /// Drop the readings of every miner this factory returns outside of `ranges`, for setups
+    /// outside of the defaults such as immersion cooling or very large machines.
+    pub fn with_plausibility_ranges(mut self, ranges: PlausibilityRanges) -> Self {
+        self.plausibility_ranges = Some(ranges);
+        self
+    }
+
+    // Command policy
+    /// Restrict the commands sent to every miner this factory returns, e.g.
+    /// [`CommandPolicy::read_only`] for monitoring only deployments.
+    ///
+    /// Identification still sends the discovery commands, the policy applies once the miner is
+    /// constructed.
+    pub fn with_command_policy(mut self, policy: CommandPolicy) -> Self {
+        self.command_policy = policy;
+        self
+    }
+
+    // Cancellation
+    /// Stop scans and identification once `token` is cancelled.
+    ///
+    /// In-flight probes are aborted, [`scan`][`Self::scan`], [`scan_miner`][`Self::scan_miner`]
+    /// and [`get_miner`][`Self::get_miner`] return [`MinerError::Cancelled`] and the scan
+    /// streams end without yielding hosts that were still being probed.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
     // Makes
     pub fn with_search_makes(mut self, search_makes: Vec<MinerMake>) -> Self {
         self.search_makes = Some(search_makes);
@@ -403,11 +1318,9 @@ impl MinerFactory {
     }
 
     pub fn add_search_make(mut self, search_make: MinerMake) -> Self {
-        if self.search_makes.is_none() {
-            self.search_makes = Some(vec![search_make]);
-        } else {
-            self.search_makes.as_mut().unwrap().push(search_make);
-        }
+        self.search_makes
+            .get_or_insert_with(Vec::new)
+            .push(search_make);
         self
     }
 
@@ -430,14 +1343,9 @@ impl MinerFactory {
     }
 
     pub fn add_search_firmware(mut self, search_firmware: MinerFirmware) -> Self {
-        if self.search_firmwares.is_none() {
-            self.search_firmwares = Some(vec![search_firmware]);
-        } else {
-            self.search_firmwares
-                .as_mut()
-                .unwrap()
-                .push(search_firmware);
-        }
+        self.search_firmwares
+            .get_or_insert_with(Vec::new)
+            .push(search_firmware);
         self
     }
 
@@ -458,15 +1366,16 @@ impl MinerFactory {
     pub fn with_subnet(mut self, subnet: &str) -> Result<Self> {
         let ips = self.hosts_from_subnet(subnet)?;
         self.ips.extend(ips);
-        self.shuffle_ips();
+        self.order_ips();
         Ok(self)
     }
 
     /// Set the subnet range to use, removing all other IPs
-    pub fn set_subnet(&mut self, subnet: &str) -> Result<&Self> {
+    pub fn set_subnet(&mut self, subnet: &str) -> Result<&mut Self> {
         let ips = self.hosts_from_subnet(subnet)?;
         self.ips = ips;
-        self.shuffle_ips();
+        self.make_hints.clear();
+        self.order_ips();
         Ok(self)
     }
 
@@ -475,10 +1384,24 @@ impl MinerFactory {
         Ok(network.hosts().collect())
     }
 
-    /// Randomize IP order to avoid bursts to a single switch/segment
-    fn shuffle_ips(&mut self) {
-        let mut rng = rand::rng();
-        self.ips.shuffle(&mut rng);
+    /// Put the IPs in the scan order, shuffled by default to avoid bursts to a single
+    /// switch/segment
+    fn order_ips(&mut self) {
+        self.scan_order.apply(&mut self.ips, self.scan_seed);
+    }
+
+    /// Set the order hosts are scanned in, see [`ScanOrder`]
+    pub fn with_scan_order(mut self, order: ScanOrder) -> Self {
+        self.scan_order = order;
+        self.order_ips();
+        self
+    }
+
+    /// Seed the shuffle of [`ScanOrder::Shuffled`], so repeated runs scan in the same order
+    pub fn with_scan_seed(mut self, seed: u64) -> Self {
+        self.scan_seed = Some(seed);
+        self.order_ips();
+        self
     }
 
     // Octet handlers
@@ -497,7 +1420,7 @@ impl MinerFactory {
     ) -> Result<Self> {
         let ips = self.hosts_from_octets(octet1, octet2, octet3, octet4)?;
         self.ips.extend(ips);
-        self.shuffle_ips();
+        self.order_ips();
         Ok(self)
     }
 
@@ -508,10 +1431,11 @@ impl MinerFactory {
         octet2: &str,
         octet3: &str,
         octet4: &str,
-    ) -> Result<&Self> {
+    ) -> Result<&mut Self> {
         let ips = self.hosts_from_octets(octet1, octet2, octet3, octet4)?;
         self.ips = ips;
-        self.shuffle_ips();
+        self.make_hints.clear();
+        self.order_ips();
         Ok(self)
     }
 
@@ -545,18 +1469,42 @@ impl MinerFactory {
     pub fn with_range(mut self, range_str: &str) -> Result<Self> {
         let ips = self.hosts_from_range(range_str)?;
         self.ips.extend(ips);
-        self.shuffle_ips();
+        self.order_ips();
         Ok(self)
     }
 
     /// Set the range string in the format "10.1-199.0.1-199", replacing all other IPs
-    pub fn set_range(&mut self, range_str: &str) -> Result<&Self> {
+    pub fn set_range(&mut self, range_str: &str) -> Result<&mut Self> {
         let ips = self.hosts_from_range(range_str)?;
         self.ips = ips;
-        self.shuffle_ips();
+        self.make_hints.clear();
+        self.order_ips();
         Ok(self)
     }
 
+    // Hinted ranges
+    /// Add a subnet ("10.20.0.0/22") or range ("10.20.0-3.1-254") whose hosts are expected to be
+    /// of the given makes, so only those makes' discovery commands are sent to them.
+    pub fn with_range_hinted(mut self, range_str: &str, makes: Vec<MinerMake>) -> Result<Self> {
+        let ips = if range_str.contains('/') {
+            self.hosts_from_subnet(range_str)?
+        } else {
+            self.hosts_from_range(range_str)?
+        };
+        for ip in &ips {
+            self.make_hints.insert(*ip, makes.clone());
+        }
+        self.ips.extend(ips);
+        self.order_ips();
+        Ok(self)
+    }
+
+    /// Retry hosts in hinted ranges with the full set of discovery commands if the hint fails
+    pub fn with_hint_fallback(mut self, enabled: bool) -> Self {
+        self.hint_fallback = enabled;
+        self
+    }
+
     fn hosts_from_range(&self, range_str: &str) -> Result<Vec<IpAddr>> {
         let parts: Vec<&str> = range_str.split('.').collect();
         if parts.len() != 4 {
@@ -593,29 +1541,128 @@ impl MinerFactory {
         self.ips.is_empty()
     }
 
+    /// The scan IPs as contiguous ranges in address order, for logging what a factory covers
+    pub fn ranges(&self) -> Vec<RangeInclusive<IpAddr>> {
+        let mut ips = self.ips.clone();
+        ips.sort();
+        ips.dedup();
+        let mut ranges: Vec<RangeInclusive<IpAddr>> = Vec::new();
+        for ip in ips {
+            match ranges.last_mut() {
+                Some(range) if next_ip(*range.end()) == Some(ip) => {
+                    *range = *range.start()..=ip;
+                }
+                _ => ranges.push(ip..=ip),
+            }
+        }
+        ranges
+    }
+
+    /// How many hosts are scanned at once, picked from the number of IPs unless set with
+    /// [`MinerFactory::with_concurrent_limit`]
+    pub fn concurrency_limit(&self) -> usize {
+        self.concurrent
+            .unwrap_or(calculate_optimal_concurrency(self.ips.len()))
+    }
+
+    /// How long identifying a host may take, see [`MinerFactory::with_identification_timeout`]
+    pub fn timeout(&self) -> Duration {
+        self.identification_timeout
+    }
+
+    /// How long the port check waits for each host to answer
+    pub fn connectivity_timeout(&self) -> Duration {
+        self.connectivity_timeout
+    }
+
+    /// The makes searched for, or `None` when all makes are
+    pub fn search_makes(&self) -> Option<&[MinerMake]> {
+        self.search_makes.as_deref()
+    }
+
+    /// The firmwares searched for, or `None` when all firmwares are
+    pub fn search_firmwares(&self) -> Option<&[MinerFirmware]> {
+        self.search_firmwares.as_deref()
+    }
+
+    /// The fallback credentials handed to each miner, see [`MinerFactory::with_credentials`]
+    pub fn credentials(&self) -> &[Credentials] {
+        &self.credentials
+    }
+
     /// Scan the IPs specified in the factory
     pub async fn scan(&self) -> Result<Vec<Box<dyn Miner>>> {
+        let found = self.scan_with(move |ip| self.scan_miner(ip)).await?;
+        Ok(found.into_iter().flatten().collect())
+    }
+
+    /// Scan the IPs specified in the factory, with the services found on the host of each
+    /// miner, see [`MinerFactory::with_service_probe`].
+    ///
+    /// Unlike [`MinerFactory::scan`], miners that were identified but have no backend are
+    /// returned too, along with how many hosts were scanned and answered.
+    pub async fn scan_detailed(&self) -> Result<DetailedScan> {
+        let mut scan = DetailedScan {
+            scanned: self.ips.len(),
+            ..Default::default()
+        };
+        for host in self.scan_with(move |ip| self.scan_host(ip)).await? {
+            match host {
+                HostScan::Unreachable => continue,
+                HostScan::Unidentified => {}
+                HostScan::Unsupported(unsupported) => scan.unsupported.push(unsupported),
+                HostScan::Miner(found) => scan.miners.push(found),
+            }
+            scan.alive += 1;
+        }
+        Ok(scan)
+    }
+
+    /// Run `scan_host` on every IP, keeping the results of the hosts that didn't fail.
+    async fn scan_with<'a, T, Fut>(&'a self, scan_host: impl Fn(IpAddr) -> Fut) -> Result<Vec<T>>
+    where
+        Fut: Future<Output = Result<T>> + 'a,
+    {
         if self.ips.is_empty() {
             return Err(anyhow::anyhow!(
                 "No IPs to scan. Use with_subnet, with_octets, or with_range to set IPs."
             ));
         }
 
+        self.start_checkpoint()?;
+
         let concurrency = self
             .concurrent
             .unwrap_or(calculate_optimal_concurrency(self.ips.len()));
 
-        let miners: Vec<Box<dyn Miner>> = stream::iter(self.ips.iter().copied())
-            .map(|ip| async move { self.scan_miner(ip).await.ok().flatten() })
+        let found: Vec<T> = stream::iter(self.ips.iter().copied())
+            .take_while(|_| std::future::ready(!self.is_cancelled()))
+            .map(|ip| {
+                let scanned = scan_host(ip);
+                async move { scanned.await.ok() }
+            })
             .buffer_unordered(concurrency)
-            .filter_map(|miner_opt| async move { miner_opt })
+            .filter_map(|found| async move { found })
             .collect()
             .await;
 
-        Ok(miners)
+        if self.is_cancelled() {
+            return Err(MinerError::Cancelled.into());
+        }
+        Ok(found)
     }
 
+    /// Scan the IPs specified in the factory, yielding each miner as soon as it is identified.
+    ///
+    /// Each host is checked for open ports and identified in a single task, so miners arrive in
+    /// roughly the order their identification completes. Hosts are only probed while the stream
+    /// is polled, and dropping the stream cancels all outstanding probes. The stream also ends
+    /// once the factory's cancellation token is cancelled.
     pub fn scan_stream(&self) -> Pin<Box<impl Stream<Item = Box<dyn Miner>> + Send + use<>>> {
+        if let Err(error) = self.start_checkpoint() {
+            tracing::warn!(%error, "failed to write scan checkpoint");
+        }
+
         let concurrency = self
             .concurrent
             .unwrap_or(calculate_optimal_concurrency(self.ips.len()));
@@ -624,7 +1671,9 @@ impl MinerFactory {
         let ips: Arc<[IpAddr]> = Arc::from(self.ips.as_slice());
 
         let ip_count = ips.len();
+        let scanning = Arc::clone(&factory);
         let stream = stream::iter(0..ip_count)
+            .take_while(move |_| std::future::ready(!scanning.is_cancelled()))
             .map(move |i| {
                 let factory = Arc::clone(&factory);
                 let ips = Arc::clone(&ips);
@@ -636,9 +1685,14 @@ impl MinerFactory {
         Box::pin(stream)
     }
 
+    #[allow(clippy::type_complexity)]
     pub fn scan_stream_with_ip(
         &self,
     ) -> Pin<Box<impl Stream<Item = (IpAddr, Option<Box<dyn Miner>>)> + Send + use<>>> {
+        if let Err(error) = self.start_checkpoint() {
+            tracing::warn!(%error, "failed to write scan checkpoint");
+        }
+
         let concurrency = self
             .concurrent
             .unwrap_or(calculate_optimal_concurrency(self.ips.len()));
@@ -647,13 +1701,21 @@ impl MinerFactory {
         let ips: Arc<[IpAddr]> = Arc::from(self.ips.as_slice());
 
         let ip_count = ips.len();
+        let scanning = Arc::clone(&factory);
         let stream = stream::iter(0..ip_count)
+            .take_while(move |_| std::future::ready(!scanning.is_cancelled()))
             .map(move |i| {
                 let factory = Arc::clone(&factory);
                 let ips = Arc::clone(&ips);
-                async move { (ips[i], factory.scan_miner(ips[i]).await.ok().flatten()) }
+                async move {
+                    match factory.scan_miner(ips[i]).await {
+                        Err(e) if matches!(e.downcast_ref(), Some(MinerError::Cancelled)) => None,
+                        result => Some((ips[i], result.ok().flatten())),
+                    }
+                }
             })
-            .buffer_unordered(concurrency);
+            .buffer_unordered(concurrency)
+            .filter_map(|result| async move { result });
 
         Box::pin(stream)
     }
@@ -728,12 +1790,14 @@ fn generate_ips_from_ranges(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test::html::{OPENWRT_ROUTER, PDU_OUTLETS};
+    use crate::test::simulator::{MinerSimulator, SimulatorFixture};
 
     #[test]
     fn test_parse_type_from_socket_whatsminer_2024_09_30() {
         const RAW_DATA: &str = r#"{"STATUS": [{"STATUS": "S", "Msg": "Device Details"}], "DEVDETAILS": [{"DEVDETAILS": 0, "Name": "SM", "ID": 0, "Driver": "bitmicro", "Kernel": "", "Model": "M30S+_VE40"}, {"DEVDETAILS": 1, "Name": "SM", "ID": 1, "Driver": "bitmicro", "Kernel": "", "Model": "M30S+_VE40"}, {"DEVDETAILS": 2, "Name": "SM", "ID": 2, "Driver": "bitmicro", "Kernel": "", "Model": "M30S+_VE40"}], "id": 1}"#;
         let parsed_data = serde_json::from_str(RAW_DATA).unwrap();
-        let result = parse_type_from_socket(parsed_data);
+        let result = parse_type_from_socket(&parsed_data);
         assert_eq!(
             result,
             Some((Some(MinerMake::WhatsMiner), Some(MinerFirmware::Stock)))
@@ -743,7 +1807,10 @@ mod tests {
     #[test]
     fn test_parse_type_from_web_whatsminer_2024_09_30() {
         let mut headers = HeaderMap::new();
-        headers.insert("location", "https://example.com/".parse().unwrap());
+        headers.insert(
+            "location",
+            "https://example.com/cgi-bin/luci".parse().unwrap(),
+        );
 
         let response_data = (String::from(""), headers, StatusCode::TEMPORARY_REDIRECT);
 
@@ -754,6 +1821,206 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_type_from_web_rejects_non_miners() {
+        let response_data = (String::from(PDU_OUTLETS), HeaderMap::new(), StatusCode::OK);
+        assert_eq!(parse_type_from_web(response_data), None);
+
+        // an https upgrade on its own is not a WhatsMiner
+        let mut headers = HeaderMap::new();
+        headers.insert("location", "https://10.0.0.9/".parse().unwrap());
+        let response_data = (String::new(), headers, StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(parse_type_from_web(response_data), None);
+
+        let response_data = (
+            String::from("<html><head><title>Avalon Device</title></head></html>"),
+            HeaderMap::new(),
+            StatusCode::OK,
+        );
+        assert_eq!(
+            parse_type_from_web(response_data),
+            Some((Some(MinerMake::AvalonMiner), Some(MinerFirmware::Stock)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirm_web_identity() {
+        let response_data = (
+            String::from(OPENWRT_ROUTER),
+            HeaderMap::new(),
+            StatusCode::OK,
+        );
+        let miner_info = parse_type_from_web(response_data).unwrap();
+        assert_eq!(miner_info.0, Some(MinerMake::WhatsMiner));
+
+        // nothing answers RPC on the router, so it is never admitted
+        let router = IpAddr::from([127, 0, 0, 46]);
+        let limits = RPCReadLimits::default();
+        let connections = ConnectionLimit::default();
+        assert_eq!(
+            confirm_web_identity(router, miner_info, &limits, &connections).await,
+            None
+        );
+
+        // makes with distinctive web UIs are admitted without a confirmation probe
+        let bitaxe = (Some(MinerMake::Bitaxe), Some(MinerFirmware::Stock));
+        assert_eq!(
+            confirm_web_identity(router, bitaxe, &limits, &connections).await,
+            Some(bitaxe)
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+    async fn test_confirm_web_identity_over_rpc() {
+        let response_data = (
+            String::from(OPENWRT_ROUTER),
+            HeaderMap::new(),
+            StatusCode::OK,
+        );
+        let miner_info = parse_type_from_web(response_data).unwrap();
+        let limits = RPCReadLimits::default();
+        let connections = ConnectionLimit::default();
+
+        let fixture = SimulatorFixture::load("avalon_1246").unwrap();
+        let simulator = MinerSimulator::start(IpAddr::from([127, 0, 0, 47]), fixture)
+            .await
+            .unwrap();
+        let avalon = (Some(MinerMake::AvalonMiner), Some(MinerFirmware::Stock));
+        assert_eq!(
+            confirm_web_identity(simulator.ip(), avalon, &limits, &connections).await,
+            Some(avalon)
+        );
+        assert_eq!(
+            confirm_web_identity(simulator.ip(), miner_info, &limits, &connections).await,
+            None
+        );
+    }
+
+    async fn probe_after(
+        delay: Duration,
+        make: Option<MinerMake>,
+        firmware: MinerFirmware,
+        transport: ProbeTransport,
+    ) -> Option<ProbeHit> {
+        tokio::time::sleep(delay).await;
+        Some(ProbeHit {
+            make,
+            firmware: Some(firmware),
+            transport,
+            response: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_probe_resolution() {
+        let stock_web = (
+            Some(MinerMake::AntMiner),
+            MinerFirmware::Stock,
+            ProbeTransport::Web,
+        );
+        let braiins_socket = (None, MinerFirmware::BraiinsOS, ProbeTransport::Socket);
+        let resolve = |settle_window: Duration, delays: (u64, u64)| async move {
+            let mut tasks = JoinSet::new();
+            let (make, firmware, transport) = stock_web;
+            tasks.spawn(probe_after(
+                Duration::from_millis(delays.0),
+                make,
+                firmware,
+                transport,
+            ));
+            let (make, firmware, transport) = braiins_socket;
+            tasks.spawn(probe_after(
+                Duration::from_millis(delays.1),
+                make,
+                firmware,
+                transport,
+            ));
+            let deadline = Instant::now() + Duration::from_secs(5);
+            let hits = collect_probe_hits(&mut tasks, deadline, settle_window).await;
+            resolve_probe_hits(&hits, &DEFAULT_FIRMWARE_PRECEDENCE)
+        };
+        let braiins = Some((None, Some(MinerFirmware::BraiinsOS)));
+
+        // the firmware signature wins whichever probe answers first
+        for delays in [(0, 30), (30, 0), (0, 0)] {
+            assert_eq!(resolve(PROBE_SETTLE_WINDOW, delays).await, braiins);
+        }
+
+        // answers after the window are not waited for
+        assert_eq!(
+            resolve(Duration::ZERO, (0, 100)).await,
+            Some((Some(MinerMake::AntMiner), Some(MinerFirmware::Stock)))
+        );
+    }
+
+    #[test]
+    fn test_probe_precedence() {
+        let hit = |make, firmware, transport| ProbeHit {
+            make,
+            firmware: Some(firmware),
+            transport,
+            response: None,
+        };
+        let hits = [
+            hit(
+                Some(MinerMake::AntMiner),
+                MinerFirmware::Stock,
+                ProbeTransport::Web,
+            ),
+            hit(None, MinerFirmware::VNish, ProbeTransport::Web),
+            hit(None, MinerFirmware::BraiinsOS, ProbeTransport::Socket),
+        ];
+
+        assert_eq!(
+            resolve_probe_hits(&hits, &DEFAULT_FIRMWARE_PRECEDENCE),
+            Some((None, Some(MinerFirmware::BraiinsOS)))
+        );
+        assert_eq!(
+            resolve_probe_hits(&hits, &[MinerFirmware::VNish]),
+            Some((None, Some(MinerFirmware::VNish)))
+        );
+        // the stock web UI kept by a BOS toolbox conversion doesn't outrank the socket
+        assert_eq!(
+            resolve_probe_hits(
+                &[hits[0].clone(), hits[2].clone()],
+                &[MinerFirmware::Stock, MinerFirmware::BraiinsOS]
+            ),
+            Some((None, Some(MinerFirmware::BraiinsOS)))
+        );
+
+        // both claim stock, the socket answer wins
+        let stock = [
+            hit(
+                Some(MinerMake::WhatsMiner),
+                MinerFirmware::Stock,
+                ProbeTransport::Web,
+            ),
+            hit(
+                Some(MinerMake::AntMiner),
+                MinerFirmware::Stock,
+                ProbeTransport::Socket,
+            ),
+        ];
+        assert_eq!(
+            resolve_probe_hits(&stock, &DEFAULT_FIRMWARE_PRECEDENCE),
+            Some((Some(MinerMake::AntMiner), Some(MinerFirmware::Stock)))
+        );
+        assert_eq!(resolve_probe_hits(&[], &DEFAULT_FIRMWARE_PRECEDENCE), None);
+
+        // a cgminer API that matched nothing doesn't classify the device
+        let unclassified = ProbeHit {
+            make: None,
+            firmware: None,
+            transport: ProbeTransport::Socket,
+            response: Some(("version", serde_json::json!({}))),
+        };
+        assert_eq!(
+            resolve_probe_hits(&[unclassified], &DEFAULT_FIRMWARE_PRECEDENCE),
+            None
+        );
+    }
+
     #[test]
     fn test_parse_octet_range() {
         // Test single value
@@ -794,4 +2061,319 @@ mod tests {
         assert!(ips.contains(&IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))));
         assert!(ips.contains(&IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2))));
     }
+
+    #[test]
+    fn test_scan_order() {
+        // the order applies to ranges added before and after it is set
+        let factory = MinerFactory::from_range("10.0.0-1.1-2")
+            .unwrap()
+            .with_scan_order(ScanOrder::Interleaved { stride: 1 })
+            .with_range("10.0.2.1-2")
+            .unwrap();
+        let expected: Vec<IpAddr> = [
+            [10, 0, 0, 1],
+            [10, 0, 1, 1],
+            [10, 0, 2, 1],
+            [10, 0, 0, 2],
+            [10, 0, 1, 2],
+            [10, 0, 2, 2],
+        ]
+        .into_iter()
+        .map(IpAddr::from)
+        .collect();
+        assert_eq!(factory.hosts(), expected);
+
+        let seeded = |seed| {
+            MinerFactory::from_subnet("10.0.0.0/23")
+                .unwrap()
+                .with_scan_seed(seed)
+                .hosts()
+        };
+        assert_eq!(seeded(1), seeded(1));
+        assert_ne!(seeded(1), seeded(2));
+    }
+
+    #[test]
+    #[cfg(all(feature = "antminer", feature = "whatsminer"))]
+    fn test_range_make_hints() {
+        let factory = MinerFactory::new()
+            .with_range_hinted("10.20.0.0/30", vec![MinerMake::WhatsMiner])
+            .unwrap()
+            .with_range("10.30.0.1-2")
+            .unwrap();
+        assert_eq!(factory.len(), 4);
+
+        let hinted = factory.discovery_commands(IpAddr::V4(Ipv4Addr::new(10, 20, 0, 1)));
+        assert_eq!(
+            hinted,
+            HashSet::from_iter(MinerMake::WhatsMiner.get_discovery_commands())
+        );
+        assert!(!hinted.contains(&commands::RPC_VERSION));
+
+        let unhinted = factory.discovery_commands(IpAddr::V4(Ipv4Addr::new(10, 30, 0, 1)));
+        assert_eq!(unhinted, factory.full_discovery_commands());
+        assert!(unhinted.contains(&commands::RPC_VERSION));
+        assert!(unhinted.is_superset(&hinted));
+
+        let mut factory = factory;
+        factory.set_range("10.20.0.1").unwrap();
+        assert_eq!(
+            factory.discovery_commands(IpAddr::V4(Ipv4Addr::new(10, 20, 0, 1))),
+            factory.full_discovery_commands()
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "antminer", feature = "whatsminer"))]
+    async fn test_make_hint_probes() {
+        use crate::test::api::MockAPIClient;
+        use serde_json::json;
+        use std::sync::Mutex;
+
+        /// Answer discovery probes from a mock, like the miner's API would over its socket,
+        /// recording the commands that were sent.
+        struct MockProbe {
+            client: MockAPIClient,
+            calls: Mutex<Vec<&'static str>>,
+        }
+
+        impl MockProbe {
+            fn new(results: HashMap<MinerCommand, serde_json::Value>) -> Arc<Self> {
+                Arc::new(Self {
+                    client: MockAPIClient::new(results),
+                    calls: Mutex::new(Vec::new()),
+                })
+            }
+
+            async fn probe(self: Arc<Self>, command: MinerCommand) -> Option<ProbeHit> {
+                let MinerCommand::RPC { command: name, .. } = command else {
+                    return None;
+                };
+                self.calls.lock().unwrap().push(name);
+                let response = self.client.get_api_result(&command).await.ok()?;
+                let (make, firmware) = parse_type_from_socket(&response)?;
+                Some(ProbeHit {
+                    make,
+                    firmware,
+                    transport: ProbeTransport::Socket,
+                    response: Some((name, response)),
+                })
+            }
+
+            fn calls(&self) -> Vec<&'static str> {
+                self.calls.lock().unwrap().clone()
+            }
+        }
+
+        let hinted = IpAddr::V4(Ipv4Addr::new(10, 20, 0, 1));
+        let unhinted = IpAddr::V4(Ipv4Addr::new(10, 30, 0, 1));
+        let factory = MinerFactory::new()
+            .with_range_hinted("10.20.0.1", vec![MinerMake::WhatsMiner])
+            .unwrap()
+            .with_range("10.30.0.1")
+            .unwrap();
+        let miner = || {
+            MockProbe::new(HashMap::from([
+                (
+                    commands::RPC_DEVDETAILS,
+                    json!({"DEVDETAILS": [{"Driver": "bitmicro", "Model": "M60S.VK30"}]}),
+                ),
+                (
+                    commands::RPC_VERSION,
+                    json!({"VERSION": [{"Type": "Antminer S19 Pro"}]}),
+                ),
+            ]))
+        };
+
+        // the hinted host only gets the WhatsMiner probes
+        let mock = miner();
+        let identification = factory
+            .identify(hinted, |command, _| mock.clone().probe(command))
+            .await
+            .unwrap();
+        assert_eq!(identification.make, Some(MinerMake::WhatsMiner));
+        assert!(mock.calls().contains(&"devdetails"));
+        assert!(!mock.calls().contains(&"version"));
+
+        // the unhinted host gets the full set
+        let mock = miner();
+        factory
+            .identify(unhinted, |command, _| mock.clone().probe(command))
+            .await;
+        assert!(mock.calls().contains(&"devdetails"));
+        assert!(mock.calls().contains(&"version"));
+
+        // a hint that doesn't match sends the skipped probes only with the fallback enabled
+        let answers = HashMap::from([(
+            commands::RPC_VERSION,
+            json!({"VERSION": [{"Type": "Antminer S19 Pro"}]}),
+        )]);
+        let mock = MockProbe::new(answers.clone());
+        let no_match = factory
+            .identify(hinted, |command, _| mock.clone().probe(command))
+            .await;
+        assert!(no_match.is_none());
+        assert!(!mock.calls().contains(&"version"));
+
+        let factory = factory.with_hint_fallback(true);
+        let mock = MockProbe::new(answers);
+        let identification = factory
+            .identify(hinted, |command, _| mock.clone().probe(command))
+            .await
+            .unwrap();
+        assert_eq!(identification.make, Some(MinerMake::AntMiner));
+        assert_eq!(mock.calls(), vec!["devdetails", "version"]);
+    }
+
+    #[test]
+    fn test_factory_template() {
+        let template = MinerFactory::new()
+            .with_search_makes(vec![MinerMake::AntMiner, MinerMake::WhatsMiner])
+            .with_identification_timeout_secs(5)
+            .with_credentials(vec![Credentials::new("root", "hunter2")]);
+
+        let mut rack_a = template.clone();
+        rack_a.set_subnet("10.0.1.0/30").unwrap();
+        let mut rack_b = template.clone();
+        rack_b
+            .set_range("10.0.2.1-3")
+            .unwrap()
+            .set_range("10.0.2.5")
+            .unwrap();
+        let rack_c = template.clone().with_range("10.0.3.254-255").unwrap();
+
+        assert!(template.is_empty());
+        assert_eq!(
+            rack_a.ranges(),
+            vec![IpAddr::from([10, 0, 1, 1])..=IpAddr::from([10, 0, 1, 2])]
+        );
+        assert_eq!(
+            rack_b.ranges(),
+            vec![IpAddr::from([10, 0, 2, 5])..=IpAddr::from([10, 0, 2, 5])]
+        );
+        assert_eq!(
+            rack_c.ranges(),
+            vec![IpAddr::from([10, 0, 3, 254])..=IpAddr::from([10, 0, 3, 255])]
+        );
+        assert_eq!(
+            rack_a.search_makes(),
+            Some([MinerMake::AntMiner, MinerMake::WhatsMiner].as_slice())
+        );
+        assert_eq!(rack_a.timeout(), Duration::from_secs(5));
+        assert_eq!(rack_a.concurrency_limit(), 1000);
+        assert_eq!(
+            rack_a.clone().with_concurrent_limit(50).concurrency_limit(),
+            50
+        );
+        assert_eq!(rack_a.credentials()[0].username, "root");
+
+        let debug = format!("{rack_a:?}");
+        assert!(debug.contains("root"));
+        assert!(!debug.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_backend_features() {
+        use crate::data::device::models::antminer::AntMinerModel;
+
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let s19 = MinerModel::AntMiner(AntMinerModel::S19Pro);
+        let cases = [
+            (MinerFirmware::Stock, "antminer"),
+            (MinerFirmware::BraiinsOS, "braiins"),
+            (MinerFirmware::LuxOS, "luxos"),
+        ];
+        for (firmware, feature) in cases {
+            let result = select_backend(ip, Some(s19.clone()), Some(firmware), None);
+            if feature_enabled(feature) {
+                assert!(result.unwrap().is_some(), "{feature}");
+            } else {
+                assert_eq!(
+                    result.unwrap_err().downcast_ref::<MinerError>(),
+                    Some(&MinerError::BackendNotCompiled(feature))
+                );
+            }
+        }
+
+        // there is no HiveOS backend to compile in
+        let hiveos = select_backend(ip, Some(s19), Some(MinerFirmware::HiveOS), None);
+        assert!(hiveos.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_miner() {
+        use crate::data::device::models::MinerModelFactory;
+
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let factory = MinerFactory::new();
+        let model = MinerModelFactory::new()
+            .with_firmware(MinerFirmware::HiveOS)
+            .parse_model("Antminer Z99 Mystery");
+        let host = factory
+            .build_host(ip, None, model, Some(MinerFirmware::HiveOS), None, true)
+            .await
+            .unwrap();
+        let HostScan::Unsupported(unsupported) = host else {
+            panic!("expected an unsupported miner, got {host:?}");
+        };
+        assert_eq!(unsupported.make, Some(MinerMake::AntMiner));
+        assert_eq!(unsupported.firmware, Some(MinerFirmware::HiveOS));
+        assert_eq!(
+            unsupported.raw_model.as_deref(),
+            Some("Antminer Z99 Mystery")
+        );
+        assert_eq!(unsupported.reason, UnsupportedReason::NoBackend);
+
+        // identified, but the model couldn't be read at all
+        let host = factory
+            .build_host(
+                ip,
+                Some(MinerMake::AntMiner),
+                None,
+                Some(MinerFirmware::Stock),
+                None,
+                true,
+            )
+            .await
+            .unwrap();
+        let HostScan::Unsupported(unsupported) = host else {
+            panic!("expected an unsupported miner, got {host:?}");
+        };
+        assert_eq!(unsupported.make, Some(MinerMake::AntMiner));
+        assert_eq!(unsupported.raw_model, None);
+        assert_eq!(unsupported.reason, UnsupportedReason::UnknownModel);
+        assert!(matches!(
+            HostScan::Unsupported(unsupported).into_miner(),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "whatsminer",
+        not(any(
+            feature = "antminer",
+            feature = "avalon",
+            feature = "braiins",
+            feature = "luxos",
+            feature = "marathon",
+            feature = "vnish",
+        ))
+    ))]
+    fn test_disabled_backends_not_searched() {
+        let factory = MinerFactory::new();
+        assert!(
+            !factory
+                .full_discovery_commands()
+                .contains(&commands::RPC_VERSION)
+        );
+
+        // searching a disabled make explicitly still probes it, so it is reported as missing
+        let factory = factory.with_search_makes(vec![MinerMake::AntMiner]);
+        assert!(
+            factory
+                .full_discovery_commands()
+                .contains(&commands::RPC_VERSION)
+        );
+    }
 }
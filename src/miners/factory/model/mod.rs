@@ -1,61 +1,150 @@
+//! Model and firmware version lookups, each compiled in with the feature of its backend.
+
+#[cfg(any(
+    feature = "braiins",
+    feature = "epic",
+    feature = "luxos",
+    feature = "marathon",
+))]
+use crate::data::device::MinerFirmware;
+#[cfg(any(
+    feature = "antminer",
+    feature = "avalon",
+    feature = "bitaxe",
+    feature = "cgminer",
+    feature = "vnish",
+))]
+use crate::data::device::MinerMake;
+use crate::data::device::MinerModel;
+#[cfg(any(
+    feature = "antminer",
+    feature = "avalon",
+    feature = "bitaxe",
+    feature = "braiins",
+    feature = "epic",
+    feature = "luxos",
+    feature = "marathon",
+    feature = "vnish",
+))]
 use crate::data::device::models::MinerModelFactory;
-use crate::data::device::{MinerFirmware, MinerMake, MinerModel};
-use crate::miners::factory::model::whatsminer::{get_model_whatsminer_v2, get_model_whatsminer_v3};
+#[cfg(feature = "antminer")]
+use crate::miners::backends::antminer;
+#[cfg(feature = "whatsminer")]
+use crate::miners::factory::model::whatsminer::{
+    get_model_whatsminer_v3, parse_model_whatsminer_v2,
+};
+#[cfg(any(
+    feature = "antminer",
+    feature = "avalon",
+    feature = "bitaxe",
+    feature = "braiins",
+    feature = "cgminer",
+    feature = "luxos",
+    feature = "marathon",
+    feature = "whatsminer",
+))]
 use crate::miners::util;
+#[cfg(feature = "antminer")]
 use chrono::{Datelike, NaiveDateTime};
+#[cfg(feature = "antminer")]
 use diqwest::WithDigestAuth;
+#[cfg(feature = "antminer")]
+use reqwest::StatusCode;
+#[cfg(any(feature = "antminer", feature = "epic", feature = "vnish"))]
 use reqwest::{Client, Response};
+#[cfg(any(
+    feature = "antminer",
+    feature = "bitaxe",
+    feature = "epic",
+    feature = "vnish",
+    feature = "whatsminer",
+))]
 use semver;
+use serde_json::Value;
+use std::collections::HashMap;
 use std::net::IpAddr;
 
+#[cfg(feature = "whatsminer")]
 pub mod whatsminer;
 
-pub(crate) async fn get_model_vnish(ip: IpAddr) -> Option<MinerModel> {
+/// RPC responses received by the discovery probes, keyed by command.
+pub(crate) type ProbeResponses = HashMap<&'static str, Value>;
+
+/// Reuse the response a discovery probe already received for `command`, or send it.
+#[cfg(any(
+    feature = "antminer",
+    feature = "avalon",
+    feature = "braiins",
+    feature = "cgminer",
+    feature = "luxos",
+    feature = "marathon",
+    feature = "whatsminer",
+))]
+async fn rpc_response(
+    ip: IpAddr,
+    command: &'static str,
+    responses: &ProbeResponses,
+) -> Option<Value> {
+    match responses.get(command) {
+        Some(response) => Some(response.clone()),
+        None => util::send_rpc_command(&ip, command).await,
+    }
+}
+
+#[cfg(feature = "vnish")]
+pub(crate) async fn get_identity_vnish(
+    ip: IpAddr,
+) -> (Option<MinerModel>, Option<semver::Version>) {
     let response: Option<Response> = Client::new()
         .get(format!("http://{ip}/api/v1/info"))
         .send()
         .await
         .ok();
-
-    match response {
-        Some(data) => {
-            let json_data = data.json::<serde_json::Value>().await.ok()?;
-            let model = json_data["miner"].as_str().unwrap_or("").to_uppercase();
-
-            // VnishOS typically runs on AntMiner hardware
-            let mut factory = MinerModelFactory::new();
-            factory.with_make(MinerMake::AntMiner).parse_model(&model)
-        }
+    let json_data = match response {
+        Some(data) => data.json::<Value>().await.ok(),
         None => None,
+    };
+
+    match json_data {
+        Some(json_data) => (
+            parse_model_vnish(&json_data),
+            parse_version_vnish(&json_data),
+        ),
+        None => (None, None),
     }
 }
 
-pub(crate) async fn get_version_vnish(ip: IpAddr) -> Option<semver::Version> {
-    let response: Option<Response> = Client::new()
-        .get(format!("http://{ip}/api/v1/info"))
-        .send()
-        .await
-        .ok();
+#[cfg(feature = "vnish")]
+fn parse_model_vnish(json_data: &Value) -> Option<MinerModel> {
+    let model = json_data["miner"].as_str().unwrap_or("").to_uppercase();
 
-    match response {
-        Some(data) => {
-            let json_data = data.json::<serde_json::Value>().await.ok()?;
-            let fw_version = json_data["fw_version"].as_str().unwrap_or("");
+    // VnishOS typically runs on AntMiner hardware
+    let mut factory = MinerModelFactory::new();
+    factory.with_make(MinerMake::AntMiner).parse_model(&model)
+}
 
-            // Try parsing directly first
-            if let Ok(version) = semver::Version::parse(fw_version) {
-                return Some(version);
-            }
+#[cfg(feature = "vnish")]
+fn parse_version_vnish(json_data: &Value) -> Option<semver::Version> {
+    let fw_version = json_data["fw_version"].as_str().unwrap_or("");
 
-            // If direct parsing fails, try adding .0 for patch version
-            let normalized_version = format!("{fw_version}.0");
-            semver::Version::parse(&normalized_version).ok()
-        }
-        None => None,
+    // Try parsing directly first
+    if let Ok(version) = semver::Version::parse(fw_version) {
+        return Some(version);
     }
+
+    // If direct parsing fails, try adding .0 for patch version
+    let normalized_version = format!("{fw_version}.0");
+    semver::Version::parse(&normalized_version).ok()
 }
 
-pub(crate) async fn get_model_epic(ip: IpAddr) -> Option<MinerModel> {
+#[cfg(feature = "epic")]
+/// The model and version live on separate endpoints, so both are requested at once.
+pub(crate) async fn get_identity_epic(ip: IpAddr) -> (Option<MinerModel>, Option<semver::Version>) {
+    tokio::join!(get_model_epic(ip), get_version_epic(ip))
+}
+
+#[cfg(feature = "epic")]
+async fn get_model_epic(ip: IpAddr) -> Option<MinerModel> {
     let response: Option<Response> = Client::new()
         .get(format!("http://{ip}:4028/capabilities"))
         .send()
@@ -64,7 +153,7 @@ pub(crate) async fn get_model_epic(ip: IpAddr) -> Option<MinerModel> {
 
     match response {
         Some(data) => {
-            let json_data = data.json::<serde_json::Value>().await.ok()?;
+            let json_data = data.json::<Value>().await.ok()?;
             let model = json_data["Model"].as_str().unwrap_or("").to_uppercase();
 
             MinerModelFactory::new()
@@ -74,7 +163,9 @@ pub(crate) async fn get_model_epic(ip: IpAddr) -> Option<MinerModel> {
         None => None,
     }
 }
-pub(crate) async fn get_version_epic(ip: IpAddr) -> Option<semver::Version> {
+
+#[cfg(feature = "epic")]
+async fn get_version_epic(ip: IpAddr) -> Option<semver::Version> {
     let response: Option<Response> = Client::new()
         .get(format!("http://{ip}:4028/summary"))
         .send()
@@ -83,7 +174,7 @@ pub(crate) async fn get_version_epic(ip: IpAddr) -> Option<semver::Version> {
 
     match response {
         Some(data) => {
-            let json_data = data.json::<serde_json::Value>().await.ok()?;
+            let json_data = data.json::<Value>().await.ok()?;
             let fw_version = json_data["Software"]
                 .as_str()
                 .unwrap_or("")
@@ -96,26 +187,77 @@ pub(crate) async fn get_version_epic(ip: IpAddr) -> Option<semver::Version> {
     }
 }
 
-pub(crate) async fn get_model_antminer(ip: IpAddr) -> Option<MinerModel> {
+#[cfg(feature = "antminer")]
+/// The system info carries both the model and the filesystem build date, so the separate
+/// summary request is only needed by firmware that leaves the build date out.
+///
+/// Firmware without the system info endpoint predates the JSON web API, so it is dated before
+/// it to get the legacy backend, whatever build date it reports.
+pub(crate) async fn get_identity_antminer(
+    ip: IpAddr,
+    responses: &ProbeResponses,
+) -> (Option<MinerModel>, Option<semver::Version>) {
     let response: Option<Response> = Client::new()
         .get(format!("http://{ip}/cgi-bin/get_system_info.cgi"))
         .send_with_digest_auth("root", "root")
         .await
         .ok();
-    match response {
-        Some(data) => {
-            let json_data = data.json::<serde_json::Value>().await.ok()?;
-            let model = json_data["minertype"].as_str().unwrap_or("").to_uppercase();
-
-            MinerModelFactory::new()
-                .with_make(MinerMake::AntMiner)
-                .parse_model(&model)
-        }
-        None => None,
+    if response
+        .as_ref()
+        .is_some_and(|response| response.status() == StatusCode::NOT_FOUND)
+    {
+        let (model, version) = get_identity_antminer_rpc(ip, responses).await;
+        let version = version
+            .filter(|version| *version < antminer::JSON_WEB_API_SINCE)
+            .unwrap_or(antminer::UNDATED_LEGACY_VERSION);
+        return (model, Some(version));
     }
+    let json_data = match response.filter(|response| response.status().is_success()) {
+        Some(data) => data.json::<Value>().await.ok(),
+        None => None,
+    };
+    let Some(json_data) = json_data else {
+        // the web password was changed, the RPC API doesn't need one
+        return get_identity_antminer_rpc(ip, responses).await;
+    };
+
+    let model = json_data["minertype"].as_str().unwrap_or("").to_uppercase();
+    let model = MinerModelFactory::new()
+        .with_make(MinerMake::AntMiner)
+        .parse_model(&model);
+
+    let version = match json_data["system_filesystem_version"]
+        .as_str()
+        .and_then(parse_compile_time_antminer)
+    {
+        Some(version) => Some(version),
+        None => get_version_antminer(ip).await,
+    };
+
+    (model, version)
 }
 
-pub(crate) async fn get_version_antminer(ip: IpAddr) -> Option<semver::Version> {
+#[cfg(feature = "antminer")]
+async fn get_identity_antminer_rpc(
+    ip: IpAddr,
+    responses: &ProbeResponses,
+) -> (Option<MinerModel>, Option<semver::Version>) {
+    let Some(response) = rpc_response(ip, "version", responses).await else {
+        return (None, None);
+    };
+    let model = response["VERSION"][0]["Type"].as_str().and_then(|model| {
+        MinerModelFactory::new()
+            .with_make(MinerMake::AntMiner)
+            .parse_model(&model.to_uppercase())
+    });
+    let version = response["VERSION"][0]["CompileTime"]
+        .as_str()
+        .and_then(parse_compile_time_antminer);
+    (model, version)
+}
+
+#[cfg(feature = "antminer")]
+async fn get_version_antminer(ip: IpAddr) -> Option<semver::Version> {
     let response: Option<Response> = Client::new()
         .get(format!("http://{ip}/cgi-bin/summary.cgi"))
         .send_with_digest_auth("root", "root")
@@ -123,118 +265,113 @@ pub(crate) async fn get_version_antminer(ip: IpAddr) -> Option<semver::Version>
         .ok();
     match response {
         Some(data) => {
-            let json_data = data.json::<serde_json::Value>().await.ok()?;
-            let fw_version = json_data["INFO"]["CompileTime"].as_str().unwrap_or("");
-
-            let cleaned: String = {
-                let mut parts: Vec<&str> = fw_version.split_whitespace().collect();
-                parts.remove(4); // remove time zone
-                parts.join(" ")
-            };
-
-            let dt = NaiveDateTime::parse_from_str(&cleaned, "%a %b %e %H:%M:%S %Y").ok()?;
-
-            let version =
-                semver::Version::new(dt.year() as u64, dt.month() as u64, dt.day() as u64);
-
-            Some(version)
+            let json_data = data.json::<Value>().await.ok()?;
+            json_data["INFO"]["CompileTime"]
+                .as_str()
+                .and_then(parse_compile_time_antminer)
         }
         None => None,
     }
 }
 
-pub(crate) async fn get_model_whatsminer(ip: IpAddr) -> Option<MinerModel> {
-    let response = util::send_rpc_command(&ip, "get_version").await;
-
-    match response {
-        Some(json_data) => {
-            let fw_version: Option<&str> = json_data["Msg"]["fw_ver"].as_str();
-            fw_version?;
-
-            let fw_version = fw_version.unwrap();
+#[cfg(feature = "antminer")]
+/// Parse a build date like `Tue Dec  6 16:12:30 CST 2022` into a `year.month.day` version.
+fn parse_compile_time_antminer(compile_time: &str) -> Option<semver::Version> {
+    let mut parts: Vec<&str> = compile_time.split_whitespace().collect();
+    if parts.len() != 6 {
+        return None;
+    }
+    parts.remove(4); // remove time zone
+    let cleaned = parts.join(" ");
 
-            // Parse the firmware version format: YYYYMMDD.XX.REL
-            // Extract the date components
-            if fw_version.len() < 8 {
-                return None;
-            }
+    let dt = NaiveDateTime::parse_from_str(&cleaned, "%a %b %e %H:%M:%S %Y").ok()?;
 
-            let date_part = &fw_version[..8];
-            if let (Ok(year), Ok(month), Ok(day)) = (
-                date_part[..4].parse::<u32>(),
-                date_part[4..6].parse::<u32>(),
-                date_part[6..8].parse::<u32>(),
-            ) {
-                let version = semver::Version::new(year as u64, month as u64, day as u64);
-                // Determine which API version to use based on the firmware date
-                if semver::VersionReq::parse(">=2024.11.0")
-                    .unwrap()
-                    .matches(&version)
-                {
-                    get_model_whatsminer_v3(ip).await
-                } else {
-                    get_model_whatsminer_v2(ip).await
-                }
-            } else {
-                None
-            }
-        }
-        None => None,
-    }
+    Some(semver::Version::new(
+        dt.year() as u64,
+        dt.month() as u64,
+        dt.day() as u64,
+    ))
 }
 
-pub(crate) async fn get_version_whatsminer(ip: IpAddr) -> Option<semver::Version> {
-    let response = util::send_rpc_command(&ip, "get_version").await;
-
-    match response {
-        Some(json_data) => {
-            let fw_version: Option<&str> = json_data["Msg"]["fw_ver"].as_str();
-            fw_version?;
+#[cfg(feature = "whatsminer")]
+/// `get_version` both routes the model lookup to the V2 or V3 API and gives the version, so it
+/// is only sent once.
+pub(crate) async fn get_identity_whatsminer(
+    ip: IpAddr,
+    responses: &ProbeResponses,
+) -> (Option<MinerModel>, Option<semver::Version>) {
+    let Some(version) = rpc_response(ip, "get_version", responses)
+        .await
+        .as_ref()
+        .and_then(parse_version_whatsminer)
+    else {
+        return (None, None);
+    };
+
+    // Determine which API version to use based on the firmware date
+    let model = if semver::VersionReq::parse(">=2024.11.0")
+        .unwrap()
+        .matches(&version)
+    {
+        get_model_whatsminer_v3(ip).await
+    } else {
+        let devdetails = rpc_response(ip, "devdetails", responses).await;
+        devdetails.as_ref().and_then(parse_model_whatsminer_v2)
+    };
+
+    (model, Some(version))
+}
 
-            let fw_version = fw_version.unwrap();
+#[cfg(feature = "whatsminer")]
+fn parse_version_whatsminer(json_data: &Value) -> Option<semver::Version> {
+    let fw_version = json_data["Msg"]["fw_ver"].as_str()?;
 
-            // Parse the firmware version format: YYYYMMDD.XX.REL
-            // Extract the date components
-            if fw_version.len() < 8 {
-                return None;
-            }
+    // Parse the firmware version format: YYYYMMDD.XX.REL
+    // Extract the date components
+    if fw_version.len() < 8 {
+        return None;
+    }
 
-            let date_part = &fw_version[..8];
-            if let (Ok(year), Ok(month), Ok(day)) = (
-                date_part[..4].parse::<u32>(),
-                date_part[4..6].parse::<u32>(),
-                date_part[6..8].parse::<u32>(),
-            ) {
-                let version = semver::Version::new(year as u64, month as u64, day as u64);
-                Some(version)
-            } else {
-                None
-            }
-        }
-        None => None,
+    let date_part = fw_version.get(..8)?;
+    if let (Ok(year), Ok(month), Ok(day)) = (
+        date_part[..4].parse::<u32>(),
+        date_part[4..6].parse::<u32>(),
+        date_part[6..8].parse::<u32>(),
+    ) {
+        Some(semver::Version::new(year as u64, month as u64, day as u64))
+    } else {
+        None
     }
 }
 
-pub(crate) async fn get_model_bitaxe(ip: IpAddr) -> Option<MinerModel> {
-    let raw_json = util::send_web_command(&ip, "/api/system/info")
+#[cfg(feature = "bitaxe")]
+pub(crate) async fn get_identity_bitaxe(
+    ip: IpAddr,
+) -> (Option<MinerModel>, Option<semver::Version>) {
+    let response = util::send_web_command(&ip, "/api/system/info")
         .await
-        .unwrap()
-        .0;
-    let response: serde_json::Value = serde_json::from_str(&raw_json).ok()?;
+        .and_then(|(raw_json, _, _)| serde_json::from_str::<Value>(&raw_json).ok());
 
+    match response {
+        Some(response) => (
+            parse_model_bitaxe(&response),
+            parse_version_bitaxe(&response),
+        ),
+        None => (None, None),
+    }
+}
+
+#[cfg(feature = "bitaxe")]
+fn parse_model_bitaxe(response: &Value) -> Option<MinerModel> {
     let model = response["ASICModel"].as_str()?;
 
     MinerModelFactory::new()
         .with_make(MinerMake::Bitaxe)
         .parse_model(model)
 }
-pub(crate) async fn get_version_bitaxe(ip: IpAddr) -> Option<semver::Version> {
-    let raw_json = util::send_web_command(&ip, "/api/system/info")
-        .await
-        .unwrap()
-        .0;
-    let response: serde_json::Value = serde_json::from_str(&raw_json).ok()?;
 
+#[cfg(feature = "bitaxe")]
+fn parse_version_bitaxe(response: &Value) -> Option<semver::Version> {
     match response["version"].as_str() {
         Some(v) => {
             let mut version = semver::Version::parse(v.strip_prefix("v")?).ok()?;
@@ -246,8 +383,12 @@ pub(crate) async fn get_version_bitaxe(ip: IpAddr) -> Option<semver::Version> {
     }
 }
 
-pub(crate) async fn get_model_avalonminer(ip: IpAddr) -> Option<MinerModel> {
-    let response = util::send_rpc_command(&ip, "version").await;
+#[cfg(feature = "avalon")]
+pub(crate) async fn get_model_avalonminer(
+    ip: IpAddr,
+    responses: &ProbeResponses,
+) -> Option<MinerModel> {
+    let response = rpc_response(ip, "version", responses).await;
 
     match response {
         Some(json_data) => {
@@ -265,8 +406,33 @@ pub(crate) async fn get_model_avalonminer(ip: IpAddr) -> Option<MinerModel> {
         None => None,
     }
 }
-pub(crate) async fn get_model_luxos(ip: IpAddr) -> Option<MinerModel> {
-    let response = util::send_rpc_command(&ip, "version").await;
+
+/// The generic fallback has no model table, the model is whatever `Type` the version reports,
+/// which plain cgminer leaves out.
+#[cfg(feature = "cgminer")]
+pub(crate) async fn get_model_cgminer(
+    ip: IpAddr,
+    responses: &ProbeResponses,
+) -> Option<MinerModel> {
+    let raw = rpc_response(ip, "version", responses)
+        .await
+        .and_then(|json_data| {
+            json_data
+                .pointer("/VERSION/0/Type")
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+        })
+        .filter(|raw| !raw.is_empty())
+        .unwrap_or_else(|| "cgminer".to_string());
+    Some(MinerModel::Unknown {
+        make: MinerMake::Unknown,
+        raw,
+    })
+}
+
+#[cfg(feature = "luxos")]
+pub(crate) async fn get_model_luxos(ip: IpAddr, responses: &ProbeResponses) -> Option<MinerModel> {
+    let response = rpc_response(ip, "version", responses).await;
     match response {
         Some(json_data) => {
             let model = json_data["VERSION"][0]["Type"].as_str();
@@ -281,28 +447,64 @@ pub(crate) async fn get_model_luxos(ip: IpAddr) -> Option<MinerModel> {
     }
 }
 
-pub(crate) async fn get_model_braiins_os(ip: IpAddr) -> Option<MinerModel> {
-    let response = util::send_rpc_command(&ip, "devdetails").await;
+#[cfg(feature = "braiins")]
+pub(crate) async fn get_model_braiins_os(
+    ip: IpAddr,
+    responses: &ProbeResponses,
+) -> Option<MinerModel> {
+    let response = rpc_response(ip, "devdetails", responses).await;
     match response {
         Some(json_data) => {
-            let model = json_data["DEVDETAILS"][0]["Model"].as_str();
-            model?;
-            let model = model
-                .unwrap()
-                .to_uppercase()
-                .replace("BITMAIN ", "")
-                .replace("S19XP", "S19 XP");
-
-            MinerModelFactory::new()
-                .with_firmware(MinerFirmware::BraiinsOS)
-                .parse_model(&model)
+            let model = json_data["DEVDETAILS"][0]["Model"].as_str()?;
+            parse_model_braiins_os(model)
         }
         None => None,
     }
 }
 
-pub(crate) async fn get_model_marathon(ip: IpAddr) -> Option<MinerModel> {
-    let response = util::send_rpc_command(&ip, "version").await;
+#[cfg(feature = "braiins")]
+fn parse_model_braiins_os(model: &str) -> Option<MinerModel> {
+    MinerModelFactory::new()
+        .with_firmware(MinerFirmware::BraiinsOS)
+        .parse_model(&normalize_model_braiins_os(model))
+}
+
+#[cfg(feature = "braiins")]
+/// Normalize the `Model` reported in BOS devdetails to the strings used by the model tables.
+fn normalize_model_braiins_os(model: &str) -> String {
+    let model = model
+        .to_uppercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let model = model.strip_prefix("BITMAIN ").unwrap_or(&model);
+
+    // Braiins Mini Miners report as "BMM100", "BMM 100" or "Braiins Mini Miner BMM 100"
+    if let Some((_, id)) = model.split_once("BMM") {
+        return format!("BMM {}", id.replace(' ', ""));
+    }
+
+    // BCB100 control boards driving Antminer hashboards report the frame alongside the board
+    let model = ["(BCB100)", "BCB100"]
+        .iter()
+        .find_map(|board| model.strip_suffix(board))
+        .map(str::trim)
+        .filter(|frame| !frame.is_empty())
+        .unwrap_or(model);
+
+    let model = model.replace("S19XP", "S19 XP");
+    match model.strip_suffix("S19 PRO+") {
+        Some(prefix) => format!("{prefix}S19PRO+"),
+        None => model,
+    }
+}
+
+#[cfg(feature = "marathon")]
+pub(crate) async fn get_model_marathon(
+    ip: IpAddr,
+    responses: &ProbeResponses,
+) -> Option<MinerModel> {
+    let response = rpc_response(ip, "version", responses).await;
 
     match response {
         Some(json_data) => {
@@ -318,3 +520,91 @@ pub(crate) async fn get_model_marathon(ip: IpAddr) -> Option<MinerModel> {
         None => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "antminer", feature = "braiins"))]
+    use super::*;
+    #[cfg(feature = "braiins")]
+    use crate::data::device::models::antminer::AntMinerModel;
+    #[cfg(feature = "braiins")]
+    use crate::data::device::models::braiins::BraiinsModel;
+
+    #[test]
+    #[cfg(feature = "antminer")]
+    fn test_parse_compile_time_antminer() {
+        assert_eq!(
+            parse_compile_time_antminer("Tue Dec  6 16:12:30 CST 2022"),
+            Some(semver::Version::new(2022, 12, 6))
+        );
+        assert_eq!(parse_compile_time_antminer("2022-12-06"), None);
+        assert_eq!(parse_compile_time_antminer(""), None);
+    }
+
+    #[test]
+    #[cfg(feature = "braiins")]
+    fn test_parse_model_braiins_os() {
+        let cases = [
+            (
+                "Bitmain Antminer S19 Pro",
+                MinerModel::AntMiner(AntMinerModel::S19Pro),
+            ),
+            (
+                "Antminer S19 Pro+",
+                MinerModel::AntMiner(AntMinerModel::S19ProPlus),
+            ),
+            (
+                "Antminer S19j Pro",
+                MinerModel::AntMiner(AntMinerModel::S19jPro),
+            ),
+            (
+                "Antminer S19j Pro+",
+                MinerModel::AntMiner(AntMinerModel::S19jProPlus),
+            ),
+            (
+                "Antminer S19J PRO+",
+                MinerModel::AntMiner(AntMinerModel::S19jProPlus),
+            ),
+            (
+                "Bitmain Antminer S19XP",
+                MinerModel::AntMiner(AntMinerModel::S19XP),
+            ),
+            (
+                "Antminer S19 XP",
+                MinerModel::AntMiner(AntMinerModel::S19XP),
+            ),
+            (
+                "Antminer S19k Pro",
+                MinerModel::AntMiner(AntMinerModel::S19KPro),
+            ),
+            ("Antminer S21", MinerModel::AntMiner(AntMinerModel::S21)),
+            (
+                "Antminer T17+",
+                MinerModel::AntMiner(AntMinerModel::T17Plus),
+            ),
+            ("Antminer S9", MinerModel::AntMiner(AntMinerModel::S9)),
+            (
+                "Antminer S19j Pro (BCB100)",
+                MinerModel::AntMiner(AntMinerModel::S19jPro),
+            ),
+            (
+                "Antminer S19 BCB100",
+                MinerModel::AntMiner(AntMinerModel::S19),
+            ),
+            ("BCB100", MinerModel::Braiins(BraiinsModel::BCB100)),
+            (
+                "Braiins Mini Miner BMM 100",
+                MinerModel::Braiins(BraiinsModel::BMM100),
+            ),
+            ("BMM100", MinerModel::Braiins(BraiinsModel::BMM100)),
+            (
+                "Braiins Mini Miner BMM101",
+                MinerModel::Braiins(BraiinsModel::BMM101),
+            ),
+        ];
+
+        for (raw, expected) in cases {
+            assert_eq!(parse_model_braiins_os(raw), Some(expected), "{raw}");
+        }
+    }
+}
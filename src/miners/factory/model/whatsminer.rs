@@ -3,26 +3,26 @@ use crate::data::device::{MinerMake, MinerModel};
 use crate::miners::backends::traits::APIClient;
 use crate::miners::backends::whatsminer::v3;
 use crate::miners::commands::MinerCommand;
-use crate::miners::util;
-use serde_json::json;
+use serde_json::{Value, json};
 use std::net::IpAddr;
 
-pub(crate) async fn get_model_whatsminer_v2(ip: IpAddr) -> Option<MinerModel> {
-    let response = util::send_rpc_command(&ip, "devdetails").await;
-    match response {
-        Some(json_data) => {
-            let model = json_data["DEVDETAILS"][0]["Model"].as_str();
-            model?;
-            let mut model = model.unwrap().to_uppercase().replace("_", "");
-            model.pop();
-            model.push('0');
+/// Reduce a reported model like `M60S++_VL31` to the model table key `M60S++VL30`.
+///
+/// The last digit is the chip bin, which doesn't change the hardware.
+pub(crate) fn normalize_model(raw: &str) -> String {
+    let mut model = raw.trim().to_uppercase().replace(['_', ' '], "");
+    model.pop();
+    model.push('0');
+    model
+}
 
-            MinerModelFactory::new()
-                .with_make(MinerMake::WhatsMiner)
-                .parse_model(&model)
-        }
-        None => None,
-    }
+/// Model from the V2 API's `devdetails`, which the discovery probes usually already received.
+pub(crate) fn parse_model_whatsminer_v2(devdetails: &Value) -> Option<MinerModel> {
+    let model = devdetails["DEVDETAILS"][0]["Model"].as_str()?;
+
+    MinerModelFactory::new()
+        .with_make(MinerMake::WhatsMiner)
+        .parse_model(&normalize_model(model))
 }
 
 pub(crate) async fn get_model_whatsminer_v3(ip: IpAddr) -> Option<MinerModel> {
@@ -36,17 +36,11 @@ pub(crate) async fn get_model_whatsminer_v3(ip: IpAddr) -> Option<MinerModel> {
 
     match response {
         Ok(json_data) => {
-            let model = json_data["msg"]["miner"]["type"].as_str();
-
-            model?;
-
-            let mut model = model.unwrap().to_uppercase().replace("_", "");
-            model.pop();
-            model.push('0');
+            let model = json_data["msg"]["miner"]["type"].as_str()?;
 
             MinerModelFactory::new()
                 .with_make(MinerMake::WhatsMiner)
-                .parse_model(&model)
+                .parse_model(&normalize_model(model))
         }
         Err(_) => None,
     }
@@ -0,0 +1,135 @@
+//! The order hosts are scanned in.
+//!
+//! Scanning a range in ascending order sends all probes through one top-of-rack switch at a
+//! time, which can trip storm control on the switch. The default shuffles the hosts, so the load
+//! spreads over the whole range.
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScanOrder {
+    /// Ascending by IP.
+    Sequential,
+    /// In random order, reproducible with [`super::MinerFactory::with_scan_seed`].
+    #[default]
+    Shuffled,
+    /// Round robin over the /24s of the range, taking `stride` hosts from each in turn.
+    Interleaved { stride: usize },
+}
+
+impl ScanOrder {
+    /// Put `ips` in this order, regardless of the order they are in.
+    pub(crate) fn apply(&self, ips: &mut [IpAddr], seed: Option<u64>) {
+        ips.sort_unstable();
+        match *self {
+            ScanOrder::Sequential => {}
+            ScanOrder::Shuffled => match seed {
+                Some(seed) => ips.shuffle(&mut StdRng::seed_from_u64(seed)),
+                None => ips.shuffle(&mut rand::rng()),
+            },
+            ScanOrder::Interleaved { stride } => interleave(ips, stride.max(1)),
+        }
+    }
+}
+
+/// Round robin over the /24s of sorted `ips`, or the last byte of IPv6 addresses.
+fn interleave(ips: &mut [IpAddr], stride: usize) {
+    let mut networks: BTreeMap<Vec<u8>, Vec<IpAddr>> = BTreeMap::new();
+    for ip in ips.iter() {
+        let network = match ip {
+            IpAddr::V4(ip) => ip.octets()[..3].to_vec(),
+            IpAddr::V6(ip) => ip.octets()[..15].to_vec(),
+        };
+        networks.entry(network).or_default().push(*ip);
+    }
+
+    let mut chunks: Vec<_> = networks
+        .into_values()
+        .map(|hosts| {
+            hosts
+                .chunks(stride)
+                .map(<[IpAddr]>::to_vec)
+                .collect::<Vec<_>>()
+        })
+        .map(Vec::into_iter)
+        .collect();
+    let mut ordered = Vec::with_capacity(ips.len());
+    while ordered.len() < ips.len() {
+        for chunk in chunks.iter_mut().filter_map(Iterator::next) {
+            ordered.extend(chunk);
+        }
+    }
+    ips.copy_from_slice(&ordered);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn hosts(networks: u8, hosts: u8) -> Vec<IpAddr> {
+        (0..networks)
+            .flat_map(|n| (1..=hosts).map(move |h| IpAddr::V4(Ipv4Addr::new(10, 0, n, h))))
+            .collect()
+    }
+
+    #[test]
+    fn test_interleaved() {
+        let mut ips = hosts(3, 4);
+        ips.reverse();
+        ScanOrder::Interleaved { stride: 2 }.apply(&mut ips, None);
+
+        let expected: Vec<IpAddr> = [
+            [10, 0, 0, 1],
+            [10, 0, 0, 2],
+            [10, 0, 1, 1],
+            [10, 0, 1, 2],
+            [10, 0, 2, 1],
+            [10, 0, 2, 2],
+            [10, 0, 0, 3],
+            [10, 0, 0, 4],
+            [10, 0, 1, 3],
+            [10, 0, 1, 4],
+            [10, 0, 2, 3],
+            [10, 0, 2, 4],
+        ]
+        .into_iter()
+        .map(IpAddr::from)
+        .collect();
+        assert_eq!(ips, expected);
+
+        // a smaller /24 drops out of the rotation once it runs out of hosts
+        let mut ips = hosts(2, 3);
+        ips.truncate(4);
+        ScanOrder::Interleaved { stride: 1 }.apply(&mut ips, None);
+        let last_octets: Vec<_> = ips
+            .iter()
+            .map(|ip| match ip {
+                IpAddr::V4(ip) => (ip.octets()[2], ip.octets()[3]),
+                IpAddr::V6(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(last_octets, vec![(0, 1), (1, 1), (0, 2), (0, 3)]);
+    }
+
+    #[test]
+    fn test_seeded_shuffle() {
+        let shuffled = |seed| {
+            let mut ips = hosts(4, 50);
+            ScanOrder::Shuffled.apply(&mut ips, seed);
+            ips
+        };
+
+        assert_eq!(shuffled(Some(7)), shuffled(Some(7)));
+        assert_ne!(shuffled(Some(7)), shuffled(Some(8)));
+        assert_ne!(shuffled(Some(7)), hosts(4, 50));
+
+        let mut sorted = shuffled(Some(7));
+        ScanOrder::Sequential.apply(&mut sorted, None);
+        assert_eq!(sorted, hosts(4, 50));
+    }
+}
@@ -0,0 +1,79 @@
+//! Services other than the miner APIs on the host of a miner, found with
+//! [`MinerFactory::with_service_probe`][`super::MinerFactory::with_service_probe`].
+//!
+//! Stock Antminer firmware has no SSH while BraiinsOS, VNish and LuxOS do, so the open ports
+//! tell recovery tooling how a host can be reached. Ports are only connected to, nothing is
+//! sent and no login is attempted.
+
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::time::Duration;
+
+use super::check_port_open;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Service {
+    Ssh,
+    Telnet,
+    /// A web UI on the alternate HTTP port
+    HttpAlt,
+}
+
+impl Service {
+    pub const ALL: [Service; 3] = [Service::Ssh, Service::Telnet, Service::HttpAlt];
+
+    /// The port the service usually listens on.
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Service::Ssh => 22,
+            Service::Telnet => 23,
+            Service::HttpAlt => 8080,
+        }
+    }
+}
+
+/// The services in `ports` with an open port on `ip`, all probed at once.
+pub(super) async fn probe_services(
+    ip: IpAddr,
+    ports: &[(Service, u16)],
+    connectivity_timeout: Duration,
+) -> Vec<Service> {
+    let probes = ports.iter().map(|&(service, port)| async move {
+        check_port_open(ip, port, connectivity_timeout)
+            .await
+            .then_some(service)
+    });
+    futures::future::join_all(probes)
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_probe_services() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap().port();
+        // bound and dropped, so nothing listens there any more
+        let closed = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let found = probe_services(
+            [127, 0, 0, 1].into(),
+            &[(Service::Ssh, open), (Service::Telnet, closed)],
+            Duration::from_secs(1),
+        )
+        .await;
+        assert_eq!(found, vec![Service::Ssh]);
+    }
+}
@@ -1,5 +1,6 @@
 use super::commands::{HTTP_WEB_ROOT, RPC_DEVDETAILS, RPC_VERSION};
 use super::model;
+use super::model::ProbeResponses;
 use crate::data::device::models::MinerModel;
 use crate::data::device::{MinerFirmware, MinerMake};
 use crate::miners::commands::MinerCommand;
@@ -9,12 +10,18 @@ use std::net::IpAddr;
 pub(crate) trait DiscoveryCommands {
     fn get_discovery_commands(&self) -> Vec<MinerCommand>;
 }
-pub(crate) trait ModelSelection {
-    async fn get_model(&self, ip: IpAddr) -> Option<MinerModel>;
+/// Maps makes and firmwares to the cargo feature that compiles in their backend.
+pub(crate) trait BackendFeature {
+    /// The feature name, `None` if there is no backend to compile in.
+    fn backend_feature(&self) -> Option<&'static str>;
 }
-
-pub(crate) trait VersionSelection {
-    async fn get_version(&self, ip: IpAddr) -> Option<semver::Version>;
+pub(crate) trait IdentitySelection {
+    /// Look up the model and firmware version, reusing the discovery probes' `responses`.
+    async fn get_identity(
+        &self,
+        ip: IpAddr,
+        responses: &ProbeResponses,
+    ) -> (Option<MinerModel>, Option<semver::Version>);
 }
 
 impl DiscoveryCommands for MinerMake {
@@ -26,6 +33,7 @@ impl DiscoveryCommands for MinerMake {
             MinerMake::EPic => vec![HTTP_WEB_ROOT],
             MinerMake::Braiins => vec![RPC_VERSION, HTTP_WEB_ROOT],
             MinerMake::Bitaxe => vec![HTTP_WEB_ROOT],
+            MinerMake::Unknown => vec![], // the generic fallback is reached from any RPC probe
         }
     }
 }
@@ -43,46 +51,97 @@ impl DiscoveryCommands for MinerFirmware {
         }
     }
 }
-impl ModelSelection for MinerFirmware {
-    async fn get_model(&self, ip: IpAddr) -> Option<MinerModel> {
+impl BackendFeature for MinerMake {
+    fn backend_feature(&self) -> Option<&'static str> {
         match self {
-            MinerFirmware::LuxOS => model::get_model_luxos(ip).await,
-            MinerFirmware::BraiinsOS => model::get_model_braiins_os(ip).await,
-            MinerFirmware::VNish => model::get_model_vnish(ip).await,
-            MinerFirmware::EPic => model::get_model_epic(ip).await,
-            MinerFirmware::Marathon => model::get_model_marathon(ip).await,
-            _ => None,
+            MinerMake::AntMiner => Some("antminer"),
+            MinerMake::WhatsMiner => Some("whatsminer"),
+            MinerMake::AvalonMiner => Some("avalon"),
+            MinerMake::EPic => Some("epic"),
+            MinerMake::Braiins => Some("braiins"),
+            MinerMake::Bitaxe => Some("bitaxe"),
+            MinerMake::Unknown => Some("cgminer"),
         }
     }
 }
-impl VersionSelection for MinerFirmware {
-    async fn get_version(&self, ip: IpAddr) -> Option<semver::Version> {
+impl BackendFeature for MinerFirmware {
+    fn backend_feature(&self) -> Option<&'static str> {
         match self {
-            MinerFirmware::VNish => model::get_version_vnish(ip).await,
-            MinerFirmware::EPic => model::get_version_epic(ip).await,
-            _ => None,
+            MinerFirmware::Stock => None, // stock firmware is picked by miner make
+            MinerFirmware::BraiinsOS => Some("braiins"),
+            MinerFirmware::VNish => Some("vnish"),
+            MinerFirmware::EPic => Some("epic"),
+            MinerFirmware::HiveOS => None,
+            MinerFirmware::LuxOS => Some("luxos"),
+            MinerFirmware::Marathon => Some("marathon"),
+            MinerFirmware::MSKMiner => None,
         }
     }
 }
 
-impl ModelSelection for MinerMake {
-    async fn get_model(&self, ip: IpAddr) -> Option<MinerModel> {
+/// The backend features and whether each was enabled at compile time.
+const BACKEND_FEATURES: [(&str, bool); 10] = [
+    ("antminer", cfg!(feature = "antminer")),
+    ("avalon", cfg!(feature = "avalon")),
+    ("bitaxe", cfg!(feature = "bitaxe")),
+    ("braiins", cfg!(feature = "braiins")),
+    ("cgminer", cfg!(feature = "cgminer")),
+    ("epic", cfg!(feature = "epic")),
+    ("luxos", cfg!(feature = "luxos")),
+    ("marathon", cfg!(feature = "marathon")),
+    ("vnish", cfg!(feature = "vnish")),
+    ("whatsminer", cfg!(feature = "whatsminer")),
+];
+
+/// Whether the backend feature `feature` was enabled at compile time.
+pub(crate) fn feature_enabled(feature: &str) -> bool {
+    BACKEND_FEATURES
+        .iter()
+        .any(|&(name, enabled)| enabled && name == feature)
+}
+
+impl IdentitySelection for MinerFirmware {
+    #[allow(unused_variables)] // without the firmware backend features nothing is looked up
+    async fn get_identity(
+        &self,
+        ip: IpAddr,
+        responses: &ProbeResponses,
+    ) -> (Option<MinerModel>, Option<semver::Version>) {
         match self {
-            MinerMake::AntMiner => model::get_model_antminer(ip).await,
-            MinerMake::WhatsMiner => model::get_model_whatsminer(ip).await,
-            MinerMake::Bitaxe => model::get_model_bitaxe(ip).await,
-            MinerMake::AvalonMiner => model::get_model_avalonminer(ip).await,
-            _ => None,
+            #[cfg(feature = "luxos")]
+            MinerFirmware::LuxOS => (model::get_model_luxos(ip, responses).await, None),
+            #[cfg(feature = "braiins")]
+            MinerFirmware::BraiinsOS => (model::get_model_braiins_os(ip, responses).await, None),
+            #[cfg(feature = "vnish")]
+            MinerFirmware::VNish => model::get_identity_vnish(ip).await,
+            #[cfg(feature = "epic")]
+            MinerFirmware::EPic => model::get_identity_epic(ip).await,
+            #[cfg(feature = "marathon")]
+            MinerFirmware::Marathon => (model::get_model_marathon(ip, responses).await, None),
+            _ => (None, None),
         }
     }
 }
-impl VersionSelection for MinerMake {
-    async fn get_version(&self, ip: IpAddr) -> Option<semver::Version> {
+
+impl IdentitySelection for MinerMake {
+    #[allow(unused_variables)] // without the stock backend features nothing is looked up
+    async fn get_identity(
+        &self,
+        ip: IpAddr,
+        responses: &ProbeResponses,
+    ) -> (Option<MinerModel>, Option<semver::Version>) {
         match self {
-            MinerMake::Bitaxe => model::get_version_bitaxe(ip).await,
-            MinerMake::WhatsMiner => model::get_version_whatsminer(ip).await,
-            MinerMake::AntMiner => model::get_version_antminer(ip).await,
-            _ => None,
+            #[cfg(feature = "antminer")]
+            MinerMake::AntMiner => model::get_identity_antminer(ip, responses).await,
+            #[cfg(feature = "whatsminer")]
+            MinerMake::WhatsMiner => model::get_identity_whatsminer(ip, responses).await,
+            #[cfg(feature = "bitaxe")]
+            MinerMake::Bitaxe => model::get_identity_bitaxe(ip).await,
+            #[cfg(feature = "avalon")]
+            MinerMake::AvalonMiner => (model::get_model_avalonminer(ip, responses).await, None),
+            #[cfg(feature = "cgminer")]
+            MinerMake::Unknown => (model::get_model_cgminer(ip, responses).await, None),
+            _ => (None, None),
         }
     }
 }
@@ -0,0 +1,58 @@
+//! Process-wide command timing metrics, enabled with the `metrics` feature.
+//!
+//! Every command sent by a [`DataCollector`][`crate::miners::data::DataCollector`] is accumulated
+//! here, keyed by backend type and command, so slow endpoints can be found across a whole fleet.
+
+use crate::miners::commands::MinerCommand;
+use crate::miners::data::CommandStats;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+/// Accumulated timings of one command on one backend type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandMetrics {
+    /// Number of times the command was sent.
+    pub count: u64,
+    /// Number of times the command returned no response.
+    pub failures: u64,
+    /// Total wall time spent on the command.
+    pub total: Duration,
+    /// Longest single wall time of the command.
+    pub max: Duration,
+}
+
+impl CommandMetrics {
+    /// Average wall time of the command.
+    pub fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => self.total.div_f64(count as f64),
+        }
+    }
+}
+
+type Registry = HashMap<(&'static str, MinerCommand), CommandMetrics>;
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub(crate) fn record(backend: &'static str, stats: &CommandStats) {
+    let mut registry = REGISTRY.lock().expect("metrics registry poisoned");
+    let metrics = registry
+        .entry((backend, stats.command.clone()))
+        .or_default();
+    metrics.count += 1;
+    metrics.failures += u64::from(!stats.success);
+    metrics.total += stats.duration;
+    metrics.max = metrics.max.max(stats.duration);
+}
+
+/// Metrics accumulated so far, keyed by backend type and command.
+pub fn snapshot() -> Registry {
+    REGISTRY.lock().expect("metrics registry poisoned").clone()
+}
+
+/// Clear all accumulated metrics.
+pub fn reset() {
+    REGISTRY.lock().expect("metrics registry poisoned").clear();
+}
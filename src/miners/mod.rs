@@ -5,13 +5,21 @@
 //! For traits implemented by each miner, see [`GetMinerData`][`backends::traits::GetMinerData`] for data gathering,
 //! and [`HasMinerControl`][`backends::traits::HasMinerControl`].
 //! These traits are unified by the [`Miner`][`backends::traits::Miner`] trait.
+//! Which of them a backend actually supports is reported by
+//! [`capabilities`][`backends::traits::GetCapabilities::capabilities`].
 //!
 //! Per-miner implementations are under [`backends`][`backends`] in their own modules.
+//!
+//! Operations rolled out across a fleet, such as a validated pool switchover, are in
+//! [`batch`][`batch`].
 
 pub mod api;
 pub mod backends;
+pub mod batch;
 pub mod commands;
 pub mod data;
 pub mod factory;
 pub mod listener;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub(crate) mod util;
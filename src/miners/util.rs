@@ -2,24 +2,33 @@ use reqwest::StatusCode;
 use reqwest::header::HeaderMap;
 use std::net::IpAddr;
 use tokio;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
+
+use crate::miners::api::rpc::reader::{self, RPCReadLimits};
 
 pub(crate) async fn send_rpc_command(
     ip: &IpAddr,
     command: &'static str,
 ) -> Option<serde_json::Value> {
-    let stream = tokio::net::TcpStream::connect(format!("{ip}:4028")).await;
-    if stream.is_err() {
-        return None;
-    }
-    let mut stream = stream.unwrap();
+    send_rpc_command_with_limits(ip, command, &RPCReadLimits::default()).await
+}
+
+/// Send a cgminer style command, returning `None` for anything that isn't a successful RPC
+/// response, including oversized or stalled replies from devices that aren't miners.
+pub(crate) async fn send_rpc_command_with_limits(
+    ip: &IpAddr,
+    command: &'static str,
+    limits: &RPCReadLimits,
+) -> Option<serde_json::Value> {
+    let mut stream = tokio::net::TcpStream::connect(format!("{ip}:4028"))
+        .await
+        .ok()?;
 
     let command = format!("{{\"command\":\"{command}\"}}");
 
-    stream.write_all(command.as_bytes()).await.unwrap();
+    stream.write_all(command.as_bytes()).await.ok()?;
 
-    let mut buffer = Vec::new();
-    stream.read_to_end(&mut buffer).await.unwrap();
+    let buffer = reader::read_to_end(&mut stream, limits).await.ok()?;
 
     let response = String::from_utf8_lossy(&buffer)
         .into_owned()
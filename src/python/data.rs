@@ -3,12 +3,16 @@ use pyo3::prelude::*;
 use crate::data::board::BoardData as BoardData_Base;
 use crate::data::board::ChipData as ChipData_Base;
 pub(crate) use crate::data::device::{HashAlgorithm, MinerFirmware, MinerMake, MinerModel};
+use crate::data::fan::FanControl as FanControl_Base;
 use crate::data::fan::FanData as FanData_Base;
+use crate::data::miner::AutotuneProfileBounds;
 use crate::data::miner::MinerData as MinerData_Base;
 use crate::data::pool::PoolURL;
+use crate::data::psu::PsuData as PsuData_Base;
+use crate::data::psu::PsuRail as PsuRail_Base;
 use crate::data::{device::DeviceInfo, hashrate::HashRate, message::MinerMessage, pool::PoolData};
 use serde::{Deserialize, Serialize};
-use std::{net::IpAddr, time::Duration};
+use std::{collections::HashMap, net::IpAddr, time::Duration};
 
 #[pyclass(get_all, module = "asic_rs")]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -20,6 +24,7 @@ pub struct ChipData {
     pub frequency: Option<f64>,
     pub tuned: Option<bool>,
     pub working: Option<bool>,
+    pub estimated: bool,
 }
 
 impl From<&ChipData_Base> for ChipData {
@@ -32,6 +37,7 @@ impl From<&ChipData_Base> for ChipData {
             frequency: base.frequency.map(|f| f.as_megahertz()),
             tuned: base.tuned,
             working: base.working,
+            estimated: base.estimated,
         }
     }
 }
@@ -49,10 +55,14 @@ pub struct BoardData {
     pub working_chips: Option<u16>,
     pub serial_number: Option<String>,
     pub chips: Vec<ChipData>,
+    pub sensor_temperatures: Vec<f64>,
     pub voltage: Option<f64>,
     pub frequency: Option<f64>,
+    pub wattage: Option<f64>,
     pub tuned: Option<bool>,
     pub active: Option<bool>,
+    pub status: Option<String>,
+    pub extra: Option<HashMap<String, String>>,
 }
 
 impl From<&BoardData_Base> for BoardData {
@@ -68,10 +78,18 @@ impl From<&BoardData_Base> for BoardData {
             working_chips: base.working_chips,
             serial_number: base.serial_number.clone(),
             chips: base.chips.iter().map(ChipData::from).collect(),
+            sensor_temperatures: base
+                .sensor_temperatures
+                .iter()
+                .map(|t| t.as_celsius())
+                .collect(),
             voltage: base.voltage.map(|v| v.as_volts()),
             frequency: base.frequency.map(|f| f.as_megahertz()),
+            wattage: base.wattage.map(|w| w.as_watts()),
             tuned: base.tuned,
             active: base.active,
+            status: base.status.as_ref().map(|s| s.to_string()),
+            extra: base.extra.clone(),
         }
     }
 }
@@ -92,36 +110,112 @@ impl From<&FanData_Base> for FanData {
     }
 }
 
+#[pyclass(get_all, module = "asic_rs")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FanControl {
+    pub mode: String,
+    pub target_pct: Option<u8>,
+}
+
+impl From<&FanControl_Base> for FanControl {
+    fn from(base: &FanControl_Base) -> Self {
+        Self {
+            mode: base.mode.to_string(),
+            target_pct: base.target_pct,
+        }
+    }
+}
+
+#[pyclass(get_all, module = "asic_rs")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct PsuData {
+    pub voltage: Option<f64>,
+    pub current: Option<f64>,
+    pub power: Option<f64>,
+}
+
+impl From<&PsuData_Base> for PsuData {
+    fn from(base: &PsuData_Base) -> Self {
+        Self {
+            voltage: base.voltage.map(|v| v.as_volts()),
+            current: base.current.map(|c| c.as_amperes()),
+            power: base.power.map(|p| p.as_watts()),
+        }
+    }
+}
+
+#[pyclass(get_all, module = "asic_rs")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PsuRail {
+    pub name: String,
+    pub voltage: Option<f64>,
+    pub current: Option<f64>,
+    pub power: Option<f64>,
+}
+
+impl From<&PsuRail_Base> for PsuRail {
+    fn from(base: &PsuRail_Base) -> Self {
+        Self {
+            name: base.name.clone(),
+            voltage: base.voltage.map(|v| v.as_volts()),
+            current: base.current.map(|c| c.as_amperes()),
+            power: base.power.map(|p| p.as_watts()),
+        }
+    }
+}
+
 #[pyclass(get_all, module = "asic_rs")]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MinerData {
     pub schema_version: String,
     pub timestamp: u64,
+    pub collection_duration: Duration,
+    pub clock_skew: Option<i64>,
     pub ip: IpAddr,
+    pub reported_ip: Option<IpAddr>,
     pub mac: Option<String>,
+    pub labels: Option<HashMap<String, String>>,
     pub device_info: DeviceInfo,
     pub serial_number: Option<String>,
     pub hostname: Option<String>,
     pub api_version: Option<String>,
     pub firmware_version: Option<String>,
+    pub firmware_build_time: Option<String>,
+    pub base_firmware: Option<String>,
     pub control_board_version: Option<String>,
+    pub control_board_type: Option<String>,
     pub expected_hashboards: Option<u8>,
     pub hashboards: Vec<BoardData>,
     pub hashrate: Option<HashRate>,
     pub expected_hashrate: Option<HashRate>,
+    pub average_hashrate: Option<HashRate>,
     pub expected_chips: Option<u16>,
     pub total_chips: Option<u16>,
     pub expected_fans: Option<u8>,
+    pub working_fans: Option<u8>,
     pub fans: Vec<FanData>,
     pub psu_fans: Vec<FanData>,
+    pub fan_control: Option<FanControl>,
     pub average_temperature: Option<f64>,
     pub fluid_temperature: Option<f64>,
+    pub fluid_flow: Option<f64>,
+    pub fluid_pressure: Option<f64>,
     pub wattage: Option<f64>,
+    pub wattage_estimated: bool,
+    pub wattage_location: Option<String>,
     pub wattage_limit: Option<f64>,
     pub efficiency: Option<f64>,
+    pub psu: Option<PsuData>,
+    pub psu_rails: Vec<PsuRail>,
+    pub frequency: Option<f64>,
+    pub voltage: Option<f64>,
+    pub work_mode: Option<String>,
+    pub autotune_enabled: Option<bool>,
+    pub autotune_profile_bounds: Option<AutotuneProfileBounds>,
     pub light_flashing: Option<bool>,
     pub messages: Vec<MinerMessage>,
     pub uptime: Option<Duration>,
+    pub status: String,
     pub is_mining: bool,
     pub pools: Vec<PoolData>,
 }
@@ -131,31 +225,53 @@ impl From<&MinerData_Base> for MinerData {
         Self {
             schema_version: base.schema_version.clone(),
             timestamp: base.timestamp,
+            collection_duration: base.collection_duration,
+            clock_skew: base.clock_skew,
             ip: base.ip,
+            reported_ip: base.reported_ip,
             mac: base.mac.map(|m| m.to_string()),
-            device_info: base.device_info,
+            labels: base.labels.clone(),
+            device_info: base.device_info.clone(),
             serial_number: base.serial_number.clone(),
             hostname: base.hostname.clone(),
             api_version: base.api_version.clone(),
             firmware_version: base.firmware_version.clone(),
+            firmware_build_time: base.firmware_build_time.clone(),
+            base_firmware: base.base_firmware.clone(),
             control_board_version: base.control_board_version.clone().map(|cb| cb.to_string()),
+            control_board_type: base.control_board_type.clone().map(|cb| cb.to_string()),
             expected_hashboards: base.expected_hashboards,
             hashboards: base.hashboards.iter().map(BoardData::from).collect(),
             hashrate: base.hashrate.clone(),
             expected_hashrate: base.expected_hashrate.clone(),
+            average_hashrate: base.average_hashrate.clone(),
             expected_chips: base.expected_chips,
             total_chips: base.total_chips,
             expected_fans: base.expected_fans,
+            working_fans: base.working_fans,
             fans: base.fans.iter().map(FanData::from).collect(),
             psu_fans: base.psu_fans.iter().map(FanData::from).collect(),
+            fan_control: base.fan_control.as_ref().map(FanControl::from),
             average_temperature: base.average_temperature.map(|t| t.as_celsius()),
             fluid_temperature: base.fluid_temperature.map(|t| t.as_celsius()),
+            fluid_flow: base.fluid_flow,
+            fluid_pressure: base.fluid_pressure,
             wattage: base.wattage.map(|w| w.as_watts()),
+            wattage_estimated: base.wattage_estimated,
+            wattage_location: base.wattage_location.map(|l| l.to_string()),
             wattage_limit: base.wattage_limit.map(|w| w.as_watts()),
             efficiency: base.efficiency,
+            psu: base.psu.as_ref().map(PsuData::from),
+            psu_rails: base.psu_rails.iter().map(PsuRail::from).collect(),
+            frequency: base.frequency.map(|f| f.as_megahertz()),
+            voltage: base.voltage.map(|v| v.as_volts()),
+            work_mode: base.work_mode.map(|m| m.to_string()),
+            autotune_enabled: base.autotune_enabled,
+            autotune_profile_bounds: base.autotune_profile_bounds.clone(),
             light_flashing: base.light_flashing,
             messages: base.messages.clone(),
             uptime: base.uptime,
+            status: base.status.to_string(),
             is_mining: base.is_mining,
             pools: base.pools.clone(),
         }
@@ -167,6 +283,10 @@ impl MinerData {
     pub fn __repr__<'a>(&self) -> String {
         serde_json::to_string(self).unwrap()
     }
+
+    pub fn as_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
 }
 
 #[pymethods]
@@ -1,3 +1,4 @@
+use crate::miners::backends::policy::CommandPolicy;
 use crate::miners::backends::traits::Miner as MinerTrait;
 use crate::miners::factory::MinerFactory as MinerFactory_Base;
 use crate::python::miner::Miner;
@@ -163,6 +164,28 @@ impl MinerFactory {
         Ok(())
     }
 
+    #[pyo3(signature = (read_only=false, allow=None, deny=None))]
+    pub fn with_command_policy(
+        &mut self,
+        read_only: bool,
+        allow: Option<Vec<String>>,
+        deny: Option<Vec<String>>,
+    ) -> PyResult<()> {
+        let policy = match (read_only, allow) {
+            (true, Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "read_only and allow can not be combined",
+                ));
+            }
+            (true, None) => CommandPolicy::read_only(),
+            (false, Some(allow)) => CommandPolicy::allow_only(allow),
+            (false, None) => CommandPolicy::allow_all(),
+        };
+        let inner = Arc::<MinerFactory_Base>::make_mut(&mut self.inner).clone();
+        self.inner = Arc::new(inner.with_command_policy(policy.deny(deny.unwrap_or_default())));
+        Ok(())
+    }
+
     pub fn scan<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         let inner = Arc::clone(&self.inner);
         future_into_py(py, async move {
@@ -174,6 +197,17 @@ impl MinerFactory {
         })
     }
 
+    pub fn scan_range<'a>(&self, py: Python<'a>, range: String) -> PyResult<Bound<'a, PyAny>> {
+        let inner = MinerFactory_Base::clone(&self.inner);
+        future_into_py(py, async move {
+            let miners = inner.scan_by_range(&range).await;
+            match miners {
+                Ok(miners) => Ok(miners.into_iter().map(Miner::from).collect::<Vec<Miner>>()),
+                Err(e) => Err(PyValueError::new_err(e.to_string())),
+            }
+        })
+    }
+
     pub fn scan_stream<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyMinerStream>> {
         let inner = Arc::clone(&self.inner);
         Bound::new(py, PyMinerStream::new(inner.scan_stream()))
@@ -199,3 +233,60 @@ impl MinerFactory {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test::simulator::{MinerSimulator, SimulatorFixture};
+    use std::net::IpAddr;
+    use tokio::process::Command;
+
+    const SCAN_SCRIPT: &str = r#"
+import asyncio
+import sys
+
+try:
+    from pyasic_rs import MinerFactory
+except ImportError:
+    sys.exit(77)
+
+
+async def main():
+    miners = await MinerFactory().scan(sys.argv[1])
+    assert len(miners) == 1, miners
+    data = await miners[0].get_data()
+    assert data.hashrate is not None
+    print(data.as_json())
+
+
+asyncio.run(main())
+"#;
+
+    /// Runs the installed `pyasic_rs` package (`maturin develop`) against a simulated Avalon.
+    #[tokio::test]
+    #[ignore = "needs pyasic_rs installed and binds miner ports on 127.0.0.x"]
+    async fn test_python_scan_simulator() {
+        let fixture = SimulatorFixture::load("avalon_1246").unwrap();
+        let simulator = MinerSimulator::start(IpAddr::from([127, 0, 0, 21]), fixture)
+            .await
+            .expect("could not bind the miner ports");
+
+        let output = Command::new("python3")
+            .arg("-c")
+            .arg(SCAN_SCRIPT)
+            .arg(simulator.ip().to_string())
+            .output()
+            .await
+            .expect("python3 not found");
+
+        assert_ne!(
+            output.status.code(),
+            Some(77),
+            "pyasic_rs is not installed, run maturin develop"
+        );
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
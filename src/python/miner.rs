@@ -1,6 +1,8 @@
-use super::data::{BoardData, FanData, MinerData};
+use super::data::{BoardData, FanControl, FanData, MinerData, PsuData, PsuRail};
 use crate::data::device::{HashAlgorithm, MinerFirmware, MinerHardware, MinerMake, MinerModel};
-use crate::miners::backends::traits::Miner as MinerTrait;
+use crate::miners::backends::capabilities::MinerCapabilities;
+use crate::miners::backends::traits::{HostnameChange, Miner as MinerTrait};
+use measurements::{Frequency, Power, Voltage};
 use std::net::IpAddr;
 
 use pyo3::prelude::*;
@@ -64,6 +66,11 @@ impl Miner {
         self.inner.get_device_info().hardware
     }
 
+    #[getter]
+    fn capabilities(&self) -> MinerCapabilities {
+        self.inner.capabilities()
+    }
+
     #[getter]
     fn expected_hashboards(&self) -> Option<u8> {
         self.inner.get_expected_hashboards()
@@ -153,6 +160,13 @@ impl Miner {
             Ok(data)
         })
     }
+    pub fn get_average_hashrate<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_average_hashrate().await;
+            Ok(data)
+        })
+    }
     pub fn get_fans<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         let inner = Arc::clone(&self.inner);
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
@@ -167,6 +181,27 @@ impl Miner {
             Ok(data.iter().map(FanData::from).collect::<Vec<FanData>>())
         })
     }
+    pub fn get_fan_control<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_fan_control().await;
+            Ok(data.as_ref().map(FanControl::from))
+        })
+    }
+    pub fn get_psu<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_psu().await;
+            Ok(data.as_ref().map(PsuData::from))
+        })
+    }
+    pub fn get_psu_rails<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_psu_rails().await;
+            Ok(data.iter().map(PsuRail::from).collect::<Vec<PsuRail>>())
+        })
+    }
     pub fn get_fluid_temperature<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         let inner = Arc::clone(&self.inner);
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
@@ -174,6 +209,20 @@ impl Miner {
             Ok(data.map(|t| t.as_celsius()))
         })
     }
+    pub fn get_fluid_flow<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_fluid_flow().await;
+            Ok(data)
+        })
+    }
+    pub fn get_fluid_pressure<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_fluid_pressure().await;
+            Ok(data)
+        })
+    }
     pub fn get_wattage<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         let inner = Arc::clone(&self.inner);
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
@@ -188,6 +237,41 @@ impl Miner {
             Ok(data.map(|w| w.as_watts()))
         })
     }
+    pub fn get_frequency<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_frequency().await;
+            Ok(data.map(|f| f.as_megahertz()))
+        })
+    }
+    pub fn get_voltage<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_voltage().await;
+            Ok(data.map(|v| v.as_volts()))
+        })
+    }
+    pub fn get_work_mode<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_work_mode().await;
+            Ok(data.map(|m| m.to_string()))
+        })
+    }
+    pub fn get_autotune_enabled<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_autotune_enabled().await;
+            Ok(data)
+        })
+    }
+    pub fn get_autotune_profile_bounds<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_autotune_profile_bounds().await;
+            Ok(data)
+        })
+    }
     pub fn get_light_flashing<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         let inner = Arc::clone(&self.inner);
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
@@ -216,6 +300,13 @@ impl Miner {
             Ok(data)
         })
     }
+    pub fn get_status<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.get_status().await;
+            Ok(data.to_string())
+        })
+    }
     pub fn get_pools<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         let inner = Arc::clone(&self.inner);
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
@@ -232,6 +323,69 @@ impl Miner {
             Ok(data.ok())
         })
     }
+    #[pyo3(signature = (limit, unchecked=false))]
+    pub fn set_power_limit<'a>(
+        &self,
+        py: Python<'a>,
+        limit: f64,
+        unchecked: bool,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let limit = Power::from_watts(limit);
+            let data = match unchecked {
+                true => inner.set_power_limit_unchecked(limit).await,
+                false => inner.set_power_limit(limit).await,
+            };
+            Ok(data.ok())
+        })
+    }
+    pub fn apply_power_limit<'a>(&self, py: Python<'a>, limit: f64) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.apply_power_limit(Power::from_watts(limit)).await;
+            Ok(data.ok().flatten().map(|p| p.as_watts()))
+        })
+    }
+    pub fn set_password<'a>(
+        &self,
+        py: Python<'a>,
+        old: String,
+        new: String,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.set_password(&old, &new).await;
+            Ok(data.ok())
+        })
+    }
+    pub fn set_hostname<'a>(&self, py: Python<'a>, hostname: String) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.set_hostname(&hostname).await;
+            Ok(data.ok().map(|c| c == HostnameChange::RebootRequired))
+        })
+    }
+    #[pyo3(signature = (board, frequency=None, voltage=None))]
+    pub fn set_board_tuning<'a>(
+        &self,
+        py: Python<'a>,
+        board: u8,
+        frequency: Option<f64>,
+        voltage: Option<f64>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner
+                .set_board_tuning(
+                    board,
+                    frequency.map(Frequency::from_megahertz),
+                    voltage.map(Voltage::from_volts),
+                )
+                .await;
+            Ok(data.ok())
+        })
+    }
     pub fn restart<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         let inner = Arc::clone(&self.inner);
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
@@ -239,6 +393,20 @@ impl Miner {
             Ok(data.ok())
         })
     }
+    pub fn restart_mining<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.restart_mining().await;
+            Ok(data.ok())
+        })
+    }
+    pub fn reboot_system<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let inner = Arc::clone(&self.inner);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let data = inner.reboot_system().await;
+            Ok(data.ok())
+        })
+    }
     pub fn pause<'a>(
         &self,
         py: Python<'a>,
@@ -1,29 +1,392 @@
-#![cfg(test)]
+//! A mock [`APIClient`] to test backends against recorded responses, without a miner.
+//!
+//! This is the way to test a backend: give the mock the responses of the commands the backend
+//! sends, collect through it with
+//! [`DataCollector::new_with_client`][`crate::miners::data::DataCollector::new_with_client`],
+//! and check the parsed [`MinerData`][`crate::data::miner::MinerData`]. Besides a fixed
+//! response per command, the mock can answer a sequence of responses, fail like a miner that
+//! times out, drops the connection or sends malformed JSON, delay its answers, and record the
+//! commands it was sent.
+//!
+//! Enable the `test-util` feature to use it from another crate, where it is
+//! `asic_rs::test_util`:
+//! ```no_run
+//! use asic_rs::miners::backends::traits::{GetMinerData, MinerInterface};
+//! use asic_rs::miners::commands::MinerCommand;
+//! use asic_rs::miners::data::DataCollector;
+//! use asic_rs::test_util::{MockAPIClient, MockResponse};
+//! use serde_json::json;
+//! use std::collections::HashMap;
+//!
+//! /// Collect from `miner` twice, through a mock whose first answer to `summary` times out.
+//! async fn check_uptime<M: MinerInterface + GetMinerData>(miner: &M) {
+//!     let summary = MinerCommand::RPC {
+//!         command: "summary",
+//!         parameters: None,
+//!     };
+//!     let mock = MockAPIClient::new(HashMap::new()).with_sequence(
+//!         summary,
+//!         [
+//!             MockResponse::Timeout,
+//!             json!({"SUMMARY": [{"Elapsed": 600}]}).into(),
+//!         ],
+//!     );
+//!
+//!     // the first collection times out, the second gets the summary
+//!     for _ in 0..2 {
+//!         let mut collector = DataCollector::new_with_client(miner, &mock);
+//!         let data = miner.parse_data(collector.collect_all().await);
+//!         println!("{:?}", data.uptime);
+//!     }
+//!     mock.assert_call_count("summary", 2);
+//! }
+//! ```
+#![cfg(any(test, feature = "test-util"))]
 
+#[cfg(feature = "test-util")]
+pub use crate::test::simulator::{MinerSimulator, SimulatorFixture};
+
+use crate::data::miner::MinerStatus;
+use crate::miners::api::rpc::errors::RPCError;
+use crate::miners::backends::policy::{CommandPolicy, command_name};
 use crate::miners::backends::traits::*;
 use crate::miners::commands::MinerCommand;
+use crate::miners::data::{CommandGate, DataCollector, DataField, DataLocation, FieldData};
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A response of [`MockAPIClient`], or the way it fails.
+///
+/// Failures are the errors the RPC clients return, so backends that handle them can be tested.
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// The command succeeds with this response
+    Value(Value),
+    /// The miner doesn't answer in time, [`RPCError::ReadTimeout`]
+    Timeout,
+    /// The miner drops the connection, [`RPCError::ConnectionFailed`]
+    ConnectionReset,
+    /// A raw response body, parsed as the clients parse one, so anything but valid JSON fails
+    /// with [`RPCError::DeserializationFailed`]
+    Malformed(String),
+}
+
+impl From<Value> for MockResponse {
+    fn from(value: Value) -> Self {
+        MockResponse::Value(value)
+    }
+}
+
+impl MockResponse {
+    fn result(&self) -> Result<Value> {
+        match self {
+            MockResponse::Value(value) => Ok(value.clone()),
+            MockResponse::Timeout => Err(RPCError::ReadTimeout.into()),
+            MockResponse::ConnectionReset => Err(RPCError::ConnectionFailed.into()),
+            MockResponse::Malformed(body) => {
+                serde_json::from_str(body).map_err(|e| RPCError::DeserializationFailed(e).into())
+            }
+        }
+    }
+}
 
-pub(crate) struct MockAPIClient {
-    results: HashMap<MinerCommand, Value>,
+/// An [`APIClient`] answering each command from a list of responses, see the
+/// [module docs][`self`].
+///
+/// The n-th call of a command gets its n-th response, and the last response once they run out,
+/// so a command with a single response always gets it. Commands without responses fail.
+/// Calls are recorded in the order they are made, including calls from concurrent tasks.
+pub struct MockAPIClient {
+    responses: HashMap<MinerCommand, Vec<MockResponse>>,
+    delays: HashMap<MinerCommand, Duration>,
+    policy: CommandPolicy,
+    calls: Mutex<Vec<MinerCommand>>,
 }
 
 #[async_trait]
 impl APIClient for MockAPIClient {
     async fn get_api_result(&self, command: &MinerCommand) -> Result<Value> {
-        if let Some(result) = self.results.get(command) {
-            Ok(result.clone())
-        } else {
-            Err(anyhow!("Command not found"))
+        if let Some(name) = command_name(command) {
+            self.policy.check(name)?;
+        }
+        let response = {
+            let mut calls = self.calls.lock().unwrap();
+            let call = calls.iter().filter(|c| *c == command).count();
+            calls.push(command.clone());
+            self.responses
+                .get(command)
+                .and_then(|responses| responses.get(call).or(responses.last()))
+                .cloned()
+        };
+        if let Some(delay) = self.delays.get(command) {
+            tokio::time::sleep(*delay).await;
+        }
+        match response {
+            Some(response) => response.result(),
+            None => Err(anyhow!("Command not found")),
         }
     }
 }
 
 impl MockAPIClient {
+    /// A mock answering each command with its response.
     pub fn new(results: HashMap<MinerCommand, Value>) -> Self {
-        Self { results }
+        Self {
+            responses: results
+                .into_iter()
+                .map(|(command, result)| (command, vec![result.into()]))
+                .collect(),
+            delays: HashMap::new(),
+            policy: CommandPolicy::default(),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Answer every call of a command with `response`, replacing its earlier responses
+    pub fn with_response(self, command: MinerCommand, response: impl Into<MockResponse>) -> Self {
+        self.with_sequence(command, [response.into()])
+    }
+
+    /// Answer the calls of a command with `responses` in order, repeating the last one, to
+    /// simulate a flaky miner or one whose state changes between calls
+    pub fn with_sequence(
+        mut self,
+        command: MinerCommand,
+        responses: impl IntoIterator<Item = MockResponse>,
+    ) -> Self {
+        self.responses
+            .insert(command, responses.into_iter().collect());
+        self
+    }
+
+    /// Delay the response to a command, to simulate a slow endpoint
+    pub fn with_delay(mut self, command: MinerCommand, delay: Duration) -> Self {
+        self.delays.insert(command, delay);
+        self
+    }
+
+    /// Check commands against a policy, like the miner API clients do
+    pub fn with_policy(mut self, policy: CommandPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The commands sent so far, in order. Commands denied by the policy are not sent.
+    pub fn calls(&self) -> Vec<MinerCommand> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// How many times a command was sent, matched by name such as `"stats"`, whatever its
+    /// parameters.
+    pub fn call_count(&self, name: &str) -> usize {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|command| command_name(command) == Some(name))
+            .count()
+    }
+
+    /// Forget the commands sent so far, the next call of each command gets its first response
+    /// again.
+    pub fn reset_calls(&self) {
+        self.calls.lock().unwrap().clear();
+    }
+
+    /// Panics unless the command named `name` was sent `count` times.
+    #[track_caller]
+    pub fn assert_call_count(&self, name: &str, count: usize) {
+        let calls = self.call_count(name);
+        assert_eq!(
+            calls,
+            count,
+            "expected {name} to be called {count} times, it was called {calls} times, calls: {:?}",
+            self.call_names()
+        );
+    }
+
+    /// Panics unless the command named `name` was sent exactly once.
+    #[track_caller]
+    pub fn assert_called_once(&self, name: &str) {
+        self.assert_call_count(name, 1);
+    }
+
+    /// Panics if the command named `name` was sent.
+    #[track_caller]
+    pub fn assert_not_called(&self, name: &str) {
+        self.assert_call_count(name, 0);
+    }
+
+    /// Panics unless the commands named in `names` were sent in this order. Other commands may
+    /// be sent in between.
+    #[track_caller]
+    pub fn assert_called_in_order(&self, names: &[&str]) {
+        let calls = self.call_names();
+        let mut remaining = calls.iter();
+        for name in names {
+            assert!(
+                remaining.any(|call| call == name),
+                "expected calls in order {names:?}, {name} is missing or out of order, calls: \
+                 {calls:?}"
+            );
+        }
+    }
+
+    fn call_names(&self) -> Vec<&'static str> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|command| command_name(command).unwrap_or("ssh"))
+            .collect()
+    }
+}
+
+/// A backend whose commands are answered by a [`MockAPIClient`], to test the getters of the data
+/// traits, like [`GetStatus::get_status`], which collect only the fields they need.
+///
+/// Implements the getters that parse more than the field they're named after.
+pub struct MockedMiner<'a, M> {
+    miner: &'a M,
+    client: &'a MockAPIClient,
+}
+
+impl<'a, M> MockedMiner<'a, M> {
+    pub fn new(miner: &'a M, client: &'a MockAPIClient) -> Self {
+        Self { miner, client }
+    }
+}
+
+impl<M: fmt::Debug> fmt::Debug for MockedMiner<'_, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockedMiner")
+            .field("miner", &self.miner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: MinerInterface> GetDataLocations for MockedMiner<'_, M> {
+    fn get_locations(&self, data_field: DataField) -> Vec<DataLocation> {
+        self.miner.get_locations(data_field)
+    }
+
+    fn command_gate(&self) -> Option<CommandGate> {
+        self.miner.command_gate()
+    }
+}
+
+impl<M: MinerInterface> CollectData for MockedMiner<'_, M> {
+    fn get_collector(&self) -> DataCollector<'_> {
+        DataCollector::new_with_client(self.miner, self.client)
+    }
+}
+
+impl<M: MinerInterface + GetIsMining> GetIsMining for MockedMiner<'_, M> {
+    fn parse_is_mining(&self, data: &FieldData) -> bool {
+        self.miner.parse_is_mining(data)
+    }
+}
+
+impl<M: MinerInterface + GetStatus> GetStatus for MockedMiner<'_, M> {
+    fn parse_status(&self, data: &FieldData) -> MinerStatus {
+        self.miner.parse_status(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rpc(command: &'static str) -> MinerCommand {
+        MinerCommand::RPC {
+            command,
+            parameters: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_sequence() {
+        let mock = MockAPIClient::new(HashMap::from([(rpc("version"), json!({"v": 1}))]))
+            .with_sequence(
+                rpc("summary"),
+                [
+                    MockResponse::Timeout,
+                    MockResponse::ConnectionReset,
+                    MockResponse::Malformed("{\"SUMMARY\": [".to_string()),
+                    json!({"SUMMARY": []}).into(),
+                ],
+            );
+
+        let error = |result: Result<Value>| result.unwrap_err().downcast::<RPCError>().unwrap();
+        assert!(matches!(
+            error(mock.get_api_result(&rpc("summary")).await),
+            RPCError::ReadTimeout
+        ));
+        assert!(matches!(
+            error(mock.get_api_result(&rpc("summary")).await),
+            RPCError::ConnectionFailed
+        ));
+        assert!(matches!(
+            error(mock.get_api_result(&rpc("summary")).await),
+            RPCError::DeserializationFailed(_)
+        ));
+        // the last response repeats
+        for _ in 0..2 {
+            assert_eq!(
+                mock.get_api_result(&rpc("summary")).await.unwrap(),
+                json!({"SUMMARY": []})
+            );
+        }
+        assert_eq!(
+            mock.get_api_result(&rpc("version")).await.unwrap(),
+            json!({"v": 1})
+        );
+        assert!(mock.get_api_result(&rpc("stats")).await.is_err());
+
+        mock.assert_call_count("summary", 5);
+        mock.assert_called_once("version");
+        mock.assert_called_once("stats");
+        mock.assert_not_called("pools");
+        mock.assert_called_in_order(&["summary", "version", "stats"]);
+
+        mock.reset_calls();
+        assert!(mock.calls().is_empty());
+        assert!(mock.get_api_result(&rpc("summary")).await.is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "stats is missing or out of order")]
+    fn test_mock_call_order() {
+        let mock = MockAPIClient::new(HashMap::new());
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let _ = mock.get_api_result(&rpc("stats")).await;
+            let _ = mock.get_api_result(&rpc("summary")).await;
+        });
+        mock.assert_called_in_order(&["summary", "stats"]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_concurrent_calls() {
+        let mock = MockAPIClient::new(HashMap::new())
+            .with_sequence(rpc("summary"), (0..8).map(|n| json!(n).into()));
+
+        let summary = rpc("summary");
+        let results =
+            futures::future::join_all((0..8).map(|_| mock.get_api_result(&summary))).await;
+        let mut values: Vec<u64> = results
+            .into_iter()
+            .map(|result| result.unwrap().as_u64().unwrap())
+            .collect();
+        values.sort();
+
+        // every call got its own response
+        assert_eq!(values, (0..8).collect::<Vec<_>>());
+        mock.assert_call_count("summary", 8);
     }
 }
@@ -0,0 +1,38 @@
+//! Finds the fields a backend can't extract from a set of fixture responses.
+#![cfg(test)]
+
+use crate::miners::backends::traits::MinerInterface;
+use crate::miners::commands::MinerCommand;
+use crate::miners::data::{DataCollector, DataField, ExtractionFailure};
+use crate::test::api::MockAPIClient;
+use serde_json::Value;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+
+/// Every field the backend has locations for that got no value out of `responses`, with why
+/// each of its extractors failed. Handy to print while adding fixtures for a backend.
+pub(crate) async fn unextracted_fields<M: MinerInterface>(
+    miner: &M,
+    responses: HashMap<MinerCommand, Value>,
+) -> HashMap<DataField, Vec<ExtractionFailure>> {
+    let mock_api = MockAPIClient::new(responses);
+    let fields = DataField::iter().collect::<Vec<_>>();
+    let mut collector = DataCollector::new_with_client(miner, &mock_api);
+    collector.collect(&fields).await;
+    collector.diagnostics(&fields)
+}
+
+/// Fails with the diagnostics of every field that got no value out of `responses`, other than
+/// the ones in `allowed`.
+pub(crate) async fn assert_fields_extracted<M: MinerInterface>(
+    miner: &M,
+    responses: HashMap<MinerCommand, Value>,
+    allowed: &[DataField],
+) {
+    let missing: HashMap<_, _> = unextracted_fields(miner, responses)
+        .await
+        .into_iter()
+        .filter(|(field, _)| !allowed.contains(field))
+        .collect();
+    assert!(missing.is_empty(), "fields without a value: {missing:#?}");
+}
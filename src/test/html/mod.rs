@@ -0,0 +1,7 @@
+#![cfg(test)]
+#![allow(dead_code)]
+
+/// Outlet page of a rack PDU whose outlet names mention miner makes.
+pub(crate) const PDU_OUTLETS: &str = include_str!("pdu_outlets.html");
+/// Web root of a stock OpenWrt router, which redirects to LuCI like a WhatsMiner.
+pub(crate) const OPENWRT_ROUTER: &str = include_str!("openwrt_router.html");
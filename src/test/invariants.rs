@@ -0,0 +1,48 @@
+//! Checks that hold for the parsed data of every backend, run against each fixture.
+#![cfg(test)]
+
+use crate::data::hashrate::{HashRate, HashRateUnit};
+use crate::data::miner::MinerData;
+
+/// Expected and actual hashrates, of the miner and of each board, must be within an order of
+/// magnitude of each other. Anything further apart is a unit mistake, not an underperforming
+/// miner.
+pub(crate) fn assert_hashrate_magnitudes(data: &MinerData) {
+    assert_same_magnitude(
+        data.hashrate.as_ref(),
+        data.expected_hashrate.as_ref(),
+        "miner",
+    );
+    for board in &data.hashboards {
+        assert_same_magnitude(
+            board.hashrate.as_ref(),
+            board.expected_hashrate.as_ref(),
+            &format!("board {}", board.position),
+        );
+    }
+}
+
+fn assert_same_magnitude(actual: Option<&HashRate>, expected: Option<&HashRate>, context: &str) {
+    let Some(expected) = expected else {
+        return;
+    };
+    assert_eq!(
+        expected.unit,
+        HashRateUnit::TeraHash,
+        "{context}: expected hashrate is not in TH/s"
+    );
+    let Some(actual) = actual else {
+        return;
+    };
+    let actual = actual.clone().as_unit(HashRateUnit::TeraHash).value;
+    // idle and paused miners report no hashrate, there is nothing to compare against
+    if actual <= 0.0 {
+        return;
+    }
+    let ratio = expected.value / actual;
+    assert!(
+        (0.1..=10.0).contains(&ratio),
+        "{context}: expected hashrate {} TH/s is not within an order of magnitude of {actual} TH/s",
+        expected.value
+    );
+}
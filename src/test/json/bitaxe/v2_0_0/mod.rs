@@ -1,3 +1,4 @@
 #![cfg(test)]
 
 pub(crate) const SYSTEM_INFO_COMMAND: &str = include_str!("system_info.json");
+pub(crate) const SYSTEM_INFO_SINGLE_POOL: &str = include_str!("system_info_single_pool.json");
@@ -0,0 +1,7 @@
+#![cfg(test)]
+#![allow(dead_code)]
+
+pub(crate) const AM_LEGACY_POOLS: &str = include_str!("pools.json");
+pub(crate) const AM_LEGACY_STATS_S9: &str = include_str!("stats_s9.json");
+pub(crate) const AM_LEGACY_SUMMARY_S9: &str = include_str!("summary_s9.json");
+pub(crate) const AM_LEGACY_VERSION: &str = include_str!("version.json");
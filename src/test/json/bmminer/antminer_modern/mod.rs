@@ -3,6 +3,11 @@
 
 pub(crate) const AM_DEVS: &str = include_str!("devs.json");
 pub(crate) const AM_STATS: &str = include_str!("stats.json");
+pub(crate) const AM_STATS_S19XP_HYD: &str = include_str!("stats_s19xp_hyd.json");
+pub(crate) const AM_STATS_S9: &str = include_str!("stats_s9.json");
+pub(crate) const AM_STATS_S19J_PRO: &str = include_str!("stats_s19jpro.json");
+pub(crate) const AM_STATS_S21: &str = include_str!("stats_s21.json");
 pub(crate) const AM_POOLS: &str = include_str!("pools.json");
 pub(crate) const AM_SUMMARY: &str = include_str!("summary.json");
+pub(crate) const AM_SUMMARY_S9: &str = include_str!("summary_s9.json");
 pub(crate) const AM_VERSION: &str = include_str!("version.json");
@@ -0,0 +1,14 @@
+#![cfg(test)]
+#![allow(dead_code)]
+
+pub(crate) const AM_WEB_STATS: &str = include_str!("stats.json");
+pub(crate) const AM_WEB_CHAIN_TEMP: &str = include_str!("chain_temp.json");
+pub(crate) const AM_WEB_STATS_S21_HYD: &str = include_str!("stats_s21_hyd.json");
+pub(crate) const AM_WEB_POOLS: &str = include_str!("pools.json");
+pub(crate) const AM_WEB_SUMMARY: &str = include_str!("summary.json");
+pub(crate) const AM_WEB_SYSTEM_INFO: &str = include_str!("system_info.json");
+pub(crate) const AM_WEB_NETWORK_INFO: &str = include_str!("network_info.json");
+pub(crate) const AM_WEB_MINER_CONF_S19_LPM: &str = include_str!("miner_conf_s19_lpm.json");
+pub(crate) const AM_WEB_MINER_CONF_S19_MANUAL_FAN: &str =
+    include_str!("miner_conf_s19_manual_fan.json");
+pub(crate) const AM_WEB_STATS_S21_PSU: &str = include_str!("stats_s21_psu.json");
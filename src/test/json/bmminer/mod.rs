@@ -1 +1,3 @@
+pub mod antminer_legacy;
 pub mod antminer_modern;
+pub mod antminer_web;
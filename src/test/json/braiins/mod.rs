@@ -0,0 +1 @@
+pub(crate) mod v25_07;
@@ -0,0 +1,4 @@
+#![cfg(test)]
+#![allow(dead_code)]
+
+pub(crate) const POOLS_TWO_GROUPS: &str = include_str!("pools_two_groups.json");
@@ -1 +1,3 @@
 pub(crate) mod v1;
+pub(crate) mod v2;
+pub(crate) mod v3;
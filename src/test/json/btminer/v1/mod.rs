@@ -1,8 +1,12 @@
 #![cfg(test)]
 
 pub(crate) const SUMMARY_COMMAND: &str = include_str!("summary.json");
+pub(crate) const SUMMARY_20200917_COMMAND: &str = include_str!("summary_20200917.json");
 pub(crate) const STATUS_COMMAND: &str = include_str!("status.json");
+pub(crate) const STATUS_MINING_COMMAND: &str = include_str!("status_mining.json");
 pub(crate) const POOLS_COMMAND: &str = include_str!("pools.json");
 pub(crate) const DEVS_COMMAND: &str = include_str!("devs.json");
 pub(crate) const GET_VERSION_COMMAND: &str = include_str!("get_version.json");
 pub(crate) const GET_PSU_COMMAND: &str = include_str!("get_psu.json");
+pub(crate) const SUMMARY_ENV_NEGATIVE_COMMAND: &str = include_str!("summary_env_negative.json");
+pub(crate) const SUMMARY_ENV_FAULT_COMMAND: &str = include_str!("summary_env_fault.json");
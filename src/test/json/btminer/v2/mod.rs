@@ -0,0 +1,21 @@
+#![cfg(test)]
+
+pub(crate) const SUMMARY_20230302_COMMAND: &str = include_str!("summary_20230302.json");
+pub(crate) const SUMMARY_ENV_NEGATIVE_COMMAND: &str = include_str!("summary_env_negative.json");
+pub(crate) const SUMMARY_ENV_FAULT_COMMAND: &str = include_str!("summary_env_fault.json");
+pub(crate) const DEVS_20230302_COMMAND: &str = include_str!("devs_20230302.json");
+pub(crate) const DEVDETAILS_20230302_COMMAND: &str = include_str!("devdetails_20230302.json");
+pub(crate) const POOLS_20230302_COMMAND: &str = include_str!("pools_20230302.json");
+pub(crate) const STATUS_20230302_COMMAND: &str = include_str!("status_20230302.json");
+pub(crate) const STATUS_MINEROFF_20230911_COMMAND: &str =
+    include_str!("status_mineroff_20230911.json");
+pub(crate) const STATUS_MINEROFF_STOPPED_20230911_COMMAND: &str =
+    include_str!("status_mineroff_stopped_20230911.json");
+pub(crate) const STATUS_NO_MINEROFF_20221111_COMMAND: &str =
+    include_str!("status_no_mineroff_20221111.json");
+pub(crate) const GET_VERSION_20230302_COMMAND: &str = include_str!("get_version_20230302.json");
+pub(crate) const GET_PSU_20230302_COMMAND: &str = include_str!("get_psu_20230302.json");
+pub(crate) const GET_MINER_INFO_20230302_COMMAND: &str =
+    include_str!("get_miner_info_20230302.json");
+pub(crate) const GET_ERROR_CODE_20230302_COMMAND: &str =
+    include_str!("get_error_code_20230302.json");
@@ -0,0 +1,14 @@
+#![cfg(test)]
+
+pub(crate) const SUMMARY_COMMAND: &str = include_str!("summary.json");
+pub(crate) const SUMMARY_ENV_NEGATIVE_COMMAND: &str = include_str!("summary_env_negative.json");
+pub(crate) const SUMMARY_ENV_FAULT_COMMAND: &str = include_str!("summary_env_fault.json");
+pub(crate) const SUMMARY_ENV_MISSING_COMMAND: &str = include_str!("summary_env_missing.json");
+pub(crate) const GET_DEVICE_INFO_POWER_OFF_COMMAND: &str =
+    include_str!("get_device_info_power_off.json");
+pub(crate) const GET_DEVICE_INFO_COMMAND: &str = include_str!("get_device_info.json");
+pub(crate) const GET_MINER_STATUS_EDEVS_COMMAND: &str = include_str!("get_miner_status_edevs.json");
+pub(crate) const GET_MINER_SETTING_COMMAND: &str = include_str!("get_miner_setting.json");
+pub(crate) const GET_FAN_SETTING_COMMAND: &str = include_str!("get_fan_setting.json");
+pub(crate) const GET_MINER_STATUS_EDEVS_STATUS_COMMAND: &str =
+    include_str!("get_miner_status_edevs_status.json");
@@ -15,5 +15,8 @@ pub(crate) const ASCSET_WORKMODE_COMMAND: &str = include_str!("ascset_workmode.j
 pub(crate) const ASCSET_SOFTON_COMMAND: &str = include_str!("ascset_softon.json");
 pub(crate) const ASCSET_SOFTOFF_COMMAND: &str = include_str!("ascset_softoff.json");
 pub(crate) const PARSED_STATS_COMMAND: &str = include_str!("stats_parsed.json");
+pub(crate) const PARSED_STATS_HBINFO_COMMAND: &str = include_str!("stats_hbinfo_parsed.json");
 pub(crate) const AVALON_A_STATS: &str = include_str!("avalon_a_stats.json");
 pub(crate) const AVALON_A_STATS_PARSED: &str = include_str!("avalon_a_stats_parsed.json");
+pub(crate) const ASCSET_HELP_MPO_COMMAND: &str = include_str!("ascset_help_mpo.json");
+pub(crate) const NANO3_STATS_PARSED: &str = include_str!("nano3_stats_parsed.json");
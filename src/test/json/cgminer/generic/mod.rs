@@ -0,0 +1,10 @@
+#![cfg(test)]
+#![allow(dead_code)]
+
+pub(crate) const CGMINER_VERSION: &str = include_str!("version.json");
+pub(crate) const CGMINER_SUMMARY: &str = include_str!("summary.json");
+pub(crate) const CGMINER_DEVS: &str = include_str!("devs.json");
+pub(crate) const CGMINER_POOLS: &str = include_str!("pools.json");
+pub(crate) const CGMINER_STATS: &str = include_str!("stats.json");
+pub(crate) const CGMINER_POOLS_WEDGED: &str = include_str!("pools_wedged.json");
+pub(crate) const CGMINER_POOLS_SOLO: &str = include_str!("pools_solo.json");
@@ -1 +1,2 @@
 pub(crate) mod avalon;
+pub(crate) mod generic;
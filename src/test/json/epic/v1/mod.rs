@@ -9,3 +9,9 @@ pub(crate) const CHIP_CLOCKS: &str = include_str!("chip_clocks.json");
 pub(crate) const CHIP_TEMPS: &str = include_str!("chip_temps.json");
 pub(crate) const CHIP_VOLTAGES: &str = include_str!("chip_voltages.json");
 pub(crate) const CHIP_HASHRATES: &str = include_str!("chip_hashrates.json");
+pub(crate) const SUMMARY_KA3: &str = include_str!("summary_ka3.json");
+pub(crate) const CAPABILITIES_KA3: &str = include_str!("capabilities_ka3.json");
+pub(crate) const SUMMARY_AC: &str = include_str!("summary_ac.json");
+pub(crate) const SUMMARY_BM520I: &str = include_str!("summary_bm520i.json");
+pub(crate) const HISTORY: &str = include_str!("history.json");
+pub(crate) const HISTORY_LEGACY: &str = include_str!("history_legacy.json");
@@ -1,6 +1,8 @@
 #![cfg(test)]
 #![allow(dead_code)]
 
+pub(crate) const ATM_DISABLED: &str = include_str!("atm_disabled.json");
+pub(crate) const ATM_DOWNSCALED: &str = include_str!("atm_downscaled.json");
 pub(crate) const CONFIG: &str = include_str!("config.json");
 pub(crate) const DEVS: &str = include_str!("devs.json");
 pub(crate) const FANS: &str = include_str!("fans.json");
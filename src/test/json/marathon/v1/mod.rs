@@ -0,0 +1,5 @@
+#![cfg(test)]
+#![allow(dead_code)]
+
+pub(crate) const OVERVIEW_S19J_PRO: &str = include_str!("overview_s19j_pro.json");
+pub(crate) const OVERVIEW_AVALON_1246: &str = include_str!("overview_avalon_1246.json");
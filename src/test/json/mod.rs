@@ -1,6 +1,9 @@
 pub(crate) mod bitaxe;
 pub(crate) mod bmminer;
+pub(crate) mod braiins;
 pub(crate) mod btminer;
 pub(crate) mod cgminer;
 pub(crate) mod epic;
 pub(crate) mod luxminer;
+pub(crate) mod marathon;
+pub(crate) mod vnish;
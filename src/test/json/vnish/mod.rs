@@ -0,0 +1 @@
+pub(crate) mod v1_2_0;
@@ -0,0 +1,11 @@
+#![cfg(test)]
+#![allow(dead_code)]
+
+pub(crate) const SUMMARY_S19K_PRO_TUNING: &str = include_str!("summary_s19k_pro_tuning.json");
+pub(crate) const STATUS_S19K_PRO_TUNING: &str = include_str!("status_s19k_pro_tuning.json");
+pub(crate) const AUTOTUNE_STATUS_S19K_PRO_TUNING: &str =
+    include_str!("autotune_status_s19k_pro_tuning.json");
+pub(crate) const SUMMARY_S19K_PRO_TUNED: &str = include_str!("summary_s19k_pro_tuned.json");
+pub(crate) const STATUS_S19K_PRO_TUNED: &str = include_str!("status_s19k_pro_tuned.json");
+pub(crate) const AUTOTUNE_STATUS_S19K_PRO_TUNED: &str =
+    include_str!("autotune_status_s19k_pro_tuned.json");
@@ -1,3 +1,8 @@
 #![allow(dead_code)]
-pub(crate) mod api;
+pub mod api;
+pub(crate) mod completeness;
+pub(crate) mod html;
+pub(crate) mod invariants;
 pub(crate) mod json;
+#[cfg(any(test, feature = "test-util"))]
+pub(crate) mod simulator;
@@ -0,0 +1,498 @@
+//! Loopback miner simulators for end-to-end tests.
+//!
+//! A simulator binds the real miner ports (4028 cgminer RPC, 4433 btminer v3 RPC and any HTTP
+//! ports) on a dedicated loopback address and answers with recorded responses, so the factory
+//! and backend clients run over their actual socket and HTTP transports.
+//!
+//! Fixtures live in `src/test/simulator/fixtures/<name>/`:
+//!
+//! - `rpc/<command>.json`: cgminer protocol responses on port 4028, NUL terminated.
+//! - `btminer_v3/<command>.json`: length prefixed btminer v3 responses on port 4433.
+//! - `http/<port>/<path>`: HTTP responses, `index.html` is served for `/` and a trailing
+//!   `.json` is stripped from the route. A `.digest` file in the port directory requires digest
+//!   authentication for every route on that port, for the username it contains if it isn't
+//!   empty. Digest responses aren't verified, so any password is accepted.
+//!
+//! Commands sent with a string parameter are looked up as `<command>@<parameter>.json` first,
+//! then fall back to `<command>.json`.
+//!
+//! A fixture can be given a response delay to simulate slow hosts, which is applied to every
+//! connection before the request is read. It can also be made to reset RPC connections opened
+//! while another is being served, like firmware that answers one API connection at a time.
+//!
+//! Every request is counted by its command or HTTP path, so tests can assert how many round
+//! trips a client needed.
+//!
+//! The `test-util` feature exports the simulator as `asic_rs::test_util::MinerSimulator`, for the
+//! command line tool's integration tests.
+//!
+//! The factory probes the real ports, so simulators can't move to ephemeral ones. Binding port 80
+//! takes privileges, and addresses past 127.0.0.1 need loopback aliases on macOS, so the tests
+//! using simulators are ignored by default and fail if the ports can't be bound. Run them with
+//! `cargo test -- --ignored`, CI runs them as root.
+//!
+//! The HTTP server is a small hand-written HTTP/1.1 responder rather than hyper or axum: it only
+//! serves recorded bodies and digest challenges, one request per connection, and writing the
+//! responses by hand keeps what goes on the wire exactly what the fixture records.
+
+#[cfg(test)]
+mod tests;
+
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+const CGMINER_RPC_PORT: u16 = 4028;
+const BTMINER_V3_RPC_PORT: u16 = 4433;
+const MAX_REQUEST_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone)]
+struct HttpResponse {
+    content_type: &'static str,
+    body: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct HttpFixture {
+    digest_auth: bool,
+    digest_username: Option<String>,
+    routes: HashMap<String, HttpResponse>,
+}
+
+/// The recorded responses a [`MinerSimulator`] serves, see the [module docs][`self`].
+#[derive(Debug, Clone, Default)]
+pub struct SimulatorFixture {
+    rpc: HashMap<String, Value>,
+    btminer_v3: HashMap<String, Value>,
+    http: HashMap<u16, HttpFixture>,
+    delay: Duration,
+    serial: bool,
+}
+
+impl SimulatorFixture {
+    /// Load one of the fixtures in `src/test/simulator/fixtures` by name.
+    pub fn load(name: &str) -> io::Result<Self> {
+        Self::from_dir(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("src/test/simulator/fixtures")
+                .join(name),
+        )
+    }
+
+    /// Load a fixture laid out like the ones in `src/test/simulator/fixtures`.
+    pub fn from_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let mut fixture = Self {
+            rpc: load_commands(&dir.join("rpc"))?,
+            btminer_v3: load_commands(&dir.join("btminer_v3"))?,
+            ..Default::default()
+        };
+
+        let http_dir = dir.join("http");
+        if http_dir.is_dir() {
+            for entry in fs::read_dir(&http_dir)? {
+                let path = entry?.path();
+                let Some(port) = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(|n| n.parse::<u16>().ok())
+                else {
+                    continue;
+                };
+                let digest = fs::read_to_string(path.join(".digest")).ok();
+                let mut http = HttpFixture {
+                    digest_auth: digest.is_some(),
+                    digest_username: digest
+                        .map(|username| username.trim().to_string())
+                        .filter(|username| !username.is_empty()),
+                    ..Default::default()
+                };
+                for file in list_files(&path)? {
+                    let relative = file.strip_prefix(&path).unwrap().to_string_lossy();
+                    if relative.starts_with('.') {
+                        continue;
+                    }
+                    let content_type = if relative.ends_with(".html") {
+                        "text/html"
+                    } else {
+                        "application/json"
+                    };
+                    let route = match relative.as_ref() {
+                        "index.html" => "/".to_string(),
+                        r => format!("/{}", r.strip_suffix(".json").unwrap_or(r)),
+                    };
+                    http.routes.insert(
+                        route,
+                        HttpResponse {
+                            content_type,
+                            body: fs::read_to_string(&file)?,
+                        },
+                    );
+                }
+                fixture.http.insert(port, http);
+            }
+        }
+
+        Ok(fixture)
+    }
+
+    /// Delay every connection before its request is read, to simulate a slow host.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Reset RPC connections on port 4028 opened while another one is being served.
+    pub fn with_serial_connections(mut self) -> Self {
+        self.serial = true;
+        self
+    }
+
+    /// Only authorize digest requests for `username` on `port`.
+    pub fn with_digest_username(mut self, port: u16, username: &str) -> Self {
+        let http = self.http.entry(port).or_default();
+        http.digest_auth = true;
+        http.digest_username = Some(username.to_string());
+        self
+    }
+}
+
+fn list_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(list_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn load_commands(dir: &Path) -> io::Result<HashMap<String, Value>> {
+    let mut commands = HashMap::new();
+    if !dir.is_dir() {
+        return Ok(commands);
+    }
+    for file in list_files(dir)? {
+        let Some(command) = file
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        let value = serde_json::from_str(&fs::read_to_string(&file)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        commands.insert(command.to_string(), value);
+    }
+    Ok(commands)
+}
+
+fn lookup_command<'a>(
+    commands: &'a HashMap<String, Value>,
+    command: &str,
+    parameter: Option<&Value>,
+) -> Option<&'a Value> {
+    parameter
+        .and_then(|p| p.as_str())
+        .and_then(|p| commands.get(&format!("{command}@{p}")))
+        .or_else(|| commands.get(command))
+}
+
+/// A running set of listeners serving one fixture, shut down when dropped.
+pub struct MinerSimulator {
+    ip: IpAddr,
+    tasks: Vec<JoinHandle<()>>,
+    connections: Arc<AtomicUsize>,
+    rejected: Arc<AtomicUsize>,
+    requests: RequestCounts,
+}
+
+type RequestCounts = Arc<Mutex<HashMap<String, usize>>>;
+
+fn count_request(requests: &RequestCounts, request: &str) {
+    *requests
+        .lock()
+        .unwrap()
+        .entry(request.to_string())
+        .or_default() += 1;
+}
+
+impl MinerSimulator {
+    pub async fn start(ip: IpAddr, fixture: SimulatorFixture) -> io::Result<Self> {
+        let mut simulator = Self {
+            ip,
+            tasks: vec![],
+            connections: Arc::new(AtomicUsize::new(0)),
+            rejected: Arc::new(AtomicUsize::new(0)),
+            requests: Arc::default(),
+        };
+        let delay = fixture.delay;
+
+        if !fixture.rpc.is_empty() {
+            let listener = TcpListener::bind((ip, CGMINER_RPC_PORT)).await?;
+            let commands = Arc::new(fixture.rpc);
+            let connections = simulator.connections.clone();
+            let rejected = simulator.rejected.clone();
+            let requests = simulator.requests.clone();
+            let serial = fixture.serial.then(|| Arc::new(Semaphore::new(1)));
+            simulator.tasks.push(tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    connections.fetch_add(1, Ordering::SeqCst);
+                    let serving = match &serial {
+                        Some(serial) => match serial.clone().try_acquire_owned() {
+                            Ok(permit) => Some(permit),
+                            Err(_) => {
+                                rejected.fetch_add(1, Ordering::SeqCst);
+                                let _ = stream.set_linger(Some(Duration::ZERO));
+                                continue;
+                            }
+                        },
+                        None => None,
+                    };
+                    let commands = commands.clone();
+                    let requests = requests.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        serve_cgminer(stream, commands, requests).await;
+                        drop(serving);
+                    });
+                }
+            }));
+        }
+
+        if !fixture.btminer_v3.is_empty() {
+            let listener = TcpListener::bind((ip, BTMINER_V3_RPC_PORT)).await?;
+            let commands = Arc::new(fixture.btminer_v3);
+            let connections = simulator.connections.clone();
+            let requests = simulator.requests.clone();
+            simulator.tasks.push(tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    connections.fetch_add(1, Ordering::SeqCst);
+                    let commands = commands.clone();
+                    let requests = requests.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        serve_btminer_v3(stream, commands, requests).await
+                    });
+                }
+            }));
+        }
+
+        for (port, http) in fixture.http {
+            let listener = TcpListener::bind((ip, port)).await?;
+            let http = Arc::new(http);
+            let connections = simulator.connections.clone();
+            let requests = simulator.requests.clone();
+            simulator.tasks.push(tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    connections.fetch_add(1, Ordering::SeqCst);
+                    let http = http.clone();
+                    let requests = requests.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        serve_http(stream, http, requests).await
+                    });
+                }
+            }));
+        }
+
+        Ok(simulator)
+    }
+
+    pub fn ip(&self) -> IpAddr {
+        self.ip
+    }
+
+    /// The number of connections accepted across all listeners so far.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    /// The number of RPC connections reset for overlapping another one so far.
+    pub fn rejected(&self) -> usize {
+        self.rejected.load(Ordering::SeqCst)
+    }
+
+    /// The number of times `request`, an RPC command or HTTP path, was received so far.
+    pub fn requests(&self, request: &str) -> usize {
+        self.requests
+            .lock()
+            .unwrap()
+            .get(request)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The number of requests received across all listeners so far.
+    pub fn total_requests(&self) -> usize {
+        self.requests.lock().unwrap().values().sum()
+    }
+}
+
+impl Drop for MinerSimulator {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn read_json_request(stream: &mut TcpStream) -> Option<Value> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = stream.read(&mut chunk).await.ok()?;
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Ok(request) = serde_json::from_slice::<Value>(buffer.trim_ascii()) {
+            return Some(request);
+        }
+        if read == 0 || buffer.len() > MAX_REQUEST_SIZE {
+            return None;
+        }
+    }
+}
+
+async fn serve_cgminer(
+    mut stream: TcpStream,
+    commands: Arc<HashMap<String, Value>>,
+    requests: RequestCounts,
+) {
+    let Some(request) = read_json_request(&mut stream).await else {
+        return;
+    };
+    let command = request["command"].as_str().unwrap_or_default();
+    count_request(&requests, command);
+    let response = lookup_command(&commands, command, request.get("parameter"))
+        .cloned()
+        .unwrap_or_else(
+            || json!({"STATUS": [{"STATUS": "E", "Code": 14, "Msg": "Invalid command"}], "id": 1}),
+        );
+
+    let mut bytes = response.to_string().into_bytes();
+    bytes.push(0);
+    let _ = stream.write_all(&bytes).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn serve_btminer_v3(
+    mut stream: TcpStream,
+    commands: Arc<HashMap<String, Value>>,
+    requests: RequestCounts,
+) {
+    let mut len_buf = [0u8; 4];
+    if stream.read_exact(&mut len_buf).await.is_err() {
+        return;
+    }
+    let request_len = u32::from_le_bytes(len_buf) as usize;
+    if request_len > MAX_REQUEST_SIZE {
+        return;
+    }
+    let mut request_buf = vec![0u8; request_len];
+    if stream.read_exact(&mut request_buf).await.is_err() {
+        return;
+    }
+    let Ok(request) = serde_json::from_slice::<Value>(&request_buf) else {
+        return;
+    };
+
+    let command = request["cmd"].as_str().unwrap_or_default();
+    count_request(&requests, command);
+    let response = lookup_command(&commands, command, request.get("param"))
+        .cloned()
+        .unwrap_or_else(|| json!({"code": -1, "msg": "invalid command"}));
+
+    let body = response.to_string().into_bytes();
+    let _ = stream.write_all(&(body.len() as u32).to_le_bytes()).await;
+    let _ = stream.write_all(&body).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn serve_http(mut stream: TcpStream, http: Arc<HttpFixture>, requests: RequestCounts) {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let Ok(read) = stream.read(&mut chunk).await else {
+            return;
+        };
+        buffer.extend_from_slice(&chunk[..read]);
+        // cgminer style probes land here too on shared ports, drop anything that isn't HTTP
+        if !buffer.is_empty() && !buffer[0].is_ascii_uppercase() {
+            return;
+        }
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if read == 0 || buffer.len() > MAX_REQUEST_SIZE {
+            return;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let path = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .unwrap_or("/");
+    let path = path.split('?').next().unwrap_or(path);
+    count_request(&requests, path);
+    let headers: HashMap<String, String> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect();
+
+    // drain the request body so the client sees a clean close
+    let content_length = headers
+        .get("content-length")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_REQUEST_SIZE);
+    let mut received = buffer.len() - header_end - 4;
+    while received < content_length {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(read) => received += read,
+        }
+    }
+
+    let authorized = !http.digest_auth
+        || headers.get("authorization").is_some_and(|a| {
+            a.starts_with("Digest ")
+                && http
+                    .digest_username
+                    .as_ref()
+                    .is_none_or(|username| a.contains(&format!("username=\"{username}\"")))
+        });
+
+    let response = if !authorized {
+        "HTTP/1.1 401 Unauthorized\r\n\
+         WWW-Authenticate: Digest realm=\"antMiner Configuration\", nonce=\"7a0d8d2c1e9f4b3a\", qop=\"auth\"\r\n\
+         Content-Length: 0\r\n\
+         Connection: close\r\n\r\n"
+            .to_string()
+    } else if let Some(route) = http.routes.get(path) {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            route.content_type,
+            route.body.len(),
+            route.body
+        )
+    } else {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+    };
+
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
@@ -0,0 +1,768 @@
+use super::*;
+use crate::data::device::models::antminer::AntMinerModel;
+use crate::data::device::models::avalon::AvalonMinerModel;
+use crate::data::device::models::bitaxe::BitaxeModel;
+use crate::data::device::models::whatsminer::WhatsMinerModel;
+use crate::data::device::{MinerFirmware, MinerModel};
+use crate::data::miner::MinerStatus;
+#[cfg(feature = "avalon")]
+use crate::miners::backends::avalonminer::v1::AvalonMinerV1;
+use crate::miners::backends::dry_run::DryRun;
+use crate::miners::backends::errors::MinerError;
+use crate::miners::backends::traits::{CollectData, Credentials, GetMinerData, Miner};
+use crate::miners::data::{DataField, ExtractionFailure};
+use crate::miners::factory::MinerFactory;
+use crate::miners::factory::checkpoint::{Checkpoint, FileCheckpoint};
+use crate::miners::factory::order::ScanOrder;
+use futures::StreamExt;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Start a simulator on `ip`, failing the test if the miner ports can't be bound (e.g. port 80
+/// without privileges, or loopback aliases missing on macOS).
+async fn start(ip: [u8; 4], fixture: SimulatorFixture) -> MinerSimulator {
+    MinerSimulator::start(IpAddr::from(ip), fixture)
+        .await
+        .unwrap_or_else(|e| panic!("could not bind the miner ports on {ip:?}: {e}"))
+}
+
+/// Start a simulator for the fixture named `fixture` on `ip`.
+async fn simulate(ip: [u8; 4], fixture: &str) -> MinerSimulator {
+    simulate_with_delay(ip, fixture, Duration::ZERO).await
+}
+
+async fn simulate_with_delay(ip: [u8; 4], fixture: &str, delay: Duration) -> MinerSimulator {
+    let fixture = SimulatorFixture::load(fixture)
+        .expect("failed to load simulator fixture")
+        .with_delay(delay);
+    start(ip, fixture).await
+}
+
+async fn discover(simulator: &MinerSimulator) -> Box<dyn Miner> {
+    MinerFactory::new()
+        .with_identification_timeout(Duration::from_secs(5))
+        .get_miner(simulator.ip())
+        .await
+        .unwrap()
+        .expect("simulated miner was not identified")
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "antminer")]
+async fn test_simulated_antminer() {
+    let simulator = simulate([127, 0, 0, 11], "antminer_s19_pro").await;
+    let miner = discover(&simulator).await;
+
+    let device_info = miner.get_device_info();
+    assert_eq!(
+        device_info.model,
+        MinerModel::AntMiner(AntMinerModel::S19Pro)
+    );
+    assert_eq!(device_info.firmware, MinerFirmware::Stock);
+
+    let data = miner.get_data().await;
+    assert_eq!(data.ip, simulator.ip());
+    assert_eq!(data.mac.unwrap().to_string(), "AA:BB:CC:DD:EE:FF");
+    assert_eq!(data.hashboards.len(), 3);
+    assert_eq!(data.light_flashing, Some(false));
+    assert!(data.hashrate.is_some());
+    assert!(!data.pools.is_empty());
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "antminer")]
+async fn test_simulated_antminer_hostname_dry_run() {
+    let simulator = simulate([127, 0, 0, 20], "antminer_s19_pro").await;
+    let mut miner = discover(&simulator).await;
+    let dry_run = DryRun::new();
+    miner.set_dry_run(Some(dry_run.clone()));
+
+    miner.set_hostname("rack4-slot12").await.unwrap();
+
+    // the plan is the whole config sent back, keeping the static addressing
+    let actions = dry_run.actions();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].command, "set_network_conf");
+    assert_eq!(
+        actions[0].payload,
+        serde_json::json!({
+            "ipAddress": "10.0.0.21",
+            "ipDns": "10.0.0.1,1.1.1.1",
+            "ipGateway": "10.0.0.1",
+            "ipHost": "rack4-slot12",
+            "ipPro": 2,
+            "ipSub": "255.255.255.0"
+        })
+    );
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "antminer")]
+async fn test_simulated_antminer_legacy() {
+    // no build date over RPC, the web interface has no JSON endpoints
+    let simulator = simulate([127, 0, 0, 22], "antminer_s9_legacy").await;
+    let miner = discover(&simulator).await;
+    assert!(format!("{miner:?}").starts_with("AntMinerLegacy"));
+
+    let data = miner.get_data().await;
+    let working: Vec<_> = data.hashboards.iter().map(|b| b.working_chips).collect();
+    assert_eq!(working, vec![Some(63), Some(61), Some(62)]);
+    assert_eq!(data.status, MinerStatus::Mining);
+    assert_eq!(miner.get_status().await, MinerStatus::Mining);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "whatsminer")]
+async fn test_simulated_whatsminer_v3() {
+    let simulator = simulate([127, 0, 0, 12], "whatsminer_m60s_vk30").await;
+    let miner = discover(&simulator).await;
+
+    assert_eq!(
+        miner.get_device_info().model,
+        MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30)
+    );
+
+    let data = miner.get_data().await;
+    assert_eq!(data.hostname.as_deref(), Some("WhatsMiner"));
+    assert_eq!(data.hashrate.unwrap().value, 186.33);
+    assert_eq!(data.wattage.unwrap().as_watts(), 3412.0);
+    assert_eq!(data.hashboards.len(), 3);
+    assert_eq!(data.fans.len(), 2);
+    assert_eq!(data.status, MinerStatus::Mining);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "avalon")]
+async fn test_simulated_avalonminer() {
+    let simulator = simulate([127, 0, 0, 13], "avalon_1246").await;
+    let miner = discover(&simulator).await;
+
+    assert_eq!(
+        miner.get_device_info().model,
+        MinerModel::AvalonMiner(AvalonMinerModel::Avalon1246)
+    );
+
+    let data = miner.get_data().await;
+    assert!(data.hashrate.is_some());
+    assert!(!data.hashboards.is_empty());
+    assert!(!data.pools.is_empty());
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "epic")]
+async fn test_simulated_epic() {
+    let simulator = simulate([127, 0, 0, 14], "epic_s19_xp").await;
+    let miner = discover(&simulator).await;
+
+    let device_info = miner.get_device_info();
+    assert_eq!(
+        device_info.model,
+        MinerModel::AntMiner(AntMinerModel::S19XP)
+    );
+    assert_eq!(device_info.firmware, MinerFirmware::EPic);
+
+    let data = miner.get_data().await;
+    assert_eq!(data.hostname.as_deref(), Some("cminer32092035"));
+    assert!(data.hashrate.is_some());
+    assert_eq!(data.status, MinerStatus::Mining);
+    assert!(data.is_mining);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "bitaxe")]
+async fn test_simulated_bitaxe() {
+    let simulator = simulate([127, 0, 0, 15], "bitaxe_supra").await;
+    let miner = discover(&simulator).await;
+
+    assert_eq!(
+        miner.get_device_info().model,
+        MinerModel::Bitaxe(BitaxeModel::Supra)
+    );
+
+    let data = miner.get_data().await;
+    assert_eq!(data.hostname.as_deref(), Some("bitaxe"));
+    assert_eq!(data.pools.len(), 2);
+    assert!(data.hashrate.is_some());
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "braiins")]
+async fn test_simulated_dual_identity() {
+    // answers as a stock Antminer on the web UI and as BOSminer on the cgminer socket
+    let simulator = simulate([127, 0, 0, 16], "braiins_dual").await;
+
+    for _ in 0..5 {
+        let miner = discover(&simulator).await;
+        let device_info = miner.get_device_info();
+        assert_eq!(device_info.firmware, MinerFirmware::BraiinsOS);
+        assert_eq!(
+            device_info.model,
+            MinerModel::AntMiner(AntMinerModel::S19Pro)
+        );
+    }
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(all(feature = "antminer", feature = "braiins"))]
+async fn test_bos_toolbox_redispatch() {
+    use crate::miners::factory::checkpoint::{CHECKPOINT_VERSION, CheckpointRecord, MinerIdentity};
+
+    // a miner converted with the BOS toolbox, identified as stock by an earlier scan
+    let simulator = simulate([127, 0, 0, 58], "braiins_dual").await;
+    let s19_pro = MinerModel::AntMiner(AntMinerModel::S19Pro);
+    let records = [
+        CheckpointRecord::Header {
+            version: CHECKPOINT_VERSION,
+            hosts: vec![simulator.ip()],
+        },
+        CheckpointRecord::Identification {
+            ip: simulator.ip(),
+            identity: Some(MinerIdentity {
+                model: s19_pro.clone(),
+                firmware: Some(MinerFirmware::Stock),
+                version: Some("2022.12.6".to_string()),
+            }),
+        },
+    ];
+    let path =
+        std::env::temp_dir().join(format!("asic-rs-bos-toolbox-{}.jsonl", std::process::id()));
+    let contents: String = records
+        .iter()
+        .map(|r| serde_json::to_string(r).unwrap() + "\n")
+        .collect();
+    std::fs::write(&path, contents).unwrap();
+
+    // the stock backend sees BOSminer in its first RPC and hands the miner to Braiins
+    let miners = MinerFactory::new()
+        .with_identification_timeout(Duration::from_secs(5))
+        .resume_scan(Checkpoint::load(&path).unwrap())
+        .with_checkpoint(Arc::new(FileCheckpoint::new(&path).unwrap()))
+        .scan()
+        .await
+        .unwrap();
+    assert_eq!(miners.len(), 1);
+    let device_info = miners[0].get_device_info();
+    assert_eq!(device_info.firmware, MinerFirmware::BraiinsOS);
+    assert_eq!(device_info.model, s19_pro);
+    assert_eq!(
+        miners[0].get_data().await.device_info.firmware,
+        MinerFirmware::BraiinsOS
+    );
+
+    // the corrected identity is recorded, so the next resume builds Braiins directly
+    let checkpoint = Checkpoint::load(&path).unwrap();
+    let identity = checkpoint.identity(&simulator.ip()).flatten().unwrap();
+    assert_eq!(identity.firmware, Some(MinerFirmware::BraiinsOS));
+    assert_eq!(identity.model, s19_pro);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(all(feature = "antminer", feature = "whatsminer"))]
+async fn test_identification_reuses_responses() {
+    let simulator = simulate([127, 0, 0, 17], "whatsminer_m60s_vk30").await;
+    discover(&simulator).await;
+
+    // routes the model lookup and gives the version from one response
+    assert_eq!(simulator.requests("get_version"), 1);
+    assert_eq!(simulator.requests("devdetails"), 1);
+    assert_eq!(simulator.requests("get.device.info"), 1);
+
+    let simulator = simulate([127, 0, 0, 18], "antminer_s19_pro").await;
+    discover(&simulator).await;
+
+    // the system info gives both model and version, each digest request is a challenge and
+    // an authenticated retry
+    assert_eq!(simulator.requests("/cgi-bin/get_system_info.cgi"), 2);
+    assert_eq!(simulator.requests("/cgi-bin/summary.cgi"), 0);
+    // the version, devdetails and web root probes, then the system info
+    assert_eq!(simulator.total_requests(), 5);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "epic")]
+async fn test_identification_lookups_run_concurrently() {
+    let delay = Duration::from_millis(500);
+    let simulator = simulate_with_delay([127, 0, 0, 19], "epic_s19_xp", delay).await;
+
+    let started = Instant::now();
+    let miner = discover(&simulator).await;
+    let elapsed = started.elapsed();
+
+    assert_eq!(miner.get_device_info().firmware, MinerFirmware::EPic);
+    assert_eq!(simulator.requests("/capabilities"), 1);
+    assert_eq!(simulator.requests("/summary"), 1);
+    // one delayed round trip for the probes and one for the model and version together
+    assert!(elapsed >= delay * 2);
+    assert!(elapsed < delay * 3, "identification took {elapsed:?}");
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "avalon")]
+async fn test_scan_stream_yields_in_completion_order() {
+    let mut simulators = Vec::new();
+    for (host, delay) in [(31, 0), (32, 300), (33, 600)] {
+        let simulator = simulate_with_delay(
+            [127, 0, 0, host],
+            "avalon_1246",
+            Duration::from_millis(delay),
+        )
+        .await;
+        simulators.push(simulator);
+    }
+
+    let factory = MinerFactory::from_range("127.0.0.31-33")
+        .unwrap()
+        .with_identification_timeout(Duration::from_secs(5));
+    let started = Instant::now();
+    let mut stream = factory.scan_stream();
+
+    let mut arrivals = Vec::new();
+    while let Some(miner) = stream.next().await {
+        arrivals.push((miner.get_ip(), started.elapsed()));
+    }
+
+    let ips: Vec<IpAddr> = arrivals.iter().map(|(ip, _)| *ip).collect();
+    let expected: Vec<IpAddr> = simulators.iter().map(|s| s.ip()).collect();
+    assert_eq!(ips, expected);
+    // the fast host must not wait on the slow ones
+    assert!(arrivals[0].1 + Duration::from_millis(500) < arrivals[2].1);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "avalon")]
+async fn test_scan_stream_drop_cancels_probes() {
+    let mut simulators = Vec::new();
+    for host in 34..=36 {
+        let simulator =
+            simulate_with_delay([127, 0, 0, host], "avalon_1246", Duration::from_secs(1)).await;
+        simulators.push(simulator);
+    }
+    let connections = |simulators: &[MinerSimulator]| -> usize {
+        simulators.iter().map(|s| s.connections()).sum()
+    };
+
+    let factory = MinerFactory::from_range("127.0.0.34-36")
+        .unwrap()
+        .with_concurrent_limit(1);
+    let mut stream = factory.scan_stream();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(300), stream.next())
+            .await
+            .is_err()
+    );
+    drop(stream);
+
+    let probed = connections(&simulators);
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    assert!(probed > 0);
+    assert_eq!(connections(&simulators), probed);
+    assert!(simulators.iter().any(|s| s.connections() == 0));
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "avalon")]
+async fn test_scan_attaches_labels() {
+    let mut simulators = Vec::new();
+    for host in 37..=38 {
+        let simulator = simulate([127, 0, 0, host], "avalon_1246").await;
+        simulators.push(simulator);
+    }
+    let labels = HashMap::from([
+        ("rack".to_string(), "A1".to_string()),
+        ("slot".to_string(), "4".to_string()),
+    ]);
+
+    let factory = MinerFactory::from_range("127.0.0.37-38")
+        .unwrap()
+        .with_identification_timeout(Duration::from_secs(5))
+        .with_labels(simulators[0].ip(), labels.clone());
+    let miners: Vec<Box<dyn Miner>> = factory.scan_stream().collect().await;
+    assert_eq!(miners.len(), 2);
+
+    for miner in miners {
+        let data = miner.get_data().await;
+        if data.ip == simulators[0].ip() {
+            assert_eq!(data.labels.as_ref(), Some(&labels));
+        } else {
+            assert_eq!(data.labels, None);
+        }
+    }
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "avalon")]
+async fn test_scan_stream_cancellation() {
+    let mut simulators = Vec::new();
+    for (host, delay) in [(39, 0), (40, 0), (41, 5), (42, 5), (43, 5)] {
+        let simulator =
+            simulate_with_delay([127, 0, 0, host], "avalon_1246", Duration::from_secs(delay)).await;
+        simulators.push(simulator);
+    }
+    let connections = |simulators: &[MinerSimulator]| -> usize {
+        simulators.iter().map(|s| s.connections()).sum()
+    };
+
+    let token = CancellationToken::new();
+    let factory = MinerFactory::from_range("127.0.0.39-43")
+        .unwrap()
+        .with_scan_order(ScanOrder::Sequential)
+        .with_identification_timeout(Duration::from_secs(10))
+        .with_cancellation(token.clone());
+    let mut stream = factory.scan_stream();
+
+    let mut found = Vec::new();
+    while found.len() < 2 {
+        let miner = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("fast hosts were not identified")
+            .expect("stream ended early");
+        found.push(miner.get_ip());
+    }
+    token.cancel();
+    found.sort();
+
+    // the slow hosts are still being probed, the stream must end without waiting on them
+    let started = Instant::now();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), stream.next())
+            .await
+            .expect("stream did not end after cancellation")
+            .is_none()
+    );
+    assert!(started.elapsed() < Duration::from_millis(500));
+    assert_eq!(found, vec![simulators[0].ip(), simulators[1].ip()]);
+
+    // aborted probes are not retried
+    let probed = connections(&simulators);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(connections(&simulators), probed);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+async fn test_scan_cancellation_returns_cancelled() {
+    let simulator =
+        simulate_with_delay([127, 0, 0, 44], "avalon_1246", Duration::from_secs(5)).await;
+
+    let token = CancellationToken::new();
+    let factory = MinerFactory::from_range("127.0.0.44")
+        .unwrap()
+        .with_cancellation(token.clone());
+    let cancel = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        token.cancel();
+    });
+
+    let started = Instant::now();
+    let result = factory.scan().await;
+    cancel.await.unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(1));
+    let Err(error) = result else {
+        panic!("cancelled scan returned miners");
+    };
+    assert_eq!(
+        error.downcast_ref::<MinerError>(),
+        Some(&MinerError::Cancelled)
+    );
+    assert!(
+        factory
+            .get_miner(simulator.ip())
+            .await
+            .unwrap_err()
+            .is::<MinerError>()
+    );
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "avalon")]
+async fn test_collection_cancellation() {
+    let simulator =
+        simulate_with_delay([127, 0, 0, 45], "avalon_1246", Duration::from_secs(5)).await;
+    let miner = AvalonMinerV1::new(
+        simulator.ip(),
+        MinerModel::AvalonMiner(AvalonMinerModel::Avalon1246),
+    );
+
+    let token = CancellationToken::new();
+    let cancel = {
+        let token = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            token.cancel();
+        })
+    };
+
+    let started = Instant::now();
+    let mut collector = miner.get_collector().with_cancellation(token);
+    let result = collector.try_collect_all().await;
+    cancel.await.unwrap();
+
+    assert_eq!(result, Err(MinerError::Cancelled));
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(simulator.connections(), 1);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "avalon")]
+async fn test_resume_interrupted_scan() {
+    // 127.0.0.49 is left without a simulator, so the scan finds it unreachable
+    let mut simulators = Vec::new();
+    for host in [48, 50, 51] {
+        let simulator = simulate([127, 0, 0, host], "avalon_1246").await;
+        simulators.push(simulator);
+    }
+    let path =
+        std::env::temp_dir().join(format!("asic-rs-checkpoint-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let writer = Arc::new(FileCheckpoint::new(&path).unwrap());
+
+    let factory = MinerFactory::from_range("127.0.0.48-51")
+        .unwrap()
+        .with_scan_order(ScanOrder::Sequential)
+        .with_identification_timeout(Duration::from_secs(5))
+        .with_concurrent_limit(1)
+        .with_checkpoint(writer);
+    let mut stream = factory.scan_stream_with_ip();
+    let mut interrupted = Vec::new();
+    for _ in 0..2 {
+        interrupted.push(stream.next().await.unwrap().0);
+    }
+    drop(stream);
+    assert_eq!(
+        interrupted,
+        vec![simulators[0].ip(), IpAddr::from([127, 0, 0, 49])]
+    );
+    let probed: Vec<usize> = simulators.iter().map(|s| s.connections()).collect();
+    assert!(probed[0] > 0);
+    assert_eq!(&probed[1..], &[0, 0]);
+
+    let checkpoint = Checkpoint::load(&path).unwrap();
+    assert!(checkpoint.is_complete(&simulators[0].ip()));
+    assert!(!checkpoint.is_complete(&simulators[1].ip()));
+
+    let miners = MinerFactory::new()
+        .with_identification_timeout(Duration::from_secs(5))
+        .resume_scan(checkpoint)
+        .with_checkpoint(Arc::new(FileCheckpoint::new(&path).unwrap()))
+        .scan()
+        .await
+        .unwrap();
+
+    let mut ips: Vec<IpAddr> = miners.iter().map(|miner| miner.get_ip()).collect();
+    ips.sort();
+    assert_eq!(ips, simulators.iter().map(|s| s.ip()).collect::<Vec<_>>());
+    for miner in &miners {
+        assert_eq!(
+            miner.get_device_info().model,
+            MinerModel::AvalonMiner(AvalonMinerModel::Avalon1246)
+        );
+    }
+    // the identified host is restored from the checkpoint, only the rest are probed
+    assert_eq!(simulators[0].connections(), probed[0]);
+    assert!(simulators[1..].iter().all(|s| s.connections() > 0));
+
+    let checkpoint = Checkpoint::load(&path).unwrap();
+    assert!(checkpoint.hosts.iter().all(|ip| checkpoint.is_complete(ip)));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "antminer")]
+async fn test_antminer_web_credentials() {
+    let fixture = SimulatorFixture::load("antminer_s19_pro")
+        .unwrap()
+        .with_digest_username(80, "admin");
+    let simulator = start([127, 0, 0, 53], fixture).await;
+    let warning = "web API authentication failed — web-only fields unavailable";
+    let factory = MinerFactory::new().with_identification_timeout(Duration::from_secs(5));
+
+    // identified over RPC, but the web API rejects the default credentials
+    let miner = factory.get_miner(simulator.ip()).await.unwrap().unwrap();
+    assert_eq!(
+        miner.get_device_info().model,
+        MinerModel::AntMiner(AntMinerModel::S19Pro)
+    );
+    let data = miner.get_data().await;
+    assert_eq!(data.mac, None);
+    assert!(data.hashrate.is_some());
+    assert!(data.messages.iter().any(|m| m.message == warning));
+
+    let mut collector = miner.get_collector();
+    collector.collect(&[DataField::Mac]).await;
+    let failures = &collector.diagnostics(&[DataField::Mac])[&DataField::Mac];
+    assert!(
+        failures
+            .iter()
+            .all(|f| matches!(f, ExtractionFailure::AuthenticationFailed { .. }))
+    );
+
+    let miner = factory
+        .with_credentials(vec![Credentials::new("admin", "hunter2")])
+        .get_miner(simulator.ip())
+        .await
+        .unwrap()
+        .unwrap();
+    let data = miner.get_data().await;
+    assert_eq!(data.mac.unwrap().to_string(), "AA:BB:CC:DD:EE:FF");
+    assert!(data.messages.iter().all(|m| m.message != warning));
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "avalon")]
+async fn test_scan_service_probe() {
+    use crate::miners::factory::services::Service;
+
+    let simulator = simulate([127, 0, 0, 54], "avalon_1246").await;
+    // stands in for SSH, which can't be bound without privileges
+    let ssh = TcpListener::bind((simulator.ip(), 2222)).await.unwrap();
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let counted = Arc::clone(&attempts);
+    tokio::spawn(async move {
+        while ssh.accept().await.is_ok() {
+            counted.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    let factory = MinerFactory::from_range("127.0.0.54")
+        .unwrap()
+        .with_identification_timeout(Duration::from_secs(5))
+        .with_service_port(Service::Ssh, 2222);
+
+    let scan = factory.scan_detailed().await.unwrap();
+    assert_eq!(scan.miners.len(), 1);
+    assert_eq!((scan.scanned, scan.alive, scan.supported()), (1, 1, 1));
+    assert!(scan.miners[0].open_services.is_empty());
+    assert_eq!(attempts.load(Ordering::SeqCst), 0);
+
+    let scan = factory
+        .with_service_probe(true)
+        .scan_detailed()
+        .await
+        .unwrap();
+    assert_eq!(scan.miners[0].ip, simulator.ip());
+    assert_eq!(scan.miners[0].open_services, vec![Service::Ssh]);
+    // the probe's connection may be accepted after the scan returns
+    for _ in 0..50 {
+        if attempts.load(Ordering::SeqCst) > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
+
+async fn simulate_serial(ip: [u8; 4], fixture: &str, delay: Duration) -> MinerSimulator {
+    let fixture = SimulatorFixture::load(fixture)
+        .expect("failed to load simulator fixture")
+        .with_delay(delay)
+        .with_serial_connections();
+    start(ip, fixture).await
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "avalon")]
+async fn test_serial_connections() {
+    use crate::miners::api::rpc::limit::ConnectionLimit;
+
+    let simulator =
+        simulate_serial([127, 0, 0, 55], "avalon_1246", Duration::from_millis(100)).await;
+    let model = MinerModel::AvalonMiner(AvalonMinerModel::Avalon1246);
+
+    // commands from several tasks wait for each other instead of overlapping
+    let miner = AvalonMinerV1::new(simulator.ip(), model.clone());
+    let (a, b, c) = tokio::join!(miner.get_data(), miner.get_data(), miner.get_data());
+    assert_eq!(simulator.rejected(), 0);
+    for data in [&a, &b, &c] {
+        assert!(data.hashrate.is_some());
+        assert!(!data.pools.is_empty());
+    }
+    assert_eq!(a.hashrate, b.hashrate);
+    assert_eq!(b.hashrate, c.hashrate);
+
+    // the same collections sent at once lose responses to reset connections
+    let miner =
+        AvalonMinerV1::new(simulator.ip(), model).with_connection_limit(ConnectionLimit::new(3));
+    let _ = tokio::join!(miner.get_data(), miner.get_data(), miner.get_data());
+    assert!(simulator.rejected() > 0);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(all(feature = "avalon", feature = "whatsminer"))]
+async fn test_serial_identification_probes() {
+    use crate::data::device::MinerMake;
+
+    let simulator =
+        simulate_serial([127, 0, 0, 56], "avalon_1246", Duration::from_millis(100)).await;
+
+    // the version and devdetails probes are both sent, one after the other
+    let miner = MinerFactory::new()
+        .with_range_hinted(
+            "127.0.0.56",
+            vec![MinerMake::WhatsMiner, MinerMake::AvalonMiner],
+        )
+        .unwrap()
+        .with_port_check(false)
+        .with_identification_timeout(Duration::from_secs(5))
+        .scan()
+        .await
+        .unwrap();
+    assert_eq!(miner.len(), 1);
+    assert_eq!(
+        miner[0].get_device_info().model,
+        MinerModel::AvalonMiner(AvalonMinerModel::Avalon1246)
+    );
+    assert_eq!(simulator.requests("devdetails"), 1);
+    assert_eq!(simulator.rejected(), 0);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+#[cfg(feature = "cgminer")]
+async fn test_generic_cgminer_fallback() {
+    use crate::data::device::MinerMake;
+
+    let simulator = simulate([127, 0, 0, 57], "cgminer_generic").await;
+    let factory = MinerFactory::new().with_identification_timeout(Duration::from_secs(5));
+
+    // a cgminer API like no known make is left unidentified unless asked for
+    assert!(factory.get_miner(simulator.ip()).await.unwrap().is_none());
+
+    let miner = factory
+        .with_generic_fallback(true)
+        .get_miner(simulator.ip())
+        .await
+        .unwrap()
+        .expect("generic cgminer was not identified");
+    let device_info = miner.get_device_info();
+    assert_eq!(device_info.make, MinerMake::Unknown);
+    assert_eq!(device_info.model.to_string(), "cgminer");
+
+    let data = miner.get_data().await;
+    assert!(data.hashrate.is_some());
+    assert_eq!(data.pools.len(), 2);
+    assert_eq!(data.uptime, Some(Duration::from_secs(86413)));
+}
@@ -0,0 +1,106 @@
+//! Runs the `asic-rs` binary against simulated miners and checks its output and exit codes.
+
+use asic_rs::test_util::{MinerSimulator, SimulatorFixture};
+use assert_cmd::Command;
+use predicates::str::contains;
+use std::net::IpAddr;
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+const USAGE_ERROR: i32 = 2;
+const PARTIAL_FAILURE: i32 = 3;
+
+/// Start a simulator for `fixture` on `ip`, failing the test if the miner ports can't be bound
+/// (e.g. port 80 without privileges, or loopback aliases missing on macOS).
+async fn simulate(ip: [u8; 4], fixture: &str) -> MinerSimulator {
+    let fixture = SimulatorFixture::load(fixture).expect("failed to load simulator fixture");
+    MinerSimulator::start(IpAddr::from(ip), fixture)
+        .await
+        .unwrap_or_else(|e| panic!("could not bind the miner ports on {ip:?}: {e}"))
+}
+
+/// Run the binary off the runtime, so the simulators keep answering while it waits.
+async fn asic_rs(args: &[&str]) -> assert_cmd::assert::Assert {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_asic-rs"))
+            .args(args)
+            .assert()
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_cli_usage_error() {
+    asic_rs(&["scan"]).await.code(USAGE_ERROR);
+    asic_rs(&["data", "not-an-ip"]).await.code(USAGE_ERROR);
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+async fn test_cli_scan() {
+    let _simulator = simulate([127, 0, 0, 61], "antminer_s19_pro").await;
+
+    asic_rs(&["scan", "--json", "127.0.0.61"])
+        .await
+        .code(SUCCESS)
+        .stdout(contains(r#""ip":"127.0.0.61""#))
+        .stdout(contains(r#""model":"S19Pro""#));
+
+    // nothing answers on the next address
+    asic_rs(&["scan", "127.0.0.62"])
+        .await
+        .code(FAILURE)
+        .stderr(contains("no miners found"));
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+async fn test_cli_data() {
+    let _simulator = simulate([127, 0, 0, 63], "antminer_s19_pro").await;
+
+    asic_rs(&["data", "--json", "127.0.0.63"])
+        .await
+        .code(SUCCESS)
+        .stdout(contains(r#""mac":"AA:BB:CC:DD:EE:FF""#));
+    asic_rs(&["data", "127.0.0.64"])
+        .await
+        .code(FAILURE)
+        .stderr(contains("no supported miner found at 127.0.0.64"));
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+async fn test_cli_control_exit_codes() {
+    let _antminer = simulate([127, 0, 0, 65], "antminer_s19_pro").await;
+    let _bitaxe = simulate([127, 0, 0, 66], "bitaxe_supra").await;
+
+    // the Antminer plans the blink, nothing is sent in dry-run
+    asic_rs(&["led", "127.0.0.65", "on", "--dry-run", "--json"])
+        .await
+        .code(SUCCESS)
+        .stdout(contains(r#""ip":"127.0.0.65","success":true"#))
+        .stdout(contains(r#""command":"blink""#));
+
+    // the Bitaxe can't set its fault light
+    asic_rs(&["led", "127.0.0.66", "on", "--dry-run"])
+        .await
+        .code(FAILURE);
+    asic_rs(&["led", "127.0.0.65-66", "on", "--dry-run", "--json"])
+        .await
+        .code(PARTIAL_FAILURE)
+        .stdout(contains(r#""ip":"127.0.0.66","success":false"#));
+}
+
+#[tokio::test]
+#[ignore = "binds miner ports on 127.0.0.x, see the simulator module docs"]
+async fn test_cli_reboot_restarts_mining() {
+    let _simulator = simulate([127, 0, 0, 67], "avalon_1246").await;
+
+    // the Avalon firmware can't reboot over its API, so reboot restarts cgminer
+    asic_rs(&["reboot", "127.0.0.67", "--dry-run", "--json"])
+        .await
+        .code(SUCCESS)
+        .stdout(contains(r#""command":"restart""#));
+}
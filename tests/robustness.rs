@@ -0,0 +1,297 @@
+//! Feeds every backend the fixture responses of every other backend, and mangled copies of
+//! them, to check the parsers make nothing of a response they don't expect instead of panicking.
+//!
+//! The harness only goes through the public `parse_data` and `DataCollector` API with the
+//! `test-util` mock client, so [`assert_parsers_robust`] works for any [`GetMinerData`].
+
+use asic_rs::data::miner::MinerData;
+use asic_rs::miners::backends::traits::GetMinerData;
+use asic_rs::miners::commands::MinerCommand;
+use asic_rs::miners::data::{DataCollector, DataField, FieldData};
+use asic_rs::test_util::MockAPIClient;
+use futures::FutureExt;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
+
+/// Every JSON fixture under `src/test/json`, named by its path there.
+fn fixture_corpus() -> Vec<(String, Value)> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/test/json");
+    let mut files = Vec::new();
+    find_json_files(&root, &mut files);
+    files.sort();
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let value = serde_json::from_str(&std::fs::read_to_string(&path).ok()?).ok()?;
+            let name = path.strip_prefix(&root).unwrap_or(&path).display();
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+fn find_json_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            find_json_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+}
+
+/// `value` wrapped in a list, and with every object, list and string in it cut in half.
+fn mangled(value: &Value) -> Vec<Value> {
+    vec![Value::Array(vec![value.clone()]), truncated(value)]
+}
+
+fn truncated(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .take(map.len().div_ceil(2))
+                .map(|(key, value)| (key.clone(), truncated(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .take(items.len().div_ceil(2))
+                .map(truncated)
+                .collect(),
+        ),
+        Value::String(s) => Value::String(s.chars().take(s.chars().count() / 2).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Parse every fixture in the corpus, the mangled copies of each and a few responses of the
+/// wrong shape with `miner`, answering every command it sends with the same response.
+///
+/// Fails on the first panic, naming the response that caused it. Responses with nothing in
+/// them must not parse to a hashrate or any pools.
+async fn assert_parsers_robust<M: GetMinerData>(miner: &M) {
+    let commands: Vec<MinerCommand> = DataField::iter()
+        .flat_map(|field| miner.get_locations(field))
+        .map(|(command, _)| command)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    for response in [Value::Null, json!({})] {
+        let data = parse(miner, &commands, &response, &response.to_string()).await;
+        assert!(data.hashrate.is_none(), "{response} parsed to a hashrate");
+        assert!(data.pools.is_empty(), "{response} parsed to pools");
+    }
+    for response in [
+        json!([]),
+        json!("garbage"),
+        json!(-1),
+        json!(1e308),
+        json!(true),
+    ] {
+        parse(miner, &commands, &response, &response.to_string()).await;
+    }
+    for (name, fixture) in fixture_corpus() {
+        parse(miner, &commands, &fixture, &name).await;
+        for response in mangled(&fixture) {
+            parse(miner, &commands, &response, &format!("mangled {name}")).await;
+        }
+    }
+}
+
+async fn parse<M: GetMinerData>(
+    miner: &M,
+    commands: &[MinerCommand],
+    response: &Value,
+    source: &str,
+) -> MinerData {
+    let backend = std::any::type_name::<M>();
+    let mock_api = MockAPIClient::new(
+        commands
+            .iter()
+            .map(|command| (command.clone(), response.clone()))
+            .collect(),
+    );
+    let mut collector = DataCollector::new_with_client(miner, &mock_api);
+    let collected = AssertUnwindSafe(collector.collect_all())
+        .catch_unwind()
+        .await
+        .unwrap_or_else(|_| panic!("{backend} panicked collecting {source}"));
+
+    // the whole response as every field, past the extractors that would pick it apart
+    let raw: FieldData = DataField::iter()
+        .map(|field| (field, response.clone()))
+        .collect();
+    std::panic::catch_unwind(AssertUnwindSafe(|| miner.parse_data(raw)))
+        .unwrap_or_else(|_| panic!("{backend} panicked parsing {source} as every field"));
+
+    std::panic::catch_unwind(AssertUnwindSafe(|| miner.parse_data(collected)))
+        .unwrap_or_else(|_| panic!("{backend} panicked parsing {source}"))
+}
+
+#[cfg(feature = "antminer")]
+#[tokio::test]
+async fn test_antminer_robust() {
+    use asic_rs::data::device::MinerModel;
+    use asic_rs::data::device::models::antminer::AntMinerModel;
+    use asic_rs::miners::backends::antminer::legacy::AntMinerLegacy;
+    use asic_rs::miners::backends::antminer::v2020::AntMinerV2020;
+
+    let ip = [127, 0, 0, 1].into();
+    assert_parsers_robust(&AntMinerV2020::new(
+        ip,
+        MinerModel::AntMiner(AntMinerModel::S19Pro),
+    ))
+    .await;
+    assert_parsers_robust(&AntMinerLegacy::new(
+        ip,
+        MinerModel::AntMiner(AntMinerModel::S9),
+    ))
+    .await;
+}
+
+#[cfg(feature = "avalon")]
+#[tokio::test]
+async fn test_avalon_robust() {
+    use asic_rs::data::device::MinerModel;
+    use asic_rs::data::device::models::avalon::AvalonMinerModel;
+    use asic_rs::miners::backends::avalonminer::v1::AvalonMinerV1;
+
+    assert_parsers_robust(&AvalonMinerV1::new(
+        [127, 0, 0, 1].into(),
+        MinerModel::AvalonMiner(AvalonMinerModel::Avalon1246),
+    ))
+    .await;
+}
+
+#[cfg(feature = "bitaxe")]
+#[tokio::test]
+async fn test_bitaxe_robust() {
+    use asic_rs::data::device::MinerModel;
+    use asic_rs::data::device::models::bitaxe::BitaxeModel;
+    use asic_rs::miners::backends::bitaxe::v2_0_0::Bitaxe200;
+    use asic_rs::miners::backends::bitaxe::v2_9_0::Bitaxe290;
+
+    let ip = [127, 0, 0, 1].into();
+    assert_parsers_robust(&Bitaxe200::new(ip, MinerModel::Bitaxe(BitaxeModel::Supra))).await;
+    assert_parsers_robust(&Bitaxe290::new(ip, MinerModel::Bitaxe(BitaxeModel::Gamma))).await;
+}
+
+#[cfg(feature = "braiins")]
+#[tokio::test]
+async fn test_braiins_robust() {
+    use asic_rs::data::device::MinerModel;
+    use asic_rs::data::device::models::antminer::AntMinerModel;
+    use asic_rs::miners::backends::braiins::v25_07::BraiinsV2507;
+
+    assert_parsers_robust(&BraiinsV2507::new(
+        [127, 0, 0, 1].into(),
+        MinerModel::AntMiner(AntMinerModel::S19jPro),
+    ))
+    .await;
+}
+
+#[cfg(feature = "cgminer")]
+#[tokio::test]
+async fn test_cgminer_robust() {
+    use asic_rs::data::device::{MinerMake, MinerModel};
+    use asic_rs::miners::backends::cgminer::GenericCGMiner;
+
+    assert_parsers_robust(&GenericCGMiner::new(
+        [127, 0, 0, 1].into(),
+        MinerModel::Unknown {
+            make: MinerMake::Unknown,
+            raw: "cgminer".to_string(),
+        },
+    ))
+    .await;
+}
+
+#[cfg(feature = "epic")]
+#[tokio::test]
+async fn test_epic_robust() {
+    use asic_rs::data::device::MinerModel;
+    use asic_rs::data::device::models::epic::EPicModel;
+    use asic_rs::miners::backends::epic::v1::PowerPlayV1;
+
+    assert_parsers_robust(&PowerPlayV1::new(
+        [127, 0, 0, 1].into(),
+        MinerModel::EPic(EPicModel::BM520i),
+    ))
+    .await;
+}
+
+#[cfg(feature = "luxos")]
+#[tokio::test]
+async fn test_luxos_robust() {
+    use asic_rs::data::device::MinerModel;
+    use asic_rs::data::device::models::antminer::AntMinerModel;
+    use asic_rs::miners::backends::luxminer::v1::LuxMinerV1;
+
+    assert_parsers_robust(&LuxMinerV1::new(
+        [127, 0, 0, 1].into(),
+        MinerModel::AntMiner(AntMinerModel::S19jPro),
+    ))
+    .await;
+}
+
+#[cfg(feature = "marathon")]
+#[tokio::test]
+async fn test_marathon_robust() {
+    use asic_rs::data::device::MinerModel;
+    use asic_rs::data::device::models::antminer::AntMinerModel;
+    use asic_rs::miners::backends::marathon::v1::MaraV1;
+
+    assert_parsers_robust(&MaraV1::new(
+        [127, 0, 0, 1].into(),
+        MinerModel::AntMiner(AntMinerModel::S19jPro),
+    ))
+    .await;
+}
+
+#[cfg(feature = "vnish")]
+#[tokio::test]
+async fn test_vnish_robust() {
+    use asic_rs::data::device::MinerModel;
+    use asic_rs::data::device::models::antminer::AntMinerModel;
+    use asic_rs::miners::backends::vnish::v1_2_0::VnishV120;
+
+    assert_parsers_robust(&VnishV120::new(
+        [127, 0, 0, 1].into(),
+        MinerModel::AntMiner(AntMinerModel::S19Pro),
+    ))
+    .await;
+}
+
+#[cfg(feature = "whatsminer")]
+#[tokio::test]
+async fn test_whatsminer_robust() {
+    use asic_rs::data::device::MinerModel;
+    use asic_rs::data::device::models::whatsminer::WhatsMinerModel;
+    use asic_rs::miners::backends::whatsminer::v1::WhatsMinerV1;
+    use asic_rs::miners::backends::whatsminer::v2::WhatsMinerV2;
+    use asic_rs::miners::backends::whatsminer::v3::WhatsMinerV3;
+
+    let ip = [127, 0, 0, 1].into();
+    assert_parsers_robust(&WhatsMinerV1::new(
+        ip,
+        MinerModel::WhatsMiner(WhatsMinerModel::M20SV10),
+    ))
+    .await;
+    assert_parsers_robust(&WhatsMinerV2::new(
+        ip,
+        MinerModel::WhatsMiner(WhatsMinerModel::M30SPlusPlusV10),
+    ))
+    .await;
+    assert_parsers_robust(&WhatsMinerV3::new(
+        ip,
+        MinerModel::WhatsMiner(WhatsMinerModel::M60SVK30),
+    ))
+    .await;
+}